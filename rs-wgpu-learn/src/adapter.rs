@@ -0,0 +1,96 @@
+use crate::WgpuAppError;
+#[cfg(not(target_arch = "wasm32"))]
+use log::info;
+use log::warn;
+
+/// 适配器（GPU）选择方式
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub enum AdapterSelection {
+    /// 由wgpu根据电源偏好自动选择
+    #[default]
+    Default,
+    /// 按`enumerate_adapters`输出的序号选择
+    Index(usize),
+    /// 按名称子串选择（不区分大小写）
+    NameContains(String),
+}
+
+impl From<&str> for AdapterSelection {
+    /// 解析选择字符串：纯数字视为序号，否则视为名称子串，空字符串为默认选择
+    fn from(value: &str) -> Self {
+        let value = value.trim();
+        if value.is_empty() {
+            Self::Default
+        } else if let Ok(index) = value.parse() {
+            Self::Index(index)
+        } else {
+            Self::NameContains(value.to_string())
+        }
+    }
+}
+
+/// 枚举所有可用适配器并打印序号、名称、后端和设备类型
+#[cfg(not(target_arch = "wasm32"))]
+pub fn enumerate_adapters(instance: &wgpu::Instance) -> Vec<wgpu::Adapter> {
+    let adapters = instance.enumerate_adapters(wgpu::Backends::all());
+    for (index, adapter) in adapters.iter().enumerate() {
+        let info = adapter.get_info();
+        info!(
+            "Adapter #{index}: {} ({:?}, {:?})",
+            info.name, info.backend, info.device_type
+        );
+    }
+    adapters
+}
+
+/// 根据选择方式请求适配器
+///
+/// 指定的适配器不存在或与表面不兼容时，打印警告并回退到默认选择；
+/// surface为None时（无窗口渲染）不检查表面兼容性。
+pub(crate) async fn select_adapter(
+    instance: &wgpu::Instance,
+    surface: Option<&wgpu::Surface<'_>>,
+    selection: &AdapterSelection,
+    power_preference: wgpu::PowerPreference,
+    backends: wgpu::Backends,
+) -> Result<wgpu::Adapter, WgpuAppError> {
+    #[cfg(not(target_arch = "wasm32"))]
+    if *selection != AdapterSelection::Default {
+        let adapters = enumerate_adapters(instance);
+        let found = match selection {
+            AdapterSelection::Default => None,
+            AdapterSelection::Index(index) => adapters.into_iter().nth(*index),
+            AdapterSelection::NameContains(name) => {
+                let name = name.to_lowercase();
+                adapters
+                    .into_iter()
+                    .find(|adapter| adapter.get_info().name.to_lowercase().contains(&name))
+            }
+        };
+        match found {
+            Some(adapter)
+                if surface.is_none_or(|surface| adapter.is_surface_supported(surface)) =>
+            {
+                return Ok(adapter);
+            }
+            Some(adapter) => warn!(
+                "Adapter {} is not compatible with the surface, using the default adapter",
+                adapter.get_info().name
+            ),
+            None => warn!("No adapter matches {selection:?}, using the default adapter"),
+        }
+    }
+    #[cfg(target_arch = "wasm32")]
+    if *selection != AdapterSelection::Default {
+        warn!("Adapter selection is not supported on the web, using the default adapter");
+    }
+
+    instance
+        .request_adapter(&wgpu::RequestAdapterOptions {
+            power_preference,            // 电源偏好（默认由系统决定）
+            compatible_surface: surface, // 需要与表面兼容（无窗口时为None）
+            force_fallback_adapter: false,
+        })
+        .await
+        .ok_or(WgpuAppError::NoAdapter(backends))
+}
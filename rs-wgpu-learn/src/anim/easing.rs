@@ -0,0 +1,95 @@
+use std::f32::consts::TAU;
+
+/// 缓动函数：把线性进度t（0..1）映射为动画进度，两端总是精确地为0和1
+///
+/// In从慢到快、Out从快到慢、InOut两端都慢。弹性（Elastic）会越过终点来回振荡，
+/// 反弹（Bounce）像落地的球一样在终点处弹跳几次。曲线与easings.net上的同名函数相同。
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub enum Easing {
+    #[default]
+    Linear,
+    QuadIn,
+    QuadOut,
+    QuadInOut,
+    CubicIn,
+    CubicOut,
+    CubicInOut,
+    ExpoIn,
+    ExpoOut,
+    ExpoInOut,
+    ElasticIn,
+    ElasticOut,
+    ElasticInOut,
+    BounceIn,
+    BounceOut,
+    BounceInOut,
+}
+
+impl Easing {
+    /// 计算t（超出0..1时截断）处的动画进度，弹性缓动的结果可能超出0..1
+    pub fn apply(self, t: f32) -> f32 {
+        let t = t.clamp(0.0, 1.0);
+        // 指数和弹性曲线在端点处的公式值不精确，直接返回端点
+        if t == 0.0 || t == 1.0 {
+            return t;
+        }
+        match self {
+            Self::Linear => t,
+            Self::QuadIn => t * t,
+            Self::QuadOut => 1.0 - (1.0 - t) * (1.0 - t),
+            Self::QuadInOut => in_out(t, |t| t * t),
+            Self::CubicIn => t * t * t,
+            Self::CubicOut => 1.0 - (1.0 - t).powi(3),
+            Self::CubicInOut => in_out(t, |t| t * t * t),
+            Self::ExpoIn => expo_in(t),
+            Self::ExpoOut => 1.0 - expo_in(1.0 - t),
+            Self::ExpoInOut => in_out(t, expo_in),
+            Self::ElasticIn => elastic_in(t),
+            Self::ElasticOut => 1.0 - elastic_in(1.0 - t),
+            // InOut的振荡周期比In更长（与easings.net一致）
+            Self::ElasticInOut => {
+                in_out(t, |t| -expo_in(t) * ((10.0 * t - 11.125) * TAU / 4.5).sin())
+            }
+            Self::BounceIn => 1.0 - bounce_out(1.0 - t),
+            Self::BounceOut => bounce_out(t),
+            Self::BounceInOut => in_out(t, |t| 1.0 - bounce_out(1.0 - t)),
+        }
+    }
+}
+
+/// 由In曲线构造InOut曲线：前一半是压缩的In，后一半是中心对称的Out
+fn in_out(t: f32, ease_in: impl Fn(f32) -> f32) -> f32 {
+    if t < 0.5 {
+        ease_in(2.0 * t) / 2.0
+    } else {
+        1.0 - ease_in(2.0 - 2.0 * t) / 2.0
+    }
+}
+
+/// 指数In：2^(10t - 10)
+fn expo_in(t: f32) -> f32 {
+    (2.0f32).powf(10.0 * t - 10.0)
+}
+
+/// 弹性In：指数增长的正弦振荡，周期为0.3
+fn elastic_in(t: f32) -> f32 {
+    -expo_in(t) * ((10.0 * t - 10.75) * TAU / 3.0).sin()
+}
+
+/// 反弹Out：四段抛物线，每次弹起的高度越来越低
+fn bounce_out(t: f32) -> f32 {
+    const N: f32 = 7.5625;
+    const D: f32 = 2.75;
+    if t < 1.0 / D {
+        N * t * t
+    } else if t < 2.0 / D {
+        let t = t - 1.5 / D;
+        N * t * t + 0.75
+    } else if t < 2.5 / D {
+        let t = t - 2.25 / D;
+        N * t * t + 0.9375
+    } else {
+        let t = t - 2.625 / D;
+        N * t * t + 0.984375
+    }
+}
@@ -0,0 +1,7 @@
+mod easing;
+mod shake;
+mod tween;
+
+pub use easing::Easing;
+pub use shake::CameraShake;
+pub use tween::{Interpolate, Tween, TweenId, Tweens};
@@ -0,0 +1,89 @@
+use crate::{camera::Camera, math::perlin};
+use glam::{Quat, Vec3};
+use std::time::Duration;
+
+/// 每个抖动分量使用的噪声种子（位置xyz、偏航、俯仰、滚转）
+const SEEDS: [u32; 6] = [1, 2, 3, 4, 5, 6];
+
+/// 相机抖动：用随时间平滑变化的Perlin噪声偏移相机的位置和朝向，强度随时间衰减
+///
+/// `shake(trauma)`累加“创伤值”（0..1），抖动幅度与创伤值的平方成正比，创伤值每秒减少`decay`。
+/// 每帧`update(dt)`后用`apply`得到叠加抖动后的相机，控制器中的相机本身不受影响。
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct CameraShake {
+    pub max_offset: f32, // 创伤值为1时位置偏移的最大值（世界单位）
+    pub max_angle: f32,  // 创伤值为1时偏航、俯仰和滚转的最大值（弧度）
+    pub frequency: f32,  // 噪声的频率（每秒变化的次数）
+    pub decay: f32,      // 创伤值每秒减少的量
+    trauma: f32,         // 当前创伤值
+    time: f32,           // 噪声的采样时间（秒）
+}
+
+impl Default for CameraShake {
+    fn default() -> Self {
+        Self::new(0.3, 0.05, 15.0, 1.0)
+    }
+}
+
+impl CameraShake {
+    pub fn new(max_offset: f32, max_angle: f32, frequency: f32, decay: f32) -> Self {
+        Self {
+            max_offset,
+            max_angle,
+            frequency,
+            decay,
+            trauma: 0.0,
+            time: 0.0,
+        }
+    }
+
+    /// 增加创伤值（结果不超过1），多次触发会叠加
+    pub fn shake(&mut self, trauma: f32) {
+        self.trauma = (self.trauma + trauma).clamp(0.0, 1.0);
+    }
+
+    /// 当前创伤值
+    pub fn trauma(&self) -> f32 {
+        self.trauma
+    }
+
+    /// 是否正在抖动
+    pub fn is_active(&self) -> bool {
+        self.trauma > 0.0
+    }
+
+    /// 推进噪声时间并衰减创伤值
+    pub fn update(&mut self, dt: Duration) {
+        let dt = dt.as_secs_f32();
+        self.time += dt;
+        self.trauma = (self.trauma - self.decay * dt).max(0.0);
+    }
+
+    /// 叠加抖动后的相机：沿相机自身的右、上、后方向偏移位置（观察点随之平移），再绕相机位置偏航、俯仰和滚转
+    pub fn apply(&self, camera: &Camera) -> Camera {
+        if !self.is_active() {
+            return *camera;
+        }
+        let amount = self.trauma * self.trauma;
+        let t = self.time * self.frequency;
+        let [x, y, z, yaw, pitch, roll] = SEEDS.map(|seed| perlin(t, seed) * amount);
+
+        let rotation = camera.orientation();
+        let offset = rotation * Vec3::new(x, y, z) * self.max_offset;
+        let shake = rotation
+            * Quat::from_euler(
+                glam::EulerRot::YXZ,
+                yaw * self.max_angle,
+                pitch * self.max_angle,
+                roll * self.max_angle,
+            )
+            * rotation.inverse();
+        let eye = camera.eye + offset;
+        Camera {
+            eye,
+            target: eye + shake * (camera.target - camera.eye),
+            up: shake * camera.up,
+            projection: camera.projection,
+        }
+    }
+}
@@ -0,0 +1,224 @@
+use super::Easing;
+use glam::{Quat, Vec3};
+use std::time::Duration;
+
+/// 可以补间的值：按系数t在两个值之间插值（t可能超出0..1，如弹性缓动越过终点时）
+pub trait Interpolate: Copy {
+    fn interpolate(&self, end: &Self, t: f32) -> Self;
+}
+
+impl Interpolate for f32 {
+    fn interpolate(&self, end: &Self, t: f32) -> Self {
+        self + (end - self) * t
+    }
+}
+
+impl Interpolate for Vec3 {
+    fn interpolate(&self, end: &Self, t: f32) -> Self {
+        self.lerp(*end, t)
+    }
+}
+
+impl Interpolate for Quat {
+    /// 球面插值（沿最短路径）
+    fn interpolate(&self, end: &Self, t: f32) -> Self {
+        self.slerp(*end, t)
+    }
+}
+
+impl Interpolate for wgpu::Color {
+    /// 逐通道线性插值（包括alpha），颜色空间与输入相同
+    fn interpolate(&self, end: &Self, t: f32) -> Self {
+        let t = t as f64;
+        Self {
+            r: self.r + (end.r - self.r) * t,
+            g: self.g + (end.g - self.g) * t,
+            b: self.b + (end.b - self.b) * t,
+            a: self.a + (end.a - self.a) * t,
+        }
+    }
+}
+
+/// 补间：在duration内按缓动函数从from变化到to
+///
+/// 每帧调用`update`推进，用`value`读取当前值。时长为零的补间在创建时就已完成，值为to。
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Tween<T> {
+    from: T,            // 起始值
+    to: T,              // 结束值
+    duration: Duration, // 总时长
+    elapsed: Duration,  // 已经过的时间（不超过duration）
+    easing: Easing,     // 缓动函数
+}
+
+impl<T: Interpolate> Tween<T> {
+    pub fn new(from: T, to: T, duration: Duration, easing: Easing) -> Self {
+        Self {
+            from,
+            to,
+            duration,
+            elapsed: Duration::ZERO,
+            easing,
+        }
+    }
+
+    /// 推进dt，返回补间是否已完成
+    pub fn update(&mut self, dt: Duration) -> bool {
+        self.elapsed = (self.elapsed + dt).min(self.duration);
+        self.is_finished()
+    }
+
+    /// 线性进度（0..1），时长为零时为1
+    pub fn progress(&self) -> f32 {
+        if self.duration.is_zero() {
+            return 1.0;
+        }
+        self.elapsed.as_secs_f32() / self.duration.as_secs_f32()
+    }
+
+    /// 是否已完成
+    pub fn is_finished(&self) -> bool {
+        self.elapsed >= self.duration
+    }
+
+    /// 当前值，完成后精确地等于to
+    pub fn value(&self) -> T {
+        if self.is_finished() {
+            return self.to;
+        }
+        self.from
+            .interpolate(&self.to, self.easing.apply(self.progress()))
+    }
+
+    /// 结束值
+    pub fn end(&self) -> T {
+        self.to
+    }
+}
+
+/// `Tweens`中补间的标识，移除后不会被复用
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct TweenId(u64);
+
+/// 补间完成时的回调，参数为结束值
+#[cfg(not(target_arch = "wasm32"))]
+type Callback<T> = Box<dyn FnOnce(T) + Send>;
+/// 补间完成时的回调，参数为结束值
+#[cfg(target_arch = "wasm32")]
+type Callback<T> = Box<dyn FnOnce(T)>;
+
+/// 进行中的补间和它的回调
+struct Entry<T> {
+    id: TweenId,
+    tween: Tween<T>,
+    on_complete: Option<Callback<T>>,
+}
+
+/// 补间管理器：同时运行多个同类型的补间，在场景的`update(dt)`中统一推进
+///
+/// `start`返回的`TweenId`用于读取当前值或提前移除（移除的补间不会调用回调）。
+/// 补间完成后调用它的回调并从管理器中移除，`update`同时返回本帧完成的补间及其结束值。
+pub struct Tweens<T> {
+    entries: Vec<Entry<T>>,
+    next_id: u64,
+}
+
+impl<T> Default for Tweens<T> {
+    fn default() -> Self {
+        Self {
+            entries: Vec::new(),
+            next_id: 0,
+        }
+    }
+}
+
+impl<T: Interpolate> Tweens<T> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 开始一个补间
+    pub fn start(&mut self, tween: Tween<T>) -> TweenId {
+        self.push(tween, None)
+    }
+
+    /// 开始一个补间，完成时以结束值调用on_complete
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn start_with(
+        &mut self,
+        tween: Tween<T>,
+        on_complete: impl FnOnce(T) + Send + 'static,
+    ) -> TweenId {
+        self.push(tween, Some(Box::new(on_complete)))
+    }
+
+    /// 开始一个补间，完成时以结束值调用on_complete
+    #[cfg(target_arch = "wasm32")]
+    pub fn start_with(
+        &mut self,
+        tween: Tween<T>,
+        on_complete: impl FnOnce(T) + 'static,
+    ) -> TweenId {
+        self.push(tween, Some(Box::new(on_complete)))
+    }
+
+    fn push(&mut self, tween: Tween<T>, on_complete: Option<Callback<T>>) -> TweenId {
+        let id = TweenId(self.next_id);
+        self.next_id += 1;
+        self.entries.push(Entry {
+            id,
+            tween,
+            on_complete,
+        });
+        id
+    }
+
+    /// 移除补间（不调用回调），返回它是否存在
+    pub fn remove(&mut self, id: TweenId) -> bool {
+        let len = self.entries.len();
+        self.entries.retain(|entry| entry.id != id);
+        self.entries.len() != len
+    }
+
+    /// 移除所有补间（不调用回调）
+    pub fn clear(&mut self) {
+        self.entries.clear();
+    }
+
+    /// 补间的当前值，补间不存在（已完成或已移除）时返回None
+    pub fn get(&self, id: TweenId) -> Option<T> {
+        self.entries
+            .iter()
+            .find(|entry| entry.id == id)
+            .map(|entry| entry.tween.value())
+    }
+
+    /// 进行中的补间数量
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// 是否没有进行中的补间
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// 推进所有补间，调用完成的补间的回调并移除它们，返回本次完成的补间及其结束值
+    ///
+    /// 时长为零的补间在开始后的第一次`update`中完成。
+    pub fn update(&mut self, dt: Duration) -> Vec<(TweenId, T)> {
+        let mut finished = Vec::new();
+        self.entries.retain_mut(|entry| {
+            if !entry.tween.update(dt) {
+                return true;
+            }
+            let end = entry.tween.end();
+            if let Some(on_complete) = entry.on_complete.take() {
+                on_complete(end);
+            }
+            finished.push((entry.id, end));
+            false
+        });
+        finished
+    }
+}
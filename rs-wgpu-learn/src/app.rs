@@ -0,0 +1,1575 @@
+#[cfg(feature = "gamepad")]
+use crate::GamepadInput;
+#[cfg(not(target_arch = "wasm32"))]
+use crate::Renderer;
+use crate::{
+    AdapterSelection, BoxedScene, Demo, FrameTimer, InputState, RedrawMode, RendererInit, Scene,
+    WgpuApp, WgpuAppBuilder, WgpuAppError,
+    config::{GraphicsSettings, Settings, WindowPlacement},
+    renderers::Clear,
+    window_icon,
+};
+use log::{debug, error, info, warn};
+#[cfg(not(target_arch = "wasm32"))]
+use std::path::PathBuf;
+use std::{collections::HashMap, sync::Arc, time::Duration};
+use web_time::Instant;
+use winit::{
+    application::ApplicationHandler,
+    dpi::{LogicalSize, PhysicalPosition, PhysicalSize},
+    error::EventLoopError,
+    event::{DeviceEvent, DeviceId, ElementState, KeyEvent, MouseButton, WindowEvent},
+    event_loop::{ActiveEventLoop, ControlFlow, EventLoop},
+    keyboard::{KeyCode, PhysicalKey},
+    monitor::MonitorHandle,
+    window::{CursorGrabMode, Fullscreen, Icon, Window, WindowId},
+};
+
+/// 在事件循环上运行演示（桌面、浏览器和Android共用）
+///
+/// 桌面上阻塞直到所有窗口关闭；浏览器上立即返回，之后由浏览器驱动事件循环。
+pub fn run_demo(
+    event_loop: EventLoop<AppEvent>,
+    settings: Settings,
+    demo: Demo,
+) -> Result<(), EventLoopError> {
+    // 没有事件时等待，持续渲染依靠每帧请求重绘，窗口不可见时停止请求即可让事件循环休眠
+    event_loop.set_control_flow(ControlFlow::Wait);
+    #[cfg(feature = "gamepad")]
+    let gamepad = GamepadInput::new(settings.input.gamepad_dead_zone);
+    let app = App {
+        windows: HashMap::new(),
+        settings,
+        demo,
+        placement: None,
+        icon: None,
+        #[cfg(feature = "gamepad")]
+        gamepad,
+        #[cfg(target_arch = "wasm32")]
+        proxy: event_loop.create_proxy(),
+        exiting: false,
+    };
+    #[cfg(target_arch = "wasm32")]
+    winit::platform::web::EventLoopExtWebSys::spawn_app(event_loop, app);
+    #[cfg(not(target_arch = "wasm32"))]
+    {
+        let mut app = app;
+        event_loop.run_app(&mut app)?;
+    }
+    Ok(())
+}
+
+/// Android入口：由NativeActivity在独立线程上调用，运行默认演示
+#[cfg(target_os = "android")]
+#[unsafe(no_mangle)]
+fn android_main(android_app: winit::platform::android::activity::AndroidApp) {
+    use winit::platform::android::EventLoopBuilderExtAndroid;
+
+    init_logging();
+    let demo = *crate::DemoRegistry::builtin()
+        .get(crate::DemoRegistry::DEFAULT)
+        .expect("the default demo is registered");
+    let result = EventLoop::<AppEvent>::with_user_event()
+        .with_android_app(android_app)
+        .build()
+        .and_then(|event_loop| run_demo(event_loop, Settings::load_or_create(), demo));
+    if let Err(e) = result {
+        error!("{e}");
+    }
+}
+
+/// 初始化日志：桌面上输出到终端，浏览器上输出到开发者工具的控制台（panic信息也输出到控制台），
+/// Android上输出到logcat
+pub fn init_logging() {
+    #[cfg(target_os = "android")]
+    android_logger::init_once(
+        android_logger::Config::default()
+            .with_max_level(log::LevelFilter::Info)
+            .with_tag("rs-wgpu-learn"),
+    );
+    #[cfg(not(any(target_arch = "wasm32", target_os = "android")))]
+    env_logger::builder()
+        .filter_level(log::LevelFilter::Info)
+        .init();
+    #[cfg(target_arch = "wasm32")]
+    {
+        console_error_panic_hook::set_once();
+        console_log::init_with_level(log::Level::Info).expect("Failed to initialize the logger");
+    }
+}
+
+/// 浏览器页面中用于渲染的画布ID（见index.html）
+#[cfg(target_arch = "wasm32")]
+const CANVAS_ID: &str = "wgpu-canvas";
+
+/// 浏览器上把窗口绑定到页面中的画布，页面中没有该画布时在body末尾追加一个
+#[cfg(target_arch = "wasm32")]
+fn attach_canvas(attributes: winit::window::WindowAttributes) -> winit::window::WindowAttributes {
+    use wasm_bindgen::JsCast;
+    use winit::platform::web::WindowAttributesExtWebSys;
+
+    let canvas = web_sys::window()
+        .and_then(|window| window.document())
+        .and_then(|document| document.get_element_by_id(CANVAS_ID))
+        .and_then(|element| element.dyn_into::<web_sys::HtmlCanvasElement>().ok());
+    match canvas {
+        Some(canvas) => attributes.with_canvas(Some(canvas)),
+        None => {
+            warn!("No <canvas id=\"{CANVAS_ID}\"> in the page, appending a new canvas");
+            attributes.with_append(true)
+        }
+    }
+}
+
+/// 解析后端名称（vulkan、dx12、metal、gl、all），无法识别时返回None
+fn parse_backends(value: &str) -> Option<wgpu::Backends> {
+    match value.trim().to_lowercase().as_str() {
+        "vulkan" | "vk" => Some(wgpu::Backends::VULKAN),
+        "dx12" | "d3d12" => Some(wgpu::Backends::DX12),
+        "metal" | "mtl" => Some(wgpu::Backends::METAL),
+        "gl" | "gles" | "opengl" => Some(wgpu::Backends::GL),
+        "all" => Some(wgpu::Backends::all()),
+        other => {
+            warn!("Unknown WGPU_BACKEND value `{other}`, using the primary backends");
+            None
+        }
+    }
+}
+
+/// 将WgpuApp错误转换为面向用户的提示信息
+fn user_message(err: &WgpuAppError) -> String {
+    match err {
+        WgpuAppError::NoAdapter(_) => format!(
+            "{err}. Make sure your GPU drivers are up to date, \
+             or try another backend, e.g. `WGPU_BACKEND=gl cargo run`"
+        ),
+        WgpuAppError::CreateSurface(_) => {
+            format!("{err}. The windowing system may not be supported by wgpu")
+        }
+        WgpuAppError::RequestDevice(_) => {
+            format!("{err}. The adapter may not support the requested features or limits")
+        }
+        WgpuAppError::MissingFeatures(_) => {
+            format!("{err}. Try another adapter, e.g. `WGPU_ADAPTER=nvidia cargo run`")
+        }
+        WgpuAppError::ShaderLoad { .. } => {
+            format!("{err}. Set ASSET_DIR to the directory containing the shaders")
+        }
+        WgpuAppError::ShaderCompile(_) | WgpuAppError::Validation(_) => err.to_string(),
+        WgpuAppError::Surface(_) => format!("{err}. Rendering cannot continue"),
+        WgpuAppError::UnsupportedPresentMode(_)
+        | WgpuAppError::UnsupportedSampleCount { .. }
+        | WgpuAppError::UnsupportedTextureUsage { .. } => err.to_string(),
+        WgpuAppError::ReadPixels(_)
+        | WgpuAppError::UnsupportedCaptureFormat(_)
+        | WgpuAppError::Screenshot(_) => err.to_string(),
+        WgpuAppError::DeviceLost => format!("{err}. Re-initialization failed"),
+        WgpuAppError::NotReady | WgpuAppError::NoRendererInit => err.to_string(),
+        WgpuAppError::UnsupportedLimits(_) => {
+            format!("{err}. Try a lower limits profile such as `LimitsProfile::Downlevel`")
+        }
+        WgpuAppError::TextureTooLarge { .. }
+        | WgpuAppError::VolumeTooLarge { .. }
+        | WgpuAppError::AtlasFull { .. }
+        | WgpuAppError::TooManyArrayLayers { .. }
+        | WgpuAppError::InvalidTextureArray(_)
+        | WgpuAppError::ImageDecode(_)
+        | WgpuAppError::TextureRead { .. }
+        | WgpuAppError::Ktx2(_)
+        | WgpuAppError::InvalidCubeMap(_)
+        | WgpuAppError::InvalidSampler(_)
+        | WgpuAppError::InvalidUpload { .. }
+        | WgpuAppError::ObjLoad(_)
+        | WgpuAppError::GltfLoad(_) => err.to_string(),
+        WgpuAppError::NoCompatibleConfig => {
+            format!("{err}. Try another adapter or backend, e.g. `WGPU_BACKEND=gl cargo run`")
+        }
+    }
+}
+
+/// 调试窗口的背景色
+#[cfg(not(target_arch = "wasm32"))]
+const DEBUG_WINDOW_COLOR: wgpu::Color = wgpu::Color {
+    r: 0.8,
+    g: 0.1,
+    b: 0.1,
+    a: 1.0,
+};
+
+// 主应用结构体
+struct App {
+    /// 所有窗口的状态，按窗口ID索引
+    ///
+    /// 窗口和设备都只在事件循环线程上使用，App直接拥有它们，不需要共享所有权或加锁。
+    /// 以后需要异步初始化时（例如wasm上无法阻塞等待），应通过`EventLoopProxy`发送用户事件
+    /// 把创建好的WgpuApp交回事件循环，而不是共享可变状态。
+    windows: HashMap<WindowId, AppWindow>,
+    /// 从settings.toml加载并被命令行参数覆盖后的窗口和图形设置
+    settings: Settings,
+    /// 主窗口运行的演示
+    demo: Demo,
+    /// 主窗口关闭时的位置和状态，退出时写回配置文件
+    placement: Option<WindowPlacement>,
+    /// 窗口图标（创建主窗口时加载，调试窗口复用）
+    icon: Option<Icon>,
+    /// 手柄输入（平台不支持时为None），在about_to_wait中轮询并写入主窗口的输入状态
+    #[cfg(feature = "gamepad")]
+    gamepad: Option<GamepadInput>,
+    /// 向事件循环发送用户事件（浏览器上异步初始化完成后使用）
+    #[cfg(target_arch = "wasm32")]
+    proxy: winit::event_loop::EventLoopProxy<AppEvent>,
+    /// 是否正在退出（不再处理事件和请求重绘）
+    exiting: bool,
+}
+
+/// 发送给事件循环的用户事件
+pub enum AppEvent {
+    /// 浏览器上异步创建的主窗口WGPU应用（或失败原因）
+    #[cfg(target_arch = "wasm32")]
+    MainWindowReady(Box<Result<WgpuApp<BoxedScene>, WgpuAppError>>),
+}
+
+/// 单个窗口的状态：WGPU应用（表面、配置和场景）以及该窗口自己的输入、计时和全屏状态
+///
+/// 同一设备上的多个窗口通过`WgpuAppBuilder::build_shared`共用设备和命令队列。
+struct WindowState<R: Scene> {
+    /// WGPU应用实例
+    app: WgpuApp<R>,
+    /// 场景构造函数（设备丢失后重建时使用，浏览器上不重建）
+    #[cfg_attr(target_arch = "wasm32", allow(dead_code))]
+    init: RendererInit<R>,
+    /// 帧计时器
+    frame_timer: FrameTimer,
+    /// 键盘和鼠标输入状态
+    input: InputState,
+    /// 进入全屏前的窗口尺寸和位置（退出全屏时恢复）
+    windowed: Option<WindowedState>,
+    /// 窗口标题（帧率信息追加在后面）
+    title: String,
+    /// 是否允许用鼠标和方向键改变背景色（调试窗口保持固定背景色）
+    tint_with_input: bool,
+    /// 文件悬停在窗口上时保存的原背景色（拖放结束或取消后恢复）
+    hover_saved_color: Option<wgpu::Color>,
+    /// 鼠标视角模式的状态（未开启时为None）
+    mouse_look: Option<MouseLookState>,
+    /// 帧率上限（None表示不限制）
+    frame_limit: Option<u32>,
+    /// 限制帧率时下一帧的开始时间（到达后请求重绘）
+    next_frame: Option<Instant>,
+}
+
+/// 运行不同场景的窗口
+enum AppWindow {
+    Demo(WindowState<BoxedScene>), // 主窗口：运行命令行选择的演示场景
+    #[cfg_attr(target_arch = "wasm32", allow(dead_code))]
+    Debug(WindowState<Clear>), // 调试窗口：与主窗口共用设备，只清除背景（浏览器上不支持）
+}
+
+/// 窗口事件处理后需要由App完成的操作（涉及其他窗口或事件循环）
+enum WindowAction {
+    None,            // 无需额外操作
+    Close,           // 关闭该窗口
+    Exit,            // 退出应用
+    Rebuild,         // 设备丢失，需要重新初始化该窗口
+    OpenDebugWindow, // 打开一个共用设备的调试窗口
+}
+
+/// 应用恢复时需要的后续操作
+#[derive(Debug, PartialEq)]
+enum ResumeAction {
+    CreateMainWindow, // 第一次启动，还没有窗口
+    Resumed,          // 所有窗口的表面都已重新创建
+    Exit,             // 重新创建表面失败，需要退出
+}
+
+/// 窗口模式下的尺寸和位置
+struct WindowedState {
+    size: PhysicalSize<u32>,
+    position: Option<PhysicalPosition<i32>>, // 部分平台（如Wayland）无法获取窗口位置
+}
+
+/// 鼠标视角模式：光标被锁定并隐藏，退出时恢复
+struct MouseLookState {
+    held: bool,                            // 是否由按住鼠标右键开启（松开右键时退出）
+    cursor: Option<PhysicalPosition<f64>>, // 开启前的光标位置
+}
+
+/// 切换全屏：exclusive为true时尝试使用当前显示器最佳视频模式的独占全屏，否则使用无边框全屏
+fn toggle_fullscreen(window: &Window, exclusive: bool, windowed: &mut Option<WindowedState>) {
+    // 已经全屏时退出，并恢复之前的窗口尺寸和位置
+    if window.fullscreen().is_some() {
+        window.set_fullscreen(None);
+        if let Some(state) = windowed.take() {
+            let _ = window.request_inner_size(state.size);
+            if let Some(position) = state.position {
+                window.set_outer_position(position);
+            }
+            info!(
+                "Left fullscreen, restored {}x{}",
+                state.size.width, state.size.height
+            );
+        }
+        return;
+    }
+
+    windowed.replace(WindowedState {
+        size: window.inner_size(),
+        position: window.outer_position().ok(),
+    });
+    let monitor = window.current_monitor();
+    // 独占全屏：选择分辨率、刷新率和色深最高的视频模式
+    let video_mode = exclusive
+        .then(|| {
+            monitor.as_ref()?.video_modes().max_by_key(|mode| {
+                let size = mode.size();
+                (
+                    size.width * size.height,
+                    mode.refresh_rate_millihertz(),
+                    mode.bit_depth(),
+                )
+            })
+        })
+        .flatten();
+    match video_mode {
+        Some(mode) => {
+            info!(
+                "Entering exclusive fullscreen at {}x{} @ {:.2} Hz",
+                mode.size().width,
+                mode.size().height,
+                mode.refresh_rate_millihertz() as f64 / 1000.0
+            );
+            window.set_fullscreen(Some(Fullscreen::Exclusive(mode)));
+        }
+        None => {
+            if exclusive {
+                warn!("No video mode available for exclusive fullscreen, using borderless");
+            }
+            let size = monitor
+                .as_ref()
+                .map(MonitorHandle::size)
+                .unwrap_or_default();
+            info!(
+                "Entering borderless fullscreen at {}x{}",
+                size.width, size.height
+            );
+            window.set_fullscreen(Some(Fullscreen::Borderless(monitor)));
+        }
+    }
+}
+
+/// 方向键调整背景色的速度（每秒的颜色分量变化量）
+const CLEAR_COLOR_SPEED: f64 = 0.5;
+
+/// 文件悬停在窗口上时的背景色（提示可以拖放）
+const HOVER_COLOR: wgpu::Color = wgpu::Color {
+    r: 0.1,
+    g: 0.35,
+    b: 0.2,
+    a: 1.0,
+};
+
+/// 方向键调整背景色：左右改变红色分量，上下改变绿色分量
+///
+/// 手柄的摇杆留给场景的相机（见`FlyController`），不参与调整背景色。
+fn nudge_clear_color<R: Scene>(app: &mut WgpuApp<R>, input: &InputState, dt: Duration) {
+    let axis = |negative, positive| {
+        (input.pressed(positive) as i32 - input.pressed(negative) as i32) as f64
+    };
+    let dx = axis(KeyCode::ArrowLeft, KeyCode::ArrowRight);
+    let dy = axis(KeyCode::ArrowDown, KeyCode::ArrowUp);
+    if dx == 0.0 && dy == 0.0 {
+        return;
+    }
+    let step = CLEAR_COLOR_SPEED * dt.as_secs_f64();
+    let color = app.clear_color;
+    app.set_clear_color(wgpu::Color {
+        r: (color.r + dx * step).clamp(0.0, 1.0),
+        g: (color.g + dy * step).clamp(0.0, 1.0),
+        ..color
+    });
+}
+
+/// 在Fifo → Mailbox → Immediate之间循环切换呈现模式，跳过不支持的模式
+fn cycle_present_mode<R: Scene>(app: &mut WgpuApp<R>) {
+    const MODES: [wgpu::PresentMode; 3] = [
+        wgpu::PresentMode::Fifo,
+        wgpu::PresentMode::Mailbox,
+        wgpu::PresentMode::Immediate,
+    ];
+    let current = MODES
+        .iter()
+        .position(|mode| *mode == app.config.present_mode)
+        .unwrap_or(0);
+    let next = (1..=MODES.len())
+        .map(|offset| MODES[(current + offset) % MODES.len()])
+        .find(|mode| app.present_modes.contains(mode));
+    if let Some(Err(e)) = next.map(|mode| app.set_present_mode(mode)) {
+        error!("{e}");
+    }
+}
+
+/// 在窗口标题中显示帧耗时、FPS、暂停状态、时间倍率、资源池占用、线框提示和场景状态（见`Scene::status`）
+fn update_title<R: Scene>(app: &WgpuApp<R>, title: &str, timer: &FrameTimer) {
+    let paused = if timer.is_paused() { " (paused)" } else { "" };
+    let time_scale = match timer.time_scale() {
+        1.0 => String::new(),
+        scale => format!(" ({scale}x)"),
+    };
+    let status = app
+        .renderer
+        .as_ref()
+        .and_then(Scene::status)
+        .map(|status| format!(" \u{2014} {status}"))
+        .unwrap_or_default();
+    // 开启了线框但场景不支持时提示，而不是看起来毫无反应
+    let wireframe = match &app.renderer {
+        Some(renderer) if app.wireframe && !renderer.supports_wireframe() => {
+            " (wireframe unsupported)"
+        }
+        _ => "",
+    };
+    // 设备支持时间戳查询时附上渲染通道的平均GPU耗时
+    let gpu = app
+        .average_gpu_time()
+        .map(|gpu_time| format!(" / GPU {:.2} ms", gpu_time.as_secs_f64() * 1000.0))
+        .unwrap_or_default();
+    // 资源池不为空时附上池中资源的数量和占用（使用中 + 空闲）
+    let stats = app.pool.borrow().stats();
+    let pool = if stats.buffers + stats.textures > 0 {
+        format!(
+            " / pool {}b {}t {}+{} KiB",
+            stats.buffers,
+            stats.textures,
+            stats.live_bytes / 1024,
+            stats.pooled_bytes / 1024
+        )
+    } else {
+        String::new()
+    };
+    app.window.set_title(&format!(
+        "{title} \u{2014} {:.1} ms / {:.0} FPS{gpu}{pool}{paused}{time_scale}{wireframe}{status}",
+        timer.average_frame_time().as_secs_f64() * 1000.0,
+        timer.fps()
+    ));
+}
+
+/// 连接了手柄时的轮询间隔
+#[cfg(feature = "gamepad")]
+const GAMEPAD_POLL_INTERVAL: Duration = Duration::from_millis(16);
+
+/// 没有手柄时检查热插拔的间隔
+#[cfg(feature = "gamepad")]
+const GAMEPAD_HOTPLUG_INTERVAL: Duration = Duration::from_millis(500);
+
+/// 帧率上限热键依次切换的值
+const FRAME_LIMITS: [Option<u32>; 4] = [None, Some(30), Some(60), Some(144)];
+
+/// 下一个帧率上限（当前值不在列表中时从头开始）
+fn next_frame_limit(current: Option<u32>) -> Option<u32> {
+    let index = FRAME_LIMITS.iter().position(|limit| *limit == current);
+    FRAME_LIMITS[index.map_or(0, |index| (index + 1) % FRAME_LIMITS.len())]
+}
+
+/// 帧率上限对应的下一帧开始时间
+///
+/// 未设置上限，或者开启了垂直同步且上限不低于显示器刷新率（垂直同步已经限制了帧率）时返回None。
+fn next_frame_deadline<R: Scene>(
+    app: &WgpuApp<R>,
+    frame_limit: Option<u32>,
+    frame_start: Instant,
+) -> Option<Instant> {
+    let fps = frame_limit.filter(|fps| *fps > 0)?;
+    let vsync = matches!(
+        app.config.present_mode,
+        wgpu::PresentMode::Fifo | wgpu::PresentMode::FifoRelaxed | wgpu::PresentMode::AutoVsync
+    );
+    let refresh_rate = app
+        .window
+        .current_monitor()
+        .and_then(|monitor| monitor.refresh_rate_millihertz())
+        .map(|millihertz| millihertz as f64 / 1000.0);
+    if vsync && refresh_rate.is_some_and(|hz| fps as f64 >= hz) {
+        return None;
+    }
+    Some(frame_start + Duration::from_secs_f64(1.0 / fps as f64))
+}
+
+/// 截图保存路径：可执行文件所在目录下的screenshot-YYYYMMDD-HHMMSS.png
+#[cfg(not(target_arch = "wasm32"))]
+fn screenshot_path() -> PathBuf {
+    let dir = std::env::current_exe()
+        .ok()
+        .and_then(|exe| exe.parent().map(|dir| dir.to_path_buf()))
+        .unwrap_or_default();
+    let name = chrono::Local::now().format("screenshot-%Y%m%d-%H%M%S.png");
+    dir.join(name.to_string())
+}
+
+/// 未设置WGPU_BACKEND时使用的图形后端（Android上同时启用GL，供不支持Vulkan的设备使用）
+#[cfg(not(target_os = "android"))]
+const DEFAULT_BACKENDS: wgpu::Backends = wgpu::Backends::PRIMARY;
+#[cfg(target_os = "android")]
+const DEFAULT_BACKENDS: wgpu::Backends = wgpu::Backends::VULKAN.union(wgpu::Backends::GL);
+
+/// 判断保存的位置时允许越出显示器的距离（物理像素），Windows上贴边的窗口边框位于屏幕外
+#[cfg(not(target_arch = "wasm32"))]
+const PLACEMENT_TOLERANCE: i32 = 16;
+
+/// 主窗口的初始位置：上次所在的显示器仍然存在且位置在其范围内时恢复，否则居中于主显示器
+///
+/// 拔掉显示器后保存的位置可能完全在屏幕外，此时同样居中；无法获取显示器（如Wayland）时由系统决定。
+#[cfg(not(target_arch = "wasm32"))]
+fn initial_position(
+    window: &crate::config::WindowSettings,
+    event_loop: &ActiveEventLoop,
+) -> Option<PhysicalPosition<i32>> {
+    if let (Some((x, y)), Some(name)) = (window.position, window.monitor.as_deref()) {
+        match event_loop
+            .available_monitors()
+            .find(|monitor| monitor.name().as_deref() == Some(name))
+        {
+            Some(monitor) if monitor_contains(&monitor, x, y) => {
+                return Some(PhysicalPosition::new(x, y));
+            }
+            Some(_) => info!("Saved window position is off-screen, centering the window"),
+            None => info!("Monitor {name} is no longer connected, centering the window"),
+        }
+    }
+    let monitor = event_loop
+        .primary_monitor()
+        .or_else(|| event_loop.available_monitors().next())?;
+    let size: PhysicalSize<i32> =
+        LogicalSize::new(window.width, window.height).to_physical(monitor.scale_factor());
+    let (origin, bounds) = (monitor.position(), monitor.size());
+    Some(PhysicalPosition::new(
+        origin.x + (bounds.width as i32 - size.width).max(0) / 2,
+        origin.y + (bounds.height as i32 - size.height).max(0) / 2,
+    ))
+}
+
+/// 窗口左上角是否位于显示器范围内（允许少量越界）
+#[cfg(not(target_arch = "wasm32"))]
+fn monitor_contains(monitor: &MonitorHandle, x: i32, y: i32) -> bool {
+    let (origin, size) = (monitor.position(), monitor.size());
+    let left = origin.x - PLACEMENT_TOLERANCE;
+    let top = origin.y - PLACEMENT_TOLERANCE;
+    (left..origin.x + size.width as i32).contains(&x)
+        && (top..origin.y + size.height as i32).contains(&y)
+}
+
+/// WGPU应用配置（时间戳查询、BC纹理压缩和桌面端的管线缓存、线框模式作为可选特性，不支持时自动跳过）
+///
+/// 启动和设备丢失后重新初始化时共用，呈现模式、多重采样数等来自配置文件的图形设置。
+fn app_builder(graphics: &GraphicsSettings) -> WgpuAppBuilder {
+    // 通过WGPU_ADAPTER环境变量选择适配器（序号或名称子串，如`WGPU_ADAPTER=nvidia`）
+    let adapter = std::env::var("WGPU_ADAPTER")
+        .map(|value| AdapterSelection::from(value.as_str()))
+        .unwrap_or_default();
+    // 通过WGPU_BACKEND环境变量选择图形后端
+    let backends = std::env::var("WGPU_BACKEND")
+        .ok()
+        .and_then(|value| parse_backends(&value))
+        .unwrap_or(DEFAULT_BACKENDS);
+    let builder = WgpuAppBuilder::new()
+        .label("Wgpu Learn Device")
+        .backends(backends)
+        .adapter(adapter)
+        .graphics_settings(graphics)
+        .fixed_timestep(Duration::from_millis(20)); // 50Hz固定更新，与显示器刷新率无关
+    #[cfg(not(target_arch = "wasm32"))]
+    let builder = builder.optional_features(
+        wgpu::Features::TIMESTAMP_QUERY
+            | wgpu::Features::PIPELINE_CACHE
+            | wgpu::Features::POLYGON_MODE_LINE
+            | wgpu::Features::MULTI_DRAW_INDIRECT
+            | wgpu::Features::MULTI_DRAW_INDIRECT_COUNT
+            | wgpu::Features::PUSH_CONSTANTS
+            | wgpu::Features::TEXTURE_COMPRESSION_BC
+            | wgpu::Features::TEXTURE_ADAPTER_SPECIFIC_FORMAT_FEATURES,
+    );
+    #[cfg(target_arch = "wasm32")]
+    let builder = builder.optional_features(
+        wgpu::Features::TIMESTAMP_QUERY | wgpu::Features::TEXTURE_COMPRESSION_BC,
+    );
+    builder
+}
+
+impl<R: Scene + wgpu::WasmNotSend + 'static> WindowState<R> {
+    fn new(
+        app: WgpuApp<R>,
+        init: RendererInit<R>,
+        title: impl Into<String>,
+        frame_limit: Option<u32>,
+    ) -> Self {
+        Self {
+            app,
+            init,
+            title: title.into(),
+            frame_timer: FrameTimer::new(),
+            input: InputState::default(),
+            windowed: None,
+            tint_with_input: true,
+            hover_saved_color: None,
+            mouse_look: None,
+            frame_limit,
+            next_frame: None,
+        }
+    }
+
+    /// 设备丢失后用同一个窗口重新初始化（先释放旧的设备和表面），保留背景色
+    #[cfg(not(target_arch = "wasm32"))]
+    fn rebuild(self, graphics: &GraphicsSettings) -> Result<Self, WgpuAppError> {
+        let window = self.app.window.clone();
+        let clear_color = self.app.clear_color;
+        let builder = app_builder(graphics).label_prefix(self.app.labels.prefix());
+        let (init, title, tint_with_input, frame_limit) = (
+            self.init,
+            self.title.clone(),
+            self.tint_with_input,
+            self.frame_limit,
+        );
+        drop(self);
+        // 重建后使用独立的新设备（共用设备的其他窗口会各自检测到设备丢失并重建）
+        let mut app = pollster::block_on(builder.build_with(window.clone(), init))?;
+        app.clear_color = clear_color;
+        window.request_redraw();
+        Ok(Self {
+            tint_with_input,
+            ..Self::new(app, init, title, frame_limit)
+        })
+    }
+
+    /// 浏览器上无法阻塞等待重新初始化，设备丢失后只能刷新页面
+    #[cfg(target_arch = "wasm32")]
+    fn rebuild(self, _graphics: &GraphicsSettings) -> Result<Self, WgpuAppError> {
+        Err(WgpuAppError::DeviceLost)
+    }
+
+    /// 窗口模式下的尺寸（全屏时为进入全屏前的尺寸），换算为逻辑像素；最小化时为None
+    fn windowed_size(&self) -> Option<LogicalSize<u32>> {
+        let size = self
+            .windowed
+            .as_ref()
+            .map_or(self.app.window.inner_size(), |windowed| windowed.size);
+        (size.width > 0 && size.height > 0).then(|| size.to_logical(self.app.scale_factor))
+    }
+
+    /// 窗口的位置和状态（全屏时为进入全屏前的位置），最大化或最小化时不记录尺寸和位置
+    fn placement(&self) -> WindowPlacement {
+        let window = &self.app.window;
+        let maximized = self.windowed.is_none() && window.is_maximized();
+        let restorable = !maximized && !self.app.minimized;
+        let position = match &self.windowed {
+            Some(windowed) => windowed.position,
+            None => window.outer_position().ok(),
+        };
+        WindowPlacement {
+            size: self
+                .windowed_size()
+                .filter(|_| restorable)
+                .map(|size| (size.width, size.height)),
+            position: position
+                .filter(|_| restorable)
+                .map(|position| (position.x, position.y)),
+            monitor: window.current_monitor().and_then(|monitor| monitor.name()),
+            maximized,
+        }
+    }
+
+    /// 按需重绘模式下，场景或应用层状态（背景色等）变化，或者正按住方向键调整背景色时请求重绘
+    fn request_redraw_if_needed(&mut self) {
+        if self.app.redraw_mode() != RedrawMode::OnDemand || !self.app.is_visible() {
+            return;
+        }
+        let dirty = self.app.needs_redraw();
+        let nudging = self.tint_with_input
+            && [
+                KeyCode::ArrowLeft,
+                KeyCode::ArrowRight,
+                KeyCode::ArrowUp,
+                KeyCode::ArrowDown,
+            ]
+            .into_iter()
+            .any(|key| self.input.pressed(key));
+        if dirty || nudging {
+            self.app.window.request_redraw();
+        }
+    }
+
+    /// 事件循环即将等待时调用：应用已经稳定的尺寸变化，到达下一帧的开始时间时请求重绘
+    ///
+    /// 返回该窗口下一次需要唤醒事件循环的时间（等待中的尺寸变化或下一帧，取较早者）。
+    fn about_to_wait(&mut self) -> Option<Instant> {
+        self.app.settle_resize();
+        if let Some(deadline) = self.next_frame
+            && Instant::now() >= deadline
+        {
+            self.next_frame = None;
+            if self.app.is_visible() {
+                self.app.window.request_redraw();
+            }
+        }
+        self.app
+            .resize_deadline()
+            .into_iter()
+            .chain(self.next_frame)
+            .min()
+    }
+
+    /// 开启或关闭鼠标视角模式：锁定并隐藏光标，移动量改用设备的原始移动；关闭时恢复光标
+    fn set_mouse_look(&mut self, enabled: bool) {
+        if enabled == self.mouse_look.is_some() {
+            return;
+        }
+        let window = &self.app.window;
+        if enabled {
+            // 优先锁定光标，不支持锁定的平台（X11、Windows）退而把光标限制在窗口内
+            let grabbed = window
+                .set_cursor_grab(CursorGrabMode::Locked)
+                .or_else(|_| window.set_cursor_grab(CursorGrabMode::Confined));
+            if let Err(e) = grabbed {
+                warn!("Failed to grab the cursor: {e}");
+                return;
+            }
+            window.set_cursor_visible(false);
+            self.mouse_look = Some(MouseLookState {
+                held: false,
+                cursor: self.input.cursor(),
+            });
+            info!("Mouse look enabled");
+        } else {
+            if let Err(e) = window.set_cursor_grab(CursorGrabMode::None) {
+                warn!("Failed to release the cursor: {e}");
+            }
+            window.set_cursor_visible(true);
+            if let Some(position) = self.mouse_look.take().and_then(|state| state.cursor) {
+                let _ = window.set_cursor_position(position);
+            }
+            info!("Mouse look disabled");
+        }
+        self.input.set_raw_motion(enabled);
+    }
+
+    /// 处理发送给该窗口的事件
+    fn window_event(&mut self, event: WindowEvent) -> WindowAction {
+        let app = &mut self.app;
+
+        // 更新输入状态（在场景消费事件之前，保证按键状态始终完整）
+        self.input.handle_event(&event);
+
+        // 先交给场景处理，被场景消费的事件不再继续处理（重绘请求始终由应用驱动）
+        if !matches!(event, WindowEvent::RedrawRequested) && app.input(&event) {
+            self.input.mark_consumed();
+            self.request_redraw_if_needed();
+            return WindowAction::None;
+        }
+
+        match event {
+            // 关闭窗口请求：只关闭该窗口
+            WindowEvent::CloseRequested => {
+                info!("Window close requested");
+                return WindowAction::Close;
+            }
+
+            // 键盘输入：Esc键退出
+            WindowEvent::KeyboardInput {
+                event:
+                    KeyEvent {
+                        physical_key: PhysicalKey::Code(KeyCode::Escape),
+                        state: ElementState::Pressed,
+                        repeat: false,
+                        ..
+                    },
+                ..
+            } => {
+                info!("Escape pressed, exiting");
+                return WindowAction::Exit;
+            }
+
+            // 键盘输入：N键打开共用设备的调试窗口
+            WindowEvent::KeyboardInput {
+                event:
+                    KeyEvent {
+                        physical_key: PhysicalKey::Code(KeyCode::KeyN),
+                        state: ElementState::Pressed,
+                        repeat: false,
+                        ..
+                    },
+                ..
+            } => return WindowAction::OpenDebugWindow,
+
+            // 键盘输入：空格键暂停或继续，Shift+空格在暂停时单步执行一次固定更新
+            WindowEvent::KeyboardInput {
+                event:
+                    KeyEvent {
+                        physical_key: PhysicalKey::Code(KeyCode::Space),
+                        state: ElementState::Pressed,
+                        repeat: false,
+                        ..
+                    },
+                ..
+            } => {
+                let timer = &mut self.frame_timer;
+                if self.input.modifiers().shift_key() {
+                    timer.set_paused(true);
+                    timer.step(app.fixed_timestep.step);
+                } else {
+                    timer.set_paused(!timer.is_paused());
+                    info!(
+                        "{}",
+                        if timer.is_paused() {
+                            "Paused"
+                        } else {
+                            "Resumed"
+                        }
+                    );
+                }
+                update_title(app, &self.title, timer);
+            }
+
+            // 键盘输入：[键慢放、]键快进（时间倍率减半或加倍），暂停和慢放同样作用于着色器中的globals.time
+            WindowEvent::KeyboardInput {
+                event:
+                    KeyEvent {
+                        physical_key:
+                            PhysicalKey::Code(key @ (KeyCode::BracketLeft | KeyCode::BracketRight)),
+                        state: ElementState::Pressed,
+                        repeat: false,
+                        ..
+                    },
+                ..
+            } => {
+                let timer = &mut self.frame_timer;
+                let factor = if key == KeyCode::BracketLeft {
+                    0.5
+                } else {
+                    2.0
+                };
+                timer.set_time_scale(timer.time_scale() * factor);
+                info!("Time scale: {}x", timer.time_scale());
+                update_title(app, &self.title, timer);
+            }
+
+            // 键盘输入：Tab键切换鼠标视角模式
+            WindowEvent::KeyboardInput {
+                event:
+                    KeyEvent {
+                        physical_key: PhysicalKey::Code(KeyCode::Tab),
+                        state: ElementState::Pressed,
+                        repeat: false,
+                        ..
+                    },
+                ..
+            } => self.set_mouse_look(self.mouse_look.is_none()),
+
+            // 鼠标右键：按住期间开启鼠标视角模式（已用Tab开启时不受影响）
+            WindowEvent::MouseInput {
+                state,
+                button: MouseButton::Right,
+                ..
+            } => match state {
+                ElementState::Pressed if self.mouse_look.is_none() => {
+                    self.set_mouse_look(true);
+                    if let Some(mouse_look) = &mut self.mouse_look {
+                        mouse_look.held = true;
+                    }
+                }
+                ElementState::Released if self.mouse_look.as_ref().is_some_and(|m| m.held) => {
+                    self.set_mouse_look(false);
+                }
+                _ => {}
+            },
+
+            // 失去焦点时退出鼠标视角模式，避免光标被困在窗口中
+            WindowEvent::Focused(false) => self.set_mouse_look(false),
+
+            // 键盘输入：V键切换呈现模式
+            WindowEvent::KeyboardInput {
+                event:
+                    KeyEvent {
+                        physical_key: PhysicalKey::Code(KeyCode::KeyV),
+                        state: ElementState::Pressed,
+                        repeat: false,
+                        ..
+                    },
+                ..
+            } => {
+                cycle_present_mode(app);
+                info!(
+                    "Present mode: {:?}, frame time: {:.2?}",
+                    app.config.present_mode,
+                    self.frame_timer.average_frame_time()
+                );
+            }
+
+            // 键盘输入：L键切换帧率上限（不限制、30、60、144）
+            WindowEvent::KeyboardInput {
+                event:
+                    KeyEvent {
+                        physical_key: PhysicalKey::Code(KeyCode::KeyL),
+                        state: ElementState::Pressed,
+                        repeat: false,
+                        ..
+                    },
+                ..
+            } => {
+                self.frame_limit = next_frame_limit(self.frame_limit);
+                match self.frame_limit {
+                    Some(fps) => info!("Frame limit: {fps} FPS"),
+                    None => info!("Frame limit: off"),
+                }
+                // 关闭上限时不再等待已经安排的下一帧
+                if self.frame_limit.is_none() && self.next_frame.take().is_some() {
+                    app.window.request_redraw();
+                }
+            }
+
+            // 键盘输入：M键在1倍和4倍多重采样之间切换
+            WindowEvent::KeyboardInput {
+                event:
+                    KeyEvent {
+                        physical_key: PhysicalKey::Code(KeyCode::KeyM),
+                        state: ElementState::Pressed,
+                        repeat: false,
+                        ..
+                    },
+                ..
+            } => {
+                let sample_count = if app.sample_count == 1 { 4 } else { 1 };
+                match app.set_sample_count(sample_count) {
+                    Ok(()) => info!("MSAA sample count: {sample_count}"),
+                    Err(e) => error!("{e}"),
+                }
+            }
+
+            // 键盘输入：Z键在填充和线框之间切换（只对支持线框的场景生效）
+            WindowEvent::KeyboardInput {
+                event:
+                    KeyEvent {
+                        physical_key: PhysicalKey::Code(KeyCode::KeyZ),
+                        state: ElementState::Pressed,
+                        repeat: false,
+                        ..
+                    },
+                ..
+            } => {
+                app.set_wireframe(!app.wireframe);
+                info!("Wireframe: {}", if app.wireframe { "on" } else { "off" });
+                let supported = app.renderer.as_ref().is_none_or(Scene::supports_wireframe);
+                if app.wireframe && !supported {
+                    warn!("This scene does not support wireframe rendering");
+                }
+            }
+
+            // 键盘输入：F11键切换无边框全屏，Shift+F11切换独占全屏
+            WindowEvent::KeyboardInput {
+                event:
+                    KeyEvent {
+                        physical_key: PhysicalKey::Code(KeyCode::F11),
+                        state: ElementState::Pressed,
+                        repeat: false,
+                        ..
+                    },
+                ..
+            } => {
+                let exclusive = self.input.modifiers().shift_key();
+                toggle_fullscreen(&app.window, exclusive, &mut self.windowed);
+            }
+
+            // 键盘输入：F12键截图（浏览器上不支持）
+            #[cfg(not(target_arch = "wasm32"))]
+            WindowEvent::KeyboardInput {
+                event:
+                    KeyEvent {
+                        physical_key: PhysicalKey::Code(KeyCode::F12),
+                        state: ElementState::Pressed,
+                        repeat: false,
+                        ..
+                    },
+                ..
+            } => {
+                let path = screenshot_path();
+                match app.capture_frame(&path) {
+                    Ok(()) => info!("Saved screenshot to {}", path.display()),
+                    Err(e) => error!("{}", user_message(&e)),
+                }
+            }
+
+            // 重绘请求（驱动渲染循环）
+            WindowEvent::RedrawRequested => {
+                // 记录帧耗时（过长的停顿会被截断）
+                let frame_start = Instant::now();
+                let timer = &mut self.frame_timer;
+                let dt = timer.tick();
+
+                // 更新场景逻辑，方向键调整背景色
+                app.update(dt, &self.input);
+                if self.tint_with_input {
+                    nudge_clear_color(app, &self.input, dt);
+                }
+
+                // 执行窗口预呈现通知
+                app.window.pre_present_notify();
+
+                // 执行实际渲染操作（设备丢失时重新初始化，其他致命错误退出事件循环）
+                match app.render() {
+                    Ok(()) => {}
+                    Err(WgpuAppError::DeviceLost) => {
+                        warn!("GPU device lost, re-initializing");
+                        return WindowAction::Rebuild;
+                    }
+                    Err(e) => {
+                        error!("{}", user_message(&e));
+                        return WindowAction::Exit;
+                    }
+                }
+
+                // 每秒在窗口标题中更新帧耗时、FPS和GPU耗时，并打印GPU耗时（设备支持时间戳查询时）
+                if timer.fps_updated() {
+                    update_title(app, &self.title, timer);
+                    if let Some(gpu_time) = app.last_gpu_time() {
+                        info!(
+                            "GPU time: {gpu_time:.2?} (average {:.2?})",
+                            app.average_gpu_time().unwrap_or_default()
+                        );
+                    }
+                }
+
+                // 清空本帧的按下/松开集合
+                self.input.end_frame();
+
+                // 请求下一帧重绘（维持持续渲染，最小化、遮挡或挂起时停止以免空转CPU）
+                // 限制帧率时等到下一帧的开始时间再请求（见App::about_to_wait），已经超时则立即请求
+                // 按需重绘的场景不在这里请求，由request_redraw_if_needed决定
+                if app.is_visible() && app.redraw_mode() == RedrawMode::Continuous {
+                    match next_frame_deadline(app, self.frame_limit, frame_start) {
+                        Some(deadline) if deadline > Instant::now() => {
+                            self.next_frame = Some(deadline);
+                        }
+                        _ => app.window.request_redraw(),
+                    }
+                }
+            }
+
+            // 窗口大小变化事件
+            WindowEvent::Resized(size) => {
+                // 记录新的尺寸，表面在下一帧渲染前或尺寸稳定后才重新配置
+                app.resize(size);
+                debug!("Window resized to {:?}", size);
+                // 从最小化恢复时重新启动渲染循环
+                if app.is_visible() {
+                    app.window.request_redraw();
+                }
+            }
+
+            // 窗口被完全遮挡时暂停渲染（事件循环进入等待），重新可见时立即重绘，避免显示过期内容
+            WindowEvent::Occluded(occluded) => {
+                app.set_occluded(occluded);
+                if app.is_visible() {
+                    app.window.request_redraw();
+                }
+            }
+
+            // 缩放因子变化（拖到DPI不同的显示器上），尺寸变化由随后的Resized事件处理
+            WindowEvent::ScaleFactorChanged { scale_factor, .. } => {
+                app.set_scale_factor(scale_factor);
+            }
+
+            // 鼠标移动：根据光标的归一化位置改变背景色（x映射到红色，y映射到绿色）
+            WindowEvent::CursorMoved { .. } if self.tint_with_input => {
+                if let Some((x, y)) = self.input.cursor_normalized(&app.config) {
+                    app.set_clear_color(wgpu::Color {
+                        r: x,
+                        g: y,
+                        b: 0.3,
+                        a: 1.0,
+                    });
+                }
+            }
+
+            // 文件悬停在窗口上：改变背景色提示可以拖放（悬停多个文件时会收到多次）
+            WindowEvent::HoveredFile(_) if self.hover_saved_color.is_none() => {
+                self.hover_saved_color = Some(app.clear_color);
+                app.set_clear_color(HOVER_COLOR);
+            }
+
+            // 拖放取消：恢复原背景色
+            WindowEvent::HoveredFileCancelled => {
+                if let Some(color) = self.hover_saved_color.take() {
+                    app.set_clear_color(color);
+                }
+            }
+
+            // 文件拖放：恢复原背景色并交给场景（多个文件按顺序各产生一次事件）
+            WindowEvent::DroppedFile(path) => {
+                if let Some(color) = self.hover_saved_color.take() {
+                    app.set_clear_color(color);
+                }
+                info!("Dropped {}", path.display());
+                app.file_dropped(&path);
+            }
+
+            // 其他未处理事件
+            _ => {}
+        }
+        self.request_redraw_if_needed();
+        WindowAction::None
+    }
+}
+
+impl AppWindow {
+    /// 将事件转发给对应场景类型的窗口状态
+    fn window_event(&mut self, event: WindowEvent) -> WindowAction {
+        match self {
+            AppWindow::Demo(state) => state.window_event(event),
+            AppWindow::Debug(state) => state.window_event(event),
+        }
+    }
+
+    /// 事件循环即将等待时调用，返回该窗口下一次需要唤醒事件循环的时间
+    fn about_to_wait(&mut self) -> Option<Instant> {
+        match self {
+            AppWindow::Demo(state) => state.about_to_wait(),
+            AppWindow::Debug(state) => state.about_to_wait(),
+        }
+    }
+
+    /// 将设备事件（鼠标原始移动等）转发给窗口的输入状态
+    fn device_event(&mut self, event: &DeviceEvent) {
+        match self {
+            AppWindow::Demo(state) => state.input.handle_device_event(event),
+            AppWindow::Debug(state) => state.input.handle_device_event(event),
+        }
+    }
+
+    /// 从后台恢复：重新创建表面并请求重绘
+    fn resume(&mut self) -> Result<(), WgpuAppError> {
+        let app_window = match self {
+            AppWindow::Demo(state) => state.app.resume().map(|()| &state.app.window),
+            AppWindow::Debug(state) => state.app.resume().map(|()| &state.app.window),
+        };
+        app_window.map(|window| window.request_redraw())
+    }
+
+    /// 关闭前等待GPU完成并执行场景清理
+    fn shutdown(&mut self) {
+        match self {
+            AppWindow::Demo(state) => state.app.shutdown(),
+            AppWindow::Debug(state) => state.app.shutdown(),
+        }
+    }
+
+    /// 进入后台：释放表面
+    fn suspend(&mut self) {
+        match self {
+            AppWindow::Demo(state) => state.app.suspend(),
+            AppWindow::Debug(state) => state.app.suspend(),
+        }
+    }
+
+    /// 设备丢失后重新初始化
+    fn rebuild(self, graphics: &GraphicsSettings) -> Result<Self, WgpuAppError> {
+        match self {
+            AppWindow::Demo(state) => state.rebuild(graphics).map(AppWindow::Demo),
+            AppWindow::Debug(state) => state.rebuild(graphics).map(AppWindow::Debug),
+        }
+    }
+
+    /// 在该窗口的设备上为另一个窗口构建只清除背景的WgpuApp
+    #[cfg(not(target_arch = "wasm32"))]
+    fn build_shared(
+        &self,
+        builder: WgpuAppBuilder,
+        window: Arc<Window>,
+    ) -> Result<WgpuApp<Clear>, WgpuAppError> {
+        match self {
+            AppWindow::Demo(state) => pollster::block_on(builder.build_shared(window, &state.app)),
+            AppWindow::Debug(state) => pollster::block_on(builder.build_shared(window, &state.app)),
+        }
+    }
+}
+
+impl App {
+    /// 对指定窗口执行操作，窗口不存在（例如已关闭）时返回None
+    fn with_window<T>(&mut self, id: WindowId, f: impl FnOnce(&mut AppWindow) -> T) -> Option<T> {
+        self.windows.get_mut(&id).map(f)
+    }
+
+    /// 创建主窗口和WGPU应用，失败时退出事件循环
+    fn create_main_window(&mut self, event_loop: &ActiveEventLoop) {
+        // 1. 创建窗口（标题、尺寸、位置等来自配置文件）
+        self.icon = window_icon(self.settings.window.icon.as_deref());
+        let attributes = self
+            .settings
+            .window
+            .window_attributes()
+            .with_window_icon(self.icon.clone());
+        #[cfg(not(target_arch = "wasm32"))]
+        let attributes = match initial_position(&self.settings.window, event_loop) {
+            Some(position) => attributes.with_position(position),
+            None => attributes,
+        };
+        #[cfg(target_arch = "wasm32")]
+        let attributes = attach_canvas(attributes);
+        let window = match event_loop.create_window(attributes) {
+            Ok(window) => Arc::new(window),
+            Err(e) => {
+                error!("Failed to create window: {e}");
+                self.shutdown(event_loop);
+                return;
+            }
+        };
+
+        // 2. 初始化WGPU应用，调试标签以演示名称为前缀
+        info!("Running demo `{}`", self.demo.name);
+        let builder = app_builder(&self.settings.graphics).label_prefix(self.demo.name);
+        // 桌面上使用pollster阻塞等待异步初始化
+        #[cfg(not(target_arch = "wasm32"))]
+        {
+            let result = pollster::block_on(builder.build_with(window.clone(), self.demo.init));
+            // 部分旧的Android设备上Vulkan驱动不可用或无法创建设备，改用GL后端重试
+            #[cfg(target_os = "android")]
+            let result = match result {
+                Err(e @ (WgpuAppError::NoAdapter(_) | WgpuAppError::RequestDevice(_))) => {
+                    warn!("{e}, retrying with the GL backend");
+                    let builder = app_builder(&self.settings.graphics)
+                        .label_prefix(self.demo.name)
+                        .backends(wgpu::Backends::GL);
+                    pollster::block_on(builder.build_with(window, self.demo.init))
+                }
+                result => result,
+            };
+            self.main_window_ready(event_loop, result);
+        }
+        // 浏览器上不能阻塞，初始化完成后通过用户事件把WgpuApp交回事件循环
+        #[cfg(target_arch = "wasm32")]
+        {
+            let (proxy, init) = (self.proxy.clone(), self.demo.init);
+            wasm_bindgen_futures::spawn_local(async move {
+                let result = builder.build_with(window, init).await;
+                let _ = proxy.send_event(AppEvent::MainWindowReady(Box::new(result)));
+            });
+        }
+    }
+
+    /// 主窗口的WGPU应用初始化完成：存储窗口状态并开始计时，失败时退出事件循环
+    fn main_window_ready(
+        &mut self,
+        event_loop: &ActiveEventLoop,
+        result: Result<WgpuApp<BoxedScene>, WgpuAppError>,
+    ) {
+        let wgpu_app = match result {
+            Ok(wgpu_app) => wgpu_app,
+            Err(e) => {
+                error!("{}", user_message(&e));
+                self.shutdown(event_loop);
+                return;
+            }
+        };
+        let id = wgpu_app.window.id();
+        wgpu_app.window.request_redraw();
+        let state = WindowState::new(
+            wgpu_app,
+            self.demo.init,
+            &self.settings.window.title,
+            self.settings.graphics.frame_limit,
+        );
+        self.windows.insert(id, AppWindow::Demo(state));
+    }
+
+    /// 创建一个与指定窗口共用设备的调试窗口
+    #[cfg(not(target_arch = "wasm32"))]
+    fn open_debug_window(&mut self, event_loop: &ActiveEventLoop, shared: WindowId) {
+        let title = format!("{} (debug)", self.settings.window.title);
+        let attributes = self
+            .settings
+            .window
+            .window_attributes()
+            .with_title(&title)
+            .with_inner_size(LogicalSize::new(480, 360))
+            .with_fullscreen(None)
+            .with_window_icon(self.icon.clone());
+        let window = match event_loop.create_window(attributes) {
+            Ok(window) => Arc::new(window),
+            Err(e) => {
+                error!("Failed to create debug window: {e}");
+                return;
+            }
+        };
+        let builder = app_builder(&self.settings.graphics);
+        let Some(result) = self.with_window(shared, |shared| {
+            shared.build_shared(builder, window.clone())
+        }) else {
+            return;
+        };
+        match result {
+            Ok(mut app) => {
+                app.clear_color = DEBUG_WINDOW_COLOR;
+                info!("Opened debug window {:?}", window.id());
+                let state = WindowState {
+                    tint_with_input: false,
+                    ..WindowState::new(app, Clear::init, title, self.settings.graphics.frame_limit)
+                };
+                self.windows.insert(window.id(), AppWindow::Debug(state));
+            }
+            Err(e) => error!("Failed to open debug window: {}", user_message(&e)),
+        }
+    }
+
+    /// 浏览器上只有一个画布，不支持调试窗口
+    #[cfg(target_arch = "wasm32")]
+    fn open_debug_window(&mut self, _event_loop: &ActiveEventLoop, _shared: WindowId) {
+        warn!("Debug windows are not supported in the browser");
+    }
+
+    /// 关闭指定窗口：等待GPU完成并执行场景清理，主窗口关闭时记录窗口位置和状态
+    fn close_window(&mut self, id: WindowId) {
+        let Some(mut window) = self.windows.remove(&id) else {
+            return;
+        };
+        window.shutdown();
+        if let AppWindow::Demo(state) = &window {
+            self.placement = Some(state.placement());
+        }
+    }
+
+    /// 退出应用：停止渲染，关闭所有窗口，把主窗口位置和状态写回配置文件后再退出事件循环
+    ///
+    /// 正常关闭和致命错误共用这一流程。
+    fn shutdown(&mut self, event_loop: &ActiveEventLoop) {
+        if self.close_all_windows() {
+            info!("Shutdown complete");
+            event_loop.exit();
+        }
+    }
+
+    /// 开始退出：关闭所有窗口并写回主窗口位置，已经在退出时什么也不做并返回false
+    fn close_all_windows(&mut self) -> bool {
+        if self.exiting {
+            return false;
+        }
+        self.exiting = true;
+        let ids: Vec<_> = self.windows.keys().copied().collect();
+        for id in ids {
+            self.close_window(id);
+        }
+        if let Some(placement) = &self.placement {
+            Settings::save_window_placement(placement);
+        }
+        true
+    }
+
+    /// 恢复所有窗口：第一次启动时还没有窗口，需要创建主窗口；
+    /// 之后（从后台恢复）只需为每个窗口重新创建表面，设备和管线沿用之前的
+    fn resume_windows(&mut self) -> ResumeAction {
+        if self.windows.is_empty() {
+            return ResumeAction::CreateMainWindow;
+        }
+        let mut action = ResumeAction::Resumed;
+        for window in self.windows.values_mut() {
+            if let Err(e) = window.resume() {
+                error!("{}", user_message(&e));
+                action = ResumeAction::Exit;
+            }
+        }
+        action
+    }
+
+    /// 轮询手柄并把输入写入主窗口，按需重绘模式下有输入时请求重绘（持续重绘模式本来就每帧重绘）
+    ///
+    /// gilrs的事件不会唤醒事件循环，所以返回下一次轮询的时间。
+    #[cfg(feature = "gamepad")]
+    fn poll_gamepad(&mut self) -> Option<Instant> {
+        let gamepad = self.gamepad.as_mut()?;
+        let interval = if gamepad.is_connected() {
+            GAMEPAD_POLL_INTERVAL
+        } else {
+            GAMEPAD_HOTPLUG_INTERVAL
+        };
+        let state = self.windows.values_mut().find_map(|window| match window {
+            AppWindow::Demo(state) => Some(state),
+            AppWindow::Debug(_) => None,
+        })?;
+        let active = gamepad.poll(&mut state.input);
+        if active && state.app.is_visible() && state.app.redraw_mode() == RedrawMode::OnDemand {
+            state.app.window.request_redraw();
+        }
+        Some(Instant::now() + interval)
+    }
+
+    /// 执行窗口事件处理后返回的操作
+    fn handle_action(&mut self, event_loop: &ActiveEventLoop, id: WindowId, action: WindowAction) {
+        match action {
+            WindowAction::None => {}
+            // 只关闭该窗口，最后一个窗口关闭时退出应用
+            WindowAction::Close => {
+                self.close_window(id);
+                if self.windows.is_empty() {
+                    self.shutdown(event_loop);
+                }
+            }
+            WindowAction::Exit => self.shutdown(event_loop),
+            WindowAction::Rebuild => {
+                let Some(window) = self.windows.remove(&id) else {
+                    return;
+                };
+                match window.rebuild(&self.settings.graphics) {
+                    Ok(window) => {
+                        self.windows.insert(id, window);
+                    }
+                    Err(e) => {
+                        error!("{}", user_message(&e));
+                        self.shutdown(event_loop);
+                    }
+                }
+            }
+            WindowAction::OpenDebugWindow => self.open_debug_window(event_loop, id),
+        }
+    }
+}
+
+// ApplicationHandler trait 是 winit 窗口库的核心事件处理接口，主要用于管理应用程序生命周期和窗口事件。
+impl ApplicationHandler<AppEvent> for App {
+    /// 当应用恢复/启动时触发（主要初始化入口）
+    fn resumed(&mut self, event_loop: &ActiveEventLoop) {
+        info!("Resumed");
+        match self.resume_windows() {
+            ResumeAction::CreateMainWindow => self.create_main_window(event_loop),
+            ResumeAction::Resumed => {}
+            ResumeAction::Exit => self.shutdown(event_loop),
+        }
+    }
+
+    /// 处理用户事件（浏览器上异步初始化的结果）
+    fn user_event(&mut self, _event_loop: &ActiveEventLoop, event: AppEvent) {
+        match event {
+            #[cfg(target_arch = "wasm32")]
+            AppEvent::MainWindowReady(result) => self.main_window_ready(_event_loop, *result),
+        }
+    }
+
+    /// 应用进入后台时触发（Android、iOS等），释放表面直到再次恢复
+    fn suspended(&mut self, _event_loop: &ActiveEventLoop) {
+        info!("Suspended");
+        self.windows.values_mut().for_each(AppWindow::suspend);
+    }
+
+    /// 处理窗口事件（核心事件循环），按窗口ID转发给对应的窗口状态
+    fn window_event(
+        &mut self,
+        event_loop: &ActiveEventLoop,
+        window_id: WindowId,
+        event: WindowEvent,
+    ) {
+        // 退出流程开始后不再处理事件；已关闭的窗口可能还会收到少量事件
+        if self.exiting {
+            return;
+        }
+        if let Some(action) = self.with_window(window_id, |window| window.window_event(event)) {
+            self.handle_action(event_loop, window_id, action);
+        }
+    }
+
+    /// 事件处理完、即将等待新事件时触发：应用已经稳定的尺寸变化，并在下一个截止时间唤醒事件循环
+    fn about_to_wait(&mut self, event_loop: &ActiveEventLoop) {
+        if self.exiting {
+            return;
+        }
+        #[cfg(feature = "gamepad")]
+        let gamepad_deadline = self.poll_gamepad();
+        #[cfg(not(feature = "gamepad"))]
+        let gamepad_deadline = None;
+        let deadline = self
+            .windows
+            .values_mut()
+            .filter_map(AppWindow::about_to_wait)
+            .chain(gamepad_deadline)
+            .min();
+        event_loop.set_control_flow(match deadline {
+            Some(deadline) => ControlFlow::WaitUntil(deadline),
+            None => ControlFlow::Wait,
+        });
+    }
+
+    /// 处理设备事件（不属于某个窗口），只有开启鼠标视角模式的窗口会使用
+    fn device_event(
+        &mut self,
+        _event_loop: &ActiveEventLoop,
+        _device_id: DeviceId,
+        event: DeviceEvent,
+    ) {
+        if self.exiting {
+            return;
+        }
+        for window in self.windows.values_mut() {
+            window.device_event(&event);
+        }
+    }
+}
+
+// 浏览器上App需要事件循环的代理，测试只在桌面上运行
+#[cfg(all(test, not(target_arch = "wasm32")))]
+mod tests {
+    use super::*;
+    use crate::DemoRegistry;
+
+    /// 没有窗口的应用（不需要事件循环）
+    fn app() -> App {
+        App {
+            windows: HashMap::new(),
+            settings: Settings::default(),
+            demo: *DemoRegistry::builtin().get("clear").unwrap(),
+            placement: None,
+            icon: None,
+            #[cfg(feature = "gamepad")]
+            gamepad: None,
+            exiting: false,
+        }
+    }
+
+    #[test]
+    fn first_resume_creates_the_main_window() {
+        let mut app = app();
+        assert_eq!(app.resume_windows(), ResumeAction::CreateMainWindow);
+        // 主窗口创建失败时窗口列表仍为空，再次恢复时重新尝试
+        assert_eq!(app.resume_windows(), ResumeAction::CreateMainWindow);
+    }
+
+    #[test]
+    fn shutdown_runs_once() {
+        let mut app = app();
+        assert!(app.close_all_windows());
+        assert!(app.exiting);
+        assert!(!app.close_all_windows());
+        assert!(app.placement.is_none());
+    }
+
+    #[test]
+    fn events_for_closed_windows_are_ignored() {
+        let mut app = app();
+        let id = WindowId::dummy();
+        assert!(app.with_window(id, |_| ()).is_none());
+        app.close_window(id);
+        assert!(app.placement.is_none());
+    }
+
+    #[test]
+    fn frame_limits_cycle_and_restart_from_unknown_values() {
+        let mut limit = None;
+        let mut seen = Vec::new();
+        for _ in 0..FRAME_LIMITS.len() {
+            limit = next_frame_limit(limit);
+            seen.push(limit);
+        }
+        assert_eq!(seen, [Some(30), Some(60), Some(144), None]);
+        assert_eq!(next_frame_limit(Some(75)), None);
+    }
+
+    #[test]
+    fn backend_names_are_case_insensitive() {
+        assert_eq!(parse_backends(" Vulkan "), Some(wgpu::Backends::VULKAN));
+        assert_eq!(parse_backends("GL"), Some(wgpu::Backends::GL));
+        assert_eq!(parse_backends("all"), Some(wgpu::Backends::all()));
+        assert_eq!(parse_backends("directx"), None);
+    }
+}
@@ -0,0 +1,667 @@
+use crate::{
+    Mesh, PbrMaterial, SamplerCache, SamplerDesc, TangentVertex, Texture, WgpuAppError, bounds,
+    generate_tangents,
+    math::{Aabb, Transform},
+    mipmap::MipmapGenerator,
+    scene::{Node, NodeId, SceneGraph},
+};
+use ::gltf::{image::Format, mesh::Mode, texture::WrappingMode};
+use log::{info, warn};
+use std::{
+    collections::HashMap,
+    path::{Path, PathBuf},
+};
+use thiserror::Error;
+
+/// 4x4矩阵，按列存储（`m[列][行]`，与glTF和WGSL的`mat4x4f`一致）
+pub type Mat4 = [[f32; 4]; 4];
+
+/// 单位矩阵
+pub const IDENTITY: Mat4 = [
+    [1.0, 0.0, 0.0, 0.0],
+    [0.0, 1.0, 0.0, 0.0],
+    [0.0, 0.0, 1.0, 0.0],
+    [0.0, 0.0, 0.0, 1.0],
+];
+
+/// glTF文件读取或转换错误
+#[derive(Debug, Error)]
+pub enum GltfError {
+    /// 读取或解析文件（包括外部缓冲区和图片）失败
+    #[error("Failed to import {}: {source}", path.display())]
+    Import {
+        path: PathBuf,
+        source: ::gltf::Error,
+    },
+
+    /// 用到了尚未支持的glTF功能
+    #[error("{}: {feature} (not supported yet)", path.display())]
+    Unsupported { path: PathBuf, feature: String },
+
+    /// 文件内容不符合要求（如图元缺少POSITION属性）
+    #[error("{}: {message}", path.display())]
+    Invalid { path: PathBuf, message: String },
+
+    /// 场景中没有任何网格
+    #[error("{} contains no meshes", .0.display())]
+    NoMeshes(PathBuf),
+}
+
+/// 从glTF文件加载的场景：节点层级和按材质分组、可直接绘制的对象
+#[derive(Debug)]
+pub struct GltfScene {
+    pub graph: SceneGraph, // 节点层级（网格句柄为glTF中的网格下标），世界矩阵已更新
+    pub groups: Vec<MaterialGroup>, // 材质分组（至少一个，按首次出现的顺序）
+    pub bounds: ([f32; 3], [f32; 3]), // 所有对象在世界空间中的包围盒
+}
+
+/// 使用同一材质的对象
+#[derive(Debug)]
+pub struct MaterialGroup {
+    pub material: PbrMaterial,    // 材质
+    pub objects: Vec<GltfObject>, // 使用该材质的对象（至少一个）
+}
+
+/// 一个图元：网格、所属节点和加载时节点的世界变换
+#[derive(Debug)]
+pub struct GltfObject {
+    pub name: String,    // 所属网格的名称（没有时为空）
+    pub mesh: Mesh,      // 顶点为TangentVertex的网格
+    pub node: NodeId,    // 所属节点（`GltfScene::graph`中）
+    pub transform: Mat4, // 模型空间到世界空间的变换
+    pub aabb: Aabb,      // 世界空间的包围盒（网格包围盒经transform变换）
+}
+
+impl GltfScene {
+    /// 所有对象
+    pub fn objects(&self) -> impl Iterator<Item = &GltfObject> {
+        self.groups.iter().flat_map(|group| group.objects.iter())
+    }
+}
+
+/// 加载`.gltf`或`.glb`文件，上传网格和贴图
+///
+/// 使用默认场景（没有时使用第一个场景），节点层级复制到`GltfScene::graph`中，
+/// 每个图元记录所属节点和加载时的世界变换。
+/// glTF与本项目一样是右手坐标系、Y轴向上，坐标无需转换。
+/// 图元没有TANGENT属性但有纹理坐标时用`generate_tangents`计算切线（MikkTSpace约定），法线贴图同样生效。
+/// 目前只支持三角形列表图元和第一套纹理坐标；稀疏访问器和动画会返回`Unsupported`错误，
+/// 蒙皮和变形目标被忽略（按绑定姿势绘制）。贴图的采样器从samplers（通常是应用的缓存）取用。
+pub fn load(
+    device: &wgpu::Device,
+    queue: &wgpu::Queue,
+    samplers: &SamplerCache,
+    path: impl AsRef<Path>,
+) -> Result<GltfScene, WgpuAppError> {
+    let path = path.as_ref();
+    let (document, buffers, images) = ::gltf::import(path).map_err(|source| GltfError::Import {
+        path: path.to_path_buf(),
+        source,
+    })?;
+    let unsupported = |feature: &str| GltfError::Unsupported {
+        path: path.to_path_buf(),
+        feature: feature.to_string(),
+    };
+    if document.animations().next().is_some() {
+        return Err(unsupported("animations").into());
+    }
+
+    // 1. 把节点层级复制到场景图中，计算每个节点的世界变换
+    let scene = document
+        .default_scene()
+        .or_else(|| document.scenes().next())
+        .ok_or_else(|| GltfError::NoMeshes(path.to_path_buf()))?;
+    let mut graph = SceneGraph::new();
+    let mut nodes = Vec::new();
+    for node in scene.nodes() {
+        add_node(node, None, &mut graph, &mut nodes);
+    }
+    graph.update_world_transforms();
+
+    // 2. 每个图元创建一个网格，按材质分组
+    let mut loader = TextureLoader {
+        device,
+        queue,
+        path,
+        images: &images,
+        cache: HashMap::new(),
+        samplers,
+        mipmaps: MipmapGenerator::new(device)?,
+    };
+    let mut groups: Vec<MaterialGroup> = Vec::new();
+    let mut group_of_material = HashMap::new();
+    let mut positions = Vec::new();
+    for (node, id) in nodes {
+        let Some(mesh) = node.mesh() else {
+            continue;
+        };
+        let transform = graph.world_transform(id).to_cols_array_2d();
+        let name = mesh.name().unwrap_or_default();
+        for primitive in mesh.primitives() {
+            let (vertices, indices) = read_primitive(&primitive, &buffers, path)?;
+            positions.extend(
+                vertices
+                    .iter()
+                    .map(|vertex| transform_point(&transform, vertex.position)),
+            );
+            let label = format!("glTF {name} #{}", primitive.index());
+            let mesh = Mesh::new(device, &label, &vertices, indices.as_deref());
+            let object = GltfObject {
+                name: name.to_string(),
+                aabb: mesh
+                    .aabb()
+                    .transform(glam::Mat4::from_cols_array_2d(&transform)),
+                mesh,
+                node: id,
+                transform,
+            };
+            let material = primitive.material();
+            let group = match group_of_material.get(&material.index()) {
+                Some(&group) => group,
+                None => {
+                    groups.push(MaterialGroup {
+                        material: loader.material(&material)?,
+                        objects: Vec::new(),
+                    });
+                    group_of_material.insert(material.index(), groups.len() - 1);
+                    groups.len() - 1
+                }
+            };
+            groups[group].objects.push(object);
+        }
+    }
+    if groups.is_empty() {
+        return Err(GltfError::NoMeshes(path.to_path_buf()).into());
+    }
+    info!(
+        "Loaded {} with {} objects and {} materials",
+        path.display(),
+        groups
+            .iter()
+            .map(|group| group.objects.len())
+            .sum::<usize>(),
+        groups.len()
+    );
+    Ok(GltfScene {
+        graph,
+        groups,
+        bounds: bounds(positions),
+    })
+}
+
+/// 深度优先遍历glTF节点，把节点及其本地变换加入场景图，记录每个glTF节点对应的场景图节点
+fn add_node<'a>(
+    node: ::gltf::Node<'a>,
+    parent: Option<NodeId>,
+    graph: &mut SceneGraph,
+    out: &mut Vec<(::gltf::Node<'a>, NodeId)>,
+) {
+    // glTF规定节点的矩阵必须可以分解为平移、旋转和缩放
+    let (translation, rotation, scale) = node.transform().decomposed();
+    let local = Transform {
+        translation: translation.into(),
+        rotation: glam::Quat::from_array(rotation),
+        scale: scale.into(),
+    };
+    let mut graph_node = Node::new(node.name().unwrap_or_default(), local);
+    graph_node.mesh = node.mesh().map(|mesh| mesh.index());
+    let id = graph.add(graph_node, parent);
+    for child in node.children() {
+        add_node(child, Some(id), graph, out);
+    }
+    out.push((node, id));
+}
+
+/// 读取三角形列表图元的顶点和索引（u8/u16/u32索引统一转为u32，没有索引时为None）
+///
+/// 各属性的数量与POSITION不同时返回错误。没有法线的图元按glTF规范使用平面法线：
+/// 展开为每个三角形独立的顶点，此时不再有索引。
+fn read_primitive(
+    primitive: &::gltf::Primitive,
+    buffers: &[::gltf::buffer::Data],
+    path: &Path,
+) -> Result<(Vec<TangentVertex>, Option<Vec<u32>>), GltfError> {
+    let unsupported = |feature: String| GltfError::Unsupported {
+        path: path.to_path_buf(),
+        feature,
+    };
+    if primitive.mode() != Mode::Triangles {
+        return Err(unsupported(format!(
+            "primitive mode {:?}",
+            primitive.mode()
+        )));
+    }
+    let sparse = primitive
+        .attributes()
+        .map(|(_, accessor)| accessor)
+        .chain(primitive.indices())
+        .any(|accessor| accessor.sparse().is_some());
+    if sparse {
+        return Err(unsupported("sparse accessors".to_string()));
+    }
+
+    let reader = primitive.reader(|buffer| Some(&buffers[buffer.index()]));
+    let invalid = |message: String| GltfError::Invalid {
+        path: path.to_path_buf(),
+        message,
+    };
+    let positions = reader
+        .read_positions()
+        .ok_or_else(|| invalid("primitive has no POSITION attribute".to_string()))?
+        .collect::<Vec<_>>();
+    let normals = reader.read_normals().map(Iterator::collect::<Vec<_>>);
+    let tangents = reader.read_tangents().map(Iterator::collect::<Vec<_>>);
+    let uvs = reader
+        .read_tex_coords(0)
+        .map(|uvs| uvs.into_f32().collect::<Vec<_>>());
+    // 同一图元的各个属性必须有相同的数量，否则顶点会错位
+    let counts = [
+        ("NORMAL", normals.as_ref().map(Vec::len)),
+        ("TANGENT", tangents.as_ref().map(Vec::len)),
+        ("TEXCOORD_0", uvs.as_ref().map(Vec::len)),
+    ];
+    for (name, count) in counts {
+        match count {
+            Some(count) if count != positions.len() => {
+                return Err(invalid(format!(
+                    "primitive has {count} {name} values for {} positions",
+                    positions.len()
+                )));
+            }
+            _ => {}
+        }
+    }
+    let mut vertices = positions
+        .iter()
+        .enumerate()
+        .map(|(i, &position)| TangentVertex {
+            position,
+            normal: normals.as_ref().map_or([0.0; 3], |normals| normals[i]),
+            tangent: tangents.as_ref().map_or([0.0; 4], |tangents| tangents[i]),
+            uv: uvs.as_ref().map_or([0.0; 2], |uvs| uvs[i]),
+        })
+        .collect::<Vec<_>>();
+    let mut indices = reader
+        .read_indices()
+        .map(|indices| indices.into_u32().collect::<Vec<_>>());
+    let out_of_range = indices
+        .as_ref()
+        .is_some_and(|indices| indices.iter().any(|&i| i as usize >= vertices.len()));
+    if out_of_range {
+        return Err(invalid(format!(
+            "primitive has indices beyond its {} vertices",
+            vertices.len()
+        )));
+    }
+    // glTF规定没有法线的图元使用平面法线
+    if normals.is_none() {
+        vertices = flat_shaded(&vertices, indices.take().as_deref());
+    }
+    // 文件中没有切线但有纹理坐标时在加载后计算切线，文件自带的切线优先
+    if tangents.is_none() && uvs.is_some() {
+        generate_tangents(&mut vertices, indices.as_deref().unwrap_or_default());
+    }
+    Ok((vertices, indices))
+}
+
+/// 展开索引，让每个三角形使用独立的3个顶点，法线为三角形的面法线（退化三角形取+Y）
+///
+/// indices为None时按顶点顺序每3个组成一个三角形，末尾不足一个三角形的顶点被丢弃。
+fn flat_shaded(vertices: &[TangentVertex], indices: Option<&[u32]>) -> Vec<TangentVertex> {
+    let mut flat = match indices {
+        Some(indices) => indices.iter().map(|&i| vertices[i as usize]).collect(),
+        None => vertices.to_vec(),
+    };
+    flat.truncate(flat.len() / 3 * 3);
+    for triangle in flat.chunks_exact_mut(3) {
+        let [a, b, c] = [0, 1, 2].map(|i| glam::Vec3::from(triangle[i].position));
+        let normal = (b - a).cross(c - a).normalize_or(glam::Vec3::Y);
+        for vertex in triangle {
+            vertex.normal = normal.into();
+        }
+    }
+    flat
+}
+
+/// 上传材质用到的贴图，同一贴图以同一格式只上传一次，配置相同的采样器共用一个
+struct TextureLoader<'a> {
+    device: &'a wgpu::Device,
+    queue: &'a wgpu::Queue,
+    path: &'a Path,
+    images: &'a [::gltf::image::Data],
+    cache: HashMap<(usize, wgpu::TextureFormat), Texture>, // (glTF贴图索引, 格式) -> 已上传的贴图
+    samplers: &'a SamplerCache,                            // 共享的采样器缓存
+    mipmaps: MipmapGenerator,                              // 所有贴图共用的mip链生成器
+}
+
+impl TextureLoader<'_> {
+    /// 转换材质并上传它用到的贴图（颜色贴图为sRGB，其他为线性数据）
+    fn material(&mut self, material: &::gltf::Material) -> Result<PbrMaterial, WgpuAppError> {
+        use wgpu::TextureFormat::{Rgba8Unorm, Rgba8UnormSrgb};
+        let pbr = material.pbr_metallic_roughness();
+        let base_color_texture = self.info_texture(pbr.base_color_texture(), Rgba8UnormSrgb)?;
+        let metallic_roughness_texture =
+            self.info_texture(pbr.metallic_roughness_texture(), Rgba8Unorm)?;
+        let emissive_texture = self.info_texture(material.emissive_texture(), Rgba8UnormSrgb)?;
+        let (normal_texture, normal_scale) = match material.normal_texture() {
+            Some(info) => {
+                self.check_tex_coord(info.tex_coord());
+                let texture = self.texture(info.texture(), Rgba8Unorm)?;
+                (Some(texture), info.scale())
+            }
+            None => (None, 1.0),
+        };
+        let (occlusion_texture, occlusion_strength) = match material.occlusion_texture() {
+            Some(info) => {
+                self.check_tex_coord(info.tex_coord());
+                let texture = self.texture(info.texture(), Rgba8Unorm)?;
+                (Some(texture), info.strength())
+            }
+            None => (None, 1.0),
+        };
+        Ok(PbrMaterial {
+            name: material.name().unwrap_or_default().to_string(),
+            base_color_factor: pbr.base_color_factor(),
+            base_color_texture,
+            metallic_factor: pbr.metallic_factor(),
+            roughness_factor: pbr.roughness_factor(),
+            metallic_roughness_texture,
+            normal_texture,
+            normal_scale,
+            occlusion_texture,
+            occlusion_strength,
+            emissive_factor: material.emissive_factor(),
+            emissive_texture,
+        })
+    }
+
+    /// 上传普通贴图引用（基础色、金属度-粗糙度、自发光）指向的贴图，没有引用时返回None
+    fn info_texture(
+        &mut self,
+        info: Option<::gltf::texture::Info>,
+        format: wgpu::TextureFormat,
+    ) -> Result<Option<Texture>, WgpuAppError> {
+        let Some(info) = info else {
+            return Ok(None);
+        };
+        self.check_tex_coord(info.tex_coord());
+        self.texture(info.texture(), format).map(Some)
+    }
+
+    /// 只读取第一套纹理坐标，使用其他坐标的贴图会错位
+    fn check_tex_coord(&self, set: u32) {
+        if set != 0 {
+            warn!(
+                "{}: TEXCOORD_{set} is not supported yet, using TEXCOORD_0",
+                self.path.display()
+            );
+        }
+    }
+
+    /// 上传glTF贴图（图片 + 采样器），图片统一转换为RGBA8
+    fn texture(
+        &mut self,
+        texture: ::gltf::Texture,
+        format: wgpu::TextureFormat,
+    ) -> Result<Texture, WgpuAppError> {
+        let key = (texture.index(), format);
+        if let Some(texture) = self.cache.get(&key) {
+            return Ok(texture.clone());
+        }
+        let sampler = self.samplers.get(&sampler_desc(&texture.sampler()))?;
+        if let Some(uploaded) = self.ktx2_texture(&texture, format)? {
+            let uploaded = uploaded.with_sampler(sampler);
+            self.cache.insert(key, uploaded.clone());
+            return Ok(uploaded);
+        }
+        let image = &self.images[texture.source().index()];
+        let pixels = match image.format {
+            Format::R8G8B8A8 => image.pixels.clone(),
+            Format::R8G8B8 => image
+                .pixels
+                .chunks_exact(3)
+                .flat_map(|rgb| [rgb[0], rgb[1], rgb[2], u8::MAX])
+                .collect(),
+            Format::R8G8 => image
+                .pixels
+                .chunks_exact(2)
+                .flat_map(|rg| [rg[0], rg[1], 0, u8::MAX])
+                .collect(),
+            Format::R8 => image
+                .pixels
+                .iter()
+                .flat_map(|&r| [r, r, r, u8::MAX])
+                .collect(),
+            other => {
+                return Err(GltfError::Unsupported {
+                    path: self.path.to_path_buf(),
+                    feature: format!("{other:?} images"),
+                }
+                .into());
+            }
+        };
+        let label = format!("glTF Texture #{}", texture.index());
+        let image = image::RgbaImage::from_raw(image.width, image.height, pixels)
+            .expect("converted glTF images have 4 bytes per pixel");
+        let uploaded = Texture::from_rgba8(
+            self.device,
+            self.queue,
+            Some(&label),
+            &image,
+            format,
+            sampler,
+            Some(&mut self.mipmaps),
+        )?;
+        self.cache.insert(key, uploaded.clone());
+        Ok(uploaded)
+    }
+
+    /// 图片引用外部文件且同一目录下有同名的`.ktx2`文件时（如`albedo.png`旁的`albedo.ktx2`）改为加载它
+    ///
+    /// KTX2文件自带格式和mip链，格式的sRGB与否应与贴图用途（format）一致，不一致时只给出警告。
+    fn ktx2_texture(
+        &self,
+        texture: &::gltf::Texture,
+        format: wgpu::TextureFormat,
+    ) -> Result<Option<Texture>, WgpuAppError> {
+        let ::gltf::image::Source::Uri { uri, .. } = texture.source().source() else {
+            return Ok(None);
+        };
+        if uri.starts_with("data:") {
+            return Ok(None);
+        }
+        let image_path = self.path.with_file_name(uri);
+        let Some(ktx2_path) = crate::texture::ktx2_sibling(&image_path) else {
+            return Ok(None);
+        };
+        let loaded = Texture::load(self.device, self.queue, self.samplers, &ktx2_path, None)?;
+        if loaded.texture.format().is_srgb() != format.is_srgb() {
+            warn!(
+                "{}: expected {} data for this texture, got {:?}",
+                ktx2_path.display(),
+                if format.is_srgb() { "sRGB" } else { "linear" },
+                loaded.texture.format()
+            );
+        }
+        Ok(Some(loaded))
+    }
+}
+
+/// 转换glTF采样器，贴图总是带有完整的mip链，缩小过滤方式不使用mipmap时只采样第0级
+fn sampler_desc(sampler: &::gltf::texture::Sampler) -> SamplerDesc {
+    use ::gltf::texture::{MagFilter, MinFilter};
+    let address_mode = |mode| match mode {
+        WrappingMode::ClampToEdge => wgpu::AddressMode::ClampToEdge,
+        WrappingMode::MirroredRepeat => wgpu::AddressMode::MirrorRepeat,
+        WrappingMode::Repeat => wgpu::AddressMode::Repeat,
+    };
+    let mag_filter = match sampler.mag_filter() {
+        Some(MagFilter::Nearest) => wgpu::FilterMode::Nearest,
+        Some(MagFilter::Linear) | None => wgpu::FilterMode::Linear,
+    };
+    let min_filter = match sampler.min_filter() {
+        Some(
+            MinFilter::Nearest | MinFilter::NearestMipmapNearest | MinFilter::NearestMipmapLinear,
+        ) => wgpu::FilterMode::Nearest,
+        _ => wgpu::FilterMode::Linear,
+    };
+    let mipmap_filter = match sampler.min_filter() {
+        Some(MinFilter::NearestMipmapNearest | MinFilter::LinearMipmapNearest) => {
+            wgpu::FilterMode::Nearest
+        }
+        _ => wgpu::FilterMode::Linear,
+    };
+    let lod_max_clamp = match sampler.min_filter() {
+        Some(MinFilter::Nearest | MinFilter::Linear) => 0.0,
+        _ => 32.0,
+    };
+    SamplerDesc {
+        address_mode: [
+            address_mode(sampler.wrap_s()),
+            address_mode(sampler.wrap_t()),
+            wgpu::AddressMode::ClampToEdge,
+        ],
+        mag_filter,
+        min_filter,
+        mipmap_filter,
+        lod_max_clamp,
+        ..SamplerDesc::linear_repeat()
+    }
+}
+
+/// 用矩阵变换点（w = 1）
+fn transform_point(m: &Mat4, p: [f32; 3]) -> [f32; 3] {
+    std::array::from_fn(|row| m[0][row] * p[0] + m[1][row] * p[1] + m[2][row] * p[2] + m[3][row])
+}
+
+/// 法线矩阵：左上3x3的逆转置，每列补齐为vec4（与WGSL中mat3x3f的uniform布局一致）
+///
+/// 使用伴随矩阵计算，结果与逆转置只差一个正的倍数，着色器中归一化后即可；
+/// 行列式为负（镜像变换）时取反，保证法线仍朝外。
+pub fn normal_matrix(m: &Mat4) -> [[f32; 4]; 3] {
+    let c = |col: usize, row: usize| m[col][row];
+    // 余子式矩阵的列i = 第(i+1)列 × 第(i+2)列
+    let cross = |a: usize, b: usize| {
+        [
+            c(a, 1) * c(b, 2) - c(a, 2) * c(b, 1),
+            c(a, 2) * c(b, 0) - c(a, 0) * c(b, 2),
+            c(a, 0) * c(b, 1) - c(a, 1) * c(b, 0),
+        ]
+    };
+    let columns = [cross(1, 2), cross(2, 0), cross(0, 1)];
+    let det: f32 = (0..3).map(|row| c(0, row) * columns[0][row]).sum();
+    let sign = if det < 0.0 { -1.0 } else { 1.0 };
+    columns.map(|column| [column[0] * sign, column[1] * sign, column[2] * sign, 0.0])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// 一个三角形：z=0平面上逆时针排列，面法线为+Z
+    const TRIANGLE: [[f32; 3]; 3] = [[0.0, 0.0, 0.0], [1.0, 0.0, 0.0], [0.0, 1.0, 0.0]];
+
+    /// 生成只有一个图元的GLB，normals为None时没有NORMAL属性
+    fn glb(normals: Option<&[[f32; 3]]>) -> Vec<u8> {
+        let mut bin: Vec<u8> = bytemuck::cast_slice(&TRIANGLE).to_vec();
+        let mut views = vec![r#"{"buffer":0,"byteOffset":0,"byteLength":36}"#.to_string()];
+        let mut accessors = vec![
+            r#"{"bufferView":0,"componentType":5126,"count":3,"type":"VEC3","min":[0,0,0],"max":[1,1,0]}"#
+                .to_string(),
+        ];
+        let mut attributes = r#""POSITION":0"#.to_string();
+        if let Some(normals) = normals {
+            views.push(format!(
+                r#"{{"buffer":0,"byteOffset":36,"byteLength":{}}}"#,
+                normals.len() * 12
+            ));
+            accessors.push(format!(
+                r#"{{"bufferView":1,"componentType":5126,"count":{},"type":"VEC3"}}"#,
+                normals.len()
+            ));
+            attributes.push_str(r#","NORMAL":1"#);
+            bin.extend_from_slice(bytemuck::cast_slice(normals));
+        }
+        let mut json = format!(
+            r#"{{"asset":{{"version":"2.0"}},"buffers":[{{"byteLength":{}}}],"bufferViews":[{}],"accessors":[{}],"meshes":[{{"primitives":[{{"attributes":{{{attributes}}}}}]}}]}}"#,
+            bin.len(),
+            views.join(","),
+            accessors.join(","),
+        )
+        .into_bytes();
+        // 块长度需要是4的倍数：JSON用空格补齐，二进制块用0补齐
+        json.resize(json.len().next_multiple_of(4), b' ');
+        bin.resize(bin.len().next_multiple_of(4), 0);
+        let mut glb = Vec::new();
+        glb.extend_from_slice(b"glTF");
+        glb.extend_from_slice(&2u32.to_le_bytes());
+        glb.extend_from_slice(&((12 + 8 + json.len() + 8 + bin.len()) as u32).to_le_bytes());
+        for (kind, chunk) in [(b"JSON", &json), (b"BIN\0", &bin)] {
+            glb.extend_from_slice(&(chunk.len() as u32).to_le_bytes());
+            glb.extend_from_slice(kind);
+            glb.extend_from_slice(chunk);
+        }
+        glb
+    }
+
+    fn read(glb: &[u8]) -> Result<(Vec<TangentVertex>, Option<Vec<u32>>), GltfError> {
+        let (document, buffers, _) = ::gltf::import_slice(glb).unwrap();
+        let primitive = document
+            .meshes()
+            .next()
+            .unwrap()
+            .primitives()
+            .next()
+            .unwrap();
+        read_primitive(&primitive, &buffers, Path::new("test.glb"))
+    }
+
+    #[test]
+    fn file_normals_are_used() {
+        let normals = [[0.0, 1.0, 0.0]; 3];
+        let (vertices, indices) = read(&glb(Some(&normals))).unwrap();
+        assert!(indices.is_none());
+        assert!(vertices.iter().all(|v| v.normal == [0.0, 1.0, 0.0]));
+    }
+
+    #[test]
+    fn missing_normals_are_generated_flat() {
+        let (vertices, indices) = read(&glb(None)).unwrap();
+        assert!(indices.is_none());
+        assert_eq!(vertices.len(), 3);
+        assert!(vertices.iter().all(|v| v.normal == [0.0, 0.0, 1.0]));
+    }
+
+    #[test]
+    fn normal_count_mismatch_is_an_error() {
+        let normals = [[0.0, 0.0, 1.0]; 2];
+        let error = read(&glb(Some(&normals))).unwrap_err();
+        assert!(matches!(error, GltfError::Invalid { .. }), "{error}");
+        assert!(
+            error
+                .to_string()
+                .contains("2 NORMAL values for 3 positions")
+        );
+    }
+
+    #[test]
+    fn flat_shading_expands_shared_vertices() {
+        // 共用一条边的两个三角形：一个在z=0平面（法线+Z），一个在y=0平面（法线+Y）
+        let vertex = |position| TangentVertex {
+            position,
+            normal: [0.0; 3],
+            tangent: [0.0; 4],
+            uv: [0.0; 2],
+        };
+        let vertices = [
+            vertex([0.0, 0.0, 0.0]),
+            vertex([1.0, 0.0, 0.0]),
+            vertex([0.0, 1.0, 0.0]),
+            vertex([0.0, 0.0, 1.0]),
+        ];
+        let flat = flat_shaded(&vertices, Some(&[0, 1, 2, 0, 3, 1]));
+        assert_eq!(flat.len(), 6);
+        assert!(flat[..3].iter().all(|v| v.normal == [0.0, 0.0, 1.0]));
+        assert!(flat[3..].iter().all(|v| v.normal == [0.0, 1.0, 0.0]));
+    }
+}
@@ -0,0 +1,4 @@
+/// glTF 2.0场景（网格、节点变换、基础色和法线贴图）
+pub mod gltf;
+/// Wavefront OBJ模型（以及MTL中的漫反射贴图路径）
+pub mod obj;
@@ -0,0 +1,523 @@
+use crate::{Mesh, MeshVertex, bounds};
+use log::{info, warn};
+use std::{
+    collections::HashMap,
+    path::{Path, PathBuf},
+};
+use thiserror::Error;
+
+/// OBJ文件读取或解析错误
+#[derive(Debug, Error)]
+pub enum ObjError {
+    /// 读取文件失败
+    #[error("Failed to read {}: {source}", path.display())]
+    Io {
+        path: PathBuf,
+        source: std::io::Error,
+    },
+
+    /// 文件格式错误，line从1开始
+    #[error("{}:{line}: {message}", path.display())]
+    Parse {
+        path: PathBuf,
+        line: usize,
+        message: String,
+    },
+
+    /// 文件中没有任何面
+    #[error("{} contains no faces", .0.display())]
+    NoFaces(PathBuf),
+}
+
+/// 从OBJ文件加载的模型：按对象、组或材质拆分的多个网格
+#[derive(Debug, Clone, Default)]
+pub struct ObjModel {
+    pub meshes: Vec<ObjMesh>,        // 网格数据（至少一个）
+    pub materials: Vec<ObjMaterial>, // MTL文件中的材质（找不到MTL文件时为空）
+}
+
+/// OBJ中的一个网格（对象、组或材质变化时开始新的网格）
+#[derive(Debug, Clone, Default)]
+pub struct ObjMesh {
+    pub name: String,              // 对象或组的名称（没有时为空）
+    pub vertices: Vec<MeshVertex>, // 按(位置, 纹理坐标, 法线)去重后的顶点
+    pub indices: Vec<u32>,         // 三角形索引（多边形按扇形三角化）
+    pub material: Option<String>,  // usemtl指定的材质名称
+}
+
+/// MTL中的材质，只读取漫反射贴图
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ObjMaterial {
+    pub name: String,                     // 材质名称
+    pub diffuse_texture: Option<PathBuf>, // 漫反射贴图（map_Kd，相对于MTL文件所在目录解析）
+}
+
+impl ObjModel {
+    /// 网格使用的材质，未指定或MTL中没有该材质时返回None
+    pub fn material(&self, mesh: &ObjMesh) -> Option<&ObjMaterial> {
+        let name = mesh.material.as_deref()?;
+        self.materials.iter().find(|material| material.name == name)
+    }
+
+    /// 所有顶点的包围盒（最小点，最大点）
+    pub fn bounds(&self) -> ([f32; 3], [f32; 3]) {
+        bounds(
+            self.meshes
+                .iter()
+                .flat_map(|mesh| mesh.vertices.iter().map(|vertex| vertex.position)),
+        )
+    }
+
+    /// 上传所有网格
+    pub fn upload(&self, device: &wgpu::Device) -> Vec<Mesh> {
+        self.meshes.iter().map(|mesh| mesh.upload(device)).collect()
+    }
+
+    /// 上传所有网格，并在CPU端保留顶点和索引（见`Mesh::new_with_cpu_data`）
+    pub fn upload_with_cpu_data(&self, device: &wgpu::Device) -> Vec<Mesh> {
+        self.meshes
+            .iter()
+            .map(|mesh| {
+                Mesh::new_with_cpu_data(device, mesh.label(), &mesh.vertices, Some(&mesh.indices))
+            })
+            .collect()
+    }
+}
+
+impl ObjMesh {
+    /// 上传为带索引的网格
+    pub fn upload(&self, device: &wgpu::Device) -> Mesh {
+        Mesh::new(device, self.label(), &self.vertices, Some(&self.indices))
+    }
+
+    /// 网格的调试标签：对象或组的名称，没有名称时为"OBJ"
+    fn label(&self) -> &str {
+        if self.name.is_empty() {
+            "OBJ"
+        } else {
+            &self.name
+        }
+    }
+}
+
+/// 读取并解析OBJ文件，以及它引用的MTL文件（MTL缺失或格式错误时只记录警告）
+pub fn load(path: impl AsRef<Path>) -> Result<ObjModel, ObjError> {
+    let path = path.as_ref();
+    let source = read(path)?;
+    let (meshes, material_libs) = parse_obj(&source, path)?;
+    // 材质库的路径相对于OBJ文件所在目录
+    let dir = path.parent().unwrap_or(Path::new(""));
+    let mut materials = Vec::new();
+    for lib in material_libs {
+        let lib = dir.join(lib);
+        match read(&lib).and_then(|source| parse_mtl(&source, &lib)) {
+            Ok(loaded) => materials.extend(loaded),
+            Err(e) => warn!("Ignoring material library: {e}"),
+        }
+    }
+    let model = ObjModel { meshes, materials };
+    info!(
+        "Loaded {}: {} meshes, {} vertices, {} triangles",
+        path.display(),
+        model.meshes.len(),
+        model
+            .meshes
+            .iter()
+            .map(|mesh| mesh.vertices.len())
+            .sum::<usize>(),
+        model
+            .meshes
+            .iter()
+            .map(|mesh| mesh.indices.len() / 3)
+            .sum::<usize>()
+    );
+    Ok(model)
+}
+
+/// 解析OBJ源码（不读取MTL文件），path只用于错误信息
+pub fn parse(source: &str, path: &Path) -> Result<ObjModel, ObjError> {
+    let (meshes, _) = parse_obj(source, path)?;
+    Ok(ObjModel {
+        meshes,
+        materials: Vec::new(),
+    })
+}
+
+/// 读取文本文件
+fn read(path: &Path) -> Result<String, ObjError> {
+    std::fs::read_to_string(path).map_err(|source| ObjError::Io {
+        path: path.to_path_buf(),
+        source,
+    })
+}
+
+/// 解析OBJ源码，返回网格和mtllib引用的材质库
+fn parse_obj(source: &str, path: &Path) -> Result<(Vec<ObjMesh>, Vec<String>), ObjError> {
+    let mut parser = ObjParser::default();
+    for (index, line) in source.lines().enumerate() {
+        parser.line(line).map_err(|message| ObjError::Parse {
+            path: path.to_path_buf(),
+            line: index + 1,
+            message,
+        })?;
+    }
+    parser.finish_mesh();
+    if parser.meshes.is_empty() {
+        return Err(ObjError::NoFaces(path.to_path_buf()));
+    }
+    Ok((parser.meshes, parser.material_libs))
+}
+
+/// 面的一个顶点引用的(位置, 纹理坐标, 法线)下标（从0开始）
+type VertexKey = (usize, Option<usize>, Option<usize>);
+
+/// 逐行解析OBJ的状态
+#[derive(Default)]
+struct ObjParser {
+    positions: Vec<[f32; 3]>,           // v
+    uvs: Vec<[f32; 2]>,                 // vt（已翻转为左上角原点）
+    normals: Vec<[f32; 3]>,             // vn
+    meshes: Vec<ObjMesh>,               // 已完成的网格
+    material_libs: Vec<String>,         // mtllib引用的文件
+    current: ObjMesh,                   // 正在构建的网格
+    lookup: HashMap<VertexKey, u32>,    // 当前网格中已经生成的顶点
+    missing_normals: Vec<(u32, usize)>, // 没有法线的顶点及其位置下标（网格结束时由面法线计算）
+}
+
+impl ObjParser {
+    /// 解析一行，出错时返回错误描述
+    fn line(&mut self, line: &str) -> Result<(), String> {
+        let line = line.split('#').next().unwrap_or_default();
+        let mut tokens = line.split_whitespace();
+        let Some(keyword) = tokens.next() else {
+            return Ok(());
+        };
+        let rest = line.trim_start()[keyword.len()..].trim();
+        match keyword {
+            "v" => self.positions.push(floats(tokens, 3)?),
+            "vt" => {
+                let [u, v] = floats(tokens, 1)?;
+                self.uvs.push([u, 1.0 - v]); // OBJ的v轴向上
+            }
+            "vn" => {
+                let normal = floats(tokens, 3)?;
+                self.normals
+                    .push(normalize(normal).unwrap_or([0.0, 1.0, 0.0]));
+            }
+            "f" => self.face(tokens)?,
+            "o" | "g" => {
+                self.finish_mesh();
+                self.current.name = rest.to_string();
+            }
+            "usemtl" => {
+                // 同一对象中切换材质也需要单独的网格（绘制时绑定不同的贴图）
+                let name = self.current.name.clone();
+                self.finish_mesh();
+                self.current.name = name;
+                self.current.material = Some(rest.to_string());
+            }
+            "mtllib" => self.material_libs.push(rest.to_string()),
+            // 平滑组、线、点、参数曲面等不影响三角形网格，直接忽略
+            _ => {}
+        }
+        Ok(())
+    }
+
+    /// 解析一个面并按扇形三角化
+    fn face<'a>(&mut self, tokens: impl Iterator<Item = &'a str>) -> Result<(), String> {
+        let corners = tokens
+            .map(|token| self.corner(token))
+            .collect::<Result<Vec<_>, _>>()?;
+        if corners.len() < 3 {
+            return Err(format!(
+                "face has {} vertices, at least 3 are required",
+                corners.len()
+            ));
+        }
+        for i in 1..corners.len() - 1 {
+            self.current
+                .indices
+                .extend([corners[0], corners[i], corners[i + 1]]);
+        }
+        Ok(())
+    }
+
+    /// 解析面的一个顶点（`v`、`v/vt`、`v//vn`或`v/vt/vn`），返回去重后的顶点下标
+    fn corner(&mut self, token: &str) -> Result<u32, String> {
+        let mut parts = token.split('/');
+        let position = resolve(parts.next(), self.positions.len(), "position", token)?
+            .ok_or_else(|| format!("face vertex `{token}` has no position"))?;
+        let uv = resolve(parts.next(), self.uvs.len(), "texture coordinate", token)?;
+        let normal = resolve(parts.next(), self.normals.len(), "normal", token)?;
+        let key = (position, uv, normal);
+        if let Some(&index) = self.lookup.get(&key) {
+            return Ok(index);
+        }
+        let index = self.current.vertices.len() as u32;
+        self.current.vertices.push(MeshVertex {
+            position: self.positions[position],
+            normal: normal.map_or([0.0; 3], |normal| self.normals[normal]),
+            uv: uv.map_or([0.0; 2], |uv| self.uvs[uv]),
+        });
+        if normal.is_none() {
+            self.missing_normals.push((index, position));
+        }
+        self.lookup.insert(key, index);
+        Ok(index)
+    }
+
+    /// 结束当前网格：为缺少法线的顶点计算平滑法线（按面积加权的相邻面法线之和）
+    fn finish_mesh(&mut self) {
+        let mut mesh = std::mem::take(&mut self.current);
+        self.lookup.clear();
+        let missing = std::mem::take(&mut self.missing_normals);
+        if mesh.indices.is_empty() {
+            return;
+        }
+        if !missing.is_empty() {
+            let positions: HashMap<u32, usize> = missing.iter().copied().collect();
+            let mut sums: HashMap<usize, [f32; 3]> = HashMap::new();
+            for triangle in mesh.indices.chunks_exact(3) {
+                let [a, b, c] = [0, 1, 2].map(|i| mesh.vertices[triangle[i] as usize].position);
+                let normal = cross(sub(b, a), sub(c, a)); // 长度为面积的两倍
+                for &vertex in triangle {
+                    if let Some(&position) = positions.get(&vertex) {
+                        let sum = sums.entry(position).or_default();
+                        *sum = std::array::from_fn(|i| sum[i] + normal[i]);
+                    }
+                }
+            }
+            for (vertex, position) in missing {
+                let normal = sums.get(&position).copied().and_then(normalize);
+                mesh.vertices[vertex as usize].normal = normal.unwrap_or([0.0, 1.0, 0.0]);
+            }
+        }
+        self.meshes.push(mesh);
+    }
+}
+
+/// 解析MTL源码，只读取材质名称和漫反射贴图
+fn parse_mtl(source: &str, path: &Path) -> Result<Vec<ObjMaterial>, ObjError> {
+    let dir = path.parent().unwrap_or(Path::new(""));
+    let mut materials: Vec<ObjMaterial> = Vec::new();
+    for (index, line) in source.lines().enumerate() {
+        let line = line.split('#').next().unwrap_or_default().trim();
+        let Some((keyword, rest)) = line.split_once(char::is_whitespace) else {
+            continue;
+        };
+        match keyword {
+            "newmtl" => materials.push(ObjMaterial {
+                name: rest.trim().to_string(),
+                diffuse_texture: None,
+            }),
+            // 贴图路径前可能有`-s 1 1 1`等选项，文件名总是最后一项
+            "map_Kd" => {
+                let (Some(material), Some(file)) =
+                    (materials.last_mut(), rest.split_whitespace().last())
+                else {
+                    return Err(ObjError::Parse {
+                        path: path.to_path_buf(),
+                        line: index + 1,
+                        message: "map_Kd outside of a material".to_string(),
+                    });
+                };
+                material.diffuse_texture = Some(dir.join(file));
+            }
+            _ => {}
+        }
+    }
+    Ok(materials)
+}
+
+/// 解析至少min个、至多N个浮点数（多余的分量如v的w被忽略），不足N个的分量为0
+fn floats<'a, const N: usize>(
+    tokens: impl Iterator<Item = &'a str>,
+    min: usize,
+) -> Result<[f32; N], String> {
+    let mut values = [0.0; N];
+    let mut count = 0;
+    for token in tokens.take(N) {
+        values[count] = token
+            .parse::<f32>()
+            .ok()
+            .filter(|value| value.is_finite())
+            .ok_or_else(|| format!("invalid number `{token}`"))?;
+        count += 1;
+    }
+    if count < min {
+        return Err(format!("expected at least {min} numbers, got {count}"));
+    }
+    Ok(values)
+}
+
+/// 把OBJ的下标（从1开始，负数表示相对于当前末尾）转换为从0开始的下标，空字段返回None
+fn resolve(
+    part: Option<&str>,
+    len: usize,
+    what: &str,
+    token: &str,
+) -> Result<Option<usize>, String> {
+    let Some(part) = part.filter(|part| !part.is_empty()) else {
+        return Ok(None);
+    };
+    let index: i64 = part
+        .parse()
+        .map_err(|_| format!("invalid {what} index in `{token}`"))?;
+    let resolved = match index {
+        1.. => index - 1,
+        ..0 => len as i64 + index,
+        0 => -1,
+    };
+    if (0..len as i64).contains(&resolved) {
+        Ok(Some(resolved as usize))
+    } else {
+        Err(format!(
+            "{what} index {index} in `{token}` is out of range (defined so far: {len})"
+        ))
+    }
+}
+
+fn sub(a: [f32; 3], b: [f32; 3]) -> [f32; 3] {
+    std::array::from_fn(|i| a[i] - b[i])
+}
+
+fn cross(a: [f32; 3], b: [f32; 3]) -> [f32; 3] {
+    [
+        a[1] * b[2] - a[2] * b[1],
+        a[2] * b[0] - a[0] * b[2],
+        a[0] * b[1] - a[1] * b[0],
+    ]
+}
+
+/// 单位化向量，长度为0时返回None
+fn normalize(v: [f32; 3]) -> Option<[f32; 3]> {
+    let length = (v[0] * v[0] + v[1] * v[1] + v[2] * v[2]).sqrt();
+    (length > f32::EPSILON).then(|| v.map(|c| c / length))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// 允许的浮点误差
+    const TOLERANCE: f32 = 1e-5;
+
+    /// z = 0平面上的单位正方形的四个顶点（逆时针，法线朝+Z）
+    const SQUARE: &str = "v 0 0 0\nv 1 0 0\nv 1 1 0\nv 0 1 0\n";
+
+    fn parse_str(source: &str) -> Result<ObjModel, ObjError> {
+        parse(source, Path::new("test.obj"))
+    }
+
+    /// 解析应失败于第line行，且错误信息包含needle
+    fn assert_parse_error(source: &str, line: usize, needle: &str) {
+        match parse_str(source) {
+            Err(ObjError::Parse {
+                line: actual,
+                message,
+                ..
+            }) => {
+                assert_eq!(actual, line, "{message}");
+                assert!(message.contains(needle), "`{message}` lacks `{needle}`");
+            }
+            other => panic!("expected a parse error, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn index_zero_is_rejected() {
+        assert_parse_error(&format!("{SQUARE}f 0 1 2\n"), 5, "index 0");
+    }
+
+    #[test]
+    fn out_of_range_indices_are_rejected() {
+        assert_parse_error(&format!("{SQUARE}f 1 2 5\n"), 5, "index 5");
+        assert_parse_error(&format!("{SQUARE}f -1 -2 -5\n"), 5, "index -5");
+        // 法线和纹理坐标也按各自已定义的数量检查
+        assert_parse_error(
+            &format!("{SQUARE}vn 0 0 1\nf 1//1 2//2 3//1\n"),
+            6,
+            "normal",
+        );
+        assert_parse_error(&format!("{SQUARE}f 1/1 2/1 3/1\n"), 5, "texture coordinate");
+    }
+
+    #[test]
+    fn face_with_two_vertices_is_rejected() {
+        assert_parse_error(&format!("{SQUARE}f 1 2\n"), 5, "face has 2 vertices");
+    }
+
+    #[test]
+    fn non_finite_or_non_numeric_coordinates_are_rejected() {
+        assert_parse_error("v 0 nan 0\n", 1, "invalid number `nan`");
+        assert_parse_error("v 0 0 inf\n", 1, "invalid number `inf`");
+        assert_parse_error("v 0 zero 0\n", 1, "invalid number `zero`");
+        assert_parse_error("v 0 0\n", 1, "expected at least 3 numbers");
+    }
+
+    #[test]
+    fn file_without_faces_is_rejected() {
+        assert!(matches!(parse_str(SQUARE), Err(ObjError::NoFaces(_))));
+        assert!(matches!(parse_str(""), Err(ObjError::NoFaces(_))));
+    }
+
+    #[test]
+    fn polygons_are_fan_triangulated() {
+        let model = parse_str(&format!("{SQUARE}f 1 2 3 4\n")).unwrap();
+        assert_eq!(model.meshes.len(), 1);
+        assert_eq!(model.meshes[0].indices, [0, 1, 2, 0, 2, 3]);
+
+        // 五边形以第一个顶点为中心分成三个三角形
+        let model =
+            parse_str("v 0 0 0\nv 2 0 0\nv 2 1 0\nv 1 2 0\nv 0 1 0\nf 1 2 3 4 5\n").unwrap();
+        assert_eq!(model.meshes[0].indices, [0, 1, 2, 0, 2, 3, 0, 3, 4]);
+    }
+
+    #[test]
+    fn vertices_are_deduplicated_by_position_uv_and_normal() {
+        let source = format!(
+            "{SQUARE}vt 0 0\nvt 1 0\nvn 0 0 1\nvn 0 0 -1\n\
+             f 1/1/1 2/2/1 3/2/1\n\
+             f 1/1/1 3/2/1 4/1/1\n\
+             f 1/2/1 2/2/1 3/2/1\n\
+             f 1/1/2 2/2/1 3/2/1\n"
+        );
+        let mesh = &parse_str(&source).unwrap().meshes[0];
+        // 前两个面共用1/1/1和3/2/1；位置相同但纹理坐标或法线不同的组合各自生成新顶点
+        assert_eq!(mesh.vertices.len(), 6);
+        assert_eq!(mesh.indices, [0, 1, 2, 0, 2, 3, 4, 1, 2, 5, 1, 2]);
+        assert_eq!(mesh.vertices[4].position, mesh.vertices[0].position);
+        assert_eq!(mesh.vertices[4].uv, [1.0, 1.0]);
+        assert_eq!(mesh.vertices[5].normal, [0.0, 0.0, -1.0]);
+        // vt的v轴翻转为左上角原点
+        assert_eq!(mesh.vertices[0].uv, [0.0, 1.0]);
+    }
+
+    #[test]
+    fn missing_normals_are_generated_from_faces() {
+        let mesh = &parse_str(&format!("{SQUARE}f 1 2 3 4\n")).unwrap().meshes[0];
+        for vertex in &mesh.vertices {
+            let [x, y, z] = vertex.normal;
+            assert!(
+                x.abs() < TOLERANCE && y.abs() < TOLERANCE && (z - 1.0).abs() < TOLERANCE,
+                "{:?}",
+                vertex.normal
+            );
+        }
+
+        // 两个面共用的边上的顶点得到按面积加权的平均法线：
+        // 面积1/2、法线+Z的面和面积√2/2、法线(1, 0, 1)/√2的面之和为(1, 0, 2)
+        let source = "v 0 0 0\nv 0 1 0\nv 1 0 0\nv -1 0 1\nf 1 3 2\nf 1 2 4\n";
+        let mesh = &parse_str(source).unwrap().meshes[0];
+        let [x, y, z] = mesh.vertices[0].normal;
+        let scale = 5.0f32.sqrt().recip();
+        assert!(
+            (x - scale).abs() < TOLERANCE
+                && y.abs() < TOLERANCE
+                && (z - 2.0 * scale).abs() < TOLERANCE,
+            "{:?}",
+            mesh.vertices[0].normal
+        );
+        assert!(((x * x + y * y + z * z).sqrt() - 1.0).abs() < TOLERANCE);
+    }
+}
@@ -0,0 +1,473 @@
+use crate::WgpuAppError;
+use std::ops::Deref;
+
+/// 绑定组布局构建器：按调用顺序分配绑定索引（从0开始）
+#[derive(Debug, Clone, Default)]
+pub struct BindGroupLayoutBuilder<'a> {
+    label: Option<&'a str>,
+    entries: Vec<wgpu::BindGroupLayoutEntry>,
+}
+
+impl<'a> BindGroupLayoutBuilder<'a> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 设置调试标签
+    pub fn label(mut self, label: &'a str) -> Self {
+        self.label = Some(label);
+        self
+    }
+
+    /// 添加任意类型的绑定
+    pub fn entry(mut self, visibility: wgpu::ShaderStages, ty: wgpu::BindingType) -> Self {
+        self.entries.push(wgpu::BindGroupLayoutEntry {
+            binding: self.entries.len() as u32,
+            visibility,
+            ty,
+            count: None,
+        });
+        self
+    }
+
+    /// 添加uniform缓冲区绑定
+    pub fn uniform(self, visibility: wgpu::ShaderStages) -> Self {
+        self.entry(
+            visibility,
+            wgpu::BindingType::Buffer {
+                ty: wgpu::BufferBindingType::Uniform,
+                has_dynamic_offset: false,
+                min_binding_size: None,
+            },
+        )
+    }
+
+    /// 添加存储缓冲区绑定
+    pub fn storage(self, visibility: wgpu::ShaderStages, read_only: bool) -> Self {
+        self.entry(
+            visibility,
+            wgpu::BindingType::Buffer {
+                ty: wgpu::BufferBindingType::Storage { read_only },
+                has_dynamic_offset: false,
+                min_binding_size: None,
+            },
+        )
+    }
+
+    /// 添加纹理绑定
+    pub fn texture(
+        self,
+        visibility: wgpu::ShaderStages,
+        sample_type: wgpu::TextureSampleType,
+        view_dimension: wgpu::TextureViewDimension,
+    ) -> Self {
+        self.entry(
+            visibility,
+            wgpu::BindingType::Texture {
+                sample_type,
+                view_dimension,
+                multisampled: false,
+            },
+        )
+    }
+
+    /// 添加存储纹理绑定：着色器中声明为`texture_storage_*<format, access>`，不经过采样器直接读写纹素
+    pub fn storage_texture(
+        self,
+        visibility: wgpu::ShaderStages,
+        format: wgpu::TextureFormat,
+        access: wgpu::StorageTextureAccess,
+        view_dimension: wgpu::TextureViewDimension,
+    ) -> Self {
+        self.entry(
+            visibility,
+            wgpu::BindingType::StorageTexture {
+                access,
+                format,
+                view_dimension,
+            },
+        )
+    }
+
+    /// 添加采样器绑定
+    pub fn sampler(self, visibility: wgpu::ShaderStages, ty: wgpu::SamplerBindingType) -> Self {
+        self.entry(visibility, wgpu::BindingType::Sampler(ty))
+    }
+
+    /// 已添加的布局条目
+    pub fn entries(&self) -> &[wgpu::BindGroupLayoutEntry] {
+        &self.entries
+    }
+
+    /// 创建绑定组布局
+    ///
+    /// # Panics
+    /// 每个着色器阶段使用的uniform/storage缓冲区、纹理或采样器数量超出设备限制时panic。
+    pub fn build(self, device: &wgpu::Device) -> BindingLayout {
+        if let Err(message) = check_stage_limits(self.label, &self.entries, &device.limits()) {
+            panic!("{message}");
+        }
+        self.create(device)
+    }
+
+    /// 创建绑定组布局，绑定数量超出设备限制时返回`WgpuAppError::UnsupportedLimits`而不是panic
+    ///
+    /// 用于依赖可选能力的场景（例如WebGL2上顶点着色器不能使用存储缓冲区），在创建管线前给出明确的错误。
+    pub fn try_build(self, device: &wgpu::Device) -> Result<BindingLayout, WgpuAppError> {
+        check_stage_limits(self.label, &self.entries, &device.limits())
+            .map_err(WgpuAppError::UnsupportedLimits)?;
+        Ok(self.create(device))
+    }
+
+    /// 创建绑定组布局（限制已检查）
+    fn create(self, device: &wgpu::Device) -> BindingLayout {
+        let layout = device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+            label: self.label,
+            entries: &self.entries,
+        });
+        BindingLayout {
+            layout,
+            entries: self.entries,
+        }
+    }
+}
+
+/// 绑定组布局及其条目，条目用于创建绑定组时校验资源数量与类型
+#[derive(Debug)]
+pub struct BindingLayout {
+    pub layout: wgpu::BindGroupLayout,
+    pub entries: Vec<wgpu::BindGroupLayoutEntry>,
+}
+
+impl Deref for BindingLayout {
+    type Target = wgpu::BindGroupLayout;
+
+    fn deref(&self) -> &Self::Target {
+        &self.layout
+    }
+}
+
+/// 绑定组构建器：资源需按布局中条目的顺序添加
+pub struct BindGroupBuilder<'a> {
+    layout: &'a BindingLayout,
+    label: Option<&'a str>,
+    resources: Vec<wgpu::BindingResource<'a>>,
+}
+
+impl<'a> BindGroupBuilder<'a> {
+    pub fn new(layout: &'a BindingLayout) -> Self {
+        Self {
+            layout,
+            label: None,
+            resources: Vec::with_capacity(layout.entries.len()),
+        }
+    }
+
+    /// 设置调试标签
+    pub fn label(mut self, label: &'a str) -> Self {
+        self.label = Some(label);
+        self
+    }
+
+    /// 添加任意绑定资源
+    pub fn resource(mut self, resource: wgpu::BindingResource<'a>) -> Self {
+        self.resources.push(resource);
+        self
+    }
+
+    /// 添加整个缓冲区（对应uniform或storage条目）
+    pub fn buffer(self, buffer: &'a wgpu::Buffer) -> Self {
+        self.resource(buffer.as_entire_binding())
+    }
+
+    /// 添加纹理视图
+    pub fn texture(self, view: &'a wgpu::TextureView) -> Self {
+        self.resource(wgpu::BindingResource::TextureView(view))
+    }
+
+    /// 添加采样器
+    pub fn sampler(self, sampler: &'a wgpu::Sampler) -> Self {
+        self.resource(wgpu::BindingResource::Sampler(sampler))
+    }
+
+    /// 创建绑定组
+    ///
+    /// # Panics
+    /// 资源数量与布局条目数量不一致，或资源类型与条目类型不匹配时panic。
+    pub fn build(self, device: &wgpu::Device) -> wgpu::BindGroup {
+        assert_eq!(
+            self.resources.len(),
+            self.layout.entries.len(),
+            "bind group {:?} has {} resources but its layout has {} entries",
+            self.label,
+            self.resources.len(),
+            self.layout.entries.len()
+        );
+        let entries = self
+            .layout
+            .entries
+            .iter()
+            .zip(self.resources)
+            .map(|(entry, resource)| {
+                assert!(
+                    resource_matches(&entry.ty, &resource),
+                    "binding {} of {:?} expects {:?}",
+                    entry.binding,
+                    self.label,
+                    entry.ty
+                );
+                wgpu::BindGroupEntry {
+                    binding: entry.binding,
+                    resource,
+                }
+            })
+            .collect::<Vec<_>>();
+        device.create_bind_group(&wgpu::BindGroupDescriptor {
+            label: self.label,
+            layout: &self.layout.layout,
+            entries: &entries,
+        })
+    }
+}
+
+/// 检查绑定资源的种类是否与布局条目一致
+fn resource_matches(ty: &wgpu::BindingType, resource: &wgpu::BindingResource) -> bool {
+    use wgpu::{BindingResource as R, BindingType as T};
+    matches!(
+        (ty, resource),
+        (T::Buffer { .. }, R::Buffer(_) | R::BufferArray(_))
+            | (T::Sampler(_), R::Sampler(_) | R::SamplerArray(_))
+            | (
+                T::Texture { .. } | T::StorageTexture { .. },
+                R::TextureView(_) | R::TextureViewArray(_)
+            )
+            | (T::AccelerationStructure, R::AccelerationStructure(_))
+    )
+}
+
+/// 检查每个着色器阶段的各类绑定数量是否超出设备限制，给出比后端验证更清楚的错误信息
+fn check_stage_limits(
+    label: Option<&str>,
+    entries: &[wgpu::BindGroupLayoutEntry],
+    limits: &wgpu::Limits,
+) -> Result<(), String> {
+    use wgpu::{BindingType as T, BufferBindingType as B};
+    for stage in [
+        wgpu::ShaderStages::VERTEX,
+        wgpu::ShaderStages::FRAGMENT,
+        wgpu::ShaderStages::COMPUTE,
+    ] {
+        let count = |f: fn(&wgpu::BindingType) -> bool| {
+            entries
+                .iter()
+                .filter(|entry| entry.visibility.contains(stage) && f(&entry.ty))
+                .count() as u32
+        };
+        let checks = [
+            (
+                "uniform buffers",
+                count(|ty| matches!(ty, T::Buffer { ty: B::Uniform, .. })),
+                limits.max_uniform_buffers_per_shader_stage,
+            ),
+            (
+                "storage buffers",
+                count(|ty| {
+                    matches!(
+                        ty,
+                        T::Buffer {
+                            ty: B::Storage { .. },
+                            ..
+                        }
+                    )
+                }),
+                limits.max_storage_buffers_per_shader_stage,
+            ),
+            (
+                "sampled textures",
+                count(|ty| matches!(ty, T::Texture { .. })),
+                limits.max_sampled_textures_per_shader_stage,
+            ),
+            (
+                "samplers",
+                count(|ty| matches!(ty, T::Sampler(_))),
+                limits.max_samplers_per_shader_stage,
+            ),
+            (
+                "storage textures",
+                count(|ty| matches!(ty, T::StorageTexture { .. })),
+                limits.max_storage_textures_per_shader_stage,
+            ),
+        ];
+        for (kind, used, max) in checks {
+            if used > max {
+                return Err(format!(
+                    "bind group layout {label:?} uses {used} {kind} in {stage:?}, \
+                     but the device allows at most {max}"
+                ));
+            }
+        }
+    }
+    Ok(())
+}
+
+/// 创建管线布局，绑定组布局的顺序即着色器中的组索引
+///
+/// # Panics
+/// 绑定组数量超出设备的`max_bind_groups`限制时panic。
+pub fn pipeline_layout(
+    device: &wgpu::Device,
+    label: Option<&str>,
+    bind_group_layouts: &[&wgpu::BindGroupLayout],
+) -> wgpu::PipelineLayout {
+    pipeline_layout_with_push_constants(device, label, bind_group_layouts, &[])
+}
+
+/// 创建带推送常量范围的管线布局（通常通过`PushConstants::pipeline_layout`使用）
+///
+/// # Panics
+/// 绑定组数量超出`max_bind_groups`，或使用了推送常量但设备没有启用PUSH_CONSTANTS、
+/// 范围超出`max_push_constant_size`时panic。
+pub fn pipeline_layout_with_push_constants(
+    device: &wgpu::Device,
+    label: Option<&str>,
+    bind_group_layouts: &[&wgpu::BindGroupLayout],
+    push_constant_ranges: &[wgpu::PushConstantRange],
+) -> wgpu::PipelineLayout {
+    let limits = device.limits();
+    let max_bind_groups = limits.max_bind_groups;
+    assert!(
+        bind_group_layouts.len() as u32 <= max_bind_groups,
+        "pipeline layout {label:?} uses {} bind groups, but the device allows at most {max_bind_groups}",
+        bind_group_layouts.len()
+    );
+    if !push_constant_ranges.is_empty() {
+        assert!(
+            device.features().contains(wgpu::Features::PUSH_CONSTANTS),
+            "pipeline layout {label:?} uses push constants, but PUSH_CONSTANTS is not enabled"
+        );
+        let max_size = limits.max_push_constant_size;
+        for range in push_constant_ranges {
+            assert!(
+                range.range.end <= max_size,
+                "pipeline layout {label:?} uses push constants up to byte {}, \
+                 but the device allows at most {max_size}",
+                range.range.end
+            );
+        }
+    }
+    device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+        label,
+        bind_group_layouts,
+        push_constant_ranges,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use wgpu::ShaderStages as S;
+
+    #[test]
+    fn entries_are_numbered_in_call_order_with_their_visibility() {
+        let builder = BindGroupLayoutBuilder::new()
+            .uniform(S::VERTEX)
+            .texture(
+                S::FRAGMENT,
+                wgpu::TextureSampleType::Float { filterable: true },
+                wgpu::TextureViewDimension::D2,
+            )
+            .sampler(S::FRAGMENT, wgpu::SamplerBindingType::Filtering)
+            .storage(S::VERTEX_FRAGMENT | S::COMPUTE, true);
+        let entries = builder.entries();
+        assert_eq!(
+            entries.iter().map(|e| e.binding).collect::<Vec<_>>(),
+            [0, 1, 2, 3]
+        );
+        assert_eq!(
+            entries.iter().map(|e| e.visibility).collect::<Vec<_>>(),
+            [
+                S::VERTEX,
+                S::FRAGMENT,
+                S::FRAGMENT,
+                S::VERTEX_FRAGMENT | S::COMPUTE
+            ]
+        );
+        assert!(entries.iter().all(|e| e.count.is_none()));
+    }
+
+    #[test]
+    fn entries_have_the_requested_binding_types() {
+        let builder = BindGroupLayoutBuilder::new()
+            .uniform(S::VERTEX)
+            .storage(S::COMPUTE, false)
+            .texture(
+                S::FRAGMENT,
+                wgpu::TextureSampleType::Depth,
+                wgpu::TextureViewDimension::Cube,
+            )
+            .sampler(S::FRAGMENT, wgpu::SamplerBindingType::Comparison)
+            .storage_texture(
+                S::COMPUTE,
+                wgpu::TextureFormat::Rgba8Unorm,
+                wgpu::StorageTextureAccess::WriteOnly,
+                wgpu::TextureViewDimension::D3,
+            );
+        let types = builder.entries().iter().map(|e| e.ty).collect::<Vec<_>>();
+        assert_eq!(
+            types,
+            [
+                wgpu::BindingType::Buffer {
+                    ty: wgpu::BufferBindingType::Uniform,
+                    has_dynamic_offset: false,
+                    min_binding_size: None,
+                },
+                wgpu::BindingType::Buffer {
+                    ty: wgpu::BufferBindingType::Storage { read_only: false },
+                    has_dynamic_offset: false,
+                    min_binding_size: None,
+                },
+                wgpu::BindingType::Texture {
+                    sample_type: wgpu::TextureSampleType::Depth,
+                    view_dimension: wgpu::TextureViewDimension::Cube,
+                    multisampled: false,
+                },
+                wgpu::BindingType::Sampler(wgpu::SamplerBindingType::Comparison),
+                wgpu::BindingType::StorageTexture {
+                    access: wgpu::StorageTextureAccess::WriteOnly,
+                    format: wgpu::TextureFormat::Rgba8Unorm,
+                    view_dimension: wgpu::TextureViewDimension::D3,
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn stage_limits_are_counted_per_stage() {
+        let limits = wgpu::Limits {
+            max_storage_buffers_per_shader_stage: 1,
+            ..wgpu::Limits::downlevel_defaults()
+        };
+        // 两个存储缓冲区分别只在不同阶段可见，每个阶段都只用了一个
+        let split = BindGroupLayoutBuilder::new()
+            .storage(S::VERTEX, true)
+            .storage(S::FRAGMENT, true);
+        assert!(check_stage_limits(None, split.entries(), &limits).is_ok());
+
+        let shared = BindGroupLayoutBuilder::new()
+            .storage(S::VERTEX, true)
+            .storage(S::VERTEX_FRAGMENT, true);
+        let message = check_stage_limits(Some("Shared"), shared.entries(), &limits).unwrap_err();
+        assert!(
+            message.contains("2 storage buffers in ShaderStages(VERTEX)"),
+            "{message}"
+        );
+    }
+
+    #[test]
+    fn webgl2_has_no_vertex_storage_buffers() {
+        let builder = BindGroupLayoutBuilder::new().storage(S::VERTEX, true);
+        let limits = wgpu::Limits::downlevel_webgl2_defaults();
+        assert!(check_stage_limits(None, builder.entries(), &limits).is_err());
+        assert!(check_stage_limits(None, builder.entries(), &wgpu::Limits::default()).is_ok());
+    }
+}
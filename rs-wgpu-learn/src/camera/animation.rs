@@ -0,0 +1,64 @@
+use super::Camera;
+use std::time::Duration;
+
+/// 相机过渡动画：在给定时长内把相机从起始姿态插值到目标姿态（见`Camera::lerp`）
+///
+/// `start`之后每帧调用`update`写入相机，过渡完成时返回true并停止；`cancel`立即停止，相机保持在当前位置。
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct CameraAnimator {
+    transition: Option<Transition>, // 进行中的过渡
+}
+
+/// 一次过渡的起止姿态和进度
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct Transition {
+    from: Camera,       // 起始姿态
+    to: Camera,         // 目标姿态
+    duration: Duration, // 总时长
+    elapsed: Duration,  // 已经过的时间
+}
+
+impl CameraAnimator {
+    /// 开始从from到to、时长为duration的过渡，替换进行中的过渡
+    pub fn start(&mut self, from: &Camera, to: &Camera, duration: Duration) {
+        self.transition = Some(Transition {
+            from: *from,
+            to: *to,
+            duration,
+            elapsed: Duration::ZERO,
+        });
+    }
+
+    /// 是否有进行中的过渡
+    pub fn is_active(&self) -> bool {
+        self.transition.is_some()
+    }
+
+    /// 进行中的过渡的目标姿态
+    pub fn target(&self) -> Option<&Camera> {
+        self.transition.as_ref().map(|transition| &transition.to)
+    }
+
+    /// 停止进行中的过渡，相机停在当前位置
+    pub fn cancel(&mut self) {
+        self.transition = None;
+    }
+
+    /// 推进dt并把插值后的姿态写入相机，返回过渡是否在本次更新中完成（完成时相机正好是目标姿态）
+    ///
+    /// 没有进行中的过渡时不修改相机并返回false。
+    pub fn update(&mut self, dt: Duration, camera: &mut Camera) -> bool {
+        let Some(transition) = &mut self.transition else {
+            return false;
+        };
+        transition.elapsed += dt;
+        if transition.elapsed >= transition.duration {
+            *camera = transition.to;
+            self.transition = None;
+            return true;
+        }
+        let t = transition.elapsed.as_secs_f32() / transition.duration.as_secs_f32();
+        *camera = transition.from.lerp(&transition.to, t);
+        false
+    }
+}
@@ -0,0 +1,102 @@
+use super::Camera;
+use log::{info, warn};
+use std::{
+    collections::BTreeMap,
+    path::{Path, PathBuf},
+};
+
+/// 可保存的相机姿态数（对应数字键1～9）
+pub const BOOKMARK_SLOTS: usize = 9;
+
+/// 文件内容：场景名 → 槽位号（从1开始，与数字键一致） → 相机
+type PoseFile = BTreeMap<String, BTreeMap<usize, Camera>>;
+
+/// 保存的相机姿态，按场景名存放在`CAMERA_POSES_FILE`中，所有场景共用一个文件
+///
+/// 用`load`创建时每次`set`都会写回文件；`new`创建的只保存在内存中（wasm和Android上`load`也是如此）。
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct CameraBookmarks {
+    file: Option<(PathBuf, String)>, // 文件路径和场景名，None时不写入文件
+    poses: [Option<Camera>; BOOKMARK_SLOTS], // 各槽位保存的姿态
+}
+
+impl CameraBookmarks {
+    /// 只保存在内存中的空书签
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 从工作目录下的`CAMERA_POSES_FILE`加载scene的姿态
+    pub fn load(scene: &str) -> Self {
+        #[cfg(any(target_arch = "wasm32", target_os = "android"))]
+        {
+            let _ = scene;
+            Self::new()
+        }
+        #[cfg(not(any(target_arch = "wasm32", target_os = "android")))]
+        Self::load_at(crate::config::CAMERA_POSES_FILE, scene)
+    }
+
+    /// 从指定路径加载scene的姿态，文件不存在时为空，格式错误时记录警告后为空（之后保存时不覆盖该文件）
+    pub fn load_at(path: impl AsRef<Path>, scene: &str) -> Self {
+        let path = path.as_ref();
+        let mut bookmarks = Self::new();
+        match read(path) {
+            Ok(mut file) => {
+                for (slot, camera) in file.remove(scene).unwrap_or_default() {
+                    match bookmarks.poses.get_mut(slot.wrapping_sub(1)) {
+                        Some(pose) => *pose = Some(camera),
+                        None => warn!("Ignoring camera pose {slot} in {}", path.display()),
+                    }
+                }
+                bookmarks.file = Some((path.to_path_buf(), scene.to_string()));
+            }
+            Err(e) => warn!("Failed to load camera poses from {}: {e}", path.display()),
+        }
+        bookmarks
+    }
+
+    /// 槽位（从0开始）保存的姿态
+    pub fn get(&self, slot: usize) -> Option<&Camera> {
+        self.poses.get(slot)?.as_ref()
+    }
+
+    /// 把相机保存到槽位（从0开始，超出范围时忽略），从文件加载的书签随即写回文件
+    pub fn set(&mut self, slot: usize, camera: &Camera) {
+        let Some(pose) = self.poses.get_mut(slot) else {
+            return;
+        };
+        *pose = Some(*camera);
+        if let Some((path, scene)) = &self.file {
+            self.save(path, scene);
+        }
+    }
+
+    /// 重新读取文件并替换其中本场景的姿态后写回，保留其他场景的姿态
+    fn save(&self, path: &Path, scene: &str) {
+        let result = read(path).and_then(|mut file| {
+            let poses = self
+                .poses
+                .iter()
+                .enumerate()
+                .filter_map(|(slot, pose)| Some((slot + 1, (*pose)?)))
+                .collect();
+            file.insert(scene.to_string(), poses);
+            let source = serde_json::to_string_pretty(&file).map_err(|e| e.to_string())?;
+            std::fs::write(path, source).map_err(|e| e.to_string())
+        });
+        match result {
+            Ok(()) => info!("Wrote camera poses to {}", path.display()),
+            Err(e) => warn!("Failed to write camera poses to {}: {e}", path.display()),
+        }
+    }
+}
+
+/// 读取姿态文件，文件不存在时为空
+fn read(path: &Path) -> Result<PoseFile, String> {
+    match std::fs::read_to_string(path) {
+        Ok(source) => serde_json::from_str(&source).map_err(|e| e.to_string()),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(PoseFile::new()),
+        Err(e) => Err(e.to_string()),
+    }
+}
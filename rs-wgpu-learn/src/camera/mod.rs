@@ -0,0 +1,831 @@
+mod animation;
+mod bookmarks;
+
+use crate::binding::BindingLayout;
+use crate::input::InputState;
+use crate::math::{Ray, smoothstep};
+use crate::{DEFAULT_FRAMES_IN_FLIGHT, FrameUniforms, RenderContext};
+pub use animation::CameraAnimator;
+pub use bookmarks::{BOOKMARK_SLOTS, CameraBookmarks};
+use glam::{Mat3, Mat4, Quat, Vec2, Vec3};
+use log::info;
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+use winit::{event::MouseButton, keyboard::KeyCode};
+
+/// 与着色器中CameraUniform对应的数据
+///
+/// ```text
+/// struct CameraUniform {
+///     view_proj: mat4x4f,
+/// }
+/// @group(0) @binding(0) var<uniform> camera: CameraUniform;
+/// ```
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq, bytemuck::Pod, bytemuck::Zeroable)]
+pub struct CameraUniform {
+    pub view_proj: [[f32; 4]; 4], // 世界空间到裁剪空间的变换（按列存储）
+}
+
+/// 投影方式，近、远裁剪面都是到相机的距离
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Projection {
+    /// 透视投影：fovy为纵向视野（弧度），znear必须大于0
+    Perspective { fovy: f32, znear: f32, zfar: f32 },
+    /// 正交投影：height为视野的高度（世界单位），宽度为height × 宽高比
+    Orthographic { height: f32, znear: f32, zfar: f32 },
+}
+
+impl Default for Projection {
+    /// 45度视野的透视投影
+    fn default() -> Self {
+        Self::Perspective {
+            fovy: std::f32::consts::FRAC_PI_4,
+            znear: 0.1,
+            zfar: 100.0,
+        }
+    }
+}
+
+impl Projection {
+    /// 投影矩阵：观察空间到裁剪空间，aspect为表面宽高比
+    ///
+    /// glam的`perspective_rh`和`orthographic_rh`把近、远裁剪面映射到深度0和1，
+    /// 与wgpu（以及D3D、Metal、Vulkan）的裁剪空间一致，不需要额外的修正矩阵；
+    /// OpenGL约定的-1..1对应`*_gl`版本，在wgpu中使用会丢掉一半的深度精度，并把近裁剪面附近的物体裁掉。
+    pub fn matrix(&self, aspect: f32) -> Mat4 {
+        let aspect = aspect.max(f32::EPSILON);
+        match *self {
+            Self::Perspective { fovy, znear, zfar } => {
+                Mat4::perspective_rh(fovy, aspect, znear, zfar)
+            }
+            Self::Orthographic {
+                height,
+                znear,
+                zfar,
+            } => {
+                let (half_width, half_height) = (height * aspect / 2.0, height / 2.0);
+                Mat4::orthographic_rh(
+                    -half_width,
+                    half_width,
+                    -half_height,
+                    half_height,
+                    znear,
+                    zfar,
+                )
+            }
+        }
+    }
+
+    /// 切换到另一种投影，保持距相机distance处的平面上看到的范围不变（切换时画面不会突然变大或变小）
+    ///
+    /// 透视投影在该距离处的视野高度为2 × distance × tan(fovy / 2)，正交投影反过来由高度求出视野。
+    pub fn toggled(&self, distance: f32) -> Self {
+        let distance = distance.max(f32::EPSILON);
+        match *self {
+            Self::Perspective { fovy, znear, zfar } => Self::Orthographic {
+                height: 2.0 * distance * (fovy / 2.0).tan(),
+                znear,
+                zfar,
+            },
+            Self::Orthographic {
+                height,
+                znear,
+                zfar,
+            } => Self::Perspective {
+                fovy: 2.0 * (height / 2.0 / distance).atan(),
+                znear,
+                zfar,
+            },
+        }
+    }
+
+    /// 插值：同一种投影时各参数线性插值，不同投影时t过半后切换到end
+    pub fn lerp(&self, end: &Self, t: f32) -> Self {
+        let mix = |a: f32, b: f32| a + (b - a) * t;
+        match (*self, *end) {
+            (
+                Self::Perspective { fovy, znear, zfar },
+                Self::Perspective {
+                    fovy: end_fovy,
+                    znear: end_znear,
+                    zfar: end_zfar,
+                },
+            ) => Self::Perspective {
+                fovy: mix(fovy, end_fovy),
+                znear: mix(znear, end_znear),
+                zfar: mix(zfar, end_zfar),
+            },
+            (
+                Self::Orthographic {
+                    height,
+                    znear,
+                    zfar,
+                },
+                Self::Orthographic {
+                    height: end_height,
+                    znear: end_znear,
+                    zfar: end_zfar,
+                },
+            ) => Self::Orthographic {
+                height: mix(height, end_height),
+                znear: mix(znear, end_znear),
+                zfar: mix(zfar, end_zfar),
+            },
+            _ if t < 0.5 => *self,
+            _ => *end,
+        }
+    }
+
+    /// 名称（用于日志）
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Perspective { .. } => "perspective",
+            Self::Orthographic { .. } => "orthographic",
+        }
+    }
+}
+
+/// 相机：位置、观察点和上方向决定视图矩阵，projection决定投影矩阵
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct Camera {
+    pub eye: Vec3,              // 相机位置（世界空间）
+    pub target: Vec3,           // 观察点
+    pub up: Vec3,               // 上方向（不需要与视线垂直）
+    pub projection: Projection, // 透视或正交投影
+}
+
+impl Default for Camera {
+    /// 位于+Z方向、看向原点的相机，45度视野的透视投影
+    fn default() -> Self {
+        Self {
+            eye: Vec3::new(0.0, 0.0, 3.0),
+            target: Vec3::ZERO,
+            up: Vec3::Y,
+            projection: Projection::default(),
+        }
+    }
+}
+
+impl Camera {
+    /// 视图矩阵：世界空间到观察空间（右手坐标系，相机看向-Z）
+    pub fn view(&self) -> Mat4 {
+        Mat4::look_at_rh(self.eye, self.target, self.up)
+    }
+
+    /// 投影矩阵：观察空间到裁剪空间（见`Projection::matrix`）
+    pub fn projection(&self, aspect: f32) -> Mat4 {
+        self.projection.matrix(aspect)
+    }
+
+    /// 视图投影矩阵，aspect为表面宽高比（见`aspect_ratio`）
+    pub fn view_proj(&self, aspect: f32) -> Mat4 {
+        self.projection(aspect) * self.view()
+    }
+
+    /// 在透视和正交投影之间切换，保持观察点所在平面上的画面范围不变
+    pub fn toggle_projection(&mut self) {
+        self.projection = self.projection.toggled(self.eye.distance(self.target));
+    }
+
+    /// 相机的朝向：把-Z（视线）、+Y（上方向）旋转到世界空间的四元数，由look-at的基向量求出
+    ///
+    /// 上方向与视线平行时任取一个与视线垂直的方向作为右方向。
+    pub fn orientation(&self) -> Quat {
+        let forward = (self.target - self.eye).normalize_or(Vec3::NEG_Z);
+        let right = forward
+            .cross(self.up)
+            .try_normalize()
+            .unwrap_or_else(|| forward.any_orthonormal_vector());
+        let up = right.cross(forward);
+        Quat::from_mat3(&Mat3::from_cols(right, up, -forward)).normalize()
+    }
+
+    /// 插值：t经过smoothstep缓动后，位置线性插值，朝向球面线性插值，观察点距离和投影参数线性插值
+    ///
+    /// t为0时为self，为1时为end。观察点沿插值后的视线方向放在插值后的距离处，上方向取插值朝向的+Y，
+    /// 所以过渡中视线不会像分别插值位置和观察点那样先偏向一侧再转回来。
+    pub fn lerp(&self, end: &Self, t: f32) -> Self {
+        let t = smoothstep(t);
+        let rotation = self.orientation().slerp(end.orientation(), t);
+        let eye = self.eye.lerp(end.eye, t);
+        let (distance, end_distance) =
+            (self.eye.distance(self.target), end.eye.distance(end.target));
+        let distance = distance + (end_distance - distance) * t;
+        Self {
+            eye,
+            target: eye + rotation * Vec3::NEG_Z * distance,
+            up: rotation * Vec3::Y,
+            projection: self.projection.lerp(&end.projection, t),
+        }
+    }
+
+    /// 上传到uniform缓冲区的数据
+    pub fn uniform(&self, aspect: f32) -> CameraUniform {
+        CameraUniform {
+            view_proj: self.view_proj(aspect).to_cols_array_2d(),
+        }
+    }
+}
+
+/// 从光标位置发出的射线：cursor为表面上的物理像素坐标（左上角为原点，y向下），
+/// surface_size为表面尺寸，inv_view_proj为视图投影矩阵的逆
+///
+/// 像素坐标换算成归一化设备坐标时翻转y（设备坐标y向上），再把近平面（深度0，wgpu的深度范围为0..1）
+/// 和远平面（深度1）上的对应点变换回世界空间，射线从近平面上的点指向远平面上的点（见`Ray::from_ndc`）。
+pub fn screen_to_ray(cursor: Vec2, surface_size: (u32, u32), inv_view_proj: Mat4) -> Ray {
+    let size = Vec2::new(surface_size.0.max(1) as f32, surface_size.1.max(1) as f32);
+    let ndc = Vec2::new(cursor.x / size.x * 2.0 - 1.0, 1.0 - cursor.y / size.y * 2.0);
+    Ray::from_ndc(ndc, inv_view_proj)
+}
+
+/// 表面宽高比，尺寸为0（如最小化时）时按1处理
+pub fn aspect_ratio(config: &wgpu::SurfaceConfiguration) -> f32 {
+    config.width.max(1) as f32 / config.height.max(1) as f32
+}
+
+/// 相机的uniform缓冲区和绑定组，约定放在第0组
+///
+/// 在创建管线布局时把`layout`放在第一个，`update`记下视图投影矩阵，绘制时用`bind`把它写入本帧的槽位并绑定。
+/// 数据按帧轮换存放（见`FrameUniforms`），绑定组带动态偏移。
+pub struct CameraBuffer {
+    uniforms: FrameUniforms<CameraUniform>,
+    uniform: CameraUniform, // 最近一次update计算的数据
+}
+
+impl CameraBuffer {
+    /// 创建`DEFAULT_FRAMES_IN_FLIGHT`个槽位、只在顶点着色器中可见的相机uniform
+    pub fn new(device: &wgpu::Device) -> Self {
+        Self::with_frames_in_flight(device, DEFAULT_FRAMES_IN_FLIGHT)
+    }
+
+    /// 创建frames_in_flight个槽位的相机uniform，绑定组布局与槽位数无关
+    pub fn with_frames_in_flight(device: &wgpu::Device, frames_in_flight: u32) -> Self {
+        Self {
+            uniforms: FrameUniforms::with_frames_in_flight(
+                device,
+                "Camera",
+                wgpu::ShaderStages::VERTEX,
+                frames_in_flight,
+            ),
+            uniform: CameraUniform {
+                view_proj: Mat4::IDENTITY.to_cols_array_2d(),
+            },
+        }
+    }
+
+    /// 第0组的绑定组布局，用于创建管线布局
+    pub fn layout(&self) -> &BindingLayout {
+        self.uniforms.layout()
+    }
+
+    /// 槽位数
+    pub fn frames_in_flight(&self) -> u32 {
+        self.uniforms.frames_in_flight()
+    }
+
+    /// 记下相机的视图投影矩阵，每帧（或相机、宽高比变化时）调用
+    pub fn update(&mut self, camera: &Camera, aspect: f32) {
+        self.uniform = camera.uniform(aspect);
+    }
+
+    /// 把最近一次`update`的数据写入本帧的槽位，并绑定到第index组
+    pub fn bind(&self, ctx: &RenderContext, pass: &mut wgpu::RenderPass, index: u32) {
+        let offset = self
+            .uniforms
+            .write(ctx.queue, ctx.frame_index, &self.uniform);
+        pass.set_bind_group(index, self.uniforms.bind_group(), &[offset]);
+    }
+}
+
+/// 轨道相机控制器：相机位于以target为球心、distance为半径的球面上，始终看向target
+///
+/// - 左键拖动：旋转（yaw绕世界Y轴，pitch为仰角，限制在±90度以内避免越过头顶时画面翻转）
+/// - 滚轮：按指数缩放距离（每行乘以`zoom_speed`），限制在`min_distance..=max_distance`
+/// - 中键拖动或按住Shift左键拖动：在相机的右、上方向构成的平面内平移target
+///
+/// 旋转和平移量都按表面高度归一化，在不同分辨率下拖过同样比例的窗口得到同样的效果。
+/// 本帧有事件被`Scene::input`消费（见`InputState::consumed`）时忽略鼠标输入。
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct OrbitController {
+    pub target: Vec3,      // 观察点（轨道中心）
+    pub distance: f32,     // 相机到观察点的距离
+    pub yaw: f32,          // 绕Y轴的角度（弧度），0时相机在观察点的+Z方向
+    pub pitch: f32,        // 仰角（弧度），正值时从上方俯视
+    pub min_distance: f32, // 最小距离
+    pub max_distance: f32, // 最大距离
+    pub rotate_speed: f32, // 从窗口顶部拖到底部旋转的角度（弧度）
+    pub zoom_speed: f32,   // 每滚动一行距离乘以的系数（小于1时向上滚动拉近）
+}
+
+impl OrbitController {
+    /// 仰角的限制，略小于90度
+    const MAX_PITCH: f32 = std::f32::consts::FRAC_PI_2 - 0.01;
+
+    /// 创建控制器，距离限制为初始距离的1%到100倍
+    pub fn new(target: Vec3, distance: f32, yaw: f32, pitch: f32) -> Self {
+        Self {
+            target,
+            distance,
+            yaw,
+            pitch: pitch.clamp(-Self::MAX_PITCH, Self::MAX_PITCH),
+            min_distance: distance * 0.01,
+            max_distance: distance * 100.0,
+            rotate_speed: std::f32::consts::PI,
+            zoom_speed: 0.9,
+        }
+    }
+
+    /// 从相机的位置和观察点求出轨道参数（忽略相机的上方向）
+    pub fn from_camera(camera: &Camera) -> Self {
+        let offset = camera.eye - camera.target;
+        let distance = offset.length().max(f32::EPSILON);
+        Self::new(
+            camera.target,
+            distance,
+            offset.x.atan2(offset.z),
+            (offset.y / distance).clamp(-1.0, 1.0).asin(),
+        )
+    }
+
+    /// 相机位置
+    pub fn eye(&self) -> Vec3 {
+        let (sin_yaw, cos_yaw) = self.yaw.sin_cos();
+        let (sin_pitch, cos_pitch) = self.pitch.sin_cos();
+        self.target + Vec3::new(cos_pitch * sin_yaw, sin_pitch, cos_pitch * cos_yaw) * self.distance
+    }
+
+    /// 根据本帧的鼠标输入更新轨道参数，并写入相机的位置、观察点和上方向，返回参数是否变化
+    ///
+    /// viewport_height为表面高度（物理像素），用于把鼠标移动量归一化。
+    /// 正交投影的视野高度随距离同比缩放，使滚轮在两种投影下都能缩放画面。
+    pub fn update(
+        &mut self,
+        input: &InputState,
+        viewport_height: u32,
+        camera: &mut Camera,
+    ) -> bool {
+        let before = *self;
+        if !input.consumed() {
+            let (dx, dy) = input.mouse_delta();
+            let (dx, dy) = (
+                dx as f32 / viewport_height.max(1) as f32,
+                dy as f32 / viewport_height.max(1) as f32,
+            );
+            let left = input.mouse_pressed(MouseButton::Left);
+            let pan =
+                input.mouse_pressed(MouseButton::Middle) || (left && input.modifiers().shift_key());
+            if pan {
+                self.pan(dx, dy, &camera.projection);
+            } else if left {
+                self.yaw -= dx * self.rotate_speed;
+                self.pitch =
+                    (self.pitch + dy * self.rotate_speed).clamp(-Self::MAX_PITCH, Self::MAX_PITCH);
+            }
+
+            let (_, scroll) = input.scroll_delta();
+            if scroll != 0.0 {
+                let distance = (self.distance * self.zoom_speed.powf(scroll))
+                    .clamp(self.min_distance, self.max_distance);
+                if let Projection::Orthographic { height, .. } = &mut camera.projection {
+                    *height *= distance / self.distance;
+                }
+                self.distance = distance;
+            }
+        }
+        self.apply(camera);
+        *self != before
+    }
+
+    /// 把轨道参数写入相机（位置、观察点、上方向），投影保持不变
+    pub fn apply(&self, camera: &mut Camera) {
+        camera.eye = self.eye();
+        camera.target = self.target;
+        camera.up = Vec3::Y;
+    }
+
+    /// 按归一化的鼠标移动量平移观察点，移动量为观察点所在平面上的视野高度乘以移动比例，
+    /// 使观察点附近的物体跟随光标移动
+    fn pan(&mut self, dx: f32, dy: f32, projection: &Projection) {
+        let view_height = match *projection {
+            Projection::Perspective { fovy, .. } => 2.0 * self.distance * (fovy / 2.0).tan(),
+            Projection::Orthographic { height, .. } => height,
+        };
+        let forward = (self.target - self.eye()).normalize_or_zero();
+        let right = forward.cross(Vec3::Y).normalize_or_zero();
+        let up = right.cross(forward);
+        self.target += (up * dy - right * dx) * view_height;
+    }
+}
+
+/// 自由飞行（第一人称）相机控制器：相机位于position，朝向由yaw和pitch决定，上方向始终为世界Y轴（不会滚转）
+///
+/// - W/S、A/D：在水平面内沿视线方向前后、左右移动；E/Q：沿世界Y轴上升、下降（空格键已用于暂停）
+/// - 按住Shift：速度乘以`boost`
+/// - 鼠标视角模式（Tab键或按住右键）下用设备的原始移动量转动视角
+/// - 手柄：左摇杆与WASD叠加（推一半时速度也是一半），右摇杆转动视角，右扳机上升、左扳机下降
+///
+/// 速度按`damping`指数趋近目标速度，松开按键后平滑减速；位移按帧间隔dt计算，与帧率无关。
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct FlyController {
+    pub position: Vec3,        // 相机位置
+    pub yaw: f32,              // 绕Y轴的角度（弧度），0时看向-Z
+    pub pitch: f32,            // 仰角（弧度），正值时向上看
+    pub velocity: Vec3,        // 当前速度（世界单位/秒）
+    pub speed: f32,            // 最大速度（世界单位/秒）
+    pub boost: f32,            // 按住Shift时的速度倍数
+    pub damping: f32,          // 速度趋近目标速度的速率（1/秒），越大加减速越快
+    pub look_sensitivity: f32, // 每单位原始移动量转动的角度（弧度）
+    pub stick_look_speed: f32, // 右摇杆推到底时每秒转动的角度（弧度）
+}
+
+impl FlyController {
+    /// 仰角的限制，略小于90度（与`OrbitController`相同）
+    const MAX_PITCH: f32 = OrbitController::MAX_PITCH;
+
+    /// 创建静止的控制器
+    pub fn new(position: Vec3, yaw: f32, pitch: f32, speed: f32) -> Self {
+        Self {
+            position,
+            yaw,
+            pitch: pitch.clamp(-Self::MAX_PITCH, Self::MAX_PITCH),
+            velocity: Vec3::ZERO,
+            speed,
+            boost: 4.0,
+            damping: 10.0,
+            look_sensitivity: 0.003,
+            stick_look_speed: 2.0,
+        }
+    }
+
+    /// 从相机的位置和视线方向求出控制器参数（忽略相机的上方向），速度为speed
+    pub fn from_camera(camera: &Camera, speed: f32) -> Self {
+        let forward = (camera.target - camera.eye).normalize_or(Vec3::NEG_Z);
+        Self::new(
+            camera.eye,
+            (-forward.x).atan2(-forward.z),
+            forward.y.clamp(-1.0, 1.0).asin(),
+            speed,
+        )
+    }
+
+    /// 视线方向（单位向量）
+    pub fn forward(&self) -> Vec3 {
+        let (sin_yaw, cos_yaw) = self.yaw.sin_cos();
+        let (sin_pitch, cos_pitch) = self.pitch.sin_cos();
+        Vec3::new(-cos_pitch * sin_yaw, sin_pitch, -cos_pitch * cos_yaw)
+    }
+
+    /// 根据本帧的键盘、鼠标和手柄输入更新朝向、速度和位置，并写入相机，返回相机是否移动或转动
+    pub fn update(&mut self, input: &InputState, dt: Duration, camera: &mut Camera) -> bool {
+        let before = (self.position, self.yaw, self.pitch);
+        let dt = dt.as_secs_f32();
+        let gamepad = input.gamepad();
+        let (mut turn_x, mut turn_y) = (0.0, 0.0);
+        if input.raw_motion() && !input.consumed() {
+            let (dx, dy) = input.mouse_delta();
+            turn_x += dx as f32 * self.look_sensitivity;
+            turn_y += dy as f32 * self.look_sensitivity;
+        }
+        // 摇杆向上为正，与鼠标的屏幕坐标相反
+        turn_x += gamepad.look.0 * self.stick_look_speed * dt;
+        turn_y -= gamepad.look.1 * self.stick_look_speed * dt;
+        self.yaw -= turn_x;
+        self.pitch = (self.pitch - turn_y).clamp(-Self::MAX_PITCH, Self::MAX_PITCH);
+
+        // 水平面内的前、右方向，俯仰不影响移动速度
+        let (sin_yaw, cos_yaw) = self.yaw.sin_cos();
+        let forward = Vec3::new(-sin_yaw, 0.0, -cos_yaw);
+        let right = Vec3::new(cos_yaw, 0.0, -sin_yaw);
+        let axis = |positive: KeyCode, negative: KeyCode| {
+            input.pressed(positive) as i32 as f32 - input.pressed(negative) as i32 as f32
+        };
+        // 按键的方向长度至少为1，限制到1后与归一化相同；摇杆没推到底时保留较小的长度
+        let (stick_x, stick_y) = gamepad.movement;
+        let direction = (forward * (axis(KeyCode::KeyW, KeyCode::KeyS) + stick_y)
+            + right * (axis(KeyCode::KeyD, KeyCode::KeyA) + stick_x)
+            + Vec3::Y * (axis(KeyCode::KeyE, KeyCode::KeyQ) + gamepad.zoom))
+            .clamp_length_max(1.0);
+        let speed = if input.modifiers().shift_key() {
+            self.speed * self.boost
+        } else {
+            self.speed
+        };
+
+        // 指数平滑：无论帧率如何，经过同样的时间速度都接近目标速度同样的比例
+        let blend = 1.0 - (-self.damping * dt).exp();
+        self.velocity = self.velocity.lerp(direction * speed, blend);
+        if self.velocity.length_squared() < 1e-8 {
+            self.velocity = Vec3::ZERO;
+        }
+        self.position += self.velocity * dt;
+        self.apply(camera);
+        (self.position, self.yaw, self.pitch) != before
+    }
+
+    /// 把位置和朝向写入相机（观察点为前方一个单位处），投影保持不变
+    pub fn apply(&self, camera: &mut Camera) {
+        camera.eye = self.position;
+        camera.target = self.position + self.forward();
+        camera.up = Vec3::Y;
+    }
+}
+
+/// 相机控制方式
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ControllerKind {
+    Orbit, // 轨道控制（见`OrbitController`）
+    Fly,   // 自由飞行（见`FlyController`）
+}
+
+/// 数字键1～9对应的书签槽位
+const BOOKMARK_KEYS: [KeyCode; BOOKMARK_SLOTS] = [
+    KeyCode::Digit1,
+    KeyCode::Digit2,
+    KeyCode::Digit3,
+    KeyCode::Digit4,
+    KeyCode::Digit5,
+    KeyCode::Digit6,
+    KeyCode::Digit7,
+    KeyCode::Digit8,
+    KeyCode::Digit9,
+];
+
+/// 切换到保存的姿态时的过渡时长
+const BOOKMARK_TRANSITION: Duration = Duration::from_millis(500);
+
+/// 可在运行时切换的相机控制器：同时保存两种控制器的设置，切换时从当前相机的位置和朝向接续
+///
+/// 按住Shift按数字键1～9把当前姿态保存到对应槽位，直接按数字键用0.5秒过渡到保存的姿态。
+/// 过渡中有鼠标或键盘操作时立即停止过渡，控制器从当前位置接续，画面不会跳变。
+pub struct CameraController {
+    pub orbit: OrbitController,     // 轨道控制器
+    pub fly: FlyController,         // 自由飞行控制器
+    pub kind: ControllerKind,       // 当前使用的控制器
+    pub animator: CameraAnimator,   // 切换到保存的姿态时的过渡动画
+    pub bookmarks: CameraBookmarks, // 数字键保存的姿态
+}
+
+impl CameraController {
+    /// 默认使用轨道控制，飞行速度按轨道距离设置（每秒飞过一半的轨道距离），保存的姿态只在内存中
+    pub fn new(orbit: OrbitController) -> Self {
+        let mut camera = Camera::default();
+        orbit.apply(&mut camera);
+        Self {
+            orbit,
+            fly: FlyController::from_camera(&camera, orbit.distance * 0.5),
+            kind: ControllerKind::Orbit,
+            animator: CameraAnimator::default(),
+            bookmarks: CameraBookmarks::new(),
+        }
+    }
+
+    /// 从文件加载scene保存的姿态，之后保存的姿态也写入文件（见`CameraBookmarks::load`）
+    pub fn with_bookmarks(mut self, scene: &str) -> Self {
+        self.bookmarks = CameraBookmarks::load(scene);
+        self
+    }
+
+    /// 处理数字键并用过渡动画或当前控制器更新相机，返回相机是否变化
+    ///
+    /// viewport_height为表面高度（物理像素），dt为帧间隔。
+    pub fn update(
+        &mut self,
+        input: &InputState,
+        dt: Duration,
+        viewport_height: u32,
+        camera: &mut Camera,
+    ) -> bool {
+        for (slot, key) in BOOKMARK_KEYS.into_iter().enumerate() {
+            if !input.just_pressed(key) {
+                continue;
+            }
+            if input.modifiers().shift_key() {
+                self.bookmarks.set(slot, camera);
+                info!("Saved camera pose {}", slot + 1);
+            } else if let Some(pose) = self.bookmarks.get(slot) {
+                self.animator.start(camera, pose, BOOKMARK_TRANSITION);
+                info!("Moving to camera pose {}", slot + 1);
+            } else {
+                info!(
+                    "No camera pose saved in slot {}, press Shift+{} to save one",
+                    slot + 1,
+                    slot + 1
+                );
+            }
+        }
+        if !self.animator.is_active() {
+            return self.update_controller(input, dt, viewport_height, camera);
+        }
+
+        // 过渡中：控制器先从当前姿态接续，有操作使相机变化时停止过渡，把控制交还给控制器
+        self.sync(camera);
+        let mut manual = *camera;
+        if self.update_controller(input, dt, viewport_height, &mut manual) {
+            self.animator.cancel();
+            *camera = manual;
+            return true;
+        }
+        if self.animator.update(dt, camera) {
+            self.sync(camera);
+        }
+        true
+    }
+
+    /// 用当前控制器更新相机
+    fn update_controller(
+        &mut self,
+        input: &InputState,
+        dt: Duration,
+        viewport_height: u32,
+        camera: &mut Camera,
+    ) -> bool {
+        match self.kind {
+            ControllerKind::Orbit => self.orbit.update(input, viewport_height, camera),
+            ControllerKind::Fly => self.fly.update(input, dt, camera),
+        }
+    }
+
+    /// 让当前控制器从相机的位置和朝向接续（轨道控制以相机的观察点为中心），飞行速度清零
+    pub fn sync(&mut self, camera: &Camera) {
+        match self.kind {
+            ControllerKind::Orbit => {
+                let pose = OrbitController::from_camera(camera);
+                let orbit = &mut self.orbit;
+                (orbit.target, orbit.distance, orbit.yaw, orbit.pitch) =
+                    (pose.target, pose.distance, pose.yaw, pose.pitch);
+            }
+            ControllerKind::Fly => {
+                let pose = FlyController::from_camera(camera, self.fly.speed);
+                let fly = &mut self.fly;
+                (fly.position, fly.yaw, fly.pitch) = (pose.position, pose.yaw, pose.pitch);
+                fly.velocity = Vec3::ZERO;
+            }
+        }
+    }
+
+    /// 切换到另一种控制器，保持相机的位置和视线方向不变
+    ///
+    /// 切换到飞行时从相机位置出发，速度清零；切换到轨道时把观察点放在视线前方原轨道距离处。
+    pub fn toggle(&mut self, camera: &Camera) {
+        match self.kind {
+            ControllerKind::Orbit => {
+                self.kind = ControllerKind::Fly;
+                self.sync(camera);
+            }
+            ControllerKind::Fly => {
+                let forward = (camera.target - camera.eye).normalize_or(Vec3::NEG_Z);
+                let orbit = &mut self.orbit;
+                orbit.target = camera.eye + forward * orbit.distance;
+                orbit.yaw = (-forward.x).atan2(-forward.z);
+                orbit.pitch = (-forward.y)
+                    .clamp(-1.0, 1.0)
+                    .asin()
+                    .clamp(-OrbitController::MAX_PITCH, OrbitController::MAX_PITCH);
+                self.kind = ControllerKind::Orbit;
+            }
+        }
+    }
+
+    /// 当前控制器的名称（用于日志）
+    pub fn name(&self) -> &'static str {
+        match self.kind {
+            ControllerKind::Orbit => "orbit",
+            ControllerKind::Fly => "fly",
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::GamepadAxes;
+
+    /// 允许的浮点误差
+    const TOLERANCE: f32 = 1e-5;
+
+    /// 把观察空间的点投影到NDC（x、y在-1..1，深度在0..1）
+    fn project(projection: Projection, aspect: f32, point: Vec3) -> Vec3 {
+        projection.matrix(aspect).project_point3(point)
+    }
+
+    fn assert_near(actual: Vec3, expected: Vec3) {
+        assert!(
+            actual.abs_diff_eq(expected, TOLERANCE),
+            "{actual} != {expected}"
+        );
+    }
+
+    #[test]
+    fn perspective_maps_near_and_far_to_0_and_1() {
+        // 90度视野：距离d处的半高为d，宽高比2时半宽为2d
+        let projection = Projection::Perspective {
+            fovy: std::f32::consts::FRAC_PI_2,
+            znear: 1.0,
+            zfar: 10.0,
+        };
+        // 近平面的右上角
+        assert_near(
+            project(projection, 2.0, Vec3::new(2.0, 1.0, -1.0)),
+            Vec3::new(1.0, 1.0, 0.0),
+        );
+        // 远平面上：x = -10 / 20，y = 5 / 10
+        assert_near(
+            project(projection, 2.0, Vec3::new(-10.0, 5.0, -10.0)),
+            Vec3::new(-0.5, 0.5, 1.0),
+        );
+        // 视线方向上的点在画面中心，深度非线性：z = -3时为 (f / (n - f) * z + n * f / (n - f)) / -z = 20 / 27
+        assert_near(
+            project(projection, 2.0, Vec3::new(0.0, 0.0, -3.0)),
+            Vec3::new(0.0, 0.0, 20.0 / 27.0),
+        );
+    }
+
+    #[test]
+    fn orthographic_maps_near_and_far_to_0_and_1() {
+        // 高4，宽高比2时宽8：半宽4、半高2，与距离无关
+        let projection = Projection::Orthographic {
+            height: 4.0,
+            znear: 1.0,
+            zfar: 10.0,
+        };
+        assert_near(
+            project(projection, 2.0, Vec3::new(4.0, -2.0, -1.0)),
+            Vec3::new(1.0, -1.0, 0.0),
+        );
+        assert_near(
+            project(projection, 2.0, Vec3::new(-2.0, 1.0, -10.0)),
+            Vec3::new(-0.5, 0.5, 1.0),
+        );
+        // 远近不同的同一点投影到同一位置
+        assert_near(
+            project(projection, 2.0, Vec3::new(-2.0, 1.0, -5.5)),
+            Vec3::new(-0.5, 0.5, 0.5),
+        );
+    }
+
+    /// 从原点看向-Z、速度为4的飞行控制器在手柄输入axes下运行seconds秒（每帧1/60秒）
+    fn fly_with_gamepad(axes: GamepadAxes, seconds: f32) -> FlyController {
+        let mut input = InputState::default();
+        input.set_gamepad(axes);
+        let mut fly = FlyController::new(Vec3::ZERO, 0.0, 0.0, 4.0);
+        let mut camera = Camera::default();
+        let dt = Duration::from_secs_f32(1.0 / 60.0);
+        for _ in 0..(seconds * 60.0).round() as u32 {
+            fly.update(&input, dt, &mut camera);
+        }
+        fly
+    }
+
+    #[test]
+    fn left_stick_moves_the_fly_camera_at_a_proportional_speed() {
+        let full = fly_with_gamepad(
+            GamepadAxes {
+                movement: (0.0, 1.0),
+                ..Default::default()
+            },
+            2.0,
+        );
+        // 向前推到底：沿-Z以接近最大速度移动
+        assert_near(full.velocity, Vec3::new(0.0, 0.0, -4.0));
+        assert!(full.position.z < -6.0, "{}", full.position);
+
+        // 向右推一半：速度也是一半
+        let half = fly_with_gamepad(
+            GamepadAxes {
+                movement: (0.5, 0.0),
+                ..Default::default()
+            },
+            2.0,
+        );
+        assert!(half.velocity.abs_diff_eq(Vec3::new(2.0, 0.0, 0.0), 1e-3));
+    }
+
+    #[test]
+    fn triggers_move_the_fly_camera_vertically() {
+        let fly = fly_with_gamepad(
+            GamepadAxes {
+                zoom: -1.0,
+                ..Default::default()
+            },
+            2.0,
+        );
+        assert!(fly.velocity.abs_diff_eq(Vec3::new(0.0, -4.0, 0.0), 1e-3));
+    }
+
+    #[test]
+    fn right_stick_turns_the_fly_camera_by_time() {
+        // 右上推到底0.5秒：向右转、向上看各1弧度（位置不变）
+        let fly = fly_with_gamepad(
+            GamepadAxes {
+                look: (1.0, 1.0),
+                ..Default::default()
+            },
+            0.5,
+        );
+        assert!((fly.yaw + 1.0).abs() < 1e-4, "yaw {}", fly.yaw);
+        assert!((fly.pitch - 1.0).abs() < 1e-4, "pitch {}", fly.pitch);
+        assert_eq!(fly.position, Vec3::ZERO);
+    }
+}
@@ -0,0 +1,254 @@
+use crate::WgpuAppBuilder;
+use log::{info, warn};
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use winit::{
+    dpi::LogicalSize,
+    window::{Fullscreen, WindowAttributes},
+};
+
+/// 默认的配置文件（相对于工作目录）
+pub const SETTINGS_FILE: &str = "settings.toml";
+
+/// 保存的相机姿态（与配置文件放在同一目录，见`camera::CameraBookmarks`）
+pub const CAMERA_POSES_FILE: &str = "camera_poses.json";
+
+/// 应用配置：窗口和图形设置，文件中缺失的项使用默认值
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Settings {
+    pub window: WindowSettings,     // 窗口设置
+    pub graphics: GraphicsSettings, // 图形设置
+    pub input: InputSettings,       // 输入设置
+}
+
+/// 窗口设置
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct WindowSettings {
+    pub title: String,                // 窗口标题
+    pub width: u32,                   // 初始宽度（逻辑像素）
+    pub height: u32,                  // 初始高度（逻辑像素）
+    pub resizable: bool,              // 是否允许调整大小
+    pub fullscreen: bool,             // 是否以无边框全屏启动
+    pub icon: Option<PathBuf>,        // 窗口图标（32x32的PNG），未设置或无法使用时使用内置图标
+    pub position: Option<(i32, i32)>, // 上次退出时窗口左上角的位置（物理像素，含边框）
+    pub monitor: Option<String>,      // 上次退出时窗口所在的显示器名称
+    pub maximized: bool,              // 上次退出时是否最大化
+}
+
+impl Default for WindowSettings {
+    fn default() -> Self {
+        Self {
+            title: "Wgpu Learn".to_string(),
+            width: 1280,
+            height: 720,
+            resizable: true,
+            fullscreen: false,
+            icon: None,
+            position: None,
+            monitor: None,
+            maximized: false,
+        }
+    }
+}
+
+impl WindowSettings {
+    /// 根据设置创建窗口属性（位置需要结合当前的显示器决定，见`WindowPlacement`）
+    pub fn window_attributes(&self) -> WindowAttributes {
+        WindowAttributes::default()
+            .with_title(&self.title)
+            .with_inner_size(LogicalSize::new(self.width.max(1), self.height.max(1)))
+            .with_resizable(self.resizable)
+            .with_maximized(self.maximized)
+            .with_fullscreen(self.fullscreen.then_some(Fullscreen::Borderless(None)))
+    }
+}
+
+/// 退出时记录的主窗口位置和状态，写回配置文件后下次启动时恢复
+///
+/// 最大化或最小化时尺寸和位置没有意义，对应字段为None，配置文件中保留之前的值。
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct WindowPlacement {
+    pub size: Option<(u32, u32)>,     // 窗口模式下的尺寸（逻辑像素）
+    pub position: Option<(i32, i32)>, // 窗口左上角的位置（物理像素，含边框）
+    pub monitor: Option<String>,      // 窗口所在的显示器名称
+    pub maximized: bool,              // 是否最大化
+}
+
+/// 图形设置
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct GraphicsSettings {
+    pub present_mode: PresentModeSetting, // 呈现模式（垂直同步）
+    pub msaa_samples: u32,                // 多重采样数（1表示不使用MSAA）
+    pub power_preference: PowerPreferenceSetting, // 适配器电源偏好
+    pub frame_limit: Option<u32>,         // 帧率上限（未设置时不限制）
+}
+
+impl Default for GraphicsSettings {
+    fn default() -> Self {
+        Self {
+            present_mode: PresentModeSetting::Auto,
+            msaa_samples: 4,
+            power_preference: PowerPreferenceSetting::Default,
+            frame_limit: None,
+        }
+    }
+}
+
+/// 输入设置
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct InputSettings {
+    pub gamepad_dead_zone: f32, // 手柄摇杆死区（0..1，启用gamepad特性时使用）
+}
+
+impl Default for InputSettings {
+    fn default() -> Self {
+        Self {
+            gamepad_dead_zone: 0.15,
+        }
+    }
+}
+
+/// 配置文件中的呈现模式
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PresentModeSetting {
+    Auto,      // 使用表面的默认呈现模式
+    Fifo,      // 垂直同步（所有平台都支持）
+    Mailbox,   // 低延迟的垂直同步（不支持时构建失败）
+    Immediate, // 关闭垂直同步（可能出现画面撕裂）
+}
+
+/// 配置文件中的适配器电源偏好
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PowerPreferenceSetting {
+    Default,         // 不指定偏好
+    LowPower,        // 优先使用集成显卡
+    HighPerformance, // 优先使用独立显卡
+}
+
+impl From<PowerPreferenceSetting> for wgpu::PowerPreference {
+    fn from(value: PowerPreferenceSetting) -> Self {
+        match value {
+            PowerPreferenceSetting::Default => wgpu::PowerPreference::None,
+            PowerPreferenceSetting::LowPower => wgpu::PowerPreference::LowPower,
+            PowerPreferenceSetting::HighPerformance => wgpu::PowerPreference::HighPerformance,
+        }
+    }
+}
+
+impl Settings {
+    /// 从工作目录下的`settings.toml`加载配置
+    ///
+    /// 文件不存在时写出一份默认配置方便用户修改；文件格式错误时记录错误并使用默认值。
+    /// wasm上没有文件系统、Android上工作目录不可写，直接使用默认值。
+    pub fn load_or_create() -> Self {
+        #[cfg(any(target_arch = "wasm32", target_os = "android"))]
+        return Self::default();
+        #[cfg(not(any(target_arch = "wasm32", target_os = "android")))]
+        Self::load_or_create_at(SETTINGS_FILE)
+    }
+
+    /// 从指定路径加载配置，规则与`load_or_create`相同
+    pub fn load_or_create_at(path: impl AsRef<Path>) -> Self {
+        let path = path.as_ref();
+        match std::fs::read_to_string(path) {
+            Ok(source) => match toml::from_str(&source) {
+                Ok(settings) => {
+                    info!("Loaded settings from {}", path.display());
+                    settings
+                }
+                // toml的错误信息中包含出错的行号、列号和对应的源码片段
+                Err(e) => {
+                    warn!("Failed to parse {}, using defaults: {e}", path.display());
+                    Self::default()
+                }
+            },
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => {
+                let settings = Self::default();
+                settings.save(path);
+                settings
+            }
+            Err(e) => {
+                warn!("Failed to read {}, using defaults: {e}", path.display());
+                Self::default()
+            }
+        }
+    }
+
+    /// 只把窗口位置和状态写回`settings.toml`，其他设置保持文件中的值（命令行覆盖的设置不会被写入）
+    pub fn save_window_placement(placement: &WindowPlacement) {
+        #[cfg(not(any(target_arch = "wasm32", target_os = "android")))]
+        Self::save_window_placement_at(SETTINGS_FILE, placement);
+        #[cfg(any(target_arch = "wasm32", target_os = "android"))]
+        let _ = placement;
+    }
+
+    /// 只把窗口位置和状态写回指定路径的配置文件，文件无法解析时不覆盖
+    pub fn save_window_placement_at(path: impl AsRef<Path>, placement: &WindowPlacement) {
+        let path = path.as_ref();
+        let mut settings: Self = match std::fs::read_to_string(path) {
+            Ok(source) => match toml::from_str(&source) {
+                Ok(settings) => settings,
+                Err(e) => {
+                    warn!(
+                        "Not saving the window placement, {} is malformed: {e}",
+                        path.display()
+                    );
+                    return;
+                }
+            },
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Self::default(),
+            Err(e) => {
+                warn!("Failed to read {}: {e}", path.display());
+                return;
+            }
+        };
+        let window = &mut settings.window;
+        let before = window.clone();
+        if let Some((width, height)) = placement.size {
+            (window.width, window.height) = (width, height);
+        }
+        if placement.position.is_some() {
+            window.position = placement.position;
+        }
+        if placement.monitor.is_some() {
+            window.monitor.clone_from(&placement.monitor);
+        }
+        window.maximized = placement.maximized;
+        if settings.window != before {
+            settings.save(path);
+        }
+    }
+
+    /// 将配置写入文件
+    pub fn save(&self, path: impl AsRef<Path>) {
+        let path = path.as_ref();
+        let result = toml::to_string_pretty(self)
+            .map_err(|e| e.to_string())
+            .and_then(|source| std::fs::write(path, source).map_err(|e| e.to_string()));
+        match result {
+            Ok(()) => info!("Wrote settings to {}", path.display()),
+            Err(e) => warn!("Failed to write settings to {}: {e}", path.display()),
+        }
+    }
+}
+
+impl WgpuAppBuilder {
+    /// 应用配置文件中的图形设置（呈现模式、多重采样数和电源偏好）
+    pub fn graphics_settings(self, settings: &GraphicsSettings) -> Self {
+        let builder = self
+            .sample_count(settings.msaa_samples)
+            .power_preference(settings.power_preference.into());
+        match settings.present_mode {
+            PresentModeSetting::Auto => builder,
+            PresentModeSetting::Fifo => builder.present_mode(wgpu::PresentMode::Fifo),
+            PresentModeSetting::Mailbox => builder.present_mode(wgpu::PresentMode::Mailbox),
+            PresentModeSetting::Immediate => builder.present_mode(wgpu::PresentMode::Immediate),
+        }
+    }
+}
@@ -0,0 +1,68 @@
+/// 调试标签：为GPU对象名称加上统一前缀（如场景名），便于在RenderDoc等工具中区分
+///
+/// 启用`no-debug-labels`特性时不生成任何标签，调试分组和标记也变为空操作。
+#[derive(Debug, Clone, Default)]
+pub struct DebugLabels {
+    prefix: String, // 标签前缀（为空时不加前缀）
+}
+
+impl DebugLabels {
+    pub fn new(prefix: impl Into<String>) -> Self {
+        Self {
+            prefix: prefix.into(),
+        }
+    }
+
+    /// 标签前缀
+    pub fn prefix(&self) -> &str {
+        &self.prefix
+    }
+
+    /// 生成`前缀 名称`形式的标签，配合`.as_deref()`传给描述符
+    pub fn label(&self, name: &str) -> Option<String> {
+        #[cfg(feature = "no-debug-labels")]
+        {
+            let _ = name;
+            None
+        }
+        #[cfg(not(feature = "no-debug-labels"))]
+        {
+            if self.prefix.is_empty() {
+                Some(name.to_string())
+            } else {
+                Some(format!("{} {name}", self.prefix))
+            }
+        }
+    }
+}
+
+/// 开始一个调试分组
+pub fn push_debug_group(encoder: &mut wgpu::CommandEncoder, name: &str) {
+    #[cfg(not(feature = "no-debug-labels"))]
+    encoder.push_debug_group(name);
+    #[cfg(feature = "no-debug-labels")]
+    let _ = (encoder, name);
+}
+
+/// 插入一个调试标记
+pub fn insert_debug_marker(encoder: &mut wgpu::CommandEncoder, name: &str) {
+    #[cfg(not(feature = "no-debug-labels"))]
+    encoder.insert_debug_marker(name);
+    #[cfg(feature = "no-debug-labels")]
+    let _ = (encoder, name);
+}
+
+/// 结束最近的调试分组
+pub fn pop_debug_group(encoder: &mut wgpu::CommandEncoder) {
+    #[cfg(not(feature = "no-debug-labels"))]
+    encoder.pop_debug_group();
+    #[cfg(feature = "no-debug-labels")]
+    let _ = encoder;
+}
+
+/// 类型名的最后一段（如`RotatingTriangle`），用作默认标签前缀
+pub(crate) fn short_type_name<T>() -> &'static str {
+    let name = std::any::type_name::<T>();
+    let name = name.split('<').next().unwrap_or(name);
+    name.rsplit("::").next().unwrap_or(name)
+}
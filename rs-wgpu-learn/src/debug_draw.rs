@@ -0,0 +1,369 @@
+use crate::{
+    DepthTexture, RenderContext, VertexNormal, WgpuAppError,
+    binding::{self, BindGroupBuilder, BindGroupLayoutBuilder},
+    math::Aabb,
+    rebuild_pipeline, shader, vertex_layout,
+};
+use glam::{Mat3, Mat4, Vec3};
+use std::path::Path;
+
+/// 调试线使用的着色器文件
+const SHADER: &str = "debug_lines.wgsl";
+
+/// 顶点缓冲区的初始容量（顶点数）
+const INITIAL_CAPACITY: u64 = 1024;
+
+/// `add_sphere`每个圆的线段数
+const SPHERE_SEGMENTS: u32 = 32;
+
+vertex_layout! {
+    /// 调试线的端点：世界空间位置和颜色
+    #[derive(Debug, PartialEq)]
+    pub struct DebugVertex {
+        pub position: [f32; 3], // 世界空间坐标，@location(0)
+        pub color: [f32; 4],    // 线性空间的RGBA颜色，@location(1)
+    }
+}
+
+/// 调试线的深度处理方式
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum DebugDepth {
+    /// 与场景一起做深度测试（被几何体遮挡，不写入深度）
+    #[default]
+    Test,
+    /// 始终显示在最上层（深度比较为Always）
+    AlwaysOnTop,
+}
+
+/// 立即模式的调试线：每帧开始时`clear`，之后随时`add`线段、包围盒或球，
+/// 渲染前`prepare`上传，最后在场景的渲染通道末尾`draw`（一次LineList绘制）
+///
+/// 渲染通道需要带`RenderContext::depth_attachment()`。顶点缓冲区容量不足时按2倍增长，不会缩小。
+pub struct DebugLines {
+    vertices: Vec<DebugVertex>, // 本帧累积的线段端点（每两个一条线段）
+    vertex_buffer: wgpu::Buffer,
+    capacity: u64,     // 顶点缓冲区能容纳的顶点数
+    vertex_count: u32, // 上一次prepare上传的顶点数
+    uniform_buffer: wgpu::Buffer,
+    bind_group: wgpu::BindGroup,
+    pipeline: wgpu::RenderPipeline,
+    pipeline_layout: wgpu::PipelineLayout,
+    format: wgpu::TextureFormat,
+    sample_count: u32,
+    cache: Option<wgpu::PipelineCache>,
+    depth: DebugDepth,
+}
+
+impl DebugLines {
+    /// 创建调试线渲染器，format和sample_count需与绘制时的渲染通道一致
+    pub fn new(
+        device: &wgpu::Device,
+        format: wgpu::TextureFormat,
+        sample_count: u32,
+        cache: Option<&wgpu::PipelineCache>,
+        depth: DebugDepth,
+    ) -> Result<Self, WgpuAppError> {
+        let uniform_buffer = device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some("Debug Lines Uniform Buffer"),
+            size: std::mem::size_of::<Mat4>() as u64,
+            usage: wgpu::BufferUsages::UNIFORM | wgpu::BufferUsages::COPY_DST,
+            mapped_at_creation: false,
+        });
+        let bind_group_layout = BindGroupLayoutBuilder::new()
+            .label("Debug Lines Bind Group Layout")
+            .uniform(wgpu::ShaderStages::VERTEX)
+            .build(device);
+        let bind_group = BindGroupBuilder::new(&bind_group_layout)
+            .label("Debug Lines Bind Group")
+            .buffer(&uniform_buffer)
+            .build(device);
+        let pipeline_layout = binding::pipeline_layout(
+            device,
+            Some("Debug Lines Pipeline Layout"),
+            &[&bind_group_layout],
+        );
+        let shader = shader::load(device, SHADER)?;
+        let pipeline = create_pipeline(
+            device,
+            &shader,
+            format,
+            &pipeline_layout,
+            sample_count,
+            cache,
+            depth,
+        );
+        Ok(Self {
+            vertices: Vec::new(),
+            vertex_buffer: create_vertex_buffer(device, INITIAL_CAPACITY),
+            capacity: INITIAL_CAPACITY,
+            vertex_count: 0,
+            uniform_buffer,
+            bind_group,
+            pipeline,
+            pipeline_layout,
+            format,
+            sample_count,
+            cache: cache.cloned(),
+            depth,
+        })
+    }
+
+    /// 清空累积的线段，每帧开始时调用
+    pub fn clear(&mut self) {
+        self.vertices.clear();
+    }
+
+    /// 本帧累积的线段数
+    pub fn len(&self) -> usize {
+        self.vertices.len() / 2
+    }
+
+    /// 本帧是否还没有线段
+    pub fn is_empty(&self) -> bool {
+        self.vertices.is_empty()
+    }
+
+    /// 添加一条线段
+    pub fn add(&mut self, from: Vec3, to: Vec3, color: [f32; 4]) {
+        self.vertices.extend([
+            DebugVertex {
+                position: from.to_array(),
+                color,
+            },
+            DebugVertex {
+                position: to.to_array(),
+                color,
+            },
+        ]);
+    }
+
+    /// 添加轴对齐包围盒的12条棱，空包围盒不添加
+    pub fn add_aabb(&mut self, aabb: &Aabb, color: [f32; 4]) {
+        self.add_box(aabb, Mat4::IDENTITY, color);
+    }
+
+    /// 添加经过transform变换的包围盒（如模型空间的包围盒画在世界空间中，旋转后不再与坐标轴对齐）
+    pub fn add_box(&mut self, aabb: &Aabb, transform: Mat4, color: [f32; 4]) {
+        if aabb.is_empty() {
+            return;
+        }
+        let (min, max) = (aabb.min, aabb.max);
+        self.add_box_corners(
+            |i| {
+                transform.transform_point3(Vec3::new(
+                    if i & 1 == 0 { min.x } else { max.x },
+                    if i & 2 == 0 { min.y } else { max.y },
+                    if i & 4 == 0 { min.z } else { max.z },
+                ))
+            },
+            color,
+        );
+    }
+
+    /// 添加视图投影矩阵对应的视锥体的12条棱（如冻结的相机，用于观察视锥体剔除）
+    ///
+    /// 用逆矩阵把裁剪空间的8个角（x、y为±1，z为0和1）变换回世界空间，需要透视除法。
+    pub fn add_frustum(&mut self, view_proj: Mat4, color: [f32; 4]) {
+        let inverse = view_proj.inverse();
+        self.add_box_corners(
+            |i| {
+                inverse.project_point3(Vec3::new(
+                    if i & 1 == 0 { -1.0 } else { 1.0 },
+                    if i & 2 == 0 { -1.0 } else { 1.0 },
+                    if i & 4 == 0 { 0.0 } else { 1.0 },
+                ))
+            },
+            color,
+        );
+    }
+
+    /// 添加8个角（第i个角的第0、1、2位分别表示X、Y、Z取较大的一侧）构成的六面体的12条棱
+    fn add_box_corners(&mut self, corner: impl Fn(usize) -> Vec3, color: [f32; 4]) {
+        // 只相差一位的两个角之间是一条棱
+        for i in 0..8 {
+            for bit in [1, 2, 4] {
+                if i & bit == 0 {
+                    self.add(corner(i), corner(i | bit), color);
+                }
+            }
+        }
+    }
+
+    /// 添加球：分别垂直于X、Y、Z轴的三个大圆
+    pub fn add_sphere(&mut self, center: Vec3, radius: f32, color: [f32; 4]) {
+        let point = |axis: usize, i: u32| {
+            let angle = i as f32 / SPHERE_SEGMENTS as f32 * std::f32::consts::TAU;
+            let (sin, cos) = angle.sin_cos();
+            let offset = match axis {
+                0 => Vec3::new(0.0, cos, sin),
+                1 => Vec3::new(cos, 0.0, sin),
+                _ => Vec3::new(cos, sin, 0.0),
+            };
+            center + offset * radius
+        };
+        for axis in 0..3 {
+            for i in 0..SPHERE_SEGMENTS {
+                self.add(point(axis, i), point(axis, i + 1), color);
+            }
+        }
+    }
+
+    /// 添加顶点法线：从变换后的顶点位置沿世界空间法线画一条长length的线段，
+    /// 颜色为法线各分量的绝对值（朝X轴为红、朝Y轴为绿、朝Z轴为蓝）
+    ///
+    /// 顶点数超过max_lines时按固定步长跳过部分顶点，使调试线的数量有上限。
+    /// 法线用transform的逆转置变换，非均匀缩放时方向也正确。
+    pub fn add_normals<V: VertexNormal>(
+        &mut self,
+        vertices: &[V],
+        transform: Mat4,
+        length: f32,
+        max_lines: usize,
+    ) {
+        let normal_matrix = Mat3::from_mat4(transform).inverse().transpose();
+        let step = vertices.len().div_ceil(max_lines.max(1)).max(1);
+        for vertex in vertices.iter().step_by(step) {
+            let position = transform.transform_point3(vertex.position().into());
+            let normal = (normal_matrix * Vec3::from(vertex.normal())).normalize_or_zero();
+            self.add(
+                position,
+                position + normal * length,
+                normal.abs().extend(1.0).to_array(),
+            );
+        }
+    }
+
+    /// 上传本帧的线段和视图投影矩阵，需在开始渲染通道之前调用
+    ///
+    /// 顶点缓冲区容量不足时按2倍（且不小于所需大小向上取2的幂）重新创建。
+    pub fn prepare(
+        &mut self,
+        ctx: &RenderContext,
+        encoder: &mut wgpu::CommandEncoder,
+        view_proj: Mat4,
+    ) {
+        let needed = self.vertices.len() as u64;
+        if needed > self.capacity {
+            self.capacity = needed.next_power_of_two().max(self.capacity * 2);
+            self.vertex_buffer = create_vertex_buffer(ctx.device, self.capacity);
+        }
+        ctx.upload(
+            encoder,
+            &self.uniform_buffer,
+            0,
+            &view_proj.to_cols_array_2d(),
+        );
+        if !self.vertices.is_empty() {
+            ctx.upload_slice(encoder, &self.vertex_buffer, 0, &self.vertices);
+        }
+        self.vertex_count = self.vertices.len() as u32;
+    }
+
+    /// 在渲染通道中绘制上一次`prepare`上传的线段
+    pub fn draw(&self, pass: &mut wgpu::RenderPass) {
+        if self.vertex_count == 0 {
+            return;
+        }
+        pass.set_pipeline(&self.pipeline);
+        pass.set_bind_group(0, &self.bind_group, &[]);
+        pass.set_vertex_buffer(0, self.vertex_buffer.slice(..));
+        pass.draw(0..self.vertex_count, 0..1);
+    }
+
+    /// 着色器文件变化时重建管线
+    pub fn shader_changed(&mut self, device: &wgpu::Device, path: &Path) {
+        if shader::depends_on(SHADER, path) {
+            let _ = self.rebuild_pipeline(device, self.sample_count);
+        }
+    }
+
+    /// 多重采样数变化时重建管线
+    pub fn sample_count_changed(
+        &mut self,
+        device: &wgpu::Device,
+        sample_count: u32,
+    ) -> Result<(), WgpuAppError> {
+        self.rebuild_pipeline(device, sample_count)
+    }
+
+    /// 用`rebuild_pipeline`按sample_count重建管线，成功后才更新多重采样数
+    fn rebuild_pipeline(
+        &mut self,
+        device: &wgpu::Device,
+        sample_count: u32,
+    ) -> Result<(), WgpuAppError> {
+        rebuild_pipeline(device, SHADER, &mut self.pipeline, |shader| {
+            create_pipeline(
+                device,
+                shader,
+                self.format,
+                &self.pipeline_layout,
+                sample_count,
+                self.cache.as_ref(),
+                self.depth,
+            )
+        })?;
+        self.sample_count = sample_count;
+        Ok(())
+    }
+}
+
+/// 创建能容纳capacity个顶点的调试线顶点缓冲区
+fn create_vertex_buffer(device: &wgpu::Device, capacity: u64) -> wgpu::Buffer {
+    device.create_buffer(&wgpu::BufferDescriptor {
+        label: Some("Debug Lines Vertex Buffer"),
+        size: capacity * std::mem::size_of::<DebugVertex>() as u64,
+        usage: wgpu::BufferUsages::VERTEX | wgpu::BufferUsages::COPY_DST,
+        mapped_at_creation: false,
+    })
+}
+
+/// 创建绘制LineList的管线：不写入深度，按depth选择深度比较方式
+fn create_pipeline(
+    device: &wgpu::Device,
+    shader: &wgpu::ShaderModule,
+    format: wgpu::TextureFormat,
+    layout: &wgpu::PipelineLayout,
+    sample_count: u32,
+    cache: Option<&wgpu::PipelineCache>,
+    depth: DebugDepth,
+) -> wgpu::RenderPipeline {
+    device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
+        label: Some("Debug Lines Pipeline"),
+        layout: Some(layout),
+        vertex: wgpu::VertexState {
+            module: shader,
+            entry_point: Some("vs"),
+            buffers: &[DebugVertex::LAYOUT],
+            compilation_options: Default::default(),
+        },
+        fragment: Some(wgpu::FragmentState {
+            module: shader,
+            entry_point: Some("fs"),
+            targets: &[Some(wgpu::ColorTargetState {
+                format,
+                blend: Some(wgpu::BlendState::ALPHA_BLENDING),
+                write_mask: wgpu::ColorWrites::ALL,
+            })],
+            compilation_options: Default::default(),
+        }),
+        primitive: wgpu::PrimitiveState {
+            topology: wgpu::PrimitiveTopology::LineList,
+            ..Default::default()
+        },
+        depth_stencil: Some(wgpu::DepthStencilState {
+            depth_write_enabled: false,
+            depth_compare: match depth {
+                DebugDepth::Test => wgpu::CompareFunction::LessEqual,
+                DebugDepth::AlwaysOnTop => wgpu::CompareFunction::Always,
+            },
+            ..DepthTexture::depth_stencil_state()
+        }),
+        multisample: wgpu::MultisampleState {
+            count: sample_count,
+            ..Default::default()
+        },
+        multiview: None,
+        cache,
+    })
+}
@@ -0,0 +1,148 @@
+use crate::{
+    BoxedScene, InitContext, RendererInit, Scene, WgpuAppError,
+    renderers::{
+        BouncingQuad, BrickCube, Clear, DepthQuads, DynamicUniformCubes, EnvironmentViewer,
+        GltfViewer, InstancedCubes, MipmapPlane, MultiDrawMeshes, NoiseFog, ObjViewer, Pentagon,
+        PulsingCubes, PushConstantCubes, RotatingTriangle, Shadertoy, SolarSystem, StorageWobble,
+        TextureArrayCubes, TexturedQuad, Triangle, UploadStress, VertexColorTriangle,
+    },
+};
+
+/// 一个可运行的演示：名称、简介和创建装箱场景的构造函数
+#[derive(Clone, Copy)]
+pub struct Demo {
+    pub name: &'static str,             // 命令行中使用的名称
+    pub description: &'static str,      // 一行简介（`--list`时显示）
+    pub init: RendererInit<BoxedScene>, // 构造函数，传给`WgpuAppBuilder::build_with`
+}
+
+/// 演示注册表：按名称查找演示，新增课程时在`builtin`中注册即可
+#[derive(Clone, Default)]
+pub struct DemoRegistry {
+    demos: Vec<Demo>,
+}
+
+impl DemoRegistry {
+    /// 未指定名称时运行的演示
+    pub const DEFAULT: &'static str = "triangle";
+
+    /// 创建空的注册表
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 包含`renderers`中所有场景的注册表
+    pub fn builtin() -> Self {
+        Self::new()
+            .register::<RotatingTriangle>("triangle", "Rotating triangle, scroll to zoom")
+            .register::<Triangle>("static-triangle", "Static triangle defined in the shader")
+            .register::<VertexColorTriangle>(
+                "vertex-color",
+                "Triangle with per-vertex colors from a vertex buffer",
+            )
+            .register::<Pentagon>("pentagon", "Pentagon drawn with an index buffer")
+            .register::<DepthQuads>("depth", "Two overlapping quads sorted by the depth buffer")
+            .register::<InstancedCubes>(
+                "instancing",
+                "100x100 rotating cubes in one instanced draw call, drag to orbit, P toggles orthographic",
+            )
+            .register::<UploadStress>(
+                "upload-stress",
+                "Instanced cubes rewriting ~1 MB per frame, U toggles uploader/write_buffer",
+            )
+            .register::<PulsingCubes>(
+                "indirect",
+                "Cube grid whose instance count is pulsed through an indirect draw buffer",
+            )
+            .register::<MultiDrawMeshes>(
+                "multi-draw",
+                "3025 shapes from one buffer via multi_draw_indexed_indirect(_count)",
+            )
+            .register::<StorageWobble>(
+                "storage",
+                "Grid displaced by heights a compute shader writes to a storage buffer, +/- resolution",
+            )
+            .register::<PushConstantCubes>(
+                "push-constants",
+                "One cube mesh drawn 16 times with per-draw colors from push constants",
+            )
+            .register::<SolarSystem>(
+                "solar-system",
+                "Sun, planet and moon as nested scene graph nodes, drag to orbit",
+            )
+            .register::<DynamicUniformCubes>(
+                "dynamic-uniform",
+                "1000 cubes drawn one by one from a dynamic-offset uniform buffer, K compares",
+            )
+            .register::<TextureArrayCubes>(
+                "texture-array",
+                "1000 cubes with 16 textures from one texture array, one bind group and one instanced draw",
+            )
+            .register::<ObjViewer>(
+                "model",
+                "Rotating OBJ model in a skybox, drop an .obj file or a cross-layout sky image",
+            )
+            .register::<GltfViewer>(
+                "gltf",
+                "Rotating glTF scene, drop a .gltf or .glb file to load it",
+            )
+            .register::<BrickCube>(
+                "normal-map",
+                "Rotating brick cube lit by a moving point light, B toggles normal mapping",
+            )
+            .register::<TexturedQuad>(
+                "texture",
+                "Quad textured with an embedded PNG, drop a PNG/JPEG/KTX2 to replace it, F toggles filtering",
+            )
+            .register::<MipmapPlane>(
+                "mipmaps",
+                "Checkerboard plane receding to the horizon, F toggles generated mipmaps, A cycles anisotropy",
+            )
+            .register::<NoiseFog>(
+                "fog",
+                "Raymarched fog from a 64³ noise volume made by a compute shader, R reseeds, G toggles CPU generation",
+            )
+            .register::<EnvironmentViewer>(
+                "environment",
+                "HDR panorama as a tonemapped skybox, drop a .hdr file, T cycles tonemapping, -/= exposure",
+            )
+            .register::<Shadertoy>(
+                "shadertoy",
+                "Fullscreen shader animated only by the global time/resolution/mouse uniforms",
+            )
+            .register::<BouncingQuad>("bouncing", "Quad moved by fixed-timestep updates")
+            .register::<Clear>("clear", "Only clears the background")
+    }
+
+    /// 注册场景S，名称重复时后注册的覆盖先注册的
+    pub fn register<S: Scene + wgpu::WasmNotSend + 'static>(
+        mut self,
+        name: &'static str,
+        description: &'static str,
+    ) -> Self {
+        self.demos.retain(|demo| demo.name != name);
+        self.demos.push(Demo {
+            name,
+            description,
+            init: init_boxed::<S>,
+        });
+        self
+    }
+
+    /// 按名称查找演示
+    pub fn get(&self, name: &str) -> Option<&Demo> {
+        self.demos.iter().find(|demo| demo.name == name)
+    }
+
+    /// 按注册顺序遍历所有演示
+    pub fn iter(&self) -> impl Iterator<Item = &Demo> {
+        self.demos.iter()
+    }
+}
+
+/// 创建场景S并装箱
+fn init_boxed<S: Scene + wgpu::WasmNotSend + 'static>(
+    ctx: &InitContext,
+) -> Result<BoxedScene, WgpuAppError> {
+    S::init(ctx).map(|scene| Box::new(scene) as BoxedScene)
+}
@@ -0,0 +1,62 @@
+/// 深度纹理：尺寸和多重采样数需与颜色附件一致，窗口大小变化时重新创建
+#[derive(Debug)]
+pub struct DepthTexture {
+    pub texture: wgpu::Texture,  // 深度纹理
+    pub view: wgpu::TextureView, // 深度纹理视图（用作深度附件）
+}
+
+impl DepthTexture {
+    /// 深度格式
+    pub const FORMAT: wgpu::TextureFormat = wgpu::TextureFormat::Depth32Float;
+
+    pub fn new(
+        device: &wgpu::Device,
+        label: Option<&str>,
+        width: u32,
+        height: u32,
+        sample_count: u32,
+    ) -> Self {
+        let texture = device.create_texture(&wgpu::TextureDescriptor {
+            label,
+            size: wgpu::Extent3d {
+                width: width.max(1),
+                height: height.max(1),
+                depth_or_array_layers: 1,
+            },
+            mip_level_count: 1,
+            sample_count,
+            dimension: wgpu::TextureDimension::D2,
+            format: Self::FORMAT,
+            usage: wgpu::TextureUsages::RENDER_ATTACHMENT,
+            view_formats: &[],
+        });
+        let view = texture.create_view(&wgpu::TextureViewDescriptor {
+            label,
+            ..Default::default()
+        });
+        Self { texture, view }
+    }
+
+    /// 渲染通道的深度附件：每帧清除为1.0（最远）并保存结果
+    pub fn attachment(&self) -> wgpu::RenderPassDepthStencilAttachment<'_> {
+        wgpu::RenderPassDepthStencilAttachment {
+            view: &self.view,
+            depth_ops: Some(wgpu::Operations {
+                load: wgpu::LoadOp::Clear(1.0),
+                store: wgpu::StoreOp::Store,
+            }),
+            stencil_ops: None,
+        }
+    }
+
+    /// 与深度附件匹配的管线深度状态（LessEqual比较，写入深度）
+    pub fn depth_stencil_state() -> wgpu::DepthStencilState {
+        wgpu::DepthStencilState {
+            format: Self::FORMAT,
+            depth_write_enabled: true,
+            depth_compare: wgpu::CompareFunction::LessEqual,
+            stencil: Default::default(),
+            bias: Default::default(),
+        }
+    }
+}
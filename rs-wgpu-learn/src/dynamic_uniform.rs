@@ -0,0 +1,203 @@
+use crate::binding::{BindGroupBuilder, BindGroupLayoutBuilder, BindingLayout};
+use log::debug;
+use std::{marker::PhantomData, num::NonZeroU64};
+
+/// 带动态偏移的uniform缓冲区：把每个对象的数据按`min_uniform_buffer_offset_alignment`对齐依次排列，
+/// 所有对象共用一个缓冲区和一个绑定组，绘制时用动态偏移选择对象
+///
+/// 每帧用`write`从对象数据的切片整体写入一次，绘制第i个对象前调用
+/// `pass.set_bind_group(group, buffer.bind_group(), &[buffer.offset(i)])`。
+/// 对象数超过容量时按两倍扩容，重新创建缓冲区和绑定组（布局不变，管线不需要重建）。
+/// 也可以用`write_slot`逐个写入固定容量中的槽位（如推送常量的回退方式）。
+#[derive(Debug)]
+pub struct DynamicUniformBuffer<T> {
+    label: String,
+    layout: BindingLayout, // 一个带动态偏移的uniform绑定
+    buffer: wgpu::Buffer,  // capacity个按对齐要求排列的槽位
+    bind_group: wgpu::BindGroup,
+    stride: u32,      // 槽位间距（按min_uniform_buffer_offset_alignment对齐）
+    capacity: u32,    // 槽位数
+    len: u32,         // 上一次write写入的对象数
+    staging: Vec<u8>, // 按槽位排列好的CPU端数据，避免每帧重新分配
+    _marker: PhantomData<T>,
+}
+
+impl<T: bytemuck::Pod> DynamicUniformBuffer<T> {
+    /// 创建容量为capacity（至少1）个对象的缓冲区，visibility为使用数据的着色器阶段
+    pub fn new(
+        device: &wgpu::Device,
+        label: &str,
+        visibility: wgpu::ShaderStages,
+        capacity: u32,
+    ) -> Self {
+        let alignment = device.limits().min_uniform_buffer_offset_alignment as u64;
+        let stride = Self::size().next_multiple_of(alignment) as u32;
+        let layout = Self::create_layout(device, label, visibility);
+        let capacity = capacity.max(1);
+        let (buffer, bind_group) = Self::create(device, label, &layout, stride, capacity);
+        Self {
+            label: label.to_string(),
+            layout,
+            buffer,
+            bind_group,
+            stride,
+            capacity,
+            len: 0,
+            staging: Vec::new(),
+            _marker: PhantomData,
+        }
+    }
+
+    /// 创建与`DynamicUniformBuffer<T>`相同的绑定组布局（一个带动态偏移的uniform绑定）
+    ///
+    /// wgpu对内容相同的布局去重，渲染器可以在拿到缓冲区实例之前用它创建管线布局。
+    pub fn create_layout(
+        device: &wgpu::Device,
+        label: &str,
+        visibility: wgpu::ShaderStages,
+    ) -> BindingLayout {
+        BindGroupLayoutBuilder::new()
+            .label(&format!("{label} Dynamic Uniform Layout"))
+            .entry(
+                visibility,
+                wgpu::BindingType::Buffer {
+                    ty: wgpu::BufferBindingType::Uniform,
+                    has_dynamic_offset: true,
+                    min_binding_size: NonZeroU64::new(Self::size()),
+                },
+            )
+            .build(device)
+    }
+
+    /// 每个对象的字节数
+    fn size() -> u64 {
+        std::mem::size_of::<T>() as u64
+    }
+
+    /// 创建capacity个槽位的缓冲区和只看到一个槽位的绑定组
+    fn create(
+        device: &wgpu::Device,
+        label: &str,
+        layout: &BindingLayout,
+        stride: u32,
+        capacity: u32,
+    ) -> (wgpu::Buffer, wgpu::BindGroup) {
+        let buffer = device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some(&format!("{label} Dynamic Uniform Buffer")),
+            size: stride as u64 * capacity as u64,
+            usage: wgpu::BufferUsages::UNIFORM | wgpu::BufferUsages::COPY_DST,
+            mapped_at_creation: false,
+        });
+        // 每次绑定只看到一个槽位，动态偏移选择槽位
+        let bind_group = BindGroupBuilder::new(layout)
+            .label(&format!("{label} Dynamic Uniform Bind Group"))
+            .resource(wgpu::BindingResource::Buffer(wgpu::BufferBinding {
+                buffer: &buffer,
+                offset: 0,
+                size: NonZeroU64::new(Self::size()),
+            }))
+            .build(device);
+        (buffer, bind_group)
+    }
+
+    /// 绑定组布局，用于创建管线布局（扩容时不变）
+    pub fn layout(&self) -> &BindingLayout {
+        &self.layout
+    }
+
+    /// 绑定组（扩容后会变化，每帧绘制时重新获取）
+    pub fn bind_group(&self) -> &wgpu::BindGroup {
+        &self.bind_group
+    }
+
+    /// 底层缓冲区
+    pub fn buffer(&self) -> &wgpu::Buffer {
+        &self.buffer
+    }
+
+    /// 槽位间距（字节）
+    pub fn stride(&self) -> u32 {
+        self.stride
+    }
+
+    /// 不扩容时最多容纳的对象数
+    pub fn capacity(&self) -> u32 {
+        self.capacity
+    }
+
+    /// 上一次`write`写入的对象数
+    pub fn len(&self) -> u32 {
+        self.len
+    }
+
+    /// 上一次`write`是否没有写入对象
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// 第index个对象的动态偏移
+    ///
+    /// # Panics
+    /// index不小于上一次`write`写入的对象数时panic。
+    pub fn offset(&self, index: u32) -> u32 {
+        assert!(
+            index < self.len,
+            "object {index} is out of range for {} ({} objects written)",
+            self.label,
+            self.len
+        );
+        index * self.stride
+    }
+
+    /// 把所有对象的数据按槽位排列后用一次`queue.write_buffer`写入，容量不足时先扩容
+    ///
+    /// 返回是否扩容（此时缓冲区和绑定组已重新创建）。
+    pub fn write(&mut self, device: &wgpu::Device, queue: &wgpu::Queue, objects: &[T]) -> bool {
+        let len = objects.len() as u32;
+        let grown = len > self.capacity;
+        if grown {
+            self.capacity = len.next_power_of_two().max(self.capacity * 2);
+            (self.buffer, self.bind_group) = Self::create(
+                device,
+                &self.label,
+                &self.layout,
+                self.stride,
+                self.capacity,
+            );
+            debug!(
+                "Grew {} dynamic uniform buffer to {} objects",
+                self.label, self.capacity
+            );
+        }
+        self.len = len;
+        if objects.is_empty() {
+            return grown;
+        }
+
+        // 槽位之间的填充保持为0
+        let stride = self.stride as usize;
+        self.staging.clear();
+        self.staging.resize(stride * objects.len(), 0);
+        for (slot, object) in self.staging.chunks_exact_mut(stride).zip(objects) {
+            slot[..Self::size() as usize].copy_from_slice(bytemuck::bytes_of(object));
+        }
+        queue.write_buffer(&self.buffer, 0, &self.staging);
+        grown
+    }
+
+    /// 只把object写入第index个槽位，返回绑定时使用的动态偏移（不扩容，也不改变`len`）
+    ///
+    /// # Panics
+    /// index不小于容量时panic。
+    pub fn write_slot(&self, queue: &wgpu::Queue, index: u32, object: &T) -> u32 {
+        assert!(
+            index < self.capacity,
+            "slot {index} is out of range for {} ({} slots)",
+            self.label,
+            self.capacity
+        );
+        let offset = index * self.stride;
+        queue.write_buffer(&self.buffer, offset as u64, bytemuck::bytes_of(object));
+        offset
+    }
+}
@@ -0,0 +1,158 @@
+use std::path::PathBuf;
+use thiserror::Error;
+
+/// WgpuApp的错误类型，便于调用方区分不同的失败原因
+#[derive(Debug, Error)]
+pub enum WgpuAppError {
+    /// 在指定后端上找不到可用的图形适配器
+    #[error("No suitable graphics adapter found for backends {0:?}")]
+    NoAdapter(wgpu::Backends),
+
+    /// 创建窗口表面失败
+    #[error("Failed to create surface: {0}")]
+    CreateSurface(#[from] wgpu::CreateSurfaceError),
+
+    /// 请求设备失败
+    #[error("Failed to request device: {0}")]
+    RequestDevice(#[from] wgpu::RequestDeviceError),
+
+    /// 适配器不支持必需的设备特性
+    #[error("Adapter does not support required features {0:?}")]
+    MissingFeatures(wgpu::Features),
+
+    /// 获取表面纹理时发生的致命错误
+    #[error("Surface error: {0}")]
+    Surface(#[from] wgpu::SurfaceError),
+
+    /// 表面不支持请求的呈现模式
+    #[error("Present mode {0:?} is not supported by the surface")]
+    UnsupportedPresentMode(wgpu::PresentMode),
+
+    /// 表面格式不支持请求的多重采样数
+    #[error(
+        "Sample count {count} is not supported by the surface format (supported: {supported:?})"
+    )]
+    UnsupportedSampleCount { count: u32, supported: Vec<u32> },
+
+    /// 适配器不支持某种纹理格式的用途（如`R16Float`的存储纹理）
+    #[error("Texture format {format:?} does not support {usage:?} on this adapter")]
+    UnsupportedTextureUsage {
+        format: wgpu::TextureFormat,
+        usage: wgpu::TextureUsages,
+    },
+
+    /// 读取着色器文件失败
+    #[error("Failed to load shader {}: {source}", path.display())]
+    ShaderLoad {
+        path: PathBuf,
+        source: std::io::Error,
+    },
+
+    /// 着色器解析或验证失败
+    #[error("Shader compilation failed:\n{0}")]
+    ShaderCompile(#[from] crate::shader::ShaderError),
+
+    /// GPU验证错误（着色器或管线无效等）
+    #[error("Validation error: {0}")]
+    Validation(String),
+
+    /// 读回纹理数据时映射缓冲区失败
+    #[error("Failed to map readback buffer: {0}")]
+    ReadPixels(#[from] wgpu::BufferAsyncError),
+
+    /// 截图不支持当前表面格式
+    #[error("Cannot capture a frame in surface format {0:?}")]
+    UnsupportedCaptureFormat(wgpu::TextureFormat),
+
+    /// 保存截图失败
+    #[error("Failed to save screenshot: {0}")]
+    Screenshot(#[from] image::ImageError),
+
+    /// 解码图片（PNG、JPEG）失败
+    #[error("Failed to decode image: {0}")]
+    ImageDecode(image::ImageError),
+
+    /// 读取纹理文件失败
+    #[error("Failed to read texture {}: {source}", path.display())]
+    TextureRead {
+        path: PathBuf,
+        source: std::io::Error,
+    },
+
+    /// KTX2纹理无效或使用了不支持的功能
+    #[error("Failed to load KTX2 texture: {0}")]
+    Ktx2(String),
+
+    /// 立方体贴图的面尺寸不一致或十字布局图片的宽高比不对
+    #[error("Invalid cube map: {0}")]
+    InvalidCubeMap(String),
+
+    /// 采样器配置无效（如各向异性过滤与最近邻过滤同时使用）
+    #[error("Invalid sampler: {0}")]
+    InvalidSampler(String),
+
+    /// GPU设备丢失（驱动重置、GPU被移除等），需要重新初始化
+    #[error("GPU device lost")]
+    DeviceLost,
+
+    /// 渲染器尚未创建（第一帧之后才创建）
+    #[error("Renderer is not ready yet")]
+    NotReady,
+
+    /// 适配器不满足限制档位要求的资源限制
+    #[error("Adapter does not support the requested limits: {0}")]
+    UnsupportedLimits(String),
+
+    /// 纹理尺寸超出设备限制
+    #[error("Texture size {width}x{height} exceeds the device limit of {max}")]
+    TextureTooLarge { width: u32, height: u32, max: u32 },
+
+    /// 三维纹理尺寸超出设备限制
+    #[error("Volume texture size {width}x{height}x{depth} exceeds the device limit of {max}")]
+    VolumeTooLarge {
+        width: u32,
+        height: u32,
+        depth: u32,
+        max: u32,
+    },
+
+    /// 纹理图集的剩余空间放不下一张图片
+    #[error("Atlas entry '{name}' ({width}x{height}) does not fit in the {size}x{size} atlas")]
+    AtlasFull {
+        name: String,
+        width: u32,
+        height: u32,
+        size: u32,
+    },
+
+    /// 二维数组纹理的层数超出设备限制
+    #[error("Texture array with {layers} layers exceeds the device limit of {max}")]
+    TooManyArrayLayers { layers: u32, max: u32 },
+
+    /// 二维数组纹理没有图片，或各层图片尺寸不一致
+    #[error("Invalid texture array: {0}")]
+    InvalidTextureArray(String),
+
+    /// 上传的偏移量或长度不是`COPY_BUFFER_ALIGNMENT`的倍数，或写入范围超出目标缓冲区
+    #[error(
+        "Cannot upload {len} bytes at offset {offset} into a {size}-byte buffer \
+         (offset and length must be multiples of 4)"
+    )]
+    InvalidUpload { offset: u64, len: u64, size: u64 },
+
+    /// 表面与适配器不兼容，无法生成表面配置
+    #[error("Surface is not supported by the adapter")]
+    NoCompatibleConfig,
+
+    /// 加载OBJ模型失败
+    #[error("Failed to load model: {0}")]
+    ObjLoad(#[from] crate::assets::obj::ObjError),
+
+    /// 加载glTF场景失败
+    #[error("Failed to load scene: {0}")]
+    GltfLoad(#[from] crate::assets::gltf::GltfError),
+
+    /// 装箱的场景没有通过`WgpuAppBuilder::build_with`提供构造函数
+    #[error("Boxed scenes must be created with WgpuAppBuilder::build_with")]
+    NoRendererInit,
+}
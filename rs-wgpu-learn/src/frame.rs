@@ -0,0 +1,109 @@
+use crate::{DebugLabels, debug, upload::Uploader};
+
+/// 一帧的渲染目标：表面纹理、默认纹理视图和命令编码器
+///
+/// 由`WgpuApp::begin_frame`创建，记录完命令后调用`finish`提交并呈现。
+pub struct Frame {
+    pub surface_texture: wgpu::SurfaceTexture, // 当前帧的表面纹理
+    pub view: wgpu::TextureView,               // 表面纹理的默认视图
+    pub encoder: wgpu::CommandEncoder,         // 本帧的命令编码器
+    pub msaa_view: Option<wgpu::TextureView>,  // 多重采样颜色附件（启用MSAA时存在）
+    pub labels: DebugLabels,                   // 调试标签
+    pub index: u64, // 帧序号（由begin_frame递增，传给RenderContext::frame_index）
+}
+
+impl Frame {
+    pub(crate) fn new(
+        device: &wgpu::Device,
+        surface_texture: wgpu::SurfaceTexture,
+        index: u64,
+        msaa_view: Option<wgpu::TextureView>,
+        labels: DebugLabels,
+    ) -> Self {
+        let view = surface_texture
+            .texture
+            .create_view(&wgpu::TextureViewDescriptor {
+                label: labels.label("Surface View").as_deref(),
+                ..Default::default()
+            });
+        let encoder = device.create_command_encoder(&wgpu::CommandEncoderDescriptor {
+            label: labels.label("Frame Encoder").as_deref(),
+        });
+        Self {
+            surface_texture,
+            view,
+            encoder,
+            msaa_view,
+            labels,
+            index,
+        }
+    }
+
+    /// 开始一个调试分组（启用`no-debug-labels`特性时为空操作）
+    pub fn push_debug_group(&mut self, name: &str) {
+        debug::push_debug_group(&mut self.encoder, name);
+    }
+
+    /// 插入一个调试标记
+    pub fn insert_debug_marker(&mut self, name: &str) {
+        debug::insert_debug_marker(&mut self.encoder, name);
+    }
+
+    /// 结束最近的调试分组
+    pub fn pop_debug_group(&mut self) {
+        debug::pop_debug_group(&mut self.encoder);
+    }
+
+    /// 开始一个以表面为唯一颜色附件的渲染通道
+    ///
+    /// clear为None时保留表面原有内容（LoadOp::Load）；启用MSAA时绘制到多重采样纹理并解析到表面。
+    pub fn render_pass(&mut self, clear: Option<wgpu::Color>) -> wgpu::RenderPass<'_> {
+        let load = match clear {
+            Some(color) => wgpu::LoadOp::Clear(color),
+            None => wgpu::LoadOp::Load,
+        };
+        let ops = wgpu::Operations {
+            load,
+            store: wgpu::StoreOp::Store,
+        };
+        self.encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
+            label: self.labels.label("Frame Render Pass").as_deref(),
+            color_attachments: &[Some(color_attachment(
+                &self.view,
+                self.msaa_view.as_ref(),
+                ops,
+            ))],
+            depth_stencil_attachment: None,
+            timestamp_writes: None,
+            occlusion_query_set: None,
+        })
+    }
+
+    /// 结束上传器本帧的写入，提交命令到队列并呈现，然后回收上传器的暂存块
+    pub fn finish(self, queue: &wgpu::Queue, uploader: &mut Uploader) {
+        uploader.finish();
+        queue.submit(std::iter::once(self.encoder.finish()));
+        self.surface_texture.present();
+        uploader.recall();
+    }
+}
+
+/// 颜色附件：启用MSAA时绘制到多重采样纹理，并以表面视图作为解析目标
+pub(crate) fn color_attachment<'a>(
+    view: &'a wgpu::TextureView,
+    msaa_view: Option<&'a wgpu::TextureView>,
+    ops: wgpu::Operations<wgpu::Color>,
+) -> wgpu::RenderPassColorAttachment<'a> {
+    match msaa_view {
+        Some(msaa_view) => wgpu::RenderPassColorAttachment {
+            view: msaa_view,
+            resolve_target: Some(view),
+            ops,
+        },
+        None => wgpu::RenderPassColorAttachment {
+            view,
+            resolve_target: None,
+            ops,
+        },
+    }
+}
@@ -0,0 +1,264 @@
+use log::warn;
+use std::time::Duration;
+use web_time::Instant;
+
+/// 单帧耗时的上限：窗口被拖动等原因导致事件循环停顿后，避免动画一次跳过太多
+const MAX_DELTA: Duration = Duration::from_millis(100);
+
+/// 统计FPS的时间窗口
+const FPS_WINDOW: Duration = Duration::from_secs(1);
+
+/// 时间倍率的范围（慢放到快进）
+pub const TIME_SCALE_RANGE: (f32, f32) = (0.125, 4.0);
+
+/// 帧计时器：记录每帧耗时、总运行时间和帧数，并按秒统计平滑后的FPS
+///
+/// 暂停后dt为零、运行时间不再前进，但FPS照常统计（渲染并没有停止）。
+/// 时间倍率小于1时慢放、大于1时快进，dt和运行时间按倍率缩放（单步不受影响）。
+#[derive(Debug)]
+pub struct FrameTimer {
+    last_tick: Instant,             // 上一次tick的时间
+    dt: Duration,                   // 本帧耗时（已截断到MAX_DELTA，暂停时为零）
+    elapsed: Duration,              // 累计运行时间（各帧dt之和）
+    frame_count: u64,               // 累计帧数
+    window_start: Instant,          // 当前FPS统计窗口的开始时间
+    window_frames: u32,             // 当前统计窗口内的帧数
+    fps: f64,                       // 上一个统计窗口的平均FPS
+    fps_updated: bool,              // 本帧是否刚刚更新了FPS
+    paused: bool,                   // 是否暂停
+    time_scale: f32,                // 时间倍率（1为正常速度）
+    pending_step: Option<Duration>, // 暂停时下一帧单步前进的时间
+}
+
+impl Default for FrameTimer {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl FrameTimer {
+    pub fn new() -> Self {
+        let now = Instant::now();
+        Self {
+            last_tick: now,
+            dt: Duration::ZERO,
+            elapsed: Duration::ZERO,
+            frame_count: 0,
+            window_start: now,
+            window_frames: 0,
+            fps: 0.0,
+            fps_updated: false,
+            paused: false,
+            time_scale: 1.0,
+            pending_step: None,
+        }
+    }
+
+    /// 开始新的一帧，返回距上一帧的时间（超过100ms时截断），暂停时返回零或单步的时间
+    pub fn tick(&mut self) -> Duration {
+        let now = Instant::now();
+        let real_dt = (now - self.last_tick).min(MAX_DELTA);
+        self.dt = match self.pending_step.take() {
+            Some(step) => step,
+            None if self.paused => Duration::ZERO,
+            None => real_dt.mul_f32(self.time_scale),
+        };
+        self.last_tick = now;
+        self.elapsed += self.dt;
+        self.frame_count += 1;
+
+        // 每满一秒用窗口内的帧数计算一次FPS
+        self.window_frames += 1;
+        let window = now - self.window_start;
+        self.fps_updated = window >= FPS_WINDOW;
+        if self.fps_updated {
+            self.fps = self.window_frames as f64 / window.as_secs_f64();
+            self.window_start = now;
+            self.window_frames = 0;
+        }
+        self.dt
+    }
+
+    /// 暂停或继续，继续后从当前时刻开始计时（不会补上暂停期间的时间）
+    pub fn set_paused(&mut self, paused: bool) {
+        self.paused = paused;
+        self.pending_step = None;
+    }
+
+    /// 是否暂停
+    pub fn is_paused(&self) -> bool {
+        self.paused
+    }
+
+    /// 设置时间倍率（限制在`TIME_SCALE_RANGE`内）
+    pub fn set_time_scale(&mut self, time_scale: f32) {
+        self.time_scale = time_scale.clamp(TIME_SCALE_RANGE.0, TIME_SCALE_RANGE.1);
+    }
+
+    /// 时间倍率
+    pub fn time_scale(&self) -> f32 {
+        self.time_scale
+    }
+
+    /// 暂停时让下一帧前进step（通常为一个固定更新步长），之后保持暂停；未暂停时无效
+    pub fn step(&mut self, step: Duration) {
+        if self.paused {
+            self.pending_step = Some(step);
+        }
+    }
+
+    /// 本帧耗时
+    pub fn dt(&self) -> Duration {
+        self.dt
+    }
+
+    /// 累计运行时间
+    pub fn elapsed(&self) -> Duration {
+        self.elapsed
+    }
+
+    /// 累计帧数
+    pub fn frame_count(&self) -> u64 {
+        self.frame_count
+    }
+
+    /// 最近一秒的平均FPS
+    pub fn fps(&self) -> f64 {
+        self.fps
+    }
+
+    /// 最近一秒的平均帧耗时
+    pub fn average_frame_time(&self) -> Duration {
+        if self.fps > 0.0 {
+            Duration::from_secs_f64(1.0 / self.fps)
+        } else {
+            Duration::ZERO
+        }
+    }
+
+    /// 本帧是否刚刚更新了FPS（每秒一次，适合刷新标题或打印统计信息）
+    pub fn fps_updated(&self) -> bool {
+        self.fps_updated
+    }
+}
+
+/// 固定时间步长：累计每帧耗时，按固定步长执行零到多次更新，剩余时间作为渲染插值系数
+///
+/// 每帧最多执行`max_steps`次更新，超出的积压时间会被丢弃，避免更新越来越慢的恶性循环。
+#[derive(Debug, Clone)]
+pub struct FixedTimestep {
+    pub step: Duration,    // 固定步长
+    pub max_steps: u32,    // 每帧最多执行的更新次数
+    accumulator: Duration, // 尚未消耗的时间
+}
+
+impl FixedTimestep {
+    /// 默认每帧最多执行的更新次数
+    pub const DEFAULT_MAX_STEPS: u32 = 5;
+
+    /// 最小步长：步长为零时每帧都会执行满`max_steps`次更新，插值系数也会除以零
+    pub const MIN_STEP: Duration = Duration::from_micros(100);
+
+    /// 步长小于`MIN_STEP`（包括零）时使用`MIN_STEP`
+    pub fn new(step: Duration) -> Self {
+        if step < Self::MIN_STEP {
+            warn!(
+                "Fixed timestep {step:?} is too small, using {:?}",
+                Self::MIN_STEP
+            );
+        }
+        Self {
+            step: step.max(Self::MIN_STEP),
+            max_steps: Self::DEFAULT_MAX_STEPS,
+            accumulator: Duration::ZERO,
+        }
+    }
+
+    /// 以指定频率（Hz）更新的固定步长，频率不是有限正数时使用默认的60Hz
+    pub fn from_hz(hz: f64) -> Self {
+        let step = (hz.is_finite() && hz > 0.0)
+            .then(|| Duration::try_from_secs_f64(1.0 / hz).ok())
+            .flatten();
+        match step {
+            Some(step) => Self::new(step),
+            None => {
+                warn!("Invalid fixed update rate {hz} Hz, using 60 Hz");
+                Self::default()
+            }
+        }
+    }
+
+    /// 实际使用的步长（`step`是公开字段，可能在创建后被改为零）
+    fn effective_step(&self) -> Duration {
+        self.step.max(Self::MIN_STEP)
+    }
+
+    /// 累计本帧耗时，返回本帧需要执行的固定更新次数
+    pub fn advance(&mut self, dt: Duration) -> u32 {
+        let step = self.effective_step();
+        self.accumulator += dt;
+        let mut steps = 0;
+        while self.accumulator >= step && steps < self.max_steps {
+            self.accumulator -= step;
+            steps += 1;
+        }
+        // 达到次数上限仍有积压时只保留不足一步的部分
+        if self.accumulator >= step {
+            let remainder = self.accumulator.as_nanos() % step.as_nanos();
+            self.accumulator = Duration::from_nanos(remainder as u64);
+        }
+        steps
+    }
+
+    /// 渲染插值系数（0..1）：当前时刻位于上一次和下一次固定更新之间的比例
+    pub fn alpha(&self) -> f32 {
+        (self.accumulator.as_secs_f64() / self.effective_step().as_secs_f64()) as f32
+    }
+}
+
+impl Default for FixedTimestep {
+    /// 默认以60Hz更新
+    fn default() -> Self {
+        Self::from_hz(60.0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn zero_step_is_clamped() {
+        let mut timestep = FixedTimestep::new(Duration::ZERO);
+        assert_eq!(timestep.step, FixedTimestep::MIN_STEP);
+        let steps = timestep.advance(Duration::from_millis(16));
+        assert_eq!(steps, FixedTimestep::DEFAULT_MAX_STEPS);
+        assert!(timestep.alpha().is_finite());
+    }
+
+    #[test]
+    fn step_zeroed_after_creation_does_not_panic() {
+        let mut timestep = FixedTimestep::from_hz(60.0);
+        timestep.step = Duration::ZERO;
+        timestep.advance(Duration::from_millis(16));
+        assert!((0.0..1.0).contains(&timestep.alpha()));
+    }
+
+    #[test]
+    fn invalid_rates_fall_back_to_the_default() {
+        for hz in [0.0, -30.0, f64::NAN, f64::INFINITY, 1e-300] {
+            assert_eq!(
+                FixedTimestep::from_hz(hz).step,
+                FixedTimestep::default().step,
+                "{hz} Hz"
+            );
+        }
+    }
+
+    #[test]
+    fn advance_keeps_the_remainder_for_interpolation() {
+        let mut timestep = FixedTimestep::new(Duration::from_millis(10));
+        assert_eq!(timestep.advance(Duration::from_millis(25)), 2);
+        assert!((timestep.alpha() - 0.5).abs() < 1e-6);
+    }
+}
@@ -0,0 +1,87 @@
+use crate::{DynamicUniformBuffer, binding::BindingLayout};
+
+/// 默认的在途帧数
+pub const DEFAULT_FRAMES_IN_FLIGHT: u32 = 2;
+
+/// 按帧轮换的uniform块：同一个缓冲区中放N份（N为在途帧数）数据，每帧写入帧序号对应的那一份，
+/// 绑定时用动态偏移选择它
+///
+/// 每个uniform块（如相机、场景时间）各用一个`FrameUniforms`。写入本帧的槽位不会覆盖GPU可能仍在读取的上一帧数据；
+/// 用`queue.write_buffer`写入时wgpu本身已保证顺序，这里的轮换主要用于对比和将来改为映射写入。
+/// 每帧调用`write(queue, ctx.frame_index, &data)`，把返回的偏移传给`set_bind_group`。
+/// 底层是容量为在途帧数的`DynamicUniformBuffer`，用`write_slot`写入本帧的槽位。
+#[derive(Debug)]
+pub struct FrameUniforms<T> {
+    uniforms: DynamicUniformBuffer<T>, // frames_in_flight个槽位，每帧写入其中一个
+}
+
+impl<T: bytemuck::Pod> FrameUniforms<T> {
+    /// 创建`DEFAULT_FRAMES_IN_FLIGHT`个槽位的uniform块，visibility为使用数据的着色器阶段
+    pub fn new(device: &wgpu::Device, label: &str, visibility: wgpu::ShaderStages) -> Self {
+        Self::with_frames_in_flight(device, label, visibility, DEFAULT_FRAMES_IN_FLIGHT)
+    }
+
+    /// 创建frames_in_flight（至少1）个槽位的uniform块
+    ///
+    /// 槽位数不影响绑定组布局，不同槽位数的`FrameUniforms`可以用于同一条管线。
+    pub fn with_frames_in_flight(
+        device: &wgpu::Device,
+        label: &str,
+        visibility: wgpu::ShaderStages,
+        frames_in_flight: u32,
+    ) -> Self {
+        Self {
+            uniforms: DynamicUniformBuffer::new(
+                device,
+                &format!("{label} Frame"),
+                visibility,
+                frames_in_flight,
+            ),
+        }
+    }
+
+    /// 创建与`FrameUniforms<T>`相同的绑定组布局（见`DynamicUniformBuffer::create_layout`）
+    pub fn create_layout(
+        device: &wgpu::Device,
+        label: &str,
+        visibility: wgpu::ShaderStages,
+    ) -> BindingLayout {
+        DynamicUniformBuffer::<T>::create_layout(device, &format!("{label} Frame"), visibility)
+    }
+
+    /// 绑定组布局，用于创建管线布局
+    pub fn layout(&self) -> &BindingLayout {
+        self.uniforms.layout()
+    }
+
+    /// 绑定组
+    pub fn bind_group(&self) -> &wgpu::BindGroup {
+        self.uniforms.bind_group()
+    }
+
+    /// 底层缓冲区
+    pub fn buffer(&self) -> &wgpu::Buffer {
+        self.uniforms.buffer()
+    }
+
+    /// 槽位数（在途帧数）
+    pub fn frames_in_flight(&self) -> u32 {
+        self.uniforms.capacity()
+    }
+
+    /// 帧序号对应的槽位
+    pub fn slot(&self, frame_index: u64) -> u32 {
+        (frame_index % self.frames_in_flight() as u64) as u32
+    }
+
+    /// 帧序号对应槽位的动态偏移
+    pub fn offset(&self, frame_index: u64) -> u32 {
+        self.slot(frame_index) * self.uniforms.stride()
+    }
+
+    /// 把data写入帧序号对应的槽位，返回绑定时使用的动态偏移
+    pub fn write(&self, queue: &wgpu::Queue, frame_index: u64, data: &T) -> u32 {
+        self.uniforms
+            .write_slot(queue, self.slot(frame_index), data)
+    }
+}
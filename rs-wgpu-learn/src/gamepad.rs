@@ -0,0 +1,117 @@
+use crate::{GamepadAxes, InputState};
+use gilrs::{Axis, Button, EventType, GamepadId, Gilrs};
+use log::{info, warn};
+
+/// 手柄输入：每帧轮询gilrs的事件，把最近操作过的手柄的摇杆和扳机写入`InputState`
+///
+/// 多个手柄同时连接时只跟随最近一次有输入的那个；手柄拔出后输入归零，重新插入即可继续使用。
+pub struct GamepadInput {
+    gilrs: Gilrs,              // gilrs实例（负责手柄的热插拔和事件）
+    active: Option<GamepadId>, // 最近操作过的手柄
+    dead_zone: f32,            // 摇杆死区（0..1）
+}
+
+impl GamepadInput {
+    /// 创建手柄输入，当前平台不支持手柄时记录警告并返回None
+    pub fn new(dead_zone: f32) -> Option<Self> {
+        let gilrs = match Gilrs::new() {
+            Ok(gilrs) => gilrs,
+            Err(gilrs::Error::NotImplemented(_)) => {
+                warn!("Gamepads are not supported on this platform");
+                return None;
+            }
+            Err(e) => {
+                warn!("Failed to initialize gamepad support: {e}");
+                return None;
+            }
+        };
+        for (_, gamepad) in gilrs.gamepads() {
+            info!("Gamepad connected: {}", gamepad.name());
+        }
+        Some(Self {
+            gilrs,
+            active: None,
+            dead_zone: dead_zone.clamp(0.0, 0.99),
+        })
+    }
+
+    /// 是否连接了至少一个手柄
+    pub fn is_connected(&self) -> bool {
+        self.gilrs.gamepads().next().is_some()
+    }
+
+    /// 处理积压的手柄事件，并把当前手柄的状态写入输入状态
+    ///
+    /// 返回是否需要重绘：有新的手柄事件（摇杆、扳机或按键变化），或者摇杆、扳机不在静止位置（按住时相机需要持续移动）。
+    pub fn poll(&mut self, input: &mut InputState) -> bool {
+        let mut changed = false;
+        while let Some(event) = self.gilrs.next_event() {
+            changed = true;
+            match event.event {
+                EventType::Connected => {
+                    info!("Gamepad connected: {}", self.gilrs.gamepad(event.id).name());
+                }
+                EventType::Disconnected => {
+                    info!(
+                        "Gamepad disconnected: {}",
+                        self.gilrs.gamepad(event.id).name()
+                    );
+                    if self.active == Some(event.id) {
+                        self.active = None;
+                    }
+                }
+                EventType::Dropped | EventType::ForceFeedbackEffectCompleted => {}
+                _ => self.active = Some(event.id),
+            }
+        }
+        let axes = self.axes();
+        input.set_gamepad(axes);
+        changed || axes != GamepadAxes::default()
+    }
+
+    /// 当前手柄的归一化输入，没有手柄时为零
+    fn axes(&self) -> GamepadAxes {
+        let Some(gamepad) = self.active.map(|id| self.gilrs.gamepad(id)) else {
+            return GamepadAxes::default();
+        };
+        let stick = |x, y| apply_dead_zone((gamepad.value(x), gamepad.value(y)), self.dead_zone);
+        let trigger = |button| gamepad.button_data(button).map_or(0.0, |data| data.value());
+        GamepadAxes {
+            movement: stick(Axis::LeftStickX, Axis::LeftStickY),
+            look: stick(Axis::RightStickX, Axis::RightStickY),
+            zoom: trigger(Button::RightTrigger2) - trigger(Button::LeftTrigger2),
+        }
+    }
+}
+
+/// 圆形死区：摇杆偏移小于死区时为零，超出部分重新映射到0..1，避免越过死区时输入突变
+fn apply_dead_zone((x, y): (f32, f32), dead_zone: f32) -> (f32, f32) {
+    let length = x.hypot(y);
+    if length <= dead_zone {
+        return (0.0, 0.0);
+    }
+    let scale = ((length - dead_zone) / (1.0 - dead_zone)).min(1.0) / length;
+    (x * scale, y * scale)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn stick_inside_the_dead_zone_is_zero() {
+        assert_eq!(apply_dead_zone((0.1, -0.1), 0.2), (0.0, 0.0));
+    }
+
+    #[test]
+    fn dead_zone_edge_maps_continuously_to_full_deflection() {
+        let (x, _) = apply_dead_zone((0.21, 0.0), 0.2);
+        assert!(x > 0.0 && x < 0.02);
+        let (x, y) = apply_dead_zone((0.0, -1.0), 0.2);
+        assert_eq!(x, 0.0);
+        assert!((y + 1.0).abs() < 1e-6);
+        // 对角方向超过1时限制在单位圆内
+        let (x, y) = apply_dead_zone((1.0, 1.0), 0.2);
+        assert!((x.hypot(y) - 1.0).abs() < 1e-6);
+    }
+}
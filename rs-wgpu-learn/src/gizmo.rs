@@ -0,0 +1,397 @@
+use crate::{
+    DepthTexture, InputState, Instance, InstanceBuffer, Mesh, MeshVertex, RenderContext,
+    WgpuAppError,
+    binding::{self, BindGroupBuilder, BindGroupLayoutBuilder},
+    math::{Ray, Transform},
+    rebuild_pipeline, shader, shapes,
+};
+use glam::{Mat4, Quat, Vec2, Vec3};
+use std::path::Path;
+use winit::event::MouseButton;
+
+/// 平移手柄使用的着色器文件
+const SHADER: &str = "gizmo.wgsl";
+
+/// 箭头的杆长（箭头总长为1）
+const SHAFT_LENGTH: f32 = 0.8;
+
+/// 悬停检测用的胶囊半径（箭头长度的倍数，比箭头本身粗一些便于点中）
+const PICK_RADIUS: f32 = 0.08;
+
+/// 悬停或拖动中的轴的颜色
+const HIGHLIGHT_COLOR: [f32; 4] = [1.0, 0.9, 0.2, 1.0];
+
+/// 手柄的轴
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GizmoAxis {
+    X,
+    Y,
+    Z,
+}
+
+impl GizmoAxis {
+    /// 所有轴
+    pub const ALL: [Self; 3] = [Self::X, Self::Y, Self::Z];
+
+    /// 轴的单位方向（世界空间）
+    pub fn direction(self) -> Vec3 {
+        match self {
+            Self::X => Vec3::X,
+            Self::Y => Vec3::Y,
+            Self::Z => Vec3::Z,
+        }
+    }
+
+    /// 轴的颜色：X红、Y绿、Z蓝
+    pub fn color(self) -> [f32; 4] {
+        match self {
+            Self::X => [0.9, 0.15, 0.15, 1.0],
+            Self::Y => [0.15, 0.8, 0.15, 1.0],
+            Self::Z => [0.15, 0.35, 0.95, 1.0],
+        }
+    }
+}
+
+/// 一次拖动更新：沿哪个轴拖动以及本帧的世界空间位移
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct GizmoDrag {
+    pub axis: GizmoAxis, // 拖动的轴
+    pub delta: Vec3,     // 本帧的位移（已经加到`position`上）
+}
+
+/// 拖动状态：沿轴的直线及上一帧光标对应的直线参数
+#[derive(Debug, Clone, Copy)]
+struct Drag {
+    axis: GizmoAxis,
+    origin: Vec3, // 开始拖动时的位置（直线的起点）
+    last: f32,    // 上一帧光标射线在直线上最近点的参数
+}
+
+/// 平移手柄：在`position`处画三个彩色箭头（圆柱 + 圆锥），屏幕上的大小保持不变
+///
+/// 每帧在`update`中传入光标和视图投影矩阵：光标射线与各轴的胶囊求交得到悬停的轴（高亮显示），
+/// 按住左键拖动时沿该轴移动`position`并返回位移。绘制时单独开一个渲染通道并清除深度，
+/// 所以不会被场景遮挡，三个箭头之间仍有正确的前后关系。
+pub struct TranslateGizmo {
+    pub position: Vec3,              // 手柄位置（世界空间）
+    pub visible: bool,               // 是否显示（隐藏时不响应鼠标）
+    pub screen_size: f32,            // 箭头长度占视口高度的比例
+    hovered: Option<GizmoAxis>,      // 光标下的轴
+    drag: Option<Drag>,              // 拖动中的轴
+    scale: f32,                      // 当前的世界空间箭头长度
+    view_proj: Mat4,                 // 上一次update的视图投影矩阵
+    mesh: Mesh,                      // 沿+Y的单位长度箭头（槽位1为实例数据）
+    instance_buffer: InstanceBuffer, // 每个轴一个实例：把箭头旋转到轴方向并缩放
+    uniform_buffer: wgpu::Buffer,
+    bind_group: wgpu::BindGroup,
+    pipeline: wgpu::RenderPipeline,
+    pipeline_layout: wgpu::PipelineLayout,
+    format: wgpu::TextureFormat,
+    sample_count: u32,
+    cache: Option<wgpu::PipelineCache>,
+}
+
+impl TranslateGizmo {
+    /// 创建手柄，format和sample_count需与绘制目标一致
+    pub fn new(
+        device: &wgpu::Device,
+        format: wgpu::TextureFormat,
+        sample_count: u32,
+        cache: Option<&wgpu::PipelineCache>,
+    ) -> Result<Self, WgpuAppError> {
+        let uniform_buffer = device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some("Gizmo Uniform Buffer"),
+            size: std::mem::size_of::<Mat4>() as u64,
+            usage: wgpu::BufferUsages::UNIFORM | wgpu::BufferUsages::COPY_DST,
+            mapped_at_creation: false,
+        });
+        let instance_buffer =
+            InstanceBuffer::new(device, "Gizmo", &[bytemuck::Zeroable::zeroed(); 3]);
+        let bind_group_layout = BindGroupLayoutBuilder::new()
+            .label("Gizmo Bind Group Layout")
+            .uniform(wgpu::ShaderStages::VERTEX)
+            .build(device);
+        let bind_group = BindGroupBuilder::new(&bind_group_layout)
+            .label("Gizmo Bind Group")
+            .buffer(&uniform_buffer)
+            .build(device);
+        let pipeline_layout =
+            binding::pipeline_layout(device, Some("Gizmo Pipeline Layout"), &[&bind_group_layout]);
+        let shader = shader::load(device, SHADER)?;
+        let pipeline = create_pipeline(
+            device,
+            &shader,
+            format,
+            &pipeline_layout,
+            sample_count,
+            cache,
+        );
+        let (vertices, indices) = arrow();
+        let mut mesh = Mesh::new(device, "Gizmo Arrow", &vertices, Some(&indices));
+        mesh.add_vertex_buffer(instance_buffer.buffer().clone());
+        Ok(Self {
+            position: Vec3::ZERO,
+            visible: true,
+            screen_size: 0.15,
+            hovered: None,
+            drag: None,
+            scale: 1.0,
+            view_proj: Mat4::IDENTITY,
+            mesh,
+            instance_buffer,
+            uniform_buffer,
+            bind_group,
+            pipeline,
+            pipeline_layout,
+            format,
+            sample_count,
+            cache: cache.cloned(),
+        })
+    }
+
+    /// 光标下的轴
+    pub fn hovered(&self) -> Option<GizmoAxis> {
+        self.hovered
+    }
+
+    /// 正在拖动的轴
+    pub fn dragging(&self) -> Option<GizmoAxis> {
+        self.drag.map(|drag| drag.axis)
+    }
+
+    /// 处理悬停和拖动，cursor为光标的归一化设备坐标（光标不在窗口内时为None）
+    ///
+    /// 拖动时`position`沿轴移动，返回本帧的位移；松开左键时结束拖动。
+    pub fn update(
+        &mut self,
+        input: &InputState,
+        cursor: Option<Vec2>,
+        view_proj: Mat4,
+    ) -> Option<GizmoDrag> {
+        self.view_proj = view_proj;
+        self.scale = self.world_size(view_proj);
+        if !self.visible || input.mouse_just_released(MouseButton::Left) {
+            self.drag = None;
+        }
+        let ray = cursor
+            .filter(|_| self.visible)
+            .map(|ndc| Ray::from_ndc(ndc, view_proj.inverse()));
+        let Some(ray) = ray else {
+            self.hovered = None;
+            return None;
+        };
+
+        // 拖动中：光标射线在轴上的最近点相对上一帧的移动即为位移
+        if let Some(drag) = &mut self.drag {
+            let direction = drag.axis.direction();
+            let s = ray.closest_on_line(drag.origin, direction)?;
+            let delta = direction * (s - drag.last);
+            drag.last = s;
+            self.position += delta;
+            return Some(GizmoDrag {
+                axis: drag.axis,
+                delta,
+            });
+        }
+
+        self.hovered = self.pick(&ray);
+        if let Some(axis) = self
+            .hovered
+            .filter(|_| input.mouse_just_pressed(MouseButton::Left))
+        {
+            let origin = self.position;
+            if let Some(last) = ray.closest_on_line(origin, axis.direction()) {
+                self.drag = Some(Drag { axis, origin, last });
+            }
+        }
+        None
+    }
+
+    /// 与光标射线相交（距离小于胶囊半径）且最近的轴
+    fn pick(&self, ray: &Ray) -> Option<GizmoAxis> {
+        GizmoAxis::ALL
+            .into_iter()
+            .filter_map(|axis| {
+                let end = self.position + axis.direction() * self.scale;
+                let (distance, t) = ray.distance_to_segment(self.position, end);
+                (distance <= PICK_RADIUS * self.scale).then_some((axis, t))
+            })
+            .min_by(|a, b| a.1.total_cmp(&b.1))
+            .map(|(axis, _)| axis)
+    }
+
+    /// 使箭头在屏幕上占screen_size视口高度所需的世界空间长度
+    ///
+    /// 在手柄所在深度上还原屏幕中心和上边缘的距离（半个视口高度），透视和正交投影都适用。
+    fn world_size(&self, view_proj: Mat4) -> f32 {
+        let ndc = view_proj.project_point3(self.position);
+        let inverse = view_proj.inverse();
+        let center = inverse.project_point3(Vec3::new(0.0, 0.0, ndc.z));
+        let top = inverse.project_point3(Vec3::new(0.0, 1.0, ndc.z));
+        let size = center.distance(top) * 2.0 * self.screen_size;
+        if size.is_finite() && size > 0.0 {
+            size
+        } else {
+            1.0
+        }
+    }
+
+    /// 在view上单独开一个渲染通道绘制手柄：保留颜色，清除深度（不被场景遮挡）
+    pub fn render(
+        &self,
+        ctx: &RenderContext,
+        view: &wgpu::TextureView,
+        encoder: &mut wgpu::CommandEncoder,
+    ) {
+        if !self.visible {
+            return;
+        }
+        let active = self.drag.map(|drag| drag.axis).or(self.hovered);
+        let instances = GizmoAxis::ALL.map(|axis| {
+            let transform = Transform {
+                translation: self.position,
+                rotation: Quat::from_rotation_arc(Vec3::Y, axis.direction()),
+                scale: Vec3::splat(self.scale),
+            };
+            let color = if active == Some(axis) {
+                HIGHLIGHT_COLOR
+            } else {
+                axis.color()
+            };
+            Instance::from_transform(&transform, color)
+        });
+        ctx.upload(
+            encoder,
+            &self.uniform_buffer,
+            0,
+            &self.view_proj.to_cols_array_2d(),
+        );
+        self.instance_buffer
+            .upload(&mut ctx.uploader.borrow_mut(), encoder, 0, &instances);
+
+        let mut pass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
+            label: Some("Gizmo Pass"),
+            color_attachments: &[Some(ctx.color_attachment(
+                view,
+                wgpu::Operations {
+                    load: wgpu::LoadOp::Load,
+                    store: wgpu::StoreOp::Store,
+                },
+            ))],
+            depth_stencil_attachment: Some(wgpu::RenderPassDepthStencilAttachment {
+                view: &ctx.depth_texture.view,
+                depth_ops: Some(wgpu::Operations {
+                    load: wgpu::LoadOp::Clear(1.0),
+                    store: wgpu::StoreOp::Discard,
+                }),
+                stencil_ops: None,
+            }),
+            timestamp_writes: None,
+            occlusion_query_set: None,
+        });
+        pass.set_pipeline(&self.pipeline);
+        pass.set_bind_group(0, &self.bind_group, &[]);
+        self.mesh.draw(&mut pass, self.instance_buffer.range());
+    }
+
+    /// 着色器文件变化时重建管线
+    pub fn shader_changed(&mut self, device: &wgpu::Device, path: &Path) {
+        if shader::depends_on(SHADER, path) {
+            let _ = self.rebuild_pipeline(device, self.sample_count);
+        }
+    }
+
+    /// 多重采样数变化时重建管线
+    pub fn sample_count_changed(
+        &mut self,
+        device: &wgpu::Device,
+        sample_count: u32,
+    ) -> Result<(), WgpuAppError> {
+        self.rebuild_pipeline(device, sample_count)
+    }
+
+    /// 用`rebuild_pipeline`按sample_count重建管线，成功后才更新多重采样数
+    fn rebuild_pipeline(
+        &mut self,
+        device: &wgpu::Device,
+        sample_count: u32,
+    ) -> Result<(), WgpuAppError> {
+        rebuild_pipeline(device, SHADER, &mut self.pipeline, |shader| {
+            create_pipeline(
+                device,
+                shader,
+                self.format,
+                &self.pipeline_layout,
+                sample_count,
+                self.cache.as_ref(),
+            )
+        })?;
+        self.sample_count = sample_count;
+        Ok(())
+    }
+}
+
+/// 沿+Y、从原点到(0, 1, 0)的箭头：细圆柱作为杆，圆锥作为箭头
+fn arrow() -> (Vec<MeshVertex>, Vec<u32>) {
+    let tip = 1.0 - SHAFT_LENGTH;
+    let parts = [
+        (shapes::cylinder(0.02, SHAFT_LENGTH, 12), SHAFT_LENGTH / 2.0),
+        (shapes::cone(0.07, tip, 16), SHAFT_LENGTH + tip / 2.0),
+    ];
+    let (mut vertices, mut indices) = (Vec::new(), Vec::new());
+    for ((part_vertices, part_indices), offset) in parts {
+        let base = vertices.len() as u32;
+        vertices.extend(part_vertices.into_iter().map(|vertex| MeshVertex {
+            position: [
+                vertex.position[0],
+                vertex.position[1] + offset,
+                vertex.position[2],
+            ],
+            ..vertex
+        }));
+        indices.extend(part_indices.into_iter().map(|index| base + index));
+    }
+    (vertices, indices)
+}
+
+/// 创建手柄管线：深度测试只在手柄自身之间进行（渲染通道开始时清除深度）
+fn create_pipeline(
+    device: &wgpu::Device,
+    shader: &wgpu::ShaderModule,
+    format: wgpu::TextureFormat,
+    layout: &wgpu::PipelineLayout,
+    sample_count: u32,
+    cache: Option<&wgpu::PipelineCache>,
+) -> wgpu::RenderPipeline {
+    device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
+        label: Some("Gizmo Pipeline"),
+        layout: Some(layout),
+        vertex: wgpu::VertexState {
+            module: shader,
+            entry_point: Some("vs"),
+            buffers: &[MeshVertex::LAYOUT, Instance::LAYOUT],
+            compilation_options: Default::default(),
+        },
+        fragment: Some(wgpu::FragmentState {
+            module: shader,
+            entry_point: Some("fs"),
+            targets: &[Some(wgpu::ColorTargetState {
+                format,
+                blend: Some(wgpu::BlendState::REPLACE),
+                write_mask: wgpu::ColorWrites::ALL,
+            })],
+            compilation_options: Default::default(),
+        }),
+        primitive: wgpu::PrimitiveState {
+            cull_mode: Some(wgpu::Face::Back),
+            ..Default::default()
+        },
+        depth_stencil: Some(DepthTexture::depth_stencil_state()),
+        multisample: wgpu::MultisampleState {
+            count: sample_count,
+            ..Default::default()
+        },
+        multiview: None,
+        cache,
+    })
+}
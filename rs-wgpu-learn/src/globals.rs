@@ -0,0 +1,107 @@
+use crate::{FrameUniforms, InputState, binding::BindingLayout};
+use std::time::Duration;
+use winit::event::MouseButton;
+
+/// 全局uniform所在的绑定组序号（着色器通过`#include "globals.wgsl"`声明）
+pub const GLOBALS_GROUP: u32 = 0;
+
+/// 全局uniform的调试标签前缀
+const LABEL: &str = "Globals";
+
+/// 使用全局uniform的着色器阶段
+const VISIBILITY: wgpu::ShaderStages = wgpu::ShaderStages::VERTEX_FRAGMENT;
+
+/// 与globals.wgsl中Globals对应的数据（按16字节对齐）
+#[repr(C)]
+#[derive(Debug, Clone, Copy, Default, PartialEq, bytemuck::Pod, bytemuck::Zeroable)]
+pub struct GlobalsUniform {
+    pub time: f32,            // 场景时间（秒），暂停和慢放时随之停止或变慢
+    pub delta_time: f32,      // 本帧的场景时间增量（秒）
+    pub resolution: [f32; 2], // 表面尺寸（像素）
+    pub mouse: [f32; 4],      // 与Shadertoy的iMouse相同（见`Globals::update`）
+    pub frame: u32,           // 帧序号
+    _padding: [u32; 3],
+}
+
+/// 应用每帧维护的全局uniform（时间、帧时间、分辨率、鼠标、帧序号），供Shadertoy风格的着色器直接使用
+///
+/// 应用在`update`时根据帧计时器和输入状态更新数据，在每帧渲染前写入本帧的槽位，渲染器通过
+/// `RenderContext::globals`取得。着色器`#include "globals.wgsl"`后即可使用`globals`变量，
+/// 管线布局的第`GLOBALS_GROUP`组使用`create_layout`创建的布局，绘制前调用`bind`。
+#[derive(Debug)]
+pub struct Globals {
+    uniforms: FrameUniforms<GlobalsUniform>, // 按帧轮换的槽位
+    uniform: GlobalsUniform,                 // 本帧的数据
+    offset: u32,                             // 最近一次写入的槽位的动态偏移
+}
+
+impl Globals {
+    pub fn new(device: &wgpu::Device) -> Self {
+        Self {
+            uniforms: FrameUniforms::new(device, LABEL, VISIBILITY),
+            uniform: GlobalsUniform::default(),
+            offset: 0,
+        }
+    }
+
+    /// 创建全局uniform的绑定组布局，渲染器在`init`中用它创建管线布局（第`GLOBALS_GROUP`组）
+    pub fn create_layout(device: &wgpu::Device) -> BindingLayout {
+        FrameUniforms::<GlobalsUniform>::create_layout(device, LABEL, VISIBILITY)
+    }
+
+    /// 绑定组布局
+    pub fn layout(&self) -> &BindingLayout {
+        self.uniforms.layout()
+    }
+
+    /// 本帧的数据
+    pub fn uniform(&self) -> &GlobalsUniform {
+        &self.uniform
+    }
+
+    /// 记录本帧的时间和鼠标状态，time为场景时间，dt为本帧的场景时间增量
+    ///
+    /// 鼠标与Shadertoy的iMouse相同：按住左键时xy跟随光标，松开后保持最后的位置；
+    /// zw为按下左键时的位置，z在按住期间为正、松开后为负，w只在按下的那一帧为正。坐标原点在左上角（与片元坐标一致）。
+    pub fn update(&mut self, time: Duration, dt: Duration, input: &InputState) {
+        self.uniform.time = time.as_secs_f32();
+        self.uniform.delta_time = dt.as_secs_f32();
+
+        let mouse = &mut self.uniform.mouse;
+        let cursor = input
+            .cursor()
+            .map(|cursor| [cursor.x as f32, cursor.y as f32]);
+        let held = input.mouse_pressed(MouseButton::Left);
+        if let Some([x, y]) = cursor.filter(|_| held) {
+            mouse[0] = x;
+            mouse[1] = y;
+        }
+        match cursor.filter(|_| input.mouse_just_pressed(MouseButton::Left)) {
+            Some([x, y]) => {
+                mouse[2] = x;
+                mouse[3] = y;
+            }
+            None => mouse[3] = -mouse[3].abs(),
+        }
+        if !held {
+            mouse[2] = -mouse[2].abs();
+        }
+    }
+
+    /// 把本帧的数据写入frame_index对应的槽位，分辨率取自当前的表面配置
+    pub fn write(
+        &mut self,
+        queue: &wgpu::Queue,
+        frame_index: u64,
+        config: &wgpu::SurfaceConfiguration,
+    ) {
+        self.uniform.resolution = [config.width as f32, config.height as f32];
+        self.uniform.frame = frame_index as u32;
+        self.offset = self.uniforms.write(queue, frame_index, &self.uniform);
+    }
+
+    /// 把全局uniform绑定到index组（通常为`GLOBALS_GROUP`），使用最近一次写入的槽位
+    pub fn bind(&self, pass: &mut wgpu::RenderPass<'_>, index: u32) {
+        pass.set_bind_group(index, self.uniforms.bind_group(), &[self.offset]);
+    }
+}
@@ -0,0 +1,128 @@
+use parking_lot::Mutex;
+use std::{collections::VecDeque, sync::Arc, time::Duration};
+
+/// 用于求平均值的最近GPU耗时数量
+const HISTORY_LEN: usize = 60;
+
+/// 查询集中时间戳的数量（渲染通道开始和结束）
+const QUERY_COUNT: u32 = 2;
+
+/// 使用时间戳查询测量渲染通道的GPU耗时（需要`Features::TIMESTAMP_QUERY`）
+///
+/// 结果异步读回：每帧只在上一次读回完成后才重新解析查询集，因此不会阻塞渲染。
+pub struct GpuTimer {
+    query_set: wgpu::QuerySet,     // 开始/结束两个时间戳
+    resolve_buffer: wgpu::Buffer,  // 查询集解析目标
+    readback_buffer: wgpu::Buffer, // 可映射的读回缓冲区
+    period: f32,                   // 每个时间戳计数对应的纳秒数
+    in_flight: bool,               // 读回缓冲区是否正在映射中
+    map_result: Arc<Mutex<Option<Result<(), wgpu::BufferAsyncError>>>>, // map_async回调结果
+    history: VecDeque<Duration>,   // 最近的GPU耗时
+}
+
+impl GpuTimer {
+    pub fn new(device: &wgpu::Device, queue: &wgpu::Queue) -> Self {
+        let size = QUERY_COUNT as u64 * wgpu::QUERY_SIZE as u64;
+        Self {
+            query_set: device.create_query_set(&wgpu::QuerySetDescriptor {
+                label: Some("GPU Timer Query Set"),
+                ty: wgpu::QueryType::Timestamp,
+                count: QUERY_COUNT,
+            }),
+            resolve_buffer: device.create_buffer(&wgpu::BufferDescriptor {
+                label: Some("GPU Timer Resolve Buffer"),
+                size,
+                usage: wgpu::BufferUsages::QUERY_RESOLVE | wgpu::BufferUsages::COPY_SRC,
+                mapped_at_creation: false,
+            }),
+            readback_buffer: device.create_buffer(&wgpu::BufferDescriptor {
+                label: Some("GPU Timer Readback Buffer"),
+                size,
+                usage: wgpu::BufferUsages::MAP_READ | wgpu::BufferUsages::COPY_DST,
+                mapped_at_creation: false,
+            }),
+            period: queue.get_timestamp_period(),
+            in_flight: false,
+            map_result: Default::default(),
+            history: VecDeque::with_capacity(HISTORY_LEN),
+        }
+    }
+
+    /// 渲染通道写入开始和结束时间戳所用的查询集
+    pub fn query_set(&self) -> &wgpu::QuerySet {
+        &self.query_set
+    }
+
+    /// 检查上一次读回是否完成，完成时记录耗时并释放读回缓冲区
+    pub fn poll(&mut self, device: &wgpu::Device) {
+        if !self.in_flight {
+            return;
+        }
+        device.poll(wgpu::Maintain::Poll);
+        let Some(result) = self.map_result.lock().take() else {
+            return;
+        };
+        if result.is_err() {
+            self.in_flight = false;
+            return;
+        }
+        let timestamps: [u64; QUERY_COUNT as usize] = {
+            let data = self.readback_buffer.slice(..).get_mapped_range();
+            bytemuck::pod_read_unaligned(&data)
+        };
+        self.readback_buffer.unmap();
+        self.in_flight = false;
+
+        // 渲染器没有写入时间戳时两个值都为0，忽略这类结果
+        let [start, end] = timestamps;
+        if end > start {
+            let nanos = (end - start) as f64 * self.period as f64;
+            if self.history.len() == HISTORY_LEN {
+                self.history.pop_front();
+            }
+            self.history.push_back(Duration::from_nanos(nanos as u64));
+        }
+    }
+
+    /// 在命令编码器中解析查询集，读回缓冲区仍在使用时跳过本帧
+    pub fn resolve(&self, encoder: &mut wgpu::CommandEncoder) {
+        if self.in_flight {
+            return;
+        }
+        encoder.resolve_query_set(&self.query_set, 0..QUERY_COUNT, &self.resolve_buffer, 0);
+        encoder.copy_buffer_to_buffer(
+            &self.resolve_buffer,
+            0,
+            &self.readback_buffer,
+            0,
+            self.resolve_buffer.size(),
+        );
+    }
+
+    /// 提交命令后开始异步映射读回缓冲区
+    pub fn map(&mut self) {
+        if self.in_flight {
+            return;
+        }
+        self.in_flight = true;
+        let map_result = self.map_result.clone();
+        self.readback_buffer
+            .slice(..)
+            .map_async(wgpu::MapMode::Read, move |result| {
+                map_result.lock().replace(result);
+            });
+    }
+
+    /// 最近一次测得的GPU耗时
+    pub fn last(&self) -> Option<Duration> {
+        self.history.back().copied()
+    }
+
+    /// 最近若干帧GPU耗时的平均值
+    pub fn average(&self) -> Option<Duration> {
+        if self.history.is_empty() {
+            return None;
+        }
+        Some(self.history.iter().sum::<Duration>() / self.history.len() as u32)
+    }
+}
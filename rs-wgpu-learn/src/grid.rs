@@ -0,0 +1,216 @@
+use crate::{
+    DepthTexture, RenderContext, WgpuAppError,
+    binding::{self, BindGroupBuilder, BindGroupLayoutBuilder},
+    rebuild_pipeline, shader,
+};
+use glam::Mat4;
+use std::path::Path;
+
+/// 参考网格使用的着色器文件
+const SHADER: &str = "grid.wgsl";
+
+/// 与着色器中GridUniform对应的数据（按16字节对齐）
+#[repr(C)]
+#[derive(Debug, Clone, Copy, bytemuck::Pod, bytemuck::Zeroable)]
+struct GridUniform {
+    view_proj: [[f32; 4]; 4],
+    inv_view_proj: [[f32; 4]; 4],
+    height: f32,
+    spacing: f32,
+    major_every: f32,
+    fade_distance: f32,
+}
+
+/// 参考网格的参数
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct GridSettings {
+    pub height: f32,        // 网格所在平面的y坐标
+    pub spacing: f32,       // 细线间距（世界单位）
+    pub major_every: u32,   // 每隔多少条细线一条粗线
+    pub fade_distance: f32, // 距相机多远时网格完全淡出（从一半距离开始淡出）
+}
+
+impl Default for GridSettings {
+    fn default() -> Self {
+        Self {
+            height: 0.0,
+            spacing: 1.0,
+            major_every: 10,
+            fade_distance: 100.0,
+        }
+    }
+}
+
+/// 地面上的“无限”参考网格（类似Blender）：一个全屏三角形，片元着色器把像素还原成射线，
+/// 在世界空间中与XZ平面求交后按坐标画出细线、粗线和红色X轴、蓝色Z轴
+///
+/// 输出交点的深度（不写入深度缓冲区），所以要在场景几何体之后、同一个带深度附件的渲染通道中`draw`，
+/// 被几何体正确遮挡。与`DebugLines`一样在渲染通道之前`prepare`。
+pub struct InfiniteGrid {
+    pub settings: GridSettings, // 网格参数，下一次prepare时生效
+    pub visible: bool,          // 是否绘制
+    uniform_buffer: wgpu::Buffer,
+    bind_group: wgpu::BindGroup,
+    pipeline: wgpu::RenderPipeline,
+    pipeline_layout: wgpu::PipelineLayout,
+    format: wgpu::TextureFormat,
+    sample_count: u32,
+    cache: Option<wgpu::PipelineCache>,
+}
+
+impl InfiniteGrid {
+    /// 创建参考网格，format和sample_count需与绘制时的渲染通道一致
+    pub fn new(
+        device: &wgpu::Device,
+        format: wgpu::TextureFormat,
+        sample_count: u32,
+        cache: Option<&wgpu::PipelineCache>,
+        settings: GridSettings,
+    ) -> Result<Self, WgpuAppError> {
+        let uniform_buffer = device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some("Grid Uniform Buffer"),
+            size: std::mem::size_of::<GridUniform>() as u64,
+            usage: wgpu::BufferUsages::UNIFORM | wgpu::BufferUsages::COPY_DST,
+            mapped_at_creation: false,
+        });
+        let bind_group_layout = BindGroupLayoutBuilder::new()
+            .label("Grid Bind Group Layout")
+            .uniform(wgpu::ShaderStages::FRAGMENT)
+            .build(device);
+        let bind_group = BindGroupBuilder::new(&bind_group_layout)
+            .label("Grid Bind Group")
+            .buffer(&uniform_buffer)
+            .build(device);
+        let pipeline_layout =
+            binding::pipeline_layout(device, Some("Grid Pipeline Layout"), &[&bind_group_layout]);
+        let shader = shader::load(device, SHADER)?;
+        let pipeline = create_pipeline(
+            device,
+            &shader,
+            format,
+            &pipeline_layout,
+            sample_count,
+            cache,
+        );
+        Ok(Self {
+            settings,
+            visible: true,
+            uniform_buffer,
+            bind_group,
+            pipeline,
+            pipeline_layout,
+            format,
+            sample_count,
+            cache: cache.cloned(),
+        })
+    }
+
+    /// 上传视图投影矩阵和网格参数，需在开始渲染通道之前调用
+    pub fn prepare(
+        &self,
+        ctx: &RenderContext,
+        encoder: &mut wgpu::CommandEncoder,
+        view_proj: Mat4,
+    ) {
+        if !self.visible {
+            return;
+        }
+        let uniform = GridUniform {
+            view_proj: view_proj.to_cols_array_2d(),
+            inv_view_proj: view_proj.inverse().to_cols_array_2d(),
+            height: self.settings.height,
+            spacing: self.settings.spacing.max(f32::EPSILON),
+            major_every: self.settings.major_every.max(1) as f32,
+            fade_distance: self.settings.fade_distance,
+        };
+        ctx.upload(encoder, &self.uniform_buffer, 0, &uniform);
+    }
+
+    /// 在渲染通道中绘制网格（场景几何体之后）
+    pub fn draw(&self, pass: &mut wgpu::RenderPass) {
+        if !self.visible {
+            return;
+        }
+        pass.set_pipeline(&self.pipeline);
+        pass.set_bind_group(0, &self.bind_group, &[]);
+        pass.draw(0..3, 0..1);
+    }
+
+    /// 着色器文件变化时重建管线
+    pub fn shader_changed(&mut self, device: &wgpu::Device, path: &Path) {
+        if shader::depends_on(SHADER, path) {
+            let _ = self.rebuild_pipeline(device, self.sample_count);
+        }
+    }
+
+    /// 多重采样数变化时重建管线
+    pub fn sample_count_changed(
+        &mut self,
+        device: &wgpu::Device,
+        sample_count: u32,
+    ) -> Result<(), WgpuAppError> {
+        self.rebuild_pipeline(device, sample_count)
+    }
+
+    /// 用`rebuild_pipeline`按sample_count重建管线，成功后才更新多重采样数
+    fn rebuild_pipeline(
+        &mut self,
+        device: &wgpu::Device,
+        sample_count: u32,
+    ) -> Result<(), WgpuAppError> {
+        rebuild_pipeline(device, SHADER, &mut self.pipeline, |shader| {
+            create_pipeline(
+                device,
+                shader,
+                self.format,
+                &self.pipeline_layout,
+                sample_count,
+                self.cache.as_ref(),
+            )
+        })?;
+        self.sample_count = sample_count;
+        Ok(())
+    }
+}
+
+/// 创建全屏网格管线：Alpha混合，深度测试但不写入深度（深度由片元着色器输出）
+fn create_pipeline(
+    device: &wgpu::Device,
+    shader: &wgpu::ShaderModule,
+    format: wgpu::TextureFormat,
+    layout: &wgpu::PipelineLayout,
+    sample_count: u32,
+    cache: Option<&wgpu::PipelineCache>,
+) -> wgpu::RenderPipeline {
+    device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
+        label: Some("Grid Pipeline"),
+        layout: Some(layout),
+        vertex: wgpu::VertexState {
+            module: shader,
+            entry_point: Some("vs"),
+            buffers: &[],
+            compilation_options: Default::default(),
+        },
+        fragment: Some(wgpu::FragmentState {
+            module: shader,
+            entry_point: Some("fs"),
+            targets: &[Some(wgpu::ColorTargetState {
+                format,
+                blend: Some(wgpu::BlendState::ALPHA_BLENDING),
+                write_mask: wgpu::ColorWrites::ALL,
+            })],
+            compilation_options: Default::default(),
+        }),
+        primitive: Default::default(),
+        depth_stencil: Some(wgpu::DepthStencilState {
+            depth_write_enabled: false,
+            ..DepthTexture::depth_stencil_state()
+        }),
+        multisample: wgpu::MultisampleState {
+            count: sample_count,
+            ..Default::default()
+        },
+        multiview: None,
+        cache,
+    })
+}
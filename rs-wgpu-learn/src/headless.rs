@@ -0,0 +1,187 @@
+use crate::{
+    DebugLabels, DepthTexture, Globals, InitContext, RenderContext, Renderer, ResourcePool,
+    SamplerCache, WgpuAppBuilder, WgpuAppError, check_sample_count, check_texture_size,
+    clear_color_for_format, create_render_targets, init_renderer, readback,
+    upload::{self, Uploader},
+};
+use log::info;
+use std::cell::RefCell;
+use wgpu::Color;
+
+/// 无窗口渲染：不创建表面，绘制到离屏纹理并可读回像素（用于CI或没有显示器的环境）
+pub struct HeadlessApp<R: Renderer> {
+    pub device: wgpu::Device,                 // GPU设备抽象
+    pub queue: wgpu::Queue,                   // 命令队列
+    pub config: wgpu::SurfaceConfiguration,   // 与离屏纹理一致的配置（供渲染器创建管线）
+    pub texture: wgpu::Texture,               // 离屏颜色纹理
+    pub view: wgpu::TextureView,              // 离屏颜色纹理视图
+    pub renderer: R,                          // 渲染器（与窗口模式共用）
+    pub clear_color: Color,                   // 背景清除色（sRGB空间）
+    pub wireframe: bool,                      // 是否以线框绘制
+    pub sample_count: u32,                    // 多重采样数
+    pub msaa_view: Option<wgpu::TextureView>, // 多重采样颜色附件
+    pub depth_texture: DepthTexture,          // 深度纹理
+    pub labels: DebugLabels,                  // GPU对象调试标签
+    pub adapter: wgpu::Adapter,               // 图形适配器（查询格式特性等）
+    pub adapter_info: wgpu::AdapterInfo,      // 适配器信息
+    pub uploader: RefCell<Uploader>,          // 暂存缓冲区上传器
+    pub pool: RefCell<ResourcePool>,          // 短期缓冲区和纹理池（读回缓冲区）
+    pub frame_index: u64,                     // 帧序号（每次render递增）
+    pub globals: Globals,                     // 全局uniform（无窗口模式下时间始终为0）
+    pub samplers: SamplerCache,               // 按配置共享的采样器
+}
+
+impl<R: Renderer> HeadlessApp<R> {
+    /// 离屏纹理格式
+    pub const FORMAT: wgpu::TextureFormat = wgpu::TextureFormat::Rgba8UnormSrgb;
+
+    /// 使用默认配置创建指定尺寸的无窗口应用
+    pub async fn new(width: u32, height: u32) -> Result<Self, WgpuAppError> {
+        WgpuAppBuilder::new().build_headless(width, height).await
+    }
+
+    /// 渲染一帧到离屏纹理
+    pub fn render(&mut self) {
+        self.frame_index += 1;
+        self.globals
+            .write(&self.queue, self.frame_index, &self.config);
+        let mut encoder = self
+            .device
+            .create_command_encoder(&wgpu::CommandEncoderDescriptor {
+                label: self.labels.label("Headless Encoder").as_deref(),
+            });
+        let ctx = RenderContext {
+            device: &self.device,
+            queue: &self.queue,
+            config: &self.config,
+            clear_color: clear_color_for_format(self.clear_color, Self::FORMAT),
+            sample_count: self.sample_count,
+            msaa_view: self.msaa_view.as_ref(),
+            depth_texture: &self.depth_texture,
+            timestamp_query_set: None,
+            alpha: 1.0, // 无窗口模式不执行固定更新，直接使用最新状态
+            time: 0.0,
+            frame_index: self.frame_index,
+            wireframe: self.wireframe,
+            globals: &self.globals,
+            samplers: &self.samplers,
+            uploader: &self.uploader,
+            pool: &self.pool,
+        };
+        crate::push_debug_group(&mut encoder, self.labels.prefix());
+        self.renderer.render(&ctx, &self.view, &mut encoder);
+        crate::pop_debug_group(&mut encoder);
+        self.uploader.get_mut().finish();
+        self.queue.submit(std::iter::once(encoder.finish()));
+        self.uploader.get_mut().recall();
+        self.pool.get_mut().end_frame();
+    }
+
+    /// 读回离屏纹理内容，返回紧密排列的RGBA8像素（每行width * 4字节）
+    pub fn read_pixels(&self) -> Result<Vec<u8>, WgpuAppError> {
+        readback::read_texture(
+            &self.device,
+            &self.queue,
+            &mut self.pool.borrow_mut(),
+            &self.texture,
+        )
+    }
+}
+
+impl WgpuAppBuilder {
+    /// 异步构建无窗口应用：跳过表面创建，渲染到width x height的离屏纹理
+    ///
+    /// 呈现模式设置在无窗口模式下没有意义，会被忽略。
+    pub async fn build_headless<R: Renderer>(
+        self,
+        width: u32,
+        height: u32,
+    ) -> Result<HeadlessApp<R>, WgpuAppError> {
+        // 1. 创建WebGPU实例
+        let instance = wgpu::Instance::new(&wgpu::InstanceDescriptor {
+            backends: self.backends,
+            ..Default::default()
+        });
+
+        // 2. 选择适配器并创建设备和命令队列（不要求与表面兼容）
+        let (adapter, device, queue) = self.request_device(&instance, None).await?;
+
+        // 3. 创建离屏颜色纹理，并构造与之对应的配置供渲染器使用
+        check_texture_size(&device.limits(), width, height)?;
+        let format = HeadlessApp::<R>::FORMAT;
+        let labels = self.labels::<R>();
+        let config = wgpu::SurfaceConfiguration {
+            usage: wgpu::TextureUsages::RENDER_ATTACHMENT | wgpu::TextureUsages::COPY_SRC,
+            format,
+            width: width.max(1),
+            height: height.max(1),
+            present_mode: wgpu::PresentMode::Fifo,
+            desired_maximum_frame_latency: 2,
+            alpha_mode: wgpu::CompositeAlphaMode::Opaque,
+            view_formats: vec![],
+        };
+        let texture = device.create_texture(&wgpu::TextureDescriptor {
+            label: labels.label("Headless Color Texture").as_deref(),
+            size: wgpu::Extent3d {
+                width: config.width,
+                height: config.height,
+                depth_or_array_layers: 1,
+            },
+            mip_level_count: 1,
+            sample_count: 1,
+            dimension: wgpu::TextureDimension::D2,
+            format,
+            usage: config.usage,
+            view_formats: &[],
+        });
+        let view = texture.create_view(&wgpu::TextureViewDescriptor {
+            label: labels.label("Headless Color View").as_deref(),
+            ..Default::default()
+        });
+        info!(
+            "Rendering headless to a {}x{} {format:?} texture",
+            config.width, config.height
+        );
+
+        // 4. 校验多重采样数并创建多重采样颜色附件和深度纹理
+        check_sample_count(&adapter, format, self.sample_count)?;
+        let (msaa_view, depth_texture) =
+            create_render_targets(&device, &config, self.sample_count, &labels);
+
+        // 5. 创建渲染器（与窗口模式共用同一套管线创建逻辑）
+        let samplers = SamplerCache::new(&device);
+        let ctx = InitContext {
+            adapter: &adapter,
+            device: &device,
+            queue: &queue,
+            config: &config,
+            sample_count: self.sample_count,
+            cache: None,
+            samplers: &samplers,
+        };
+        let renderer = init_renderer(R::init, &ctx).await?;
+        let pool = ResourcePool::new(&device, config.desired_maximum_frame_latency);
+
+        Ok(HeadlessApp {
+            config,
+            texture,
+            view,
+            renderer,
+            clear_color: Color::BLACK,
+            wireframe: false,
+            sample_count: self.sample_count,
+            msaa_view,
+            depth_texture,
+            labels,
+            adapter_info: adapter.get_info(),
+            adapter,
+            uploader: RefCell::new(Uploader::new(&device, upload::DEFAULT_CHUNK_SIZE)),
+            pool: RefCell::new(pool),
+            frame_index: 0,
+            globals: Globals::new(&device),
+            samplers,
+            device,
+            queue,
+        })
+    }
+}
@@ -0,0 +1,69 @@
+use log::warn;
+use notify::{EventKind, RecommendedWatcher, RecursiveMode, Watcher};
+use std::{
+    collections::HashMap,
+    path::{Path, PathBuf},
+    sync::mpsc,
+    time::{Duration, Instant},
+};
+
+/// 去抖时间：编辑器保存时往往连续触发多个事件，静默这么久之后才认为文件已写完
+const DEBOUNCE: Duration = Duration::from_millis(100);
+
+/// 着色器文件监听器：监听资源目录中的`.wgsl`文件变化
+pub struct ShaderWatcher {
+    _watcher: RecommendedWatcher, // 需要保持存活，drop后停止监听
+    root: PathBuf,                // 监听的资源根目录（规范化的绝对路径）
+    events: mpsc::Receiver<notify::Result<notify::Event>>, // 文件系统事件
+    pending: HashMap<PathBuf, Instant>, // 等待去抖的文件及最后一次事件时间
+}
+
+impl ShaderWatcher {
+    /// 开始监听指定目录（递归）
+    pub fn new(root: impl AsRef<Path>) -> notify::Result<Self> {
+        let root = root.as_ref().canonicalize().map_err(notify::Error::io)?;
+        let (sender, events) = mpsc::channel();
+        let mut watcher = notify::recommended_watcher(sender)?;
+        watcher.watch(&root, RecursiveMode::Recursive)?;
+        Ok(Self {
+            _watcher: watcher,
+            root,
+            events,
+            pending: HashMap::new(),
+        })
+    }
+
+    /// 返回已经稳定下来的修改过的着色器，路径相对于资源根目录
+    pub fn changed(&mut self) -> Vec<PathBuf> {
+        let now = Instant::now();
+        for event in self.events.try_iter() {
+            match event {
+                Ok(event) if matches!(event.kind, EventKind::Modify(_) | EventKind::Create(_)) => {
+                    for path in event.paths {
+                        if path.extension().is_some_and(|ext| ext == "wgsl") {
+                            self.pending.insert(path, now);
+                        }
+                    }
+                }
+                Ok(_) => {}
+                Err(e) => warn!("Shader watcher error: {e}"),
+            }
+        }
+
+        let mut ready = Vec::new();
+        self.pending.retain(|path, last_event| {
+            if now - *last_event < DEBOUNCE {
+                return true;
+            }
+            ready.push(path.clone());
+            false
+        });
+        ready
+            .into_iter()
+            .map(|path| match path.strip_prefix(&self.root) {
+                Ok(relative) => relative.to_path_buf(),
+                Err(_) => path,
+            })
+            .collect()
+    }
+}
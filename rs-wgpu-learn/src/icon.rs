@@ -0,0 +1,45 @@
+use log::warn;
+use std::path::Path;
+use winit::window::Icon;
+
+/// 内置的窗口图标（RGBA PNG）
+const EMBEDDED_ICON: &[u8] = include_bytes!("../assets/icon.png");
+
+/// 图标边长（像素），外部图标文件必须是这个尺寸
+pub const ICON_SIZE: u32 = 32;
+
+/// 加载窗口图标：优先使用path指定的PNG文件，文件不存在或尺寸不对时回退到内置图标
+///
+/// macOS（图标来自应用包）和wasm上窗口图标不起作用，直接返回None；
+/// 任何解码失败都只记录警告，不会中断启动。
+pub fn window_icon(path: Option<&Path>) -> Option<Icon> {
+    if cfg!(any(target_os = "macos", target_arch = "wasm32")) {
+        return None;
+    }
+    if let Some(path) = path {
+        match std::fs::read(path) {
+            Ok(bytes) => match decode_icon(&bytes) {
+                Ok(icon) => return Some(icon),
+                Err(e) => warn!("Ignoring window icon {}: {e}", path.display()),
+            },
+            Err(e) => warn!("Failed to read window icon {}: {e}", path.display()),
+        }
+    }
+    decode_icon(EMBEDDED_ICON)
+        .inspect_err(|e| warn!("Failed to decode the embedded window icon: {e}"))
+        .ok()
+}
+
+/// 把PNG解码为RGBA并创建图标，尺寸不是ICON_SIZE时返回错误
+fn decode_icon(bytes: &[u8]) -> Result<Icon, String> {
+    let image = image::load_from_memory_with_format(bytes, image::ImageFormat::Png)
+        .map_err(|e| e.to_string())?
+        .into_rgba8();
+    let (width, height) = image.dimensions();
+    if (width, height) != (ICON_SIZE, ICON_SIZE) {
+        return Err(format!(
+            "expected {ICON_SIZE}x{ICON_SIZE} pixels, got {width}x{height}"
+        ));
+    }
+    Icon::from_rgba(image.into_raw(), width, height).map_err(|e| e.to_string())
+}
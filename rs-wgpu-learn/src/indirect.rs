@@ -0,0 +1,202 @@
+use wgpu::util::DeviceExt;
+
+/// 间接绘制参数：可以写入间接缓冲区的参数结构体，SIZE为每条参数的字节数
+pub trait IndirectArgs: bytemuck::Pod {
+    const SIZE: wgpu::BufferAddress = std::mem::size_of::<Self>() as wgpu::BufferAddress;
+}
+
+/// `draw_indirect`的参数，内存布局与`wgpu::util::DrawIndirectArgs`一致
+#[repr(C)]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, bytemuck::Pod, bytemuck::Zeroable)]
+pub struct DrawIndirectArgs {
+    pub vertex_count: u32,   // 顶点数
+    pub instance_count: u32, // 实例数（为0时不绘制）
+    pub first_vertex: u32,   // 第一个顶点
+    pub first_instance: u32, // 第一个实例（非0时需要INDIRECT_FIRST_INSTANCE特性）
+}
+
+impl IndirectArgs for DrawIndirectArgs {}
+
+/// `draw_indexed_indirect`的参数，内存布局与`wgpu::util::DrawIndexedIndirectArgs`一致
+#[repr(C)]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, bytemuck::Pod, bytemuck::Zeroable)]
+pub struct DrawIndexedIndirectArgs {
+    pub index_count: u32,    // 索引数
+    pub instance_count: u32, // 实例数（为0时不绘制）
+    pub first_index: u32,    // 第一个索引
+    pub base_vertex: i32,    // 加到每个索引上的顶点偏移
+    pub first_instance: u32, // 第一个实例（非0时需要INDIRECT_FIRST_INSTANCE特性）
+}
+
+impl IndirectArgs for DrawIndexedIndirectArgs {}
+
+// 与wgpu的定义保持一致，GPU按这个布局读取参数
+const _: () =
+    assert!(DrawIndirectArgs::SIZE == std::mem::size_of::<wgpu::util::DrawIndirectArgs>() as u64);
+const _: () = assert!(
+    DrawIndexedIndirectArgs::SIZE
+        == std::mem::size_of::<wgpu::util::DrawIndexedIndirectArgs>() as u64
+);
+
+/// 用参数数组创建间接缓冲区（INDIRECT | STORAGE | COPY_DST：可由计算着色器或CPU更新）
+pub fn create_indirect_buffer<T: IndirectArgs>(
+    device: &wgpu::Device,
+    label: Option<&str>,
+    args: &[T],
+) -> wgpu::Buffer {
+    device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+        label,
+        contents: bytemuck::cast_slice(args),
+        usage: wgpu::BufferUsages::INDIRECT
+            | wgpu::BufferUsages::STORAGE
+            | wgpu::BufferUsages::COPY_DST,
+    })
+}
+
+/// 第index条参数在间接缓冲区中的字节偏移
+pub fn indirect_offset<T: IndirectArgs>(index: u32) -> wgpu::BufferAddress {
+    index as wgpu::BufferAddress * T::SIZE
+}
+
+/// 调试断言：offset按4字节对齐，且从offset开始的一条T参数没有超出缓冲区
+///
+/// 后端对这两种错误只给出难以理解的验证信息，这里提前给出明确的提示。
+pub(crate) fn debug_check_offset<T: IndirectArgs>(
+    buffer: &wgpu::Buffer,
+    offset: wgpu::BufferAddress,
+) {
+    debug_assert!(
+        offset.is_multiple_of(4),
+        "indirect offset {offset} is not a multiple of 4"
+    );
+    debug_assert!(
+        buffer.usage().contains(wgpu::BufferUsages::INDIRECT),
+        "buffer used for an indirect draw lacks the INDIRECT usage"
+    );
+    debug_assert!(
+        offset + T::SIZE <= buffer.size(),
+        "indirect args of {} bytes at offset {offset} overflow a buffer of {} bytes",
+        T::SIZE,
+        buffer.size()
+    );
+}
+
+/// 多条间接绘制的提交方式，由设备启用的特性决定
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MultiDrawPath {
+    /// `multi_draw_indexed_indirect_count`：绘制条数也从GPU缓冲区读取（MULTI_DRAW_INDIRECT_COUNT）
+    IndirectCount,
+    /// `multi_draw_indexed_indirect`：一次调用提交所有参数（MULTI_DRAW_INDIRECT）
+    MultiIndirect,
+    /// 逐条调用`draw_indexed_indirect`（不支持上面两种特性时的回退）
+    Loop,
+}
+
+impl MultiDrawPath {
+    /// 设备特性支持的最佳方式
+    pub fn for_features(features: wgpu::Features) -> Self {
+        if features.contains(wgpu::Features::MULTI_DRAW_INDIRECT_COUNT) {
+            Self::IndirectCount
+        } else if features.contains(wgpu::Features::MULTI_DRAW_INDIRECT) {
+            Self::MultiIndirect
+        } else {
+            Self::Loop
+        }
+    }
+
+    /// 在渲染通道中调用的API名称
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::IndirectCount => "multi_draw_indexed_indirect_count",
+            Self::MultiIndirect => "multi_draw_indexed_indirect",
+            Self::Loop => "draw_indexed_indirect loop",
+        }
+    }
+}
+
+/// 打包在一个间接缓冲区中的多条带索引绘制，按设备特性一次提交或逐条回退
+///
+/// 所有绘制共用调用方绑定的顶点缓冲区和索引缓冲区，各条参数用`first_index`和`base_vertex`
+/// 选择自己的那一段。使用`IndirectCount`方式时，实际绘制条数从计数缓冲区读取（初始为全部），
+/// 计算着色器可以改写它来跳过被剔除的绘制。
+#[derive(Debug)]
+pub struct MultiDrawBatch {
+    buffer: wgpu::Buffer,               // DrawIndexedIndirectArgs数组
+    count_buffer: Option<wgpu::Buffer>, // 绘制条数（u32，只在IndirectCount方式下创建）
+    len: u32,                           // 参数条数
+    path: MultiDrawPath,                // 提交方式
+}
+
+impl MultiDrawBatch {
+    /// 上传绘制参数，提交方式由device启用的特性决定
+    pub fn new(device: &wgpu::Device, label: &str, args: &[DrawIndexedIndirectArgs]) -> Self {
+        let path = MultiDrawPath::for_features(device.features());
+        let buffer =
+            create_indirect_buffer(device, Some(&format!("{label} Indirect Buffer")), args);
+        let count_buffer = (path == MultiDrawPath::IndirectCount).then(|| {
+            device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+                label: Some(&format!("{label} Draw Count Buffer")),
+                contents: bytemuck::bytes_of(&(args.len() as u32)),
+                usage: wgpu::BufferUsages::INDIRECT
+                    | wgpu::BufferUsages::STORAGE
+                    | wgpu::BufferUsages::COPY_DST,
+            })
+        });
+        Self {
+            buffer,
+            count_buffer,
+            len: args.len() as u32,
+            path,
+        }
+    }
+
+    /// 提交所有绘制，顶点缓冲区和索引缓冲区需已绑定（见`Mesh::draw_batch`），返回发出的绘制调用数
+    pub fn draw(&self, pass: &mut wgpu::RenderPass) -> u32 {
+        if self.len == 0 {
+            return 0;
+        }
+        match (self.path, &self.count_buffer) {
+            (MultiDrawPath::IndirectCount, Some(count_buffer)) => {
+                pass.multi_draw_indexed_indirect_count(&self.buffer, 0, count_buffer, 0, self.len);
+                1
+            }
+            (MultiDrawPath::MultiIndirect, _) => {
+                pass.multi_draw_indexed_indirect(&self.buffer, 0, self.len);
+                1
+            }
+            _ => {
+                for index in 0..self.len {
+                    let offset = indirect_offset::<DrawIndexedIndirectArgs>(index);
+                    debug_check_offset::<DrawIndexedIndirectArgs>(&self.buffer, offset);
+                    pass.draw_indexed_indirect(&self.buffer, offset);
+                }
+                self.len
+            }
+        }
+    }
+
+    /// 间接参数缓冲区
+    pub fn buffer(&self) -> &wgpu::Buffer {
+        &self.buffer
+    }
+
+    /// 绘制条数缓冲区（只在`IndirectCount`方式下存在）
+    pub fn count_buffer(&self) -> Option<&wgpu::Buffer> {
+        self.count_buffer.as_ref()
+    }
+
+    /// 参数条数
+    pub fn len(&self) -> u32 {
+        self.len
+    }
+
+    /// 是否没有绘制
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// 提交方式
+    pub fn path(&self) -> MultiDrawPath {
+        self.path
+    }
+}
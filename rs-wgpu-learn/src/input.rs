@@ -0,0 +1,312 @@
+use std::collections::{HashMap, HashSet};
+use winit::{
+    dpi::PhysicalPosition,
+    event::{
+        DeviceEvent, ElementState, KeyEvent, MouseButton, MouseScrollDelta, Touch, TouchPhase,
+        WindowEvent,
+    },
+    keyboard::{KeyCode, ModifiersState, PhysicalKey},
+};
+
+/// 像素滚动量换算为行数时每行对应的像素数（触控板通常报告像素滚动）
+const PIXELS_PER_LINE: f32 = 20.0;
+
+/// 手柄的归一化输入（已应用死区），未启用`gamepad`特性或没有手柄时全为零
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct GamepadAxes {
+    pub movement: (f32, f32), // 左摇杆（-1..1，向右、向上为正），用于移动
+    pub look: (f32, f32),     // 右摇杆（-1..1，向右、向上为正），用于转动视角
+    pub zoom: f32,            // 右扳机减左扳机（-1..1），飞行相机用于上升（正）和下降
+}
+
+/// 键盘、鼠标和触摸输入状态：由窗口事件更新，供场景在update中轮询
+///
+/// 按下/松开集合、鼠标移动量和滚动量只在一帧内有效，需要在每帧结束时调用`end_frame`清空。
+/// 单指拖动模拟按住鼠标左键拖动，双指捏合模拟滚轮，场景不需要区分鼠标和触摸。
+#[derive(Debug, Default)]
+pub struct InputState {
+    pressed: HashSet<KeyCode>,                    // 当前按住的按键
+    just_pressed: HashSet<KeyCode>,               // 本帧刚按下的按键（不包括系统的按键重复）
+    just_released: HashSet<KeyCode>,              // 本帧刚松开的按键
+    modifiers: ModifiersState,                    // 当前按住的修饰键（Shift、Ctrl等）
+    cursor: Option<PhysicalPosition<f64>>,        // 光标位置（物理像素，离开窗口时为None）
+    mouse_delta: (f64, f64),                      // 本帧光标移动量（物理像素）
+    buttons: HashSet<MouseButton>,                // 当前按住的鼠标按键
+    buttons_just_pressed: HashSet<MouseButton>,   // 本帧刚按下的鼠标按键
+    buttons_just_released: HashSet<MouseButton>,  // 本帧刚松开的鼠标按键
+    scroll_delta: (f32, f32),                     // 本帧滚动量（行）
+    touches: HashMap<u64, PhysicalPosition<f64>>, // 当前的触点及位置（物理像素），按触点ID索引
+    touch_button: bool,                           // 鼠标左键是否由单指触摸模拟按下
+    gamepad: GamepadAxes,                         // 手柄的摇杆和扳机（每帧轮询更新）
+    raw_motion: bool,                             // 鼠标视角模式：移动量改用设备的原始移动
+    consumed: bool,                               // 本帧是否有事件被场景的input消费
+}
+
+impl InputState {
+    /// 根据窗口事件更新输入状态
+    pub fn handle_event(&mut self, event: &WindowEvent) {
+        match event {
+            WindowEvent::KeyboardInput {
+                event:
+                    KeyEvent {
+                        physical_key: PhysicalKey::Code(key),
+                        state,
+                        repeat,
+                        ..
+                    },
+                ..
+            } => match state {
+                ElementState::Pressed => {
+                    // 按键重复只表示仍然按住，不算作新的按下
+                    if self.pressed.insert(*key) && !repeat {
+                        self.just_pressed.insert(*key);
+                    }
+                }
+                ElementState::Released => {
+                    if self.pressed.remove(key) {
+                        self.just_released.insert(*key);
+                    }
+                }
+            },
+            WindowEvent::ModifiersChanged(modifiers) => self.modifiers = modifiers.state(),
+            // 光标位置已经是物理像素，与表面配置的尺寸单位一致，不需要再乘缩放因子
+            // 鼠标视角模式下光标被锁定，移动量由设备事件提供
+            WindowEvent::CursorMoved { position, .. } => {
+                if let Some(last) = self.cursor.filter(|_| !self.raw_motion) {
+                    self.mouse_delta.0 += position.x - last.x;
+                    self.mouse_delta.1 += position.y - last.y;
+                }
+                self.cursor = Some(*position);
+            }
+            // 光标离开窗口后停止累计移动量，重新进入时不会产生一次跳变
+            WindowEvent::CursorLeft { .. } => self.cursor = None,
+            WindowEvent::MouseInput { state, button, .. } => match state {
+                ElementState::Pressed => {
+                    if self.buttons.insert(*button) {
+                        self.buttons_just_pressed.insert(*button);
+                    }
+                }
+                ElementState::Released => {
+                    if self.buttons.remove(button) {
+                        self.buttons_just_released.insert(*button);
+                    }
+                }
+            },
+            // 统一换算为行数：滚轮按行报告，触控板按像素报告
+            WindowEvent::MouseWheel { delta, .. } => {
+                let (x, y) = match delta {
+                    MouseScrollDelta::LineDelta(x, y) => (*x, *y),
+                    MouseScrollDelta::PixelDelta(position) => (
+                        position.x as f32 / PIXELS_PER_LINE,
+                        position.y as f32 / PIXELS_PER_LINE,
+                    ),
+                };
+                self.scroll_delta.0 += x;
+                self.scroll_delta.1 += y;
+            }
+            WindowEvent::Touch(touch) => self.handle_touch(touch),
+            // 失去焦点后收不到松开事件，视为所有按键都已松开、所有触点都已抬起
+            WindowEvent::Focused(false) => {
+                self.just_released.extend(self.pressed.drain());
+                self.modifiers = ModifiersState::empty();
+                self.buttons_just_released.extend(self.buttons.drain());
+                self.touches.clear();
+                self.touch_button = false;
+            }
+            _ => {}
+        }
+    }
+
+    /// 根据设备事件更新输入状态：鼠标视角模式下累计鼠标的原始移动量
+    pub fn handle_device_event(&mut self, event: &DeviceEvent) {
+        if let DeviceEvent::MouseMotion { delta } = event
+            && self.raw_motion
+        {
+            self.mouse_delta.0 += delta.0;
+            self.mouse_delta.1 += delta.1;
+        }
+    }
+
+    /// 开启或关闭鼠标视角模式（光标的锁定和隐藏由窗口负责）
+    pub fn set_raw_motion(&mut self, enabled: bool) {
+        self.raw_motion = enabled;
+    }
+
+    /// 是否处于鼠标视角模式
+    pub fn raw_motion(&self) -> bool {
+        self.raw_motion
+    }
+
+    /// 处理触摸事件：单指按下/拖动/抬起对应鼠标左键，双指间距的变化对应纵向滚动
+    fn handle_touch(&mut self, touch: &Touch) {
+        let Touch {
+            id,
+            phase,
+            location,
+            ..
+        } = *touch;
+        match phase {
+            TouchPhase::Started => {
+                self.touches.insert(id, location);
+                if self.touches.len() == 1 {
+                    self.cursor = Some(location);
+                    self.press_touch_button();
+                } else {
+                    // 放下第二根手指：从拖动切换为捏合缩放
+                    self.release_touch_button();
+                }
+            }
+            TouchPhase::Moved => {
+                let spread = self.touch_spread();
+                // 未记录的触点（例如在获得焦点前按下）不产生移动量
+                let Some(last) = self.touches.insert(id, location) else {
+                    return;
+                };
+                match self.touches.len() {
+                    1 => {
+                        self.mouse_delta.0 += location.x - last.x;
+                        self.mouse_delta.1 += location.y - last.y;
+                        self.cursor = Some(location);
+                    }
+                    // 两指张开为正（与滚轮向上一致，表示放大）
+                    2 => {
+                        if let (Some(before), Some(after)) = (spread, self.touch_spread()) {
+                            self.scroll_delta.1 += ((after - before) as f32) / PIXELS_PER_LINE;
+                        }
+                    }
+                    _ => {}
+                }
+            }
+            // 取消（如防误触拒绝了手掌）与抬起相同处理，不会留下按住的状态
+            TouchPhase::Ended | TouchPhase::Cancelled => {
+                self.touches.remove(&id);
+                if self.touches.is_empty() {
+                    self.release_touch_button();
+                }
+            }
+        }
+    }
+
+    /// 单指触摸模拟按下鼠标左键（鼠标已经按住时不接管）
+    fn press_touch_button(&mut self) {
+        if self.buttons.insert(MouseButton::Left) {
+            self.buttons_just_pressed.insert(MouseButton::Left);
+            self.touch_button = true;
+        }
+    }
+
+    /// 松开由触摸模拟按下的鼠标左键
+    fn release_touch_button(&mut self) {
+        if std::mem::take(&mut self.touch_button) && self.buttons.remove(&MouseButton::Left) {
+            self.buttons_just_released.insert(MouseButton::Left);
+        }
+    }
+
+    /// 恰好有两个触点时它们之间的距离（物理像素）
+    fn touch_spread(&self) -> Option<f64> {
+        let mut points = self.touches.values();
+        match (points.next(), points.next(), points.next()) {
+            (Some(a), Some(b), None) => Some((a.x - b.x).hypot(a.y - b.y)),
+            _ => None,
+        }
+    }
+
+    /// 按键当前是否按住
+    pub fn pressed(&self, key: KeyCode) -> bool {
+        self.pressed.contains(&key)
+    }
+
+    /// 按键是否在本帧刚按下
+    pub fn just_pressed(&self, key: KeyCode) -> bool {
+        self.just_pressed.contains(&key)
+    }
+
+    /// 按键是否在本帧刚松开
+    pub fn just_released(&self, key: KeyCode) -> bool {
+        self.just_released.contains(&key)
+    }
+
+    /// 当前按住的修饰键
+    pub fn modifiers(&self) -> ModifiersState {
+        self.modifiers
+    }
+
+    /// 光标位置（物理像素），光标不在窗口内时返回None
+    pub fn cursor(&self) -> Option<PhysicalPosition<f64>> {
+        self.cursor
+    }
+
+    /// 光标在表面上的归一化坐标（左上角为(0, 0)，右下角为(1, 1)）
+    pub fn cursor_normalized(&self, config: &wgpu::SurfaceConfiguration) -> Option<(f64, f64)> {
+        self.cursor.map(|cursor| {
+            (
+                cursor.x / config.width.max(1) as f64,
+                cursor.y / config.height.max(1) as f64,
+            )
+        })
+    }
+
+    /// 本帧光标移动量（物理像素；鼠标视角模式下为设备的原始移动量，单位与平台有关）
+    pub fn mouse_delta(&self) -> (f64, f64) {
+        self.mouse_delta
+    }
+
+    /// 鼠标按键当前是否按住
+    pub fn mouse_pressed(&self, button: MouseButton) -> bool {
+        self.buttons.contains(&button)
+    }
+
+    /// 鼠标按键是否在本帧刚按下
+    pub fn mouse_just_pressed(&self, button: MouseButton) -> bool {
+        self.buttons_just_pressed.contains(&button)
+    }
+
+    /// 鼠标按键是否在本帧刚松开
+    pub fn mouse_just_released(&self, button: MouseButton) -> bool {
+        self.buttons_just_released.contains(&button)
+    }
+
+    /// 本帧滚动量（行，向上/向右为正），包括双指捏合产生的纵向滚动
+    pub fn scroll_delta(&self) -> (f32, f32) {
+        self.scroll_delta
+    }
+
+    /// 当前的触点ID及位置（物理像素，与表面配置的尺寸单位一致）
+    pub fn touches(&self) -> impl Iterator<Item = (u64, PhysicalPosition<f64>)> + '_ {
+        self.touches.iter().map(|(id, position)| (*id, *position))
+    }
+
+    /// 本帧是否有窗口事件被`Scene::input`消费（如UI覆盖层处理了点击或滚动）
+    ///
+    /// 事件被消费前已经更新了输入状态，相机控制器等轮询鼠标的逻辑应在此时忽略本帧的鼠标输入。
+    pub fn consumed(&self) -> bool {
+        self.consumed
+    }
+
+    /// 标记本帧有事件被场景消费（由应用在`Scene::input`返回true时调用）
+    pub(crate) fn mark_consumed(&mut self) {
+        self.consumed = true;
+    }
+
+    /// 手柄的摇杆和扳机
+    pub fn gamepad(&self) -> GamepadAxes {
+        self.gamepad
+    }
+
+    /// 更新手柄状态（由`GamepadInput::poll`调用）
+    #[cfg(any(test, feature = "gamepad"))]
+    pub(crate) fn set_gamepad(&mut self, axes: GamepadAxes) {
+        self.gamepad = axes;
+    }
+
+    /// 每帧结束时清空本帧的按下/松开集合、移动量和滚动量
+    pub fn end_frame(&mut self) {
+        self.just_pressed.clear();
+        self.just_released.clear();
+        self.buttons_just_pressed.clear();
+        self.buttons_just_released.clear();
+        self.mouse_delta = (0.0, 0.0);
+        self.scroll_delta = (0.0, 0.0);
+        self.consumed = false;
+    }
+}
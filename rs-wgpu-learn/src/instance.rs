@@ -0,0 +1,139 @@
+use crate::{math::Transform, upload::Uploader, vertex_layout};
+use std::marker::PhantomData;
+use wgpu::util::DeviceExt;
+
+vertex_layout! {
+    /// 每个实例一份的数据：模型矩阵和颜色，占用`@location(5)`到`@location(9)`
+    ///
+    /// 网格顶点使用0到4，着色器中用4个vec4f重新组成模型矩阵：
+    /// `mat4x4f(in.model_0, in.model_1, in.model_2, in.model_3)`。
+    #[step_mode(Instance)]
+    #[start_location(5)]
+    #[derive(Debug, PartialEq)]
+    pub struct Instance {
+        pub model: [[f32; 4]; 4], // 模型矩阵（按列存储），@location(5)到@location(8)
+        pub color: [f32; 4],      // 线性空间的RGBA颜色，@location(9)
+    }
+}
+
+impl Instance {
+    /// 由物体的变换和颜色生成实例数据
+    pub fn from_transform(transform: &Transform, color: [f32; 4]) -> Self {
+        Self {
+            model: transform.matrix().to_cols_array_2d(),
+            color,
+        }
+    }
+}
+
+vertex_layout! {
+    /// 使用二维数组纹理的实例数据：模型矩阵和贴图的层号，占用`@location(5)`到`@location(9)`
+    ///
+    /// 所有贴图放在同一个`ArrayTexture`中，每个实例通过层号选择自己的贴图，
+    /// 整批实例只需一个纹理绑定组和一次绘制调用。着色器中层号要以`@interpolate(flat)`传给片元着色器，
+    /// 再用`textureSample(t, s, uv, layer)`采样。
+    #[step_mode(Instance)]
+    #[start_location(5)]
+    #[derive(Debug, PartialEq)]
+    pub struct LayeredInstance {
+        pub model: [[f32; 4]; 4], // 模型矩阵（按列存储），@location(5)到@location(8)
+        pub layer: u32,           // 数组纹理的层号，@location(9)
+    }
+}
+
+impl LayeredInstance {
+    /// 由物体的变换和贴图层号生成实例数据
+    pub fn from_transform(transform: &Transform, layer: u32) -> Self {
+        Self {
+            model: transform.matrix().to_cols_array_2d(),
+            layer,
+        }
+    }
+}
+
+/// 实例缓冲区：按实例步进的顶点缓冲区，创建后长度固定，可以用`update`更新其中一段
+///
+/// 通过`Mesh::add_vertex_buffer`挂到网格上（槽位1），绘制时把`range()`作为实例范围传给
+/// `Mesh::draw`，所有实例只需一次绘制调用。
+#[derive(Debug)]
+pub struct InstanceBuffer<T = Instance> {
+    buffer: wgpu::Buffer,
+    len: u32,
+    _marker: PhantomData<T>,
+}
+
+impl<T: bytemuck::Pod> InstanceBuffer<T> {
+    /// 上传实例数据
+    pub fn new(device: &wgpu::Device, label: &str, instances: &[T]) -> Self {
+        let buffer = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+            label: Some(&format!("{label} Instance Buffer")),
+            contents: bytemuck::cast_slice(instances),
+            usage: wgpu::BufferUsages::VERTEX | wgpu::BufferUsages::COPY_DST,
+        });
+        Self {
+            buffer,
+            len: instances.len() as u32,
+            _marker: PhantomData,
+        }
+    }
+
+    /// 从第first个实例开始覆盖写入（`queue.write_buffer`），在下一次提交前生效
+    ///
+    /// # Panics
+    /// 写入范围超出创建时的实例数时panic。
+    pub fn update(&self, queue: &wgpu::Queue, first: u32, instances: &[T]) {
+        let offset = self.offset(first, instances.len());
+        if !instances.is_empty() {
+            queue.write_buffer(&self.buffer, offset, bytemuck::cast_slice(instances));
+        }
+    }
+
+    /// 通过上传器从第first个实例开始覆盖写入，复制命令记录到encoder中
+    ///
+    /// # Panics
+    /// 写入范围超出创建时的实例数时panic。
+    pub fn upload(
+        &self,
+        uploader: &mut Uploader,
+        encoder: &mut wgpu::CommandEncoder,
+        first: u32,
+        instances: &[T],
+    ) {
+        let offset = self.offset(first, instances.len());
+        // 范围已经检查过，实例类型的大小也必须是4的倍数，写入不会失败
+        uploader
+            .write_slice(encoder, &self.buffer, offset, instances)
+            .expect("instance upload is in range and aligned");
+    }
+
+    /// 检查写入范围并返回第first个实例的字节偏移
+    fn offset(&self, first: u32, count: usize) -> wgpu::BufferAddress {
+        assert!(
+            first as usize + count <= self.len as usize,
+            "updating instances {first}..{} of a buffer with {} instances",
+            first as usize + count,
+            self.len
+        );
+        first as u64 * std::mem::size_of::<T>() as u64
+    }
+
+    /// 底层顶点缓冲区
+    pub fn buffer(&self) -> &wgpu::Buffer {
+        &self.buffer
+    }
+
+    /// 实例数
+    pub fn len(&self) -> u32 {
+        self.len
+    }
+
+    /// 是否没有实例
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// 所有实例的范围，传给`Mesh::draw`
+    pub fn range(&self) -> std::ops::Range<u32> {
+        0..self.len
+    }
+}
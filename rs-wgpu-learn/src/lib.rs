@@ -1,154 +1,1369 @@
-use anyhow::Result;
-use std::sync::Arc;
-use wgpu::{Color, include_wgsl};
+mod adapter;
+pub mod anim;
+mod app;
+pub mod assets;
+pub mod binding;
+pub mod camera;
+pub mod config;
+mod debug;
+pub mod debug_draw;
+mod demo;
+mod depth;
+mod dynamic_uniform;
+mod error;
+mod frame;
+mod frame_timer;
+mod frame_uniforms;
+#[cfg(feature = "gamepad")]
+mod gamepad;
+pub mod gizmo;
+mod globals;
+mod gpu_timer;
+pub mod grid;
+#[cfg(not(target_arch = "wasm32"))]
+mod headless;
+#[cfg(feature = "hot-reload")]
+pub mod hot_reload;
+mod icon;
+pub mod indirect;
+mod input;
+mod instance;
+mod limits;
+mod material;
+pub mod math;
+mod mesh;
+pub mod mipmap;
+pub mod noise;
+pub mod picking;
+mod pipeline_cache;
+mod pipeline_set;
+mod pool;
+mod push_constants;
+#[cfg(not(target_arch = "wasm32"))]
+mod readback;
+mod renderer;
+pub mod renderers;
+mod sampler;
+pub mod scene;
+pub mod shader;
+pub mod skybox;
+mod texture;
+pub mod tonemap;
+pub mod upload;
+mod vertex;
+
+pub use adapter::AdapterSelection;
+#[cfg(not(target_arch = "wasm32"))]
+pub use adapter::enumerate_adapters;
+pub use app::{AppEvent, init_logging, run_demo};
+pub use debug::{DebugLabels, insert_debug_marker, pop_debug_group, push_debug_group};
+pub use demo::{Demo, DemoRegistry};
+pub use depth::DepthTexture;
+pub use dynamic_uniform::DynamicUniformBuffer;
+pub use error::WgpuAppError;
+pub use frame::Frame;
+pub use frame_timer::{FixedTimestep, FrameTimer, TIME_SCALE_RANGE};
+pub use frame_uniforms::{DEFAULT_FRAMES_IN_FLIGHT, FrameUniforms};
+#[cfg(feature = "gamepad")]
+pub use gamepad::GamepadInput;
+pub use globals::{GLOBALS_GROUP, Globals, GlobalsUniform};
+pub use gpu_timer::GpuTimer;
+#[cfg(not(target_arch = "wasm32"))]
+pub use headless::HeadlessApp;
+pub use icon::{ICON_SIZE, window_icon};
+pub use input::{GamepadAxes, InputState};
+pub use instance::{Instance, InstanceBuffer, LayeredInstance};
+pub use limits::{LimitsProfile, check_array_layers, check_texture_size, check_volume_size};
+use log::{debug, error, info, warn};
+pub use material::{
+    MaterialHandle, MaterialRegistry, MaterialStats, PbrFallbacks, PbrMaterial, PbrUniform, SortKey,
+};
+pub use mesh::{
+    Mesh, MeshIndex, bounds, create_index_buffer, deindex, generate_tangents, index_format_for,
+    shapes, with_tangents,
+};
+pub use pipeline_cache::PipelineCache;
+pub use pipeline_set::{PipelineSet, PipelineVariant};
+pub use pool::{PoolStats, ResourcePool};
+pub use push_constants::{MAX_PUSH_CONSTANT_SIZE, PushConstants};
+pub use renderer::{
+    InitContext, RenderContext, Renderer, RendererInit, rebuild_or_keep, rebuild_pipeline,
+    rebuild_pipeline_with_defines,
+};
+pub use sampler::{MAX_ANISOTROPY, SamplerCache, SamplerDesc};
+pub use scene::{BoxedScene, RedrawMode, Scene};
+use std::{
+    cell::RefCell,
+    path::Path,
+    sync::{
+        Arc,
+        atomic::{AtomicBool, Ordering},
+    },
+    time::Duration,
+};
+pub use texture::{
+    ArrayTexture, ArrayTextureBuilder, Atlas, AtlasBuilder, AtlasId, AtlasRect, CUBE_FACES,
+    CubeMap, EnvironmentMap, Texture, VolumeTexture,
+};
+use upload::Uploader;
+pub use vertex::{
+    MeshVertex, TangentVertex, Vertex, VertexField, VertexNormal, VertexPosition,
+    create_vertex_buffer,
+};
+use web_time::Instant; // 浏览器上std::time::Instant不可用
+use wgpu::Color;
 use winit::window::Window;
 
-// Wgpu应用核心结构体
-pub struct WgpuApp {
-    pub window: Arc<Window>,                // 窗口对象
-    pub surface: wgpu::Surface<'static>,    // GPU表面（用于绘制到窗口）
-    pub device: wgpu::Device,               // GPU设备抽象
-    pub queue: wgpu::Queue,                 // 命令队列（用于提交GPU命令）
-    pub config: wgpu::SurfaceConfiguration, // 表面配置（格式、尺寸等）
-    pub pipeline: wgpu::RenderPipeline,     // 渲染管线（包含着色器、状态配置等）
+/// 窗口尺寸稳定这么久后才重新配置表面（拖动窗口边框时会连续收到大量Resized事件）
+pub const RESIZE_DEBOUNCE: Duration = Duration::from_millis(100);
+
+// Wgpu应用核心结构体：管理设备、表面等基础设施，绘制和每帧逻辑交给场景R
+pub struct WgpuApp<R: Scene> {
+    pub window: Arc<Window>,                     // 窗口对象
+    pub surface: Option<wgpu::Surface<'static>>, // GPU表面（挂起期间为None）
+    pub device: wgpu::Device,                    // GPU设备抽象
+    pub queue: wgpu::Queue,                      // 命令队列（用于提交GPU命令）
+    pub config: wgpu::SurfaceConfiguration,      // 表面配置（格式、尺寸等）
+    pub renderer: Option<R>,                     // 渲染器（着色器、管线等资源），创建完成前为None
+    pub minimized: bool,                         // 窗口是否最小化（最小化时跳过渲染）
+    pub occluded: bool,                          // 窗口是否被完全遮挡（遮挡时跳过渲染）
+    pub scale_factor: f64,                       // 窗口缩放因子（物理像素 / 逻辑像素）
+    pub clear_color: Color,                      // 背景清除色（sRGB空间）
+    pub wireframe: bool,                         // 是否以线框绘制（由支持线框的场景处理）
+    pub present_modes: Vec<wgpu::PresentMode>,   // 表面支持的呈现模式
+    pub needs_gamma_correction: bool,            // 表面不是sRGB格式时，着色器需要自行进行gamma校正
+    pub adapter_info: wgpu::AdapterInfo,         // 适配器信息（名称、后端、驱动等）
+    pub enabled_features: wgpu::Features,        // 设备实际启用的特性（必需 + 可用的可选特性）
+    pub device_limits: wgpu::Limits,             // 设备实际生效的资源限制
+    pub sample_count: u32,                       // 多重采样数（1表示不使用MSAA）
+    pub sample_counts: Vec<u32>,                 // 表面格式支持的多重采样数
+    pub msaa_view: Option<wgpu::TextureView>,    // 多重采样颜色附件（sample_count > 1时存在）
+    pub depth_texture: DepthTexture,             // 与表面尺寸一致的深度纹理
+    pub gpu_timer: Option<GpuTimer>,             // GPU计时（设备启用TIMESTAMP_QUERY时存在）
+    pub globals: Globals,                        // 每帧更新的全局uniform（时间、分辨率、鼠标等）
+    pub device_lost: Arc<AtomicBool>,            // 设备丢失标志（由设备丢失回调设置）
+    pub labels: DebugLabels,                     // GPU对象调试标签（带场景名等前缀）
+    pub pipeline_cache: Option<PipelineCache>,   // 管线缓存（设备启用PIPELINE_CACHE时存在）
+    pub fixed_timestep: FixedTimestep,           // 场景固定更新的时间步长
+    pub scene_time: Duration,                    // 场景时间（update收到的dt之和）
+    pub frame_index: u64,                        // 帧序号（begin_frame每次获取到表面纹理时递增）
+    pub uploader: RefCell<Uploader>, // 暂存缓冲区上传器（渲染器通过RenderContext::upload使用）
+    pub pool: RefCell<ResourcePool>, // 短期缓冲区和纹理池（后处理纹理、截图等每帧或偶尔用到的资源）
+    pub samplers: SamplerCache, // 按配置共享的采样器（渲染器通过InitContext和RenderContext使用）
+    #[cfg(feature = "hot-reload")]
+    pub shader_watcher: Option<hot_reload::ShaderWatcher>, // 着色器文件监听器
+    instance: wgpu::Instance,   // WebGPU实例（恢复时重新创建表面）
+    adapter: wgpu::Adapter,     // 图形适配器（其他窗口共用设备时检查表面兼容性）
+    #[cfg(not(target_arch = "wasm32"))]
+    pending_renderer: Option<PendingRenderer<R>>, // 等待创建的渲染器
+    launched_at: Option<Instant>, // 开始构建的时间（第一帧真实渲染后清空）
+    pending_size: Option<((u32, u32), Instant)>, // 尚未应用到表面的尺寸及最后一次变化的时间
+    dirty: bool,                // 背景色等应用层状态变化或跳过了一帧，需要重绘（按需重绘时使用）
 }
 
-impl WgpuApp {
-    /// 异步构造函数：初始化WebGPU环境
-    pub async fn new(window: Arc<Window>) -> Result<Self> {
-        // 1. 创建WebGPU实例
-        let instance = wgpu::Instance::new(&wgpu::InstanceDescriptor::default());
+/// 多个窗口可以共用的GPU对象
+struct SharedGpu {
+    instance: wgpu::Instance,
+    adapter: wgpu::Adapter,
+    device: wgpu::Device,
+    queue: wgpu::Queue,
+    device_lost: Arc<AtomicBool>,
+    pipeline_cache: Option<PipelineCache>,
+}
 
-        // 2. 创建窗口表面
-        let surface = instance.create_surface(window.clone())?;
+/// 等待创建的渲染器：先呈现一帧纯色背景让窗口尽快出现，之后在主线程上创建
+///
+/// wgpu的错误作用域属于整个设备而不是线程，在后台线程创建管线时，
+/// 主线程上的验证错误会被后台线程的作用域捕获（反之亦然），所以不使用后台线程。
+#[cfg(not(target_arch = "wasm32"))]
+struct PendingRenderer<R> {
+    init: RendererInit<R>,              // 渲染器构造函数
+    cache: Option<wgpu::PipelineCache>, // 管线缓存
+}
 
-        // 3. 请求图形适配器（选择GPU）
-        let adapter = instance
-            .request_adapter(&wgpu::RequestAdapterOptions {
-                power_preference: wgpu::PowerPreference::default(), // 默认选择高性能GPU
-                compatible_surface: Some(&surface),                 // 需要与表面兼容
-                force_fallback_adapter: false,
-            })
-            .await
-            .ok_or_else(|| anyhow::anyhow!("No adapter found"))?;
+/// WgpuApp构建器：配置适配器、设备特性、限制和呈现模式
+#[derive(Debug, Clone)]
+pub struct WgpuAppBuilder {
+    label: Option<String>,                   // 设备标签（用于调试）
+    backends: wgpu::Backends,                // 允许使用的图形后端
+    power_preference: wgpu::PowerPreference, // 适配器电源偏好
+    adapter: AdapterSelection,               // 适配器选择方式
+    required_features: wgpu::Features,       // 必需的设备特性（缺失时创建失败）
+    optional_features: wgpu::Features,       // 可选的设备特性（适配器支持时才启用）
+    limits: LimitsProfile,                   // 设备资源限制档位
+    present_mode: Option<wgpu::PresentMode>, // 呈现模式（None表示使用默认值）
+    sample_count: u32,                       // 多重采样数
+    label_prefix: Option<String>,            // 调试标签前缀（None表示使用场景类型名）
+    fixed_timestep: Duration,                // 场景固定更新的时间步长
+}
+
+impl Default for WgpuAppBuilder {
+    fn default() -> Self {
+        Self {
+            label: None,
+            backends: wgpu::Backends::PRIMARY,
+            power_preference: Default::default(),
+            adapter: Default::default(),
+            required_features: Default::default(),
+            optional_features: Default::default(),
+            limits: LimitsProfile::platform_default(),
+            present_mode: None,
+            sample_count: 1,
+            label_prefix: None,
+            fixed_timestep: FixedTimestep::default().step,
+        }
+    }
+}
+
+impl WgpuAppBuilder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 设置设备标签
+    pub fn label(mut self, label: impl Into<String>) -> Self {
+        self.label = Some(label.into());
+        self
+    }
+
+    /// 设置允许使用的图形后端（默认为Vulkan/Metal/DX12/WebGPU）
+    pub fn backends(mut self, backends: wgpu::Backends) -> Self {
+        self.backends = backends;
+        self
+    }
+
+    /// 设置适配器电源偏好（低功耗/高性能）
+    pub fn power_preference(mut self, power_preference: wgpu::PowerPreference) -> Self {
+        self.power_preference = power_preference;
+        self
+    }
+
+    /// 设置适配器选择方式（按序号或名称），找不到或不兼容时回退到默认适配器
+    pub fn adapter(mut self, adapter: AdapterSelection) -> Self {
+        self.adapter = adapter;
+        self
+    }
+
+    /// 设置必需的设备特性，适配器不支持时构建返回错误
+    pub fn required_features(mut self, features: wgpu::Features) -> Self {
+        self.required_features = features;
+        self
+    }
+
+    /// 设置可选的设备特性，只启用适配器支持的部分
+    ///
+    /// 构建后通过`WgpuApp::has_feature`查询实际是否启用，并据此选择回退方案。
+    pub fn optional_features(mut self, features: wgpu::Features) -> Self {
+        self.optional_features = features;
+        self
+    }
+
+    /// 设置设备资源限制档位（默认：wasm上为WebGL2，其他平台为wgpu默认限制）
+    pub fn limits_profile(mut self, profile: LimitsProfile) -> Self {
+        self.limits = profile;
+        self
+    }
+
+    /// 设置自定义的设备资源限制，等同于`limits_profile(LimitsProfile::Custom(limits))`
+    pub fn required_limits(mut self, limits: wgpu::Limits) -> Self {
+        self.limits = LimitsProfile::Custom(limits);
+        self
+    }
+
+    /// 设置呈现模式，表面不支持时回退到默认模式
+    pub fn present_mode(mut self, present_mode: wgpu::PresentMode) -> Self {
+        self.present_mode = Some(present_mode);
+        self
+    }
+
+    /// 设置多重采样数（MSAA），必须是表面格式支持的值，默认为1
+    pub fn sample_count(mut self, sample_count: u32) -> Self {
+        self.sample_count = sample_count;
+        self
+    }
+
+    /// 设置`Scene::fixed_update`的时间步长，默认为1/60秒
+    pub fn fixed_timestep(mut self, step: Duration) -> Self {
+        self.fixed_timestep = step;
+        self
+    }
+
+    /// 选择适配器、协商特性并创建设备，窗口和无窗口模式共用
+    ///
+    /// surface为None时（无窗口渲染）不要求适配器与表面兼容。
+    async fn request_device(
+        &self,
+        instance: &wgpu::Instance,
+        surface: Option<&wgpu::Surface<'_>>,
+    ) -> Result<(wgpu::Adapter, wgpu::Device, wgpu::Queue), WgpuAppError> {
+        // 1. 请求图形适配器（选择GPU）
+        let adapter = adapter::select_adapter(
+            instance,
+            surface,
+            &self.adapter,
+            self.power_preference,
+            self.backends,
+        )
+        .await?;
+
+        // 2. 协商设备特性：必需特性缺失时报错，可选特性与适配器支持的特性取交集
+        let missing_features = self.required_features - adapter.features();
+        if !missing_features.is_empty() {
+            return Err(WgpuAppError::MissingFeatures(missing_features));
+        }
+        let unsupported_optional = self.optional_features - adapter.features();
+        if !unsupported_optional.is_empty() {
+            info!("Optional features {unsupported_optional:?} are not supported, skipping");
+        }
+        let required_features =
+            self.required_features | (self.optional_features & adapter.features());
+
+        // 3. 根据限制档位和适配器支持的限制确定设备限制
+        let mut required_limits = self.limits.resolve(&adapter.limits())?;
+        // 启用推送常量时请求适配器支持的大小（不超过MAX_PUSH_CONSTANT_SIZE），否则上限为0
+        if required_features.contains(wgpu::Features::PUSH_CONSTANTS) {
+            required_limits.max_push_constant_size = required_limits.max_push_constant_size.max(
+                adapter
+                    .limits()
+                    .max_push_constant_size
+                    .min(MAX_PUSH_CONSTANT_SIZE),
+            );
+        }
+        debug!("Requesting device with {:?} limits", self.limits);
 
         // 4. 创建设备和命令队列
         let (device, queue) = adapter
             .request_device(
                 &wgpu::DeviceDescriptor {
-                    label: Some("Device"),
-                    required_features: wgpu::Features::empty(),
-                    required_limits: wgpu::Limits::default(),
+                    label: Some(self.label.as_deref().unwrap_or("Device")),
+                    required_features,
+                    required_limits,
                     memory_hints: wgpu::MemoryHints::Performance,
                 },
                 None,
             )
             .await?;
+        // 未被错误作用域捕获的错误只记录日志，不再panic
+        device.on_uncaptured_error(Box::new(|e| error!("Uncaptured wgpu error: {e}")));
+        let adapter_info = adapter.get_info();
+        let adapter_limits = adapter.limits();
+        info!(
+            "Using adapter {} ({:?}, {:?}), driver: {} {}",
+            adapter_info.name,
+            adapter_info.backend,
+            adapter_info.device_type,
+            adapter_info.driver,
+            adapter_info.driver_info
+        );
+        info!(
+            "Adapter limits: max texture size {}, max bind groups {}; features: {:?}",
+            adapter_limits.max_texture_dimension_2d,
+            adapter_limits.max_bind_groups,
+            adapter.features()
+        );
 
-        // 5. 配置表面（设置像素格式、尺寸等）
-        let config = surface
-            .get_default_config(
-                &adapter,
-                window.inner_size().width.max(1),  // 确保最小宽度为1
-                window.inner_size().height.max(1), // 确保最小高度为1
-            )
-            .unwrap();
-        surface.configure(&device, &config);
+        Ok((adapter, device, queue))
+    }
 
-        // 6. 创建着色器模块（加载WGSL着色器）
-        let shader = device.create_shader_module(include_wgsl!("../../source/triangle.wgsl"));
-
-        // 7. 创建渲染管线
-        let pipeline = device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
-            label: Some("Render Pipeline"),
-            layout: None, // 使用默认管线布局
-            vertex: wgpu::VertexState {
-                module: &shader,         // 顶点着色器模块
-                entry_point: Some("vs"), // 入口函数
-                buffers: &[],            // 顶点缓冲区布局（本示例为空）
-                compilation_options: Default::default(),
-            },
-            fragment: Some(wgpu::FragmentState {
-                module: &shader,         // 片元着色器模块
-                entry_point: Some("fs"), // 入口函数
-                targets: &[Some(wgpu::ColorTargetState {
-                    format: config.format,                  // 使用表面配置的格式
-                    blend: Some(wgpu::BlendState::REPLACE), // 混合模式：直接替换
-                    write_mask: wgpu::ColorWrites::ALL,     // 允许写入所有颜色通道
-                })],
-                compilation_options: Default::default(),
-            }),
-            primitive: Default::default(), // 使用默认图元配置（三角形列表）
-            depth_stencil: None,           // 禁用深度/模板测试
-            multisample: Default::default(), // 多重采样配置
-            multiview: None,
-            cache: None,
+    /// 设置GPU对象调试标签的前缀，默认使用场景的类型名
+    pub fn label_prefix(mut self, prefix: impl Into<String>) -> Self {
+        self.label_prefix = Some(prefix.into());
+        self
+    }
+
+    /// 调试标签：使用配置的前缀，未配置时使用类型名
+    fn labels<R>(&self) -> DebugLabels {
+        DebugLabels::new(
+            self.label_prefix
+                .as_deref()
+                .unwrap_or(debug::short_type_name::<R>()),
+        )
+    }
+
+    /// 异步构建：根据配置初始化WebGPU环境
+    ///
+    /// 桌面端只创建表面和设备，第一帧只清除背景，之后的`render`才创建渲染器（着色器编译、管线创建），
+    /// 窗口因此不必等待管线编译完成才出现，可通过`WgpuApp::is_ready`查询。
+    pub async fn build<R: Scene>(self, window: Arc<Window>) -> Result<WgpuApp<R>, WgpuAppError> {
+        self.build_with(window, R::init).await
+    }
+
+    /// 与`build`相同，但使用init代替`R::init`创建渲染器（例如从`DemoRegistry`中选出的装箱场景）
+    ///
+    /// 设备丢失后重新初始化时需要再次传入同一个构造函数。
+    pub async fn build_with<R: Scene>(
+        self,
+        window: Arc<Window>,
+        init: RendererInit<R>,
+    ) -> Result<WgpuApp<R>, WgpuAppError> {
+        let launched_at = Instant::now();
+
+        // 1. 创建WebGPU实例
+        let instance = wgpu::Instance::new(&wgpu::InstanceDescriptor {
+            backends: self.backends,
+            ..Default::default()
         });
 
-        Ok(Self {
-            window,
-            surface,
+        // 2. 创建窗口表面
+        let surface = instance.create_surface(window.clone())?;
+
+        // 3. 选择适配器并创建设备和命令队列
+        let (adapter, device, queue) = self.request_device(&instance, Some(&surface)).await?;
+
+        // 4. 加载管线缓存（设备支持时）
+        let pipeline_cache = device
+            .features()
+            .contains(wgpu::Features::PIPELINE_CACHE)
+            .then(|| {
+                PipelineCache::load(
+                    &device,
+                    &adapter.get_info(),
+                    self.labels::<R>().label("Pipeline Cache").as_deref(),
+                )
+            })
+            .flatten();
+
+        // 5. 设备丢失时设置标志，由渲染循环检查并重新初始化
+        let device_lost = Arc::new(AtomicBool::new(false));
+        let flag = device_lost.clone();
+        device.set_device_lost_callback(move |reason, message| {
+            // 设备被正常销毁（如应用退出或重建）时不需要处理
+            if reason != wgpu::DeviceLostReason::Destroyed {
+                error!("GPU device lost ({reason:?}): {message}");
+                flag.store(true, Ordering::Release);
+            }
+        });
+
+        // 6. 配置表面并创建与窗口相关的资源
+        let gpu = SharedGpu {
+            instance,
+            adapter,
+            device,
+            queue,
+            device_lost,
+            pipeline_cache,
+        };
+        self.build_with_gpu(window, surface, gpu, init, launched_at)
+            .await
+    }
+
+    /// 为另一个窗口构建WgpuApp，与shared共用同一个设备和命令队列（例如打开调试视图窗口）
+    ///
+    /// 适配器、设备特性和限制沿用shared的设置，构建器中的对应选项会被忽略；
+    /// 表面、多重采样、渲染目标和场景等与窗口相关的状态各自独立。
+    pub async fn build_shared<R, S>(
+        self,
+        window: Arc<Window>,
+        shared: &WgpuApp<S>,
+    ) -> Result<WgpuApp<R>, WgpuAppError>
+    where
+        R: Scene,
+        S: Scene,
+    {
+        let launched_at = Instant::now();
+        let surface = shared.instance.create_surface(window.clone())?;
+        // 适配器不一定能呈现到其他显示器上的窗口
+        if !shared.adapter.is_surface_supported(&surface) {
+            return Err(WgpuAppError::NoCompatibleConfig);
+        }
+        // wgpu的句柄内部是引用计数的，克隆后指向同一个设备
+        let gpu = SharedGpu {
+            instance: shared.instance.clone(),
+            adapter: shared.adapter.clone(),
+            device: shared.device.clone(),
+            queue: shared.queue.clone(),
+            device_lost: shared.device_lost.clone(),
+            pipeline_cache: shared.pipeline_cache.clone(),
+        };
+        self.build_with_gpu(window, surface, gpu, R::init, launched_at)
+            .await
+    }
+
+    /// 在已有设备上配置窗口表面，并创建渲染目标、GPU计时器和渲染器
+    async fn build_with_gpu<R: Scene>(
+        self,
+        window: Arc<Window>,
+        surface: wgpu::Surface<'static>,
+        gpu: SharedGpu,
+        init: RendererInit<R>,
+        launched_at: Instant,
+    ) -> Result<WgpuApp<R>, WgpuAppError> {
+        let SharedGpu {
+            instance,
+            adapter,
             device,
             queue,
+            device_lost,
+            pipeline_cache,
+        } = gpu;
+
+        // 1. 配置表面（设置像素格式、尺寸、呈现模式等）
+        let capabilities = surface.get_capabilities(&adapter);
+        let (width, height) = clamp_size(
+            &device.limits(),
+            window.inner_size().width,
+            window.inner_size().height,
+        );
+        let mut config = surface
+            .get_default_config(&adapter, width, height)
+            .ok_or(WgpuAppError::NoCompatibleConfig)?;
+
+        // 优先选择sRGB格式，否则回退到第一个支持的格式并记录需要在着色器中做gamma校正
+        let needs_gamma_correction;
+        (config.format, needs_gamma_correction) =
+            match capabilities.formats.iter().find(|format| format.is_srgb()) {
+                Some(format) => (*format, false),
+                None => (capabilities.formats[0], true),
+            };
+        info!(
+            "Selected surface format {:?} (gamma correction in shader: {needs_gamma_correction})",
+            config.format
+        );
+        // 允许创建非sRGB的线性视图
+        let linear_format = config.format.remove_srgb_suffix();
+        if linear_format != config.format {
+            config.view_formats = vec![linear_format];
+        }
+
+        if let Some(present_mode) = self.present_mode {
+            if capabilities.present_modes.contains(&present_mode) {
+                config.present_mode = present_mode;
+            } else {
+                warn!(
+                    "Present mode {present_mode:?} is not supported, using {:?}",
+                    config.present_mode
+                );
+            }
+        }
+        surface.configure(&device, &config);
+
+        // 2. 校验多重采样数并创建多重采样颜色附件和深度纹理
+        let sample_counts = check_sample_count(&adapter, config.format, self.sample_count)?;
+        let labels = self.labels::<R>();
+        let (msaa_view, depth_texture) =
+            create_render_targets(&device, &config, self.sample_count, &labels);
+
+        // 3. 设备支持时间戳查询时测量渲染通道的GPU耗时，否则不计时
+        let gpu_timer = device
+            .features()
+            .contains(wgpu::Features::TIMESTAMP_QUERY)
+            .then(|| GpuTimer::new(&device, &queue));
+
+        // 4. 创建渲染器（着色器、管线等绘制资源），采样器缓存由应用持有并传给渲染器
+        let samplers = SamplerCache::new(&device);
+        let cache = pipeline_cache.as_ref().map(|cache| cache.cache.clone());
+        #[cfg(not(target_arch = "wasm32"))]
+        let (renderer, pending_renderer) = (None, Some(PendingRenderer { init, cache }));
+        // wasm上无法阻塞等待错误作用域，直接异步创建
+        #[cfg(target_arch = "wasm32")]
+        let renderer = {
+            let ctx = InitContext {
+                adapter: &adapter,
+                device: &device,
+                queue: &queue,
+                config: &config,
+                sample_count: self.sample_count,
+                cache: cache.as_ref(),
+                samplers: &samplers,
+            };
+            Some(init_renderer(init, &ctx).await?)
+        };
+
+        // 5. 监听着色器目录，文件变化后在下一帧重建管线
+        #[cfg(feature = "hot-reload")]
+        let shader_watcher = match hot_reload::ShaderWatcher::new(shader::asset_root()) {
+            Ok(watcher) => Some(watcher),
+            Err(e) => {
+                warn!(
+                    "Shader hot reload disabled, cannot watch {}: {e}",
+                    shader::resolve("").display()
+                );
+                None
+            }
+        };
+
+        let scale_factor = window.scale_factor();
+        let pool = ResourcePool::new(&device, config.desired_maximum_frame_latency);
+        Ok(WgpuApp {
+            window,
+            surface: Some(surface),
+            instance,
+            queue,
             config,
-            pipeline,
+            renderer,
+            minimized: false,
+            occluded: false,
+            scale_factor,
+            clear_color: Color::BLACK,
+            wireframe: false,
+            present_modes: capabilities.present_modes,
+            needs_gamma_correction,
+            adapter_info: adapter.get_info(),
+            enabled_features: device.features(),
+            device_limits: device.limits(),
+            sample_count: self.sample_count,
+            sample_counts,
+            msaa_view,
+            depth_texture,
+            gpu_timer,
+            globals: Globals::new(&device),
+            device_lost,
+            labels,
+            pipeline_cache,
+            fixed_timestep: FixedTimestep::new(self.fixed_timestep),
+            scene_time: Duration::ZERO,
+            frame_index: 0,
+            uploader: RefCell::new(Uploader::new(&device, upload::DEFAULT_CHUNK_SIZE)),
+            pool: RefCell::new(pool),
+            samplers,
+            #[cfg(feature = "hot-reload")]
+            shader_watcher,
+            #[cfg(not(target_arch = "wasm32"))]
+            pending_renderer,
+            launched_at: Some(launched_at),
+            pending_size: None,
+            dirty: false,
+            adapter,
+            device,
         })
     }
+}
+
+impl<R: Scene> WgpuApp<R> {
+    /// 异步构造函数：使用默认配置初始化WebGPU环境
+    pub async fn new(window: Arc<Window>) -> Result<Self, WgpuAppError> {
+        WgpuAppBuilder::new().build(window).await
+    }
+
+    /// 创建构建器，用于自定义适配器、设备和表面选项
+    pub fn builder() -> WgpuAppBuilder {
+        WgpuAppBuilder::new()
+    }
 
     /// 执行渲染操作
-    pub fn render(&mut self) -> Result<()> {
-        // 1. 获取当前帧缓冲区
-        let output = self.surface.get_current_texture()?;
+    ///
+    /// 可恢复的表面错误会跳过当前帧，只有致命错误（如显存不足）才会返回Err，
+    /// 调用方应据此退出事件循环。
+    pub fn render(&mut self) -> Result<(), WgpuAppError> {
+        // 设备丢失后无法继续渲染，调用方需要重新创建WgpuApp
+        if self.is_device_lost() {
+            return Err(WgpuAppError::DeviceLost);
+        }
+
+        // 窗口最小化或被完全遮挡时不触碰表面
+        if !self.is_visible() {
+            return Ok(());
+        }
+
+        // 应用等待中的尺寸变化，保证获取表面纹理时使用的配置就是最后一次configure的配置
+        self.apply_pending_resize();
+
+        // 第一帧之后创建等待中的渲染器
+        #[cfg(not(target_arch = "wasm32"))]
+        self.poll_renderer()?;
+
+        // 热重载：重建修改过的着色器对应的管线
+        #[cfg(feature = "hot-reload")]
+        if let Some(watcher) = &mut self.shader_watcher {
+            for path in watcher.changed() {
+                if let Some(renderer) = &mut self.renderer {
+                    renderer.shader_changed(&self.device, &self.config, &path);
+                }
+            }
+        }
+
+        // 1. 获取当前帧（表面不可用时跳过本帧）
+        let Some(mut frame) = self.begin_frame()? else {
+            return Ok(());
+        };
+
+        // 2. 管线尚未就绪：只清除背景
+        let clear_color = self.surface_clear_color();
+        let Some(renderer) = &mut self.renderer else {
+            drop(frame.render_pass(Some(clear_color)));
+            frame.finish(&self.queue, self.uploader.get_mut());
+            self.pool.get_mut().end_frame();
+            return Ok(());
+        };
+
+        // 3. 取回之前帧的GPU耗时
+        if let Some(timer) = &mut self.gpu_timer {
+            timer.poll(&self.device);
+        }
+
+        // 4. 写入本帧的全局uniform，由渲染器记录绘制命令
+        self.globals.write(&self.queue, frame.index, &self.config);
+        let ctx = RenderContext {
+            device: &self.device,
+            queue: &self.queue,
+            config: &self.config,
+            clear_color,
+            sample_count: self.sample_count,
+            msaa_view: self.msaa_view.as_ref(),
+            depth_texture: &self.depth_texture,
+            timestamp_query_set: self.gpu_timer.as_ref().map(GpuTimer::query_set),
+            alpha: self.fixed_timestep.alpha(),
+            time: self.scene_time.as_secs_f32(),
+            frame_index: frame.index,
+            wireframe: self.wireframe,
+            globals: &self.globals,
+            samplers: &self.samplers,
+            uploader: &self.uploader,
+            pool: &self.pool,
+        };
+        frame.push_debug_group(self.labels.prefix());
+        renderer.render(&ctx, &frame.view, &mut frame.encoder);
+        frame.pop_debug_group();
+        if let Some(timer) = &self.gpu_timer {
+            frame.insert_debug_marker("Resolve GPU Timer");
+            timer.resolve(&mut frame.encoder);
+        }
+
+        // 5. 提交并呈现，然后开始异步读回时间戳
+        frame.finish(&self.queue, self.uploader.get_mut());
+        self.pool.get_mut().end_frame();
+        if let Some(timer) = &mut self.gpu_timer {
+            timer.map();
+        }
+        if let Some(launched_at) = self.launched_at.take() {
+            info!(
+                "First frame rendered {:.2?} after startup",
+                launched_at.elapsed()
+            );
+        }
 
-        // 2. 创建纹理视图
-        let view = output
-            .texture
-            .create_view(&wgpu::TextureViewDescriptor::default());
+        Ok(())
+    }
+
+    /// 获取当前帧的表面纹理并创建视图和命令编码器
+    ///
+    /// 窗口最小化或被遮挡、表面丢失、过期或获取超时等可恢复的情况会在内部处理并返回`Ok(None)`，
+    /// 调用方应跳过本帧；只有显存不足时返回错误。
+    pub fn begin_frame(&mut self) -> Result<Option<Frame>, WgpuAppError> {
+        if !self.is_visible() {
+            return Ok(None);
+        }
+        let Some(surface) = &self.surface else {
+            return Ok(None);
+        };
+        let surface_texture = match surface.get_current_texture() {
+            Ok(surface_texture) => surface_texture,
+            // 表面丢失或过期（常见于拖拽调整窗口大小）：用当前配置重新配置并跳过本帧
+            Err(wgpu::SurfaceError::Lost | wgpu::SurfaceError::Outdated) => {
+                debug!("Surface lost or outdated, reconfiguring");
+                self.reconfigure();
+                self.dirty = true;
+                return Ok(None);
+            }
+            // 获取超时：跳过本帧
+            Err(wgpu::SurfaceError::Timeout) => {
+                warn!("Surface texture acquisition timed out, skipping frame");
+                self.dirty = true;
+                return Ok(None);
+            }
+            // 显存不足：无法继续渲染
+            Err(e @ wgpu::SurfaceError::OutOfMemory) => return Err(e.into()),
+            // 其他错误：记录后跳过本帧
+            Err(e) => {
+                warn!("Failed to acquire surface texture: {e}, skipping frame");
+                return Ok(None);
+            }
+        };
+        self.frame_index += 1;
+        Ok(Some(Frame::new(
+            &self.device,
+            surface_texture,
+            self.frame_index,
+            self.msaa_view.clone(),
+            self.labels.clone(),
+        )))
+    }
+
+    /// 至少呈现过一帧后创建等待中的渲染器，使用当前的尺寸和多重采样数
+    #[cfg(not(target_arch = "wasm32"))]
+    fn poll_renderer(&mut self) -> Result<(), WgpuAppError> {
+        if self.frame_index == 0 {
+            return Ok(());
+        }
+        let Some(pending) = self.pending_renderer.take() else {
+            return Ok(());
+        };
+        let ctx = InitContext {
+            adapter: &self.adapter,
+            device: &self.device,
+            queue: &self.queue,
+            config: &self.config,
+            sample_count: self.sample_count,
+            cache: pending.cache.as_ref(),
+            samplers: &self.samplers,
+        };
+        let renderer = pollster::block_on(init_renderer(pending.init, &ctx))?;
+        self.renderer = Some(renderer);
+        info!("Renderer ready");
+        Ok(())
+    }
 
-        // 3. 创建命令编码器
+    /// 渲染器（管线等）是否已经创建完成
+    pub fn is_ready(&self) -> bool {
+        self.renderer.is_some()
+    }
+
+    /// 更新场景的每帧逻辑，应在render之前调用
+    ///
+    /// 先按固定步长执行零到多次`fixed_update`，再执行一次可变步长的`update`。
+    /// 渲染器创建完成前不累计时间，避免就绪后一次补上大量固定更新。
+    pub fn update(&mut self, dt: Duration, input: &InputState) {
+        let Some(renderer) = &mut self.renderer else {
+            return;
+        };
+        self.scene_time += dt;
+        self.globals.update(self.scene_time, dt, input);
+        let step = self.fixed_timestep.step.as_secs_f32();
+        for _ in 0..self.fixed_timestep.advance(dt) {
+            renderer.fixed_update(step);
+        }
+        renderer.update(dt, &self.queue, input);
+    }
+
+    /// 将窗口事件转发给场景，返回事件是否已被场景消费
+    pub fn input(&mut self, event: &winit::event::WindowEvent) -> bool {
+        self.renderer
+            .as_mut()
+            .is_some_and(|renderer| renderer.input(event))
+    }
+
+    /// 将拖放到窗口上的文件转发给场景，不支持的文件类型记录日志后忽略
+    pub fn file_dropped(&mut self, path: &Path) {
+        let extension = path
+            .extension()
+            .and_then(|ext| ext.to_str())
+            .map(str::to_ascii_lowercase)
+            .filter(|ext| scene::DROPPABLE_EXTENSIONS.contains(&ext.as_str()));
+        let Some(extension) = extension else {
+            warn!("Ignoring dropped file {}: unsupported type", path.display());
+            return;
+        };
+        let Some(renderer) = &mut self.renderer else {
+            warn!("Ignoring dropped file {}: scene not ready", path.display());
+            return;
+        };
+        if renderer.file_dropped(path, &self.device, &self.queue) {
+            self.dirty = true;
+        } else {
+            info!("The current demo doesn't use dropped .{extension} files");
+        }
+    }
+
+    /// 设置背景清除色（sRGB空间的颜色值）
+    pub fn set_clear_color(&mut self, color: Color) {
+        self.dirty |= color != self.clear_color;
+        self.clear_color = color;
+    }
+
+    /// 切换填充或线框绘制
+    pub fn set_wireframe(&mut self, wireframe: bool) {
+        self.dirty |= wireframe != self.wireframe;
+        self.wireframe = wireframe;
+    }
+
+    /// 当前场景的重绘方式（场景创建完成前持续重绘）
+    ///
+    /// 启用热重载时始终持续重绘，因为修改过的着色器是在每帧渲染开始时检查的。
+    pub fn redraw_mode(&self) -> RedrawMode {
+        #[cfg(feature = "hot-reload")]
+        if self.shader_watcher.is_some() {
+            return RedrawMode::Continuous;
+        }
+        self.renderer
+            .as_ref()
+            .map_or(RedrawMode::Continuous, Scene::redraw_mode)
+    }
+
+    /// 按需重绘时是否需要重绘：应用层状态变化或场景内容变化，查询后清除标记
+    pub fn needs_redraw(&mut self) -> bool {
+        let scene = self
+            .renderer
+            .as_mut()
+            .is_some_and(|renderer| renderer.needs_redraw());
+        std::mem::take(&mut self.dirty) || scene
+    }
+
+    /// 生成适配器和设备的多行描述信息（用于调试输出或界面叠加层）
+    pub fn describe(&self) -> String {
+        let info = &self.adapter_info;
+        format!(
+            "Adapter: {}\n\
+             Backend: {:?}\n\
+             Device type: {:?}\n\
+             Driver: {} {}\n\
+             Surface format: {:?}\n\
+             Present mode: {:?}\n\
+             Max texture size: {}\n\
+             Max bind groups: {}\n\
+             Features: {:?}",
+            info.name,
+            info.backend,
+            info.device_type,
+            info.driver,
+            info.driver_info,
+            self.config.format,
+            self.config.present_mode,
+            self.device_limits.max_texture_dimension_2d,
+            self.device_limits.max_bind_groups,
+            self.enabled_features,
+        )
+    }
+
+    /// 查询设备是否启用了指定特性（可同时查询多个）
+    pub fn has_feature(&self, features: wgpu::Features) -> bool {
+        self.enabled_features.contains(features)
+    }
+
+    /// 表面使用的像素格式（创建渲染管线时需要与之匹配）
+    pub fn surface_format(&self) -> wgpu::TextureFormat {
+        self.config.format
+    }
+
+    /// 切换多重采样数，通知场景重建管线并重建多重采样颜色附件和深度纹理
+    ///
+    /// 场景重建管线失败时返回错误，保留原来的多重采样数和渲染目标。
+    pub fn set_sample_count(&mut self, sample_count: u32) -> Result<(), WgpuAppError> {
+        if !self.sample_counts.contains(&sample_count) {
+            return Err(WgpuAppError::UnsupportedSampleCount {
+                count: sample_count,
+                supported: self.sample_counts.clone(),
+            });
+        }
+        // 先重建管线，全部成功后再替换渲染目标，避免管线与渲染目标的采样数不一致
+        if let Some(renderer) = &mut self.renderer {
+            let changed = renderer.sample_count_changed(&self.device, &self.config, sample_count);
+            if let Err(e) = changed {
+                // 部分管线可能已按新的采样数重建，按原来的采样数恢复
+                let restored =
+                    renderer.sample_count_changed(&self.device, &self.config, self.sample_count);
+                if let Err(rollback) = restored {
+                    error!(
+                        "Failed to restore sample count {}: {rollback}",
+                        self.sample_count
+                    );
+                }
+                return Err(e);
+            }
+        }
+        self.sample_count = sample_count;
+        (self.msaa_view, self.depth_texture) =
+            create_render_targets(&self.device, &self.config, sample_count, &self.labels);
+        self.dirty = true;
+        Ok(())
+    }
+
+    /// 截图：将当前场景重新渲染到可复制的中间纹理，读回后保存为PNG
+    ///
+    /// 读回会等待本次提交完成，渲染线程最多被阻塞约一帧。
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn capture_frame(&mut self, path: &Path) -> Result<(), WgpuAppError> {
+        if !self.is_ready() {
+            return Err(WgpuAppError::NotReady);
+        }
+
+        // 1. 只支持8位RGBA/BGRA表面格式
+        let format = self.config.format;
+        let is_bgra = match format.remove_srgb_suffix() {
+            wgpu::TextureFormat::Bgra8Unorm => true,
+            wgpu::TextureFormat::Rgba8Unorm => false,
+            _ => return Err(WgpuAppError::UnsupportedCaptureFormat(format)),
+        };
+
+        // 2. 从资源池取用与表面格式、尺寸一致的中间纹理（表面纹理通常不支持COPY_SRC）
+        let texture = self.pool.get_mut().get_texture(&wgpu::TextureDescriptor {
+            label: self.labels.label("Capture Texture").as_deref(),
+            size: wgpu::Extent3d {
+                width: self.config.width,
+                height: self.config.height,
+                depth_or_array_layers: 1,
+            },
+            mip_level_count: 1,
+            sample_count: 1,
+            dimension: wgpu::TextureDimension::D2,
+            format,
+            usage: wgpu::TextureUsages::RENDER_ATTACHMENT | wgpu::TextureUsages::COPY_SRC,
+            view_formats: &[],
+        });
+        let view = texture.create_view(&wgpu::TextureViewDescriptor {
+            label: self.labels.label("Capture View").as_deref(),
+            ..Default::default()
+        });
+
+        // 3. 由渲染器绘制到中间纹理
         let mut encoder = self
             .device
-            .create_command_encoder(&wgpu::CommandEncoderDescriptor::default());
-
-        // 4. 开始渲染通道
-        {
-            let mut pass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
-                label: Some("Render Pass"),
-                color_attachments: &[Some(wgpu::RenderPassColorAttachment {
-                    view: &view,
-                    ops: wgpu::Operations {
-                        load: wgpu::LoadOp::Clear(Color::BLACK), // 用黑色清除背景
-                        store: wgpu::StoreOp::Store,             // 存储渲染结果
-                    },
-                    resolve_target: None,
-                })],
-                depth_stencil_attachment: None,
-                timestamp_writes: None,
-                occlusion_query_set: None,
+            .create_command_encoder(&wgpu::CommandEncoderDescriptor {
+                label: self.labels.label("Capture Encoder").as_deref(),
             });
+        let ctx = RenderContext {
+            device: &self.device,
+            queue: &self.queue,
+            config: &self.config,
+            clear_color: self.surface_clear_color(),
+            sample_count: self.sample_count,
+            msaa_view: self.msaa_view.as_ref(),
+            depth_texture: &self.depth_texture,
+            timestamp_query_set: None,
+            alpha: self.fixed_timestep.alpha(),
+            time: self.scene_time.as_secs_f32(),
+            frame_index: self.frame_index,
+            wireframe: self.wireframe,
+            globals: &self.globals,
+            samplers: &self.samplers,
+            uploader: &self.uploader,
+            pool: &self.pool,
+        };
+        if let Some(renderer) = &mut self.renderer {
+            renderer.render(&ctx, &view, &mut encoder);
+        }
+        self.uploader.get_mut().finish();
+        self.queue.submit(std::iter::once(encoder.finish()));
+        self.uploader.get_mut().recall();
+
+        // 4. 读回像素，BGRA格式需交换红蓝通道
+        let mut pixels =
+            readback::read_texture(&self.device, &self.queue, self.pool.get_mut(), &texture)?;
+        if is_bgra {
+            pixels
+                .chunks_exact_mut(4)
+                .for_each(|pixel| pixel.swap(0, 2));
+        }
+
+        // 5. 保存为PNG
+        image::save_buffer(
+            path,
+            &pixels,
+            self.config.width,
+            self.config.height,
+            image::ExtendedColorType::Rgba8,
+        )?;
+        Ok(())
+    }
 
-            // 5. 设置渲染管线
-            pass.set_pipeline(&self.pipeline);
+    /// 将管线缓存写回磁盘，应在正常退出时调用
+    pub fn save_pipeline_cache(&self) {
+        if let Some(cache) = &self.pipeline_cache {
+            cache.save();
+        }
+    }
 
-            // 6. 绘制调用（绘制3个顶点，组成一个三角形）
-            pass.draw(0..3, 0..1);
+    /// 正常退出前调用：等待已提交的GPU工作完成，再执行`Scene::shutdown`并保存管线缓存
+    ///
+    /// 设备已经丢失时不再等待；渲染器尚未创建时跳过场景清理。
+    pub fn shutdown(&mut self) {
+        if !self.is_device_lost() {
+            self.device.poll(wgpu::Maintain::Wait);
+        }
+        if let Some(renderer) = &mut self.renderer {
+            renderer.shutdown();
         }
+        self.save_pipeline_cache();
+        debug!("Shut down the {} scene", self.labels.prefix());
+    }
 
-        // 7. 提交命令到队列
-        let command_buffer = encoder.finish();
-        self.queue.submit(std::iter::once(command_buffer));
+    /// 设备是否已经丢失（驱动重置、GPU被移除等）
+    pub fn is_device_lost(&self) -> bool {
+        self.device_lost.load(Ordering::Acquire)
+    }
 
-        // 8. 呈现渲染结果
-        output.present();
+    /// 最近一帧渲染通道的GPU耗时，设备不支持时间戳查询时为None
+    pub fn last_gpu_time(&self) -> Option<Duration> {
+        self.gpu_timer.as_ref().and_then(GpuTimer::last)
+    }
 
+    /// 最近若干帧GPU耗时的平均值
+    pub fn average_gpu_time(&self) -> Option<Duration> {
+        self.gpu_timer.as_ref().and_then(GpuTimer::average)
+    }
+
+    /// 用当前设备创建管线布局，绑定组布局的顺序即着色器中的组索引
+    pub fn create_pipeline_layout(
+        &self,
+        bind_group_layouts: &[&wgpu::BindGroupLayout],
+    ) -> wgpu::PipelineLayout {
+        binding::pipeline_layout(&self.device, None, bind_group_layouts)
+    }
+
+    /// 切换呈现模式（垂直同步等），表面不支持时返回错误
+    ///
+    /// 呈现模式保存在表面配置中，后续resize会沿用该模式。
+    pub fn set_present_mode(&mut self, mode: wgpu::PresentMode) -> Result<(), WgpuAppError> {
+        if !self.present_modes.contains(&mode) {
+            return Err(WgpuAppError::UnsupportedPresentMode(mode));
+        }
+        self.config.present_mode = mode;
+        if let Some(surface) = &self.surface {
+            surface.configure(&self.device, &self.config);
+        }
         Ok(())
     }
 
+    /// 应用进入后台（Android等平台）时释放表面，设备、队列和管线保持不变
+    ///
+    /// 挂起期间render直接返回，不会绘制任何内容。
+    pub fn suspend(&mut self) {
+        if self.surface.take().is_some() {
+            info!("Suspended, surface released");
+        }
+    }
+
+    /// 从后台恢复时用保留的窗口重新创建表面，并按保存的配置（尺寸、格式、呈现模式）重新配置
+    pub fn resume(&mut self) -> Result<(), WgpuAppError> {
+        if self.surface.is_some() {
+            return Ok(());
+        }
+        let surface = self.instance.create_surface(self.window.clone())?;
+        // 挂起期间窗口尺寸可能发生变化（如屏幕旋转），以当前尺寸为准
+        let size = self.window.inner_size();
+        self.surface = Some(surface);
+        if size.width > 0 && size.height > 0 {
+            (self.config.width, self.config.height) =
+                clamp_size(&self.device_limits, size.width, size.height);
+        }
+        self.reconfigure();
+        info!(
+            "Resumed, surface recreated at {}x{}",
+            self.config.width, self.config.height
+        );
+        Ok(())
+    }
+
+    /// 是否处于挂起状态（没有可用的表面）
+    pub fn is_suspended(&self) -> bool {
+        self.surface.is_none()
+    }
+
+    /// 记录窗口是否被完全遮挡（`WindowEvent::Occluded`）
+    ///
+    /// 部分平台或合成器从不发送该事件，此时标志始终为false，渲染不受影响。
+    pub fn set_occluded(&mut self, occluded: bool) {
+        if occluded != self.occluded {
+            debug!(
+                "Window {}",
+                if occluded {
+                    "occluded"
+                } else {
+                    "visible again"
+                }
+            );
+            self.occluded = occluded;
+        }
+    }
+
+    /// 窗口当前是否需要渲染：没有最小化、没有被完全遮挡且表面可用
+    ///
+    /// 最小化和遮挡分别记录，任何一个为true都会暂停渲染，两者都解除后才恢复。
+    pub fn is_visible(&self) -> bool {
+        !self.minimized && !self.occluded && !self.is_suspended()
+    }
+
+    /// 将sRGB清除色转换为表面格式所需的值
+    ///
+    /// sRGB格式的表面在写入时会自动进行线性到sRGB的编码，
+    /// 因此清除色需要先转换到线性空间，否则0.5的灰色会显得过亮。
+    fn surface_clear_color(&self) -> Color {
+        clear_color_for_format(self.clear_color, self.config.format)
+    }
+
+    /// 缩放因子变化（例如窗口被拖到DPI不同的显示器上）
+    ///
+    /// 只记录新的缩放因子，随后的Resized事件会负责重新配置表面。
+    pub fn set_scale_factor(&mut self, scale_factor: f64) {
+        if scale_factor != self.scale_factor {
+            info!(
+                "Scale factor changed: {} -> {scale_factor}",
+                self.scale_factor
+            );
+            self.scale_factor = scale_factor;
+        }
+    }
+
+    /// 表面尺寸（物理像素）
+    pub fn physical_size(&self) -> winit::dpi::PhysicalSize<u32> {
+        winit::dpi::PhysicalSize::new(self.config.width, self.config.height)
+    }
+
+    /// 表面尺寸（逻辑像素），文字和界面布局应使用该尺寸并按缩放因子渲染
+    pub fn logical_size(&self) -> winit::dpi::LogicalSize<f64> {
+        self.physical_size().to_logical(self.scale_factor)
+    }
+
     /// 处理窗口大小变化
+    ///
+    /// 宽或高为0表示窗口被最小化，此时只记录状态而不重新配置表面。
+    /// 其他尺寸只记录下来，在下一次`render`开始时或尺寸稳定`RESIZE_DEBOUNCE`后（见`settle_resize`）
+    /// 才重新配置表面，避免拖动窗口边框时每个Resized事件都configure一次。
+    /// 尺寸没有变化时（例如缩放因子变化后紧跟的Resized）不会重复配置。
     pub fn resize(&mut self, size: winit::dpi::PhysicalSize<u32>) {
-        self.config.width = size.width.max(1);
-        self.config.height = size.height.max(1);
-        // 重新配置表面（更新尺寸）
-        self.surface.configure(&self.device, &self.config);
+        if size.width == 0 || size.height == 0 {
+            self.minimized = true;
+            return;
+        }
+        let size = clamp_size(&self.device_limits, size.width, size.height);
+        let restored = std::mem::replace(&mut self.minimized, false);
+        // 拖回原来的尺寸时取消等待中的变化
+        if !restored && size == (self.config.width, self.config.height) {
+            self.pending_size = None;
+            return;
+        }
+        self.pending_size = Some((size, Instant::now()));
+    }
+
+    /// 等待中的尺寸变化最迟应在何时应用（没有等待中的变化时为None），可用于设置事件循环的唤醒时间
+    pub fn resize_deadline(&self) -> Option<Instant> {
+        self.pending_size.map(|(_, at)| at + RESIZE_DEBOUNCE)
+    }
+
+    /// 尺寸已经稳定`RESIZE_DEBOUNCE`时应用等待中的变化（拖动结束后没有新的重绘时保证最终尺寸生效）
+    pub fn settle_resize(&mut self) {
+        if self
+            .resize_deadline()
+            .is_some_and(|deadline| Instant::now() >= deadline)
+        {
+            self.apply_pending_resize();
+        }
+    }
+
+    /// 立即应用等待中的尺寸变化：重新配置表面并重建多重采样和深度附件
+    fn apply_pending_resize(&mut self) {
+        if let Some((size, _)) = self.pending_size.take() {
+            (self.config.width, self.config.height) = size;
+            self.reconfigure();
+        }
+    }
+
+    /// 按当前配置重新配置表面，并重建与尺寸相关的资源
+    fn reconfigure(&mut self) {
+        // 重新配置表面（更新尺寸），挂起期间只记录尺寸，恢复时再配置
+        if let Some(surface) = &self.surface {
+            surface.configure(&self.device, &self.config);
+        }
+        // 多重采样颜色附件和深度纹理需要与表面尺寸一致，池中旧尺寸的纹理不会再被用到
+        self.pool.get_mut().clear();
+        (self.msaa_view, self.depth_texture) =
+            create_render_targets(&self.device, &self.config, self.sample_count, &self.labels);
+        // 通知场景重建与尺寸相关的资源
+        if let Some(renderer) = &mut self.renderer {
+            renderer.resized(&self.device, &self.config);
+        }
+    }
+}
+
+/// 创建渲染器，并将期间产生的验证错误（着色器、管线无效等）转换为`WgpuAppError::Validation`
+async fn init_renderer<R: Renderer>(
+    init: RendererInit<R>,
+    ctx: &InitContext<'_>,
+) -> Result<R, WgpuAppError> {
+    ctx.device.push_error_scope(wgpu::ErrorFilter::Validation);
+    let renderer = init(ctx);
+    if let Some(error) = ctx.device.pop_error_scope().await {
+        return Err(WgpuAppError::Validation(error.to_string()));
+    }
+    renderer
+}
+
+/// 将表面尺寸限制在1到设备允许的最大纹理尺寸之间，超出时打印警告
+fn clamp_size(limits: &wgpu::Limits, width: u32, height: u32) -> (u32, u32) {
+    let max = limits.max_texture_dimension_2d;
+    if width > max || height > max {
+        warn!("Surface size {width}x{height} exceeds the device limit {max}, clamping");
+    }
+    (width.clamp(1, max), height.clamp(1, max))
+}
+
+/// 校验格式是否支持指定的多重采样数，成功时返回该格式支持的全部多重采样数
+fn check_sample_count(
+    adapter: &wgpu::Adapter,
+    format: wgpu::TextureFormat,
+    sample_count: u32,
+) -> Result<Vec<u32>, WgpuAppError> {
+    let sample_counts = adapter
+        .get_texture_format_features(format)
+        .flags
+        .supported_sample_counts();
+    if !sample_counts.contains(&sample_count) {
+        return Err(WgpuAppError::UnsupportedSampleCount {
+            count: sample_count,
+            supported: sample_counts,
+        });
+    }
+    Ok(sample_counts)
+}
+
+/// 将sRGB空间的清除色转换为写入format格式目标时应使用的值
+fn clear_color_for_format(color: Color, format: wgpu::TextureFormat) -> Color {
+    if !format.is_srgb() {
+        return color;
+    }
+    Color {
+        r: srgb_to_linear(color.r),
+        g: srgb_to_linear(color.g),
+        b: srgb_to_linear(color.b),
+        a: color.a, // alpha通道始终是线性的
+    }
+}
+
+/// 创建与表面尺寸一致的多重采样颜色附件（sample_count为1时不需要）和深度纹理
+fn create_render_targets(
+    device: &wgpu::Device,
+    config: &wgpu::SurfaceConfiguration,
+    sample_count: u32,
+    labels: &DebugLabels,
+) -> (Option<wgpu::TextureView>, DepthTexture) {
+    let depth_texture = DepthTexture::new(
+        device,
+        labels.label("Depth Texture").as_deref(),
+        config.width,
+        config.height,
+        sample_count,
+    );
+    (
+        create_msaa_view(device, config, sample_count, labels),
+        depth_texture,
+    )
+}
+
+/// 创建与表面尺寸、格式一致的多重采样颜色纹理，sample_count为1时不需要
+fn create_msaa_view(
+    device: &wgpu::Device,
+    config: &wgpu::SurfaceConfiguration,
+    sample_count: u32,
+    labels: &DebugLabels,
+) -> Option<wgpu::TextureView> {
+    if sample_count <= 1 {
+        return None;
+    }
+    let label = labels.label("MSAA Color Texture");
+    let texture = device.create_texture(&wgpu::TextureDescriptor {
+        label: label.as_deref(),
+        size: wgpu::Extent3d {
+            width: config.width,
+            height: config.height,
+            depth_or_array_layers: 1,
+        },
+        mip_level_count: 1,
+        sample_count,
+        dimension: wgpu::TextureDimension::D2,
+        format: config.format,
+        usage: wgpu::TextureUsages::RENDER_ATTACHMENT,
+        view_formats: &[],
+    });
+    Some(texture.create_view(&wgpu::TextureViewDescriptor {
+        label: label.as_deref(),
+        ..Default::default()
+    }))
+}
+
+/// sRGB分量转换为线性分量（标准sRGB传递函数的逆函数）
+fn srgb_to_linear(c: f64) -> f64 {
+    if c <= 0.04045 {
+        c / 12.92
+    } else {
+        ((c + 0.055) / 1.055).powf(2.4)
     }
 }
@@ -0,0 +1,95 @@
+use crate::WgpuAppError;
+
+/// 设备资源限制档位
+#[derive(Debug, Clone, Default, PartialEq)]
+pub enum LimitsProfile {
+    /// wgpu默认限制（大多数桌面GPU都支持）
+    #[default]
+    Default,
+    /// 低端GPU（如GLES 3.0）可用的限制
+    Downlevel,
+    /// WebGL2可用的限制（wasm上默认使用）
+    DownlevelWebgl2,
+    /// 自定义限制
+    Custom(wgpu::Limits),
+}
+
+impl LimitsProfile {
+    /// 当前平台的默认档位：wasm上为WebGL2，其他平台为wgpu默认限制
+    pub fn platform_default() -> Self {
+        if cfg!(target_arch = "wasm32") {
+            Self::DownlevelWebgl2
+        } else {
+            Self::Default
+        }
+    }
+
+    /// 档位对应的基础限制
+    pub fn limits(&self) -> wgpu::Limits {
+        match self {
+            Self::Default => wgpu::Limits::default(),
+            Self::Downlevel => wgpu::Limits::downlevel_defaults(),
+            Self::DownlevelWebgl2 => wgpu::Limits::downlevel_webgl2_defaults(),
+            Self::Custom(limits) => limits.clone(),
+        }
+    }
+
+    /// 结合适配器实际支持的限制得出请求设备时使用的限制
+    ///
+    /// 纹理尺寸上限取适配器的值（窗口可能比档位允许的更大），
+    /// 其余限制超出适配器支持范围时返回错误并列出不满足的项。
+    pub fn resolve(&self, adapter_limits: &wgpu::Limits) -> Result<wgpu::Limits, WgpuAppError> {
+        let limits = self.limits().using_resolution(adapter_limits.clone());
+        let mut unsupported = Vec::new();
+        limits.check_limits_with_fail_fn(adapter_limits, false, |name, requested, allowed| {
+            unsupported.push(format!(
+                "{name} (requested {requested}, adapter allows {allowed})"
+            ));
+        });
+        if !unsupported.is_empty() {
+            return Err(WgpuAppError::UnsupportedLimits(unsupported.join(", ")));
+        }
+        Ok(limits)
+    }
+}
+
+/// 检查二维纹理尺寸是否超出设备限制
+pub fn check_texture_size(
+    limits: &wgpu::Limits,
+    width: u32,
+    height: u32,
+) -> Result<(), WgpuAppError> {
+    let max = limits.max_texture_dimension_2d;
+    if width > max || height > max {
+        return Err(WgpuAppError::TextureTooLarge { width, height, max });
+    }
+    Ok(())
+}
+
+/// 检查二维数组纹理的层数是否超出设备限制（`max_texture_array_layers`）
+pub fn check_array_layers(limits: &wgpu::Limits, layers: u32) -> Result<(), WgpuAppError> {
+    let max = limits.max_texture_array_layers;
+    if layers > max {
+        return Err(WgpuAppError::TooManyArrayLayers { layers, max });
+    }
+    Ok(())
+}
+
+/// 检查三维纹理尺寸是否超出设备限制（`max_texture_dimension_3d`，三个方向分别比较）
+pub fn check_volume_size(limits: &wgpu::Limits, size: wgpu::Extent3d) -> Result<(), WgpuAppError> {
+    let max = limits.max_texture_dimension_3d;
+    let wgpu::Extent3d {
+        width,
+        height,
+        depth_or_array_layers: depth,
+    } = size;
+    if width > max || height > max || depth > max {
+        return Err(WgpuAppError::VolumeTooLarge {
+            width,
+            height,
+            depth,
+            max,
+        });
+    }
+    Ok(())
+}
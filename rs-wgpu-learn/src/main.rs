@@ -1,101 +1,104 @@
-use log::info;
-use parking_lot::Mutex;
-use rs_wgpu_learn::WgpuApp;
-use std::{rc::Rc, sync::Arc};
-use winit::{
-    application::ApplicationHandler, event::WindowEvent, event_loop::EventLoop,
-    window::WindowAttributes,
+use clap::{Parser, ValueEnum};
+use rs_wgpu_learn::{
+    AppEvent, DemoRegistry, config::PresentModeSetting, config::Settings, init_logging, run_demo,
 };
+use winit::event_loop::EventLoop;
 
-fn main() -> anyhow::Result<()> {
-    // 初始化日志系统（配置为仅显示INFO及以上级别的日志）
-    env_logger::builder()
-        .filter_level(log::LevelFilter::Info)
-        .init();
-
-    // 创建事件循环（窗口系统的核心事件处理器）
-    let event_loop = EventLoop::new()?;
-    // 创建应用实例并运行事件循环
-    let mut app = App::default();
-    event_loop.run_app(&mut app)?;
-    Ok(())
+/// 命令行参数：选择要运行的演示，并覆盖配置文件中的部分设置（不会写回配置文件）
+#[derive(Parser)]
+#[command(about = "WebGPU learning demos")]
+struct Cli {
+    /// 要运行的演示名称（`--list`查看全部）
+    #[arg(default_value = DemoRegistry::DEFAULT)]
+    demo: String,
+    /// 列出所有演示后退出
+    #[arg(long)]
+    list: bool,
+    /// 窗口尺寸（逻辑像素），如`1920x1080`
+    #[arg(long, value_name = "WxH", value_parser = parse_size)]
+    size: Option<(u32, u32)>,
+    /// 是否开启垂直同步
+    #[arg(long)]
+    vsync: Option<Toggle>,
+    /// 多重采样数（1表示不使用MSAA）
+    #[arg(long, value_name = "N")]
+    msaa: Option<u32>,
 }
 
-// 主应用结构体
-#[derive(Default)]
-struct App {
-    /// WGPU应用实例的共享引用（使用 Rc + Mutex 实现跨线程安全访问）
-    wgpu_app: Rc<Mutex<Option<WgpuApp>>>,
+/// 开关型参数的取值
+#[derive(Clone, Copy, ValueEnum)]
+enum Toggle {
+    On,
+    Off,
 }
 
-// ApplicationHandler trait 是 winit 窗口库的核心事件处理接口，主要用于管理应用程序生命周期和窗口事件。
-impl ApplicationHandler for App {
-    /// 当应用恢复/启动时触发（主要初始化入口）
-    fn resumed(&mut self, event_loop: &winit::event_loop::ActiveEventLoop) {
-        info!("Resumed");
-        // 防止重复初始化
-        if self.wgpu_app.lock().is_some() {
-            return;
+impl Cli {
+    /// 用命令行参数覆盖配置文件中的设置
+    fn apply(&self, settings: &mut Settings) {
+        if let Some((width, height)) = self.size {
+            settings.window.width = width;
+            settings.window.height = height;
         }
-
-        // 1. 创建窗口
-        let window = Arc::new(
-            event_loop
-                .create_window(
-                    WindowAttributes::default().with_title("Wgpu Learn"), // 设置窗口标题
-                )
-                .unwrap(),
-        );
-
-        // 2. 同步初始化WGPU应用（使用pollster阻塞等待异步初始化）
-        let wgpu_app = pollster::block_on(WgpuApp::new(window)).unwrap();
-
-        // 3. 存储WGPU应用实例
-        self.wgpu_app.lock().replace(wgpu_app);
-    }
-
-    /// 处理窗口事件（核心事件循环）
-    fn window_event(
-        &mut self,
-        event_loop: &winit::event_loop::ActiveEventLoop,
-        _window_id: winit::window::WindowId,
-        event: winit::event::WindowEvent,
-    ) {
-        let mut app_guard = self.wgpu_app.lock();
-        // 确保WGPU应用已初始化
-        if app_guard.is_none() {
-            return;
+        if let Some(vsync) = self.vsync {
+            settings.graphics.present_mode = match vsync {
+                Toggle::On => PresentModeSetting::Fifo,
+                Toggle::Off => PresentModeSetting::Immediate, // 不支持时使用表面的默认模式
+            };
         }
-        let app = app_guard.as_mut().unwrap();
-
-        match event {
-            // 关闭窗口请求
-            WindowEvent::CloseRequested => {
-                info!("Window close requested");
-                event_loop.exit(); // 退出事件循环
-            }
-
-            // 重绘请求（驱动渲染循环）
-            WindowEvent::RedrawRequested => {
-                // 执行窗口预呈现通知
-                app.window.pre_present_notify();
+        if let Some(msaa) = self.msaa {
+            settings.graphics.msaa_samples = msaa;
+        }
+    }
+}
 
-                // 执行实际渲染操作
-                app.render().unwrap();
+/// 解析`WxH`形式的尺寸
+fn parse_size(value: &str) -> Result<(u32, u32), String> {
+    let (width, height) = value
+        .split_once(['x', 'X'])
+        .ok_or_else(|| format!("expected WxH, got `{value}`"))?;
+    let parse = |part: &str| {
+        part.trim()
+            .parse::<u32>()
+            .ok()
+            .filter(|size| *size > 0)
+            .ok_or_else(|| format!("invalid size `{part}` in `{value}`"))
+    };
+    Ok((parse(width)?, parse(height)?))
+}
 
-                // 请求下一帧重绘（维持持续渲染）
-                app.window.request_redraw();
-            }
+/// 打印所有演示的名称和简介
+fn print_demos(registry: &DemoRegistry, out: &mut impl std::io::Write) {
+    let width = registry
+        .iter()
+        .map(|demo| demo.name.len())
+        .max()
+        .unwrap_or(0);
+    for demo in registry.iter() {
+        let _ = writeln!(out, "  {:width$}  {}", demo.name, demo.description);
+    }
+}
 
-            // 窗口大小变化事件
-            WindowEvent::Resized(size) => {
-                // 更新WGPU表面配置
-                app.resize(size);
-                info!("Window resized to {:?}", size);
-            }
+fn main() -> anyhow::Result<()> {
+    // 初始化日志系统（配置为仅显示INFO及以上级别的日志）
+    init_logging();
 
-            // 其他未处理事件
-            _ => {}
-        }
+    // 解析命令行参数并选择演示，名称未知时打印列表并以非零状态退出
+    let cli = Cli::parse();
+    let registry = DemoRegistry::builtin();
+    if cli.list {
+        print_demos(&registry, &mut std::io::stdout());
+        return Ok(());
     }
+    let Some(&demo) = registry.get(&cli.demo) else {
+        eprintln!("Available demos:");
+        print_demos(&registry, &mut std::io::stderr());
+        anyhow::bail!("Unknown demo `{}`", cli.demo);
+    };
+    let mut settings = Settings::load_or_create();
+    cli.apply(&mut settings);
+
+    // 创建事件循环（窗口系统的核心事件处理器）并运行演示
+    let event_loop = EventLoop::<AppEvent>::with_user_event().build()?;
+    run_demo(event_loop, settings, demo)?;
+    Ok(())
 }
@@ -0,0 +1,5 @@
+mod pbr;
+mod registry;
+
+pub use pbr::{PbrFallbacks, PbrMaterial, PbrUniform};
+pub use registry::{MaterialHandle, MaterialRegistry, MaterialStats, SortKey};
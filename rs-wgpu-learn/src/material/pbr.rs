@@ -0,0 +1,178 @@
+use crate::{
+    SamplerCache, Texture,
+    binding::{BindGroupBuilder, BindGroupLayoutBuilder, BindingLayout},
+};
+use wgpu::util::DeviceExt;
+
+/// 金属度-粗糙度PBR材质，参数和贴图的含义与glTF 2.0一致
+///
+/// GPU上是一个绑定组（见`create_layout`）：绑定0为`PbrUniform`，之后依次是基础色、金属度-粗糙度、
+/// 法线、遮蔽和自发光贴图，每张贴图后跟它的采样器。缺少的贴图由`PbrFallbacks`中1x1的默认纹理代替，
+/// 所以所有材质共用同一个布局。着色器一侧的计算见`pbr.wgsl`。
+#[derive(Debug, Clone)]
+pub struct PbrMaterial {
+    pub name: String,                                // 材质名称（没有时为空）
+    pub base_color_factor: [f32; 4],                 // 线性空间的基础色，与贴图相乘
+    pub base_color_texture: Option<Texture>,         // 基础色贴图（Rgba8UnormSrgb）
+    pub metallic_factor: f32,                        // 金属度，与贴图的B通道相乘
+    pub roughness_factor: f32,                       // 感知粗糙度，与贴图的G通道相乘
+    pub metallic_roughness_texture: Option<Texture>, // 金属度-粗糙度贴图（Rgba8Unorm）
+    pub normal_texture: Option<Texture>,             // 切线空间法线贴图（Rgba8Unorm）
+    pub normal_scale: f32,                           // 法线贴图xy分量的缩放
+    pub occlusion_texture: Option<Texture>,          // 环境光遮蔽贴图（Rgba8Unorm，使用R通道）
+    pub occlusion_strength: f32,                     // 遮蔽强度（0表示不遮蔽）
+    pub emissive_factor: [f32; 3],                   // 线性空间的自发光颜色，与贴图相乘
+    pub emissive_texture: Option<Texture>,           // 自发光贴图（Rgba8UnormSrgb）
+}
+
+/// 与`pbr.wgsl`中PbrMaterial对应的数据（48字节）
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq, bytemuck::Pod, bytemuck::Zeroable)]
+pub struct PbrUniform {
+    pub base_color: [f32; 4],
+    pub emissive: [f32; 3],
+    pub metallic: f32,
+    pub roughness: f32,
+    pub normal_scale: f32,
+    pub occlusion_strength: f32,
+    pub normal_map: u32, // 是否有法线贴图（0表示只使用顶点法线）
+}
+
+/// 材质缺少贴图时使用的1x1纹理，与贴图因子相乘后不改变因子
+#[derive(Debug, Clone)]
+pub struct PbrFallbacks {
+    pub white: Texture,        // 白色sRGB纹理：基础色、自发光
+    pub white_linear: Texture, // 白色线性纹理：金属度-粗糙度、遮蔽
+    pub flat_normal: Texture,  // 切线空间的+Z：法线
+}
+
+impl Default for PbrMaterial {
+    /// glTF规定的默认材质：白色、完全金属、完全粗糙，没有贴图和自发光
+    fn default() -> Self {
+        Self {
+            name: String::new(),
+            base_color_factor: [1.0; 4],
+            base_color_texture: None,
+            metallic_factor: 1.0,
+            roughness_factor: 1.0,
+            metallic_roughness_texture: None,
+            normal_texture: None,
+            normal_scale: 1.0,
+            occlusion_texture: None,
+            occlusion_strength: 1.0,
+            emissive_factor: [0.0; 3],
+            emissive_texture: None,
+        }
+    }
+}
+
+impl PbrMaterial {
+    /// 材质参数对应的uniform数据
+    pub fn uniform(&self) -> PbrUniform {
+        PbrUniform {
+            base_color: self.base_color_factor,
+            emissive: self.emissive_factor,
+            metallic: self.metallic_factor,
+            roughness: self.roughness_factor,
+            normal_scale: self.normal_scale,
+            occlusion_strength: self.occlusion_strength,
+            normal_map: self.normal_texture.is_some() as u32,
+        }
+    }
+
+    /// 创建保存`uniform`的缓冲区，之后修改参数时可以用`queue.write_buffer`更新
+    pub fn create_buffer(&self, device: &wgpu::Device) -> wgpu::Buffer {
+        device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+            label: Some("PBR Material Buffer"),
+            contents: bytemuck::bytes_of(&self.uniform()),
+            usage: wgpu::BufferUsages::UNIFORM | wgpu::BufferUsages::COPY_DST,
+        })
+    }
+
+    /// 创建与`bind_group`对应的绑定组布局：片元着色器使用的uniform和5组贴图 + 采样器（共11个绑定）
+    pub fn create_layout(device: &wgpu::Device) -> BindingLayout {
+        let float = wgpu::TextureSampleType::Float { filterable: true };
+        (0..5)
+            .fold(
+                BindGroupLayoutBuilder::new()
+                    .label("PBR Material Layout")
+                    .uniform(wgpu::ShaderStages::FRAGMENT),
+                |builder, _| {
+                    builder
+                        .texture(
+                            wgpu::ShaderStages::FRAGMENT,
+                            float,
+                            wgpu::TextureViewDimension::D2,
+                        )
+                        .sampler(
+                            wgpu::ShaderStages::FRAGMENT,
+                            wgpu::SamplerBindingType::Filtering,
+                        )
+                },
+            )
+            .build(device)
+    }
+
+    /// 用uniform缓冲区（通常由`create_buffer`创建）和贴图创建绑定组，缺少的贴图用fallbacks代替
+    pub fn bind_group(
+        &self,
+        device: &wgpu::Device,
+        layout: &BindingLayout,
+        fallbacks: &PbrFallbacks,
+        buffer: &wgpu::Buffer,
+    ) -> wgpu::BindGroup {
+        self.textures(fallbacks)
+            .into_iter()
+            .fold(
+                BindGroupBuilder::new(layout)
+                    .label("PBR Material Bind Group")
+                    .buffer(buffer),
+                |builder, texture| builder.texture(&texture.view).sampler(&texture.sampler),
+            )
+            .build(device)
+    }
+
+    /// 按绑定顺序排列的五张贴图（基础色、金属度-粗糙度、法线、遮蔽、自发光），缺少的贴图换成fallbacks中的默认纹理
+    pub fn textures<'a>(&'a self, fallbacks: &'a PbrFallbacks) -> [&'a Texture; 5] {
+        [
+            (&self.base_color_texture, &fallbacks.white),
+            (&self.metallic_roughness_texture, &fallbacks.white_linear),
+            (&self.normal_texture, &fallbacks.flat_normal),
+            (&self.occlusion_texture, &fallbacks.white_linear),
+            (&self.emissive_texture, &fallbacks.white),
+        ]
+        .map(|(texture, fallback)| texture.as_ref().unwrap_or(fallback))
+    }
+}
+
+impl PbrFallbacks {
+    /// 创建三张1x1的默认纹理
+    pub fn new(device: &wgpu::Device, queue: &wgpu::Queue, samplers: &SamplerCache) -> Self {
+        Self {
+            white: Texture::solid(
+                device,
+                queue,
+                samplers,
+                Some("PBR White"),
+                [u8::MAX; 4],
+                wgpu::TextureFormat::Rgba8UnormSrgb,
+            ),
+            white_linear: Texture::solid(
+                device,
+                queue,
+                samplers,
+                Some("PBR White Linear"),
+                [u8::MAX; 4],
+                wgpu::TextureFormat::Rgba8Unorm,
+            ),
+            flat_normal: Texture::solid(
+                device,
+                queue,
+                samplers,
+                Some("PBR Flat Normal"),
+                [128, 128, 255, 255],
+                wgpu::TextureFormat::Rgba8Unorm,
+            ),
+        }
+    }
+}
@@ -0,0 +1,207 @@
+use super::{PbrFallbacks, PbrMaterial, PbrUniform};
+use crate::{SamplerCache, binding::BindingLayout};
+use std::collections::HashMap;
+
+/// 材质句柄：`MaterialRegistry`中材质的编号，按注册顺序从0开始
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct MaterialHandle(u32);
+
+impl MaterialHandle {
+    /// 材质在注册表中的下标
+    pub fn index(self) -> usize {
+        self.0 as usize
+    }
+}
+
+/// 绘制排序键：依次按管线、材质、深度分桶比较，排序后使用同一管线和材质的绘制相邻，
+/// 切换管线和材质绑定组的次数最少；同一材质内按深度从近到远，不透明物体可以尽早被深度测试剔除
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct SortKey(pub u64);
+
+impl SortKey {
+    /// 组合排序键：高16位为管线编号，中间32位为材质编号，低16位为深度分桶
+    ///
+    /// depth为归一化到0..1的深度（0最近），超出范围时钳制，分成65536个桶。
+    pub fn new(pipeline: u16, material: MaterialHandle, depth: f32) -> Self {
+        let bucket = (depth.clamp(0.0, 1.0) * u16::MAX as f32) as u64;
+        Self((pipeline as u64) << 48 | (material.0 as u64) << 16 | bucket)
+    }
+
+    /// 管线编号
+    pub fn pipeline(self) -> u16 {
+        (self.0 >> 48) as u16
+    }
+
+    /// 材质句柄
+    pub fn material(self) -> MaterialHandle {
+        MaterialHandle((self.0 >> 16) as u32)
+    }
+}
+
+/// 材质绑定的统计（`MaterialRegistry::reset_stats`之后的计数）
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct MaterialStats {
+    pub materials: usize,     // 注册的材质数量（去重后）
+    pub binds: u32,           // 实际调用set_bind_group的次数
+    pub redundant_binds: u32, // 材质已经绑定、被跳过的次数
+}
+
+/// 材质的去重键：uniform数据（按位比较）和各贴图的视图及采样器（缺少的贴图为默认纹理）
+type MaterialKey = ([u32; 12], [(wgpu::TextureView, wgpu::Sampler); 5]);
+
+/// 注册的材质：名称、去重键、uniform缓冲区和绑定组
+#[derive(Debug)]
+struct Entry {
+    name: String,
+    key: MaterialKey,
+    buffer: wgpu::Buffer,
+    bind_group: wgpu::BindGroup,
+}
+
+/// 材质注册表：参数和贴图都相同的`PbrMaterial`只创建一个绑定组，用句柄引用
+///
+/// 绘制前先把每个对象的`SortKey`排序，再对每次绘制调用`bind`：材质已经绑定时不再调用`set_bind_group`。
+/// 每个渲染通道开始时调用`begin_pass`（新通道没有任何绑定组），每帧开始时调用`reset_stats`。
+/// 运行时修改参数用`update`，只重写该材质的uniform缓冲区，贴图和绑定组不变。
+#[derive(Debug)]
+pub struct MaterialRegistry {
+    layout: BindingLayout, // 所有材质共用的绑定组布局（见`PbrMaterial::create_layout`）
+    fallbacks: PbrFallbacks, // 材质缺少贴图时使用
+    group: u32,            // 材质绑定组的序号
+    entries: Vec<Entry>,
+    handles: HashMap<MaterialKey, MaterialHandle>, // 去重键 -> 句柄
+    bound: Option<MaterialHandle>,                 // 当前渲染通道中已绑定的材质
+    stats: MaterialStats,
+}
+
+impl MaterialRegistry {
+    /// 创建空的注册表，材质绑定组绑定到第group组
+    pub fn new(
+        device: &wgpu::Device,
+        queue: &wgpu::Queue,
+        samplers: &SamplerCache,
+        group: u32,
+    ) -> Self {
+        Self {
+            layout: PbrMaterial::create_layout(device),
+            fallbacks: PbrFallbacks::new(device, queue, samplers),
+            group,
+            entries: Vec::new(),
+            handles: HashMap::new(),
+            bound: None,
+            stats: MaterialStats::default(),
+        }
+    }
+
+    /// 材质绑定组的布局，用于创建管线布局
+    pub fn layout(&self) -> &BindingLayout {
+        &self.layout
+    }
+
+    /// 注册材质并返回句柄：已有参数和贴图都相同的材质时直接返回它的句柄（名称不参与比较）
+    pub fn intern(&mut self, device: &wgpu::Device, material: &PbrMaterial) -> MaterialHandle {
+        let key = self.key(material);
+        if let Some(&handle) = self.handles.get(&key) {
+            return handle;
+        }
+        let handle = MaterialHandle(self.entries.len() as u32);
+        let buffer = material.create_buffer(device);
+        let bind_group = material.bind_group(device, &self.layout, &self.fallbacks, &buffer);
+        self.handles.insert(key.clone(), handle);
+        self.entries.push(Entry {
+            name: material.name.clone(),
+            key,
+            buffer,
+            bind_group,
+        });
+        handle
+    }
+
+    /// 修改材质的参数：只重写它的uniform缓冲区，贴图和绑定组不变
+    ///
+    /// 修改后与其他材质相同时不会合并（已有的句柄保持有效），之后注册的相同材质使用第一个。
+    pub fn update(
+        &mut self,
+        queue: &wgpu::Queue,
+        handle: MaterialHandle,
+        f: impl FnOnce(&mut PbrUniform),
+    ) {
+        let entry = &mut self.entries[handle.index()];
+        let mut uniform: PbrUniform = bytemuck::cast(entry.key.0);
+        f(&mut uniform);
+        let uniform_bits = bytemuck::cast(uniform);
+        if uniform_bits == entry.key.0 {
+            return;
+        }
+        if self.handles.get(&entry.key) == Some(&handle) {
+            self.handles.remove(&entry.key);
+        }
+        entry.key.0 = uniform_bits;
+        self.handles.entry(entry.key.clone()).or_insert(handle);
+        queue.write_buffer(&entry.buffer, 0, bytemuck::bytes_of(&uniform));
+    }
+
+    /// 材质当前的参数
+    pub fn uniform(&self, handle: MaterialHandle) -> PbrUniform {
+        bytemuck::cast(self.entries[handle.index()].key.0)
+    }
+
+    /// 材质名称（没有时为空）
+    pub fn name(&self, handle: MaterialHandle) -> &str {
+        &self.entries[handle.index()].name
+    }
+
+    /// 注册的材质数量
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// 是否没有注册任何材质
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// 删除所有材质（如替换场景时），之前的句柄全部失效
+    pub fn clear(&mut self) {
+        self.entries.clear();
+        self.handles.clear();
+        self.bound = None;
+    }
+
+    /// 新的渲染通道开始：忘记已绑定的材质
+    pub fn begin_pass(&mut self) {
+        self.bound = None;
+    }
+
+    /// 绑定材质的绑定组，与上一次绑定的材质相同时跳过（计入`redundant_binds`）
+    pub fn bind(&mut self, pass: &mut wgpu::RenderPass<'_>, handle: MaterialHandle) {
+        if self.bound == Some(handle) {
+            self.stats.redundant_binds += 1;
+            return;
+        }
+        pass.set_bind_group(self.group, &self.entries[handle.index()].bind_group, &[]);
+        self.bound = Some(handle);
+        self.stats.binds += 1;
+    }
+
+    /// 自上次`reset_stats`以来的统计
+    pub fn stats(&self) -> MaterialStats {
+        MaterialStats {
+            materials: self.entries.len(),
+            ..self.stats
+        }
+    }
+
+    /// 清零绑定计数，通常在每帧开始时调用
+    pub fn reset_stats(&mut self) {
+        self.stats = MaterialStats::default();
+    }
+
+    /// 材质的去重键，缺少的贴图换成默认纹理
+    fn key(&self, material: &PbrMaterial) -> MaterialKey {
+        let textures = material
+            .textures(&self.fallbacks)
+            .map(|texture| (texture.view.clone(), texture.sampler.clone()));
+        (bytemuck::cast(material.uniform()), textures)
+    }
+}
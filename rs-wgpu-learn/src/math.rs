@@ -0,0 +1,670 @@
+use glam::{Mat3, Mat4, Quat, Vec2, Vec3, Vec4};
+use std::ops::Mul;
+
+/// 射线：起点和单位方向
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Ray {
+    pub origin: Vec3,    // 起点
+    pub direction: Vec3, // 单位方向
+}
+
+impl Ray {
+    /// 创建射线，direction会被归一化
+    pub fn new(origin: Vec3, direction: Vec3) -> Self {
+        Self {
+            origin,
+            direction: direction.normalize_or_zero(),
+        }
+    }
+
+    /// 从屏幕上的点发出的射线：ndc为归一化设备坐标（左下角(-1, -1)，右上角(1, 1)），
+    /// 起点在近平面上，方向指向远平面上的对应点
+    pub fn from_ndc(ndc: Vec2, inv_view_proj: Mat4) -> Self {
+        let near = inv_view_proj.project_point3(ndc.extend(0.0));
+        let far = inv_view_proj.project_point3(ndc.extend(1.0));
+        Self::new(near, far - near)
+    }
+
+    /// 射线上距起点t处的点
+    pub fn at(&self, t: f32) -> Vec3 {
+        self.origin + self.direction * t
+    }
+
+    /// 经过仿射变换后的射线（如把世界空间的射线变换到模型空间），方向不归一化
+    ///
+    /// 保持方向的长度变化，变换后的射线上参数t处的点正好是原射线上t处的点经过变换的结果，
+    /// 所以在模型空间求出的t可以直接用作世界空间的距离。
+    pub fn transform(&self, mat: Mat4) -> Self {
+        Self {
+            origin: mat.transform_point3(self.origin),
+            direction: mat.transform_vector3(self.direction),
+        }
+    }
+
+    /// 射线与包围盒相交时返回射线进入包围盒的参数t（起点在包围盒内时为0），否则返回None
+    ///
+    /// 使用slab方法：依次求射线在每个轴的两个平面之间的区间并取交集。
+    /// 射线与某个轴平行时，起点在该轴的范围外则不相交，否则该轴不限制区间（退化的扁平包围盒也能正确处理）。
+    pub fn intersect_aabb(&self, aabb: &Aabb) -> Option<f32> {
+        if aabb.is_empty() {
+            return None;
+        }
+        let (mut near, mut far) = (0.0f32, f32::INFINITY);
+        for axis in 0..3 {
+            let (origin, direction) = (self.origin[axis], self.direction[axis]);
+            let (min, max) = (aabb.min[axis], aabb.max[axis]);
+            if direction.abs() < f32::EPSILON {
+                if origin < min || origin > max {
+                    return None;
+                }
+                continue;
+            }
+            let (t0, t1) = ((min - origin) / direction, (max - origin) / direction);
+            near = near.max(t0.min(t1));
+            far = far.min(t0.max(t1));
+            if near > far {
+                return None;
+            }
+        }
+        Some(near)
+    }
+
+    /// 射线与三角形abc相交时返回交点的参数t（t ≥ 0，正反两面都算），否则返回None
+    ///
+    /// Möller–Trumbore算法：用重心坐标(u, v)表示交点，解`origin + t × direction = a + u × (b - a) + v × (c - a)`。
+    /// 行列式即方向与三角形法线的混合积，只在射线与三角形平面的夹角的正弦小于`f32::EPSILON`时才视为平行，
+    /// 与三角形的大小无关，掠射角下也不会被误判为不相交。边和顶点上的点算作相交，相邻三角形的公共边不会漏掉。
+    pub fn intersect_triangle(&self, a: Vec3, b: Vec3, c: Vec3) -> Option<f32> {
+        let (edge1, edge2) = (b - a, c - a);
+        let p = self.direction.cross(edge2);
+        let determinant = edge1.dot(p);
+        let scale = edge1.cross(edge2).length() * self.direction.length();
+        if determinant.abs() <= f32::EPSILON * scale {
+            return None;
+        }
+        let inverse = 1.0 / determinant;
+        let s = self.origin - a;
+        let u = s.dot(p) * inverse;
+        if !(0.0..=1.0).contains(&u) {
+            return None;
+        }
+        let q = s.cross(edge1);
+        let v = self.direction.dot(q) * inverse;
+        if v < 0.0 || u + v > 1.0 {
+            return None;
+        }
+        let t = edge2.dot(q) * inverse;
+        (t >= 0.0).then_some(t)
+    }
+
+    /// 直线`origin + s * direction`（direction为单位向量）上离射线最近的点的参数s，
+    /// 两者接近平行时返回None
+    pub fn closest_on_line(&self, origin: Vec3, direction: Vec3) -> Option<f32> {
+        let b = direction.dot(self.direction);
+        let denominator = 1.0 - b * b;
+        if denominator < 1e-6 {
+            return None;
+        }
+        let w = origin - self.origin;
+        let (d, e) = (direction.dot(w), self.direction.dot(w));
+        Some((b * e - d) / denominator)
+    }
+
+    /// 射线与线段ab的最近距离及射线上对应点的参数t（t ≥ 0）
+    pub fn distance_to_segment(&self, a: Vec3, b: Vec3) -> (f32, f32) {
+        let length = a.distance(b);
+        let point = match self.closest_on_line(a, (b - a) / length.max(f32::EPSILON)) {
+            Some(s) => a + (b - a) * (s / length.max(f32::EPSILON)).clamp(0.0, 1.0),
+            // 平行时线段上各点到射线的距离相同，取离起点近的一端
+            None if (a - self.origin).dot(self.direction)
+                <= (b - self.origin).dot(self.direction) =>
+            {
+                a
+            }
+            None => b,
+        };
+        let t = (point - self.origin).dot(self.direction).max(0.0);
+        (self.at(t).distance(point), t)
+    }
+}
+
+/// 轴对齐包围盒：最小点和最大点，最小点大于最大点时为空（见`Aabb::EMPTY`）
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Aabb {
+    pub min: Vec3, // 最小点
+    pub max: Vec3, // 最大点
+}
+
+impl Default for Aabb {
+    /// 空包围盒
+    fn default() -> Self {
+        Self::EMPTY
+    }
+}
+
+impl Aabb {
+    /// 空包围盒：与任何包围盒的并集都是对方，不包含任何点
+    pub const EMPTY: Self = Self {
+        min: Vec3::splat(f32::MAX),
+        max: Vec3::splat(f32::MIN),
+    };
+
+    /// 用最小点和最大点创建包围盒
+    pub fn new(min: Vec3, max: Vec3) -> Self {
+        Self { min, max }
+    }
+
+    /// 包含所有点的最小包围盒，没有点时为空
+    pub fn from_points(points: impl IntoIterator<Item = Vec3>) -> Self {
+        points.into_iter().fold(Self::EMPTY, |aabb, point| Self {
+            min: aabb.min.min(point),
+            max: aabb.max.max(point),
+        })
+    }
+
+    /// 是否为空（某个轴上最小点大于最大点）；退化成平面、线或点的包围盒不算空
+    pub fn is_empty(&self) -> bool {
+        self.min.cmpgt(self.max).any()
+    }
+
+    /// 中心点
+    pub fn center(&self) -> Vec3 {
+        (self.min + self.max) * 0.5
+    }
+
+    /// 半边长（中心到最大点的向量）
+    pub fn extent(&self) -> Vec3 {
+        (self.max - self.min) * 0.5
+    }
+
+    /// 经过仿射变换后的包围盒（包含变换后的原包围盒的最小轴对齐包围盒）
+    ///
+    /// 只变换最小点和最大点在有旋转时是错误的（如旋转45度后两点可能落在同一条竖线上），
+    /// 这里用中心和半边长计算：中心直接变换，新的半边长为变换矩阵左上3x3各元素取绝对值后乘以原半边长，
+    /// 结果与变换8个角再求包围盒相同。
+    pub fn transform(&self, mat: Mat4) -> Self {
+        if self.is_empty() {
+            return Self::EMPTY;
+        }
+        let center = mat.transform_point3(self.center());
+        let extent = self.extent();
+        let extent = mat.x_axis.truncate().abs() * extent.x
+            + mat.y_axis.truncate().abs() * extent.y
+            + mat.z_axis.truncate().abs() * extent.z;
+        Self::new(center - extent, center + extent)
+    }
+
+    /// 同时包含两个包围盒的最小包围盒
+    pub fn union(&self, other: &Self) -> Self {
+        Self::new(self.min.min(other.min), self.max.max(other.max))
+    }
+
+    /// 是否包含点（包括边界）
+    pub fn contains(&self, point: Vec3) -> bool {
+        point.cmpge(self.min).all() && point.cmple(self.max).all()
+    }
+
+    /// 射线与包围盒相交时返回射线进入包围盒的参数t（起点在包围盒内时为0），否则返回None（见`Ray::intersect_aabb`）
+    pub fn ray_intersect(&self, ray: &Ray) -> Option<f32> {
+        ray.intersect_aabb(self)
+    }
+}
+
+/// 视锥体：6个平面（左、右、下、上、近、远），平面为`(法线, d)`，法线指向视锥体内部并已归一化，
+/// 点p在平面内侧（包括平面上）当且仅当`normal · p + d >= 0`（判断时允许`EPSILON`的误差）
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Frustum {
+    pub planes: [Vec4; 6], // 左、右、下、上、近、远平面
+}
+
+impl Frustum {
+    /// 判断点在平面内侧时允许的误差：从矩阵提取的平面有舍入误差（如远平面的d可能比实际距离小1e-6量级），
+    /// 恰好在平面上的点和包围盒不应因此被剔除
+    pub const EPSILON: f32 = 1e-4;
+
+    /// 从视图投影矩阵提取6个平面（Gribb-Hartmann方法），得到的是世界空间的平面
+    ///
+    /// 裁剪空间中点在视锥体内当且仅当-w ≤ x ≤ w、-w ≤ y ≤ w、0 ≤ z ≤ w（wgpu的深度范围为0..1），
+    /// 每个不等式都是矩阵某两行之和或差与世界坐标的点积，即一个平面。近平面对应z ≥ 0，只用第三行。
+    pub fn from_view_proj(view_proj: Mat4) -> Self {
+        let (x, y, z, w) = (
+            view_proj.row(0),
+            view_proj.row(1),
+            view_proj.row(2),
+            view_proj.row(3),
+        );
+        let planes = [w + x, w - x, w + y, w - y, z, w - z].map(|plane| {
+            let length = plane.truncate().length();
+            if length > 0.0 { plane / length } else { plane }
+        });
+        Self { planes }
+    }
+
+    /// 点是否在视锥体内（包括边界）
+    pub fn contains_point(&self, point: Vec3) -> bool {
+        self.planes
+            .iter()
+            .all(|plane| plane.truncate().dot(point) + plane.w >= -Self::EPSILON)
+    }
+
+    /// 包围盒是否与视锥体相交（包括只接触边界），空包围盒不相交
+    ///
+    /// p顶点测试：对每个平面取包围盒在法线方向上最远的角（法线分量为正的轴取最大点，否则取最小点），
+    /// 这个角在平面外侧时整个包围盒都在外侧。结果是保守的：包围盒在视锥体的角附近、
+    /// 没有被任何一个平面完全排除时也会判为相交，只会多画而不会漏画。
+    pub fn intersects_aabb(&self, aabb: &Aabb) -> bool {
+        if aabb.is_empty() {
+            return false;
+        }
+        self.planes.iter().all(|plane| {
+            let normal = plane.truncate();
+            let positive = Vec3::select(normal.cmpge(Vec3::ZERO), aabb.max, aabb.min);
+            normal.dot(positive) + plane.w >= -Self::EPSILON
+        })
+    }
+}
+
+/// 物体的位置、旋转和缩放，对应的矩阵为T × R × S（先缩放，再旋转，最后平移）
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Transform {
+    pub translation: Vec3, // 平移
+    pub rotation: Quat,    // 旋转（单位四元数）
+    pub scale: Vec3,       // 各轴的缩放
+}
+
+impl Default for Transform {
+    /// 单位变换
+    fn default() -> Self {
+        Self::IDENTITY
+    }
+}
+
+impl Transform {
+    /// 单位变换：不平移、不旋转、缩放为1
+    pub const IDENTITY: Self = Self {
+        translation: Vec3::ZERO,
+        rotation: Quat::IDENTITY,
+        scale: Vec3::ONE,
+    };
+
+    /// 只有平移的变换
+    pub fn from_translation(translation: Vec3) -> Self {
+        Self {
+            translation,
+            ..Self::IDENTITY
+        }
+    }
+
+    /// 只有旋转的变换
+    pub fn from_rotation(rotation: Quat) -> Self {
+        Self {
+            rotation,
+            ..Self::IDENTITY
+        }
+    }
+
+    /// 只有缩放的变换
+    pub fn from_scale(scale: Vec3) -> Self {
+        Self {
+            scale,
+            ..Self::IDENTITY
+        }
+    }
+
+    /// 把矩阵分解为平移、旋转和缩放，矩阵含有切变（如非均匀缩放的父节点下的旋转）时结果是近似的
+    pub fn from_matrix(matrix: Mat4) -> Self {
+        let (scale, rotation, translation) = matrix.to_scale_rotation_translation();
+        Self {
+            translation,
+            rotation,
+            scale,
+        }
+    }
+
+    /// 模型矩阵：T × R × S
+    pub fn matrix(&self) -> Mat4 {
+        Mat4::from_scale_rotation_translation(self.scale, self.rotation, self.translation)
+    }
+
+    /// 法线矩阵：模型矩阵左上3x3的逆转置
+    ///
+    /// 非均匀缩放时直接用模型矩阵变换法线会使法线不再垂直于表面（如缩放(2, 1, 1)时斜面的法线会向X轴倾斜），
+    /// 逆转置对旋转不变、把缩放取倒数，结果未归一化，着色器中需要normalize。缩放为0的轴上结果为0。
+    pub fn normal_matrix(&self) -> Mat3 {
+        Mat3::from_quat(self.rotation)
+            * Mat3::from_diagonal(self.scale.recip().map(zero_if_infinite))
+    }
+
+    /// 前方向（-Z轴旋转后的方向，与相机看向-Z的约定一致）
+    pub fn forward(&self) -> Vec3 {
+        self.rotation * Vec3::NEG_Z
+    }
+
+    /// 右方向（+X轴旋转后的方向）
+    pub fn right(&self) -> Vec3 {
+        self.rotation * Vec3::X
+    }
+
+    /// 上方向（+Y轴旋转后的方向）
+    pub fn up(&self) -> Vec3 {
+        self.rotation * Vec3::Y
+    }
+
+    /// 变换一个点
+    pub fn transform_point(&self, point: Vec3) -> Vec3 {
+        self.translation + self.rotation * (self.scale * point)
+    }
+
+    /// 插值：平移和缩放线性插值，旋转球面线性插值，t为0时为self，为1时为end
+    pub fn lerp(&self, end: &Self, t: f32) -> Self {
+        Self {
+            translation: self.translation.lerp(end.translation, t),
+            rotation: self.rotation.slerp(end.rotation, t),
+            scale: self.scale.lerp(end.scale, t),
+        }
+    }
+}
+
+impl Mul for Transform {
+    type Output = Self;
+
+    /// 组合变换：`parent * child`先应用child再应用parent，与矩阵乘法的顺序一致
+    ///
+    /// 子变换的平移经过父变换的完整TRS，旋转和缩放分别相乘。父变换为非均匀缩放且子变换有旋转时，
+    /// 真实结果含有切变，TRS无法表示，此时结果与`parent.matrix() * child.matrix()`不同。
+    fn mul(self, child: Self) -> Self {
+        Self {
+            translation: self.transform_point(child.translation),
+            rotation: self.rotation * child.rotation,
+            scale: self.scale * child.scale,
+        }
+    }
+}
+
+/// 平滑插值系数：t在0..1之间时为3t² - 2t³（两端导数为0，起止都是缓动），超出范围时截断
+pub fn smoothstep(t: f32) -> f32 {
+    let t = t.clamp(0.0, 1.0);
+    t * t * (3.0 - 2.0 * t)
+}
+
+/// 一维Perlin噪声：整数点上的随机梯度之间用五次曲线平滑插值，结果在-1..1之间，整数点上为0
+///
+/// 相同的x和seed总是得到相同的值，不同的seed得到互不相关的噪声（例如每个轴用一个seed）。
+pub fn perlin(x: f32, seed: u32) -> f32 {
+    let cell = x.floor();
+    let f = x - cell;
+    let cell = cell as i32;
+    let fade = f * f * f * (f * (f * 6.0 - 15.0) + 10.0);
+    let start = gradient(cell, seed) * f;
+    let end = gradient(cell.wrapping_add(1), seed) * (f - 1.0);
+    // 梯度在-1..1之间时插值结果不超过0.5，乘2映射到-1..1
+    (start + (end - start) * fade) * 2.0
+}
+
+/// 整数点上的伪随机梯度（-1..1），由整数哈希得到
+fn gradient(cell: i32, seed: u32) -> f32 {
+    let mut h = (cell as u32) ^ seed.wrapping_mul(0x9E37_79B9);
+    h = (h ^ (h >> 16)).wrapping_mul(0x7FEB_352D);
+    h = (h ^ (h >> 15)).wrapping_mul(0x846C_A68B);
+    h ^= h >> 16;
+    h as f32 / u32::MAX as f32 * 2.0 - 1.0
+}
+
+/// 无穷大（除以0得到）替换为0
+fn zero_if_infinite(x: f32) -> f32 {
+    if x.is_finite() { x } else { 0.0 }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::f32::consts::{FRAC_PI_2, FRAC_PI_4, SQRT_2};
+
+    /// 允许的浮点误差
+    const TOLERANCE: f32 = 1e-5;
+
+    #[test]
+    fn rotated_unit_box_grows_to_sqrt_2() {
+        let unit = Aabb::new(Vec3::splat(-1.0), Vec3::ONE);
+        let rotated = unit.transform(Mat4::from_rotation_y(FRAC_PI_4));
+        let extent = rotated.extent();
+        assert!((extent.x - SQRT_2).abs() < TOLERANCE, "{extent}");
+        assert!((extent.y - 1.0).abs() < TOLERANCE, "{extent}");
+        assert!((extent.z - SQRT_2).abs() < TOLERANCE, "{extent}");
+        assert!(rotated.center().length() < TOLERANCE);
+    }
+
+    #[test]
+    fn transform_matches_the_transformed_corners() {
+        let aabb = Aabb::new(Vec3::new(1.0, -2.0, 0.5), Vec3::new(3.0, 1.0, 2.0));
+        let mat = Mat4::from_scale_rotation_translation(
+            Vec3::new(2.0, 0.5, 1.0),
+            Quat::from_euler(glam::EulerRot::YXZ, 0.3, -0.7, 1.1),
+            Vec3::new(-4.0, 2.0, 7.0),
+        );
+        let corners = (0..8).map(|i| {
+            let pick = |bit: usize, axis: usize| {
+                if i & bit != 0 {
+                    aabb.max[axis]
+                } else {
+                    aabb.min[axis]
+                }
+            };
+            mat.transform_point3(Vec3::new(pick(1, 0), pick(2, 1), pick(4, 2)))
+        });
+        let expected = Aabb::from_points(corners);
+        let transformed = aabb.transform(mat);
+        assert!(transformed.min.abs_diff_eq(expected.min, TOLERANCE));
+        assert!(transformed.max.abs_diff_eq(expected.max, TOLERANCE));
+    }
+
+    #[test]
+    fn flat_box_stays_flat() {
+        // 厚度为0的包围盒（如平面网格）变换后仍然是扁平的，而且不算空
+        let flat = Aabb::new(Vec3::new(-1.0, 0.0, -1.0), Vec3::new(1.0, 0.0, 1.0));
+        let moved =
+            flat.transform(Mat4::from_rotation_y(FRAC_PI_4) * Mat4::from_translation(Vec3::Y));
+        assert!(!moved.is_empty());
+        assert_eq!(moved.min.y, moved.max.y);
+        assert!((moved.min.y - 1.0).abs() < TOLERANCE);
+        assert!((moved.extent().x - SQRT_2).abs() < TOLERANCE);
+    }
+
+    #[test]
+    fn empty_box_stays_empty() {
+        let moved = Aabb::EMPTY.transform(Mat4::from_translation(Vec3::ONE));
+        assert!(moved.is_empty());
+    }
+
+    /// 相机在原点看向-Z的正交视锥体：x、y在-1..1，近平面z = -1，远平面z = -10
+    fn box_frustum() -> Frustum {
+        Frustum::from_view_proj(Mat4::orthographic_rh(-1.0, 1.0, -1.0, 1.0, 1.0, 10.0))
+    }
+
+    /// x在min_x..max_x、其余轴在视锥体中间的包围盒
+    fn box_between(min_x: f32, max_x: f32) -> Aabb {
+        Aabb::new(Vec3::new(min_x, -0.5, -5.0), Vec3::new(max_x, 0.5, -4.0))
+    }
+
+    #[test]
+    fn aabb_touching_a_plane_intersects() {
+        let frustum = box_frustum();
+        // 最大点恰好在左平面上
+        assert!(frustum.intersects_aabb(&box_between(-3.0, -1.0)));
+        // 扁平的包围盒恰好在远平面上
+        let on_far = Aabb::new(Vec3::new(-0.5, -0.5, -10.0), Vec3::new(0.5, 0.5, -10.0));
+        assert!(frustum.intersects_aabb(&on_far));
+        // 恰好在近平面上
+        let on_near = Aabb::new(Vec3::new(-0.5, -0.5, -1.0), Vec3::new(0.5, 0.5, -1.0));
+        assert!(frustum.intersects_aabb(&on_near));
+    }
+
+    #[test]
+    fn aabb_behind_the_near_plane_is_culled() {
+        let frustum = box_frustum();
+        // 在相机和近平面之间
+        let before_near = Aabb::new(Vec3::new(-0.5, -0.5, -0.9), Vec3::new(0.5, 0.5, -0.1));
+        assert!(!frustum.intersects_aabb(&before_near));
+        // 在相机后面（透视投影下也一样）
+        let behind = Aabb::new(Vec3::new(-0.5, -0.5, 1.0), Vec3::new(0.5, 0.5, 2.0));
+        assert!(!frustum.intersects_aabb(&behind));
+        let perspective = Frustum::from_view_proj(Mat4::perspective_rh(FRAC_PI_2, 1.0, 1.0, 10.0));
+        assert!(!perspective.intersects_aabb(&behind));
+        assert!(!perspective.intersects_aabb(&before_near));
+        assert!(perspective.intersects_aabb(&box_between(-0.5, 0.5)));
+    }
+
+    #[test]
+    fn plane_tolerance_is_epsilon() {
+        let frustum = box_frustum();
+        // 在左平面外侧不到EPSILON时仍算相交，超出EPSILON时被剔除
+        let inside_tolerance = -1.0 - Frustum::EPSILON * 0.5;
+        let outside_tolerance = -1.0 - Frustum::EPSILON * 2.0;
+        assert!(frustum.intersects_aabb(&box_between(-3.0, inside_tolerance)));
+        assert!(!frustum.intersects_aabb(&box_between(-3.0, outside_tolerance)));
+        assert!(frustum.contains_point(Vec3::new(inside_tolerance, 0.0, -5.0)));
+        assert!(!frustum.contains_point(Vec3::new(outside_tolerance, 0.0, -5.0)));
+    }
+
+    #[test]
+    fn child_rotation_under_parent_scale() {
+        let parent = Transform {
+            translation: Vec3::new(10.0, 0.0, 0.0),
+            scale: Vec3::splat(2.0),
+            ..Transform::IDENTITY
+        };
+        let child = Transform {
+            translation: Vec3::X,
+            rotation: Quat::from_rotation_y(FRAC_PI_2),
+            ..Transform::IDENTITY
+        };
+        let world = parent * child;
+        // 子节点原点：(1, 0, 0)放大2倍后平移到(12, 0, 0)
+        assert!(
+            world
+                .translation
+                .abs_diff_eq(Vec3::new(12.0, 0.0, 0.0), TOLERANCE)
+        );
+        // 子节点的+X先绕Y旋转90度到-Z，加上子节点平移得(1, 0, -1)，再经过父节点得(12, 0, -2)
+        let point = world.transform_point(Vec3::X);
+        assert!(
+            point.abs_diff_eq(Vec3::new(12.0, 0.0, -2.0), TOLERANCE),
+            "{point}"
+        );
+        assert!(point.abs_diff_eq(
+            parent.transform_point(child.transform_point(Vec3::X)),
+            TOLERANCE
+        ));
+    }
+
+    #[test]
+    fn child_translation_under_non_uniform_parent_scale() {
+        // 非均匀缩放下旋转无法精确组合，但子节点的位置仍然经过父节点的完整TRS
+        let parent = Transform {
+            translation: Vec3::Y,
+            rotation: Quat::from_rotation_z(FRAC_PI_2),
+            scale: Vec3::new(2.0, 1.0, 1.0),
+        };
+        let world = parent * Transform::from_translation(Vec3::new(1.0, 1.0, 0.0));
+        // 缩放得(2, 1, 0)，绕Z旋转90度得(-1, 2, 0)，平移得(-1, 3, 0)
+        assert!(
+            world
+                .translation
+                .abs_diff_eq(Vec3::new(-1.0, 3.0, 0.0), TOLERANCE)
+        );
+    }
+
+    #[test]
+    fn mul_matches_matrix_product_for_uniform_scale() {
+        let parent = Transform {
+            translation: Vec3::new(1.0, -2.0, 3.0),
+            rotation: Quat::from_euler(glam::EulerRot::YXZ, 0.4, 0.2, -0.9),
+            scale: Vec3::splat(1.5),
+        };
+        let child = Transform {
+            translation: Vec3::new(-0.5, 2.0, 4.0),
+            rotation: Quat::from_rotation_x(1.2),
+            scale: Vec3::splat(0.25),
+        };
+        let composed = (parent * child).matrix();
+        let expected = parent.matrix() * child.matrix();
+        assert!(
+            composed.abs_diff_eq(expected, TOLERANCE),
+            "{composed} != {expected}"
+        );
+    }
+
+    #[test]
+    fn normal_matrix_keeps_normals_perpendicular_under_non_uniform_scale() {
+        let transform = Transform::from_scale(Vec3::new(2.0, 1.0, 1.0));
+        // 斜面x + y = 0：法线(1, 1, 0)，面内的切线(1, -1, 0)
+        let (normal, tangent) = (Vec3::new(1.0, 1.0, 0.0), Vec3::new(1.0, -1.0, 0.0));
+        let tangent = transform.matrix().transform_vector3(tangent);
+        let transformed = transform.normal_matrix() * normal;
+        assert!(transformed.dot(tangent).abs() < TOLERANCE);
+        // 法线向Y轴倾斜：(0.5, 1, 0)，归一化为(1, 2, 0) / √5
+        let expected = Vec3::new(1.0, 2.0, 0.0).normalize();
+        assert!(transformed.normalize().abs_diff_eq(expected, TOLERANCE));
+        // 直接用模型矩阵变换的法线不再垂直于表面
+        let wrong = transform.matrix().transform_vector3(normal);
+        assert!(wrong.dot(tangent).abs() > 1.0);
+    }
+
+    #[test]
+    fn ray_at_a_glancing_angle_still_hits_a_large_triangle() {
+        // 直角边长1000的三角形在y = 0平面内，射线从高度1处以2度向-X下方射出，约28.6处落到平面上
+        let (a, b, c) = (
+            Vec3::new(-500.0, 0.0, -500.0),
+            Vec3::new(500.0, 0.0, -500.0),
+            Vec3::new(-500.0, 0.0, 500.0),
+        );
+        let angle = 2.0f32.to_radians();
+        let ray = Ray::new(Vec3::Y, Vec3::new(-angle.cos(), -angle.sin(), 0.0));
+        let t = ray
+            .intersect_triangle(a, b, c)
+            .expect("glancing ray missed");
+        let expected = 1.0 / angle.sin();
+        assert!((t - expected).abs() < expected * 1e-4, "{t} != {expected}");
+        assert!(ray.at(t).y.abs() < 1e-4);
+    }
+
+    #[test]
+    fn ray_on_a_shared_edge_hits_both_triangles() {
+        // 正方形沿对角线分成两个三角形，竖直向下的射线正好落在公共边上
+        let (a, b, c, d) = (
+            Vec3::new(-1.0, 0.0, -1.0),
+            Vec3::new(1.0, 0.0, -1.0),
+            Vec3::new(1.0, 0.0, 1.0),
+            Vec3::new(-1.0, 0.0, 1.0),
+        );
+        let ray = Ray::new(Vec3::new(0.25, 2.0, 0.25), Vec3::NEG_Y);
+        for (p, q, r) in [(a, b, c), (a, c, d)] {
+            let t = ray.intersect_triangle(p, q, r).expect("edge point missed");
+            assert!((t - 2.0).abs() < TOLERANCE, "{t}");
+        }
+        // 公共顶点也算相交
+        let ray = Ray::new(Vec3::new(1.0, 2.0, 1.0), Vec3::NEG_Y);
+        assert!(ray.intersect_triangle(a, b, c).is_some());
+        assert!(ray.intersect_triangle(a, c, d).is_some());
+    }
+
+    #[test]
+    fn parallel_ray_misses_the_triangle_and_respects_the_box_slab() {
+        let (a, b, c) = (Vec3::ZERO, Vec3::X, Vec3::Z);
+        // 在三角形平面内和平面上方平行射出
+        for origin in [Vec3::new(-1.0, 0.0, 0.2), Vec3::new(-1.0, 0.5, 0.2)] {
+            assert_eq!(Ray::new(origin, Vec3::X).intersect_triangle(a, b, c), None);
+        }
+        // 射线平行于y轴的两个平面：起点在y范围内时由其他轴决定，范围外时不相交
+        let aabb = Aabb::new(Vec3::splat(-1.0), Vec3::ONE);
+        let inside = Ray::new(Vec3::new(-3.0, 0.5, 0.0), Vec3::X);
+        assert_eq!(inside.intersect_aabb(&aabb), Some(2.0));
+        let outside = Ray::new(Vec3::new(-3.0, 1.5, 0.0), Vec3::X);
+        assert_eq!(outside.intersect_aabb(&aabb), None);
+        // 起点在包围盒内时进入距离为0
+        assert_eq!(
+            Ray::new(Vec3::ZERO, Vec3::X).intersect_aabb(&aabb),
+            Some(0.0)
+        );
+    }
+}
@@ -0,0 +1,331 @@
+/// 基本形状的网格数据，返回顶点和索引，可直接传给`Mesh::new`
+///
+/// 约定：右手坐标系，Y轴向上；所有三角形从外侧看为逆时针（与wgpu默认的`FrontFace::Ccw`一致，
+/// 开启背面剔除后只剔除内侧）。纹理坐标左上角为(0, 0)。
+pub mod shapes;
+mod tangent;
+
+use crate::{
+    VertexPosition, create_vertex_buffer,
+    indirect::{self, DrawIndexedIndirectArgs, DrawIndirectArgs, MultiDrawBatch},
+    math::Aabb,
+};
+use glam::Vec3;
+use std::{any::Any, ops::Range};
+use wgpu::util::DeviceExt;
+
+pub use tangent::{generate_tangents, with_tangents};
+
+/// 索引缓冲区可用的索引类型（u16或u32），FORMAT需与`set_index_buffer`的格式一致
+pub trait MeshIndex: bytemuck::Pod {
+    const FORMAT: wgpu::IndexFormat;
+}
+
+impl MeshIndex for u16 {
+    const FORMAT: wgpu::IndexFormat = wgpu::IndexFormat::Uint16;
+}
+
+impl MeshIndex for u32 {
+    const FORMAT: wgpu::IndexFormat = wgpu::IndexFormat::Uint32;
+}
+
+/// 用索引数据创建索引缓冲区，绘制时使用`I::FORMAT`作为索引格式
+pub fn create_index_buffer<I: MeshIndex>(
+    device: &wgpu::Device,
+    label: Option<&str>,
+    indices: &[I],
+) -> wgpu::Buffer {
+    device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+        label,
+        contents: bytemuck::cast_slice(indices),
+        usage: wgpu::BufferUsages::INDEX,
+    })
+}
+
+/// 网格：顶点缓冲区、可选的索引缓冲区以及绘制所需的数量和格式
+///
+/// 有索引时使用`draw_indexed`，否则按顶点顺序`draw`。顶点数不超过u16的表示范围时索引以u16上传，
+/// 节省一半的索引缓冲区。槽位0是网格自己的顶点缓冲区，实例数据等可以通过`add_vertex_buffer`
+/// 追加到后面的槽位，绘制时一并绑定。创建时记录顶点的模型空间包围盒，用于剔除和拾取；
+/// 用`new_with_cpu_data`创建时还保留一份顶点和索引，供调试显示法线等需要读取顶点的功能使用。
+#[derive(Debug)]
+pub struct Mesh {
+    vertex_buffers: Vec<wgpu::Buffer>, // 顶点缓冲区，下标即槽位（槽位0为网格顶点）
+    index_buffer: Option<wgpu::Buffer>, // 索引缓冲区（没有索引时为None）
+    index_format: wgpu::IndexFormat,   // 索引格式（根据顶点数自动选择）
+    vertex_count: u32,                 // 顶点数量
+    index_count: u32,                  // 索引数量（没有索引时为0）
+    aabb: Aabb,                        // 顶点的模型空间包围盒
+    cpu_vertices: Option<Box<dyn Any + Send + Sync>>, // 保留的顶点（Vec<V>），默认不保留
+    cpu_indices: Option<Box<[u32]>>,   // 保留的索引
+    cpu_positions: Option<Box<[Vec3]>>, // 保留的顶点位置（拾取时与三角形求交）
+}
+
+impl Mesh {
+    /// 上传顶点和索引，索引为空或引用了不存在的顶点时触发调试断言（而不是等到GPU验证报错）
+    pub fn new<V: VertexPosition>(
+        device: &wgpu::Device,
+        label: &str,
+        vertices: &[V],
+        indices: Option<&[u32]>,
+    ) -> Self {
+        let vertex_buffer =
+            create_vertex_buffer(device, Some(&format!("{label} Vertex Buffer")), vertices);
+        let mut mesh = Self::with_buffer(device, label, vertex_buffer, vertices.len(), indices);
+        mesh.aabb = Aabb::from_points(vertices.iter().map(|vertex| vertex.position().into()));
+        mesh
+    }
+
+    /// 与`new`相同，但在CPU端保留一份顶点和索引（可用`cpu_vertices`、`cpu_indices`读取）
+    pub fn new_with_cpu_data<V: VertexPosition + Send + Sync>(
+        device: &wgpu::Device,
+        label: &str,
+        vertices: &[V],
+        indices: Option<&[u32]>,
+    ) -> Self {
+        let mut mesh = Self::new(device, label, vertices, indices);
+        mesh.cpu_vertices = Some(Box::new(vertices.to_vec()));
+        mesh.cpu_indices = indices.map(Box::from);
+        mesh.cpu_positions = Some(
+            vertices
+                .iter()
+                .map(|vertex| vertex.position().into())
+                .collect(),
+        );
+        mesh
+    }
+
+    /// 用已经交错排列好的顶点字节创建网格，stride为每个顶点的字节数（与顶点布局的`array_stride`一致）
+    ///
+    /// 字节中的位置无法解析，包围盒为空，需要时用`set_aabb`设置。
+    pub fn from_raw(
+        device: &wgpu::Device,
+        label: &str,
+        bytes: &[u8],
+        stride: wgpu::BufferAddress,
+        indices: Option<&[u32]>,
+    ) -> Self {
+        debug_assert!(
+            stride > 0 && (bytes.len() as u64).is_multiple_of(stride),
+            "mesh `{label}` has {} bytes of vertex data, not a multiple of the stride {stride}",
+            bytes.len()
+        );
+        let vertex_buffer =
+            create_vertex_buffer(device, Some(&format!("{label} Vertex Buffer")), bytes);
+        let vertex_count = (bytes.len() as u64 / stride.max(1)) as usize;
+        Self::with_buffer(device, label, vertex_buffer, vertex_count, indices)
+    }
+
+    /// 用上传好的顶点缓冲区和索引创建网格
+    fn with_buffer(
+        device: &wgpu::Device,
+        label: &str,
+        vertex_buffer: wgpu::Buffer,
+        vertex_count: usize,
+        indices: Option<&[u32]>,
+    ) -> Self {
+        let index_format = index_format_for(vertex_count);
+        let index_buffer = indices.map(|indices| {
+            debug_assert!(!indices.is_empty(), "mesh `{label}` has no indices");
+            debug_assert!(
+                indices.iter().all(|&index| (index as usize) < vertex_count),
+                "mesh `{label}` has an index beyond its {vertex_count} vertices"
+            );
+            let index_label = format!("{label} Index Buffer");
+            match index_format {
+                wgpu::IndexFormat::Uint16 => {
+                    let indices: Vec<u16> = indices.iter().map(|&index| index as u16).collect();
+                    create_index_buffer(device, Some(&index_label), &indices)
+                }
+                wgpu::IndexFormat::Uint32 => {
+                    create_index_buffer(device, Some(&index_label), indices)
+                }
+            }
+        });
+        Self {
+            vertex_buffers: vec![vertex_buffer],
+            index_buffer,
+            index_format,
+            vertex_count: vertex_count as u32,
+            index_count: indices.map_or(0, |indices| indices.len() as u32),
+            aabb: Aabb::EMPTY,
+            cpu_vertices: None,
+            cpu_indices: None,
+            cpu_positions: None,
+        }
+    }
+
+    /// 追加一个顶点缓冲区（如实例数据），返回它绑定的槽位
+    pub fn add_vertex_buffer(&mut self, buffer: wgpu::Buffer) -> u32 {
+        self.vertex_buffers.push(buffer);
+        self.vertex_buffers.len() as u32 - 1
+    }
+
+    /// 顶点的模型空间包围盒
+    pub fn aabb(&self) -> Aabb {
+        self.aabb
+    }
+
+    /// 设置包围盒（用于`from_raw`创建的网格，或顶点在着色器中被移动的网格）
+    pub fn set_aabb(&mut self, aabb: Aabb) {
+        self.aabb = aabb;
+    }
+
+    /// 保留在CPU端的顶点，没有保留或V与创建时的顶点类型不同时返回None
+    pub fn cpu_vertices<V: 'static>(&self) -> Option<&[V]> {
+        self.cpu_vertices
+            .as_ref()?
+            .downcast_ref::<Vec<V>>()
+            .map(Vec::as_slice)
+    }
+
+    /// 保留在CPU端的索引，没有保留或网格没有索引时返回None
+    pub fn cpu_indices(&self) -> Option<&[u32]> {
+        self.cpu_indices.as_deref()
+    }
+
+    /// 保留在CPU端的三角形（模型空间的三个顶点位置），没有保留时返回None
+    ///
+    /// 按三角形列表解释顶点（有索引时每3个索引一个三角形），末尾不足3个的顶点被忽略。
+    pub fn cpu_triangles(&self) -> Option<impl Iterator<Item = [Vec3; 3]> + '_> {
+        let positions = self.cpu_positions.as_deref()?;
+        let triangles: Box<dyn Iterator<Item = [Vec3; 3]> + '_> = match self.cpu_indices.as_deref()
+        {
+            Some(indices) => Box::new(
+                indices
+                    .chunks_exact(3)
+                    .map(|triangle| [0, 1, 2].map(|corner| positions[triangle[corner] as usize])),
+            ),
+            None => Box::new(
+                positions
+                    .chunks_exact(3)
+                    .map(|triangle| [triangle[0], triangle[1], triangle[2]]),
+            ),
+        };
+        Some(triangles)
+    }
+
+    /// 指定槽位的顶点缓冲区（用于更新实例数据等）
+    pub fn vertex_buffer(&self, slot: u32) -> Option<&wgpu::Buffer> {
+        self.vertex_buffers.get(slot as usize)
+    }
+
+    /// 顶点数量
+    pub fn vertex_count(&self) -> u32 {
+        self.vertex_count
+    }
+
+    /// 索引数量，没有索引时为0
+    pub fn index_count(&self) -> u32 {
+        self.index_count
+    }
+
+    /// 索引格式，没有索引时为None
+    pub fn index_format(&self) -> Option<wgpu::IndexFormat> {
+        self.index_buffer.as_ref().map(|_| self.index_format)
+    }
+
+    /// 绑定所有顶点缓冲区（和索引缓冲区），绘制指定范围的实例
+    pub fn draw(&self, pass: &mut wgpu::RenderPass, instances: Range<u32>) {
+        self.bind(pass);
+        match self.index_buffer {
+            Some(_) => pass.draw_indexed(0..self.index_count, 0, instances),
+            None => pass.draw(0..self.vertex_count, instances),
+        }
+    }
+
+    /// 绘制整个网格指定范围实例的间接绘制参数（有索引的网格使用`indexed_indirect_args`）
+    pub fn indirect_args(&self, instances: Range<u32>) -> DrawIndirectArgs {
+        DrawIndirectArgs {
+            vertex_count: self.vertex_count,
+            instance_count: instances.len() as u32,
+            first_vertex: 0,
+            first_instance: instances.start,
+        }
+    }
+
+    /// 绘制整个网格指定范围实例的带索引间接绘制参数
+    pub fn indexed_indirect_args(&self, instances: Range<u32>) -> DrawIndexedIndirectArgs {
+        DrawIndexedIndirectArgs {
+            index_count: self.index_count,
+            instance_count: instances.len() as u32,
+            first_index: 0,
+            base_vertex: 0,
+            first_instance: instances.start,
+        }
+    }
+
+    /// 绑定所有顶点缓冲区（和索引缓冲区），按buffer中offset处的参数间接绘制
+    ///
+    /// 有索引时参数为`DrawIndexedIndirectArgs`，否则为`DrawIndirectArgs`。
+    /// 调试构建中检查offset的对齐和范围。
+    pub fn draw_indirect(
+        &self,
+        pass: &mut wgpu::RenderPass,
+        buffer: &wgpu::Buffer,
+        offset: wgpu::BufferAddress,
+    ) {
+        self.bind(pass);
+        match self.index_buffer {
+            Some(_) => {
+                indirect::debug_check_offset::<DrawIndexedIndirectArgs>(buffer, offset);
+                pass.draw_indexed_indirect(buffer, offset);
+            }
+            None => {
+                indirect::debug_check_offset::<DrawIndirectArgs>(buffer, offset);
+                pass.draw_indirect(buffer, offset);
+            }
+        }
+    }
+
+    /// 绑定所有顶点缓冲区和索引缓冲区，提交batch中的所有绘制，返回发出的绘制调用数
+    ///
+    /// batch中的参数用`first_index`和`base_vertex`引用本网格的索引和顶点。
+    pub fn draw_batch(&self, pass: &mut wgpu::RenderPass, batch: &MultiDrawBatch) -> u32 {
+        debug_assert!(
+            self.index_buffer.is_some(),
+            "multi-draw batches need an indexed mesh"
+        );
+        self.bind(pass);
+        batch.draw(pass)
+    }
+
+    /// 绑定所有顶点缓冲区和索引缓冲区
+    fn bind(&self, pass: &mut wgpu::RenderPass) {
+        for (slot, buffer) in self.vertex_buffers.iter().enumerate() {
+            pass.set_vertex_buffer(slot as u32, buffer.slice(..));
+        }
+        if let Some(index_buffer) = &self.index_buffer {
+            pass.set_index_buffer(index_buffer.slice(..), self.index_format);
+        }
+    }
+}
+
+/// 根据顶点数选择索引格式：所有索引都能用u16表示时使用u16
+pub fn index_format_for(vertex_count: usize) -> wgpu::IndexFormat {
+    if vertex_count <= u16::MAX as usize + 1 {
+        wgpu::IndexFormat::Uint16
+    } else {
+        wgpu::IndexFormat::Uint32
+    }
+}
+
+/// 顶点位置的包围盒（最小点，最大点），没有顶点时最小点大于最大点
+pub fn bounds(positions: impl IntoIterator<Item = [f32; 3]>) -> ([f32; 3], [f32; 3]) {
+    positions
+        .into_iter()
+        .fold(([f32::MAX; 3], [f32::MIN; 3]), |(min, max), position| {
+            (
+                std::array::from_fn(|i| min[i].min(position[i])),
+                std::array::from_fn(|i| max[i].max(position[i])),
+            )
+        })
+}
+
+/// 展开索引：每个三角形得到三个独立的顶点，用于需要按三角形编号顶点的着色器（如重心坐标线框）
+pub fn deindex<V: Copy>(vertices: &[V], indices: &[u32]) -> Vec<V> {
+    indices
+        .iter()
+        .map(|&index| vertices[index as usize])
+        .collect()
+}
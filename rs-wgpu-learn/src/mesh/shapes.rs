@@ -0,0 +1,363 @@
+use crate::MeshVertex;
+use std::f32::consts::{PI, TAU};
+
+/// XY平面上的正方形，边长为size，法线朝+Z
+pub fn quad(size: f32) -> (Vec<MeshVertex>, Vec<u32>) {
+    let h = size / 2.0;
+    let vertex = |x: f32, y: f32, uv| MeshVertex {
+        position: [x, y, 0.0],
+        normal: [0.0, 0.0, 1.0],
+        uv,
+    };
+    let vertices = vec![
+        vertex(-h, -h, [0.0, 1.0]),
+        vertex(h, -h, [1.0, 1.0]),
+        vertex(h, h, [1.0, 0.0]),
+        vertex(-h, h, [0.0, 0.0]),
+    ];
+    (vertices, vec![0, 1, 2, 0, 2, 3])
+}
+
+/// 以原点为中心的立方体，边长为size
+///
+/// 每个面使用独立的4个顶点，法线垂直于面（棱角分明），每个面的纹理坐标都覆盖整张纹理。
+pub fn cube(size: f32) -> (Vec<MeshVertex>, Vec<u32>) {
+    let h = size / 2.0;
+    // 每个面的法线n和面内的两个方向u、v（u × v = n，保证从外侧看为逆时针）
+    let faces: [([f32; 3], [f32; 3], [f32; 3]); 6] = [
+        ([1.0, 0.0, 0.0], [0.0, 0.0, -1.0], [0.0, 1.0, 0.0]), // +X
+        ([-1.0, 0.0, 0.0], [0.0, 0.0, 1.0], [0.0, 1.0, 0.0]), // -X
+        ([0.0, 1.0, 0.0], [1.0, 0.0, 0.0], [0.0, 0.0, -1.0]), // +Y
+        ([0.0, -1.0, 0.0], [1.0, 0.0, 0.0], [0.0, 0.0, 1.0]), // -Y
+        ([0.0, 0.0, 1.0], [1.0, 0.0, 0.0], [0.0, 1.0, 0.0]),  // +Z
+        ([0.0, 0.0, -1.0], [-1.0, 0.0, 0.0], [0.0, 1.0, 0.0]), // -Z
+    ];
+    let corners = [
+        (-1.0, -1.0, [0.0, 1.0]),
+        (1.0, -1.0, [1.0, 1.0]),
+        (1.0, 1.0, [1.0, 0.0]),
+        (-1.0, 1.0, [0.0, 0.0]),
+    ];
+    let mut vertices = Vec::with_capacity(24);
+    let mut indices = Vec::with_capacity(36);
+    for (n, u, v) in faces {
+        let base = vertices.len() as u32;
+        for (su, sv, uv) in corners {
+            let position = std::array::from_fn(|i| (n[i] + su * u[i] + sv * v[i]) * h);
+            vertices.push(MeshVertex {
+                position,
+                normal: n,
+                uv,
+            });
+        }
+        indices.extend([0, 1, 2, 0, 2, 3].map(|i| base + i));
+    }
+    (vertices, indices)
+}
+
+/// 以原点为中心的UV球，rings为纬线方向的分段数（至少2），segments为经线方向的分段数（至少3）
+///
+/// 经线接缝处的顶点重复一份以便纹理坐标从1回到0；两极的三角形只保留一个（另一个退化），
+/// 极点顶点的u取所在分段的中点，减轻贴图在极点处的扭曲。法线为平滑法线。
+pub fn uv_sphere(radius: f32, rings: u32, segments: u32) -> (Vec<MeshVertex>, Vec<u32>) {
+    let (rings, segments) = (rings.max(2), segments.max(3));
+    let mut vertices = Vec::with_capacity(((rings + 1) * (segments + 1)) as usize);
+    for ring in 0..=rings {
+        let v = ring as f32 / rings as f32;
+        let phi = v * PI; // 与+Y的夹角（0为北极）
+        // 极点上所有顶点重合，u取分段中点
+        let u_offset = if ring == 0 || ring == rings { 0.5 } else { 0.0 };
+        for segment in 0..=segments {
+            let u = (segment as f32 + u_offset) / segments as f32;
+            let theta = u * TAU;
+            // 从外侧看u向右增加
+            let normal = [phi.sin() * theta.cos(), phi.cos(), -phi.sin() * theta.sin()];
+            vertices.push(MeshVertex {
+                position: normal.map(|c| c * radius),
+                normal,
+                uv: [u, v],
+            });
+        }
+    }
+    let stride = segments + 1;
+    let mut indices = Vec::with_capacity((6 * segments * (rings - 1)) as usize);
+    for ring in 0..rings {
+        for segment in 0..segments {
+            let top_left = ring * stride + segment;
+            let bottom_left = top_left + stride;
+            let (top_right, bottom_right) = (top_left + 1, bottom_left + 1);
+            if ring != 0 {
+                indices.extend([top_left, bottom_left, top_right]);
+            }
+            if ring != rings - 1 {
+                indices.extend([top_right, bottom_left, bottom_right]);
+            }
+        }
+    }
+    (vertices, indices)
+}
+
+/// XZ平面上的正方形，边长为size，法线朝+Y，每边分为subdivisions格（至少1）
+///
+/// 纹理坐标覆盖整个平面一次：u沿+X增加，v沿+Z增加。
+pub fn plane(size: f32, subdivisions: u32) -> (Vec<MeshVertex>, Vec<u32>) {
+    let n = subdivisions.max(1);
+    let h = size / 2.0;
+    let mut vertices = Vec::with_capacity(((n + 1) * (n + 1)) as usize);
+    for row in 0..=n {
+        let v = row as f32 / n as f32;
+        for column in 0..=n {
+            let u = column as f32 / n as f32;
+            vertices.push(MeshVertex {
+                position: [-h + u * size, 0.0, -h + v * size],
+                normal: [0.0, 1.0, 0.0],
+                uv: [u, v],
+            });
+        }
+    }
+    let stride = n + 1;
+    let mut indices = Vec::with_capacity((6 * n * n) as usize);
+    for row in 0..n {
+        for column in 0..n {
+            let near_left = row * stride + column; // 较小的z、较小的x
+            let far_left = near_left + stride;
+            let (near_right, far_right) = (near_left + 1, far_left + 1);
+            indices.extend([near_left, far_left, near_right]);
+            indices.extend([near_right, far_left, far_right]);
+        }
+    }
+    (vertices, indices)
+}
+
+/// 绕Y轴的圆环，radius为圆环中心线的半径，tube_radius为管道半径
+///
+/// segments为绕Y轴的分段数，sides为管道截面的分段数（均至少3），接缝处的顶点重复一份。
+pub fn torus(
+    radius: f32,
+    tube_radius: f32,
+    segments: u32,
+    sides: u32,
+) -> (Vec<MeshVertex>, Vec<u32>) {
+    let (segments, sides) = (segments.max(3), sides.max(3));
+    let mut vertices = Vec::with_capacity(((segments + 1) * (sides + 1)) as usize);
+    for segment in 0..=segments {
+        let u = segment as f32 / segments as f32;
+        let theta = u * TAU; // 绕Y轴的角度
+        let (sin_theta, cos_theta) = theta.sin_cos();
+        let center = [radius * cos_theta, 0.0, -radius * sin_theta];
+        for side in 0..=sides {
+            let v = side as f32 / sides as f32;
+            let phi = v * TAU; // 管道截面上的角度（0为外侧赤道）
+            let (sin_phi, cos_phi) = phi.sin_cos();
+            let normal = [cos_phi * cos_theta, sin_phi, -cos_phi * sin_theta];
+            vertices.push(MeshVertex {
+                position: std::array::from_fn(|i| center[i] + tube_radius * normal[i]),
+                normal,
+                uv: [u, v],
+            });
+        }
+    }
+    let stride = sides + 1;
+    let mut indices = Vec::with_capacity((6 * segments * sides) as usize);
+    for segment in 0..segments {
+        for side in 0..sides {
+            let a = segment * stride + side;
+            let b = a + stride; // 下一个分段
+            let (c, d) = (a + 1, b + 1); // 截面上的下一个点
+            indices.extend([a, b, c]);
+            indices.extend([c, b, d]);
+        }
+    }
+    (vertices, indices)
+}
+
+/// 以原点为中心、沿Y轴的圆柱，segments为绕Y轴的分段数（至少3）
+///
+/// 侧面使用平滑法线，上下底面使用独立的顶点和垂直于底面的法线；侧面的接缝处顶点重复一份。
+pub fn cylinder(radius: f32, height: f32, segments: u32) -> (Vec<MeshVertex>, Vec<u32>) {
+    let segments = segments.max(3);
+    let h = height / 2.0;
+    let mut vertices = Vec::with_capacity((4 * (segments + 1) + 2) as usize);
+    let mut indices = Vec::with_capacity((12 * segments) as usize);
+    // 侧面：每个分段上下各一个顶点
+    for segment in 0..=segments {
+        let u = segment as f32 / segments as f32;
+        let (sin, cos) = (u * TAU).sin_cos();
+        let normal = [cos, 0.0, -sin];
+        for (y, v) in [(h, 0.0), (-h, 1.0)] {
+            vertices.push(MeshVertex {
+                position: [radius * cos, y, -radius * sin],
+                normal,
+                uv: [u, v],
+            });
+        }
+    }
+    for segment in 0..segments {
+        let top_left = segment * 2;
+        let (bottom_left, top_right, bottom_right) = (top_left + 1, top_left + 2, top_left + 3);
+        indices.extend([top_left, bottom_left, top_right]);
+        indices.extend([top_right, bottom_left, bottom_right]);
+    }
+    disk(&mut vertices, &mut indices, radius, h, segments);
+    disk(&mut vertices, &mut indices, radius, -h, segments);
+    (vertices, indices)
+}
+
+/// 以原点为中心、沿Y轴的圆锥（顶点在+Y），segments为绕Y轴的分段数（至少3）
+///
+/// 侧面的法线垂直于斜面；尖端每个分段一个顶点，法线取分段中间的方向，避免尖端处的法线退化。
+pub fn cone(radius: f32, height: f32, segments: u32) -> (Vec<MeshVertex>, Vec<u32>) {
+    let segments = segments.max(3);
+    let h = height / 2.0;
+    let slant = (height * height + radius * radius).sqrt().max(f32::EPSILON);
+    let normal_at = |u: f32| {
+        let (sin, cos) = (u * TAU).sin_cos();
+        [height * cos / slant, radius / slant, -height * sin / slant]
+    };
+    let mut vertices = Vec::with_capacity((3 * segments + 2) as usize);
+    let mut indices = Vec::with_capacity((6 * segments) as usize);
+    for segment in 0..segments {
+        let (u0, u1) = (
+            segment as f32 / segments as f32,
+            (segment + 1) as f32 / segments as f32,
+        );
+        let base = vertices.len() as u32;
+        let rim = |u: f32| {
+            let (sin, cos) = (u * TAU).sin_cos();
+            MeshVertex {
+                position: [radius * cos, -h, -radius * sin],
+                normal: normal_at(u),
+                uv: [u, 1.0],
+            }
+        };
+        let middle = (u0 + u1) / 2.0;
+        vertices.extend([
+            MeshVertex {
+                position: [0.0, h, 0.0],
+                normal: normal_at(middle),
+                uv: [middle, 0.0],
+            },
+            rim(u0),
+            rim(u1),
+        ]);
+        indices.extend([base, base + 1, base + 2]);
+    }
+    disk(&mut vertices, &mut indices, radius, -h, segments);
+    (vertices, indices)
+}
+
+/// 在高度y处追加一个水平圆面（y > 0时法线朝+Y，否则朝-Y），用于圆柱和圆锥的底面
+fn disk(
+    vertices: &mut Vec<MeshVertex>,
+    indices: &mut Vec<u32>,
+    radius: f32,
+    y: f32,
+    segments: u32,
+) {
+    let up = y > 0.0;
+    let normal = [0.0, if up { 1.0 } else { -1.0 }, 0.0];
+    let center = vertices.len() as u32;
+    vertices.push(MeshVertex {
+        position: [0.0, y, 0.0],
+        normal,
+        uv: [0.5, 0.5],
+    });
+    for segment in 0..=segments {
+        let (sin, cos) = (segment as f32 / segments as f32 * TAU).sin_cos();
+        vertices.push(MeshVertex {
+            position: [radius * cos, y, -radius * sin],
+            normal,
+            uv: [0.5 + 0.5 * cos, 0.5 + 0.5 * sin],
+        });
+    }
+    for segment in 0..segments {
+        let (a, b) = (center + 1 + segment, center + 2 + segment);
+        // 从外侧看为逆时针：朝上的面按角度增加的顺序，朝下的面相反
+        if up {
+            indices.extend([center, a, b]);
+        } else {
+            indices.extend([center, b, a]);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use glam::Vec3;
+
+    /// 检查索引数、索引范围、法线长度，以及三角形从外侧看为逆时针（面法线与顶点法线同向）
+    fn check((vertices, indices): (Vec<MeshVertex>, Vec<u32>), index_count: usize) {
+        assert_eq!(indices.len(), index_count);
+        assert!(indices.iter().all(|&i| (i as usize) < vertices.len()));
+        for vertex in &vertices {
+            let length = Vec3::from(vertex.normal).length();
+            assert!((length - 1.0).abs() < 1e-5, "normal length {length}");
+        }
+        for triangle in indices.chunks_exact(3) {
+            let [a, b, c] = [0, 1, 2].map(|i| &vertices[triangle[i] as usize]);
+            let [pa, pb, pc] = [a, b, c].map(|v| Vec3::from(v.position));
+            let face = (pb - pa).cross(pc - pa);
+            let normal = Vec3::from(a.normal) + Vec3::from(b.normal) + Vec3::from(c.normal);
+            assert!(face.dot(normal) > 0.0, "clockwise triangle {triangle:?}");
+        }
+    }
+
+    #[test]
+    fn quad() {
+        check(super::quad(2.0), 6);
+    }
+
+    #[test]
+    fn cube() {
+        let (vertices, indices) = super::cube(2.0);
+        assert_eq!(vertices.len(), 24);
+        assert!(
+            vertices
+                .iter()
+                .all(|v| v.position.iter().all(|c| c.abs() == 1.0))
+        );
+        check((vertices, indices), 36);
+    }
+
+    #[test]
+    fn uv_sphere() {
+        let (vertices, indices) = super::uv_sphere(2.0, 8, 12);
+        assert_eq!(vertices.len(), 9 * 13);
+        assert!(
+            vertices
+                .iter()
+                .all(|v| (Vec3::from(v.position).length() - 2.0).abs() < 1e-5)
+        );
+        check((vertices, indices), 6 * 12 * 7);
+        // 过小的分段数被限制到最小值
+        check(super::uv_sphere(1.0, 0, 0), 6 * 3);
+    }
+
+    #[test]
+    fn plane() {
+        let (vertices, indices) = super::plane(4.0, 3);
+        assert_eq!(vertices.len(), 16);
+        check((vertices, indices), 6 * 9);
+        check(super::plane(1.0, 0), 6);
+    }
+
+    #[test]
+    fn torus() {
+        check(super::torus(1.0, 0.25, 16, 8), 6 * 16 * 8);
+    }
+
+    #[test]
+    fn cylinder() {
+        let (vertices, indices) = super::cylinder(0.5, 2.0, 10);
+        assert_eq!(vertices.len(), 2 * 11 + 2 * 12);
+        check((vertices, indices), 12 * 10);
+    }
+
+    #[test]
+    fn cone() {
+        let (vertices, indices) = super::cone(0.5, 1.0, 10);
+        assert_eq!(vertices.len(), 3 * 10 + 12);
+        check((vertices, indices), 6 * 10);
+    }
+}
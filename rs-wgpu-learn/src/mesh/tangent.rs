@@ -0,0 +1,168 @@
+use crate::{MeshVertex, TangentVertex};
+use glam::{Vec2, Vec3};
+
+/// 纹理坐标的行列式小于它的三角形视为退化，不参与切线的累加
+const MIN_UV_AREA: f32 = 1e-12;
+
+/// 为三角形列表计算每个顶点的切线，写入`TangentVertex::tangent`，用于法线贴图
+///
+/// 每个三角形由位置和纹理坐标的变化求出沿u增大的切线和沿v减小的副切线（纹理坐标左上角为(0, 0)，
+/// 法线贴图的绿色通道指向图片上方，与glTF一致），累加到三个顶点上。之后每个顶点的切线对法线做
+/// Gram-Schmidt正交化并归一化，w为副切线相对于cross(法线, 切线)的方向（±1），
+/// 与MikkTSpace的约定相同：着色器中副切线 = cross(法线, 切线) * w，纹理坐标镜像的三角形w为-1，光照不会反转。
+///
+/// indices为空时按顶点顺序每3个组成一个三角形。纹理坐标退化的三角形不参与累加，
+/// 没有有效切线的顶点取任意一个与法线垂直的方向。与完整的MikkTSpace不同，不会拆分顶点：
+/// 镜像接缝两侧共用的顶点切线会互相抵消，这样的网格需要在接缝处使用各自的顶点。
+pub fn generate_tangents(vertices: &mut [TangentVertex], indices: &[u32]) {
+    let corner = |i: usize| {
+        if indices.is_empty() {
+            i
+        } else {
+            indices[i] as usize
+        }
+    };
+    let corner_count = if indices.is_empty() {
+        vertices.len()
+    } else {
+        indices.len()
+    };
+
+    // 1. 按三角形累加切线和（沿v增大的）副切线
+    let mut tangents = vec![Vec3::ZERO; vertices.len()];
+    let mut bitangents = vec![Vec3::ZERO; vertices.len()];
+    for triangle in 0..corner_count / 3 {
+        let [a, b, c] = [0, 1, 2].map(|k| corner(triangle * 3 + k));
+        let position = |i: usize| Vec3::from(vertices[i].position);
+        let uv = |i: usize| Vec2::from(vertices[i].uv);
+        let (e1, e2) = (position(b) - position(a), position(c) - position(a));
+        let (d1, d2) = (uv(b) - uv(a), uv(c) - uv(a));
+        let det = d1.x * d2.y - d2.x * d1.y;
+        if det.abs() < MIN_UV_AREA {
+            continue;
+        }
+        let tangent = (e1 * d2.y - e2 * d1.y) / det;
+        let bitangent = (e2 * d1.x - e1 * d2.x) / det;
+        for i in [a, b, c] {
+            tangents[i] += tangent;
+            bitangents[i] += bitangent;
+        }
+    }
+
+    // 2. 正交化并确定副切线的方向
+    for ((vertex, tangent), bitangent) in vertices.iter_mut().zip(tangents).zip(bitangents) {
+        let normal = Vec3::from(vertex.normal).try_normalize().unwrap_or(Vec3::Z);
+        let tangent = (tangent - normal * normal.dot(tangent))
+            .try_normalize()
+            .unwrap_or_else(|| normal.any_orthonormal_vector());
+        // 法线贴图的+Y指向v减小的方向
+        let w = if normal.cross(tangent).dot(-bitangent) < 0.0 {
+            -1.0
+        } else {
+            1.0
+        };
+        vertex.tangent = tangent.extend(w).to_array();
+    }
+}
+
+/// 把MeshVertex（如`shapes`生成的网格）转换为TangentVertex并计算切线，见`generate_tangents`
+pub fn with_tangents(vertices: &[MeshVertex], indices: &[u32]) -> Vec<TangentVertex> {
+    let mut vertices = vertices
+        .iter()
+        .map(|vertex| TangentVertex {
+            position: vertex.position,
+            normal: vertex.normal,
+            tangent: [0.0; 4],
+            uv: vertex.uv,
+        })
+        .collect::<Vec<_>>();
+    generate_tangents(&mut vertices, indices);
+    vertices
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use glam::Vec4;
+
+    /// 允许的浮点误差
+    const TOLERANCE: f32 = 1e-5;
+
+    /// 法线为+Z的四边形，uv(u)把左上角为(0, 0)的标准纹理坐标映射为顶点的纹理坐标
+    fn quad(uv: impl Fn([f32; 2]) -> [f32; 2]) -> (Vec<TangentVertex>, Vec<u32>) {
+        let vertices = [
+            ([-1.0, -1.0], [0.0, 1.0]),
+            ([1.0, -1.0], [1.0, 1.0]),
+            ([1.0, 1.0], [1.0, 0.0]),
+            ([-1.0, 1.0], [0.0, 0.0]),
+        ]
+        .map(|([x, y], standard)| TangentVertex {
+            position: [x, y, 0.0],
+            normal: [0.0, 0.0, 1.0],
+            tangent: [0.0; 4],
+            uv: uv(standard),
+        });
+        (vertices.to_vec(), vec![0, 1, 2, 0, 2, 3])
+    }
+
+    /// 着色器中重建的副切线：cross(法线, 切线) * w
+    fn bitangent(vertex: &TangentVertex) -> Vec3 {
+        let tangent = Vec4::from(vertex.tangent);
+        Vec3::from(vertex.normal).cross(tangent.truncate()) * tangent.w
+    }
+
+    #[test]
+    fn standard_uvs_give_a_positive_x_tangent() {
+        let (mut vertices, indices) = quad(|uv| uv);
+        generate_tangents(&mut vertices, &indices);
+        for vertex in &vertices {
+            assert!(
+                Vec4::from(vertex.tangent).abs_diff_eq(Vec4::new(1.0, 0.0, 0.0, 1.0), TOLERANCE),
+                "{:?}",
+                vertex.tangent
+            );
+            // 法线贴图的+Y指向图片上方，即v减小的+Y方向
+            assert!(bitangent(vertex).abs_diff_eq(Vec3::Y, TOLERANCE));
+        }
+    }
+
+    #[test]
+    fn mirrored_u_flips_w_but_keeps_the_bitangent() {
+        let (mut vertices, indices) = quad(|[u, v]| [1.0 - u, v]);
+        generate_tangents(&mut vertices, &indices);
+        for vertex in &vertices {
+            assert!(
+                Vec4::from(vertex.tangent).abs_diff_eq(Vec4::new(-1.0, 0.0, 0.0, -1.0), TOLERANCE),
+                "{:?}",
+                vertex.tangent
+            );
+            assert!(bitangent(vertex).abs_diff_eq(Vec3::Y, TOLERANCE));
+        }
+    }
+
+    #[test]
+    fn degenerate_uvs_still_give_a_unit_perpendicular_tangent() {
+        let normal = Vec3::new(1.0, 2.0, 3.0).normalize();
+        let mut vertices = [[0.0, 0.0, 0.0], [1.0, 0.0, 0.0], [0.0, 1.0, 0.0]].map(|position| {
+            TangentVertex {
+                position,
+                normal: normal.to_array(),
+                tangent: [0.0; 4],
+                uv: [0.5, 0.5], // 所有顶点的纹理坐标相同
+            }
+        });
+        generate_tangents(&mut vertices, &[]);
+        for vertex in &vertices {
+            let tangent = Vec4::from(vertex.tangent);
+            assert!(
+                (tangent.truncate().length() - 1.0).abs() < TOLERANCE,
+                "{tangent}"
+            );
+            assert!(
+                tangent.truncate().dot(normal).abs() < TOLERANCE,
+                "{tangent}"
+            );
+            assert!(tangent.w == 1.0 || tangent.w == -1.0);
+        }
+    }
+}
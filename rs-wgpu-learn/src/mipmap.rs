@@ -0,0 +1,152 @@
+use crate::{
+    WgpuAppError,
+    binding::{self, BindGroupBuilder, BindGroupLayoutBuilder, BindingLayout},
+    shader,
+};
+use std::collections::HashMap;
+
+/// 生成mipmap使用的着色器文件
+const SHADER: &str = "mipmap.wgsl";
+
+/// 完整mip链的级数：floor(log2(max(width, height))) + 1，最后一级为1x1
+pub fn mip_level_count(width: u32, height: u32) -> u32 {
+    u32::BITS - width.max(height).max(1).leading_zeros()
+}
+
+/// 第level级的尺寸：每级宽高减半并向下取整，最小为1（与wgpu计算各级尺寸的规则相同）
+pub fn mip_size(width: u32, height: u32, level: u32) -> (u32, u32) {
+    ((width >> level).max(1), (height >> level).max(1))
+}
+
+/// 用渲染管线逐级生成mipmap：第i级的每个像素是第i-1级对应的2x2个像素的平均值
+///
+/// 第i-1级在某个轴上的尺寸为奇数时，该轴上改为按覆盖比例加权读取3个像素（见mipmap.wgsl），
+/// 非2的幂的纹理缩小后也不会偏移或漏掉像素。
+/// 使用渲染而不是计算着色器，sRGB格式（不能作为存储纹理写入）和WebGL2上也能工作，
+/// 平均在线性空间中进行。每种纹理格式的管线在第一次用到时创建并缓存，批量加载纹理时复用同一个生成器
+/// （`Texture::from_rgba8`、`CubeMap::from_faces`等都接受调用方传入的生成器）。
+pub struct MipmapGenerator {
+    shader: wgpu::ShaderModule,
+    layout: BindingLayout, // 上一级的纹理
+    pipeline_layout: wgpu::PipelineLayout,
+    pipelines: HashMap<wgpu::TextureFormat, wgpu::RenderPipeline>, // 按目标格式缓存的管线
+}
+
+impl MipmapGenerator {
+    pub fn new(device: &wgpu::Device) -> Result<Self, WgpuAppError> {
+        let layout = BindGroupLayoutBuilder::new()
+            .label("Mipmap Layout")
+            .texture(
+                wgpu::ShaderStages::FRAGMENT,
+                wgpu::TextureSampleType::Float { filterable: true },
+                wgpu::TextureViewDimension::D2,
+            )
+            .build(device);
+        let pipeline_layout =
+            binding::pipeline_layout(device, Some("Mipmap Pipeline Layout"), &[&layout]);
+        Ok(Self {
+            shader: shader::load(device, SHADER)?,
+            layout,
+            pipeline_layout,
+            pipelines: HashMap::new(),
+        })
+    }
+
+    /// 由第0级生成texture的其余各级（每个数组层分别生成），命令记录到encoder中
+    ///
+    /// texture须为二维纹理，用途包含`TEXTURE_BINDING`和`RENDER_ATTACHMENT`，格式可以渲染且为浮点采样类型
+    /// （如`Rgba8UnormSrgb`、`Rgba8Unorm`、`Rgba16Float`）。只有一级时什么也不做。
+    pub fn generate(
+        &mut self,
+        device: &wgpu::Device,
+        encoder: &mut wgpu::CommandEncoder,
+        texture: &wgpu::Texture,
+    ) {
+        if texture.mip_level_count() <= 1 {
+            return;
+        }
+        let pipeline = self
+            .pipelines
+            .entry(texture.format())
+            .or_insert_with(|| {
+                create_pipeline(
+                    device,
+                    &self.shader,
+                    texture.format(),
+                    &self.pipeline_layout,
+                )
+            })
+            .clone();
+        let level_view = |layer, level| {
+            texture.create_view(&wgpu::TextureViewDescriptor {
+                label: Some("Mipmap Level View"),
+                dimension: Some(wgpu::TextureViewDimension::D2),
+                base_mip_level: level,
+                mip_level_count: Some(1),
+                base_array_layer: layer,
+                array_layer_count: Some(1),
+                ..Default::default()
+            })
+        };
+        for layer in 0..texture.depth_or_array_layers() {
+            for level in 1..texture.mip_level_count() {
+                let bind_group = BindGroupBuilder::new(&self.layout)
+                    .label("Mipmap Bind Group")
+                    .texture(&level_view(layer, level - 1))
+                    .build(device);
+                let target = level_view(layer, level);
+                let mut pass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
+                    label: Some("Mipmap Pass"),
+                    color_attachments: &[Some(wgpu::RenderPassColorAttachment {
+                        view: &target,
+                        resolve_target: None,
+                        ops: wgpu::Operations {
+                            load: wgpu::LoadOp::Clear(wgpu::Color::TRANSPARENT),
+                            store: wgpu::StoreOp::Store,
+                        },
+                    })],
+                    depth_stencil_attachment: None,
+                    timestamp_writes: None,
+                    occlusion_query_set: None,
+                });
+                pass.set_pipeline(&pipeline);
+                pass.set_bind_group(0, &bind_group, &[]);
+                pass.draw(0..3, 0..1);
+            }
+        }
+    }
+}
+
+/// 创建写入format格式的一级mipmap的渲染管线
+fn create_pipeline(
+    device: &wgpu::Device,
+    shader: &wgpu::ShaderModule,
+    format: wgpu::TextureFormat,
+    layout: &wgpu::PipelineLayout,
+) -> wgpu::RenderPipeline {
+    device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
+        label: Some("Mipmap Pipeline"),
+        layout: Some(layout),
+        vertex: wgpu::VertexState {
+            module: shader,
+            entry_point: Some("vs"),
+            buffers: &[],
+            compilation_options: Default::default(),
+        },
+        fragment: Some(wgpu::FragmentState {
+            module: shader,
+            entry_point: Some("fs"),
+            targets: &[Some(wgpu::ColorTargetState {
+                format,
+                blend: None,
+                write_mask: wgpu::ColorWrites::ALL,
+            })],
+            compilation_options: Default::default(),
+        }),
+        primitive: Default::default(),
+        depth_stencil: None,
+        multisample: Default::default(),
+        multiview: None,
+        cache: None,
+    })
+}
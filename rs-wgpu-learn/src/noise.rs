@@ -0,0 +1,330 @@
+use crate::{
+    SamplerCache, VolumeTexture, WgpuAppError,
+    binding::{self, BindGroupBuilder, BindGroupLayoutBuilder, BindingLayout},
+    shader,
+};
+use log::{info, warn};
+use wgpu::util::DeviceExt;
+
+/// 生成噪声体积的计算着色器文件
+const SHADER: &str = "noise_volume.wgsl";
+
+/// 计算着色器每个方向的工作组大小（与着色器中的@workgroup_size一致）
+const WORKGROUP_SIZE: u32 = 4;
+
+/// 梯度噪声晶格点上的12个梯度方向（立方体的棱的中点，与着色器中的GRADIENTS一致）
+const GRADIENTS: [[f32; 3]; 12] = [
+    [1.0, 1.0, 0.0],
+    [-1.0, 1.0, 0.0],
+    [1.0, -1.0, 0.0],
+    [-1.0, -1.0, 0.0],
+    [1.0, 0.0, 1.0],
+    [-1.0, 0.0, 1.0],
+    [1.0, 0.0, -1.0],
+    [-1.0, 0.0, -1.0],
+    [0.0, 1.0, 1.0],
+    [0.0, -1.0, 1.0],
+    [0.0, 1.0, -1.0],
+    [0.0, -1.0, -1.0],
+];
+
+/// 可平铺的分形噪声参数
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct NoiseParams {
+    pub seed: u32,    // 随机种子，不同的种子得到不相关的噪声
+    pub period: u32,  // 第一个八度在体积每个方向上的晶格数（之后每个八度翻倍）
+    pub octaves: u32, // 叠加的八度数
+}
+
+impl Default for NoiseParams {
+    fn default() -> Self {
+        Self {
+            seed: 0,
+            period: 4,
+            octaves: 4,
+        }
+    }
+}
+
+/// 与着色器中NoiseParams对应的数据
+#[repr(C)]
+#[derive(Debug, Clone, Copy, bytemuck::Pod, bytemuck::Zeroable)]
+struct NoiseUniform {
+    size: u32,
+    seed: u32,
+    period: u32,
+    octaves: u32,
+}
+
+/// 计算着色器生成噪声所需的资源
+struct ComputeNoise {
+    pipeline: wgpu::ComputePipeline,
+    layout: BindingLayout, // 参数uniform和R16Float的三维存储纹理
+}
+
+/// 可平铺的三维噪声体积生成器：把分形Perlin噪声（0..1）写入`R16Float`的`VolumeTexture`
+///
+/// 噪声的晶格在体积的边界处回绕，体积在三个方向上都可以无缝平铺（配合重复寻址的采样器滚动）。
+/// 优先用计算着色器写入三维存储纹理；`R16Float`不在WebGPU保证可写的存储格式中，需要
+/// `TEXTURE_ADAPTER_SPECIFIC_FORMAT_FEATURES`且适配器支持，没有计算着色器或存储纹理（如WebGL2）时也不可用，
+/// 这些情况下改为在CPU上用相同的哈希和梯度计算后上传，结果与计算着色器一致（只差浮点误差）。
+pub struct NoiseGenerator {
+    compute: Option<ComputeNoise>, // 不支持写入三维存储纹理时为None
+}
+
+impl NoiseGenerator {
+    /// 噪声体积的格式
+    pub const FORMAT: wgpu::TextureFormat = wgpu::TextureFormat::R16Float;
+
+    /// 检查设备能否用计算着色器写入`R16Float`三维存储纹理（见`check_storage_support`），
+    /// 不能时记录原因并使用CPU回退
+    pub fn new(
+        adapter: &wgpu::Adapter,
+        device: &wgpu::Device,
+        cache: Option<&wgpu::PipelineCache>,
+    ) -> Self {
+        let compute =
+            check_storage_support(adapter, device).and_then(|()| ComputeNoise::new(device, cache));
+        let compute = match compute {
+            Ok(compute) => {
+                info!("Generating noise volumes with a compute shader");
+                Some(compute)
+            }
+            Err(e) => {
+                warn!(
+                    "Cannot write R16Float 3D storage textures ({e}), generating noise on the CPU"
+                );
+                None
+            }
+        };
+        Self { compute }
+    }
+
+    /// 是否使用计算着色器生成
+    pub fn uses_compute(&self) -> bool {
+        self.compute.is_some()
+    }
+
+    /// 创建size³的噪声体积，使用计算着色器时带`STORAGE_BINDING`用途
+    pub fn create_volume(
+        &self,
+        device: &wgpu::Device,
+        samplers: &SamplerCache,
+        label: Option<&str>,
+        size: u32,
+    ) -> Result<VolumeTexture, WgpuAppError> {
+        let usage = match self.compute {
+            Some(_) => wgpu::TextureUsages::STORAGE_BINDING,
+            None => wgpu::TextureUsages::empty(),
+        };
+        VolumeTexture::new(
+            device,
+            samplers,
+            label,
+            wgpu::Extent3d {
+                width: size,
+                height: size,
+                depth_or_array_layers: size,
+            },
+            Self::FORMAT,
+            usage,
+        )
+    }
+
+    /// 用params生成噪声并写入volume（须由`create_volume`创建），支持时用计算着色器，否则见`generate_on_cpu`
+    pub fn generate(
+        &self,
+        device: &wgpu::Device,
+        queue: &wgpu::Queue,
+        volume: &VolumeTexture,
+        params: NoiseParams,
+    ) {
+        let Some(compute) = &self.compute else {
+            self.generate_on_cpu(queue, volume, params);
+            return;
+        };
+        let size = volume.size().width;
+        let uniform = NoiseUniform {
+            size,
+            seed: params.seed,
+            period: params.period,
+            octaves: params.octaves,
+        };
+        let buffer = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+            label: Some("Noise Params Buffer"),
+            contents: bytemuck::bytes_of(&uniform),
+            usage: wgpu::BufferUsages::UNIFORM,
+        });
+        let bind_group = BindGroupBuilder::new(&compute.layout)
+            .label("Noise Bind Group")
+            .buffer(&buffer)
+            .texture(&volume.view)
+            .build(device);
+        let mut encoder = device.create_command_encoder(&wgpu::CommandEncoderDescriptor {
+            label: Some("Noise Encoder"),
+        });
+        {
+            let mut pass = encoder.begin_compute_pass(&wgpu::ComputePassDescriptor {
+                label: Some("Noise Pass"),
+                timestamp_writes: None,
+            });
+            pass.set_pipeline(&compute.pipeline);
+            pass.set_bind_group(0, &bind_group, &[]);
+            let groups = size.div_ceil(WORKGROUP_SIZE);
+            pass.dispatch_workgroups(groups, groups, groups);
+        }
+        queue.submit([encoder.finish()]);
+    }
+
+    /// 在CPU上生成噪声并上传，与计算着色器的结果一致；不支持计算着色器时`generate`调用它
+    pub fn generate_on_cpu(
+        &self,
+        queue: &wgpu::Queue,
+        volume: &VolumeTexture,
+        params: NoiseParams,
+    ) {
+        let size = volume.size().width;
+        let texels = noise_volume(size, params)
+            .into_iter()
+            .map(half::f16::from_f32)
+            .collect::<Vec<_>>();
+        volume.write(queue, bytemuck::cast_slice(&texels));
+    }
+}
+
+impl ComputeNoise {
+    /// 创建绑定组布局和计算管线，调用前须通过`check_storage_support`
+    fn new(
+        device: &wgpu::Device,
+        cache: Option<&wgpu::PipelineCache>,
+    ) -> Result<Self, WgpuAppError> {
+        let layout = BindGroupLayoutBuilder::new()
+            .label("Noise Layout")
+            .uniform(wgpu::ShaderStages::COMPUTE)
+            .storage_texture(
+                wgpu::ShaderStages::COMPUTE,
+                NoiseGenerator::FORMAT,
+                wgpu::StorageTextureAccess::WriteOnly,
+                wgpu::TextureViewDimension::D3,
+            )
+            .try_build(device)?;
+        let shader = shader::load(device, SHADER)?;
+        let pipeline_layout =
+            binding::pipeline_layout(device, Some("Noise Pipeline Layout"), &[&layout]);
+        let pipeline = device.create_compute_pipeline(&wgpu::ComputePipelineDescriptor {
+            label: Some("Noise Pipeline"),
+            layout: Some(&pipeline_layout),
+            module: &shader,
+            entry_point: Some("cs"),
+            compilation_options: Default::default(),
+            cache,
+        });
+        Ok(Self { pipeline, layout })
+    }
+}
+
+/// 检查能否用计算着色器写入`NoiseGenerator::FORMAT`的三维存储纹理
+///
+/// 需要计算着色器、每个阶段至少一个存储纹理、设备启用`TEXTURE_ADAPTER_SPECIFIC_FORMAT_FEATURES`，
+/// 并且适配器报告该格式允许`STORAGE_BINDING`用途（与`check_sample_count`一样直接查询适配器）。
+/// GL后端把三维存储纹理只绑定为第0层（其余层不会被写入），也改用CPU生成。
+fn check_storage_support(
+    adapter: &wgpu::Adapter,
+    device: &wgpu::Device,
+) -> Result<(), WgpuAppError> {
+    let downlevel = adapter.get_downlevel_capabilities();
+    if !downlevel
+        .flags
+        .contains(wgpu::DownlevelFlags::COMPUTE_SHADERS)
+        || device.limits().max_storage_textures_per_shader_stage == 0
+        || adapter.get_info().backend == wgpu::Backend::Gl
+    {
+        return Err(WgpuAppError::UnsupportedTextureUsage {
+            format: NoiseGenerator::FORMAT,
+            usage: wgpu::TextureUsages::STORAGE_BINDING,
+        });
+    }
+    let feature = wgpu::Features::TEXTURE_ADAPTER_SPECIFIC_FORMAT_FEATURES;
+    if !device.features().contains(feature) {
+        return Err(WgpuAppError::MissingFeatures(feature));
+    }
+    let usages = adapter
+        .get_texture_format_features(NoiseGenerator::FORMAT)
+        .allowed_usages;
+    if !usages.contains(wgpu::TextureUsages::STORAGE_BINDING) {
+        return Err(WgpuAppError::UnsupportedTextureUsage {
+            format: NoiseGenerator::FORMAT,
+            usage: wgpu::TextureUsages::STORAGE_BINDING,
+        });
+    }
+    Ok(())
+}
+
+/// 在CPU上计算size³个纹素的噪声值（0..1），顺序与`VolumeTexture::write`一致
+pub fn noise_volume(size: u32, params: NoiseParams) -> Vec<f32> {
+    let mut texels = Vec::with_capacity((size * size * size) as usize);
+    for z in 0..size {
+        for y in 0..size {
+            for x in 0..size {
+                let uvw = [x, y, z].map(|i| (i as f32 + 0.5) / size as f32);
+                texels.push(fractal_noise(uvw, params));
+            }
+        }
+    }
+    texels
+}
+
+/// 体积坐标uvw（0..1）处的分形噪声：各八度的晶格数从period开始翻倍、振幅减半，结果映射到0..1
+fn fractal_noise(uvw: [f32; 3], params: NoiseParams) -> f32 {
+    let (mut sum, mut total) = (0.0, 0.0);
+    let mut amplitude = 1.0;
+    for octave in 0..params.octaves {
+        let period = params.period << octave;
+        let p = uvw.map(|t| t * period as f32);
+        sum += amplitude * perlin(p, period, params.seed.wrapping_add(octave));
+        total += amplitude;
+        amplitude *= 0.5;
+    }
+    if total == 0.0 {
+        return 0.5;
+    }
+    (0.5 + 0.5 * sum / total).clamp(0.0, 1.0)
+}
+
+/// 周期为period个晶格的三维Perlin噪声（约-1..1），晶格坐标按period回绕，因此可以平铺
+fn perlin(p: [f32; 3], period: u32, seed: u32) -> f32 {
+    let cell = p.map(|t| t.floor());
+    let f = [p[0] - cell[0], p[1] - cell[1], p[2] - cell[2]];
+    let cell = cell.map(|t| t as u32);
+    // 8个角上梯度与相对位置的点积，按五次平滑曲线三线性插值
+    let corner = |dx: u32, dy: u32, dz: u32| {
+        let hash = hash(
+            (cell[0] + dx) % period,
+            (cell[1] + dy) % period,
+            (cell[2] + dz) % period,
+            seed,
+        );
+        let g = GRADIENTS[(hash % 12) as usize];
+        g[0] * (f[0] - dx as f32) + g[1] * (f[1] - dy as f32) + g[2] * (f[2] - dz as f32)
+    };
+    let [u, v, w] = f.map(|t| t * t * t * (t * (t * 6.0 - 15.0) + 10.0));
+    let lerp = |a: f32, b: f32, t: f32| a + (b - a) * t;
+    let x00 = lerp(corner(0, 0, 0), corner(1, 0, 0), u);
+    let x10 = lerp(corner(0, 1, 0), corner(1, 1, 0), u);
+    let x01 = lerp(corner(0, 0, 1), corner(1, 0, 1), u);
+    let x11 = lerp(corner(0, 1, 1), corner(1, 1, 1), u);
+    lerp(lerp(x00, x10, v), lerp(x01, x11, v), w)
+}
+
+/// 晶格点的整数哈希（与着色器中的hash一致，乘法按u32回绕）
+fn hash(x: u32, y: u32, z: u32, seed: u32) -> u32 {
+    let mut h = x.wrapping_mul(0x8da6b343)
+        ^ y.wrapping_mul(0xd8163841)
+        ^ z.wrapping_mul(0xcb1ab31f)
+        ^ seed.wrapping_mul(0x165667b1);
+    h ^= h >> 16;
+    h = h.wrapping_mul(0x7feb352d);
+    h ^= h >> 15;
+    h = h.wrapping_mul(0x846ca68b);
+    h ^ (h >> 16)
+}
@@ -0,0 +1,174 @@
+mod pass;
+
+use crate::{
+    Mesh,
+    math::{Aabb, Ray},
+};
+use glam::{Mat4, Vec3};
+pub use pass::{ObjectId, PickingPass};
+
+/// 拾取结果
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PickHit {
+    pub object: usize,  // 被拾取的物体在传给`pick`的序列中的下标
+    pub distance: f32,  // 射线起点到交点的距离（世界空间）
+    pub position: Vec3, // 交点（世界空间）
+}
+
+/// 可以被`pick`拾取的形状：模型空间的包围盒，以及可选的三角形
+pub trait Pickable {
+    /// 模型空间的包围盒
+    fn aabb(&self) -> Aabb;
+
+    /// 模型空间的三角形，没有时以包围盒的交点作为拾取结果
+    fn triangles(&self) -> Option<impl Iterator<Item = [Vec3; 3]> + '_>;
+}
+
+impl Pickable for Mesh {
+    fn aabb(&self) -> Aabb {
+        Mesh::aabb(self)
+    }
+
+    /// 保留了CPU数据的网格（见`Mesh::new_with_cpu_data`）的三角形
+    fn triangles(&self) -> Option<impl Iterator<Item = [Vec3; 3]> + '_> {
+        self.cpu_triangles()
+    }
+}
+
+/// 通过包围盒测试、等待与三角形求交的物体
+struct Candidate<'a, P> {
+    entry: f32, // 射线进入包围盒的距离（三角形交点不会比它近）
+    object: usize,
+    ray: Ray, // 变换到模型空间的射线
+    shape: &'a P,
+}
+
+/// 用射线拾取最近的物体，objects为每个物体的模型矩阵和网格（或其他`Pickable`），
+/// ray的方向须为单位向量（如`camera::screen_to_ray`的结果）
+///
+/// 先把射线变换到各物体的模型空间与网格的包围盒求交，再按进入包围盒的距离从近到远逐个与三角形求交，
+/// 已有的交点比下一个包围盒还近时停止，所以物体重叠时也能得到最近的交点，而不是最近的包围盒。
+/// 保留了CPU数据的网格（见`Mesh::new_with_cpu_data`）与三角形求交，其他网格以包围盒的交点作为结果。
+/// 模型矩阵不可逆（如某个轴的缩放为0）的物体被跳过。
+pub fn pick<'a, P: Pickable + 'a>(
+    objects: impl IntoIterator<Item = (Mat4, &'a P)>,
+    ray: &Ray,
+) -> Option<PickHit> {
+    // 1. 包围盒测试：模型空间中的射线方向不归一化，求出的参数就是世界空间的距离（见`Ray::transform`）
+    let mut candidates = objects
+        .into_iter()
+        .enumerate()
+        .filter_map(|(object, (transform, shape))| {
+            let inverse = transform.inverse();
+            if !inverse.is_finite() {
+                return None;
+            }
+            let ray = ray.transform(inverse);
+            let entry = ray.intersect_aabb(&shape.aabb())?;
+            Some(Candidate {
+                entry,
+                object,
+                ray,
+                shape,
+            })
+        })
+        .collect::<Vec<_>>();
+    candidates.sort_by(|a, b| a.entry.total_cmp(&b.entry));
+
+    // 2. 从近到远与三角形求交
+    let mut best: Option<PickHit> = None;
+    for candidate in candidates {
+        if best.is_some_and(|hit| candidate.entry > hit.distance) {
+            break;
+        }
+        let distance = match candidate.shape.triangles() {
+            Some(triangles) => triangles
+                .filter_map(|[a, b, c]| candidate.ray.intersect_triangle(a, b, c))
+                .min_by(f32::total_cmp),
+            None => Some(candidate.entry),
+        };
+        let Some(distance) = distance else {
+            continue;
+        };
+        if best.is_none_or(|hit| distance < hit.distance) {
+            best = Some(PickHit {
+                object: candidate.object,
+                distance,
+                position: ray.at(distance),
+            });
+        }
+    }
+    best
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// 允许的浮点误差
+    const TOLERANCE: f32 = 1e-5;
+
+    /// 只有三角形的拾取形状，包围盒由顶点求出
+    struct Triangles(Vec<[Vec3; 3]>);
+
+    impl Pickable for Triangles {
+        fn aabb(&self) -> Aabb {
+            Aabb::from_points(self.0.iter().flatten().copied())
+        }
+
+        fn triangles(&self) -> Option<impl Iterator<Item = [Vec3; 3]> + '_> {
+            Some(self.0.iter().copied())
+        }
+    }
+
+    /// z = depth平面上覆盖原点附近的三角形
+    fn facing_triangle(depth: f32) -> [Vec3; 3] {
+        [
+            Vec3::new(-1.0, -1.0, depth),
+            Vec3::new(1.0, -1.0, depth),
+            Vec3::new(0.0, 1.0, depth),
+        ]
+    }
+
+    #[test]
+    fn overlapping_objects_pick_the_nearest_triangle_not_the_nearest_box() {
+        // 物体0的包围盒从z = -1开始（旁边的小三角形不在射线上），射线上的三角形在z = -10；
+        // 物体1的包围盒在z = -5才进入，但三角形更近
+        let far = Triangles(vec![
+            facing_triangle(-10.0),
+            [
+                Vec3::new(0.5, 0.5, -1.0),
+                Vec3::new(0.9, 0.5, -1.0),
+                Vec3::new(0.9, 0.9, -1.0),
+            ],
+        ]);
+        let near = Triangles(vec![facing_triangle(-5.0)]);
+        let ray = Ray::new(Vec3::ZERO, Vec3::NEG_Z);
+        assert!(far.aabb().min.z < near.aabb().min.z && far.aabb().max.z > near.aabb().max.z);
+
+        let objects = [(Mat4::IDENTITY, &far), (Mat4::IDENTITY, &near)];
+        let hit = pick(objects, &ray).expect("ray missed both objects");
+        assert_eq!(hit.object, 1);
+        assert!((hit.distance - 5.0).abs() < TOLERANCE, "{}", hit.distance);
+        assert!(
+            hit.position
+                .abs_diff_eq(Vec3::new(0.0, 0.0, -5.0), TOLERANCE)
+        );
+    }
+
+    #[test]
+    fn scaled_object_reports_the_world_space_distance() {
+        // 模型空间z = 1的三角形放大3倍后移到z = -10，世界空间中位于z = -7
+        let shape = Triangles(vec![facing_triangle(1.0)]);
+        let transform =
+            Mat4::from_translation(Vec3::new(0.0, 0.0, -10.0)) * Mat4::from_scale(Vec3::splat(3.0));
+        let ray = Ray::new(Vec3::new(0.0, 0.0, 2.0), Vec3::NEG_Z);
+        let hit = pick([(transform, &shape)], &ray).expect("ray missed the scaled object");
+        assert_eq!(hit.object, 0);
+        assert!((hit.distance - 9.0).abs() < TOLERANCE, "{}", hit.distance);
+        assert!(
+            hit.position
+                .abs_diff_eq(Vec3::new(0.0, 0.0, -7.0), TOLERANCE)
+        );
+    }
+}
@@ -0,0 +1,223 @@
+use crate::DepthTexture;
+use glam::Vec2;
+use parking_lot::Mutex;
+use std::sync::Arc;
+
+/// ID缓冲区中的物体ID，0表示光标下没有物体
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub struct ObjectId(pub u32);
+
+impl ObjectId {
+    /// 没有物体
+    pub const NONE: Self = Self(0);
+
+    /// 第index个物体的ID（从1开始，跳过表示没有物体的0）
+    pub fn from_index(index: usize) -> Self {
+        Self(index as u32 + 1)
+    }
+
+    /// ID对应的物体下标，没有物体时返回None
+    pub fn index(self) -> Option<usize> {
+        self.0.checked_sub(1).map(|index| index as usize)
+    }
+
+    /// 是否表示没有物体
+    pub fn is_none(self) -> bool {
+        self.0 == 0
+    }
+}
+
+/// 读回的状态：请求的像素先在某一帧复制到读回缓冲区，提交之后的帧才能映射
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum State {
+    Idle,    // 读回缓冲区空闲
+    Copied,  // 复制命令已记录，等待提交后映射
+    Mapping, // 正在异步映射
+}
+
+/// GPU拾取：把物体ID绘制到`R32Uint`离屏目标上，读回光标下的像素
+///
+/// `request`记录光标位置，之后第一次`render`只在光标处1×1的裁剪矩形内绘制ID（带独立的深度缓冲，最前面的物体胜出）
+/// 并把该像素复制到读回缓冲区，下一次`render`开始异步映射，结果通常在一两帧后由`poll`返回，不会阻塞渲染。
+/// ID管线的片元着色器输出`u32`，颜色目标为`FORMAT`、深度状态为`DepthTexture::depth_stencil_state()`、不开启多重采样。
+/// 表面尺寸变化时调用`resize`重新创建离屏目标。
+pub struct PickingPass {
+    device: wgpu::Device,           // 用于在poll中推进异步映射
+    target: wgpu::Texture,          // 与表面同样大小的ID目标
+    target_view: wgpu::TextureView, // ID目标的视图（颜色附件）
+    depth: DepthTexture,            // ID通道专用的深度缓冲（单采样）
+    readback_buffer: wgpu::Buffer,  // 一个像素的可映射读回缓冲区
+    requested: Option<Vec2>,        // 等待绘制的光标位置（物理像素）
+    state: State,                   // 读回进度
+    map_result: Arc<Mutex<Option<Result<(), wgpu::BufferAsyncError>>>>, // map_async回调结果
+}
+
+impl PickingPass {
+    /// ID目标的格式
+    pub const FORMAT: wgpu::TextureFormat = wgpu::TextureFormat::R32Uint;
+
+    pub fn new(device: &wgpu::Device, config: &wgpu::SurfaceConfiguration) -> Self {
+        let (target, target_view, depth) = create_targets(device, config);
+        Self {
+            device: device.clone(),
+            target,
+            target_view,
+            depth,
+            readback_buffer: device.create_buffer(&wgpu::BufferDescriptor {
+                label: Some("Picking Readback Buffer"),
+                size: Self::FORMAT.block_copy_size(None).unwrap_or(4) as u64,
+                usage: wgpu::BufferUsages::MAP_READ | wgpu::BufferUsages::COPY_DST,
+                mapped_at_creation: false,
+            }),
+            requested: None,
+            state: State::Idle,
+            map_result: Default::default(),
+        }
+    }
+
+    /// ID管线的颜色目标（整数格式不支持混合）
+    pub fn color_target() -> wgpu::ColorTargetState {
+        wgpu::ColorTargetState {
+            format: Self::FORMAT,
+            blend: None,
+            write_mask: wgpu::ColorWrites::ALL,
+        }
+    }
+
+    /// 表面尺寸变化后重新创建离屏目标，进行中的读回不受影响
+    pub fn resize(&mut self, device: &wgpu::Device, config: &wgpu::SurfaceConfiguration) {
+        (self.target, self.target_view, self.depth) = create_targets(device, config);
+    }
+
+    /// 请求拾取cursor（物理像素）处的物体，替换尚未绘制的请求
+    pub fn request(&mut self, cursor: Vec2) {
+        self.requested = Some(cursor);
+    }
+
+    /// 是否有尚未返回结果的请求
+    pub fn is_pending(&self) -> bool {
+        self.requested.is_some() || self.state != State::Idle
+    }
+
+    /// 每帧在主通道之外调用：开始映射上一帧复制的像素，或在有请求时用draw绘制ID并复制光标处的像素
+    ///
+    /// draw收到的渲染通道已设置好裁剪矩形，只需设置ID管线并绘制物体。读回缓冲区仍在使用时新的请求留到之后的帧。
+    pub fn render(
+        &mut self,
+        encoder: &mut wgpu::CommandEncoder,
+        draw: impl FnOnce(&mut wgpu::RenderPass<'_>),
+    ) {
+        match self.state {
+            // 复制命令已随上一帧提交，现在可以开始映射
+            State::Copied => {
+                self.state = State::Mapping;
+                let map_result = self.map_result.clone();
+                self.readback_buffer
+                    .slice(..)
+                    .map_async(wgpu::MapMode::Read, move |result| {
+                        map_result.lock().replace(result);
+                    });
+                return;
+            }
+            State::Mapping => return,
+            State::Idle => {}
+        }
+        let Some(cursor) = self.requested.take() else {
+            return;
+        };
+
+        // 光标限制在目标范围内（光标在窗口边缘时坐标可能略微越界）
+        let size = self.target.size();
+        let x = (cursor.x.max(0.0) as u32).min(size.width - 1);
+        let y = (cursor.y.max(0.0) as u32).min(size.height - 1);
+        {
+            let mut pass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
+                label: Some("Picking Pass"),
+                color_attachments: &[Some(wgpu::RenderPassColorAttachment {
+                    view: &self.target_view,
+                    resolve_target: None,
+                    ops: wgpu::Operations {
+                        load: wgpu::LoadOp::Clear(wgpu::Color::TRANSPARENT), // ID 0：没有物体
+                        store: wgpu::StoreOp::Store,
+                    },
+                })],
+                depth_stencil_attachment: Some(self.depth.attachment()),
+                timestamp_writes: None,
+                occlusion_query_set: None,
+            });
+            pass.set_scissor_rect(x, y, 1, 1);
+            draw(&mut pass);
+        }
+        encoder.copy_texture_to_buffer(
+            wgpu::TexelCopyTextureInfo {
+                texture: &self.target,
+                mip_level: 0,
+                origin: wgpu::Origin3d { x, y, z: 0 },
+                aspect: wgpu::TextureAspect::All,
+            },
+            wgpu::TexelCopyBufferInfo {
+                buffer: &self.readback_buffer,
+                layout: wgpu::TexelCopyBufferLayout {
+                    offset: 0,
+                    bytes_per_row: Some(wgpu::COPY_BYTES_PER_ROW_ALIGNMENT),
+                    rows_per_image: Some(1),
+                },
+            },
+            wgpu::Extent3d {
+                width: 1,
+                height: 1,
+                depth_or_array_layers: 1,
+            },
+        );
+        self.state = State::Copied;
+    }
+
+    /// 检查读回是否完成，完成时返回光标下的物体ID（没有物体时为`ObjectId::NONE`），否则返回None
+    pub fn poll(&mut self) -> Option<ObjectId> {
+        if self.state != State::Mapping {
+            return None;
+        }
+        self.device.poll(wgpu::Maintain::Poll);
+        let result = self.map_result.lock().take()?;
+        self.state = State::Idle;
+        if result.is_err() {
+            return None;
+        }
+        let id = {
+            let data = self.readback_buffer.slice(..).get_mapped_range();
+            bytemuck::pod_read_unaligned::<u32>(&data)
+        };
+        self.readback_buffer.unmap();
+        Some(ObjectId(id))
+    }
+}
+
+/// 创建与表面同样大小的ID目标及其深度缓冲
+fn create_targets(
+    device: &wgpu::Device,
+    config: &wgpu::SurfaceConfiguration,
+) -> (wgpu::Texture, wgpu::TextureView, DepthTexture) {
+    let target = device.create_texture(&wgpu::TextureDescriptor {
+        label: Some("Picking Target"),
+        size: wgpu::Extent3d {
+            width: config.width.max(1),
+            height: config.height.max(1),
+            depth_or_array_layers: 1,
+        },
+        mip_level_count: 1,
+        sample_count: 1,
+        dimension: wgpu::TextureDimension::D2,
+        format: PickingPass::FORMAT,
+        usage: wgpu::TextureUsages::RENDER_ATTACHMENT | wgpu::TextureUsages::COPY_SRC,
+        view_formats: &[],
+    });
+    let target_view = target.create_view(&Default::default());
+    let depth = DepthTexture::new(
+        device,
+        Some("Picking Depth Texture"),
+        config.width,
+        config.height,
+        1,
+    );
+    (target, target_view, depth)
+}
@@ -0,0 +1,98 @@
+use log::{debug, info, warn};
+use std::path::PathBuf;
+
+/// 持久化到磁盘的管线缓存（需要`Features::PIPELINE_CACHE`），避免每次启动都重新编译着色器
+///
+/// 缓存文件按适配器名称和驱动版本区分，位于平台缓存目录下的`rs-wgpu-learn`目录。
+#[derive(Clone)]
+pub struct PipelineCache {
+    pub cache: wgpu::PipelineCache, // wgpu管线缓存（创建管线时传入）
+    pub path: PathBuf,              // 缓存文件路径
+}
+
+impl PipelineCache {
+    /// 从缓存文件创建管线缓存，文件不存在、损坏或与驱动不匹配时使用空缓存
+    ///
+    /// 找不到平台缓存目录时返回None。
+    pub fn load(
+        device: &wgpu::Device,
+        adapter_info: &wgpu::AdapterInfo,
+        label: Option<&str>,
+    ) -> Option<Self> {
+        let path = cache_path(adapter_info)?;
+        let data = match std::fs::read(&path) {
+            Ok(data) => {
+                info!(
+                    "Pipeline cache found at {} ({} bytes)",
+                    path.display(),
+                    data.len()
+                );
+                Some(data)
+            }
+            Err(e) => {
+                info!("No pipeline cache at {} ({e})", path.display());
+                None
+            }
+        };
+        // SAFETY: 数据来自之前由get_data写出的文件；fallback为true时
+        // wgpu会校验数据头，损坏或不匹配的数据会被丢弃并创建空缓存
+        let cache = unsafe {
+            device.create_pipeline_cache(&wgpu::PipelineCacheDescriptor {
+                label,
+                data: data.as_deref(),
+                fallback: true,
+            })
+        };
+        Some(Self { cache, path })
+    }
+
+    /// 将缓存数据写回磁盘（先写临时文件再重命名，避免写到一半留下损坏的文件）
+    pub fn save(&self) {
+        let Some(data) = self.cache.get_data() else {
+            debug!("Pipeline cache has no data to save");
+            return;
+        };
+        let temp = self.path.with_extension("tmp");
+        let result = self
+            .path
+            .parent()
+            .map_or(Ok(()), std::fs::create_dir_all)
+            .and_then(|()| std::fs::write(&temp, &data))
+            .and_then(|()| std::fs::rename(&temp, &self.path));
+        match result {
+            Ok(()) => info!(
+                "Saved pipeline cache to {} ({} bytes)",
+                self.path.display(),
+                data.len()
+            ),
+            Err(e) => warn!(
+                "Failed to save pipeline cache to {}: {e}",
+                self.path.display()
+            ),
+        }
+    }
+}
+
+/// 缓存文件路径：`<缓存目录>/rs-wgpu-learn/<适配器名称>-<驱动版本>.bin`
+fn cache_path(adapter_info: &wgpu::AdapterInfo) -> Option<PathBuf> {
+    let key = format!(
+        "{}-{}-{}",
+        adapter_info.name, adapter_info.driver, adapter_info.driver_info
+    );
+    // 只保留文件名中安全的字符
+    let file_name: String = key
+        .chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() || c == '.' || c == '-' {
+                c
+            } else {
+                '_'
+            }
+        })
+        .collect();
+    Some(
+        dirs::cache_dir()?
+            .join("rs-wgpu-learn")
+            .join(format!("{file_name}.bin")),
+    )
+}
@@ -0,0 +1,91 @@
+/// 同一套布局和着色器创建的一种管线变体
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PipelineVariant {
+    /// 正常填充三角形
+    Fill,
+    /// 只光栅化三角形的边（`PolygonMode::Line`，需要POLYGON_MODE_LINE特性）
+    Line,
+    /// 填充三角形，由片元着色器根据重心坐标只保留靠近边的片元（不需要特性）
+    ///
+    /// 着色器需提供`vs_wireframe`和`fs_wireframe`入口，重心坐标由`vertex_index % 3`得出，
+    /// 所以网格需用`deindex`展开索引后不带索引绘制。
+    Barycentric,
+}
+
+impl PipelineVariant {
+    /// 设备上可用的线框方式：支持POLYGON_MODE_LINE时用线模式，否则用重心坐标
+    pub fn wireframe_for(features: wgpu::Features) -> Self {
+        if features.contains(wgpu::Features::POLYGON_MODE_LINE) {
+            Self::Line
+        } else {
+            Self::Barycentric
+        }
+    }
+
+    /// 用于日志的名称
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Fill => "fill",
+            Self::Line => "polygon lines",
+            Self::Barycentric => "barycentric edges",
+        }
+    }
+
+    /// 顶点和片元着色器的入口（重心坐标方式使用`vs_wireframe`和`fs_wireframe`，其余为`vs`和`fs`）
+    pub fn entry_points(self) -> (&'static str, &'static str) {
+        match self {
+            Self::Barycentric => ("vs_wireframe", "fs_wireframe"),
+            Self::Fill | Self::Line => ("vs", "fs"),
+        }
+    }
+
+    /// 在primitive的基础上设置该变体的多边形模式
+    pub fn primitive(self, primitive: wgpu::PrimitiveState) -> wgpu::PrimitiveState {
+        wgpu::PrimitiveState {
+            polygon_mode: match self {
+                Self::Line => wgpu::PolygonMode::Line,
+                Self::Fill | Self::Barycentric => wgpu::PolygonMode::Fill,
+            },
+            ..primitive
+        }
+    }
+}
+
+/// 填充和线框两个管线：共用布局和着色器，一起创建、一起重建，绘制时按`RenderContext::wireframe`选择
+///
+/// 切换线框不需要重新创建缓冲区或管线，只是换一个`set_pipeline`。
+#[derive(Debug)]
+pub struct PipelineSet {
+    fill: wgpu::RenderPipeline,      // 填充管线
+    wireframe: wgpu::RenderPipeline, // 线框管线（线模式或重心坐标）
+    wireframe_variant: PipelineVariant,
+}
+
+impl PipelineSet {
+    /// 按设备特性选择线框方式，create为每种变体创建一次管线
+    pub fn new(
+        features: wgpu::Features,
+        mut create: impl FnMut(PipelineVariant) -> wgpu::RenderPipeline,
+    ) -> Self {
+        let wireframe_variant = PipelineVariant::wireframe_for(features);
+        Self {
+            fill: create(PipelineVariant::Fill),
+            wireframe: create(wireframe_variant),
+            wireframe_variant,
+        }
+    }
+
+    /// 填充或线框管线
+    pub fn get(&self, wireframe: bool) -> &wgpu::RenderPipeline {
+        if wireframe {
+            &self.wireframe
+        } else {
+            &self.fill
+        }
+    }
+
+    /// 线框管线使用的变体（`Line`或`Barycentric`）
+    pub fn wireframe_variant(&self) -> PipelineVariant {
+        self.wireframe_variant
+    }
+}
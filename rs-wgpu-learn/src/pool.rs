@@ -0,0 +1,196 @@
+use std::collections::HashMap;
+
+/// 资源连续这么多帧没有被取用时释放
+pub const DEFAULT_MAX_IDLE_FRAMES: u64 = 120;
+
+/// 缓冲区按尺寸和用途复用
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+struct BufferKey {
+    size: wgpu::BufferAddress,
+    usage: wgpu::BufferUsages,
+}
+
+/// 纹理按尺寸、格式和用途等复用
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+struct TextureKey {
+    size: wgpu::Extent3d,
+    mip_level_count: u32,
+    sample_count: u32,
+    dimension: wgpu::TextureDimension,
+    format: wgpu::TextureFormat,
+    usage: wgpu::TextureUsages,
+    view_formats: Vec<wgpu::TextureFormat>,
+}
+
+/// 池中的一个资源
+#[derive(Debug)]
+struct Pooled<T> {
+    resource: T,
+    bytes: u64,     // 占用的显存（估算）
+    last_used: u64, // 最后一次被取用的帧序号
+}
+
+/// 资源池的统计信息
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct PoolStats {
+    pub live_bytes: u64,   // 最近几帧取用过、GPU可能仍在使用的资源字节数
+    pub pooled_bytes: u64, // 空闲、可以立即复用的资源字节数
+    pub buffers: usize,    // 池中的缓冲区数量
+    pub textures: usize,   // 池中的纹理数量
+}
+
+/// 短期GPU资源池：按描述符复用每帧都要用到的缓冲区和纹理，避免反复创建和销毁
+///
+/// 取用的资源在当前帧被标记为使用中，`frames_in_flight`帧之后（GPU已经用完）才会再次交出；
+/// 连续`max_idle_frames`帧没有被取用的资源会被释放。每帧结束时调用`end_frame`。
+/// 返回的是资源句柄的克隆，调用方不应在归还的帧之后继续使用。
+#[derive(Debug)]
+pub struct ResourcePool {
+    device: wgpu::Device,
+    frame: u64,            // 当前帧序号
+    frames_in_flight: u64, // 资源取用后需要等待的帧数
+    max_idle_frames: u64,  // 空闲多少帧后释放
+    buffers: HashMap<BufferKey, Vec<Pooled<wgpu::Buffer>>>,
+    textures: HashMap<TextureKey, Vec<Pooled<wgpu::Texture>>>,
+}
+
+impl ResourcePool {
+    pub fn new(device: &wgpu::Device, frames_in_flight: u32) -> Self {
+        Self {
+            device: device.clone(),
+            frame: 0,
+            frames_in_flight: frames_in_flight.max(1) as u64,
+            max_idle_frames: DEFAULT_MAX_IDLE_FRAMES,
+            buffers: HashMap::new(),
+            textures: HashMap::new(),
+        }
+    }
+
+    /// 设置空闲多少帧后释放资源
+    pub fn set_max_idle_frames(&mut self, frames: u64) {
+        self.max_idle_frames = frames;
+    }
+
+    /// 设置资源取用后需要等待的帧数（通常为表面的最大帧延迟）
+    pub fn set_frames_in_flight(&mut self, frames: u32) {
+        self.frames_in_flight = frames.max(1) as u64;
+    }
+
+    /// 取用一个与描述符匹配的缓冲区，没有可复用的时创建新的（标签只在创建时使用）
+    ///
+    /// # Panics
+    /// 描述符要求`mapped_at_creation`时panic（复用的缓冲区无法保证处于映射状态）。
+    pub fn get_buffer(&mut self, desc: &wgpu::BufferDescriptor) -> wgpu::Buffer {
+        assert!(
+            !desc.mapped_at_creation,
+            "pooled buffer {:?} cannot be mapped at creation",
+            desc.label
+        );
+        let key = BufferKey {
+            size: desc.size,
+            usage: desc.usage,
+        };
+        let entries = self.buffers.entry(key).or_default();
+        if let Some(entry) = take(entries, self.frame, self.frames_in_flight) {
+            return entry.resource.clone();
+        }
+        let buffer = self.device.create_buffer(desc);
+        entries.push(Pooled {
+            resource: buffer.clone(),
+            bytes: desc.size,
+            last_used: self.frame,
+        });
+        buffer
+    }
+
+    /// 取用一个与描述符匹配的纹理，没有可复用的时创建新的（标签只在创建时使用）
+    pub fn get_texture(&mut self, desc: &wgpu::TextureDescriptor) -> wgpu::Texture {
+        let key = TextureKey {
+            size: desc.size,
+            mip_level_count: desc.mip_level_count,
+            sample_count: desc.sample_count,
+            dimension: desc.dimension,
+            format: desc.format,
+            usage: desc.usage,
+            view_formats: desc.view_formats.to_vec(),
+        };
+        let entries = self.textures.entry(key).or_default();
+        if let Some(entry) = take(entries, self.frame, self.frames_in_flight) {
+            return entry.resource.clone();
+        }
+        let texture = self.device.create_texture(desc);
+        entries.push(Pooled {
+            resource: texture.clone(),
+            bytes: texture_bytes(desc),
+            last_used: self.frame,
+        });
+        texture
+    }
+
+    /// 结束当前帧：帧序号加一，并释放空闲太久的资源
+    pub fn end_frame(&mut self) {
+        self.frame += 1;
+        let (frame, max_idle) = (self.frame, self.max_idle_frames);
+        let keep = |last_used: u64| frame - last_used <= max_idle;
+        self.buffers.retain(|_, entries| {
+            entries.retain(|entry| keep(entry.last_used));
+            !entries.is_empty()
+        });
+        self.textures.retain(|_, entries| {
+            entries.retain(|entry| keep(entry.last_used));
+            !entries.is_empty()
+        });
+    }
+
+    /// 释放所有资源（例如窗口尺寸变化后，旧尺寸的纹理不会再被用到）
+    pub fn clear(&mut self) {
+        self.buffers.clear();
+        self.textures.clear();
+    }
+
+    /// 使用中和空闲资源的字节数及数量
+    pub fn stats(&self) -> PoolStats {
+        let mut stats = PoolStats::default();
+        let mut count = |bytes: u64, last_used: u64| {
+            if self.frame < last_used + self.frames_in_flight {
+                stats.live_bytes += bytes;
+            } else {
+                stats.pooled_bytes += bytes;
+            }
+        };
+        for entry in self.buffers.values().flatten() {
+            count(entry.bytes, entry.last_used);
+        }
+        for entry in self.textures.values().flatten() {
+            count(entry.bytes, entry.last_used);
+        }
+        stats.buffers = self.buffers.values().map(Vec::len).sum();
+        stats.textures = self.textures.values().map(Vec::len).sum();
+        stats
+    }
+}
+
+/// 找到一个在frames_in_flight帧之前就不再使用的资源，标记为当前帧使用
+fn take<T>(entries: &mut [Pooled<T>], frame: u64, frames_in_flight: u64) -> Option<&Pooled<T>> {
+    let entry = entries
+        .iter_mut()
+        .find(|entry| entry.last_used + frames_in_flight <= frame)?;
+    entry.last_used = frame;
+    Some(entry)
+}
+
+/// 估算纹理占用的字节数（所有mip层级、数组层和采样点）
+fn texture_bytes(desc: &wgpu::TextureDescriptor) -> u64 {
+    let (block_width, block_height) = desc.format.block_dimensions();
+    let block_bytes = desc.format.block_copy_size(None).unwrap_or(4) as u64;
+    let bytes: u64 = (0..desc.mip_level_count)
+        .map(|level| {
+            // 二维纹理的数组层数不随层级变化，三维纹理的深度逐级减半
+            let size = desc.size.mip_level_size(level, desc.dimension);
+            let blocks_x = size.width.div_ceil(block_width) as u64;
+            let blocks_y = size.height.div_ceil(block_height) as u64;
+            blocks_x * blocks_y * size.depth_or_array_layers as u64 * block_bytes
+        })
+        .sum();
+    bytes * desc.sample_count as u64
+}
@@ -0,0 +1,155 @@
+use crate::{DynamicUniformBuffer, binding};
+use std::collections::HashMap;
+
+/// 启用PUSH_CONSTANTS时请求的推送常量大小上限（Vulkan保证至少支持128字节）
+pub const MAX_PUSH_CONSTANT_SIZE: u32 = 128;
+
+/// 每帧的少量逐绘制数据（颜色、模型序号等）：支持PUSH_CONSTANTS时使用推送常量，
+/// 否则（如浏览器上）回退到带动态偏移的uniform缓冲区，调用代码在两种方式下完全相同
+///
+/// 着色器中用两个常量声明数据，由`defines()`在加载时替换：
+///
+/// ```wgsl
+/// //#define PUSH_CONSTANT_BINDING
+/// //#define PUSH_CONSTANT_SPACE push_constant
+/// PUSH_CONSTANT_BINDING var<PUSH_CONSTANT_SPACE> draw: DrawData;
+/// ```
+///
+/// 每帧绘制前调用`begin`，每次绘制前调用`set`。回退方式下每帧最多`set` capacity次。
+#[derive(Debug)]
+pub struct PushConstants<T> {
+    stages: wgpu::ShaderStages,    // 使用数据的着色器阶段
+    fallback: Option<Fallback<T>>, // 不支持推送常量时使用的uniform缓冲区
+}
+
+/// 回退方式：每次`set`写入uniform缓冲区的下一个槽位，并以该槽位的偏移绑定
+#[derive(Debug)]
+struct Fallback<T> {
+    group: u32,                        // 绑定组索引
+    uniforms: DynamicUniformBuffer<T>, // capacity个槽位，容量即每帧最多的set次数
+    cursor: u32,                       // 本帧下一个可用的槽位
+}
+
+impl<T: bytemuck::Pod> PushConstants<T> {
+    /// 根据设备是否启用PUSH_CONSTANTS（且大小上限足够）选择方式
+    ///
+    /// group为回退方式使用的绑定组索引（通常是管线布局中最后一组），capacity为每帧最多的绘制次数。
+    ///
+    /// # Panics
+    /// T的大小不是4的倍数时panic（推送常量按4字节对齐）。
+    pub fn new(
+        device: &wgpu::Device,
+        label: &str,
+        stages: wgpu::ShaderStages,
+        group: u32,
+        capacity: u32,
+    ) -> Self {
+        let size = Self::size();
+        assert!(
+            size > 0 && size.is_multiple_of(wgpu::PUSH_CONSTANT_ALIGNMENT),
+            "push constant data of {size} bytes is not a multiple of {}",
+            wgpu::PUSH_CONSTANT_ALIGNMENT
+        );
+        let supported = device.features().contains(wgpu::Features::PUSH_CONSTANTS)
+            && size <= device.limits().max_push_constant_size;
+        let fallback = (!supported).then(|| Fallback {
+            group,
+            uniforms: DynamicUniformBuffer::new(
+                device,
+                &format!("{label} Push Constant Fallback"),
+                stages,
+                capacity,
+            ),
+            cursor: 0,
+        });
+        Self { stages, fallback }
+    }
+
+    /// 数据的字节数
+    fn size() -> u32 {
+        std::mem::size_of::<T>() as u32
+    }
+
+    /// 是否使用真正的推送常量
+    pub fn is_native(&self) -> bool {
+        self.fallback.is_none()
+    }
+
+    /// 加载着色器时传入的常量，决定数据声明为推送常量还是uniform
+    pub fn defines(&self) -> HashMap<String, String> {
+        let (binding, space) = match &self.fallback {
+            None => (String::new(), "push_constant"),
+            Some(fallback) => (format!("@group({}) @binding(0)", fallback.group), "uniform"),
+        };
+        HashMap::from([
+            ("PUSH_CONSTANT_BINDING".to_string(), binding),
+            ("PUSH_CONSTANT_SPACE".to_string(), space.to_string()),
+        ])
+    }
+
+    /// 创建管线布局：推送常量方式下加入推送常量范围，回退方式下在最后追加uniform绑定组
+    ///
+    /// # Panics
+    /// 回退方式下bind_group_layouts的数量与创建时给出的group不一致时panic。
+    pub fn pipeline_layout(
+        &self,
+        device: &wgpu::Device,
+        label: Option<&str>,
+        bind_group_layouts: &[&wgpu::BindGroupLayout],
+    ) -> wgpu::PipelineLayout {
+        match &self.fallback {
+            None => binding::pipeline_layout_with_push_constants(
+                device,
+                label,
+                bind_group_layouts,
+                &[wgpu::PushConstantRange {
+                    stages: self.stages,
+                    range: 0..Self::size(),
+                }],
+            ),
+            Some(fallback) => {
+                assert_eq!(
+                    bind_group_layouts.len() as u32,
+                    fallback.group,
+                    "the push constant fallback expects to be bind group {}",
+                    fallback.group
+                );
+                let layouts = bind_group_layouts
+                    .iter()
+                    .copied()
+                    .chain(std::iter::once(&**fallback.uniforms.layout()))
+                    .collect::<Vec<_>>();
+                binding::pipeline_layout(device, label, &layouts)
+            }
+        }
+    }
+
+    /// 开始新的一帧（回退方式下从第一个槽位重新写入）
+    pub fn begin(&mut self) {
+        if let Some(fallback) = &mut self.fallback {
+            fallback.cursor = 0;
+        }
+    }
+
+    /// 设置之后绘制使用的数据
+    ///
+    /// 回退方式下通过`queue.write_buffer`写入下一个槽位（在本帧提交前生效）并以其偏移重新绑定。
+    ///
+    /// # Panics
+    /// 回退方式下本帧的`set`次数超出capacity时panic。
+    pub fn set(&mut self, queue: &wgpu::Queue, pass: &mut wgpu::RenderPass, value: &T) {
+        match &mut self.fallback {
+            None => pass.set_push_constants(self.stages, 0, bytemuck::bytes_of(value)),
+            Some(fallback) => {
+                assert!(
+                    fallback.cursor < fallback.uniforms.capacity(),
+                    "push constants set more than {} times in one frame",
+                    fallback.uniforms.capacity()
+                );
+                let offset = fallback.uniforms.write_slot(queue, fallback.cursor, value);
+                pass.set_bind_group(fallback.group, fallback.uniforms.bind_group(), &[offset]);
+                fallback.cursor += 1;
+            }
+        }
+    }
+}
@@ -0,0 +1,66 @@
+use crate::{ResourcePool, WgpuAppError};
+
+/// 将纹理复制到缓冲区并读回，返回去掉行填充后紧密排列的像素数据
+///
+/// 纹理需要COPY_SRC用途；读回缓冲区从资源池取用，函数会阻塞到GPU完成复制（通常不超过一帧）。
+pub(crate) fn read_texture(
+    device: &wgpu::Device,
+    queue: &wgpu::Queue,
+    pool: &mut ResourcePool,
+    texture: &wgpu::Texture,
+) -> Result<Vec<u8>, WgpuAppError> {
+    let size = texture.size();
+    let bytes_per_pixel = texture
+        .format()
+        .block_copy_size(None)
+        .expect("readback requires a color format");
+
+    // 1. 复制到缓冲区时每行字节数必须按256字节对齐（宽度为奇数时两者不同）
+    let unpadded_bytes_per_row = size.width * bytes_per_pixel;
+    let padded_bytes_per_row = unpadded_bytes_per_row.div_ceil(wgpu::COPY_BYTES_PER_ROW_ALIGNMENT)
+        * wgpu::COPY_BYTES_PER_ROW_ALIGNMENT;
+    let buffer = pool.get_buffer(&wgpu::BufferDescriptor {
+        label: Some("Readback Buffer"),
+        size: (padded_bytes_per_row * size.height) as u64,
+        usage: wgpu::BufferUsages::COPY_DST | wgpu::BufferUsages::MAP_READ,
+        mapped_at_creation: false,
+    });
+
+    // 2. 将纹理复制到缓冲区
+    let mut encoder = device.create_command_encoder(&wgpu::CommandEncoderDescriptor {
+        label: Some("Readback Encoder"),
+    });
+    encoder.copy_texture_to_buffer(
+        texture.as_image_copy(),
+        wgpu::TexelCopyBufferInfo {
+            buffer: &buffer,
+            layout: wgpu::TexelCopyBufferLayout {
+                offset: 0,
+                bytes_per_row: Some(padded_bytes_per_row),
+                rows_per_image: Some(size.height),
+            },
+        },
+        size,
+    );
+    queue.submit(std::iter::once(encoder.finish()));
+
+    // 3. 映射缓冲区并等待GPU完成
+    let slice = buffer.slice(..);
+    let (sender, receiver) = std::sync::mpsc::channel();
+    slice.map_async(wgpu::MapMode::Read, move |result| {
+        let _ = sender.send(result);
+    });
+    device.poll(wgpu::Maintain::Wait);
+    receiver.recv().map_err(|_| wgpu::BufferAsyncError)??;
+
+    // 4. 去掉每行末尾的填充字节
+    let data = slice.get_mapped_range();
+    let pixels = data
+        .chunks(padded_bytes_per_row as usize)
+        .flat_map(|row| &row[..unpadded_bytes_per_row as usize])
+        .copied()
+        .collect();
+    drop(data);
+    buffer.unmap();
+    Ok(pixels)
+}
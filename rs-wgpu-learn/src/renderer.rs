@@ -0,0 +1,194 @@
+use crate::{
+    DepthTexture, Globals, ResourcePool, SamplerCache, WgpuAppError, shader, upload::Uploader,
+};
+use log::{error, info};
+use std::{cell::RefCell, collections::HashMap, path::Path};
+
+/// 创建渲染器时传递给`Renderer::init`的上下文
+///
+/// 创建时需要的设备资源都放在这里，新增一项不需要改动每个渲染器的签名。
+#[derive(Clone, Copy)]
+pub struct InitContext<'a> {
+    pub adapter: &'a wgpu::Adapter, // 图形适配器（查询格式特性等，见`NoiseGenerator::new`）
+    pub device: &'a wgpu::Device,   // GPU设备
+    pub queue: &'a wgpu::Queue,     // 命令队列
+    pub config: &'a wgpu::SurfaceConfiguration, // 当前表面配置
+    pub sample_count: u32,          // 多重采样数，管线的multisample.count需与之一致
+    pub cache: Option<&'a wgpu::PipelineCache>, // 管线缓存（存在时传给所有管线描述符）
+    pub samplers: &'a SamplerCache, // 应用共享的采样器缓存
+}
+
+/// 渲染时传递给Renderer的上下文
+pub struct RenderContext<'a> {
+    pub device: &'a wgpu::Device,                        // GPU设备
+    pub queue: &'a wgpu::Queue,                          // 命令队列
+    pub config: &'a wgpu::SurfaceConfiguration,          // 当前表面配置
+    pub clear_color: wgpu::Color,                        // 已针对表面格式转换过的清除色
+    pub sample_count: u32, // 多重采样数，管线的multisample.count需与之一致
+    pub msaa_view: Option<&'a wgpu::TextureView>, // 多重采样颜色附件（启用MSAA时存在）
+    pub depth_texture: &'a DepthTexture, // 深度纹理（需要深度测试时使用）
+    pub timestamp_query_set: Option<&'a wgpu::QuerySet>, // GPU计时查询集（支持时间戳查询时存在）
+    pub alpha: f32,        // 固定更新的插值系数（0..1），用于在上一次和当前状态之间平滑插值
+    pub time: f32,         // 场景时间（秒，update收到的dt之和），暂停时不前进
+    pub frame_index: u64,  // 帧序号（begin_frame时递增），FrameUniforms据此选择本帧的槽位
+    pub wireframe: bool,   // 是否以线框绘制（Z键切换，支持线框的场景据此从PipelineSet中选择管线）
+    pub globals: &'a Globals, // 全局uniform（本帧的数据已写入），着色器通过globals.wgsl使用
+    pub samplers: &'a SamplerCache, // 按配置共享的采样器
+    pub uploader: &'a RefCell<Uploader>, // 暂存缓冲区上传器（提交前后由应用finish和recall）
+    pub pool: &'a RefCell<ResourcePool>, // 短期缓冲区和纹理池（每帧结束时由应用end_frame）
+}
+
+impl<'a> RenderContext<'a> {
+    /// 以view为目标的颜色附件，启用MSAA时自动改为绘制到多重采样纹理并解析到view
+    pub fn color_attachment(
+        &self,
+        view: &'a wgpu::TextureView,
+        ops: wgpu::Operations<wgpu::Color>,
+    ) -> wgpu::RenderPassColorAttachment<'a> {
+        crate::frame::color_attachment(view, self.msaa_view, ops)
+    }
+
+    /// 用于测量GPU耗时的时间戳写入，每帧应只传给一个渲染通道（通常是主通道）
+    pub fn timestamp_writes(&self) -> Option<wgpu::RenderPassTimestampWrites<'a>> {
+        self.timestamp_query_set
+            .map(|query_set| wgpu::RenderPassTimestampWrites {
+                query_set,
+                beginning_of_pass_write_index: Some(0),
+                end_of_pass_write_index: Some(1),
+            })
+    }
+
+    /// 通过上传器把value写入target的offset处，复制命令记录到encoder中（在之后的渲染通道之前执行）
+    ///
+    /// T的大小必须是4的倍数（否则无法编译）；offset未对齐或写入范围超出target时记录错误并跳过本次写入。
+    pub fn upload<T: bytemuck::Pod>(
+        &self,
+        encoder: &mut wgpu::CommandEncoder,
+        target: &wgpu::Buffer,
+        offset: wgpu::BufferAddress,
+        value: &T,
+    ) {
+        self.upload_slice(encoder, target, offset, std::slice::from_ref(value));
+    }
+
+    /// 与`upload`相同，但写入一组值
+    pub fn upload_slice<T: bytemuck::Pod>(
+        &self,
+        encoder: &mut wgpu::CommandEncoder,
+        target: &wgpu::Buffer,
+        offset: wgpu::BufferAddress,
+        data: &[T],
+    ) {
+        let written = self
+            .uploader
+            .borrow_mut()
+            .write_slice(encoder, target, offset, data);
+        if let Err(e) = written {
+            error!("{e}");
+        }
+    }
+
+    /// 深度附件（清除为1.0），使用它的管线需设置`DepthTexture::depth_stencil_state()`
+    pub fn depth_attachment(&self) -> Option<wgpu::RenderPassDepthStencilAttachment<'a>> {
+        Some(self.depth_texture.attachment())
+    }
+}
+
+/// 渲染器构造函数，签名与`Renderer::init`一致
+///
+/// 用函数指针而不是泛型参数传递构造方式，使装箱的场景（运行时才确定具体类型）也能被创建。
+pub type RendererInit<R> = fn(&InitContext) -> Result<R, WgpuAppError>;
+
+/// 渲染器：负责具体的绘制逻辑，设备、表面等基础设施由WgpuApp管理
+pub trait Renderer {
+    /// 创建渲染器（着色器、管线、缓冲区等资源），管线需使用ctx.sample_count作为多重采样数
+    ///
+    /// ctx.cache存在时应传给所有管线描述符，并保存一份用于之后重建管线。
+    fn init(ctx: &InitContext) -> Result<Self, WgpuAppError>
+    where
+        Self: Sized;
+
+    /// 将一帧内容记录到命令编码器中，view为当前帧的表面纹理视图
+    fn render(
+        &mut self,
+        ctx: &RenderContext,
+        view: &wgpu::TextureView,
+        encoder: &mut wgpu::CommandEncoder,
+    );
+
+    /// 表面尺寸变化后调用，用于重建与尺寸相关的资源
+    fn resize(&mut self, _config: &wgpu::SurfaceConfiguration) {}
+
+    /// 多重采样数变化后调用，实现者应使用新的sample_count重建管线
+    ///
+    /// 任何管线重建失败时返回错误，应用保留原来的多重采样数和渲染目标，并用原来的sample_count再调用一次，
+    /// 把已经按新的采样数重建的管线恢复回来。
+    fn sample_count_changed(
+        &mut self,
+        _device: &wgpu::Device,
+        _config: &wgpu::SurfaceConfiguration,
+        _sample_count: u32,
+    ) -> Result<(), WgpuAppError> {
+        Ok(())
+    }
+
+    /// 着色器文件发生变化时调用（hot-reload特性），path为相对于资源目录的路径
+    ///
+    /// 实现者应重建使用该着色器的管线，编译失败时保留旧管线继续绘制。
+    fn shader_changed(
+        &mut self,
+        _device: &wgpu::Device,
+        _config: &wgpu::SurfaceConfiguration,
+        _path: &Path,
+    ) {
+    }
+}
+
+/// 用重建结果替换pipeline：成功时替换并记录日志，失败时保留旧管线、记录错误并返回该错误
+///
+/// 热重载时着色器可能写到一半，旧管线可以继续绘制，调用方忽略返回值即可；
+/// 多重采样数变化时旧管线与新的渲染目标不匹配，调用方需要把错误传给应用。
+pub fn rebuild_or_keep<P>(
+    pipeline: &mut P,
+    name: &str,
+    rebuilt: Result<P, WgpuAppError>,
+) -> Result<(), WgpuAppError> {
+    match rebuilt {
+        Ok(rebuilt) => {
+            *pipeline = rebuilt;
+            info!("Rebuilt {name} pipeline");
+            Ok(())
+        }
+        Err(e) => {
+            error!("Failed to rebuild {name} pipeline, keeping the previous one: {e}");
+            Err(e)
+        }
+    }
+}
+
+/// 重新加载着色器path，用build创建新管线并替换pipeline，失败时保留旧管线（见`rebuild_or_keep`）
+///
+/// 创建管线期间的验证错误（例如入口点或绑定与布局不匹配）转换为`WgpuAppError::Validation`。
+pub fn rebuild_pipeline<P>(
+    device: &wgpu::Device,
+    path: &str,
+    pipeline: &mut P,
+    build: impl FnOnce(&wgpu::ShaderModule) -> P,
+) -> Result<(), WgpuAppError> {
+    rebuild_pipeline_with_defines(device, path, &HashMap::new(), pipeline, build)
+}
+
+/// 与`rebuild_pipeline`相同，但加载着色器时替换defines中的常量（见`shader::load_with_defines`）
+pub fn rebuild_pipeline_with_defines<P>(
+    device: &wgpu::Device,
+    path: &str,
+    defines: &HashMap<String, String>,
+    pipeline: &mut P,
+    build: impl FnOnce(&wgpu::ShaderModule) -> P,
+) -> Result<(), WgpuAppError> {
+    let rebuilt = shader::load_with_defines(device, path, defines).and_then(|shader| {
+        shader::catch_validation(device, || build(&shader))
+            .map_err(|e| WgpuAppError::Validation(e.to_string()))
+    });
+    rebuild_or_keep(pipeline, path, rebuilt)
+}
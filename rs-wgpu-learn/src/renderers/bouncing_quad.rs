@@ -0,0 +1,244 @@
+use crate::{
+    InitContext, RenderContext, Renderer, Scene, WgpuAppError,
+    binding::{self, BindGroupBuilder, BindGroupLayoutBuilder, BindingLayout},
+    rebuild_pipeline, shader,
+};
+use std::path::Path;
+
+/// 反弹正方形使用的着色器文件
+const SHADER: &str = "bouncing_quad.wgsl";
+
+/// 正方形的半边长（NDC，以窗口高度为准）
+const HALF_SIZE: f32 = 0.1;
+
+/// 初始速度（NDC/秒）
+const VELOCITY: [f32; 2] = [0.9, 0.6];
+
+/// 与着色器中QuadUniform对应的数据
+#[repr(C)]
+#[derive(Debug, Clone, Copy, bytemuck::Pod, bytemuck::Zeroable)]
+struct QuadUniform {
+    center: [f32; 2],
+    half_size: [f32; 2],
+}
+
+/// 在窗口边缘反弹的正方形，用于验证固定步长更新和渲染插值
+///
+/// 位置只在`fixed_update`中更新（例如50Hz），渲染时按`RenderContext::alpha`
+/// 在上一次和当前位置之间插值，刷新率高于更新频率时移动依然平滑。
+pub struct BouncingQuad {
+    pipeline: wgpu::RenderPipeline,
+    pipeline_layout: wgpu::PipelineLayout,
+    sample_count: u32,
+    cache: Option<wgpu::PipelineCache>,
+    uniform_buffer: wgpu::Buffer,
+    bind_group: wgpu::BindGroup,
+    aspect: f32,        // 表面宽高比（保持正方形不被拉伸）
+    position: [f32; 2], // 当前位置（最近一次固定更新后）
+    previous: [f32; 2], // 上一次固定更新前的位置
+    velocity: [f32; 2], // 速度（NDC/秒）
+}
+
+impl Renderer for BouncingQuad {
+    fn init(ctx: &InitContext) -> Result<Self, WgpuAppError> {
+        let InitContext {
+            device,
+            config,
+            sample_count,
+            cache,
+            ..
+        } = *ctx;
+        // 1. 创建uniform缓冲区（每帧写入插值后的位置）
+        let uniform_buffer = device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some("Bouncing Quad Uniform Buffer"),
+            size: std::mem::size_of::<QuadUniform>() as u64,
+            usage: wgpu::BufferUsages::UNIFORM | wgpu::BufferUsages::COPY_DST,
+            mapped_at_creation: false,
+        });
+
+        // 2. 创建绑定组布局、绑定组和管线布局
+        let bind_group_layout = create_bind_group_layout(device);
+        let bind_group = BindGroupBuilder::new(&bind_group_layout)
+            .label("Bouncing Quad Bind Group")
+            .buffer(&uniform_buffer)
+            .build(device);
+        let pipeline_layout = binding::pipeline_layout(
+            device,
+            Some("Bouncing Quad Pipeline Layout"),
+            &[&bind_group_layout],
+        );
+
+        // 3. 加载着色器并创建管线
+        let shader = shader::load(device, SHADER)?;
+        let pipeline = create_pipeline(
+            device,
+            &shader,
+            config.format,
+            &pipeline_layout,
+            sample_count,
+            cache,
+        );
+
+        Ok(Self {
+            pipeline,
+            pipeline_layout,
+            sample_count,
+            cache: cache.cloned(),
+            uniform_buffer,
+            bind_group,
+            aspect: aspect(config),
+            position: [0.0; 2],
+            previous: [0.0; 2],
+            velocity: VELOCITY,
+        })
+    }
+
+    fn render(
+        &mut self,
+        ctx: &RenderContext,
+        view: &wgpu::TextureView,
+        encoder: &mut wgpu::CommandEncoder,
+    ) {
+        // 在上一次和当前位置之间插值
+        let lerp = |axis: usize| {
+            self.previous[axis] + (self.position[axis] - self.previous[axis]) * ctx.alpha
+        };
+        let uniform = QuadUniform {
+            center: [lerp(0), lerp(1)],
+            half_size: [HALF_SIZE / self.aspect, HALF_SIZE],
+        };
+        ctx.upload(encoder, &self.uniform_buffer, 0, &uniform);
+
+        let mut pass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
+            label: Some("Bouncing Quad Pass"),
+            color_attachments: &[Some(ctx.color_attachment(
+                view,
+                wgpu::Operations {
+                    load: wgpu::LoadOp::Clear(ctx.clear_color),
+                    store: wgpu::StoreOp::Store,
+                },
+            ))],
+            depth_stencil_attachment: None,
+            timestamp_writes: ctx.timestamp_writes(),
+            occlusion_query_set: None,
+        });
+        pass.set_pipeline(&self.pipeline);
+        pass.set_bind_group(0, &self.bind_group, &[]);
+        pass.draw(0..6, 0..1);
+    }
+
+    fn resize(&mut self, config: &wgpu::SurfaceConfiguration) {
+        self.aspect = aspect(config);
+    }
+
+    fn shader_changed(
+        &mut self,
+        device: &wgpu::Device,
+        config: &wgpu::SurfaceConfiguration,
+        path: &Path,
+    ) {
+        if !shader::depends_on(SHADER, path) {
+            return;
+        }
+        let _ = self.rebuild_pipeline(device, config.format, self.sample_count);
+    }
+
+    fn sample_count_changed(
+        &mut self,
+        device: &wgpu::Device,
+        config: &wgpu::SurfaceConfiguration,
+        sample_count: u32,
+    ) -> Result<(), WgpuAppError> {
+        self.rebuild_pipeline(device, config.format, sample_count)
+    }
+}
+
+impl Scene for BouncingQuad {
+    fn fixed_update(&mut self, dt: f32) {
+        self.previous = self.position;
+        // x方向的边界按宽高比缩放，保证正方形碰到窗口边缘时反弹
+        let bounds = [1.0 - HALF_SIZE / self.aspect, 1.0 - HALF_SIZE];
+        for (axis, bound) in bounds.into_iter().enumerate() {
+            let position = self.position[axis] + self.velocity[axis] * dt;
+            if position.abs() > bound {
+                self.velocity[axis] = -self.velocity[axis];
+            }
+            self.position[axis] = position.clamp(-bound, bound);
+        }
+    }
+}
+
+impl BouncingQuad {
+    /// 用`rebuild_pipeline`按sample_count重建管线，成功后才更新多重采样数
+    fn rebuild_pipeline(
+        &mut self,
+        device: &wgpu::Device,
+        format: wgpu::TextureFormat,
+        sample_count: u32,
+    ) -> Result<(), WgpuAppError> {
+        rebuild_pipeline(device, SHADER, &mut self.pipeline, |shader| {
+            create_pipeline(
+                device,
+                shader,
+                format,
+                &self.pipeline_layout,
+                sample_count,
+                self.cache.as_ref(),
+            )
+        })?;
+        self.sample_count = sample_count;
+        Ok(())
+    }
+}
+
+/// 表面宽高比
+fn aspect(config: &wgpu::SurfaceConfiguration) -> f32 {
+    config.width.max(1) as f32 / config.height.max(1) as f32
+}
+
+/// 第0组：顶点着色器使用的QuadUniform
+fn create_bind_group_layout(device: &wgpu::Device) -> BindingLayout {
+    BindGroupLayoutBuilder::new()
+        .label("Bouncing Quad Bind Group Layout")
+        .uniform(wgpu::ShaderStages::VERTEX)
+        .build(device)
+}
+
+/// 创建渲染管线
+fn create_pipeline(
+    device: &wgpu::Device,
+    shader: &wgpu::ShaderModule,
+    format: wgpu::TextureFormat,
+    layout: &wgpu::PipelineLayout,
+    sample_count: u32,
+    cache: Option<&wgpu::PipelineCache>,
+) -> wgpu::RenderPipeline {
+    device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
+        label: Some("Bouncing Quad Pipeline"),
+        layout: Some(layout),
+        vertex: wgpu::VertexState {
+            module: shader,
+            entry_point: Some("vs"),
+            buffers: &[],
+            compilation_options: Default::default(),
+        },
+        fragment: Some(wgpu::FragmentState {
+            module: shader,
+            entry_point: Some("fs"),
+            targets: &[Some(wgpu::ColorTargetState {
+                format,
+                blend: Some(wgpu::BlendState::REPLACE),
+                write_mask: wgpu::ColorWrites::ALL,
+            })],
+            compilation_options: Default::default(),
+        }),
+        primitive: Default::default(),
+        depth_stencil: None,
+        multisample: wgpu::MultisampleState {
+            count: sample_count,
+            ..Default::default()
+        },
+        multiview: None,
+        cache,
+    })
+}
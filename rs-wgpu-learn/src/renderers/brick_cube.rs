@@ -0,0 +1,406 @@
+use crate::{
+    DepthTexture, InitContext, InputState, Mesh, RenderContext, Renderer, SamplerDesc, Scene,
+    TangentVertex, Texture, WgpuAppError,
+    binding::{self, BindGroupBuilder, BindGroupLayoutBuilder, BindingLayout},
+    camera::{Camera, CameraController, OrbitController, Projection, aspect_ratio},
+    debug_draw::{DebugDepth, DebugLines},
+    math::Transform,
+    mipmap::MipmapGenerator,
+    rebuild_pipeline, shader, shapes, with_tangents,
+};
+use glam::{Quat, Vec3};
+use log::info;
+use std::{path::Path, time::Duration};
+use wgpu::util::DeviceExt;
+use winit::keyboard::KeyCode;
+
+/// 砖块立方体使用的着色器文件
+const SHADER: &str = "brick_cube.wgsl";
+
+/// 砖墙的颜色贴图（sRGB）和切线空间法线贴图（线性数据，绿色通道指向图片上方）
+const BRICKS_ALBEDO: &[u8] = include_bytes!("../../assets/bricks_albedo.png");
+const BRICKS_NORMAL: &[u8] = include_bytes!("../../assets/bricks_normal.png");
+
+/// 立方体的旋转速度（弧度/秒）
+const ROTATION_SPEED: f32 = 0.3;
+
+/// 点光源绕立方体一周的角速度（弧度/秒）、轨道半径和强度
+const LIGHT_SPEED: f32 = 0.8;
+const LIGHT_RADIUS: f32 = 1.4;
+const LIGHT_INTENSITY: f32 = 2.5;
+
+/// 环境光强度
+const AMBIENT: f32 = 0.06;
+
+/// 纹理坐标左右镜像的面（-X、-Y、-Z，shapes::cube中每个面4个顶点）
+const MIRRORED_FACES: [usize; 3] = [1, 3, 5];
+
+/// 与着色器中SceneUniform对应的数据（mat3x3f的每列占16字节）
+#[repr(C)]
+#[derive(Debug, Clone, Copy, bytemuck::Pod, bytemuck::Zeroable)]
+struct SceneUniform {
+    view_proj: [[f32; 4]; 4],
+    model: [[f32; 4]; 4],
+    normal: [[f32; 4]; 3],
+    light_position: [f32; 3],
+    light_intensity: f32,
+    eye: [f32; 3],
+    ambient: f32,
+}
+
+/// 与着色器中Material对应的数据
+#[repr(C)]
+#[derive(Debug, Clone, Copy, bytemuck::Pod, bytemuck::Zeroable)]
+struct MaterialUniform {
+    normal_scale: f32,
+    normal_map: u32, // 是否使用法线贴图
+    _padding: [u32; 2],
+}
+
+/// 法线贴图演示：被点光源照亮的旋转砖块立方体，B键开关法线贴图
+///
+/// 立方体的顶点由`with_tangents`计算切线，片元着色器用切线、副切线和法线组成的矩阵把法线贴图中的
+/// 切线空间法线变换到世界空间。关闭法线贴图时每个面都是平的，只有颜色贴图；打开后砖块的倒角和
+/// 灰缝随光源移动出现明暗变化。-X、-Y、-Z面的纹理坐标左右镜像（切线的w为-1），用来检查镜像后光照不会反转。
+/// 黄色的小球标出光源位置。用鼠标拖动旋转相机、滚轮缩放（见`OrbitController`）。
+pub struct BrickCube {
+    pipeline: wgpu::RenderPipeline,
+    pipeline_layout: wgpu::PipelineLayout, // 第0组：场景，第1组：材质
+    sample_count: u32,
+    cache: Option<wgpu::PipelineCache>,
+    config: wgpu::SurfaceConfiguration, // 表面配置，用于相机的宽高比
+    camera: Camera,                     // 观察立方体的相机
+    controller: CameraController,       // 轨道控制器
+    scene_buffer: wgpu::Buffer,
+    scene_bind_group: wgpu::BindGroup,
+    material_buffer: wgpu::Buffer,
+    material_bind_group: wgpu::BindGroup, // 第1组：材质uniform、颜色贴图和法线贴图
+    material: MaterialUniform,            // 材质参数（开关法线贴图时重新写入）
+    cube: Mesh,                           // 顶点为TangentVertex的立方体
+    debug_lines: DebugLines,              // 光源位置的标记
+}
+
+impl Renderer for BrickCube {
+    fn init(ctx: &InitContext) -> Result<Self, WgpuAppError> {
+        let InitContext {
+            device,
+            queue,
+            config,
+            sample_count,
+            cache,
+            samplers,
+            ..
+        } = *ctx;
+        // 1. 场景uniform、材质uniform和两张贴图
+        let scene_buffer = device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some("Brick Cube Scene Buffer"),
+            size: std::mem::size_of::<SceneUniform>() as u64,
+            usage: wgpu::BufferUsages::UNIFORM | wgpu::BufferUsages::COPY_DST,
+            mapped_at_creation: false,
+        });
+        let scene_layout = BindGroupLayoutBuilder::new()
+            .label("Brick Cube Scene Layout")
+            .uniform(wgpu::ShaderStages::VERTEX_FRAGMENT)
+            .build(device);
+        let scene_bind_group = BindGroupBuilder::new(&scene_layout)
+            .label("Brick Cube Scene Bind Group")
+            .buffer(&scene_buffer)
+            .build(device);
+        let material = MaterialUniform {
+            normal_scale: 1.0,
+            normal_map: 1,
+            _padding: [0; 2],
+        };
+        let material_buffer = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+            label: Some("Brick Cube Material Buffer"),
+            contents: bytemuck::bytes_of(&material),
+            usage: wgpu::BufferUsages::UNIFORM | wgpu::BufferUsages::COPY_DST,
+        });
+        let mut mipmaps = MipmapGenerator::new(device)?;
+        let albedo = Texture::from_bytes(
+            device,
+            queue,
+            samplers,
+            BRICKS_ALBEDO,
+            Some("Bricks Albedo"),
+            Some(&mut mipmaps),
+        )?;
+        // 法线贴图保存的是向量而不是颜色，按线性数据上传，采样时不做sRGB解码
+        let normal_image =
+            image::load_from_memory(BRICKS_NORMAL).map_err(WgpuAppError::ImageDecode)?;
+        let normal = Texture::from_rgba8(
+            device,
+            queue,
+            Some("Bricks Normal"),
+            &normal_image.to_rgba8(),
+            wgpu::TextureFormat::Rgba8Unorm,
+            samplers.get(&SamplerDesc::linear_repeat())?,
+            Some(&mut mipmaps),
+        )?;
+        let material_layout = create_material_layout(device);
+        let material_bind_group = BindGroupBuilder::new(&material_layout)
+            .label("Brick Cube Material Bind Group")
+            .buffer(&material_buffer)
+            .texture(&albedo.view)
+            .sampler(&albedo.sampler)
+            .texture(&normal.view)
+            .sampler(&normal.sampler)
+            .build(device);
+        let pipeline_layout = binding::pipeline_layout(
+            device,
+            Some("Brick Cube Pipeline Layout"),
+            &[&scene_layout, &material_layout],
+        );
+
+        // 2. 加载着色器并创建管线
+        let shader = shader::load(device, SHADER)?;
+        let pipeline = create_pipeline(
+            device,
+            &shader,
+            config.format,
+            &pipeline_layout,
+            sample_count,
+            cache,
+        );
+        let debug_lines =
+            DebugLines::new(device, config.format, sample_count, cache, DebugDepth::Test)?;
+
+        // 3. 一半的面镜像纹理坐标后计算切线
+        let (mut vertices, indices) = shapes::cube(1.0);
+        for face in MIRRORED_FACES {
+            for vertex in &mut vertices[face * 4..face * 4 + 4] {
+                vertex.uv[0] = 1.0 - vertex.uv[0];
+            }
+        }
+        let vertices: Vec<TangentVertex> = with_tangents(&vertices, &indices);
+        let cube = Mesh::new(device, "Brick Cube", &vertices, Some(&indices));
+
+        let camera = Camera {
+            eye: Vec3::new(0.0, 1.2, 2.6),
+            projection: Projection::Perspective {
+                fovy: std::f32::consts::FRAC_PI_4,
+                znear: 0.1,
+                zfar: 20.0,
+            },
+            ..Default::default()
+        };
+        let mut orbit = OrbitController::from_camera(&camera);
+        orbit.min_distance = 1.2;
+        orbit.max_distance = 10.0;
+        Ok(Self {
+            pipeline,
+            pipeline_layout,
+            sample_count,
+            cache: cache.cloned(),
+            config: config.clone(),
+            controller: CameraController::new(orbit).with_bookmarks("bricks"),
+            camera,
+            scene_buffer,
+            scene_bind_group,
+            material_buffer,
+            material_bind_group,
+            material,
+            cube,
+            debug_lines,
+        })
+    }
+
+    fn render(
+        &mut self,
+        ctx: &RenderContext,
+        view: &wgpu::TextureView,
+        encoder: &mut wgpu::CommandEncoder,
+    ) {
+        let angle = (ROTATION_SPEED * ctx.time) % std::f32::consts::TAU;
+        let model = Transform::from_rotation(Quat::from_rotation_y(angle));
+        let normal = model.normal_matrix();
+        // 光源绕Y轴转动，同时上下起伏
+        let light_angle = LIGHT_SPEED * ctx.time;
+        let light = Vec3::new(
+            LIGHT_RADIUS * light_angle.cos(),
+            0.3 + 0.6 * (0.7 * light_angle).sin(),
+            LIGHT_RADIUS * light_angle.sin(),
+        );
+        let view_proj = self.camera.view_proj(aspect_ratio(&self.config));
+        let uniform = SceneUniform {
+            view_proj: view_proj.to_cols_array_2d(),
+            model: model.matrix().to_cols_array_2d(),
+            normal: [normal.x_axis, normal.y_axis, normal.z_axis].map(|c| c.extend(0.0).to_array()),
+            light_position: light.to_array(),
+            light_intensity: LIGHT_INTENSITY,
+            eye: self.camera.eye.to_array(),
+            ambient: AMBIENT,
+        };
+        ctx.upload(encoder, &self.scene_buffer, 0, &uniform);
+        self.debug_lines.clear();
+        self.debug_lines
+            .add_sphere(light, 0.05, [1.0, 0.9, 0.3, 1.0]);
+        self.debug_lines.prepare(ctx, encoder, view_proj);
+
+        let mut pass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
+            label: Some("Brick Cube Pass"),
+            color_attachments: &[Some(ctx.color_attachment(
+                view,
+                wgpu::Operations {
+                    load: wgpu::LoadOp::Clear(ctx.clear_color),
+                    store: wgpu::StoreOp::Store,
+                },
+            ))],
+            depth_stencil_attachment: ctx.depth_attachment(), // 深度附件（每帧清除为1.0）
+            timestamp_writes: ctx.timestamp_writes(),
+            occlusion_query_set: None,
+        });
+        pass.set_pipeline(&self.pipeline);
+        pass.set_bind_group(0, &self.scene_bind_group, &[]);
+        pass.set_bind_group(1, &self.material_bind_group, &[]);
+        self.cube.draw(&mut pass, 0..1);
+        self.debug_lines.draw(&mut pass);
+    }
+
+    fn shader_changed(
+        &mut self,
+        device: &wgpu::Device,
+        config: &wgpu::SurfaceConfiguration,
+        path: &Path,
+    ) {
+        self.debug_lines.shader_changed(device, path);
+        if shader::depends_on(SHADER, path) {
+            let _ = self.rebuild_pipeline(device, config.format, self.sample_count);
+        }
+    }
+
+    fn sample_count_changed(
+        &mut self,
+        device: &wgpu::Device,
+        config: &wgpu::SurfaceConfiguration,
+        sample_count: u32,
+    ) -> Result<(), WgpuAppError> {
+        self.rebuild_pipeline(device, config.format, sample_count)?;
+        self.debug_lines
+            .sample_count_changed(device, sample_count)?;
+        Ok(())
+    }
+}
+
+impl Scene for BrickCube {
+    /// B键开关法线贴图（只改写材质uniform中的标志），其余输入交给相机
+    fn update(&mut self, dt: Duration, queue: &wgpu::Queue, input: &InputState) {
+        if input.just_pressed(KeyCode::KeyB) {
+            self.material.normal_map ^= 1;
+            queue.write_buffer(&self.material_buffer, 0, bytemuck::bytes_of(&self.material));
+            info!("Normal mapping: {}", self.normal_mapping());
+        }
+        self.controller
+            .update(input, dt, self.config.height, &mut self.camera);
+    }
+
+    fn status(&self) -> Option<String> {
+        Some(format!("normal mapping {} (B)", self.normal_mapping()))
+    }
+
+    /// 记录新的表面配置，用于相机的宽高比
+    fn resized(&mut self, _device: &wgpu::Device, config: &wgpu::SurfaceConfiguration) {
+        self.config = config.clone();
+    }
+}
+
+impl BrickCube {
+    /// 法线贴图的开关状态（显示用）
+    fn normal_mapping(&self) -> &'static str {
+        if self.material.normal_map != 0 {
+            "on"
+        } else {
+            "off"
+        }
+    }
+
+    /// 用`rebuild_pipeline`按sample_count重建管线，成功后才更新多重采样数
+    fn rebuild_pipeline(
+        &mut self,
+        device: &wgpu::Device,
+        format: wgpu::TextureFormat,
+        sample_count: u32,
+    ) -> Result<(), WgpuAppError> {
+        rebuild_pipeline(device, SHADER, &mut self.pipeline, |shader| {
+            create_pipeline(
+                device,
+                shader,
+                format,
+                &self.pipeline_layout,
+                sample_count,
+                self.cache.as_ref(),
+            )
+        })?;
+        self.sample_count = sample_count;
+        Ok(())
+    }
+}
+
+/// 第1组：材质uniform、颜色贴图和采样器、法线贴图和采样器
+fn create_material_layout(device: &wgpu::Device) -> BindingLayout {
+    let float = wgpu::TextureSampleType::Float { filterable: true };
+    BindGroupLayoutBuilder::new()
+        .label("Brick Cube Material Layout")
+        .uniform(wgpu::ShaderStages::FRAGMENT)
+        .texture(
+            wgpu::ShaderStages::FRAGMENT,
+            float,
+            wgpu::TextureViewDimension::D2,
+        )
+        .sampler(
+            wgpu::ShaderStages::FRAGMENT,
+            wgpu::SamplerBindingType::Filtering,
+        )
+        .texture(
+            wgpu::ShaderStages::FRAGMENT,
+            float,
+            wgpu::TextureViewDimension::D2,
+        )
+        .sampler(
+            wgpu::ShaderStages::FRAGMENT,
+            wgpu::SamplerBindingType::Filtering,
+        )
+        .build(device)
+}
+
+/// 创建读取TangentVertex、剔除背面并开启深度测试的渲染管线
+fn create_pipeline(
+    device: &wgpu::Device,
+    shader: &wgpu::ShaderModule,
+    format: wgpu::TextureFormat,
+    layout: &wgpu::PipelineLayout,
+    sample_count: u32,
+    cache: Option<&wgpu::PipelineCache>,
+) -> wgpu::RenderPipeline {
+    device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
+        label: Some("Brick Cube Pipeline"),
+        layout: Some(layout),
+        vertex: wgpu::VertexState {
+            module: shader,
+            entry_point: Some("vs"),
+            buffers: &[TangentVertex::LAYOUT],
+            compilation_options: Default::default(),
+        },
+        fragment: Some(wgpu::FragmentState {
+            module: shader,
+            entry_point: Some("fs"),
+            targets: &[Some(wgpu::ColorTargetState {
+                format,
+                blend: Some(wgpu::BlendState::REPLACE),
+                write_mask: wgpu::ColorWrites::ALL,
+            })],
+            compilation_options: Default::default(),
+        }),
+        primitive: wgpu::PrimitiveState {
+            cull_mode: Some(wgpu::Face::Back),
+            ..Default::default()
+        },
+        depth_stencil: Some(DepthTexture::depth_stencil_state()), // LessEqual深度测试
+        multisample: wgpu::MultisampleState {
+            count: sample_count,
+            ..Default::default()
+        },
+        multiview: None,
+        cache,
+    })
+}
@@ -0,0 +1,38 @@
+use crate::{InitContext, RedrawMode, RenderContext, Renderer, Scene, WgpuAppError};
+
+/// 只清除背景、不绘制任何内容的场景（例如用作调试窗口或验证表面配置）
+pub struct Clear;
+
+impl Renderer for Clear {
+    fn init(_ctx: &InitContext) -> Result<Self, WgpuAppError> {
+        Ok(Self)
+    }
+
+    fn render(
+        &mut self,
+        ctx: &RenderContext,
+        view: &wgpu::TextureView,
+        encoder: &mut wgpu::CommandEncoder,
+    ) {
+        encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
+            label: Some("Clear Pass"),
+            color_attachments: &[Some(ctx.color_attachment(
+                view,
+                wgpu::Operations {
+                    load: wgpu::LoadOp::Clear(ctx.clear_color),
+                    store: wgpu::StoreOp::Store,
+                },
+            ))],
+            depth_stencil_attachment: None,
+            timestamp_writes: ctx.timestamp_writes(),
+            occlusion_query_set: None,
+        });
+    }
+}
+
+impl Scene for Clear {
+    /// 画面只随背景色变化，不需要持续重绘
+    fn redraw_mode(&self) -> RedrawMode {
+        RedrawMode::OnDemand
+    }
+}
@@ -0,0 +1,138 @@
+use crate::{
+    DepthTexture, InitContext, RenderContext, Renderer, Scene, WgpuAppError, rebuild_pipeline,
+    shader,
+};
+use std::path::Path;
+
+/// 深度测试示例使用的着色器文件
+const SHADER: &str = "depth_quads.wgsl";
+
+/// 两个部分重叠的四边形：先绘制近处的，再绘制远处的
+///
+/// 只有深度纹理正确绑定时远处的蓝色四边形才会被橙色四边形遮挡，
+/// 调整窗口大小后遮挡关系出错说明深度纹理没有随表面重建。
+pub struct DepthQuads {
+    pipeline: wgpu::RenderPipeline,     // 开启深度测试的渲染管线
+    sample_count: u32,                  // 管线使用的多重采样数
+    cache: Option<wgpu::PipelineCache>, // 管线缓存（重建管线时复用）
+}
+
+impl Renderer for DepthQuads {
+    fn init(ctx: &InitContext) -> Result<Self, WgpuAppError> {
+        let InitContext {
+            device,
+            config,
+            sample_count,
+            cache,
+            ..
+        } = *ctx;
+        let shader = shader::load(device, SHADER)?;
+        let pipeline = create_pipeline(device, &shader, config.format, sample_count, cache);
+        Ok(Self {
+            pipeline,
+            sample_count,
+            cache: cache.cloned(),
+        })
+    }
+
+    fn render(
+        &mut self,
+        ctx: &RenderContext,
+        view: &wgpu::TextureView,
+        encoder: &mut wgpu::CommandEncoder,
+    ) {
+        let mut pass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
+            label: Some("Depth Quads Pass"),
+            color_attachments: &[Some(ctx.color_attachment(
+                view,
+                wgpu::Operations {
+                    load: wgpu::LoadOp::Clear(ctx.clear_color),
+                    store: wgpu::StoreOp::Store,
+                },
+            ))],
+            depth_stencil_attachment: ctx.depth_attachment(), // 深度附件（每帧清除为1.0）
+            timestamp_writes: ctx.timestamp_writes(),
+            occlusion_query_set: None,
+        });
+        pass.set_pipeline(&self.pipeline);
+        // 每个实例是一个四边形（6个顶点），实例0在近处、实例1在远处
+        pass.draw(0..6, 0..2);
+    }
+
+    fn shader_changed(
+        &mut self,
+        device: &wgpu::Device,
+        config: &wgpu::SurfaceConfiguration,
+        path: &Path,
+    ) {
+        if !shader::depends_on(SHADER, path) {
+            return;
+        }
+        let _ = self.rebuild_pipeline(device, config.format, self.sample_count);
+    }
+
+    fn sample_count_changed(
+        &mut self,
+        device: &wgpu::Device,
+        config: &wgpu::SurfaceConfiguration,
+        sample_count: u32,
+    ) -> Result<(), WgpuAppError> {
+        self.rebuild_pipeline(device, config.format, sample_count)
+    }
+}
+
+impl Scene for DepthQuads {}
+
+impl DepthQuads {
+    /// 用`rebuild_pipeline`按sample_count重建管线，成功后才更新多重采样数
+    fn rebuild_pipeline(
+        &mut self,
+        device: &wgpu::Device,
+        format: wgpu::TextureFormat,
+        sample_count: u32,
+    ) -> Result<(), WgpuAppError> {
+        rebuild_pipeline(device, SHADER, &mut self.pipeline, |shader| {
+            create_pipeline(device, shader, format, sample_count, self.cache.as_ref())
+        })?;
+        self.sample_count = sample_count;
+        Ok(())
+    }
+}
+
+/// 创建开启深度测试的渲染管线
+fn create_pipeline(
+    device: &wgpu::Device,
+    shader: &wgpu::ShaderModule,
+    format: wgpu::TextureFormat,
+    sample_count: u32,
+    cache: Option<&wgpu::PipelineCache>,
+) -> wgpu::RenderPipeline {
+    device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
+        label: Some("Depth Quads Pipeline"),
+        layout: None,
+        vertex: wgpu::VertexState {
+            module: shader,
+            entry_point: Some("vs"),
+            buffers: &[],
+            compilation_options: Default::default(),
+        },
+        fragment: Some(wgpu::FragmentState {
+            module: shader,
+            entry_point: Some("fs"),
+            targets: &[Some(wgpu::ColorTargetState {
+                format,
+                blend: Some(wgpu::BlendState::REPLACE),
+                write_mask: wgpu::ColorWrites::ALL,
+            })],
+            compilation_options: Default::default(),
+        }),
+        primitive: Default::default(),
+        depth_stencil: Some(DepthTexture::depth_stencil_state()), // LessEqual深度测试
+        multisample: wgpu::MultisampleState {
+            count: sample_count,
+            ..Default::default()
+        },
+        multiview: None,
+        cache,
+    })
+}
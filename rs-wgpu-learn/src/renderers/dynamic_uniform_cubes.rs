@@ -0,0 +1,559 @@
+use crate::{
+    DEFAULT_FRAMES_IN_FLIGHT, DepthTexture, DynamicUniformBuffer, InitContext, InputState, Mesh,
+    MeshVertex, RenderContext, Renderer, Scene, WgpuAppError,
+    anim::{CameraShake, Easing, Tween, TweenId, Tweens},
+    binding::{self, BindGroupBuilder, BindingLayout},
+    camera::{
+        Camera, CameraBuffer, CameraController, OrbitController, aspect_ratio, screen_to_ray,
+    },
+    debug_draw::{DebugDepth, DebugLines},
+    math::{Frustum, Transform},
+    picking, rebuild_pipeline, shader, shapes,
+};
+use glam::{Mat4, Quat, Vec2, Vec3};
+use log::{debug, info};
+use std::{collections::HashMap, num::NonZeroU64, path::Path, time::Duration};
+use winit::{event::MouseButton, keyboard::KeyCode};
+
+/// 动态偏移示例使用的着色器文件
+const SHADER: &str = "dynamic_uniform.wgsl";
+
+/// 每边的立方体数量（共GRID³个立方体、GRID³次绘制）
+pub(super) const GRID: u32 = 10;
+
+/// 相邻立方体中心的距离
+pub(super) const SPACING: f32 = 1.5;
+
+/// 立方体边长
+pub(super) const CUBE_SIZE: f32 = 0.7;
+
+/// 旋转速度（弧度/秒）
+pub(super) const ROTATION_SPEED: f32 = 1.0;
+
+/// 冻结的视锥体的颜色
+const FROZEN_FRUSTUM_COLOR: [f32; 4] = [1.0, 0.8, 0.2, 1.0];
+
+/// 选中的立方体的颜色（线性空间）
+const SELECTED_COLOR: [f32; 4] = [1.0, 0.05, 0.5, 1.0];
+
+/// 拾取点的颜色和半径
+const HIT_COLOR: [f32; 4] = [1.0, 1.0, 1.0, 1.0];
+const HIT_RADIUS: f32 = 0.08;
+
+/// 点中立方体时的缩放：从PULSE_SCALE倍以弹性缓动恢复到原大小
+const PULSE_SCALE: f32 = 1.6;
+const PULSE_DURATION: Duration = Duration::from_millis(800);
+
+/// 点中立方体时相机抖动的创伤值
+const SHAKE_TRAUMA: f32 = 0.5;
+
+/// 按下和松开左键之间光标移动不超过这个距离（物理像素）时算作点击，否则是拖动旋转
+const CLICK_SLOP: f32 = 4.0;
+
+/// 与着色器中ObjectUniform对应的逐对象数据
+#[repr(C)]
+#[derive(Debug, Clone, Copy, bytemuck::Pod, bytemuck::Zeroable)]
+struct ObjectUniform {
+    model: [[f32; 4]; 4], // 模型矩阵（按列存储）
+    color: [f32; 4],      // 线性空间的颜色
+}
+
+/// 对照用的朴素方式：每个立方体一个uniform缓冲区和一个绑定组
+struct PerObjectBindings {
+    buffers: Vec<wgpu::Buffer>,
+    bind_groups: Vec<wgpu::BindGroup>,
+}
+
+/// 动态偏移：GRID³个立方体逐个绘制，每个立方体有自己的模型矩阵和颜色，
+/// 但所有数据都在一个`DynamicUniformBuffer`中，只有一个缓冲区和一个绑定组
+///
+/// 每帧整体写入一次，每次绘制前以不同的动态偏移重新绑定第1组。按K键切换到朴素方式
+/// （每个立方体一个缓冲区和绑定组、每帧写入GRID³次）作对比，窗口标题中显示两种方式的资源和写入次数。
+/// 按R键切换相机uniform的槽位数（按帧轮换的`DEFAULT_FRAMES_IN_FLIGHT`份或只有1份），
+/// 对比窗口标题中的帧耗时和GPU耗时。鼠标控制相机（C键切换轨道和飞行控制）。
+///
+/// 写入和绘制之前按立方体的世界空间包围盒做视锥体剔除，只有可见的立方体占用槽位和绘制调用，
+/// 窗口标题中显示总数和绘制数。按F键冻结剔除使用的视锥体（并画出它），移动相机可以看到立方体在冻结的视锥体边界处出现和消失。
+///
+/// 左键点击用光标射线拾取绘制出的立方体（见`picking::pick`），选中的立方体改变颜色，并在交点处画一个小球；
+/// 同时相机抖动一下，被点中的立方体先放大再以弹性缓动恢复原大小（见`anim`）。
+pub struct DynamicUniformCubes {
+    pipeline: wgpu::RenderPipeline,
+    pipeline_layout: wgpu::PipelineLayout,
+    sample_count: u32,
+    cache: Option<wgpu::PipelineCache>,
+    camera: Camera,                                // 从斜上方看向立方体阵列中心的相机
+    controller: CameraController,                  // 轨道或飞行控制器（C键切换）
+    shake: CameraShake,                            // 点中立方体时触发的相机抖动
+    shaken: Camera,                                // 叠加抖动后用于渲染和拾取的相机
+    camera_buffer: CameraBuffer,                   // 第0组：相机的视图投影矩阵
+    camera_frames: u32, // 相机uniform的槽位数（R键切换，与camera_buffer不一致时在render中重建）
+    config: wgpu::SurfaceConfiguration, // 表面配置（宽高比和鼠标灵敏度，尺寸变化时更新）
+    mesh: Mesh,         // 所有立方体共用的网格
+    objects: Vec<ObjectUniform>, // CPU上所有立方体的数据，每帧更新
+    visible: Vec<ObjectUniform>, // 通过视锥体剔除的立方体，每帧整体写入
+    visible_indices: Vec<usize>, // visible中各立方体在objects中的下标
+    selected: Option<(usize, Vec3)>, // 选中的立方体的下标和模型空间的拾取点（随立方体旋转）
+    press: Option<Vec2>, // 按下左键时的光标位置，用于区分点击和拖动
+    pulses: Tweens<f32>, // 被点中的立方体的缩放补间
+    pulse_ids: HashMap<usize, TweenId>, // 立方体下标 → 它的缩放补间
+    frozen: Option<Mat4>, // 冻结的视图投影矩阵（F键切换），存在时剔除使用它而不是当前相机
+    debug_lines: DebugLines, // 冻结的视锥体
+    uniforms: DynamicUniformBuffer<ObjectUniform>, // 第1组：所有立方体的数据
+    per_object: Option<PerObjectBindings>, // 朴素方式的资源（K键切换，只在使用时存在）
+    naive: bool,        // 是否使用朴素方式
+}
+
+impl Renderer for DynamicUniformCubes {
+    fn init(ctx: &InitContext) -> Result<Self, WgpuAppError> {
+        let InitContext {
+            device,
+            queue,
+            config,
+            sample_count,
+            cache,
+            ..
+        } = *ctx;
+        // 1. 创建相机缓冲区、逐对象的动态uniform缓冲区和管线布局
+        let extent = (GRID - 1) as f32 * SPACING;
+        let orbit = OrbitController::new(Vec3::ZERO, extent * 2.4, 0.6, 0.5);
+        let mut camera = Camera::default();
+        orbit.apply(&mut camera);
+        let mut camera_buffer = CameraBuffer::new(device);
+        camera_buffer.update(&camera, aspect_ratio(config));
+        let objects = (0..GRID * GRID * GRID)
+            .map(|i| grid_object(i, 0.0, 1.0))
+            .collect::<Vec<_>>();
+        let mut uniforms = DynamicUniformBuffer::new(
+            device,
+            "Cube Objects",
+            wgpu::ShaderStages::VERTEX_FRAGMENT,
+            objects.len() as u32,
+        );
+        uniforms.write(device, queue, &objects);
+        let pipeline_layout = binding::pipeline_layout(
+            device,
+            Some("Dynamic Uniform Pipeline Layout"),
+            &[camera_buffer.layout(), uniforms.layout()],
+        );
+        info!(
+            "{} cubes share one {} KiB uniform buffer with a {}-byte stride",
+            objects.len(),
+            uniforms.buffer().size() / 1024,
+            uniforms.stride()
+        );
+
+        // 2. 加载着色器并创建管线
+        let shader = shader::load(device, SHADER)?;
+        let pipeline = create_pipeline(
+            device,
+            &shader,
+            config.format,
+            &pipeline_layout,
+            sample_count,
+            cache,
+        );
+
+        let (vertices, indices) = shapes::cube(CUBE_SIZE);
+        // 保留CPU数据，拾取时与三角形求交
+        let mesh =
+            Mesh::new_with_cpu_data(device, "Dynamic Uniform Cube", &vertices, Some(&indices));
+        let debug_lines =
+            DebugLines::new(device, config.format, sample_count, cache, DebugDepth::Test)?;
+
+        Ok(Self {
+            pipeline,
+            pipeline_layout,
+            sample_count,
+            cache: cache.cloned(),
+            camera,
+            controller: CameraController::new(orbit).with_bookmarks("dynamic-uniform"),
+            shake: CameraShake::default(),
+            shaken: camera,
+            camera_buffer,
+            camera_frames: DEFAULT_FRAMES_IN_FLIGHT,
+            config: config.clone(),
+            mesh,
+            visible: objects.clone(),
+            visible_indices: (0..objects.len()).collect(),
+            selected: None,
+            press: None,
+            pulses: Tweens::new(),
+            pulse_ids: HashMap::new(),
+            objects,
+            frozen: None,
+            debug_lines,
+            uniforms,
+            per_object: None,
+            naive: false,
+        })
+    }
+
+    fn render(
+        &mut self,
+        ctx: &RenderContext,
+        view: &wgpu::TextureView,
+        encoder: &mut wgpu::CommandEncoder,
+    ) {
+        // 在CPU上更新所有立方体的数据，剔除视锥体外的立方体，再按当前方式写入
+        let angle = (ROTATION_SPEED * ctx.time) % std::f32::consts::TAU;
+        for (i, object) in self.objects.iter_mut().enumerate() {
+            let scale = self
+                .pulse_ids
+                .get(&i)
+                .and_then(|id| self.pulses.get(*id))
+                .unwrap_or(1.0);
+            *object = grid_object(i as u32, angle, scale);
+        }
+        if let Some((selected, _)) = self.selected {
+            self.objects[selected].color = SELECTED_COLOR;
+        }
+        let view_proj = self.shaken.view_proj(aspect_ratio(&self.config));
+        let frustum = Frustum::from_view_proj(self.frozen.unwrap_or(view_proj));
+        let aabb = self.mesh.aabb();
+        self.visible.clear();
+        self.visible_indices.clear();
+        for (i, object) in self.objects.iter().enumerate() {
+            if frustum.intersects_aabb(&aabb.transform(Mat4::from_cols_array_2d(&object.model))) {
+                self.visible.push(*object);
+                self.visible_indices.push(i);
+            }
+        }
+        self.debug_lines.clear();
+        if let Some(frozen) = self.frozen {
+            self.debug_lines.add_frustum(frozen, FROZEN_FRUSTUM_COLOR);
+        }
+        if let Some((selected, local)) = self.selected {
+            let model = Mat4::from_cols_array_2d(&self.objects[selected].model);
+            self.debug_lines
+                .add_sphere(model.transform_point3(local), HIT_RADIUS, HIT_COLOR);
+        }
+        self.debug_lines.prepare(ctx, encoder, view_proj);
+
+        if self.camera_buffer.frames_in_flight() != self.camera_frames {
+            self.camera_buffer =
+                CameraBuffer::with_frames_in_flight(ctx.device, self.camera_frames);
+            self.camera_buffer
+                .update(&self.shaken, aspect_ratio(&self.config));
+        }
+        if self.naive {
+            let per_object = self.per_object.get_or_insert_with(|| {
+                PerObjectBindings::new(ctx.device, self.uniforms.layout(), self.objects.len())
+            });
+            for (buffer, object) in per_object.buffers.iter().zip(&self.visible) {
+                ctx.queue
+                    .write_buffer(buffer, 0, bytemuck::bytes_of(object));
+            }
+        } else {
+            self.per_object = None;
+            self.uniforms.write(ctx.device, ctx.queue, &self.visible);
+        }
+
+        let mut pass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
+            label: Some("Dynamic Uniform Pass"),
+            color_attachments: &[Some(ctx.color_attachment(
+                view,
+                wgpu::Operations {
+                    load: wgpu::LoadOp::Clear(ctx.clear_color),
+                    store: wgpu::StoreOp::Store,
+                },
+            ))],
+            depth_stencil_attachment: ctx.depth_attachment(),
+            timestamp_writes: ctx.timestamp_writes(),
+            occlusion_query_set: None,
+        });
+        pass.set_pipeline(&self.pipeline);
+        self.camera_buffer.bind(ctx, &mut pass, 0);
+        // 每个立方体一次绘制：同一个绑定组以不同的动态偏移绑定，或者朴素方式下换成该立方体的绑定组
+        for i in 0..self.visible.len() as u32 {
+            match &self.per_object {
+                Some(per_object) => {
+                    pass.set_bind_group(1, &per_object.bind_groups[i as usize], &[0])
+                }
+                None => {
+                    pass.set_bind_group(1, self.uniforms.bind_group(), &[self.uniforms.offset(i)])
+                }
+            }
+            self.mesh.draw(&mut pass, 0..1);
+        }
+        self.debug_lines.draw(&mut pass);
+    }
+
+    fn shader_changed(
+        &mut self,
+        device: &wgpu::Device,
+        config: &wgpu::SurfaceConfiguration,
+        path: &Path,
+    ) {
+        self.debug_lines.shader_changed(device, path);
+        if !shader::depends_on(SHADER, path) {
+            return;
+        }
+        let _ = self.rebuild_pipeline(device, config.format, self.sample_count);
+    }
+
+    fn resize(&mut self, config: &wgpu::SurfaceConfiguration) {
+        self.config = config.clone();
+    }
+
+    fn sample_count_changed(
+        &mut self,
+        device: &wgpu::Device,
+        config: &wgpu::SurfaceConfiguration,
+        sample_count: u32,
+    ) -> Result<(), WgpuAppError> {
+        self.rebuild_pipeline(device, config.format, sample_count)?;
+        self.debug_lines
+            .sample_count_changed(device, sample_count)?;
+        Ok(())
+    }
+}
+
+impl Scene for DynamicUniformCubes {
+    /// 更新相机（C键切换轨道和飞行控制），按K键切换动态偏移和朴素方式，按R键切换相机uniform的槽位数，
+    /// 按F键冻结或解冻剔除使用的视锥体，左键点击拾取立方体
+    fn update(&mut self, dt: Duration, _queue: &wgpu::Queue, input: &InputState) {
+        let cursor = input
+            .cursor()
+            .map(|cursor| Vec2::new(cursor.x as f32, cursor.y as f32));
+        if input.mouse_just_pressed(MouseButton::Left) && !input.consumed() {
+            self.press = cursor;
+        }
+        if input.mouse_just_released(MouseButton::Left) {
+            let click = self
+                .press
+                .take()
+                .zip(cursor)
+                .filter(|(press, cursor)| press.distance(*cursor) <= CLICK_SLOP);
+            if let Some((_, cursor)) = click {
+                self.pick(cursor);
+            }
+        }
+        if input.just_pressed(KeyCode::KeyF) {
+            self.frozen = match self.frozen {
+                Some(_) => None,
+                None => Some(self.camera.view_proj(aspect_ratio(&self.config))),
+            };
+            info!(
+                "Culling frustum {}",
+                if self.frozen.is_some() {
+                    "frozen"
+                } else {
+                    "follows the camera"
+                }
+            );
+        }
+        if input.just_pressed(KeyCode::KeyR) {
+            self.camera_frames = if self.camera_frames == 1 {
+                DEFAULT_FRAMES_IN_FLIGHT
+            } else {
+                1
+            };
+            info!("Camera uniform frame slots: {}", self.camera_frames);
+        }
+        if input.just_pressed(KeyCode::KeyK) {
+            self.naive = !self.naive;
+            info!(
+                "Per-object data: {}",
+                if self.naive {
+                    "one buffer and bind group per cube"
+                } else {
+                    "one dynamic uniform buffer"
+                }
+            );
+        }
+        if input.just_pressed(KeyCode::KeyC) {
+            self.controller.toggle(&self.camera);
+            info!("Camera controller: {}", self.controller.name());
+        }
+        self.controller
+            .update(input, dt, self.config.height, &mut self.camera);
+
+        // 推进缩放补间和相机抖动，渲染使用叠加抖动后的相机
+        for (id, _) in self.pulses.update(dt) {
+            self.pulse_ids.retain(|_, pulse| *pulse != id);
+        }
+        self.shake.update(dt);
+        self.shaken = self.shake.apply(&self.camera);
+        self.camera_buffer
+            .update(&self.shaken, aspect_ratio(&self.config));
+    }
+
+    /// 剔除后的绘制数、当前方式使用的缓冲区、绑定组数量、每帧的写入次数和相机uniform的槽位数
+    fn status(&self) -> Option<String> {
+        let count = self.visible.len();
+        let objects = match &self.per_object {
+            Some(per_object) => format!(
+                "naive: {} buffers, {} bind groups, {count} writes, {count} draws",
+                per_object.buffers.len(),
+                per_object.bind_groups.len()
+            ),
+            None => format!("dynamic offsets: 1 buffer, 1 bind group, 1 write, {count} draws"),
+        };
+        let frozen = if self.frozen.is_some() {
+            " (frozen)"
+        } else {
+            ""
+        };
+        Some(format!(
+            "{count}/{} cubes drawn{frozen}, {objects}, camera frame slots: {}",
+            self.objects.len(),
+            self.camera_buffer.frames_in_flight()
+        ))
+    }
+}
+
+impl DynamicUniformCubes {
+    /// 用光标射线拾取上一帧绘制的立方体，选中最近的一个并触发抖动和缩放，没有命中时取消选中
+    fn pick(&mut self, cursor: Vec2) {
+        let view_proj = self.shaken.view_proj(aspect_ratio(&self.config));
+        let ray = screen_to_ray(
+            cursor,
+            (self.config.width, self.config.height),
+            view_proj.inverse(),
+        );
+        let objects = self
+            .visible
+            .iter()
+            .map(|object| (Mat4::from_cols_array_2d(&object.model), &self.mesh));
+        self.selected = picking::pick(objects, &ray).map(|hit| {
+            let index = self.visible_indices[hit.object];
+            let model = Mat4::from_cols_array_2d(&self.visible[hit.object].model);
+            info!(
+                "Picked cube {index} at distance {:.3}, point {:.3}",
+                hit.distance, hit.position
+            );
+            (index, model.inverse().transform_point3(hit.position))
+        });
+        let Some((index, _)) = self.selected else {
+            info!("Picked nothing");
+            return;
+        };
+        // 再次点中正在缩放的立方体时替换它的补间
+        self.shake.shake(SHAKE_TRAUMA);
+        let tween = Tween::new(PULSE_SCALE, 1.0, PULSE_DURATION, Easing::ElasticOut);
+        let id = self.pulses.start_with(tween, move |_| {
+            debug!("Cube {index} finished its pulse");
+        });
+        if let Some(previous) = self.pulse_ids.insert(index, id) {
+            self.pulses.remove(previous);
+        }
+    }
+
+    /// 用`rebuild_pipeline`按sample_count重建管线，成功后才更新多重采样数
+    fn rebuild_pipeline(
+        &mut self,
+        device: &wgpu::Device,
+        format: wgpu::TextureFormat,
+        sample_count: u32,
+    ) -> Result<(), WgpuAppError> {
+        rebuild_pipeline(device, SHADER, &mut self.pipeline, |shader| {
+            create_pipeline(
+                device,
+                shader,
+                format,
+                &self.pipeline_layout,
+                sample_count,
+                self.cache.as_ref(),
+            )
+        })?;
+        self.sample_count = sample_count;
+        Ok(())
+    }
+}
+
+impl PerObjectBindings {
+    /// 为count个立方体各创建一个uniform缓冲区和绑定组
+    ///
+    /// 与动态uniform缓冲区共用绑定组布局（绑定时动态偏移为0），所以不需要另一条管线。
+    fn new(device: &wgpu::Device, layout: &BindingLayout, count: usize) -> Self {
+        let size = std::mem::size_of::<ObjectUniform>() as u64;
+        let buffers = (0..count)
+            .map(|_| {
+                device.create_buffer(&wgpu::BufferDescriptor {
+                    label: Some("Cube Object Buffer"),
+                    size,
+                    usage: wgpu::BufferUsages::UNIFORM | wgpu::BufferUsages::COPY_DST,
+                    mapped_at_creation: false,
+                })
+            })
+            .collect::<Vec<_>>();
+        let bind_groups = buffers
+            .iter()
+            .map(|buffer| {
+                BindGroupBuilder::new(layout)
+                    .label("Cube Object Bind Group")
+                    .resource(wgpu::BindingResource::Buffer(wgpu::BufferBinding {
+                        buffer,
+                        offset: 0,
+                        size: NonZeroU64::new(size),
+                    }))
+                    .build(device)
+            })
+            .collect();
+        Self {
+            buffers,
+            bind_groups,
+        }
+    }
+}
+
+/// 以原点为中心、每边GRID个立方体的阵列中第i个立方体，旋转角度在angle的基础上按位置错开，颜色随位置渐变，
+/// scale为统一缩放
+fn grid_object(i: u32, angle: f32, scale: f32) -> ObjectUniform {
+    let (x, y, z) = (i % GRID, i / GRID % GRID, i / (GRID * GRID));
+    let last = (GRID - 1) as f32;
+    let uvw = Vec3::new(x as f32, y as f32, z as f32) / last;
+    let transform = Transform {
+        translation: (uvw - 0.5) * last * SPACING,
+        rotation: Quat::from_rotation_y(angle + uvw.x * std::f32::consts::PI)
+            * Quat::from_rotation_x(angle + uvw.z * std::f32::consts::PI),
+        scale: Vec3::splat(scale),
+    };
+    ObjectUniform {
+        model: transform.matrix().to_cols_array_2d(),
+        color: [uvw.x, uvw.y, uvw.z, 1.0],
+    }
+}
+
+/// 创建读取MeshVertex并开启深度测试的渲染管线
+fn create_pipeline(
+    device: &wgpu::Device,
+    shader: &wgpu::ShaderModule,
+    format: wgpu::TextureFormat,
+    layout: &wgpu::PipelineLayout,
+    sample_count: u32,
+    cache: Option<&wgpu::PipelineCache>,
+) -> wgpu::RenderPipeline {
+    device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
+        label: Some("Dynamic Uniform Pipeline"),
+        layout: Some(layout),
+        vertex: wgpu::VertexState {
+            module: shader,
+            entry_point: Some("vs"),
+            buffers: &[MeshVertex::LAYOUT],
+            compilation_options: Default::default(),
+        },
+        fragment: Some(wgpu::FragmentState {
+            module: shader,
+            entry_point: Some("fs"),
+            targets: &[Some(wgpu::ColorTargetState {
+                format,
+                blend: Some(wgpu::BlendState::REPLACE),
+                write_mask: wgpu::ColorWrites::ALL,
+            })],
+            compilation_options: Default::default(),
+        }),
+        primitive: wgpu::PrimitiveState {
+            cull_mode: Some(wgpu::Face::Back),
+            ..Default::default()
+        },
+        depth_stencil: Some(DepthTexture::depth_stencil_state()),
+        multisample: wgpu::MultisampleState {
+            count: sample_count,
+            ..Default::default()
+        },
+        multiview: None,
+        cache,
+    })
+}
@@ -0,0 +1,196 @@
+use crate::{
+    EnvironmentMap, InitContext, InputState, RenderContext, Renderer, SamplerCache, Scene,
+    WgpuAppError,
+    camera::{Camera, CameraController, OrbitController, Projection, aspect_ratio},
+    skybox::SkyboxPass,
+    tonemap::{HDR_FORMAT, Tonemapper},
+};
+use glam::Vec3;
+use log::{error, info};
+use std::{path::Path, time::Duration};
+use winit::keyboard::KeyCode;
+
+/// 内置的HDR全景图（512x256的程序生成天空，太阳的亮度为400）
+const SKY_HDR: &[u8] = include_bytes!("../../assets/sky.hdr");
+
+/// 立方体贴图每个面的边长（像素）
+const FACE_SIZE: u32 = 512;
+
+/// 每次按键调整的曝光值（EV）
+const EXPOSURE_STEP: f32 = 0.5;
+
+/// HDR环境贴图查看器：把等距柱状投影的`.hdr`全景图转换为立方体贴图，作为天空盒绘制到HDR纹理，
+/// 再经过色调映射写入表面
+///
+/// 启动时显示内置的程序生成天空，拖放`.hdr`文件（如Poly Haven的全景图）到窗口上可以替换它。
+/// T键依次切换色调映射曲线（ACES、直接截断、Reinhard），截断时太阳周围大于1的亮度都变成一片白色；
+/// -/=键降低或提高曝光。用鼠标拖动环视（见`OrbitController`），按C键切换到飞行相机，移动相机时天空不动。
+pub struct EnvironmentViewer {
+    camera: Camera,                     // 观察天空的相机（只有旋转影响画面）
+    controller: CameraController,       // 轨道或飞行控制器（C键切换）
+    config: wgpu::SurfaceConfiguration, // 表面配置，用于相机的宽高比
+    skybox: SkyboxPass,                 // 绘制到HDR纹理的天空盒
+    tonemapper: Tonemapper,             // HDR纹理到表面的色调映射
+    panorama: String,                   // 当前全景图的名称
+    peak: f32,                          // 当前全景图中最亮的颜色分量
+    samplers: SamplerCache,             // 替换全景图时立方体贴图的采样器
+}
+
+impl Renderer for EnvironmentViewer {
+    fn init(ctx: &InitContext) -> Result<Self, WgpuAppError> {
+        let InitContext {
+            device,
+            queue,
+            config,
+            sample_count,
+            cache,
+            samplers,
+            ..
+        } = *ctx;
+        let environment = EnvironmentMap::from_hdr_bytes(
+            device,
+            queue,
+            samplers,
+            SKY_HDR,
+            FACE_SIZE,
+            Some("Sky"),
+        )?;
+        // 天空盒绘制到HDR纹理，格式与表面无关
+        let skybox = SkyboxPass::new(
+            device,
+            HDR_FORMAT,
+            sample_count,
+            cache,
+            &environment.cube_map,
+        )?;
+        let tonemapper = Tonemapper::new(device, config, sample_count, cache)?;
+        let camera = Camera {
+            eye: Vec3::new(0.0, 0.0, 1.0),
+            projection: Projection::Perspective {
+                fovy: std::f32::consts::FRAC_PI_2,
+                znear: 0.1,
+                zfar: 10.0,
+            },
+            ..Default::default()
+        };
+        Ok(Self {
+            controller: CameraController::new(OrbitController::from_camera(&camera))
+                .with_bookmarks("environment"),
+            camera,
+            config: config.clone(),
+            skybox,
+            tonemapper,
+            panorama: "built-in sky".to_string(),
+            peak: environment.peak,
+            samplers: samplers.clone(),
+        })
+    }
+
+    fn render(
+        &mut self,
+        ctx: &RenderContext,
+        view: &wgpu::TextureView,
+        encoder: &mut wgpu::CommandEncoder,
+    ) {
+        let projection = self.camera.projection(aspect_ratio(&self.config));
+        self.skybox
+            .prepare(ctx, encoder, self.camera.view(), projection);
+        self.tonemapper.prepare(ctx);
+
+        let mut pass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
+            label: Some("Environment Viewer Pass"),
+            color_attachments: &[Some(self.tonemapper.color_attachment(wgpu::Operations {
+                load: wgpu::LoadOp::Clear(wgpu::Color::BLACK),
+                store: wgpu::StoreOp::Store,
+            }))],
+            depth_stencil_attachment: ctx.depth_attachment(), // 深度附件（每帧清除为1.0）
+            timestamp_writes: ctx.timestamp_writes(),
+            occlusion_query_set: None,
+        });
+        self.skybox.draw(&mut pass);
+        drop(pass);
+
+        self.tonemapper.render(ctx, view, encoder);
+    }
+
+    fn shader_changed(
+        &mut self,
+        device: &wgpu::Device,
+        _config: &wgpu::SurfaceConfiguration,
+        path: &Path,
+    ) {
+        self.skybox.shader_changed(device, path);
+        self.tonemapper.shader_changed(device, path);
+    }
+
+    fn sample_count_changed(
+        &mut self,
+        device: &wgpu::Device,
+        _config: &wgpu::SurfaceConfiguration,
+        sample_count: u32,
+    ) -> Result<(), WgpuAppError> {
+        self.skybox.sample_count_changed(device, sample_count)?;
+        self.tonemapper.sample_count_changed(sample_count);
+        Ok(())
+    }
+}
+
+impl Scene for EnvironmentViewer {
+    /// 处理色调映射和曝光的按键和相机控制，按C键切换轨道和飞行控制
+    fn update(&mut self, dt: Duration, _queue: &wgpu::Queue, input: &InputState) {
+        if input.just_pressed(KeyCode::KeyT) {
+            self.tonemapper.operator = self.tonemapper.operator.next();
+            info!("Tonemapping: {}", self.tonemapper.operator.name());
+        }
+        if input.just_pressed(KeyCode::Equal) || input.just_pressed(KeyCode::NumpadAdd) {
+            self.tonemapper.exposure += EXPOSURE_STEP;
+        }
+        if input.just_pressed(KeyCode::Minus) || input.just_pressed(KeyCode::NumpadSubtract) {
+            self.tonemapper.exposure -= EXPOSURE_STEP;
+        }
+        if input.just_pressed(KeyCode::KeyC) {
+            self.controller.toggle(&self.camera);
+            info!("Camera controller: {}", self.controller.name());
+        }
+        self.controller
+            .update(input, dt, self.config.height, &mut self.camera);
+    }
+
+    fn status(&self) -> Option<String> {
+        Some(format!(
+            "{} (peak {:.0}), {} tonemapping, exposure {:+.1} EV",
+            self.panorama,
+            self.peak,
+            self.tonemapper.operator.name(),
+            self.tonemapper.exposure
+        ))
+    }
+
+    /// 表面尺寸变化时记录新的宽高比和HDR纹理的尺寸
+    fn resized(&mut self, _device: &wgpu::Device, config: &wgpu::SurfaceConfiguration) {
+        self.config = config.clone();
+        self.tonemapper.resize(config);
+    }
+
+    /// 拖放`.hdr`全景图时转换为立方体贴图并替换天空，加载失败时保留原来的天空
+    fn file_dropped(&mut self, path: &Path, device: &wgpu::Device, queue: &wgpu::Queue) -> bool {
+        if path
+            .extension()
+            .is_none_or(|ext| !ext.eq_ignore_ascii_case("hdr"))
+        {
+            return false;
+        }
+        match EnvironmentMap::from_equirect(device, queue, &self.samplers, path, FACE_SIZE) {
+            Ok(environment) => {
+                self.skybox.set_cube_map(device, &environment.cube_map);
+                self.panorama = path.file_name().map_or_else(
+                    || path.display().to_string(),
+                    |name| name.to_string_lossy().into(),
+                );
+                self.peak = environment.peak;
+            }
+            Err(e) => error!("Failed to load environment map, keeping the previous one: {e}"),
+        }
+        true
+    }
+}
@@ -0,0 +1,514 @@
+use crate::{
+    DepthTexture, InitContext, InputState, MaterialRegistry, Mesh, PbrMaterial, RenderContext,
+    Renderer, SamplerCache, Scene, SortKey, TangentVertex, WgpuAppError,
+    assets::gltf::{self, GltfScene, Mat4},
+    binding::{self, BindGroupBuilder, BindGroupLayoutBuilder, BindingLayout},
+    bounds,
+    picking::{ObjectId, PickingPass},
+    rebuild_pipeline, shader, shapes, with_tangents,
+};
+use glam::{Quat, Vec2, Vec3};
+use log::{error, info};
+use std::{path::Path, time::Duration};
+use wgpu::util::DeviceExt;
+use winit::{
+    event::{MouseButton, WindowEvent},
+    keyboard::KeyCode,
+};
+
+/// glTF查看器使用的着色器文件
+const SHADER: &str = "gltf_viewer.wgsl";
+
+/// 旋转速度（弧度/秒）
+const ROTATION_SPEED: f32 = 0.5;
+
+/// 相机到原点的距离（与着色器中的CAMERA_DISTANCE一致），场景缩放到单位球内
+const CAMERA_DISTANCE: f32 = 3.0;
+
+/// 排序键中主管线的编号（这个查看器只有一条绘制管线）
+const MAIN_PIPELINE: u16 = 0;
+
+/// 每次按键调整粗糙度的幅度
+const ROUGHNESS_STEP: f32 = 0.1;
+
+/// 与着色器中ViewUniform对应的数据（按16字节对齐）
+#[repr(C)]
+#[derive(Debug, Clone, Copy, bytemuck::Pod, bytemuck::Zeroable)]
+struct ViewUniform {
+    center: [f32; 3],
+    scale: f32,
+    angle: f32,
+    aspect: f32,
+    selected: u32, // 高亮的物体ID（0表示没有）
+    _padding: f32,
+}
+
+/// 与着色器中Object对应的数据（mat3x3f的每列占16字节）
+#[repr(C)]
+#[derive(Debug, Clone, Copy, bytemuck::Pod, bytemuck::Zeroable)]
+struct ObjectUniform {
+    model: Mat4,
+    normal: [[f32; 4]; 3],
+    id: u32, // 拾取用的物体ID（从1开始）
+    _padding: [u32; 3],
+}
+
+/// 一次绘制：网格、对应的Object绑定组和排序键（包含材质句柄）
+struct DrawItem {
+    key: SortKey,
+    id: ObjectId, // 拾取用的对象ID
+    mesh: Mesh,
+    object: wgpu::BindGroup,
+    center: Vec3, // 世界空间包围盒的中心，每帧据此计算深度
+}
+
+/// glTF查看器：绘制旋转的场景，拖放`.gltf`或`.glb`文件到窗口上即可替换
+///
+/// 启动时显示一个使用glTF默认材质的立方体。材质为`PbrMaterial`，由`pbr.wgsl`在单个方向光下计算
+/// Cook-Torrance直接光照，再加上简单的环境光（还没有基于图像的光照）；有法线贴图的材质通过切线
+/// （文件自带或加载时计算）应用法线贴图。右键单击通过GPU拾取（见`PickingPass`）选中并高亮光标下的对象，
+/// 上下方向键调整选中对象所用材质的粗糙度。
+///
+/// 材质由`MaterialRegistry`去重，每帧按`SortKey`（管线、材质、深度）排序后绘制，
+/// 相邻的绘制使用同一材质时不重复绑定，窗口标题中显示绑定次数和跳过的次数。
+pub struct GltfViewer {
+    pipeline: wgpu::RenderPipeline,
+    id_pipeline: wgpu::RenderPipeline, // 把对象ID绘制到拾取目标上
+    pipeline_layout: wgpu::PipelineLayout,
+    object_layout: BindingLayout,
+    sample_count: u32,
+    cache: Option<wgpu::PipelineCache>,
+    uniform_buffer: wgpu::Buffer,
+    bind_group: wgpu::BindGroup,
+    uniform: ViewUniform,
+    materials: MaterialRegistry, // 第1组：场景中的材质
+    samplers: SamplerCache,      // 应用的采样器缓存（加载拖放的场景时使用）
+    draws: Vec<DrawItem>,        // 按排序键排序（每帧重新排序）
+    names: Vec<String>,          // 对象名称，按ID（减1）索引
+    picking: PickingPass,
+    selected: ObjectId, // 最近一次拾取的结果
+}
+
+impl Renderer for GltfViewer {
+    fn init(ctx: &InitContext) -> Result<Self, WgpuAppError> {
+        let InitContext {
+            device,
+            queue,
+            config,
+            sample_count,
+            cache,
+            samplers,
+            ..
+        } = *ctx;
+        // 1. 创建uniform缓冲区、三个绑定组布局和管线布局
+        let uniform_buffer = device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some("glTF Viewer Uniform Buffer"),
+            size: std::mem::size_of::<ViewUniform>() as u64,
+            usage: wgpu::BufferUsages::UNIFORM | wgpu::BufferUsages::COPY_DST,
+            mapped_at_creation: false,
+        });
+        let view_layout = BindGroupLayoutBuilder::new()
+            .label("glTF Viewer View Layout")
+            .uniform(wgpu::ShaderStages::VERTEX_FRAGMENT)
+            .build(device);
+        let mut materials = MaterialRegistry::new(device, queue, samplers, 1);
+        let object_layout = BindGroupLayoutBuilder::new()
+            .label("glTF Viewer Object Layout")
+            .uniform(wgpu::ShaderStages::VERTEX_FRAGMENT)
+            .build(device);
+        let bind_group = BindGroupBuilder::new(&view_layout)
+            .label("glTF Viewer View Bind Group")
+            .buffer(&uniform_buffer)
+            .build(device);
+        let pipeline_layout = binding::pipeline_layout(
+            device,
+            Some("glTF Viewer Pipeline Layout"),
+            &[&view_layout, materials.layout(), &object_layout],
+        );
+
+        // 2. 加载着色器并创建开启深度测试的管线，以及共用同一布局的ID管线
+        let shader = shader::load(device, SHADER)?;
+        let pipeline = create_pipeline(
+            device,
+            &shader,
+            config.format,
+            &pipeline_layout,
+            sample_count,
+            cache,
+        );
+        let id_pipeline = create_id_pipeline(device, &shader, &pipeline_layout, cache);
+
+        // 3. 拖放文件之前显示一个使用默认材质的立方体
+        let (vertices, indices) = shapes::cube(1.0);
+        let vertices = with_tangents(&vertices, &indices);
+        let material = materials.intern(device, &PbrMaterial::default());
+        let mut viewer = Self {
+            pipeline,
+            id_pipeline,
+            pipeline_layout,
+            object_layout,
+            sample_count,
+            cache: cache.cloned(),
+            uniform_buffer,
+            bind_group,
+            uniform: bytemuck::Zeroable::zeroed(),
+            materials,
+            samplers: samplers.clone(),
+            draws: Vec::new(),
+            names: vec!["Cube".to_string()],
+            picking: PickingPass::new(device, config),
+            selected: ObjectId::NONE,
+        };
+        let id = ObjectId::from_index(0);
+        viewer.draws = vec![DrawItem {
+            key: SortKey::new(MAIN_PIPELINE, material, 0.0),
+            id,
+            mesh: Mesh::new(device, "Cube", &vertices, Some(&indices)),
+            object: viewer.object_bind_group(device, &gltf::IDENTITY, id),
+            center: Vec3::ZERO,
+        }];
+        viewer.fit(bounds(vertices.iter().map(|vertex| vertex.position)));
+        Ok(viewer)
+    }
+
+    fn render(
+        &mut self,
+        ctx: &RenderContext,
+        view: &wgpu::TextureView,
+        encoder: &mut wgpu::CommandEncoder,
+    ) {
+        self.uniform.angle = (ROTATION_SPEED * ctx.time) % std::f32::consts::TAU;
+        self.uniform.aspect = ctx.config.width.max(1) as f32 / ctx.config.height.max(1) as f32;
+        self.uniform.selected = self.selected.0;
+        ctx.upload(encoder, &self.uniform_buffer, 0, &self.uniform);
+        self.sort_draws();
+        self.materials.reset_stats();
+
+        // 有拾取请求时先在光标处绘制ID（与主通道使用同一帧的旋转角度）
+        let (id_pipeline, bind_group) = (&self.id_pipeline, &self.bind_group);
+        let (materials, draws) = (&mut self.materials, &self.draws);
+        self.picking.render(encoder, |pass| {
+            pass.set_pipeline(id_pipeline);
+            pass.set_bind_group(0, bind_group, &[]);
+            draw_sorted(pass, materials, draws);
+        });
+
+        let mut pass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
+            label: Some("glTF Viewer Pass"),
+            color_attachments: &[Some(ctx.color_attachment(
+                view,
+                wgpu::Operations {
+                    load: wgpu::LoadOp::Clear(ctx.clear_color),
+                    store: wgpu::StoreOp::Store,
+                },
+            ))],
+            depth_stencil_attachment: ctx.depth_attachment(), // 深度附件（每帧清除为1.0）
+            timestamp_writes: ctx.timestamp_writes(),
+            occlusion_query_set: None,
+        });
+        pass.set_pipeline(&self.pipeline);
+        pass.set_bind_group(0, &self.bind_group, &[]);
+        draw_sorted(&mut pass, &mut self.materials, &self.draws);
+    }
+
+    fn shader_changed(
+        &mut self,
+        device: &wgpu::Device,
+        config: &wgpu::SurfaceConfiguration,
+        path: &Path,
+    ) {
+        if !shader::depends_on(SHADER, path) {
+            return;
+        }
+        let _ = self.rebuild_pipeline(device, config.format, self.sample_count);
+    }
+
+    fn sample_count_changed(
+        &mut self,
+        device: &wgpu::Device,
+        config: &wgpu::SurfaceConfiguration,
+        sample_count: u32,
+    ) -> Result<(), WgpuAppError> {
+        self.rebuild_pipeline(device, config.format, sample_count)
+    }
+}
+
+impl Scene for GltfViewer {
+    /// 右键单击时请求拾取，并接收之前请求的结果；上下方向键调整选中对象的材质粗糙度
+    fn update(&mut self, _dt: Duration, queue: &wgpu::Queue, input: &InputState) {
+        if let Some(id) = self.picking.poll() {
+            self.selected = id;
+            match self.object_name(id) {
+                Some(name) => info!("Picked object {} ({name})", id.0),
+                None => info!("Picked nothing"),
+            }
+        }
+        let clicked = input
+            .cursor()
+            .filter(|_| input.mouse_just_pressed(MouseButton::Right));
+        if let Some(cursor) = clicked {
+            self.picking
+                .request(Vec2::new(cursor.x as f32, cursor.y as f32));
+        }
+        let step = if input.just_pressed(KeyCode::ArrowUp) {
+            ROUGHNESS_STEP
+        } else if input.just_pressed(KeyCode::ArrowDown) {
+            -ROUGHNESS_STEP
+        } else {
+            0.0
+        };
+        let material = self
+            .draws
+            .iter()
+            .find(|draw| draw.id == self.selected)
+            .map(|draw| draw.key.material());
+        if let Some(material) = material.filter(|_| step != 0.0) {
+            self.materials.update(queue, material, |uniform| {
+                uniform.roughness = (uniform.roughness + step).clamp(0.0, 1.0);
+            });
+            info!(
+                "Material {} roughness: {:.1}",
+                material.index(),
+                self.materials.uniform(material).roughness
+            );
+        }
+    }
+
+    /// 这个场景没有可自由移动的相机，右键留给拾取，不进入鼠标视角模式
+    fn input(&mut self, event: &WindowEvent) -> bool {
+        matches!(
+            event,
+            WindowEvent::MouseInput {
+                button: MouseButton::Right,
+                ..
+            }
+        )
+    }
+
+    /// 表面尺寸变化后重新创建拾取目标
+    fn resized(&mut self, device: &wgpu::Device, config: &wgpu::SurfaceConfiguration) {
+        self.picking.resize(device, config);
+        self.resize(config);
+    }
+
+    fn status(&self) -> Option<String> {
+        let selection = match self.object_name(self.selected) {
+            Some("") => format!("selected object {}", self.selected.0),
+            Some(name) => format!("selected {name}"),
+            None => "right-click to pick".to_string(),
+        };
+        let stats = self.materials.stats();
+        Some(format!(
+            "{selection}, {} materials, {} binds ({} redundant skipped)",
+            stats.materials, stats.binds, stats.redundant_binds
+        ))
+    }
+
+    /// 拖放`.gltf`或`.glb`文件时加载并替换当前场景，加载失败时保留原场景
+    fn file_dropped(&mut self, path: &Path, device: &wgpu::Device, queue: &wgpu::Queue) -> bool {
+        let is_gltf = path
+            .extension()
+            .is_some_and(|ext| ext.eq_ignore_ascii_case("gltf") || ext.eq_ignore_ascii_case("glb"));
+        if !is_gltf {
+            return false;
+        }
+        match gltf::load(device, queue, &self.samplers, path) {
+            Ok(scene) => self.set_scene(device, scene),
+            Err(e) => error!("Failed to load glTF scene, keeping the previous one: {e}"),
+        }
+        true
+    }
+}
+
+impl GltfViewer {
+    /// 注册加载的场景的材质、为对象创建绑定组并替换当前场景
+    fn set_scene(&mut self, device: &wgpu::Device, scene: GltfScene) {
+        self.fit(scene.bounds);
+        self.selected = ObjectId::NONE;
+        self.names.clear();
+        self.materials.clear();
+        self.draws.clear();
+        // 对象ID按所有分组中的顺序从1开始编号
+        for group in scene.groups {
+            let material = self.materials.intern(device, &group.material);
+            for object in group.objects {
+                let id = ObjectId::from_index(self.names.len());
+                self.draws.push(DrawItem {
+                    key: SortKey::new(MAIN_PIPELINE, material, 0.0),
+                    id,
+                    object: self.object_bind_group(device, &object.transform, id),
+                    center: object.aabb.center(),
+                    mesh: object.mesh,
+                });
+                self.names.push(object.name);
+            }
+        }
+    }
+
+    /// 按当前帧的旋转角度更新每次绘制的深度分桶，再按排序键排序
+    ///
+    /// 场景缩放到单位球内，对象中心到相机的距离在CAMERA_DISTANCE ± 1之间，映射到0..1。
+    fn sort_draws(&mut self) {
+        let rotation = Quat::from_rotation_y(self.uniform.angle);
+        let center = Vec3::from(self.uniform.center);
+        for draw in &mut self.draws {
+            let position = rotation * ((draw.center - center) * self.uniform.scale);
+            let distance = CAMERA_DISTANCE - position.z;
+            let depth = (distance - (CAMERA_DISTANCE - 1.0)) / 2.0;
+            draw.key = SortKey::new(MAIN_PIPELINE, draw.key.material(), depth);
+        }
+        self.draws.sort_unstable_by_key(|draw| draw.key);
+    }
+
+    /// ID对应的对象名称，ID为0或不存在时返回None
+    fn object_name(&self, id: ObjectId) -> Option<&str> {
+        id.index()
+            .and_then(|index| self.names.get(index))
+            .map(String::as_str)
+    }
+
+    /// 第2组：对象的模型矩阵、法线矩阵和拾取ID
+    fn object_bind_group(
+        &self,
+        device: &wgpu::Device,
+        transform: &Mat4,
+        id: ObjectId,
+    ) -> wgpu::BindGroup {
+        let uniform = ObjectUniform {
+            model: *transform,
+            normal: gltf::normal_matrix(transform),
+            id: id.0,
+            _padding: [0; 3],
+        };
+        let buffer = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+            label: Some("glTF Object Buffer"),
+            contents: bytemuck::bytes_of(&uniform),
+            usage: wgpu::BufferUsages::UNIFORM,
+        });
+        BindGroupBuilder::new(&self.object_layout)
+            .label("glTF Object Bind Group")
+            .buffer(&buffer)
+            .build(device)
+    }
+
+    /// 把包围盒的中心移到原点，并缩放到单位球内
+    fn fit(&mut self, (min, max): ([f32; 3], [f32; 3])) {
+        let extent: [f32; 3] = std::array::from_fn(|i| max[i] - min[i]);
+        let radius = extent.iter().map(|e| e * e).sum::<f32>().sqrt() / 2.0;
+        self.uniform.center = std::array::from_fn(|i| (min[i] + max[i]) / 2.0);
+        self.uniform.scale = if radius > f32::EPSILON {
+            1.0 / radius
+        } else {
+            1.0
+        };
+    }
+
+    /// 用`rebuild_pipeline`按sample_count重建管线和拾取用的ID管线，成功后才更新多重采样数
+    fn rebuild_pipeline(
+        &mut self,
+        device: &wgpu::Device,
+        format: wgpu::TextureFormat,
+        sample_count: u32,
+    ) -> Result<(), WgpuAppError> {
+        let mut pipelines = (self.pipeline.clone(), self.id_pipeline.clone());
+        rebuild_pipeline(device, SHADER, &mut pipelines, |shader| {
+            let pipeline = create_pipeline(
+                device,
+                shader,
+                format,
+                &self.pipeline_layout,
+                sample_count,
+                self.cache.as_ref(),
+            );
+            let id_pipeline =
+                create_id_pipeline(device, shader, &self.pipeline_layout, self.cache.as_ref());
+            (pipeline, id_pipeline)
+        })?;
+        (self.pipeline, self.id_pipeline) = pipelines;
+        self.sample_count = sample_count;
+        Ok(())
+    }
+}
+
+/// 按排序后的顺序绘制所有对象，材质与上一次绘制相同时由注册表跳过绑定
+fn draw_sorted(
+    pass: &mut wgpu::RenderPass<'_>,
+    materials: &mut MaterialRegistry,
+    draws: &[DrawItem],
+) {
+    materials.begin_pass();
+    for draw in draws {
+        materials.bind(pass, draw.key.material());
+        pass.set_bind_group(2, &draw.object, &[]);
+        draw.mesh.draw(pass, 0..1);
+    }
+}
+
+/// 创建读取TangentVertex并开启深度测试的渲染管线
+fn create_pipeline(
+    device: &wgpu::Device,
+    shader: &wgpu::ShaderModule,
+    format: wgpu::TextureFormat,
+    layout: &wgpu::PipelineLayout,
+    sample_count: u32,
+    cache: Option<&wgpu::PipelineCache>,
+) -> wgpu::RenderPipeline {
+    device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
+        label: Some("glTF Viewer Pipeline"),
+        layout: Some(layout),
+        vertex: wgpu::VertexState {
+            module: shader,
+            entry_point: Some("vs"),
+            buffers: &[TangentVertex::LAYOUT],
+            compilation_options: Default::default(),
+        },
+        fragment: Some(wgpu::FragmentState {
+            module: shader,
+            entry_point: Some("fs"),
+            targets: &[Some(wgpu::ColorTargetState {
+                format,
+                blend: Some(wgpu::BlendState::REPLACE),
+                write_mask: wgpu::ColorWrites::ALL,
+            })],
+            compilation_options: Default::default(),
+        }),
+        primitive: Default::default(), // 不剔除背面（双面材质也能正确显示）
+        depth_stencil: Some(DepthTexture::depth_stencil_state()), // LessEqual深度测试
+        multisample: wgpu::MultisampleState {
+            count: sample_count,
+            ..Default::default()
+        },
+        multiview: None,
+        cache,
+    })
+}
+
+/// 创建把对象ID写入拾取目标的管线：与主管线共用顶点着色器和布局，单采样、开启深度测试
+fn create_id_pipeline(
+    device: &wgpu::Device,
+    shader: &wgpu::ShaderModule,
+    layout: &wgpu::PipelineLayout,
+    cache: Option<&wgpu::PipelineCache>,
+) -> wgpu::RenderPipeline {
+    device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
+        label: Some("glTF Viewer ID Pipeline"),
+        layout: Some(layout),
+        vertex: wgpu::VertexState {
+            module: shader,
+            entry_point: Some("vs"),
+            buffers: &[TangentVertex::LAYOUT],
+            compilation_options: Default::default(),
+        },
+        fragment: Some(wgpu::FragmentState {
+            module: shader,
+            entry_point: Some("fs_id"),
+            targets: &[Some(PickingPass::color_target())],
+            compilation_options: Default::default(),
+        }),
+        primitive: Default::default(),
+        depth_stencil: Some(DepthTexture::depth_stencil_state()),
+        multisample: Default::default(),
+        multiview: None,
+        cache,
+    })
+}
@@ -0,0 +1,313 @@
+use crate::{
+    DepthTexture, InitContext, InputState, Instance, InstanceBuffer, Mesh, MeshVertex,
+    RenderContext, Renderer, Scene, WgpuAppError, binding,
+    camera::{Camera, CameraBuffer, CameraController, OrbitController, Projection, aspect_ratio},
+    math::Transform,
+    rebuild_pipeline, shader, shapes,
+};
+use glam::{Quat, Vec3};
+use log::info;
+use std::{path::Path, time::Duration};
+use winit::keyboard::KeyCode;
+
+/// 实例化示例使用的着色器文件
+pub(super) const SHADER: &str = "instancing.wgsl";
+
+/// 相邻立方体中心的距离
+pub(super) const SPACING: f32 = 1.0;
+
+/// 立方体边长
+pub(super) const CUBE_SIZE: f32 = 0.6;
+
+/// 旋转速度（弧度/秒）
+const ROTATION_SPEED: f32 = 1.0;
+
+/// 上传压力测试的网格边长：115×115个实例共约1 MB
+const STRESS_GRID_SIZE: u32 = 115;
+
+/// 相机向下俯视的角度（弧度）
+const CAMERA_PITCH: f32 = 0.7;
+
+/// GRID×GRID个旋转的立方体：共用一个网格，每帧在CPU上更新所有实例的模型矩阵，只用一次绘制调用
+///
+/// 实例数据默认通过上传器（暂存缓冲区）写入，按U键在上传器和`queue.write_buffer`之间切换，
+/// 可以在标题栏的帧耗时中对比两种方式。按P键在透视和正交投影之间切换，
+/// 用鼠标左键拖动旋转、滚轮缩放、中键（或Shift+左键）拖动平移相机（见`OrbitController`），
+/// 按C键切换到用WASD、Q/E和鼠标视角移动的飞行相机（见`FlyController`）。
+pub struct InstancedCubes<const GRID: u32 = 100> {
+    pipeline: wgpu::RenderPipeline,
+    pipeline_layout: wgpu::PipelineLayout,
+    sample_count: u32,
+    cache: Option<wgpu::PipelineCache>,
+    camera: Camera,                     // 从斜上方看向网格中心的相机
+    controller: CameraController,       // 轨道或飞行控制器（C键切换）
+    camera_buffer: CameraBuffer,        // 第0组：相机的视图投影矩阵
+    config: wgpu::SurfaceConfiguration, // 表面配置（宽高比和鼠标灵敏度，尺寸变化时更新）
+    mesh: Mesh,                         // 立方体网格，槽位1挂着实例缓冲区
+    instance_buffer: InstanceBuffer,    // 所有立方体的实例数据
+    instances: Vec<Instance>,           // CPU上的实例数据，每帧更新后整体写入
+    use_uploader: bool,                 // 通过上传器写入实例数据（否则使用queue.write_buffer）
+}
+
+/// 上传压力测试：每帧写入约1 MB实例数据的立方体网格
+pub type UploadStress = InstancedCubes<STRESS_GRID_SIZE>;
+
+impl<const GRID: u32> Renderer for InstancedCubes<GRID> {
+    fn init(ctx: &InitContext) -> Result<Self, WgpuAppError> {
+        let InitContext {
+            device,
+            config,
+            sample_count,
+            cache,
+            ..
+        } = *ctx;
+        // 1. 创建相机缓冲区和管线布局（相机在第0组）
+        let (camera, orbit) = grid_camera(GRID);
+        let mut camera_buffer = CameraBuffer::new(device);
+        camera_buffer.update(&camera, aspect_ratio(config));
+        let pipeline_layout = binding::pipeline_layout(
+            device,
+            Some("Instancing Pipeline Layout"),
+            &[camera_buffer.layout()],
+        );
+
+        // 2. 加载着色器并创建管线（顶点缓冲区 + 实例缓冲区）
+        let shader = shader::load(device, SHADER)?;
+        let pipeline = create_pipeline(
+            device,
+            &shader,
+            config.format,
+            &pipeline_layout,
+            sample_count,
+            cache,
+        );
+
+        // 3. 创建立方体网格和实例缓冲区，实例缓冲区挂到网格的槽位1
+        let (vertices, indices) = shapes::cube(CUBE_SIZE);
+        let mut mesh = Mesh::new(device, "Instanced Cube", &vertices, Some(&indices));
+        let instances = (0..GRID * GRID)
+            .map(|i| grid_instance(GRID, i, 0.0))
+            .collect::<Vec<_>>();
+        let instance_buffer = InstanceBuffer::new(device, "Cube Grid", &instances);
+        mesh.add_vertex_buffer(instance_buffer.buffer().clone());
+        info!(
+            "Drawing {} cubes with a single draw call, uploading {} KiB of instance data per frame",
+            instance_buffer.len(),
+            std::mem::size_of_val(instances.as_slice()) / 1024
+        );
+
+        Ok(Self {
+            pipeline,
+            pipeline_layout,
+            sample_count,
+            cache: cache.cloned(),
+            camera,
+            controller: CameraController::new(orbit)
+                .with_bookmarks(&format!("instancing-{GRID}x{GRID}")),
+            camera_buffer,
+            config: config.clone(),
+            mesh,
+            instance_buffer,
+            instances,
+            use_uploader: true,
+        })
+    }
+
+    fn render(
+        &mut self,
+        ctx: &RenderContext,
+        view: &wgpu::TextureView,
+        encoder: &mut wgpu::CommandEncoder,
+    ) {
+        // 在CPU上更新所有实例的旋转，再整体写入实例缓冲区
+        let angle = (ROTATION_SPEED * ctx.time) % std::f32::consts::TAU;
+        for (i, instance) in self.instances.iter_mut().enumerate() {
+            *instance = grid_instance(GRID, i as u32, angle);
+        }
+        if self.use_uploader {
+            self.instance_buffer.upload(
+                &mut ctx.uploader.borrow_mut(),
+                encoder,
+                0,
+                &self.instances,
+            );
+        } else {
+            self.instance_buffer.update(ctx.queue, 0, &self.instances);
+        }
+
+        let mut pass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
+            label: Some("Instancing Pass"),
+            color_attachments: &[Some(ctx.color_attachment(
+                view,
+                wgpu::Operations {
+                    load: wgpu::LoadOp::Clear(ctx.clear_color),
+                    store: wgpu::StoreOp::Store,
+                },
+            ))],
+            depth_stencil_attachment: ctx.depth_attachment(), // 深度附件（每帧清除为1.0）
+            timestamp_writes: ctx.timestamp_writes(),
+            occlusion_query_set: None,
+        });
+        pass.set_pipeline(&self.pipeline);
+        self.camera_buffer.bind(ctx, &mut pass, 0);
+        // 一次draw_indexed绘制所有实例
+        self.mesh.draw(&mut pass, self.instance_buffer.range());
+    }
+
+    fn shader_changed(
+        &mut self,
+        device: &wgpu::Device,
+        config: &wgpu::SurfaceConfiguration,
+        path: &Path,
+    ) {
+        if !shader::depends_on(SHADER, path) {
+            return;
+        }
+        let _ = self.rebuild_pipeline(device, config.format, self.sample_count);
+    }
+
+    fn resize(&mut self, config: &wgpu::SurfaceConfiguration) {
+        self.config = config.clone();
+    }
+
+    fn sample_count_changed(
+        &mut self,
+        device: &wgpu::Device,
+        config: &wgpu::SurfaceConfiguration,
+        sample_count: u32,
+    ) -> Result<(), WgpuAppError> {
+        self.rebuild_pipeline(device, config.format, sample_count)
+    }
+}
+
+impl<const GRID: u32> Scene for InstancedCubes<GRID> {
+    /// 更新相机（C键切换轨道和飞行控制）并写入相机矩阵，按P键切换投影方式，按U键切换实例数据的写入方式
+    fn update(&mut self, dt: Duration, _queue: &wgpu::Queue, input: &InputState) {
+        if input.just_pressed(KeyCode::KeyP) {
+            self.camera.toggle_projection();
+            info!("Projection: {}", self.camera.projection.name());
+        }
+        if input.just_pressed(KeyCode::KeyC) {
+            self.controller.toggle(&self.camera);
+            info!("Camera controller: {}", self.controller.name());
+        }
+        self.controller
+            .update(input, dt, self.config.height, &mut self.camera);
+        self.camera_buffer
+            .update(&self.camera, aspect_ratio(&self.config));
+        if input.just_pressed(KeyCode::KeyU) {
+            self.use_uploader = !self.use_uploader;
+            let path = if self.use_uploader {
+                "staging belt uploader"
+            } else {
+                "queue.write_buffer"
+            };
+            info!("Uploading instance data with {path}");
+        }
+    }
+}
+
+impl<const GRID: u32> InstancedCubes<GRID> {
+    /// 用`rebuild_pipeline`按sample_count重建管线，成功后才更新多重采样数
+    fn rebuild_pipeline(
+        &mut self,
+        device: &wgpu::Device,
+        format: wgpu::TextureFormat,
+        sample_count: u32,
+    ) -> Result<(), WgpuAppError> {
+        rebuild_pipeline(device, SHADER, &mut self.pipeline, |shader| {
+            create_pipeline(
+                device,
+                shader,
+                format,
+                &self.pipeline_layout,
+                sample_count,
+                self.cache.as_ref(),
+            )
+        })?;
+        self.sample_count = sample_count;
+        Ok(())
+    }
+}
+
+/// 从斜上方（俯视CAMERA_PITCH）看向原点、能看到每边grid个立方体的整个网格的相机，
+/// 以及控制它绕网格中心旋转的轨道控制器
+///
+/// 距离限制在近裁剪面之外到远裁剪面能容纳整个网格的范围内。
+pub(super) fn grid_camera(grid: u32) -> (Camera, OrbitController) {
+    let distance = grid as f32 * SPACING * 1.1;
+    let mut orbit = OrbitController::new(Vec3::ZERO, distance, 0.0, CAMERA_PITCH);
+    orbit.min_distance = 2.0 * SPACING;
+    orbit.max_distance = 2.0 * distance;
+    let mut camera = Camera {
+        projection: Projection::Perspective {
+            fovy: std::f32::consts::FRAC_PI_4,
+            znear: 1.0,
+            zfar: 300.0,
+        },
+        ..Default::default()
+    };
+    orbit.apply(&mut camera);
+    (camera, orbit)
+}
+
+/// XZ平面上以原点为中心、每边grid个立方体的网格中第i个立方体，先绕X轴再绕Y轴旋转
+///
+/// 旋转角度在angle的基础上按位置错开，颜色随位置渐变。
+pub(super) fn grid_instance(grid: u32, i: u32, angle: f32) -> Instance {
+    let (row, column) = (i / grid, i % grid);
+    let last = (grid - 1) as f32;
+    let (u, v) = (column as f32 / last, row as f32 / last);
+    let x = (column as f32 - last / 2.0) * SPACING;
+    let z = (row as f32 - last / 2.0) * SPACING;
+    let rotation = Quat::from_rotation_y(angle + (u + v) * std::f32::consts::PI)
+        * Quat::from_rotation_x(2.0 * angle + u * std::f32::consts::TAU);
+    let transform = Transform {
+        translation: Vec3::new(x, 0.0, z),
+        rotation,
+        scale: Vec3::ONE,
+    };
+    Instance::from_transform(&transform, [u, 0.4, 1.0 - v, 1.0])
+}
+
+/// 创建读取MeshVertex（槽位0）和Instance（槽位1）并开启深度测试的渲染管线
+pub(super) fn create_pipeline(
+    device: &wgpu::Device,
+    shader: &wgpu::ShaderModule,
+    format: wgpu::TextureFormat,
+    layout: &wgpu::PipelineLayout,
+    sample_count: u32,
+    cache: Option<&wgpu::PipelineCache>,
+) -> wgpu::RenderPipeline {
+    device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
+        label: Some("Instancing Pipeline"),
+        layout: Some(layout),
+        vertex: wgpu::VertexState {
+            module: shader,
+            entry_point: Some("vs"),
+            buffers: &[MeshVertex::LAYOUT, Instance::LAYOUT],
+            compilation_options: Default::default(),
+        },
+        fragment: Some(wgpu::FragmentState {
+            module: shader,
+            entry_point: Some("fs"),
+            targets: &[Some(wgpu::ColorTargetState {
+                format,
+                blend: Some(wgpu::BlendState::REPLACE),
+                write_mask: wgpu::ColorWrites::ALL,
+            })],
+            compilation_options: Default::default(),
+        }),
+        primitive: wgpu::PrimitiveState {
+            cull_mode: Some(wgpu::Face::Back), // 立方体是封闭的，剔除背面
+            ..Default::default()
+        },
+        depth_stencil: Some(DepthTexture::depth_stencil_state()), // LessEqual深度测试
+        multisample: wgpu::MultisampleState {
+            count: sample_count,
+            ..Default::default()
+        },
+        multiview: None,
+        cache,
+    })
+}
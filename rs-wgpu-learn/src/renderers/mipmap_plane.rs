@@ -0,0 +1,318 @@
+use crate::{
+    InitContext, InputState, Mesh, MeshVertex, RenderContext, Renderer, SamplerDesc, Scene,
+    Texture, WgpuAppError,
+    binding::{self, BindingLayout},
+    camera::{Camera, CameraBuffer, Projection, aspect_ratio},
+    mipmap::{MipmapGenerator, mip_level_count},
+    rebuild_pipeline, shader, shapes,
+};
+use glam::Vec3;
+use log::{error, info};
+use std::{path::Path, time::Duration};
+use winit::keyboard::KeyCode;
+
+/// 贴图平面使用的着色器文件
+const SHADER: &str = "mipmap_plane.wgsl";
+
+/// 平面的边长（世界单位）
+const PLANE_SIZE: f32 = 400.0;
+
+/// 纹理在平面上每个方向重复的次数（与着色器中的REPEAT一致）
+const REPEAT: f32 = 100.0;
+
+/// 棋盘格纹理的边长和每格的像素数
+const CHECKER_SIZE: u32 = 256;
+const CHECKER_CELL: u32 = 8;
+
+/// 相机的高度和前进速度（世界单位/秒）
+const EYE_HEIGHT: f32 = 1.0;
+const SPEED: f32 = 2.0;
+
+/// A键依次切换的各向异性过滤采样数（1表示关闭）
+const ANISOTROPY_LEVELS: [u16; 3] = [1, 4, 16];
+
+/// 伸向地平线的棋盘格平面，用于对比有无mipmap时缩小纹理的效果
+///
+/// 相机贴近平面缓慢前进，远处的纹理被大幅缩小：只有一级时闪烁并出现摩尔纹，
+/// 带完整mip链（三线性过滤）时远处平滑地过渡为灰色。F键切换两张纹理。
+/// A键在1/4/16之间切换各向异性过滤：三线性过滤在掠射角下按较长的方向选择mip级别，远处的格子过早变模糊，
+/// 各向异性过滤沿纹理被压缩的方向多次采样，格子在更远处仍然清晰（设备不支持时采样数被忽略）。
+pub struct MipmapPlane {
+    pipeline: wgpu::RenderPipeline,
+    pipeline_layout: wgpu::PipelineLayout, // 第0组：相机，第1组：纹理和采样器
+    sample_count: u32,
+    cache: Option<wgpu::PipelineCache>,
+    camera: Camera,                    // 贴近平面、看向远处的相机
+    camera_buffer: CameraBuffer,       // 第0组：相机的视图投影矩阵
+    aspect: f32,                       // 表面宽高比
+    mesh: Mesh,                        // 平面网格
+    textures: [Texture; 2],            // 只有一级和带完整mip链的纹理
+    texture_layout: BindingLayout,     // 第1组的布局（切换采样器时重建绑定组）
+    bind_groups: [wgpu::BindGroup; 2], // 两张纹理的绑定组（使用bound_anisotropy对应的采样器）
+    anisotropy: usize,                 // 当前各向异性过滤在ANISOTROPY_LEVELS中的下标
+    bound_anisotropy: usize,           // 绑定组使用的采样器对应的下标（不同时在render中重建）
+    levels: u32,                       // 完整mip链的级数
+    travelled: f32,                    // 相机在一格纹理内前进的距离（循环）
+    mipmaps: bool,                     // 是否使用带mip链的纹理
+}
+
+impl Renderer for MipmapPlane {
+    fn init(ctx: &InitContext) -> Result<Self, WgpuAppError> {
+        let InitContext {
+            device,
+            queue,
+            config,
+            sample_count,
+            cache,
+            samplers,
+            ..
+        } = *ctx;
+        // 1. 创建相机缓冲区和平面网格
+        let camera = Camera {
+            eye: Vec3::new(0.0, EYE_HEIGHT, 0.0),
+            target: Vec3::new(0.0, EYE_HEIGHT * 0.6, -10.0),
+            up: Vec3::Y,
+            projection: Projection::Perspective {
+                fovy: std::f32::consts::FRAC_PI_3,
+                znear: 0.1,
+                zfar: PLANE_SIZE,
+            },
+        };
+        let camera_buffer = CameraBuffer::new(device);
+        let (vertices, indices) = shapes::plane(PLANE_SIZE, 1);
+        let mesh = Mesh::new(device, "Mipmap Plane", &vertices, Some(&indices));
+
+        // 2. 同一张棋盘格图片分别创建只有一级和带完整mip链的纹理
+        let checker = checker_image();
+        let texture_layout = Texture::create_layout(
+            device,
+            "Mipmap Plane Texture Layout",
+            wgpu::ShaderStages::FRAGMENT,
+        );
+        let mut mipmaps = MipmapGenerator::new(device)?;
+        let create_texture = |mipmaps| {
+            Texture::from_image(
+                device,
+                queue,
+                samplers,
+                &checker,
+                Some("Checkerboard"),
+                mipmaps,
+            )
+        };
+        let textures = [create_texture(None)?, create_texture(Some(&mut mipmaps))?];
+        let bind_groups = textures
+            .each_ref()
+            .map(|texture| texture.bind_group(device, &texture_layout));
+        let pipeline_layout = binding::pipeline_layout(
+            device,
+            Some("Mipmap Plane Pipeline Layout"),
+            &[camera_buffer.layout(), &texture_layout],
+        );
+
+        // 3. 加载着色器并创建管线
+        let shader = shader::load(device, SHADER)?;
+        let pipeline = create_pipeline(
+            device,
+            &shader,
+            config.format,
+            &pipeline_layout,
+            sample_count,
+            cache,
+        );
+
+        Ok(Self {
+            pipeline,
+            pipeline_layout,
+            sample_count,
+            cache: cache.cloned(),
+            camera,
+            camera_buffer,
+            aspect: aspect_ratio(config),
+            mesh,
+            textures,
+            texture_layout,
+            bind_groups,
+            anisotropy: 0,
+            bound_anisotropy: 0,
+            levels: mip_level_count(CHECKER_SIZE, CHECKER_SIZE),
+            travelled: 0.0,
+            mipmaps: true,
+        })
+    }
+
+    fn render(
+        &mut self,
+        ctx: &RenderContext,
+        view: &wgpu::TextureView,
+        encoder: &mut wgpu::CommandEncoder,
+    ) {
+        if self.bound_anisotropy != self.anisotropy {
+            self.rebind(ctx);
+        }
+
+        let mut pass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
+            label: Some("Mipmap Plane Pass"),
+            color_attachments: &[Some(ctx.color_attachment(
+                view,
+                wgpu::Operations {
+                    load: wgpu::LoadOp::Clear(ctx.clear_color),
+                    store: wgpu::StoreOp::Store,
+                },
+            ))],
+            depth_stencil_attachment: None,
+            timestamp_writes: ctx.timestamp_writes(),
+            occlusion_query_set: None,
+        });
+        pass.set_pipeline(&self.pipeline);
+        self.camera_buffer.bind(ctx, &mut pass, 0);
+        pass.set_bind_group(1, &self.bind_groups[self.mipmaps as usize], &[]);
+        self.mesh.draw(&mut pass, 0..1);
+    }
+
+    fn resize(&mut self, config: &wgpu::SurfaceConfiguration) {
+        self.aspect = aspect_ratio(config);
+    }
+
+    fn shader_changed(
+        &mut self,
+        device: &wgpu::Device,
+        config: &wgpu::SurfaceConfiguration,
+        path: &Path,
+    ) {
+        if !shader::depends_on(SHADER, path) {
+            return;
+        }
+        let _ = self.rebuild_pipeline(device, config.format, self.sample_count);
+    }
+
+    fn sample_count_changed(
+        &mut self,
+        device: &wgpu::Device,
+        config: &wgpu::SurfaceConfiguration,
+        sample_count: u32,
+    ) -> Result<(), WgpuAppError> {
+        self.rebuild_pipeline(device, config.format, sample_count)
+    }
+}
+
+impl Scene for MipmapPlane {
+    fn update(&mut self, dt: Duration, _queue: &wgpu::Queue, input: &InputState) {
+        if input.just_pressed(KeyCode::KeyF) {
+            self.mipmaps = !self.mipmaps;
+            info!("Mipmaps {}", if self.mipmaps { "on" } else { "off" });
+        }
+        if input.just_pressed(KeyCode::KeyA) {
+            self.anisotropy = (self.anisotropy + 1) % ANISOTROPY_LEVELS.len();
+            info!("Anisotropy {}x", ANISOTROPY_LEVELS[self.anisotropy]);
+        }
+
+        // 前进一格纹理后回到起点，画面无缝循环，相机不会走出平面
+        let tile = PLANE_SIZE / REPEAT;
+        self.travelled = (self.travelled + SPEED * dt.as_secs_f32()) % tile;
+        let forward = Vec3::new(0.0, 0.0, -self.travelled);
+        let look = self.camera.target - self.camera.eye;
+        self.camera.eye = Vec3::new(0.0, EYE_HEIGHT, 0.0) + forward;
+        self.camera.target = self.camera.eye + look;
+        self.camera_buffer.update(&self.camera, self.aspect);
+    }
+
+    fn status(&self) -> Option<String> {
+        let mipmaps = if self.mipmaps {
+            format!("mipmaps on ({} levels, trilinear)", self.levels)
+        } else {
+            "mipmaps off".to_string()
+        };
+        let anisotropy = ANISOTROPY_LEVELS[self.anisotropy];
+        Some(format!("{mipmaps}, anisotropy {anisotropy}x"))
+    }
+}
+
+impl MipmapPlane {
+    /// 用当前各向异性过滤的采样器（来自共享的采样器缓存）重建两张纹理的绑定组，失败时保留旧绑定组
+    fn rebind(&mut self, ctx: &RenderContext) {
+        let anisotropy = ANISOTROPY_LEVELS[self.anisotropy];
+        let desc = SamplerDesc::linear_repeat().with_anisotropy(anisotropy);
+        match ctx.samplers.get(&desc) {
+            Ok(sampler) => {
+                self.bind_groups = self.textures.each_ref().map(|texture| {
+                    texture
+                        .clone()
+                        .with_sampler(sampler.clone())
+                        .bind_group(ctx.device, &self.texture_layout)
+                });
+            }
+            Err(e) => error!("Failed to create {anisotropy}x anisotropic sampler: {e}"),
+        }
+        self.bound_anisotropy = self.anisotropy;
+    }
+
+    /// 用`rebuild_pipeline`按sample_count重建管线，成功后才更新多重采样数
+    fn rebuild_pipeline(
+        &mut self,
+        device: &wgpu::Device,
+        format: wgpu::TextureFormat,
+        sample_count: u32,
+    ) -> Result<(), WgpuAppError> {
+        rebuild_pipeline(device, SHADER, &mut self.pipeline, |shader| {
+            create_pipeline(
+                device,
+                shader,
+                format,
+                &self.pipeline_layout,
+                sample_count,
+                self.cache.as_ref(),
+            )
+        })?;
+        self.sample_count = sample_count;
+        Ok(())
+    }
+}
+
+/// 黑白棋盘格图片，格子边缘锐利，缩小时最容易看出闪烁
+fn checker_image() -> image::DynamicImage {
+    let image = image::GrayImage::from_fn(CHECKER_SIZE, CHECKER_SIZE, |x, y| {
+        let white = (x / CHECKER_CELL + y / CHECKER_CELL).is_multiple_of(2);
+        image::Luma([if white { 230 } else { 20 }])
+    });
+    image::DynamicImage::ImageLuma8(image)
+}
+
+/// 创建读取MeshVertex位置和纹理坐标的渲染管线
+fn create_pipeline(
+    device: &wgpu::Device,
+    shader: &wgpu::ShaderModule,
+    format: wgpu::TextureFormat,
+    layout: &wgpu::PipelineLayout,
+    sample_count: u32,
+    cache: Option<&wgpu::PipelineCache>,
+) -> wgpu::RenderPipeline {
+    device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
+        label: Some("Mipmap Plane Pipeline"),
+        layout: Some(layout),
+        vertex: wgpu::VertexState {
+            module: shader,
+            entry_point: Some("vs"),
+            buffers: &[MeshVertex::LAYOUT],
+            compilation_options: Default::default(),
+        },
+        fragment: Some(wgpu::FragmentState {
+            module: shader,
+            entry_point: Some("fs"),
+            targets: &[Some(wgpu::ColorTargetState {
+                format,
+                blend: Some(wgpu::BlendState::REPLACE),
+                write_mask: wgpu::ColorWrites::ALL,
+            })],
+            compilation_options: Default::default(),
+        }),
+        primitive: Default::default(),
+        depth_stencil: None,
+        multisample: wgpu::MultisampleState {
+            count: sample_count,
+            ..Default::default()
+        },
+        multiview: None,
+        cache,
+    })
+}
@@ -0,0 +1,47 @@
+mod bouncing_quad;
+mod brick_cube;
+mod clear;
+mod depth_quads;
+mod dynamic_uniform_cubes;
+mod environment_viewer;
+mod gltf_viewer;
+mod instanced_cubes;
+mod mipmap_plane;
+mod multi_draw_meshes;
+mod noise_fog;
+mod obj_viewer;
+mod pentagon;
+mod pulsing_cubes;
+mod push_constant_cubes;
+mod rotating_triangle;
+mod shadertoy;
+mod solar_system;
+mod storage_wobble;
+mod texture_array_cubes;
+mod textured_quad;
+mod triangle;
+mod vertex_color;
+
+pub use bouncing_quad::BouncingQuad;
+pub use brick_cube::BrickCube;
+pub use clear::Clear;
+pub use depth_quads::DepthQuads;
+pub use dynamic_uniform_cubes::DynamicUniformCubes;
+pub use environment_viewer::EnvironmentViewer;
+pub use gltf_viewer::GltfViewer;
+pub use instanced_cubes::{InstancedCubes, UploadStress};
+pub use mipmap_plane::MipmapPlane;
+pub use multi_draw_meshes::MultiDrawMeshes;
+pub use noise_fog::NoiseFog;
+pub use obj_viewer::ObjViewer;
+pub use pentagon::Pentagon;
+pub use pulsing_cubes::PulsingCubes;
+pub use push_constant_cubes::PushConstantCubes;
+pub use rotating_triangle::RotatingTriangle;
+pub use shadertoy::Shadertoy;
+pub use solar_system::SolarSystem;
+pub use storage_wobble::StorageWobble;
+pub use texture_array_cubes::TextureArrayCubes;
+pub use textured_quad::TexturedQuad;
+pub use triangle::Triangle;
+pub use vertex_color::VertexColorTriangle;
@@ -0,0 +1,282 @@
+use crate::{
+    DepthTexture, InitContext, Mesh, MeshVertex, RenderContext, Renderer, Scene, WgpuAppError,
+    binding::{self, BindGroupBuilder, BindGroupLayoutBuilder},
+    indirect::{DrawIndexedIndirectArgs, MultiDrawBatch},
+    rebuild_pipeline, shader, shapes,
+};
+use log::info;
+use std::path::Path;
+
+/// 多重间接绘制示例使用的着色器文件
+const SHADER: &str = "multi_draw.wgsl";
+
+/// 网格边长：55×55个形状
+const GRID_SIZE: u32 = 55;
+
+/// 相邻形状中心的距离
+const SPACING: f32 = 1.0;
+
+/// 场景绕Y轴旋转的速度（弧度/秒）
+const ROTATION_SPEED: f32 = 0.2;
+
+/// 与着色器中ViewUniform对应的数据（按16字节对齐）
+#[repr(C)]
+#[derive(Debug, Clone, Copy, bytemuck::Pod, bytemuck::Zeroable)]
+struct ViewUniform {
+    aspect: f32,
+    camera_distance: f32,
+    angle: f32,
+    _padding: f32,
+}
+
+/// 几千个索引数各不相同的形状：顶点烘焙到世界空间后合并进一个网格，每个形状是一条间接绘制参数
+///
+/// 设备支持MULTI_DRAW_INDIRECT(_COUNT)时一次调用提交所有参数，否则逐条`draw_indexed_indirect`。
+/// 窗口标题中显示实际使用的方式和绘制调用数。
+pub struct MultiDrawMeshes {
+    pipeline: wgpu::RenderPipeline,
+    pipeline_layout: wgpu::PipelineLayout,
+    sample_count: u32,
+    cache: Option<wgpu::PipelineCache>,
+    uniform_buffer: wgpu::Buffer,
+    bind_group: wgpu::BindGroup,
+    mesh: Mesh,            // 所有形状合并后的网格
+    batch: MultiDrawBatch, // 每个形状一条绘制参数
+    draw_calls: u32,       // 上一帧发出的绘制调用数
+}
+
+impl Renderer for MultiDrawMeshes {
+    fn init(ctx: &InitContext) -> Result<Self, WgpuAppError> {
+        let InitContext {
+            device,
+            config,
+            sample_count,
+            cache,
+            ..
+        } = *ctx;
+        // 1. 创建uniform缓冲区、绑定组和管线布局
+        let uniform_buffer = device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some("Multi Draw Uniform Buffer"),
+            size: std::mem::size_of::<ViewUniform>() as u64,
+            usage: wgpu::BufferUsages::UNIFORM | wgpu::BufferUsages::COPY_DST,
+            mapped_at_creation: false,
+        });
+        let bind_group_layout = BindGroupLayoutBuilder::new()
+            .label("Multi Draw Bind Group Layout")
+            .uniform(wgpu::ShaderStages::VERTEX)
+            .build(device);
+        let bind_group = BindGroupBuilder::new(&bind_group_layout)
+            .label("Multi Draw Bind Group")
+            .buffer(&uniform_buffer)
+            .build(device);
+        let pipeline_layout = binding::pipeline_layout(
+            device,
+            Some("Multi Draw Pipeline Layout"),
+            &[&bind_group_layout],
+        );
+
+        // 2. 加载着色器并创建管线
+        let shader = shader::load(device, SHADER)?;
+        let pipeline = create_pipeline(
+            device,
+            &shader,
+            config.format,
+            &pipeline_layout,
+            sample_count,
+            cache,
+        );
+
+        // 3. 把所有形状合并进一个网格，并为每个形状生成一条绘制参数
+        let (vertices, indices, args) = build_shapes();
+        let mesh = Mesh::new(device, "Multi Draw Shapes", &vertices, Some(&indices));
+        let batch = MultiDrawBatch::new(device, "Multi Draw Shapes", &args);
+        info!(
+            "Drawing {} shapes ({} vertices, {} indices) with {}",
+            batch.len(),
+            vertices.len(),
+            indices.len(),
+            batch.path().name()
+        );
+
+        Ok(Self {
+            pipeline,
+            pipeline_layout,
+            sample_count,
+            cache: cache.cloned(),
+            uniform_buffer,
+            bind_group,
+            mesh,
+            batch,
+            draw_calls: 0,
+        })
+    }
+
+    fn render(
+        &mut self,
+        ctx: &RenderContext,
+        view: &wgpu::TextureView,
+        encoder: &mut wgpu::CommandEncoder,
+    ) {
+        let uniform = ViewUniform {
+            aspect: ctx.config.width.max(1) as f32 / ctx.config.height.max(1) as f32,
+            camera_distance: GRID_SIZE as f32 * SPACING * 1.2,
+            angle: (ROTATION_SPEED * ctx.time) % std::f32::consts::TAU,
+            _padding: 0.0,
+        };
+        ctx.upload(encoder, &self.uniform_buffer, 0, &uniform);
+
+        let mut pass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
+            label: Some("Multi Draw Pass"),
+            color_attachments: &[Some(ctx.color_attachment(
+                view,
+                wgpu::Operations {
+                    load: wgpu::LoadOp::Clear(ctx.clear_color),
+                    store: wgpu::StoreOp::Store,
+                },
+            ))],
+            depth_stencil_attachment: ctx.depth_attachment(),
+            timestamp_writes: ctx.timestamp_writes(),
+            occlusion_query_set: None,
+        });
+        pass.set_pipeline(&self.pipeline);
+        pass.set_bind_group(0, &self.bind_group, &[]);
+        self.draw_calls = self.mesh.draw_batch(&mut pass, &self.batch);
+    }
+
+    fn shader_changed(
+        &mut self,
+        device: &wgpu::Device,
+        config: &wgpu::SurfaceConfiguration,
+        path: &Path,
+    ) {
+        if !shader::depends_on(SHADER, path) {
+            return;
+        }
+        let _ = self.rebuild_pipeline(device, config.format, self.sample_count);
+    }
+
+    fn sample_count_changed(
+        &mut self,
+        device: &wgpu::Device,
+        config: &wgpu::SurfaceConfiguration,
+        sample_count: u32,
+    ) -> Result<(), WgpuAppError> {
+        self.rebuild_pipeline(device, config.format, sample_count)
+    }
+}
+
+impl Scene for MultiDrawMeshes {
+    fn status(&self) -> Option<String> {
+        Some(format!(
+            "{} shapes, {} draw calls ({})",
+            self.batch.len(),
+            self.draw_calls,
+            self.batch.path().name()
+        ))
+    }
+}
+
+impl MultiDrawMeshes {
+    /// 用`rebuild_pipeline`按sample_count重建管线，成功后才更新多重采样数
+    fn rebuild_pipeline(
+        &mut self,
+        device: &wgpu::Device,
+        format: wgpu::TextureFormat,
+        sample_count: u32,
+    ) -> Result<(), WgpuAppError> {
+        rebuild_pipeline(device, SHADER, &mut self.pipeline, |shader| {
+            create_pipeline(
+                device,
+                shader,
+                format,
+                &self.pipeline_layout,
+                sample_count,
+                self.cache.as_ref(),
+            )
+        })?;
+        self.sample_count = sample_count;
+        Ok(())
+    }
+}
+
+/// 生成GRID_SIZE×GRID_SIZE个形状的合并顶点、索引和每个形状的绘制参数
+///
+/// 形状在几种细分程度不同的立方体、球和圆环之间轮换，所以每条参数的索引数都不同；
+/// 索引保持各形状内的局部编号，由`base_vertex`偏移到合并后的顶点。
+fn build_shapes() -> (Vec<MeshVertex>, Vec<u32>, Vec<DrawIndexedIndirectArgs>) {
+    let variants = [
+        shapes::cube(0.6),
+        shapes::uv_sphere(0.35, 4, 6),
+        shapes::uv_sphere(0.35, 8, 12),
+        shapes::uv_sphere(0.35, 12, 16),
+        shapes::torus(0.28, 0.1, 8, 6),
+        shapes::torus(0.28, 0.1, 16, 8),
+    ];
+    let last = (GRID_SIZE - 1) as f32;
+    let (mut vertices, mut indices, mut args) = (Vec::new(), Vec::new(), Vec::new());
+    for i in 0..GRID_SIZE * GRID_SIZE {
+        let (row, column) = (i / GRID_SIZE, i % GRID_SIZE);
+        let x = (column as f32 - last / 2.0) * SPACING;
+        let z = (row as f32 - last / 2.0) * SPACING;
+        let (shape_vertices, shape_indices) = &variants[(row + column) as usize % variants.len()];
+        args.push(DrawIndexedIndirectArgs {
+            index_count: shape_indices.len() as u32,
+            instance_count: 1,
+            first_index: indices.len() as u32,
+            base_vertex: vertices.len() as i32,
+            first_instance: 0, // 非0需要INDIRECT_FIRST_INSTANCE特性
+        });
+        vertices.extend(shape_vertices.iter().map(|vertex| MeshVertex {
+            position: [
+                vertex.position[0] + x,
+                vertex.position[1],
+                vertex.position[2] + z,
+            ],
+            ..*vertex
+        }));
+        indices.extend_from_slice(shape_indices);
+    }
+    (vertices, indices, args)
+}
+
+/// 创建读取MeshVertex并开启深度测试的渲染管线
+fn create_pipeline(
+    device: &wgpu::Device,
+    shader: &wgpu::ShaderModule,
+    format: wgpu::TextureFormat,
+    layout: &wgpu::PipelineLayout,
+    sample_count: u32,
+    cache: Option<&wgpu::PipelineCache>,
+) -> wgpu::RenderPipeline {
+    device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
+        label: Some("Multi Draw Pipeline"),
+        layout: Some(layout),
+        vertex: wgpu::VertexState {
+            module: shader,
+            entry_point: Some("vs"),
+            buffers: &[MeshVertex::LAYOUT],
+            compilation_options: Default::default(),
+        },
+        fragment: Some(wgpu::FragmentState {
+            module: shader,
+            entry_point: Some("fs"),
+            targets: &[Some(wgpu::ColorTargetState {
+                format,
+                blend: Some(wgpu::BlendState::REPLACE),
+                write_mask: wgpu::ColorWrites::ALL,
+            })],
+            compilation_options: Default::default(),
+        }),
+        primitive: wgpu::PrimitiveState {
+            cull_mode: Some(wgpu::Face::Back), // 所有形状都是封闭的，剔除背面
+            ..Default::default()
+        },
+        depth_stencil: Some(DepthTexture::depth_stencil_state()),
+        multisample: wgpu::MultisampleState {
+            count: sample_count,
+            ..Default::default()
+        },
+        multiview: None,
+        cache,
+    })
+}
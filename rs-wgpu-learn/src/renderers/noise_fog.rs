@@ -0,0 +1,359 @@
+use crate::{
+    DepthTexture, InitContext, InputState, Mesh, MeshVertex, RenderContext, Renderer, Scene,
+    VolumeTexture, WgpuAppError,
+    binding::{self, BindGroupBuilder, BindGroupLayoutBuilder},
+    camera::{Camera, CameraController, OrbitController, Projection, aspect_ratio},
+    debug_draw::{DebugDepth, DebugLines},
+    math::Aabb,
+    noise::{NoiseGenerator, NoiseParams},
+    rebuild_pipeline, shader, shapes,
+};
+use glam::Vec3;
+use log::info;
+use std::{path::Path, time::Duration};
+use web_time::Instant;
+use winit::keyboard::KeyCode;
+
+/// 雾示例使用的着色器文件
+const SHADER: &str = "noise_fog.wgsl";
+
+/// 噪声体积的边长（纹素）
+const VOLUME_SIZE: u32 = 64;
+
+/// 噪声的滚动速度（纹理坐标/秒）
+const WIND: Vec3 = Vec3::new(0.03, -0.01, 0.02);
+
+/// 雾的消光系数（每单位长度）
+const DENSITY: f32 = 12.0;
+
+/// 立方体边框的颜色
+const BOUNDS_COLOR: [f32; 4] = [0.6, 0.6, 0.7, 1.0];
+
+/// 与着色器中FogUniform对应的数据
+#[repr(C)]
+#[derive(Debug, Clone, Copy, bytemuck::Pod, bytemuck::Zeroable)]
+struct FogUniform {
+    view_proj: [[f32; 4]; 4],
+    eye: [f32; 3],
+    time: f32,
+    wind: [f32; 3],
+    density: f32,
+}
+
+/// 三维纹理：64³的`R16Float`噪声体积显示为单位立方体中飘动的雾
+///
+/// 噪声体积由`NoiseGenerator`生成：支持时用计算着色器写入三维存储纹理，否则在CPU上计算后上传。
+/// 片元着色器沿视线在立方体内步进采样（透射率足够低时提前结束），体积可以平铺，随时间无缝滚动。
+/// 按R键换一个种子重新生成，按G键在计算着色器和CPU生成之间切换（只有计算着色器可用时）；
+/// 用鼠标拖动旋转相机、滚轮缩放，可以一直推进到立方体内部。
+pub struct NoiseFog {
+    pipeline: wgpu::RenderPipeline,
+    pipeline_layout: wgpu::PipelineLayout, // 第0组：雾的参数，第1组：噪声体积
+    sample_count: u32,
+    cache: Option<wgpu::PipelineCache>,
+    config: wgpu::SurfaceConfiguration, // 表面配置，用于相机的宽高比
+    camera: Camera,                     // 观察立方体的相机
+    controller: CameraController,       // 轨道控制器
+    fog_buffer: wgpu::Buffer,
+    fog_bind_group: wgpu::BindGroup,
+    volume: VolumeTexture,              // 噪声体积
+    volume_bind_group: wgpu::BindGroup, // 第1组：体积纹理和采样器
+    generator: NoiseGenerator,          // 计算着色器或CPU回退
+    params: NoiseParams,                // 当前的噪声参数（R键换种子）
+    use_compute: bool,                  // 是否用计算着色器生成（G键切换）
+    regenerate: bool,                   // 参数或生成方式变化后在下一次渲染时重新生成
+    generation_time: Option<Duration>,  // 上一次在CPU上生成的耗时
+    cube: Mesh,                         // 单位立方体（绘制背面）
+    debug_lines: DebugLines,            // 立方体的边框
+}
+
+impl Renderer for NoiseFog {
+    fn init(ctx: &InitContext) -> Result<Self, WgpuAppError> {
+        let InitContext {
+            adapter,
+            device,
+            queue,
+            config,
+            sample_count,
+            cache,
+            samplers,
+        } = *ctx;
+        // 1. 创建噪声体积并生成第一份噪声
+        let generator = NoiseGenerator::new(adapter, device, cache);
+        let volume =
+            generator.create_volume(device, samplers, Some("Noise Volume"), VOLUME_SIZE)?;
+        let params = NoiseParams::default();
+        let start = Instant::now();
+        generator.generate(device, queue, &volume, params);
+        let generation_time = (!generator.uses_compute()).then(|| start.elapsed());
+
+        // 2. 雾的参数和两个绑定组
+        let fog_buffer = device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some("Noise Fog Buffer"),
+            size: std::mem::size_of::<FogUniform>() as u64,
+            usage: wgpu::BufferUsages::UNIFORM | wgpu::BufferUsages::COPY_DST,
+            mapped_at_creation: false,
+        });
+        let fog_layout = BindGroupLayoutBuilder::new()
+            .label("Noise Fog Layout")
+            .uniform(wgpu::ShaderStages::VERTEX_FRAGMENT)
+            .build(device);
+        let fog_bind_group = BindGroupBuilder::new(&fog_layout)
+            .label("Noise Fog Bind Group")
+            .buffer(&fog_buffer)
+            .build(device);
+        let volume_layout =
+            VolumeTexture::create_layout(device, "Noise Volume", wgpu::ShaderStages::FRAGMENT);
+        let volume_bind_group = volume.bind_group(device, &volume_layout);
+        let pipeline_layout = binding::pipeline_layout(
+            device,
+            Some("Noise Fog Pipeline Layout"),
+            &[&fog_layout, &volume_layout],
+        );
+
+        // 3. 加载着色器并创建管线
+        let shader = shader::load(device, SHADER)?;
+        let pipeline = create_pipeline(
+            device,
+            &shader,
+            config.format,
+            &pipeline_layout,
+            sample_count,
+            cache,
+        );
+        let debug_lines =
+            DebugLines::new(device, config.format, sample_count, cache, DebugDepth::Test)?;
+
+        let (vertices, indices) = shapes::cube(1.0);
+        let cube = Mesh::new(device, "Fog Cube", &vertices, Some(&indices));
+        let camera = Camera {
+            eye: Vec3::new(0.0, 0.8, 2.0),
+            projection: Projection::Perspective {
+                fovy: std::f32::consts::FRAC_PI_4,
+                znear: 0.05,
+                zfar: 20.0,
+            },
+            ..Default::default()
+        };
+        let mut orbit = OrbitController::from_camera(&camera);
+        orbit.min_distance = 0.2;
+        orbit.max_distance = 10.0;
+        Ok(Self {
+            pipeline,
+            pipeline_layout,
+            sample_count,
+            cache: cache.cloned(),
+            config: config.clone(),
+            controller: CameraController::new(orbit).with_bookmarks("noise-fog"),
+            camera,
+            fog_buffer,
+            fog_bind_group,
+            volume,
+            volume_bind_group,
+            use_compute: generator.uses_compute(),
+            generator,
+            params,
+            regenerate: false,
+            generation_time,
+            cube,
+            debug_lines,
+        })
+    }
+
+    fn render(
+        &mut self,
+        ctx: &RenderContext,
+        view: &wgpu::TextureView,
+        encoder: &mut wgpu::CommandEncoder,
+    ) {
+        if self.regenerate {
+            self.regenerate = false;
+            let start = Instant::now();
+            if self.use_compute {
+                self.generator
+                    .generate(ctx.device, ctx.queue, &self.volume, self.params);
+                self.generation_time = None;
+            } else {
+                self.generator
+                    .generate_on_cpu(ctx.queue, &self.volume, self.params);
+                self.generation_time = Some(start.elapsed());
+            }
+        }
+
+        let view_proj = self.camera.view_proj(aspect_ratio(&self.config));
+        let uniform = FogUniform {
+            view_proj: view_proj.to_cols_array_2d(),
+            eye: self.camera.eye.to_array(),
+            time: ctx.time,
+            wind: WIND.to_array(),
+            density: DENSITY,
+        };
+        ctx.upload(encoder, &self.fog_buffer, 0, &uniform);
+        self.debug_lines.clear();
+        self.debug_lines.add_aabb(
+            &Aabb::new(Vec3::splat(-0.5), Vec3::splat(0.5)),
+            BOUNDS_COLOR,
+        );
+        self.debug_lines.prepare(ctx, encoder, view_proj);
+
+        let mut pass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
+            label: Some("Noise Fog Pass"),
+            color_attachments: &[Some(ctx.color_attachment(
+                view,
+                wgpu::Operations {
+                    load: wgpu::LoadOp::Clear(ctx.clear_color),
+                    store: wgpu::StoreOp::Store,
+                },
+            ))],
+            depth_stencil_attachment: ctx.depth_attachment(),
+            timestamp_writes: ctx.timestamp_writes(),
+            occlusion_query_set: None,
+        });
+        pass.set_pipeline(&self.pipeline);
+        pass.set_bind_group(0, &self.fog_bind_group, &[]);
+        pass.set_bind_group(1, &self.volume_bind_group, &[]);
+        self.cube.draw(&mut pass, 0..1);
+        self.debug_lines.draw(&mut pass);
+    }
+
+    fn shader_changed(
+        &mut self,
+        device: &wgpu::Device,
+        config: &wgpu::SurfaceConfiguration,
+        path: &Path,
+    ) {
+        self.debug_lines.shader_changed(device, path);
+        if shader::depends_on(SHADER, path) {
+            let _ = self.rebuild_pipeline(device, config.format, self.sample_count);
+        }
+    }
+
+    fn sample_count_changed(
+        &mut self,
+        device: &wgpu::Device,
+        config: &wgpu::SurfaceConfiguration,
+        sample_count: u32,
+    ) -> Result<(), WgpuAppError> {
+        self.rebuild_pipeline(device, config.format, sample_count)?;
+        self.debug_lines
+            .sample_count_changed(device, sample_count)?;
+        Ok(())
+    }
+}
+
+impl Scene for NoiseFog {
+    /// R键换一个种子重新生成噪声，G键切换生成方式，其余输入交给相机
+    fn update(&mut self, dt: Duration, _queue: &wgpu::Queue, input: &InputState) {
+        if input.just_pressed(KeyCode::KeyR) {
+            self.params.seed = self.params.seed.wrapping_add(1);
+            self.regenerate = true;
+            info!(
+                "Regenerating the noise volume with seed {}",
+                self.params.seed
+            );
+        }
+        if input.just_pressed(KeyCode::KeyG) {
+            if self.generator.uses_compute() {
+                self.use_compute = !self.use_compute;
+                self.regenerate = true;
+                info!("Generating noise {}", self.generation_method());
+            } else {
+                info!("3D storage texture writes are unavailable, noise stays on the CPU");
+            }
+        }
+        self.controller
+            .update(input, dt, self.config.height, &mut self.camera);
+    }
+
+    fn status(&self) -> Option<String> {
+        let time = self
+            .generation_time
+            .map(|time| format!(" in {:.1} ms", time.as_secs_f64() * 1000.0))
+            .unwrap_or_default();
+        Some(format!(
+            "{VOLUME_SIZE}³ R16Float noise, seed {} (R), generated {}{time}",
+            self.params.seed,
+            self.generation_method()
+        ))
+    }
+
+    /// 记录新的表面配置，用于相机的宽高比
+    fn resized(&mut self, _device: &wgpu::Device, config: &wgpu::SurfaceConfiguration) {
+        self.config = config.clone();
+    }
+}
+
+impl NoiseFog {
+    /// 当前的生成方式（显示用）
+    fn generation_method(&self) -> &'static str {
+        match (self.use_compute, self.generator.uses_compute()) {
+            (true, _) => "by a compute shader (G)",
+            (false, true) => "on the CPU (G)",
+            (false, false) => "on the CPU (no 3D storage writes)",
+        }
+    }
+
+    /// 用`rebuild_pipeline`按sample_count重建管线，成功后才更新多重采样数
+    fn rebuild_pipeline(
+        &mut self,
+        device: &wgpu::Device,
+        format: wgpu::TextureFormat,
+        sample_count: u32,
+    ) -> Result<(), WgpuAppError> {
+        rebuild_pipeline(device, SHADER, &mut self.pipeline, |shader| {
+            create_pipeline(
+                device,
+                shader,
+                format,
+                &self.pipeline_layout,
+                sample_count,
+                self.cache.as_ref(),
+            )
+        })?;
+        self.sample_count = sample_count;
+        Ok(())
+    }
+}
+
+/// 创建绘制立方体背面、以预乘alpha与背景混合的渲染管线
+fn create_pipeline(
+    device: &wgpu::Device,
+    shader: &wgpu::ShaderModule,
+    format: wgpu::TextureFormat,
+    layout: &wgpu::PipelineLayout,
+    sample_count: u32,
+    cache: Option<&wgpu::PipelineCache>,
+) -> wgpu::RenderPipeline {
+    device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
+        label: Some("Noise Fog Pipeline"),
+        layout: Some(layout),
+        vertex: wgpu::VertexState {
+            module: shader,
+            entry_point: Some("vs"),
+            buffers: &[MeshVertex::LAYOUT],
+            compilation_options: Default::default(),
+        },
+        fragment: Some(wgpu::FragmentState {
+            module: shader,
+            entry_point: Some("fs"),
+            targets: &[Some(wgpu::ColorTargetState {
+                format,
+                blend: Some(wgpu::BlendState::PREMULTIPLIED_ALPHA_BLENDING),
+                write_mask: wgpu::ColorWrites::ALL,
+            })],
+            compilation_options: Default::default(),
+        }),
+        primitive: wgpu::PrimitiveState {
+            // 只画背面：相机在立方体内部时正面在身后，背面仍然覆盖整个视野
+            cull_mode: Some(wgpu::Face::Front),
+            ..Default::default()
+        },
+        depth_stencil: Some(DepthTexture::depth_stencil_state()),
+        multisample: wgpu::MultisampleState {
+            count: sample_count,
+            ..Default::default()
+        },
+        multiview: None,
+        cache,
+    })
+}
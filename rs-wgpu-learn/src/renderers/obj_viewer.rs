@@ -0,0 +1,577 @@
+use crate::{
+    CubeMap, DepthTexture, InitContext, InputState, Mesh, MeshVertex, PipelineSet, PipelineVariant,
+    RenderContext, Renderer, SamplerCache, Scene, WgpuAppError,
+    assets::obj,
+    binding::{self, BindGroupBuilder, BindGroupLayoutBuilder, BindingLayout},
+    camera::{Camera, CameraController, OrbitController, Projection, aspect_ratio},
+    debug_draw::{DebugDepth, DebugLines},
+    deindex,
+    gizmo::TranslateGizmo,
+    grid::{GridSettings, InfiniteGrid},
+    math::{Aabb, Transform},
+    mipmap::MipmapGenerator,
+    rebuild_pipeline, shader, shapes,
+    skybox::SkyboxPass,
+};
+use glam::{Mat4, Quat, Vec2, Vec3};
+use log::{error, info};
+use std::{path::Path, time::Duration};
+use winit::keyboard::KeyCode;
+
+/// 模型查看器使用的着色器文件
+const SHADER: &str = "obj_viewer.wgsl";
+
+/// 旋转速度（弧度/秒）
+const ROTATION_SPEED: f32 = 0.5;
+
+/// 相机到原点的初始距离
+const CAMERA_DISTANCE: f32 = 3.0;
+
+/// 纵向视野（45度）
+const FOV_Y: f32 = std::f32::consts::FRAC_PI_4;
+
+/// 显示法线时线段的长度（世界空间，模型已缩放到单位球内）
+const NORMAL_LENGTH: f32 = 0.08;
+
+/// 每个网格最多显示的法线数（顶点更多时按步长跳过）
+const MAX_NORMALS: usize = 4096;
+
+/// 内置的调试天空（横向十字布局，每个面写着自己的名称，左上角有白色三角形标记方向）
+const DEBUG_CUBE_MAP: &[u8] = include_bytes!("../../tests/assets/debug_cubemap.png");
+
+/// 近、远裁剪面
+const NEAR: f32 = 0.1;
+const FAR: f32 = 10.0;
+
+/// 与着色器中ViewUniform对应的数据（mat3x3f的每列占16字节）
+#[repr(C)]
+#[derive(Debug, Clone, Copy, bytemuck::Pod, bytemuck::Zeroable)]
+struct ViewUniform {
+    view_proj: [[f32; 4]; 4],
+    model: [[f32; 4]; 4],
+    normal: [[f32; 4]; 3],
+}
+
+/// 模型查看器：开启深度测试绘制旋转的模型，拖放`.obj`文件到窗口上即可替换
+///
+/// 启动时显示一个圆环。模型会被平移到包围盒中心并缩放到合适的大小，
+/// 缺少法线的模型使用由面计算出的平滑法线。按Z键切换线框显示，按B键显示包围盒（模型的包围盒随模型旋转，
+/// 每个网格在世界空间中的轴对齐包围盒）、包围球和坐标轴，按H键显示顶点法线（N键已用于打开调试窗口），
+/// 按G键显示或隐藏模型下方的参考网格。按T键显示平移手柄，拖动箭头沿对应的轴移动模型。
+/// 背景是天空盒（默认为标出各面名称的调试立方体贴图），按K键显示或隐藏，拖放十字布局的PNG或JPEG图片可以替换它。
+/// 用鼠标左键拖动旋转、滚轮缩放、中键（或Shift+左键）拖动平移相机（见`OrbitController`），
+/// 按C键切换到用WASD、Q/E和鼠标视角移动的飞行相机（见`FlyController`）。
+pub struct ObjViewer {
+    pipelines: PipelineSet, // 填充和线框管线
+    pipeline_layout: wgpu::PipelineLayout,
+    sample_count: u32,
+    cache: Option<wgpu::PipelineCache>,
+    config: wgpu::SurfaceConfiguration, // 表面配置，用于把光标换算成归一化设备坐标
+    camera: Camera,                     // 观察模型的相机
+    controller: CameraController,       // 轨道或飞行控制器（C键切换）
+    uniform_buffer: wgpu::Buffer,
+    bind_group: wgpu::BindGroup,
+    uniform: ViewUniform,
+    transform: Transform, // 模型在世界空间中的放置（平移手柄的位置和自动旋转）
+    fit: Transform,       // 把包围盒中心移到原点并缩放到单位球内的变换
+    meshes: Vec<Mesh>,    // 当前模型的网格（每个对象、组或材质一个，保留CPU端顶点）
+    wireframe_meshes: Vec<Mesh>, // 展开索引后的网格（只在重心坐标线框方式下上传）
+    bounds: Aabb,         // 当前模型在模型空间中的包围盒
+    show_bounds: bool,    // 是否绘制包围盒等调试线
+    show_normals: bool,   // 是否绘制顶点法线
+    debug_lines: DebugLines, // 与模型做深度测试的世界空间调试线（包围盒、法线）
+    debug_overlay: DebugLines, // 始终在最上层的调试线（坐标轴）
+    grid: InfiniteGrid,   // 模型下方的参考网格（世界空间，不随模型旋转）
+    skybox: SkyboxPass,   // 模型之后绘制的天空盒
+    mipmaps: MipmapGenerator, // 替换天空盒时生成立方体贴图的mip链
+    samplers: SamplerCache, // 替换天空盒时立方体贴图的采样器
+    gizmo: TranslateGizmo, // 移动模型的平移手柄（位置即transform.translation）
+}
+
+impl Renderer for ObjViewer {
+    fn init(ctx: &InitContext) -> Result<Self, WgpuAppError> {
+        let InitContext {
+            device,
+            queue,
+            config,
+            sample_count,
+            cache,
+            samplers,
+            ..
+        } = *ctx;
+        // 1. 创建uniform缓冲区、绑定组和管线布局
+        let uniform_buffer = device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some("OBJ Viewer Uniform Buffer"),
+            size: std::mem::size_of::<ViewUniform>() as u64,
+            usage: wgpu::BufferUsages::UNIFORM | wgpu::BufferUsages::COPY_DST,
+            mapped_at_creation: false,
+        });
+        let bind_group_layout = create_bind_group_layout(device);
+        let bind_group = BindGroupBuilder::new(&bind_group_layout)
+            .label("OBJ Viewer Bind Group")
+            .buffer(&uniform_buffer)
+            .build(device);
+        let pipeline_layout = binding::pipeline_layout(
+            device,
+            Some("OBJ Viewer Pipeline Layout"),
+            &[&bind_group_layout],
+        );
+
+        // 2. 加载着色器并创建开启深度测试的填充和线框管线
+        let shader = shader::load(device, SHADER)?;
+        let pipelines = PipelineSet::new(device.features(), |variant| {
+            create_pipeline(
+                device,
+                &shader,
+                config.format,
+                &pipeline_layout,
+                sample_count,
+                cache,
+                variant,
+            )
+        });
+        info!("Wireframe uses {}", pipelines.wireframe_variant().name());
+        let debug_lines =
+            DebugLines::new(device, config.format, sample_count, cache, DebugDepth::Test)?;
+        let debug_overlay = DebugLines::new(
+            device,
+            config.format,
+            sample_count,
+            cache,
+            DebugDepth::AlwaysOnTop,
+        )?;
+        // 模型缩放到单位球内，网格放在球的下方
+        let grid = InfiniteGrid::new(
+            device,
+            config.format,
+            sample_count,
+            cache,
+            GridSettings {
+                height: -1.0,
+                spacing: 0.25,
+                major_every: 4,
+                fade_distance: 8.0,
+            },
+        )?;
+        let sky = image::load_from_memory(DEBUG_CUBE_MAP).map_err(WgpuAppError::ImageDecode)?;
+        let mut mipmaps = MipmapGenerator::new(device)?;
+        let sky = CubeMap::from_cross(
+            device,
+            queue,
+            samplers,
+            &sky,
+            &mut mipmaps,
+            Some("Debug Cube Map"),
+        )?;
+        let skybox = SkyboxPass::new(device, config.format, sample_count, cache, &sky)?;
+        let mut gizmo = TranslateGizmo::new(device, config.format, sample_count, cache)?;
+        gizmo.visible = false;
+        let camera = Camera {
+            eye: Vec3::new(0.0, 0.0, CAMERA_DISTANCE),
+            projection: Projection::Perspective {
+                fovy: FOV_Y,
+                znear: NEAR,
+                zfar: FAR,
+            },
+            ..Default::default()
+        };
+        // 模型在单位球内，距离限制在近裁剪面之外、远裁剪面能容纳整个模型的范围内
+        let mut orbit = OrbitController::from_camera(&camera);
+        orbit.min_distance = 1.2;
+        orbit.max_distance = FAR - 2.0;
+
+        // 3. 拖放模型之前显示一个圆环
+        let (vertices, indices) = shapes::torus(1.0, 0.4, 48, 24);
+        let mut viewer = Self {
+            pipelines,
+            pipeline_layout,
+            sample_count,
+            cache: cache.cloned(),
+            config: config.clone(),
+            camera,
+            controller: CameraController::new(orbit).with_bookmarks("model"),
+            uniform_buffer,
+            bind_group,
+            uniform: bytemuck::Zeroable::zeroed(),
+            transform: Transform::IDENTITY,
+            fit: Transform::IDENTITY,
+            meshes: vec![Mesh::new_with_cpu_data(
+                device,
+                "Torus",
+                &vertices,
+                Some(&indices),
+            )],
+            wireframe_meshes: Vec::new(),
+            bounds: Aabb::EMPTY,
+            show_bounds: false,
+            show_normals: false,
+            debug_lines,
+            debug_overlay,
+            grid,
+            skybox,
+            mipmaps,
+            samplers: samplers.clone(),
+            gizmo,
+        };
+        viewer.wireframe_meshes =
+            viewer.upload_wireframe(device, [("Torus", vertices.as_slice(), indices.as_slice())]);
+        viewer.fit();
+        Ok(viewer)
+    }
+
+    fn render(
+        &mut self,
+        ctx: &RenderContext,
+        view: &wgpu::TextureView,
+        encoder: &mut wgpu::CommandEncoder,
+    ) {
+        let angle = (ROTATION_SPEED * ctx.time) % std::f32::consts::TAU;
+        self.transform.rotation = Quat::from_rotation_y(angle);
+        let world = self.transform * self.fit;
+        let normal = world.normal_matrix();
+        self.uniform = ViewUniform {
+            view_proj: self.view_proj().to_cols_array_2d(),
+            model: world.matrix().to_cols_array_2d(),
+            normal: [normal.x_axis, normal.y_axis, normal.z_axis].map(|c| c.extend(0.0).to_array()),
+        };
+        ctx.upload(encoder, &self.uniform_buffer, 0, &self.uniform);
+        // 调试线按本帧的旋转角度生成；坐标轴位于模型空间，与着色器使用相同的模型、视图和投影变换
+        self.add_debug_lines();
+        let view_proj = self.view_proj();
+        let model_view_proj = view_proj * self.model();
+        self.debug_lines.prepare(ctx, encoder, view_proj);
+        self.debug_overlay.prepare(ctx, encoder, model_view_proj);
+        self.grid.prepare(ctx, encoder, view_proj);
+        let projection = self.camera.projection(aspect_ratio(&self.config));
+        self.skybox
+            .prepare(ctx, encoder, self.camera.view(), projection);
+
+        let mut pass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
+            label: Some("OBJ Viewer Pass"),
+            color_attachments: &[Some(ctx.color_attachment(
+                view,
+                wgpu::Operations {
+                    load: wgpu::LoadOp::Clear(ctx.clear_color),
+                    store: wgpu::StoreOp::Store,
+                },
+            ))],
+            depth_stencil_attachment: ctx.depth_attachment(), // 深度附件（每帧清除为1.0）
+            timestamp_writes: ctx.timestamp_writes(),
+            occlusion_query_set: None,
+        });
+        pass.set_pipeline(self.pipelines.get(ctx.wireframe));
+        pass.set_bind_group(0, &self.bind_group, &[]);
+        // 重心坐标方式需要不带索引绘制展开后的网格
+        let meshes = if ctx.wireframe
+            && self.pipelines.wireframe_variant() == PipelineVariant::Barycentric
+        {
+            &self.wireframe_meshes
+        } else {
+            &self.meshes
+        };
+        for mesh in meshes {
+            mesh.draw(&mut pass, 0..1);
+        }
+        // 天空只填充模型没有覆盖的像素，半透明的网格画在天空之上
+        self.skybox.draw(&mut pass);
+        self.grid.draw(&mut pass);
+        self.debug_lines.draw(&mut pass);
+        self.debug_overlay.draw(&mut pass);
+        drop(pass);
+
+        // 手柄单独一个渲染通道，画在所有内容之上
+        self.gizmo.render(ctx, view, encoder);
+    }
+
+    fn shader_changed(
+        &mut self,
+        device: &wgpu::Device,
+        config: &wgpu::SurfaceConfiguration,
+        path: &Path,
+    ) {
+        self.debug_lines.shader_changed(device, path);
+        self.debug_overlay.shader_changed(device, path);
+        self.grid.shader_changed(device, path);
+        self.skybox.shader_changed(device, path);
+        self.gizmo.shader_changed(device, path);
+        if !shader::depends_on(SHADER, path) {
+            return;
+        }
+        let _ = self.rebuild_pipeline(device, config.format, self.sample_count);
+    }
+
+    fn sample_count_changed(
+        &mut self,
+        device: &wgpu::Device,
+        config: &wgpu::SurfaceConfiguration,
+        sample_count: u32,
+    ) -> Result<(), WgpuAppError> {
+        self.rebuild_pipeline(device, config.format, sample_count)?;
+        self.debug_lines
+            .sample_count_changed(device, sample_count)?;
+        self.debug_overlay
+            .sample_count_changed(device, sample_count)?;
+        self.grid.sample_count_changed(device, sample_count)?;
+        self.skybox.sample_count_changed(device, sample_count)?;
+        self.gizmo.sample_count_changed(device, sample_count)?;
+        Ok(())
+    }
+}
+
+impl Scene for ObjViewer {
+    /// 处理开关按键、平移手柄的拖动和相机控制（拖动手柄时不移动相机），按C键切换轨道和飞行控制
+    fn update(&mut self, dt: Duration, _queue: &wgpu::Queue, input: &InputState) {
+        if input.just_pressed(KeyCode::KeyB) {
+            self.show_bounds = !self.show_bounds;
+        }
+        if input.just_pressed(KeyCode::KeyH) {
+            self.show_normals = !self.show_normals;
+        }
+        if input.just_pressed(KeyCode::KeyG) {
+            self.grid.visible = !self.grid.visible;
+        }
+        if input.just_pressed(KeyCode::KeyK) {
+            self.skybox.visible = !self.skybox.visible;
+        }
+        if input.just_pressed(KeyCode::KeyT) {
+            self.gizmo.visible = !self.gizmo.visible;
+        }
+        // 光标的表面坐标（左上角为原点，y向下）换算成归一化设备坐标（y向上）
+        let cursor = input
+            .cursor_normalized(&self.config)
+            .map(|(x, y)| Vec2::new(x as f32 * 2.0 - 1.0, 1.0 - y as f32 * 2.0));
+        self.gizmo.position = self.transform.translation;
+        if let Some(drag) = self.gizmo.update(input, cursor, self.view_proj()) {
+            self.transform.translation += drag.delta;
+        }
+        if input.just_pressed(KeyCode::KeyC) {
+            self.controller.toggle(&self.camera);
+            info!("Camera controller: {}", self.controller.name());
+        }
+        if self.gizmo.dragging().is_none() {
+            self.controller
+                .update(input, dt, self.config.height, &mut self.camera);
+        }
+    }
+
+    /// 记录新的表面配置，用于光标坐标换算和相机的宽高比
+    fn resized(&mut self, _device: &wgpu::Device, config: &wgpu::SurfaceConfiguration) {
+        self.config = config.clone();
+    }
+
+    /// 填充和线框管线都在`PipelineSet`中
+    fn supports_wireframe(&self) -> bool {
+        true
+    }
+
+    /// 拖放`.obj`文件时加载并替换当前模型，拖放PNG或JPEG图片时把它作为十字布局的立方体贴图替换天空，
+    /// 加载失败时保留原来的模型或天空
+    fn file_dropped(&mut self, path: &Path, device: &wgpu::Device, queue: &wgpu::Queue) -> bool {
+        let is_image = path.extension().is_some_and(|ext| {
+            ["png", "jpg", "jpeg"]
+                .iter()
+                .any(|image| ext.eq_ignore_ascii_case(image))
+        });
+        if is_image {
+            match CubeMap::load_cross(device, queue, &self.samplers, path, &mut self.mipmaps) {
+                Ok(sky) => {
+                    self.skybox.set_cube_map(device, &sky);
+                    info!("Skybox {} ({}px faces)", path.display(), sky.face_size());
+                }
+                Err(e) => error!("Failed to load skybox, keeping the previous one: {e}"),
+            }
+            return true;
+        }
+        if path
+            .extension()
+            .is_none_or(|ext| !ext.eq_ignore_ascii_case("obj"))
+        {
+            return false;
+        }
+        match obj::load(path) {
+            Ok(model) => {
+                for mesh in &model.meshes {
+                    if let Some(texture) = model
+                        .material(mesh)
+                        .and_then(|material| material.diffuse_texture.as_ref())
+                    {
+                        info!("Mesh `{}` uses texture {}", mesh.name, texture.display());
+                    }
+                }
+                self.meshes = model.upload_with_cpu_data(device);
+                self.wireframe_meshes = self.upload_wireframe(
+                    device,
+                    model.meshes.iter().map(|mesh| {
+                        (
+                            mesh.name.as_str(),
+                            mesh.vertices.as_slice(),
+                            mesh.indices.as_slice(),
+                        )
+                    }),
+                );
+                self.fit();
+            }
+            Err(e) => error!("Failed to load model, keeping the previous one: {e}"),
+        }
+        true
+    }
+}
+
+impl ObjViewer {
+    /// 合并所有网格的包围盒，把中心移到原点，并缩放到单位球内
+    fn fit(&mut self) {
+        self.bounds = self
+            .meshes
+            .iter()
+            .fold(Aabb::EMPTY, |bounds, mesh| bounds.union(&mesh.aabb()));
+        let radius = self.bounds.extent().length();
+        let scale = if radius > f32::EPSILON {
+            1.0 / radius
+        } else {
+            1.0
+        };
+        self.fit = Transform::from_scale(Vec3::splat(scale))
+            * Transform::from_translation(-self.bounds.center());
+    }
+    /// 重新生成调试线（立即模式）：B键打开时添加随模型旋转的模型包围盒、每个网格在世界空间中的
+    /// 轴对齐包围盒（旋转时会变大）、包围球和模型空间的坐标轴，H键打开时添加顶点法线
+    fn add_debug_lines(&mut self) {
+        self.debug_lines.clear();
+        self.debug_overlay.clear();
+        let model = self.model();
+        if self.show_normals {
+            for mesh in &self.meshes {
+                if let Some(vertices) = mesh.cpu_vertices::<MeshVertex>() {
+                    self.debug_lines
+                        .add_normals(vertices, model, NORMAL_LENGTH, MAX_NORMALS);
+                }
+            }
+        }
+        if !self.show_bounds {
+            return;
+        }
+        self.debug_lines
+            .add_box(&self.bounds, model, [1.0, 0.8, 0.1, 1.0]);
+        for mesh in &self.meshes {
+            self.debug_lines
+                .add_aabb(&mesh.aabb().transform(model), [0.2, 0.9, 0.9, 1.0]);
+        }
+        // 模型缩放到单位球内，世界空间中包围球的半径为1
+        let center = self.bounds.center();
+        self.debug_lines
+            .add_sphere(model.transform_point3(center), 1.0, [0.4, 0.4, 0.4, 1.0]);
+        let length = 0.5 / self.fit.scale.x;
+        for (axis, color) in [
+            (Vec3::X, [1.0, 0.2, 0.2, 1.0]),
+            (Vec3::Y, [0.2, 1.0, 0.2, 1.0]),
+            (Vec3::Z, [0.2, 0.4, 1.0, 1.0]),
+        ] {
+            self.debug_overlay
+                .add(center, center + axis * length, color);
+        }
+    }
+
+    /// 世界空间到裁剪空间的变换（每帧写入ViewUniform，调试线、网格和手柄也使用它）
+    fn view_proj(&self) -> Mat4 {
+        self.camera.view_proj(aspect_ratio(&self.config))
+    }
+
+    /// 模型空间到世界空间的变换：移到包围盒中心、缩放到单位球内，绕Y轴旋转，再按手柄平移
+    fn model(&self) -> Mat4 {
+        (self.transform * self.fit).matrix()
+    }
+
+    /// 重心坐标线框方式下上传展开索引后的网格（每个三角形三个独立顶点），其他方式下不需要
+    fn upload_wireframe<'a>(
+        &self,
+        device: &wgpu::Device,
+        parts: impl IntoIterator<Item = (&'a str, &'a [MeshVertex], &'a [u32])>,
+    ) -> Vec<Mesh> {
+        if self.pipelines.wireframe_variant() != PipelineVariant::Barycentric {
+            return Vec::new();
+        }
+        parts
+            .into_iter()
+            .map(|(name, vertices, indices)| {
+                let label = format!("{name} Wireframe");
+                Mesh::new(device, &label, &deindex(vertices, indices), None)
+            })
+            .collect()
+    }
+
+    /// 用`rebuild_pipeline`按sample_count重建填充和线框管线，成功后才更新多重采样数
+    fn rebuild_pipeline(
+        &mut self,
+        device: &wgpu::Device,
+        format: wgpu::TextureFormat,
+        sample_count: u32,
+    ) -> Result<(), WgpuAppError> {
+        rebuild_pipeline(device, SHADER, &mut self.pipelines, |shader| {
+            PipelineSet::new(device.features(), |variant| {
+                create_pipeline(
+                    device,
+                    shader,
+                    format,
+                    &self.pipeline_layout,
+                    sample_count,
+                    self.cache.as_ref(),
+                    variant,
+                )
+            })
+        })?;
+        self.sample_count = sample_count;
+        Ok(())
+    }
+}
+
+/// 第0组：顶点着色器使用的ViewUniform
+fn create_bind_group_layout(device: &wgpu::Device) -> BindingLayout {
+    BindGroupLayoutBuilder::new()
+        .label("OBJ Viewer Bind Group Layout")
+        .uniform(wgpu::ShaderStages::VERTEX)
+        .build(device)
+}
+
+/// 创建读取MeshVertex并开启深度测试的渲染管线，variant决定多边形模式和着色器入口
+fn create_pipeline(
+    device: &wgpu::Device,
+    shader: &wgpu::ShaderModule,
+    format: wgpu::TextureFormat,
+    layout: &wgpu::PipelineLayout,
+    sample_count: u32,
+    cache: Option<&wgpu::PipelineCache>,
+    variant: PipelineVariant,
+) -> wgpu::RenderPipeline {
+    let (vs, fs) = variant.entry_points();
+    device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
+        label: Some(match variant {
+            PipelineVariant::Fill => "OBJ Viewer Pipeline",
+            PipelineVariant::Line | PipelineVariant::Barycentric => "OBJ Viewer Wireframe Pipeline",
+        }),
+        layout: Some(layout),
+        vertex: wgpu::VertexState {
+            module: shader,
+            entry_point: Some(vs),
+            buffers: &[MeshVertex::LAYOUT],
+            compilation_options: Default::default(),
+        },
+        fragment: Some(wgpu::FragmentState {
+            module: shader,
+            entry_point: Some(fs),
+            targets: &[Some(wgpu::ColorTargetState {
+                format,
+                blend: Some(wgpu::BlendState::REPLACE),
+                write_mask: wgpu::ColorWrites::ALL,
+            })],
+            compilation_options: Default::default(),
+        }),
+        primitive: variant.primitive(Default::default()), // 不剔除背面（OBJ文件的绕序不一定一致）
+        depth_stencil: Some(DepthTexture::depth_stencil_state()), // LessEqual深度测试
+        multisample: wgpu::MultisampleState {
+            count: sample_count,
+            ..Default::default()
+        },
+        multiview: None,
+        cache,
+    })
+}
@@ -0,0 +1,133 @@
+use super::vertex_color::{SHADER, create_pipeline};
+use crate::{
+    InitContext, Mesh, RedrawMode, RenderContext, Renderer, Scene, Vertex, WgpuAppError,
+    rebuild_pipeline, shader,
+};
+use std::path::Path;
+
+/// 五边形的5个顶点（逆时针）
+const VERTICES: &[Vertex] = &[
+    Vertex {
+        position: [-0.0868241, 0.49240386, 0.0],
+        color: [1.0, 0.0, 0.0],
+    },
+    Vertex {
+        position: [-0.49513406, 0.06958647, 0.0],
+        color: [1.0, 1.0, 0.0],
+    },
+    Vertex {
+        position: [-0.21918549, -0.44939706, 0.0],
+        color: [0.0, 1.0, 0.0],
+    },
+    Vertex {
+        position: [0.35966998, -0.3473291, 0.0],
+        color: [0.0, 0.0, 1.0],
+    },
+    Vertex {
+        position: [0.44147372, 0.2347359, 0.0],
+        color: [1.0, 0.0, 1.0],
+    },
+];
+
+/// 3个三角形共9个索引，顶点4被三个三角形共用
+const INDICES: &[u32] = &[0, 1, 4, 1, 2, 4, 2, 3, 4];
+
+/// 索引绘制的五边形：5个顶点通过9个索引组成3个三角形，共用的顶点只存储一次
+pub struct Pentagon {
+    pipeline: wgpu::RenderPipeline,     // 使用Vertex::LAYOUT的渲染管线
+    mesh: Mesh,                         // 顶点和索引缓冲区
+    sample_count: u32,                  // 管线使用的多重采样数
+    cache: Option<wgpu::PipelineCache>, // 管线缓存（重建管线时复用）
+}
+
+impl Renderer for Pentagon {
+    fn init(ctx: &InitContext) -> Result<Self, WgpuAppError> {
+        let InitContext {
+            device,
+            config,
+            sample_count,
+            cache,
+            ..
+        } = *ctx;
+        // 1. 上传顶点和索引（5个顶点，索引使用u16）
+        let mesh = Mesh::new(device, "Pentagon", VERTICES, Some(INDICES));
+
+        // 2. 与顶点颜色三角形共用着色器和管线配置
+        let shader = shader::load(device, SHADER)?;
+        let pipeline = create_pipeline(device, &shader, config.format, sample_count, cache);
+        Ok(Self {
+            pipeline,
+            mesh,
+            sample_count,
+            cache: cache.cloned(),
+        })
+    }
+
+    fn render(
+        &mut self,
+        ctx: &RenderContext,
+        view: &wgpu::TextureView,
+        encoder: &mut wgpu::CommandEncoder,
+    ) {
+        let mut pass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
+            label: Some("Pentagon Pass"),
+            color_attachments: &[Some(ctx.color_attachment(
+                view,
+                wgpu::Operations {
+                    load: wgpu::LoadOp::Clear(ctx.clear_color),
+                    store: wgpu::StoreOp::Store,
+                },
+            ))],
+            depth_stencil_attachment: None,
+            timestamp_writes: ctx.timestamp_writes(),
+            occlusion_query_set: None,
+        });
+        pass.set_pipeline(&self.pipeline);
+        // 按索引绘制：set_index_buffer + draw_indexed
+        self.mesh.draw(&mut pass, 0..1);
+    }
+
+    fn shader_changed(
+        &mut self,
+        device: &wgpu::Device,
+        config: &wgpu::SurfaceConfiguration,
+        path: &Path,
+    ) {
+        if !shader::depends_on(SHADER, path) {
+            return;
+        }
+        let _ = self.rebuild_pipeline(device, config.format, self.sample_count);
+    }
+
+    fn sample_count_changed(
+        &mut self,
+        device: &wgpu::Device,
+        config: &wgpu::SurfaceConfiguration,
+        sample_count: u32,
+    ) -> Result<(), WgpuAppError> {
+        self.rebuild_pipeline(device, config.format, sample_count)
+    }
+}
+
+impl Scene for Pentagon {
+    /// 静态画面，只在尺寸、背景色或着色器变化时重绘
+    fn redraw_mode(&self) -> RedrawMode {
+        RedrawMode::OnDemand
+    }
+}
+
+impl Pentagon {
+    /// 用`rebuild_pipeline`按sample_count重建管线，成功后才更新多重采样数
+    fn rebuild_pipeline(
+        &mut self,
+        device: &wgpu::Device,
+        format: wgpu::TextureFormat,
+        sample_count: u32,
+    ) -> Result<(), WgpuAppError> {
+        rebuild_pipeline(device, SHADER, &mut self.pipeline, |shader| {
+            create_pipeline(device, shader, format, sample_count, self.cache.as_ref())
+        })?;
+        self.sample_count = sample_count;
+        Ok(())
+    }
+}
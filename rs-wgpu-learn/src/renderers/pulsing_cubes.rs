@@ -0,0 +1,204 @@
+use super::instanced_cubes::{CUBE_SIZE, SHADER, create_pipeline, grid_camera, grid_instance};
+use crate::{
+    InitContext, InputState, InstanceBuffer, Mesh, RenderContext, Renderer, Scene, WgpuAppError,
+    binding,
+    camera::{Camera, CameraBuffer, CameraController, aspect_ratio},
+    indirect::{self, DrawIndexedIndirectArgs},
+    rebuild_pipeline, shader, shapes,
+};
+use log::info;
+use std::{path::Path, time::Duration};
+use winit::keyboard::KeyCode;
+
+/// 网格边长
+const GRID_SIZE: u32 = 40;
+
+/// 可见立方体数量的脉动周期（秒）
+const PULSE_PERIOD: f32 = 4.0;
+
+/// 间接绘制：实例数不写在绘制调用里，而是每帧由CPU写入间接缓冲区
+///
+/// 实例按到网格中心的距离排序，所以改变实例数时可见的立方体从中心向外扩张、再收缩回中心。
+/// 之后由计算着色器填写间接缓冲区（GPU驱动的剔除等）时，绘制代码不需要任何改动。
+pub struct PulsingCubes {
+    pipeline: wgpu::RenderPipeline,
+    pipeline_layout: wgpu::PipelineLayout,
+    sample_count: u32,
+    cache: Option<wgpu::PipelineCache>,
+    camera: Camera,                     // 从斜上方看向网格中心的相机
+    controller: CameraController,       // 轨道或飞行控制器（C键切换）
+    camera_buffer: CameraBuffer,        // 第0组：相机的视图投影矩阵
+    config: wgpu::SurfaceConfiguration, // 表面配置（宽高比和鼠标灵敏度，尺寸变化时更新）
+    mesh: Mesh,                         // 立方体网格，槽位1挂着实例缓冲区
+    instance_buffer: InstanceBuffer,    // 按到中心的距离排序的实例数据（创建后不再变化）
+    indirect_buffer: wgpu::Buffer,      // 一条DrawIndexedIndirectArgs
+}
+
+impl Renderer for PulsingCubes {
+    fn init(ctx: &InitContext) -> Result<Self, WgpuAppError> {
+        let InitContext {
+            device,
+            config,
+            sample_count,
+            cache,
+            ..
+        } = *ctx;
+        // 1. 创建相机缓冲区和管线布局（与实例化示例共用着色器）
+        let (camera, orbit) = grid_camera(GRID_SIZE);
+        let mut camera_buffer = CameraBuffer::new(device);
+        camera_buffer.update(&camera, aspect_ratio(config));
+        let pipeline_layout = binding::pipeline_layout(
+            device,
+            Some("Pulsing Cubes Pipeline Layout"),
+            &[camera_buffer.layout()],
+        );
+
+        // 2. 加载着色器并创建管线
+        let shader = shader::load(device, SHADER)?;
+        let pipeline = create_pipeline(
+            device,
+            &shader,
+            config.format,
+            &pipeline_layout,
+            sample_count,
+            cache,
+        );
+
+        // 3. 创建立方体网格和按到中心距离排序的实例
+        let (vertices, indices) = shapes::cube(CUBE_SIZE);
+        let mut mesh = Mesh::new(device, "Pulsing Cube", &vertices, Some(&indices));
+        let mut instances = (0..GRID_SIZE * GRID_SIZE)
+            .map(|i| grid_instance(GRID_SIZE, i, 0.0))
+            .collect::<Vec<_>>();
+        instances.sort_by(|a, b| {
+            let distance = |model: &[[f32; 4]; 4]| model[3][0].hypot(model[3][2]);
+            distance(&a.model).total_cmp(&distance(&b.model))
+        });
+        let instance_buffer = InstanceBuffer::new(device, "Pulsing Cubes", &instances);
+        mesh.add_vertex_buffer(instance_buffer.buffer().clone());
+
+        // 4. 间接缓冲区：初始绘制所有实例，之后每帧改写实例数
+        let indirect_buffer = indirect::create_indirect_buffer(
+            device,
+            Some("Pulsing Cubes Indirect Buffer"),
+            &[mesh.indexed_indirect_args(instance_buffer.range())],
+        );
+
+        Ok(Self {
+            pipeline,
+            pipeline_layout,
+            sample_count,
+            cache: cache.cloned(),
+            camera,
+            controller: CameraController::new(orbit).with_bookmarks("indirect"),
+            camera_buffer,
+            config: config.clone(),
+            mesh,
+            instance_buffer,
+            indirect_buffer,
+        })
+    }
+
+    fn render(
+        &mut self,
+        ctx: &RenderContext,
+        view: &wgpu::TextureView,
+        encoder: &mut wgpu::CommandEncoder,
+    ) {
+        // 可见实例数在0和全部之间按余弦脉动，写入间接缓冲区
+        let phase = ctx.time / PULSE_PERIOD * std::f32::consts::TAU;
+        let fraction = 0.5 - 0.5 * phase.cos();
+        let count = (fraction * self.instance_buffer.len() as f32).round() as u32;
+        let args = DrawIndexedIndirectArgs {
+            instance_count: count,
+            ..self
+                .mesh
+                .indexed_indirect_args(self.instance_buffer.range())
+        };
+        ctx.upload(encoder, &self.indirect_buffer, 0, &args);
+
+        let mut pass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
+            label: Some("Pulsing Cubes Pass"),
+            color_attachments: &[Some(ctx.color_attachment(
+                view,
+                wgpu::Operations {
+                    load: wgpu::LoadOp::Clear(ctx.clear_color),
+                    store: wgpu::StoreOp::Store,
+                },
+            ))],
+            depth_stencil_attachment: ctx.depth_attachment(),
+            timestamp_writes: ctx.timestamp_writes(),
+            occlusion_query_set: None,
+        });
+        pass.set_pipeline(&self.pipeline);
+        self.camera_buffer.bind(ctx, &mut pass, 0);
+        // 绘制参数（包括实例数）从间接缓冲区读取
+        self.mesh.draw_indirect(
+            &mut pass,
+            &self.indirect_buffer,
+            indirect::indirect_offset::<DrawIndexedIndirectArgs>(0),
+        );
+    }
+
+    fn shader_changed(
+        &mut self,
+        device: &wgpu::Device,
+        config: &wgpu::SurfaceConfiguration,
+        path: &Path,
+    ) {
+        if !shader::depends_on(SHADER, path) {
+            return;
+        }
+        let _ = self.rebuild_pipeline(device, config.format, self.sample_count);
+    }
+
+    fn resize(&mut self, config: &wgpu::SurfaceConfiguration) {
+        self.config = config.clone();
+    }
+
+    fn sample_count_changed(
+        &mut self,
+        device: &wgpu::Device,
+        config: &wgpu::SurfaceConfiguration,
+        sample_count: u32,
+    ) -> Result<(), WgpuAppError> {
+        self.rebuild_pipeline(device, config.format, sample_count)
+    }
+}
+
+impl Scene for PulsingCubes {
+    /// 用鼠标和键盘更新相机（C键切换轨道和飞行控制，见`CameraController`）并写入相机矩阵
+    fn update(&mut self, dt: Duration, _queue: &wgpu::Queue, input: &InputState) {
+        if input.just_pressed(KeyCode::KeyC) {
+            self.controller.toggle(&self.camera);
+            info!("Camera controller: {}", self.controller.name());
+        }
+        self.controller
+            .update(input, dt, self.config.height, &mut self.camera);
+        self.camera_buffer
+            .update(&self.camera, aspect_ratio(&self.config));
+    }
+}
+
+impl PulsingCubes {
+    /// 用`rebuild_pipeline`按sample_count重建管线，成功后才更新多重采样数
+    fn rebuild_pipeline(
+        &mut self,
+        device: &wgpu::Device,
+        format: wgpu::TextureFormat,
+        sample_count: u32,
+    ) -> Result<(), WgpuAppError> {
+        rebuild_pipeline(device, SHADER, &mut self.pipeline, |shader| {
+            create_pipeline(
+                device,
+                shader,
+                format,
+                &self.pipeline_layout,
+                sample_count,
+                self.cache.as_ref(),
+            )
+        })?;
+        self.sample_count = sample_count;
+        Ok(())
+    }
+}
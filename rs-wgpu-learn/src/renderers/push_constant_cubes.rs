@@ -0,0 +1,277 @@
+use crate::{
+    DepthTexture, InitContext, Mesh, MeshVertex, PushConstants, RenderContext, Renderer, Scene,
+    WgpuAppError,
+    binding::{BindGroupBuilder, BindGroupLayoutBuilder},
+    rebuild_pipeline_with_defines, shader, shapes,
+};
+use log::info;
+use std::path::Path;
+
+/// 推送常量示例使用的着色器文件
+const SHADER: &str = "push_constants.wgsl";
+
+/// 每边的立方体数量（共GRID×GRID次绘制）
+const GRID: u32 = 4;
+
+/// 相邻立方体中心的距离
+const SPACING: f32 = 1.5;
+
+/// 旋转速度（弧度/秒）
+const ROTATION_SPEED: f32 = 0.8;
+
+/// 与着色器中ViewUniform对应的数据（按16字节对齐）
+#[repr(C)]
+#[derive(Debug, Clone, Copy, bytemuck::Pod, bytemuck::Zeroable)]
+struct ViewUniform {
+    aspect: f32,
+    angle: f32,
+    _padding: [f32; 2],
+}
+
+/// 与着色器中DrawData对应的逐绘制数据
+#[repr(C)]
+#[derive(Debug, Clone, Copy, bytemuck::Pod, bytemuck::Zeroable)]
+struct DrawData {
+    offset: [f32; 4], // xyz为立方体中心，w为缩放
+    color: [f32; 4],  // 线性空间的颜色
+}
+
+/// 推送常量：同一个立方体网格绘制16次，每次的位置和颜色通过`PushConstants`传入
+///
+/// 设备不支持PUSH_CONSTANTS时自动回退到带动态偏移的uniform缓冲区，绘制代码相同；
+/// 窗口标题中显示实际使用的方式。
+pub struct PushConstantCubes {
+    pipeline: wgpu::RenderPipeline,
+    pipeline_layout: wgpu::PipelineLayout,
+    sample_count: u32,
+    cache: Option<wgpu::PipelineCache>,
+    uniform_buffer: wgpu::Buffer,
+    bind_group: wgpu::BindGroup,
+    mesh: Mesh,
+    push_constants: PushConstants<DrawData>, // 逐绘制数据（推送常量或回退的uniform缓冲区）
+}
+
+impl Renderer for PushConstantCubes {
+    fn init(ctx: &InitContext) -> Result<Self, WgpuAppError> {
+        let InitContext {
+            device,
+            config,
+            sample_count,
+            cache,
+            ..
+        } = *ctx;
+        // 1. 创建uniform缓冲区和绑定组，逐绘制数据的回退绑定组排在其后（组1）
+        let uniform_buffer = device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some("Push Constants Uniform Buffer"),
+            size: std::mem::size_of::<ViewUniform>() as u64,
+            usage: wgpu::BufferUsages::UNIFORM | wgpu::BufferUsages::COPY_DST,
+            mapped_at_creation: false,
+        });
+        let bind_group_layout = BindGroupLayoutBuilder::new()
+            .label("Push Constants Bind Group Layout")
+            .uniform(wgpu::ShaderStages::VERTEX)
+            .build(device);
+        let bind_group = BindGroupBuilder::new(&bind_group_layout)
+            .label("Push Constants Bind Group")
+            .buffer(&uniform_buffer)
+            .build(device);
+        let push_constants = PushConstants::new(
+            device,
+            "Push Constants",
+            wgpu::ShaderStages::VERTEX_FRAGMENT,
+            1,
+            GRID * GRID,
+        );
+        info!(
+            "Per-draw data uses {}",
+            if push_constants.is_native() {
+                "push constants"
+            } else {
+                "a dynamic uniform buffer (PUSH_CONSTANTS unavailable)"
+            }
+        );
+        let pipeline_layout = push_constants.pipeline_layout(
+            device,
+            Some("Push Constants Pipeline Layout"),
+            &[&bind_group_layout],
+        );
+
+        // 2. 按推送常量的方式加载着色器并创建管线
+        let shader = shader::load_with_defines(device, SHADER, &push_constants.defines())?;
+        let pipeline = create_pipeline(
+            device,
+            &shader,
+            config.format,
+            &pipeline_layout,
+            sample_count,
+            cache,
+        );
+
+        let (vertices, indices) = shapes::cube(1.0);
+        let mesh = Mesh::new(device, "Push Constant Cube", &vertices, Some(&indices));
+
+        Ok(Self {
+            pipeline,
+            pipeline_layout,
+            sample_count,
+            cache: cache.cloned(),
+            uniform_buffer,
+            bind_group,
+            mesh,
+            push_constants,
+        })
+    }
+
+    fn render(
+        &mut self,
+        ctx: &RenderContext,
+        view: &wgpu::TextureView,
+        encoder: &mut wgpu::CommandEncoder,
+    ) {
+        let uniform = ViewUniform {
+            aspect: ctx.config.width.max(1) as f32 / ctx.config.height.max(1) as f32,
+            angle: (ROTATION_SPEED * ctx.time) % std::f32::consts::TAU,
+            _padding: [0.0; 2],
+        };
+        ctx.upload(encoder, &self.uniform_buffer, 0, &uniform);
+
+        let mut pass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
+            label: Some("Push Constants Pass"),
+            color_attachments: &[Some(ctx.color_attachment(
+                view,
+                wgpu::Operations {
+                    load: wgpu::LoadOp::Clear(ctx.clear_color),
+                    store: wgpu::StoreOp::Store,
+                },
+            ))],
+            depth_stencil_attachment: ctx.depth_attachment(),
+            timestamp_writes: ctx.timestamp_writes(),
+            occlusion_query_set: None,
+        });
+        pass.set_pipeline(&self.pipeline);
+        pass.set_bind_group(0, &self.bind_group, &[]);
+        // 同一个网格绘制GRID×GRID次，每次之前设置位置和颜色
+        self.push_constants.begin();
+        let last = (GRID - 1) as f32;
+        for i in 0..GRID * GRID {
+            let (row, column) = (i / GRID, i % GRID);
+            let (u, v) = (column as f32 / last, row as f32 / last);
+            let data = DrawData {
+                offset: [
+                    (column as f32 - last / 2.0) * SPACING,
+                    0.0,
+                    (row as f32 - last / 2.0) * SPACING,
+                    0.5 + 0.4 * (u + v) / 2.0,
+                ],
+                color: [u, 1.0 - v, 0.5 + 0.5 * v * u, 1.0],
+            };
+            self.push_constants.set(ctx.queue, &mut pass, &data);
+            self.mesh.draw(&mut pass, 0..1);
+        }
+    }
+
+    fn shader_changed(
+        &mut self,
+        device: &wgpu::Device,
+        config: &wgpu::SurfaceConfiguration,
+        path: &Path,
+    ) {
+        if !shader::depends_on(SHADER, path) {
+            return;
+        }
+        let _ = self.rebuild_pipeline(device, config.format, self.sample_count);
+    }
+
+    fn sample_count_changed(
+        &mut self,
+        device: &wgpu::Device,
+        config: &wgpu::SurfaceConfiguration,
+        sample_count: u32,
+    ) -> Result<(), WgpuAppError> {
+        self.rebuild_pipeline(device, config.format, sample_count)
+    }
+}
+
+impl Scene for PushConstantCubes {
+    fn status(&self) -> Option<String> {
+        Some(
+            if self.push_constants.is_native() {
+                "push constants"
+            } else {
+                "dynamic uniform fallback"
+            }
+            .to_string(),
+        )
+    }
+}
+
+impl PushConstantCubes {
+    /// 用`rebuild_pipeline_with_defines`按sample_count重建管线，成功后才更新多重采样数
+    fn rebuild_pipeline(
+        &mut self,
+        device: &wgpu::Device,
+        format: wgpu::TextureFormat,
+        sample_count: u32,
+    ) -> Result<(), WgpuAppError> {
+        rebuild_pipeline_with_defines(
+            device,
+            SHADER,
+            &self.push_constants.defines(),
+            &mut self.pipeline,
+            |shader| {
+                create_pipeline(
+                    device,
+                    shader,
+                    format,
+                    &self.pipeline_layout,
+                    sample_count,
+                    self.cache.as_ref(),
+                )
+            },
+        )?;
+        self.sample_count = sample_count;
+        Ok(())
+    }
+}
+
+/// 创建读取MeshVertex并开启深度测试的渲染管线
+fn create_pipeline(
+    device: &wgpu::Device,
+    shader: &wgpu::ShaderModule,
+    format: wgpu::TextureFormat,
+    layout: &wgpu::PipelineLayout,
+    sample_count: u32,
+    cache: Option<&wgpu::PipelineCache>,
+) -> wgpu::RenderPipeline {
+    device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
+        label: Some("Push Constants Pipeline"),
+        layout: Some(layout),
+        vertex: wgpu::VertexState {
+            module: shader,
+            entry_point: Some("vs"),
+            buffers: &[MeshVertex::LAYOUT],
+            compilation_options: Default::default(),
+        },
+        fragment: Some(wgpu::FragmentState {
+            module: shader,
+            entry_point: Some("fs"),
+            targets: &[Some(wgpu::ColorTargetState {
+                format,
+                blend: Some(wgpu::BlendState::REPLACE),
+                write_mask: wgpu::ColorWrites::ALL,
+            })],
+            compilation_options: Default::default(),
+        }),
+        primitive: wgpu::PrimitiveState {
+            cull_mode: Some(wgpu::Face::Back),
+            ..Default::default()
+        },
+        depth_stencil: Some(DepthTexture::depth_stencil_state()),
+        multisample: wgpu::MultisampleState {
+            count: sample_count,
+            ..Default::default()
+        },
+        multiview: None,
+        cache,
+    })
+}
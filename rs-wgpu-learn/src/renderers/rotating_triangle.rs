@@ -0,0 +1,220 @@
+use crate::{
+    FrameUniforms, InitContext, InputState, RenderContext, Renderer, Scene, WgpuAppError, binding,
+    rebuild_pipeline_with_defines, shader,
+};
+use std::{collections::HashMap, path::Path, time::Duration};
+
+/// 旋转三角形使用的着色器文件
+const SHADER: &str = "rotating_triangle.wgsl";
+
+/// 旋转速度（弧度/秒）
+const ROTATION_SPEED: f32 = 1.0;
+
+/// 每滚动一行三角形缩放的倍数
+const SCROLL_ZOOM: f32 = 1.1;
+
+/// 手柄扳机按到底时每秒相当于滚动的行数
+const TRIGGER_ZOOM_SPEED: f32 = 10.0;
+
+/// 三角形的缩放范围
+const SCALE_RANGE: (f32, f32) = (0.2, 3.0);
+
+/// 与着色器中SceneUniform对应的数据（按16字节对齐）
+#[repr(C)]
+#[derive(Debug, Clone, Copy, bytemuck::Pod, bytemuck::Zeroable)]
+struct SceneUniform {
+    angle: f32,
+    time: f32,
+    scale: f32,
+    _padding: f32,
+}
+
+/// 随时间旋转并变换顶点颜色的三角形，用于验证update/input等场景回调
+///
+/// 滚动鼠标滚轮可以缩放三角形。
+pub struct RotatingTriangle {
+    pipeline: wgpu::RenderPipeline,
+    pipeline_layout: wgpu::PipelineLayout,
+    sample_count: u32,
+    cache: Option<wgpu::PipelineCache>,
+    uniforms: FrameUniforms<SceneUniform>, // 按帧轮换的SceneUniform
+    uniform: SceneUniform,
+}
+
+impl Renderer for RotatingTriangle {
+    fn init(ctx: &InitContext) -> Result<Self, WgpuAppError> {
+        let InitContext {
+            device,
+            config,
+            sample_count,
+            cache,
+            ..
+        } = *ctx;
+        // 1. 创建按帧轮换的uniform（每帧写入本帧的槽位），顶点和片元着色器共用
+        let uniforms = FrameUniforms::new(
+            device,
+            "Rotating Triangle",
+            wgpu::ShaderStages::VERTEX_FRAGMENT,
+        );
+
+        // 2. 创建管线布局
+        let pipeline_layout = binding::pipeline_layout(
+            device,
+            Some("Rotating Triangle Pipeline Layout"),
+            &[uniforms.layout()],
+        );
+
+        // 3. 加载着色器并创建管线
+        let shader = shader::load_with_defines(device, SHADER, &defines())?;
+        let pipeline = create_pipeline(
+            device,
+            &shader,
+            config.format,
+            &pipeline_layout,
+            sample_count,
+            cache,
+        );
+
+        Ok(Self {
+            pipeline,
+            pipeline_layout,
+            sample_count,
+            cache: cache.cloned(),
+            uniforms,
+            uniform: SceneUniform {
+                scale: 1.0,
+                ..bytemuck::Zeroable::zeroed()
+            },
+        })
+    }
+
+    fn render(
+        &mut self,
+        ctx: &RenderContext,
+        view: &wgpu::TextureView,
+        encoder: &mut wgpu::CommandEncoder,
+    ) {
+        // 旋转角度和颜色都由场景时间决定，暂停时画面保持不动
+        self.uniform.time = ctx.time;
+        self.uniform.angle = (ROTATION_SPEED * ctx.time) % std::f32::consts::TAU;
+        let offset = self
+            .uniforms
+            .write(ctx.queue, ctx.frame_index, &self.uniform);
+
+        let mut pass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
+            label: Some("Rotating Triangle Pass"),
+            color_attachments: &[Some(ctx.color_attachment(
+                view,
+                wgpu::Operations {
+                    load: wgpu::LoadOp::Clear(ctx.clear_color),
+                    store: wgpu::StoreOp::Store,
+                },
+            ))],
+            depth_stencil_attachment: None,
+            timestamp_writes: ctx.timestamp_writes(),
+            occlusion_query_set: None,
+        });
+        pass.set_pipeline(&self.pipeline);
+        pass.set_bind_group(0, self.uniforms.bind_group(), &[offset]);
+        pass.draw(0..3, 0..1);
+    }
+
+    fn shader_changed(
+        &mut self,
+        device: &wgpu::Device,
+        config: &wgpu::SurfaceConfiguration,
+        path: &Path,
+    ) {
+        if !shader::depends_on(SHADER, path) {
+            return;
+        }
+        // 管线布局是显式创建的，绑定组无需随管线重建
+        let _ = self.rebuild_pipeline(device, config.format, self.sample_count);
+    }
+
+    fn sample_count_changed(
+        &mut self,
+        device: &wgpu::Device,
+        config: &wgpu::SurfaceConfiguration,
+        sample_count: u32,
+    ) -> Result<(), WgpuAppError> {
+        self.rebuild_pipeline(device, config.format, sample_count)
+    }
+}
+
+impl RotatingTriangle {
+    /// 用`rebuild_pipeline_with_defines`按sample_count重建管线，成功后才更新多重采样数
+    fn rebuild_pipeline(
+        &mut self,
+        device: &wgpu::Device,
+        format: wgpu::TextureFormat,
+        sample_count: u32,
+    ) -> Result<(), WgpuAppError> {
+        rebuild_pipeline_with_defines(device, SHADER, &defines(), &mut self.pipeline, |shader| {
+            create_pipeline(
+                device,
+                shader,
+                format,
+                &self.pipeline_layout,
+                sample_count,
+                self.cache.as_ref(),
+            )
+        })?;
+        self.sample_count = sample_count;
+        Ok(())
+    }
+}
+
+impl Scene for RotatingTriangle {
+    fn update(&mut self, dt: Duration, _queue: &wgpu::Queue, input: &InputState) {
+        // 滚轮和手柄扳机都可以缩放
+        let (_, scroll) = input.scroll_delta();
+        let scroll = scroll + input.gamepad().zoom * TRIGGER_ZOOM_SPEED * dt.as_secs_f32();
+        self.uniform.scale =
+            (self.uniform.scale * SCROLL_ZOOM.powf(scroll)).clamp(SCALE_RANGE.0, SCALE_RANGE.1);
+    }
+}
+
+/// 传给着色器预处理器的常量（覆盖着色器中的默认值）
+fn defines() -> HashMap<String, String> {
+    HashMap::from([("COLOR_SPEED".to_string(), "2.0".to_string())])
+}
+
+/// 创建渲染管线
+fn create_pipeline(
+    device: &wgpu::Device,
+    shader: &wgpu::ShaderModule,
+    format: wgpu::TextureFormat,
+    layout: &wgpu::PipelineLayout,
+    sample_count: u32,
+    cache: Option<&wgpu::PipelineCache>,
+) -> wgpu::RenderPipeline {
+    device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
+        label: Some("Rotating Triangle Pipeline"),
+        layout: Some(layout),
+        vertex: wgpu::VertexState {
+            module: shader,
+            entry_point: Some("vs"),
+            buffers: &[],
+            compilation_options: Default::default(),
+        },
+        fragment: Some(wgpu::FragmentState {
+            module: shader,
+            entry_point: Some("fs"),
+            targets: &[Some(wgpu::ColorTargetState {
+                format,
+                blend: Some(wgpu::BlendState::REPLACE),
+                write_mask: wgpu::ColorWrites::ALL,
+            })],
+            compilation_options: Default::default(),
+        }),
+        primitive: Default::default(),
+        depth_stencil: None,
+        multisample: wgpu::MultisampleState {
+            count: sample_count,
+            ..Default::default()
+        },
+        multiview: None,
+        cache,
+    })
+}
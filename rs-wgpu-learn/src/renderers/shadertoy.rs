@@ -0,0 +1,215 @@
+use crate::{
+    GLOBALS_GROUP, Globals, InitContext, RenderContext, Renderer, Scene, WgpuAppError, binding,
+    rebuild_or_keep, shader,
+};
+use log::{error, info};
+use std::path::{Path, PathBuf};
+
+/// Shadertoy演示使用的着色器文件
+const SHADER: &str = "shadertoy.wgsl";
+
+/// Shadertoy风格的演示：全屏三角形，片元着色器只依赖全局uniform（见`Globals`）
+///
+/// 画面随暂停和慢放一起停止或变速，按住左键时在光标处画一个圆环。拖放一个`.wgsl`文件到窗口上可以替换片元着色器
+/// （文件需`#include "globals.wgsl"`并提供`fs`入口，只能读取`@builtin(position)`）。
+pub struct Shadertoy {
+    pipeline: wgpu::RenderPipeline,
+    pipeline_layout: wgpu::PipelineLayout, // 只有第0组：全局uniform
+    format: wgpu::TextureFormat,           // 颜色附件格式（拖放着色器时重建管线使用）
+    sample_count: u32,
+    cache: Option<wgpu::PipelineCache>,
+    fragment: Option<PathBuf>, // 拖放进来的片元着色器（None时使用SHADER中的fs）
+}
+
+impl Renderer for Shadertoy {
+    fn init(ctx: &InitContext) -> Result<Self, WgpuAppError> {
+        let InitContext {
+            device,
+            config,
+            sample_count,
+            cache,
+            ..
+        } = *ctx;
+        // 1. 管线布局的第0组为应用维护的全局uniform
+        let pipeline_layout = binding::pipeline_layout(
+            device,
+            Some("Shadertoy Pipeline Layout"),
+            &[&Globals::create_layout(device)],
+        );
+
+        // 2. 加载着色器并创建管线
+        let shader = shader::load(device, SHADER)?;
+        let pipeline = create_pipeline(
+            device,
+            &shader,
+            &shader,
+            config.format,
+            &pipeline_layout,
+            sample_count,
+            cache,
+        );
+
+        Ok(Self {
+            pipeline,
+            pipeline_layout,
+            format: config.format,
+            sample_count,
+            cache: cache.cloned(),
+            fragment: None,
+        })
+    }
+
+    fn render(
+        &mut self,
+        ctx: &RenderContext,
+        view: &wgpu::TextureView,
+        encoder: &mut wgpu::CommandEncoder,
+    ) {
+        let mut pass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
+            label: Some("Shadertoy Pass"),
+            color_attachments: &[Some(ctx.color_attachment(
+                view,
+                wgpu::Operations {
+                    load: wgpu::LoadOp::Clear(ctx.clear_color),
+                    store: wgpu::StoreOp::Store,
+                },
+            ))],
+            depth_stencil_attachment: None,
+            timestamp_writes: ctx.timestamp_writes(),
+            occlusion_query_set: None,
+        });
+        pass.set_pipeline(&self.pipeline);
+        ctx.globals.bind(&mut pass, GLOBALS_GROUP);
+        pass.draw(0..3, 0..1);
+    }
+
+    fn shader_changed(
+        &mut self,
+        device: &wgpu::Device,
+        config: &wgpu::SurfaceConfiguration,
+        path: &Path,
+    ) {
+        let fragment_changed = self
+            .fragment
+            .as_ref()
+            .is_some_and(|fragment| shader::depends_on(fragment, path));
+        if !shader::depends_on(SHADER, path) && !fragment_changed {
+            return;
+        }
+        let _ = self.rebuild_pipeline(device, config.format, self.sample_count);
+    }
+
+    fn sample_count_changed(
+        &mut self,
+        device: &wgpu::Device,
+        config: &wgpu::SurfaceConfiguration,
+        sample_count: u32,
+    ) -> Result<(), WgpuAppError> {
+        self.rebuild_pipeline(device, config.format, sample_count)
+    }
+}
+
+impl Shadertoy {
+    /// 加载着色器并按sample_count创建管线，fragment为替换用的片元着色器
+    fn build_pipeline(
+        &self,
+        device: &wgpu::Device,
+        format: wgpu::TextureFormat,
+        fragment: Option<&Path>,
+        sample_count: u32,
+    ) -> Result<wgpu::RenderPipeline, WgpuAppError> {
+        let shader = shader::load(device, SHADER)?;
+        let fragment = fragment
+            .map(|path| shader::load(device, path))
+            .transpose()?;
+        shader::catch_validation(device, || {
+            create_pipeline(
+                device,
+                &shader,
+                fragment.as_ref().unwrap_or(&shader),
+                format,
+                &self.pipeline_layout,
+                sample_count,
+                self.cache.as_ref(),
+            )
+        })
+        .map_err(|e| WgpuAppError::Validation(e.to_string()))
+    }
+
+    /// 按sample_count和当前的片元着色器重建管线，成功后才更新多重采样数
+    fn rebuild_pipeline(
+        &mut self,
+        device: &wgpu::Device,
+        format: wgpu::TextureFormat,
+        sample_count: u32,
+    ) -> Result<(), WgpuAppError> {
+        let rebuilt = self.build_pipeline(device, format, self.fragment.as_deref(), sample_count);
+        rebuild_or_keep(&mut self.pipeline, SHADER, rebuilt)?;
+        self.sample_count = sample_count;
+        Ok(())
+    }
+}
+
+impl Scene for Shadertoy {
+    /// 拖放`.wgsl`文件时用它替换片元着色器，验证失败时保留原管线
+    fn file_dropped(&mut self, path: &Path, device: &wgpu::Device, _queue: &wgpu::Queue) -> bool {
+        if path
+            .extension()
+            .is_none_or(|ext| !ext.eq_ignore_ascii_case("wgsl"))
+        {
+            return false;
+        }
+        match self.build_pipeline(device, self.format, Some(path), self.sample_count) {
+            Ok(pipeline) => {
+                self.pipeline = pipeline;
+                self.fragment = Some(path.to_path_buf());
+                info!("Using fragment shader from {}", path.display());
+            }
+            Err(e) => error!(
+                "Failed to use fragment shader {}, keeping the previous one: {e}",
+                path.display()
+            ),
+        }
+        true
+    }
+}
+
+/// 创建全屏三角形的渲染管线
+fn create_pipeline(
+    device: &wgpu::Device,
+    shader: &wgpu::ShaderModule,
+    fragment: &wgpu::ShaderModule,
+    format: wgpu::TextureFormat,
+    layout: &wgpu::PipelineLayout,
+    sample_count: u32,
+    cache: Option<&wgpu::PipelineCache>,
+) -> wgpu::RenderPipeline {
+    device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
+        label: Some("Shadertoy Pipeline"),
+        layout: Some(layout),
+        vertex: wgpu::VertexState {
+            module: shader,
+            entry_point: Some("vs"),
+            buffers: &[],
+            compilation_options: Default::default(),
+        },
+        fragment: Some(wgpu::FragmentState {
+            module: fragment,
+            entry_point: Some("fs"),
+            targets: &[Some(wgpu::ColorTargetState {
+                format,
+                blend: Some(wgpu::BlendState::REPLACE),
+                write_mask: wgpu::ColorWrites::ALL,
+            })],
+            compilation_options: Default::default(),
+        }),
+        primitive: Default::default(),
+        depth_stencil: None,
+        multisample: wgpu::MultisampleState {
+            count: sample_count,
+            ..Default::default()
+        },
+        multiview: None,
+        cache,
+    })
+}
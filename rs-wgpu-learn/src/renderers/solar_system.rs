@@ -0,0 +1,257 @@
+use super::instanced_cubes::{SHADER, create_pipeline};
+use crate::{
+    InitContext, InputState, Instance, InstanceBuffer, Mesh, RenderContext, Renderer, Scene,
+    WgpuAppError, binding,
+    camera::{Camera, CameraBuffer, CameraController, OrbitController, aspect_ratio},
+    math::Transform,
+    rebuild_pipeline,
+    scene::{Node, NodeId, SceneGraph},
+    shader, shapes,
+};
+use glam::{Quat, Vec3};
+use log::info;
+use std::{path::Path, time::Duration};
+use winit::keyboard::KeyCode;
+
+/// 节点使用的网格句柄（只有一个球体网格）
+const SPHERE: usize = 0;
+
+/// 材质句柄对应的颜色（线性空间）：太阳、行星、卫星
+const COLORS: [[f32; 4]; 3] = [
+    [1.0, 0.7, 0.1, 1.0],
+    [0.2, 0.4, 1.0, 1.0],
+    [0.7, 0.7, 0.7, 1.0],
+];
+
+/// 太阳、行星、卫星的自转速度（弧度/秒），子节点随父节点的自转公转
+const SPIN_SPEEDS: [f32; 3] = [0.3, 1.5, 2.0];
+
+/// 太阳系：太阳、行星、卫星三个嵌套的节点，每个节点只绕自身的Y轴自转
+///
+/// 行星是太阳的子节点，太阳自转时行星随之公转；卫星是行星的子节点，同样随行星公转。
+/// 行星的缩放也会作用到卫星的位置和大小上。每帧在`update`中修改本地变换并更新场景图的世界矩阵，
+/// 渲染时由世界矩阵生成实例数据，所有星体用一次实例化绘制完成（与实例化示例共用着色器）。
+/// 鼠标控制相机（C键切换轨道和飞行控制）。
+pub struct SolarSystem {
+    pipeline: wgpu::RenderPipeline,
+    pipeline_layout: wgpu::PipelineLayout,
+    sample_count: u32,
+    cache: Option<wgpu::PipelineCache>,
+    camera: Camera,                     // 从斜上方看向太阳的相机
+    controller: CameraController,       // 轨道或飞行控制器（C键切换）
+    camera_buffer: CameraBuffer,        // 第0组：相机的视图投影矩阵
+    config: wgpu::SurfaceConfiguration, // 表面配置（宽高比和鼠标灵敏度，尺寸变化时更新）
+    graph: SceneGraph,                  // 太阳、行星、卫星的节点层级
+    bodies: [NodeId; 3],                // 太阳、行星、卫星的节点
+    mesh: Mesh,                         // 球体网格，槽位1挂着实例缓冲区
+    instance_buffer: InstanceBuffer,    // 每个带网格的节点一个实例，每帧由世界矩阵生成
+}
+
+impl Renderer for SolarSystem {
+    fn init(ctx: &InitContext) -> Result<Self, WgpuAppError> {
+        let InitContext {
+            device,
+            config,
+            sample_count,
+            cache,
+            ..
+        } = *ctx;
+        // 1. 创建相机缓冲区和管线布局（与实例化示例共用着色器）
+        let camera = Camera {
+            eye: Vec3::new(0.0, 6.0, 12.0),
+            ..Default::default()
+        };
+        let mut orbit = OrbitController::from_camera(&camera);
+        orbit.min_distance = 2.0;
+        orbit.max_distance = 40.0;
+        let mut camera_buffer = CameraBuffer::new(device);
+        camera_buffer.update(&camera, aspect_ratio(config));
+        let pipeline_layout = binding::pipeline_layout(
+            device,
+            Some("Solar System Pipeline Layout"),
+            &[camera_buffer.layout()],
+        );
+
+        // 2. 加载着色器并创建管线
+        let shader = shader::load(device, SHADER)?;
+        let pipeline = create_pipeline(
+            device,
+            &shader,
+            config.format,
+            &pipeline_layout,
+            sample_count,
+            cache,
+        );
+
+        // 3. 创建节点层级：行星在太阳的X轴方向，卫星在行星的X轴方向
+        let mut graph = SceneGraph::new();
+        let sun = graph.add(
+            Node::new("Sun", Transform::IDENTITY)
+                .with_mesh(SPHERE)
+                .with_material(0),
+            None,
+        );
+        let planet = graph.add(
+            Node::new(
+                "Planet",
+                Transform {
+                    translation: Vec3::new(4.0, 0.0, 0.0),
+                    scale: Vec3::splat(0.4),
+                    ..Transform::IDENTITY
+                },
+            )
+            .with_mesh(SPHERE)
+            .with_material(1),
+            Some(sun),
+        );
+        // 本地平移和缩放都在行星的空间中：世界空间中距行星1.0，半径0.16
+        let moon = graph.add(
+            Node::new(
+                "Moon",
+                Transform {
+                    translation: Vec3::new(2.5, 0.0, 0.0),
+                    scale: Vec3::splat(0.4),
+                    ..Transform::IDENTITY
+                },
+            )
+            .with_mesh(SPHERE)
+            .with_material(2),
+            Some(planet),
+        );
+        graph.update_world_transforms();
+
+        // 4. 创建球体网格和实例缓冲区，实例缓冲区挂到网格的槽位1
+        let (vertices, indices) = shapes::uv_sphere(1.0, 16, 32);
+        let mut mesh = Mesh::new(device, "Solar System Sphere", &vertices, Some(&indices));
+        let instance_buffer = InstanceBuffer::new(device, "Solar System", &body_instances(&graph));
+        mesh.add_vertex_buffer(instance_buffer.buffer().clone());
+
+        Ok(Self {
+            pipeline,
+            pipeline_layout,
+            sample_count,
+            cache: cache.cloned(),
+            camera,
+            controller: CameraController::new(orbit).with_bookmarks("solar-system"),
+            camera_buffer,
+            config: config.clone(),
+            graph,
+            bodies: [sun, planet, moon],
+            mesh,
+            instance_buffer,
+        })
+    }
+
+    fn render(
+        &mut self,
+        ctx: &RenderContext,
+        view: &wgpu::TextureView,
+        encoder: &mut wgpu::CommandEncoder,
+    ) {
+        // 由本帧的世界矩阵生成实例数据
+        self.instance_buffer.upload(
+            &mut ctx.uploader.borrow_mut(),
+            encoder,
+            0,
+            &body_instances(&self.graph),
+        );
+
+        let mut pass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
+            label: Some("Solar System Pass"),
+            color_attachments: &[Some(ctx.color_attachment(
+                view,
+                wgpu::Operations {
+                    load: wgpu::LoadOp::Clear(ctx.clear_color),
+                    store: wgpu::StoreOp::Store,
+                },
+            ))],
+            depth_stencil_attachment: ctx.depth_attachment(),
+            timestamp_writes: ctx.timestamp_writes(),
+            occlusion_query_set: None,
+        });
+        pass.set_pipeline(&self.pipeline);
+        self.camera_buffer.bind(ctx, &mut pass, 0);
+        self.mesh.draw(&mut pass, self.instance_buffer.range());
+    }
+
+    fn shader_changed(
+        &mut self,
+        device: &wgpu::Device,
+        config: &wgpu::SurfaceConfiguration,
+        path: &Path,
+    ) {
+        if !shader::depends_on(SHADER, path) {
+            return;
+        }
+        let _ = self.rebuild_pipeline(device, config.format, self.sample_count);
+    }
+
+    fn resize(&mut self, config: &wgpu::SurfaceConfiguration) {
+        self.config = config.clone();
+    }
+
+    fn sample_count_changed(
+        &mut self,
+        device: &wgpu::Device,
+        config: &wgpu::SurfaceConfiguration,
+        sample_count: u32,
+    ) -> Result<(), WgpuAppError> {
+        self.rebuild_pipeline(device, config.format, sample_count)
+    }
+}
+
+impl Scene for SolarSystem {
+    /// 按dt转动每个星体的本地变换并更新世界矩阵，更新相机（C键切换轨道和飞行控制）
+    fn update(&mut self, dt: Duration, _queue: &wgpu::Queue, input: &InputState) {
+        for (&body, speed) in self.bodies.iter().zip(SPIN_SPEEDS) {
+            let local = &mut self.graph.node_mut(body).local;
+            local.rotation =
+                (Quat::from_rotation_y(speed * dt.as_secs_f32()) * local.rotation).normalize();
+        }
+        self.graph.update_world_transforms();
+
+        if input.just_pressed(KeyCode::KeyC) {
+            self.controller.toggle(&self.camera);
+            info!("Camera controller: {}", self.controller.name());
+        }
+        self.controller
+            .update(input, dt, self.config.height, &mut self.camera);
+        self.camera_buffer
+            .update(&self.camera, aspect_ratio(&self.config));
+    }
+}
+
+impl SolarSystem {
+    /// 用`rebuild_pipeline`按sample_count重建管线，成功后才更新多重采样数
+    fn rebuild_pipeline(
+        &mut self,
+        device: &wgpu::Device,
+        format: wgpu::TextureFormat,
+        sample_count: u32,
+    ) -> Result<(), WgpuAppError> {
+        rebuild_pipeline(device, SHADER, &mut self.pipeline, |shader| {
+            create_pipeline(
+                device,
+                shader,
+                format,
+                &self.pipeline_layout,
+                sample_count,
+                self.cache.as_ref(),
+            )
+        })?;
+        self.sample_count = sample_count;
+        Ok(())
+    }
+}
+
+/// 每个带网格的节点一个实例：模型矩阵为节点的世界矩阵，颜色由材质句柄决定
+fn body_instances(graph: &SceneGraph) -> Vec<Instance> {
+    graph
+        .iter()
+        .filter(|(_, node)| node.mesh == Some(SPHERE))
+        .map(|(id, node)| Instance {
+            model: graph.world_transform(id).to_cols_array_2d(),
+            color: COLORS[node.material.unwrap_or_default()],
+        })
+        .collect()
+}
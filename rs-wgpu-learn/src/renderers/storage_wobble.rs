@@ -0,0 +1,365 @@
+use crate::{
+    DepthTexture, InitContext, InputState, Mesh, MeshVertex, RenderContext, Renderer, Scene,
+    WgpuAppError,
+    binding::{self, BindGroupBuilder, BindGroupLayoutBuilder, BindingLayout},
+    rebuild_pipeline, shader, shapes,
+};
+use log::info;
+use std::{path::Path, time::Duration};
+use winit::keyboard::KeyCode;
+
+/// 渲染部分的着色器文件
+const SHADER: &str = "storage_wobble.wgsl";
+
+/// 计算部分的着色器文件
+const COMPUTE_SHADER: &str = "storage_wobble_compute.wgsl";
+
+/// 可选的网格分辨率（每边顶点数），+/-键切换
+const RESOLUTIONS: [u32; 6] = [17, 33, 65, 129, 257, 513];
+
+/// 默认使用的分辨率在RESOLUTIONS中的下标
+const DEFAULT_LEVEL: usize = 3;
+
+/// 计算着色器的工作组大小（与着色器中的@workgroup_size一致）
+const WORKGROUP_SIZE: u32 = 64;
+
+/// 与着色器中WobbleUniform对应的数据（按16字节对齐）
+#[repr(C)]
+#[derive(Debug, Clone, Copy, bytemuck::Pod, bytemuck::Zeroable)]
+struct WobbleUniform {
+    time: f32,
+    resolution: u32,
+    aspect: f32,
+    camera_distance: f32,
+}
+
+/// 与分辨率相关的资源：网格、高度缓冲区和引用它的两个绑定组，分辨率变化时一起重建
+struct Grid {
+    resolution: u32,                     // 每边顶点数
+    mesh: Mesh,                          // 平面网格
+    heights: wgpu::Buffer,               // 每个顶点一个f32高度（STORAGE）
+    compute_bind_group: wgpu::BindGroup, // 以read_write绑定高度缓冲区
+    render_bind_group: wgpu::BindGroup,  // 以read绑定同一个高度缓冲区
+}
+
+/// 存储缓冲区：计算着色器每帧把顶点高度写入存储缓冲区，顶点着色器读取同一个缓冲区让网格起伏
+///
+/// 按+/-键改变网格分辨率，高度缓冲区的大小随之变化，缓冲区和绑定组一起重建。
+/// 设备不支持存储缓冲区（如WebGL2）时创建失败并返回`WgpuAppError::UnsupportedLimits`。
+pub struct StorageWobble {
+    render_pipeline: wgpu::RenderPipeline,
+    compute_pipeline: wgpu::ComputePipeline,
+    render_layout: wgpu::PipelineLayout,
+    compute_layout: wgpu::PipelineLayout,
+    render_bind_layout: BindingLayout,
+    compute_bind_layout: BindingLayout,
+    sample_count: u32,
+    cache: Option<wgpu::PipelineCache>,
+    uniform_buffer: wgpu::Buffer,
+    grid: Grid,   // 当前分辨率的资源
+    level: usize, // 请求的分辨率下标（与grid不同时在下一次渲染前重建）
+}
+
+impl Renderer for StorageWobble {
+    fn init(ctx: &InitContext) -> Result<Self, WgpuAppError> {
+        let InitContext {
+            device,
+            config,
+            sample_count,
+            cache,
+            ..
+        } = *ctx;
+        // 1. 创建uniform缓冲区和两个绑定组布局，创建管线前检查存储缓冲区数量是否超出设备限制
+        let uniform_buffer = device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some("Storage Wobble Uniform Buffer"),
+            size: std::mem::size_of::<WobbleUniform>() as u64,
+            usage: wgpu::BufferUsages::UNIFORM | wgpu::BufferUsages::COPY_DST,
+            mapped_at_creation: false,
+        });
+        let compute_bind_layout = BindGroupLayoutBuilder::new()
+            .label("Storage Wobble Compute Bind Group Layout")
+            .uniform(wgpu::ShaderStages::COMPUTE)
+            .storage(wgpu::ShaderStages::COMPUTE, false)
+            .try_build(device)?;
+        let render_bind_layout = BindGroupLayoutBuilder::new()
+            .label("Storage Wobble Render Bind Group Layout")
+            .uniform(wgpu::ShaderStages::VERTEX)
+            .storage(wgpu::ShaderStages::VERTEX, true)
+            .try_build(device)?;
+        let compute_layout = binding::pipeline_layout(
+            device,
+            Some("Storage Wobble Compute Pipeline Layout"),
+            &[&compute_bind_layout],
+        );
+        let render_layout = binding::pipeline_layout(
+            device,
+            Some("Storage Wobble Render Pipeline Layout"),
+            &[&render_bind_layout],
+        );
+
+        // 2. 加载着色器并创建计算管线和渲染管线
+        let compute_shader = shader::load(device, COMPUTE_SHADER)?;
+        let compute_pipeline =
+            create_compute_pipeline(device, &compute_shader, &compute_layout, cache);
+        let shader = shader::load(device, SHADER)?;
+        let render_pipeline = create_render_pipeline(
+            device,
+            &shader,
+            config.format,
+            &render_layout,
+            sample_count,
+            cache,
+        );
+
+        // 3. 创建默认分辨率的网格和高度缓冲区
+        let grid = Grid::new(
+            device,
+            RESOLUTIONS[DEFAULT_LEVEL],
+            &uniform_buffer,
+            &compute_bind_layout,
+            &render_bind_layout,
+        );
+
+        Ok(Self {
+            render_pipeline,
+            compute_pipeline,
+            render_layout,
+            compute_layout,
+            render_bind_layout,
+            compute_bind_layout,
+            sample_count,
+            cache: cache.cloned(),
+            uniform_buffer,
+            grid,
+            level: DEFAULT_LEVEL,
+        })
+    }
+
+    fn render(
+        &mut self,
+        ctx: &RenderContext,
+        view: &wgpu::TextureView,
+        encoder: &mut wgpu::CommandEncoder,
+    ) {
+        // 分辨率变化后重建网格、高度缓冲区和绑定组
+        let resolution = RESOLUTIONS[self.level];
+        if self.grid.resolution != resolution {
+            self.grid = Grid::new(
+                ctx.device,
+                resolution,
+                &self.uniform_buffer,
+                &self.compute_bind_layout,
+                &self.render_bind_layout,
+            );
+            info!(
+                "Grid resolution {resolution}x{resolution}, height buffer {} KiB",
+                self.grid.heights.size() / 1024
+            );
+        }
+
+        let uniform = WobbleUniform {
+            time: ctx.time,
+            resolution,
+            aspect: ctx.config.width.max(1) as f32 / ctx.config.height.max(1) as f32,
+            camera_distance: 3.2,
+        };
+        ctx.upload(encoder, &self.uniform_buffer, 0, &uniform);
+
+        // 1. 计算通道：每个顶点一个线程，写入高度
+        {
+            let mut pass = encoder.begin_compute_pass(&wgpu::ComputePassDescriptor {
+                label: Some("Storage Wobble Compute Pass"),
+                timestamp_writes: None,
+            });
+            pass.set_pipeline(&self.compute_pipeline);
+            pass.set_bind_group(0, &self.grid.compute_bind_group, &[]);
+            let vertex_count = resolution * resolution;
+            pass.dispatch_workgroups(vertex_count.div_ceil(WORKGROUP_SIZE), 1, 1);
+        }
+
+        // 2. 渲染通道：顶点着色器读取同一个缓冲区中的高度
+        let mut pass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
+            label: Some("Storage Wobble Render Pass"),
+            color_attachments: &[Some(ctx.color_attachment(
+                view,
+                wgpu::Operations {
+                    load: wgpu::LoadOp::Clear(ctx.clear_color),
+                    store: wgpu::StoreOp::Store,
+                },
+            ))],
+            depth_stencil_attachment: ctx.depth_attachment(),
+            timestamp_writes: ctx.timestamp_writes(),
+            occlusion_query_set: None,
+        });
+        pass.set_pipeline(&self.render_pipeline);
+        pass.set_bind_group(0, &self.grid.render_bind_group, &[]);
+        self.grid.mesh.draw(&mut pass, 0..1);
+    }
+
+    fn shader_changed(
+        &mut self,
+        device: &wgpu::Device,
+        config: &wgpu::SurfaceConfiguration,
+        path: &Path,
+    ) {
+        if shader::depends_on(COMPUTE_SHADER, path) {
+            let _ = self.rebuild_compute_pipeline(device);
+        }
+        if shader::depends_on(SHADER, path) {
+            let _ = self.rebuild_render_pipeline(device, config.format, self.sample_count);
+        }
+    }
+
+    fn sample_count_changed(
+        &mut self,
+        device: &wgpu::Device,
+        config: &wgpu::SurfaceConfiguration,
+        sample_count: u32,
+    ) -> Result<(), WgpuAppError> {
+        self.rebuild_render_pipeline(device, config.format, sample_count)
+    }
+}
+
+impl Scene for StorageWobble {
+    fn update(&mut self, _dt: Duration, _queue: &wgpu::Queue, input: &InputState) {
+        if input.just_pressed(KeyCode::Equal) || input.just_pressed(KeyCode::NumpadAdd) {
+            self.level = (self.level + 1).min(RESOLUTIONS.len() - 1);
+        }
+        if input.just_pressed(KeyCode::Minus) || input.just_pressed(KeyCode::NumpadSubtract) {
+            self.level = self.level.saturating_sub(1);
+        }
+    }
+
+    fn status(&self) -> Option<String> {
+        let resolution = self.grid.resolution;
+        Some(format!("{resolution}x{resolution} grid"))
+    }
+}
+
+impl StorageWobble {
+    /// 重新加载计算着色器并重建计算管线（见`rebuild_pipeline`），失败时保留旧管线
+    fn rebuild_compute_pipeline(&mut self, device: &wgpu::Device) -> Result<(), WgpuAppError> {
+        rebuild_pipeline(
+            device,
+            COMPUTE_SHADER,
+            &mut self.compute_pipeline,
+            |shader| {
+                create_compute_pipeline(device, shader, &self.compute_layout, self.cache.as_ref())
+            },
+        )
+    }
+
+    /// 用`rebuild_pipeline`按sample_count重建渲染管线，成功后才更新多重采样数
+    fn rebuild_render_pipeline(
+        &mut self,
+        device: &wgpu::Device,
+        format: wgpu::TextureFormat,
+        sample_count: u32,
+    ) -> Result<(), WgpuAppError> {
+        rebuild_pipeline(device, SHADER, &mut self.render_pipeline, |shader| {
+            create_render_pipeline(
+                device,
+                shader,
+                format,
+                &self.render_layout,
+                sample_count,
+                self.cache.as_ref(),
+            )
+        })?;
+        self.sample_count = sample_count;
+        Ok(())
+    }
+}
+
+impl Grid {
+    /// 创建每边resolution个顶点的平面网格、对应大小的高度缓冲区和两个绑定组
+    fn new(
+        device: &wgpu::Device,
+        resolution: u32,
+        uniform_buffer: &wgpu::Buffer,
+        compute_layout: &BindingLayout,
+        render_layout: &BindingLayout,
+    ) -> Self {
+        let (vertices, indices) = shapes::plane(2.0, resolution - 1);
+        let mesh = Mesh::new(device, "Storage Wobble Grid", &vertices, Some(&indices));
+        let heights = device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some("Storage Wobble Height Buffer"),
+            size: (resolution * resolution) as u64 * std::mem::size_of::<f32>() as u64,
+            usage: wgpu::BufferUsages::STORAGE,
+            mapped_at_creation: false,
+        });
+        let compute_bind_group = BindGroupBuilder::new(compute_layout)
+            .label("Storage Wobble Compute Bind Group")
+            .buffer(uniform_buffer)
+            .buffer(&heights)
+            .build(device);
+        let render_bind_group = BindGroupBuilder::new(render_layout)
+            .label("Storage Wobble Render Bind Group")
+            .buffer(uniform_buffer)
+            .buffer(&heights)
+            .build(device);
+        Self {
+            resolution,
+            mesh,
+            heights,
+            compute_bind_group,
+            render_bind_group,
+        }
+    }
+}
+
+/// 创建写入高度的计算管线
+fn create_compute_pipeline(
+    device: &wgpu::Device,
+    shader: &wgpu::ShaderModule,
+    layout: &wgpu::PipelineLayout,
+    cache: Option<&wgpu::PipelineCache>,
+) -> wgpu::ComputePipeline {
+    device.create_compute_pipeline(&wgpu::ComputePipelineDescriptor {
+        label: Some("Storage Wobble Compute Pipeline"),
+        layout: Some(layout),
+        module: shader,
+        entry_point: Some("cs"),
+        compilation_options: Default::default(),
+        cache,
+    })
+}
+
+/// 创建读取MeshVertex和高度缓冲区并开启深度测试的渲染管线
+fn create_render_pipeline(
+    device: &wgpu::Device,
+    shader: &wgpu::ShaderModule,
+    format: wgpu::TextureFormat,
+    layout: &wgpu::PipelineLayout,
+    sample_count: u32,
+    cache: Option<&wgpu::PipelineCache>,
+) -> wgpu::RenderPipeline {
+    device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
+        label: Some("Storage Wobble Render Pipeline"),
+        layout: Some(layout),
+        vertex: wgpu::VertexState {
+            module: shader,
+            entry_point: Some("vs"),
+            buffers: &[MeshVertex::LAYOUT],
+            compilation_options: Default::default(),
+        },
+        fragment: Some(wgpu::FragmentState {
+            module: shader,
+            entry_point: Some("fs"),
+            targets: &[Some(wgpu::ColorTargetState {
+                format,
+                blend: Some(wgpu::BlendState::REPLACE),
+                write_mask: wgpu::ColorWrites::ALL,
+            })],
+            compilation_options: Default::default(),
+        }),
+        primitive: wgpu::PrimitiveState::default(), // 平面两面都可见，不剔除
+        depth_stencil: Some(DepthTexture::depth_stencil_state()),
+        multisample: wgpu::MultisampleState {
+            count: sample_count,
+            ..Default::default()
+        },
+        multiview: None,
+        cache,
+    })
+}
@@ -0,0 +1,315 @@
+use super::dynamic_uniform_cubes::{CUBE_SIZE, GRID, ROTATION_SPEED, SPACING};
+use crate::{
+    ArrayTexture, ArrayTextureBuilder, DepthTexture, InitContext, InputState, InstanceBuffer,
+    LayeredInstance, Mesh, MeshVertex, RenderContext, Renderer, Scene, WgpuAppError, binding,
+    camera::{Camera, CameraBuffer, CameraController, OrbitController, aspect_ratio},
+    math::Transform,
+    rebuild_pipeline, shader, shapes,
+};
+use glam::{Quat, Vec3};
+use log::info;
+use std::{path::Path, time::Duration};
+use winit::keyboard::KeyCode;
+
+/// 数组纹理示例使用的着色器文件
+const SHADER: &str = "texture_array.wgsl";
+
+/// 数组纹理的层数：4种图案 × 4种颜色
+const LAYERS: u32 = 16;
+
+/// 每层贴图的边长（像素）
+const LAYER_SIZE: u32 = 64;
+
+/// 图案的颜色（sRGB）
+const PALETTE: [[u8; 3]; 4] = [[230, 80, 60], [70, 170, 90], [60, 120, 220], [240, 190, 60]];
+
+/// 动态偏移示例中的GRID³个立方体改用二维数组纹理：每个立方体使用16张贴图中的一张，
+/// 但所有贴图都在同一个`ArrayTexture`中，层号随实例数据（`LayeredInstance`）传入
+///
+/// 整个阵列只有一个纹理绑定组、一次实例化绘制，窗口标题中显示立方体数、层数、绑定组和绘制调用的数量。
+/// 按T键让所有立方体换用下一层贴图（只重写实例数据，绑定组不变），鼠标控制相机（C键切换轨道和飞行控制）。
+pub struct TextureArrayCubes {
+    pipeline: wgpu::RenderPipeline,
+    pipeline_layout: wgpu::PipelineLayout,
+    sample_count: u32,
+    cache: Option<wgpu::PipelineCache>,
+    camera: Camera,                      // 从斜上方看向立方体阵列中心的相机
+    controller: CameraController,        // 轨道或飞行控制器（C键切换）
+    camera_buffer: CameraBuffer,         // 第0组：相机的视图投影矩阵
+    config: wgpu::SurfaceConfiguration,  // 表面配置（宽高比和鼠标灵敏度，尺寸变化时更新）
+    textures: ArrayTexture,              // 16层贴图
+    texture_bind_group: wgpu::BindGroup, // 第1组：数组纹理和采样器
+    mesh: Mesh,                          // 立方体网格，槽位1挂着实例缓冲区
+    instance_buffer: InstanceBuffer<LayeredInstance>, // 所有立方体的实例数据
+    instances: Vec<LayeredInstance>,     // CPU上的实例数据，每帧更新后整体写入
+    layer_offset: u32,                   // 加到每个立方体层号上的偏移（T键递增）
+}
+
+impl Renderer for TextureArrayCubes {
+    fn init(ctx: &InitContext) -> Result<Self, WgpuAppError> {
+        let InitContext {
+            device,
+            queue,
+            config,
+            sample_count,
+            cache,
+            samplers,
+            ..
+        } = *ctx;
+        // 1. 生成16张尺寸相同的贴图，每张一层
+        let mut builder = ArrayTextureBuilder::new().label("Cube Textures");
+        for layer in 0..LAYERS {
+            builder.add_rgba(&format!("pattern-{layer}"), layer_image(layer));
+        }
+        let textures = builder.build(device, queue, samplers)?;
+        let texture_layout =
+            ArrayTexture::create_layout(device, "Cube Textures", wgpu::ShaderStages::FRAGMENT);
+        let texture_bind_group = textures.texture().bind_group(device, &texture_layout);
+
+        // 2. 创建相机缓冲区和管线布局（相机在第0组，数组纹理在第1组）
+        let extent = (GRID - 1) as f32 * SPACING;
+        let orbit = OrbitController::new(Vec3::ZERO, extent * 2.4, 0.6, 0.5);
+        let mut camera = Camera::default();
+        orbit.apply(&mut camera);
+        let mut camera_buffer = CameraBuffer::new(device);
+        camera_buffer.update(&camera, aspect_ratio(config));
+        let pipeline_layout = binding::pipeline_layout(
+            device,
+            Some("Texture Array Pipeline Layout"),
+            &[camera_buffer.layout(), &texture_layout],
+        );
+
+        // 3. 加载着色器并创建管线（顶点缓冲区 + 实例缓冲区）
+        let shader = shader::load(device, SHADER)?;
+        let pipeline = create_pipeline(
+            device,
+            &shader,
+            config.format,
+            &pipeline_layout,
+            sample_count,
+            cache,
+        );
+
+        // 4. 创建立方体网格和实例缓冲区，实例缓冲区挂到网格的槽位1
+        let (vertices, indices) = shapes::cube(CUBE_SIZE);
+        let mut mesh = Mesh::new(device, "Texture Array Cube", &vertices, Some(&indices));
+        let instances = (0..GRID * GRID * GRID)
+            .map(|i| grid_instance(i, 0.0, 0))
+            .collect::<Vec<_>>();
+        let instance_buffer = InstanceBuffer::new(device, "Texture Array Cubes", &instances);
+        mesh.add_vertex_buffer(instance_buffer.buffer().clone());
+        info!(
+            "{} cubes sample {} layers of one {LAYER_SIZE}x{LAYER_SIZE} texture array",
+            instances.len(),
+            textures.len()
+        );
+
+        Ok(Self {
+            pipeline,
+            pipeline_layout,
+            sample_count,
+            cache: cache.cloned(),
+            camera,
+            controller: CameraController::new(orbit).with_bookmarks("texture-array"),
+            camera_buffer,
+            config: config.clone(),
+            textures,
+            texture_bind_group,
+            mesh,
+            instance_buffer,
+            instances,
+            layer_offset: 0,
+        })
+    }
+
+    fn render(
+        &mut self,
+        ctx: &RenderContext,
+        view: &wgpu::TextureView,
+        encoder: &mut wgpu::CommandEncoder,
+    ) {
+        // 在CPU上更新所有立方体的旋转和层号，再整体写入实例缓冲区
+        let angle = (ROTATION_SPEED * ctx.time) % std::f32::consts::TAU;
+        for (i, instance) in self.instances.iter_mut().enumerate() {
+            *instance = grid_instance(i as u32, angle, self.layer_offset);
+        }
+        self.instance_buffer.update(ctx.queue, 0, &self.instances);
+
+        let mut pass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
+            label: Some("Texture Array Pass"),
+            color_attachments: &[Some(ctx.color_attachment(
+                view,
+                wgpu::Operations {
+                    load: wgpu::LoadOp::Clear(ctx.clear_color),
+                    store: wgpu::StoreOp::Store,
+                },
+            ))],
+            depth_stencil_attachment: ctx.depth_attachment(),
+            timestamp_writes: ctx.timestamp_writes(),
+            occlusion_query_set: None,
+        });
+        pass.set_pipeline(&self.pipeline);
+        self.camera_buffer.bind(ctx, &mut pass, 0);
+        // 一个绑定组包含所有贴图，一次draw_indexed绘制所有立方体
+        pass.set_bind_group(1, &self.texture_bind_group, &[]);
+        self.mesh.draw(&mut pass, self.instance_buffer.range());
+    }
+
+    fn shader_changed(
+        &mut self,
+        device: &wgpu::Device,
+        config: &wgpu::SurfaceConfiguration,
+        path: &Path,
+    ) {
+        if !shader::depends_on(SHADER, path) {
+            return;
+        }
+        let _ = self.rebuild_pipeline(device, config.format, self.sample_count);
+    }
+
+    fn resize(&mut self, config: &wgpu::SurfaceConfiguration) {
+        self.config = config.clone();
+    }
+
+    fn sample_count_changed(
+        &mut self,
+        device: &wgpu::Device,
+        config: &wgpu::SurfaceConfiguration,
+        sample_count: u32,
+    ) -> Result<(), WgpuAppError> {
+        self.rebuild_pipeline(device, config.format, sample_count)
+    }
+}
+
+impl Scene for TextureArrayCubes {
+    /// 更新相机（C键切换轨道和飞行控制），按T键让所有立方体换用下一层贴图
+    fn update(&mut self, dt: Duration, _queue: &wgpu::Queue, input: &InputState) {
+        if input.just_pressed(KeyCode::KeyT) {
+            self.layer_offset = (self.layer_offset + 1) % self.textures.len() as u32;
+            info!("Texture layer offset: {}", self.layer_offset);
+        }
+        if input.just_pressed(KeyCode::KeyC) {
+            self.controller.toggle(&self.camera);
+            info!("Camera controller: {}", self.controller.name());
+        }
+        self.controller
+            .update(input, dt, self.config.height, &mut self.camera);
+        self.camera_buffer
+            .update(&self.camera, aspect_ratio(&self.config));
+    }
+
+    /// 立方体数、贴图层数、纹理绑定组和绘制调用的数量，以及层号偏移
+    fn status(&self) -> Option<String> {
+        Some(format!(
+            "{} cubes, {} texture layers, 1 texture bind group, 1 draw call, layer offset {} (T)",
+            self.instance_buffer.len(),
+            self.textures.len(),
+            self.layer_offset
+        ))
+    }
+}
+
+impl TextureArrayCubes {
+    /// 用`rebuild_pipeline`按sample_count重建管线，成功后才更新多重采样数
+    fn rebuild_pipeline(
+        &mut self,
+        device: &wgpu::Device,
+        format: wgpu::TextureFormat,
+        sample_count: u32,
+    ) -> Result<(), WgpuAppError> {
+        rebuild_pipeline(device, SHADER, &mut self.pipeline, |shader| {
+            create_pipeline(
+                device,
+                shader,
+                format,
+                &self.pipeline_layout,
+                sample_count,
+                self.cache.as_ref(),
+            )
+        })?;
+        self.sample_count = sample_count;
+        Ok(())
+    }
+}
+
+/// 以原点为中心、每边GRID个立方体的阵列中第i个立方体，旋转方式与动态偏移示例相同，
+/// 层号按位置错开后加上offset
+fn grid_instance(i: u32, angle: f32, offset: u32) -> LayeredInstance {
+    let (x, y, z) = (i % GRID, i / GRID % GRID, i / (GRID * GRID));
+    let last = (GRID - 1) as f32;
+    let uvw = Vec3::new(x as f32, y as f32, z as f32) / last;
+    let transform = Transform {
+        translation: (uvw - 0.5) * last * SPACING,
+        rotation: Quat::from_rotation_y(angle + uvw.x * std::f32::consts::PI)
+            * Quat::from_rotation_x(angle + uvw.z * std::f32::consts::PI),
+        scale: Vec3::ONE,
+    };
+    LayeredInstance::from_transform(&transform, (x + 3 * y + 7 * z + offset) % LAYERS)
+}
+
+/// 第layer层的贴图：layer % 4选择图案（棋盘格、条纹、圆点、边框），layer / 4选择颜色
+fn layer_image(layer: u32) -> image::RgbaImage {
+    let [r, g, b] = PALETTE[(layer / 4) as usize];
+    let cell = LAYER_SIZE / 8;
+    image::RgbaImage::from_fn(LAYER_SIZE, LAYER_SIZE, |x, y| {
+        let lit = match layer % 4 {
+            0 => (x / cell + y / cell).is_multiple_of(2),
+            1 => ((x + y) / cell).is_multiple_of(2),
+            2 => {
+                let (dx, dy) = (
+                    (x % (2 * cell)) as i32 - cell as i32,
+                    (y % (2 * cell)) as i32 - cell as i32,
+                );
+                dx * dx + dy * dy <= (cell * cell * 5 / 8) as i32
+            }
+            _ => x.min(y).min(LAYER_SIZE - 1 - x.max(y)) < cell,
+        };
+        if lit {
+            image::Rgba([r, g, b, 255])
+        } else {
+            image::Rgba([235, 235, 230, 255])
+        }
+    })
+}
+
+/// 创建读取MeshVertex（槽位0）和LayeredInstance（槽位1）并开启深度测试的渲染管线
+fn create_pipeline(
+    device: &wgpu::Device,
+    shader: &wgpu::ShaderModule,
+    format: wgpu::TextureFormat,
+    layout: &wgpu::PipelineLayout,
+    sample_count: u32,
+    cache: Option<&wgpu::PipelineCache>,
+) -> wgpu::RenderPipeline {
+    device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
+        label: Some("Texture Array Pipeline"),
+        layout: Some(layout),
+        vertex: wgpu::VertexState {
+            module: shader,
+            entry_point: Some("vs"),
+            buffers: &[MeshVertex::LAYOUT, LayeredInstance::LAYOUT],
+            compilation_options: Default::default(),
+        },
+        fragment: Some(wgpu::FragmentState {
+            module: shader,
+            entry_point: Some("fs"),
+            targets: &[Some(wgpu::ColorTargetState {
+                format,
+                blend: Some(wgpu::BlendState::REPLACE),
+                write_mask: wgpu::ColorWrites::ALL,
+            })],
+            compilation_options: Default::default(),
+        }),
+        primitive: wgpu::PrimitiveState {
+            cull_mode: Some(wgpu::Face::Back), // 立方体是封闭的，剔除背面
+            ..Default::default()
+        },
+        depth_stencil: Some(DepthTexture::depth_stencil_state()), // LessEqual深度测试
+        multisample: wgpu::MultisampleState {
+            count: sample_count,
+            ..Default::default()
+        },
+        multiview: None,
+        cache,
+    })
+}
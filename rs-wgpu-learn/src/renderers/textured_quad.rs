@@ -0,0 +1,338 @@
+use crate::{
+    InitContext, InputState, Mesh, MeshVertex, RenderContext, Renderer, SamplerCache, SamplerDesc,
+    Scene, Texture, WgpuAppError,
+    binding::{self, BindGroupBuilder, BindGroupLayoutBuilder, BindingLayout},
+    mipmap::MipmapGenerator,
+    rebuild_pipeline, shader, shapes,
+};
+use log::{error, info};
+use std::{path::Path, time::Duration};
+use winit::keyboard::KeyCode;
+
+/// 贴图四边形使用的着色器文件
+const SHADER: &str = "textured_quad.wgsl";
+
+/// 内置的测试图片（320x200的RGB PNG，左上角有白色三角形标记方向）
+const TEST_IMAGE: &[u8] = include_bytes!("../../assets/test_pattern.png");
+
+/// 图片在窗口中占据的最大比例（NDC）
+const MARGIN: f32 = 0.9;
+
+/// 与着色器中QuadUniform对应的数据
+#[repr(C)]
+#[derive(Debug, Clone, Copy, bytemuck::Pod, bytemuck::Zeroable)]
+struct QuadUniform {
+    scale: [f32; 2],
+}
+
+/// 贴了一张图片的四边形：按图片的宽高比缩放后居中显示
+///
+/// 默认显示内置的测试图片，拖放PNG、JPEG或KTX2文件到窗口上可以替换它（尺寸不要求是2的幂，灰度图片也可以）。
+/// F键在线性和最近邻过滤之间切换。
+pub struct TexturedQuad {
+    pipeline: wgpu::RenderPipeline,
+    pipeline_layout: wgpu::PipelineLayout, // 第0组：QuadUniform，第1组：纹理和采样器
+    sample_count: u32,
+    cache: Option<wgpu::PipelineCache>,
+    mesh: Mesh,                                // 边长为2的正方形
+    uniform_buffer: wgpu::Buffer,              // 第0组：QuadUniform
+    uniform_bind_group: wgpu::BindGroup,       // 第0组的绑定组
+    texture_layout: BindingLayout,             // 第1组的布局（替换图片时创建绑定组）
+    texture_bind_groups: [wgpu::BindGroup; 2], // 线性和最近邻过滤的纹理绑定组
+    image_size: (u32, u32),                    // 图片尺寸（像素）
+    aspect: f32,                               // 表面宽高比
+    nearest: bool,                             // 是否使用最近邻过滤
+    mipmaps: MipmapGenerator,                  // 替换图片时生成mip链
+    samplers: SamplerCache,                    // 线性和最近邻过滤的采样器
+}
+
+impl Renderer for TexturedQuad {
+    fn init(ctx: &InitContext) -> Result<Self, WgpuAppError> {
+        let InitContext {
+            device,
+            queue,
+            config,
+            sample_count,
+            cache,
+            samplers,
+            ..
+        } = *ctx;
+        // 1. 创建网格、uniform缓冲区（每帧写入缩放）和内置图片的纹理
+        let (vertices, indices) = shapes::quad(2.0);
+        let mesh = Mesh::new(device, "Textured Quad", &vertices, Some(&indices));
+        let uniform_buffer = device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some("Textured Quad Uniform Buffer"),
+            size: std::mem::size_of::<QuadUniform>() as u64,
+            usage: wgpu::BufferUsages::UNIFORM | wgpu::BufferUsages::COPY_DST,
+            mapped_at_creation: false,
+        });
+        let mut mipmaps = MipmapGenerator::new(device)?;
+        let texture = Texture::from_bytes(
+            device,
+            queue,
+            samplers,
+            TEST_IMAGE,
+            Some("Test Pattern"),
+            Some(&mut mipmaps),
+        )?;
+
+        // 2. 创建绑定组布局、绑定组和管线布局
+        let uniform_layout = create_uniform_layout(device);
+        let uniform_bind_group = BindGroupBuilder::new(&uniform_layout)
+            .label("Textured Quad Uniform Bind Group")
+            .buffer(&uniform_buffer)
+            .build(device);
+        let texture_layout = Texture::create_layout(
+            device,
+            "Textured Quad Texture Layout",
+            wgpu::ShaderStages::FRAGMENT,
+        );
+        let pipeline_layout = binding::pipeline_layout(
+            device,
+            Some("Textured Quad Pipeline Layout"),
+            &[&uniform_layout, &texture_layout],
+        );
+
+        // 3. 加载着色器并创建管线
+        let shader = shader::load(device, SHADER)?;
+        let pipeline = create_pipeline(
+            device,
+            &shader,
+            config.format,
+            &pipeline_layout,
+            sample_count,
+            cache,
+        );
+
+        Ok(Self {
+            pipeline,
+            pipeline_layout,
+            sample_count,
+            cache: cache.cloned(),
+            mesh,
+            uniform_buffer,
+            uniform_bind_group,
+            texture_bind_groups: texture_bind_groups(device, samplers, &texture_layout, &texture)?,
+            texture_layout,
+            image_size: texture_size(&texture),
+            aspect: aspect(config),
+            nearest: false,
+            mipmaps,
+            samplers: samplers.clone(),
+        })
+    }
+
+    fn render(
+        &mut self,
+        ctx: &RenderContext,
+        view: &wgpu::TextureView,
+        encoder: &mut wgpu::CommandEncoder,
+    ) {
+        let uniform = QuadUniform {
+            scale: fit(self.image_size, self.aspect),
+        };
+        ctx.upload(encoder, &self.uniform_buffer, 0, &uniform);
+
+        let mut pass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
+            label: Some("Textured Quad Pass"),
+            color_attachments: &[Some(ctx.color_attachment(
+                view,
+                wgpu::Operations {
+                    load: wgpu::LoadOp::Clear(ctx.clear_color),
+                    store: wgpu::StoreOp::Store,
+                },
+            ))],
+            depth_stencil_attachment: None,
+            timestamp_writes: ctx.timestamp_writes(),
+            occlusion_query_set: None,
+        });
+        pass.set_pipeline(&self.pipeline);
+        pass.set_bind_group(0, &self.uniform_bind_group, &[]);
+        pass.set_bind_group(1, &self.texture_bind_groups[self.nearest as usize], &[]);
+        self.mesh.draw(&mut pass, 0..1);
+    }
+
+    fn resize(&mut self, config: &wgpu::SurfaceConfiguration) {
+        self.aspect = aspect(config);
+    }
+
+    fn shader_changed(
+        &mut self,
+        device: &wgpu::Device,
+        config: &wgpu::SurfaceConfiguration,
+        path: &Path,
+    ) {
+        if !shader::depends_on(SHADER, path) {
+            return;
+        }
+        let _ = self.rebuild_pipeline(device, config.format, self.sample_count);
+    }
+
+    fn sample_count_changed(
+        &mut self,
+        device: &wgpu::Device,
+        config: &wgpu::SurfaceConfiguration,
+        sample_count: u32,
+    ) -> Result<(), WgpuAppError> {
+        self.rebuild_pipeline(device, config.format, sample_count)
+    }
+}
+
+impl Scene for TexturedQuad {
+    fn update(&mut self, _dt: Duration, _queue: &wgpu::Queue, input: &InputState) {
+        if input.just_pressed(KeyCode::KeyF) {
+            self.nearest = !self.nearest;
+            info!(
+                "Using {} filtering",
+                if self.nearest { "nearest" } else { "linear" }
+            );
+        }
+    }
+
+    fn status(&self) -> Option<String> {
+        let (width, height) = self.image_size;
+        let filter = if self.nearest { "nearest" } else { "linear" };
+        Some(format!("{width}x{height}, {filter} filtering"))
+    }
+
+    /// 拖放PNG、JPEG或KTX2文件时用它替换图片（旁边有同名的`.ktx2`文件时加载它），加载失败时保留原图片
+    fn file_dropped(&mut self, path: &Path, device: &wgpu::Device, queue: &wgpu::Queue) -> bool {
+        let is_image = path.extension().is_some_and(|ext| {
+            ["png", "jpg", "jpeg", "ktx2"]
+                .iter()
+                .any(|image| ext.eq_ignore_ascii_case(image))
+        });
+        if !is_image {
+            return false;
+        }
+        let loaded = Texture::load(device, queue, &self.samplers, path, Some(&mut self.mipmaps))
+            .and_then(|texture| {
+                let bind_groups =
+                    texture_bind_groups(device, &self.samplers, &self.texture_layout, &texture)?;
+                Ok((texture, bind_groups))
+            });
+        match loaded {
+            Ok((texture, bind_groups)) => {
+                self.texture_bind_groups = bind_groups;
+                self.image_size = texture_size(&texture);
+                info!(
+                    "Showing {} ({}x{})",
+                    path.display(),
+                    self.image_size.0,
+                    self.image_size.1
+                );
+            }
+            Err(e) => error!(
+                "Failed to load image {}, keeping the previous one: {e}",
+                path.display()
+            ),
+        }
+        true
+    }
+}
+
+impl TexturedQuad {
+    /// 用`rebuild_pipeline`按sample_count重建管线，成功后才更新多重采样数
+    fn rebuild_pipeline(
+        &mut self,
+        device: &wgpu::Device,
+        format: wgpu::TextureFormat,
+        sample_count: u32,
+    ) -> Result<(), WgpuAppError> {
+        rebuild_pipeline(device, SHADER, &mut self.pipeline, |shader| {
+            create_pipeline(
+                device,
+                shader,
+                format,
+                &self.pipeline_layout,
+                sample_count,
+                self.cache.as_ref(),
+            )
+        })?;
+        self.sample_count = sample_count;
+        Ok(())
+    }
+}
+
+/// 表面宽高比
+fn aspect(config: &wgpu::SurfaceConfiguration) -> f32 {
+    config.width.max(1) as f32 / config.height.max(1) as f32
+}
+
+/// 纹理尺寸（像素）
+fn texture_size(texture: &Texture) -> (u32, u32) {
+    (texture.texture.width(), texture.texture.height())
+}
+
+/// 边长为2的正方形的缩放：图片完整显示在窗口中央，保持原始宽高比
+fn fit((width, height): (u32, u32), aspect: f32) -> [f32; 2] {
+    let image_aspect = width.max(1) as f32 / height.max(1) as f32;
+    if image_aspect > aspect {
+        [MARGIN, MARGIN * aspect / image_aspect]
+    } else {
+        [MARGIN * image_aspect / aspect, MARGIN]
+    }
+}
+
+/// 同一张纹理分别使用线性过滤（默认采样器）和最近邻过滤（F键切换，放大时可以看清单个像素）的绑定组
+fn texture_bind_groups(
+    device: &wgpu::Device,
+    samplers: &SamplerCache,
+    layout: &BindingLayout,
+    texture: &Texture,
+) -> Result<[wgpu::BindGroup; 2], WgpuAppError> {
+    let nearest = texture
+        .clone()
+        .with_sampler(samplers.get(&SamplerDesc::nearest_clamp())?);
+    Ok([
+        texture.bind_group(device, layout),
+        nearest.bind_group(device, layout),
+    ])
+}
+
+/// 第0组：顶点着色器使用的QuadUniform
+fn create_uniform_layout(device: &wgpu::Device) -> BindingLayout {
+    BindGroupLayoutBuilder::new()
+        .label("Textured Quad Uniform Layout")
+        .uniform(wgpu::ShaderStages::VERTEX)
+        .build(device)
+}
+
+/// 创建读取MeshVertex位置和纹理坐标的渲染管线
+fn create_pipeline(
+    device: &wgpu::Device,
+    shader: &wgpu::ShaderModule,
+    format: wgpu::TextureFormat,
+    layout: &wgpu::PipelineLayout,
+    sample_count: u32,
+    cache: Option<&wgpu::PipelineCache>,
+) -> wgpu::RenderPipeline {
+    device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
+        label: Some("Textured Quad Pipeline"),
+        layout: Some(layout),
+        vertex: wgpu::VertexState {
+            module: shader,
+            entry_point: Some("vs"),
+            buffers: &[MeshVertex::LAYOUT],
+            compilation_options: Default::default(),
+        },
+        fragment: Some(wgpu::FragmentState {
+            module: shader,
+            entry_point: Some("fs"),
+            targets: &[Some(wgpu::ColorTargetState {
+                format,
+                blend: Some(wgpu::BlendState::REPLACE),
+                write_mask: wgpu::ColorWrites::ALL,
+            })],
+            compilation_options: Default::default(),
+        }),
+        primitive: Default::default(),
+        depth_stencil: None,
+        multisample: wgpu::MultisampleState {
+            count: sample_count,
+            ..Default::default()
+        },
+        multiview: None,
+        cache,
+    })
+}
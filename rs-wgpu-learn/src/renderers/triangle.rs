@@ -0,0 +1,206 @@
+use crate::{
+    InitContext, RedrawMode, RenderContext, Renderer, Scene, WgpuAppError, rebuild_or_keep, shader,
+};
+use log::{error, info};
+use std::path::{Path, PathBuf};
+
+/// 三角形使用的着色器文件
+const SHADER: &str = "triangle.wgsl";
+
+/// 基础三角形：顶点坐标直接写在着色器中
+///
+/// 拖放一个`.wgsl`文件到窗口上可以替换片元着色器（文件需提供`fs`入口，只能读取`@builtin(position)`）。
+pub struct Triangle {
+    pipeline: wgpu::RenderPipeline, // 渲染管线（包含着色器、状态配置等）
+    format: wgpu::TextureFormat,    // 颜色附件格式（拖放着色器时重建管线使用）
+    sample_count: u32,              // 管线使用的多重采样数
+    cache: Option<wgpu::PipelineCache>, // 管线缓存（重建管线时复用）
+    fragment: Option<PathBuf>,      // 拖放进来的片元着色器（None时使用SHADER中的fs）
+}
+
+impl Renderer for Triangle {
+    fn init(ctx: &InitContext) -> Result<Self, WgpuAppError> {
+        let InitContext {
+            device,
+            config,
+            sample_count,
+            cache,
+            ..
+        } = *ctx;
+        // 1. 创建着色器模块（运行时从资源目录加载WGSL着色器）
+        let shader = shader::load(device, SHADER)?;
+
+        // 2. 创建渲染管线
+        let pipeline =
+            create_pipeline(device, &shader, &shader, config.format, sample_count, cache);
+
+        Ok(Self {
+            pipeline,
+            format: config.format,
+            sample_count,
+            cache: cache.cloned(),
+            fragment: None,
+        })
+    }
+
+    fn render(
+        &mut self,
+        ctx: &RenderContext,
+        view: &wgpu::TextureView,
+        encoder: &mut wgpu::CommandEncoder,
+    ) {
+        // 1. 开始渲染通道
+        let mut pass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
+            label: Some("Render Pass"),
+            color_attachments: &[Some(ctx.color_attachment(
+                view,
+                wgpu::Operations {
+                    load: wgpu::LoadOp::Clear(ctx.clear_color), // 清除背景
+                    store: wgpu::StoreOp::Store,                // 存储渲染结果
+                },
+            ))],
+            depth_stencil_attachment: None,
+            timestamp_writes: ctx.timestamp_writes(),
+            occlusion_query_set: None,
+        });
+
+        // 2. 设置渲染管线
+        pass.set_pipeline(&self.pipeline);
+
+        // 3. 绘制调用（绘制3个顶点，组成一个三角形）
+        pass.draw(0..3, 0..1);
+    }
+
+    fn shader_changed(
+        &mut self,
+        device: &wgpu::Device,
+        config: &wgpu::SurfaceConfiguration,
+        path: &Path,
+    ) {
+        let fragment_changed = self
+            .fragment
+            .as_ref()
+            .is_some_and(|fragment| shader::depends_on(fragment, path));
+        if !shader::depends_on(SHADER, path) && !fragment_changed {
+            return;
+        }
+        let _ = self.rebuild_pipeline(device, config.format, self.sample_count);
+    }
+
+    fn sample_count_changed(
+        &mut self,
+        device: &wgpu::Device,
+        config: &wgpu::SurfaceConfiguration,
+        sample_count: u32,
+    ) -> Result<(), WgpuAppError> {
+        self.rebuild_pipeline(device, config.format, sample_count)
+    }
+}
+
+impl Triangle {
+    /// 加载着色器并按sample_count创建管线，fragment为替换用的片元着色器
+    fn build_pipeline(
+        &self,
+        device: &wgpu::Device,
+        format: wgpu::TextureFormat,
+        fragment: Option<&Path>,
+        sample_count: u32,
+    ) -> Result<wgpu::RenderPipeline, WgpuAppError> {
+        let shader = shader::load(device, SHADER)?;
+        let fragment = fragment
+            .map(|path| shader::load(device, path))
+            .transpose()?;
+        shader::catch_validation(device, || {
+            create_pipeline(
+                device,
+                &shader,
+                fragment.as_ref().unwrap_or(&shader),
+                format,
+                sample_count,
+                self.cache.as_ref(),
+            )
+        })
+        .map_err(|e| WgpuAppError::Validation(e.to_string()))
+    }
+
+    /// 按sample_count和当前的片元着色器重建管线，成功后才更新多重采样数
+    fn rebuild_pipeline(
+        &mut self,
+        device: &wgpu::Device,
+        format: wgpu::TextureFormat,
+        sample_count: u32,
+    ) -> Result<(), WgpuAppError> {
+        let rebuilt = self.build_pipeline(device, format, self.fragment.as_deref(), sample_count);
+        rebuild_or_keep(&mut self.pipeline, SHADER, rebuilt)?;
+        self.sample_count = sample_count;
+        Ok(())
+    }
+}
+
+impl Scene for Triangle {
+    /// 静态画面，只在尺寸、背景色或着色器变化时重绘
+    fn redraw_mode(&self) -> RedrawMode {
+        RedrawMode::OnDemand
+    }
+
+    /// 拖放`.wgsl`文件时用它替换片元着色器，验证失败时保留原管线
+    fn file_dropped(&mut self, path: &Path, device: &wgpu::Device, _queue: &wgpu::Queue) -> bool {
+        if path
+            .extension()
+            .is_none_or(|ext| !ext.eq_ignore_ascii_case("wgsl"))
+        {
+            return false;
+        }
+        match self.build_pipeline(device, self.format, Some(path), self.sample_count) {
+            Ok(pipeline) => {
+                self.pipeline = pipeline;
+                self.fragment = Some(path.to_path_buf());
+                info!("Using fragment shader from {}", path.display());
+            }
+            Err(e) => error!(
+                "Failed to use fragment shader {}, keeping the previous one: {e}",
+                path.display()
+            ),
+        }
+        true
+    }
+}
+
+/// 创建三角形渲染管线
+fn create_pipeline(
+    device: &wgpu::Device,
+    shader: &wgpu::ShaderModule,
+    fragment: &wgpu::ShaderModule,
+    format: wgpu::TextureFormat,
+    sample_count: u32,
+    cache: Option<&wgpu::PipelineCache>,
+) -> wgpu::RenderPipeline {
+    device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
+        label: Some("Render Pipeline"),
+        layout: None, // 使用默认管线布局
+        vertex: wgpu::VertexState {
+            module: shader,          // 顶点着色器模块
+            entry_point: Some("vs"), // 入口函数
+            buffers: &[],            // 顶点缓冲区布局（本示例为空）
+            compilation_options: Default::default(),
+        },
+        fragment: Some(wgpu::FragmentState {
+            module: fragment,        // 片元着色器模块
+            entry_point: Some("fs"), // 入口函数
+            targets: &[Some(wgpu::ColorTargetState {
+                format,                                 // 使用表面配置的格式
+                blend: Some(wgpu::BlendState::REPLACE), // 混合模式：直接替换
+                write_mask: wgpu::ColorWrites::ALL,     // 允许写入所有颜色通道
+            })],
+            compilation_options: Default::default(),
+        }),
+        primitive: Default::default(), // 使用默认图元配置（三角形列表）
+        depth_stencil: None,           // 禁用深度/模板测试
+        multisample: wgpu::MultisampleState {
+            count: sample_count, // 需与颜色附件的多重采样数一致
+            ..Default::default()
+        },
+        multiview: None,
+        cache,
+    })
+}
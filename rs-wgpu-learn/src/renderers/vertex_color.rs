@@ -0,0 +1,162 @@
+use crate::{
+    InitContext, Mesh, RedrawMode, RenderContext, Renderer, Scene, Vertex, WgpuAppError,
+    rebuild_pipeline, shader,
+};
+use std::path::Path;
+
+/// 顶点颜色示例使用的着色器文件
+pub(super) const SHADER: &str = "vertex_color.wgsl";
+
+/// 三角形的顶点（逆时针），每个顶点一种颜色
+const VERTICES: &[Vertex] = &[
+    Vertex {
+        position: [0.0, 0.5, 0.0],
+        color: [1.0, 0.0, 0.0],
+    },
+    Vertex {
+        position: [-0.5, -0.5, 0.0],
+        color: [0.0, 1.0, 0.0],
+    },
+    Vertex {
+        position: [0.5, -0.5, 0.0],
+        color: [0.0, 0.0, 1.0],
+    },
+];
+
+/// 顶点颜色三角形：位置和颜色从顶点缓冲区读取，片元颜色由三个顶点的颜色插值得到
+pub struct VertexColorTriangle {
+    pipeline: wgpu::RenderPipeline,     // 使用Vertex::LAYOUT的渲染管线
+    mesh: Mesh,                         // 顶点缓冲区（没有索引）
+    sample_count: u32,                  // 管线使用的多重采样数
+    cache: Option<wgpu::PipelineCache>, // 管线缓存（重建管线时复用）
+}
+
+impl Renderer for VertexColorTriangle {
+    fn init(ctx: &InitContext) -> Result<Self, WgpuAppError> {
+        let InitContext {
+            device,
+            config,
+            sample_count,
+            cache,
+            ..
+        } = *ctx;
+        // 1. 创建顶点缓冲区（内容在创建时上传，之后不再修改）
+        let mesh = Mesh::new(device, "Vertex Color", VERTICES, None);
+
+        // 2. 创建着色器模块和渲染管线
+        let shader = shader::load(device, SHADER)?;
+        let pipeline = create_pipeline(device, &shader, config.format, sample_count, cache);
+        Ok(Self {
+            pipeline,
+            mesh,
+            sample_count,
+            cache: cache.cloned(),
+        })
+    }
+
+    fn render(
+        &mut self,
+        ctx: &RenderContext,
+        view: &wgpu::TextureView,
+        encoder: &mut wgpu::CommandEncoder,
+    ) {
+        let mut pass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
+            label: Some("Vertex Color Pass"),
+            color_attachments: &[Some(ctx.color_attachment(
+                view,
+                wgpu::Operations {
+                    load: wgpu::LoadOp::Clear(ctx.clear_color),
+                    store: wgpu::StoreOp::Store,
+                },
+            ))],
+            depth_stencil_attachment: None,
+            timestamp_writes: ctx.timestamp_writes(),
+            occlusion_query_set: None,
+        });
+        pass.set_pipeline(&self.pipeline);
+        // 顶点缓冲区绑定到槽位0，对应VertexState.buffers中的第一个布局
+        self.mesh.draw(&mut pass, 0..1);
+    }
+
+    fn shader_changed(
+        &mut self,
+        device: &wgpu::Device,
+        config: &wgpu::SurfaceConfiguration,
+        path: &Path,
+    ) {
+        if !shader::depends_on(SHADER, path) {
+            return;
+        }
+        let _ = self.rebuild_pipeline(device, config.format, self.sample_count);
+    }
+
+    fn sample_count_changed(
+        &mut self,
+        device: &wgpu::Device,
+        config: &wgpu::SurfaceConfiguration,
+        sample_count: u32,
+    ) -> Result<(), WgpuAppError> {
+        self.rebuild_pipeline(device, config.format, sample_count)
+    }
+}
+
+impl Scene for VertexColorTriangle {
+    /// 静态画面，只在尺寸、背景色或着色器变化时重绘
+    fn redraw_mode(&self) -> RedrawMode {
+        RedrawMode::OnDemand
+    }
+}
+
+impl VertexColorTriangle {
+    /// 用`rebuild_pipeline`按sample_count重建管线，成功后才更新多重采样数
+    fn rebuild_pipeline(
+        &mut self,
+        device: &wgpu::Device,
+        format: wgpu::TextureFormat,
+        sample_count: u32,
+    ) -> Result<(), WgpuAppError> {
+        rebuild_pipeline(device, SHADER, &mut self.pipeline, |shader| {
+            create_pipeline(device, shader, format, sample_count, self.cache.as_ref())
+        })?;
+        self.sample_count = sample_count;
+        Ok(())
+    }
+}
+
+/// 创建从顶点缓冲区读取位置和颜色的渲染管线（顶点布局为`Vertex::LAYOUT`的场景共用）
+pub(super) fn create_pipeline(
+    device: &wgpu::Device,
+    shader: &wgpu::ShaderModule,
+    format: wgpu::TextureFormat,
+    sample_count: u32,
+    cache: Option<&wgpu::PipelineCache>,
+) -> wgpu::RenderPipeline {
+    device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
+        label: Some("Vertex Color Pipeline"),
+        layout: None,
+        vertex: wgpu::VertexState {
+            module: shader,
+            entry_point: Some("vs"),
+            buffers: &[Vertex::LAYOUT], // 顶点缓冲区布局（槽位0）
+            compilation_options: Default::default(),
+        },
+        fragment: Some(wgpu::FragmentState {
+            module: shader,
+            entry_point: Some("fs"),
+            targets: &[Some(wgpu::ColorTargetState {
+                format,
+                blend: Some(wgpu::BlendState::REPLACE),
+                write_mask: wgpu::ColorWrites::ALL,
+            })],
+            compilation_options: Default::default(),
+        }),
+        primitive: Default::default(),
+        depth_stencil: None,
+        multisample: wgpu::MultisampleState {
+            count: sample_count,
+            ..Default::default()
+        },
+        multiview: None,
+        cache,
+    })
+}
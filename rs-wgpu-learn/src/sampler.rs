@@ -0,0 +1,308 @@
+use crate::WgpuAppError;
+use parking_lot::Mutex;
+use std::{
+    collections::HashMap,
+    hash::{Hash, Hasher},
+    sync::Arc,
+};
+
+/// 各向异性过滤允许的最大值（wgpu把超出的值视为无效）
+pub const MAX_ANISOTROPY: u16 = 16;
+
+/// 采样器的配置：过滤方式、寻址方式、各向异性过滤、比较函数和LOD范围
+///
+/// 与`wgpu::SamplerDescriptor`对应但不带标签，可以作为`SamplerCache`的键：配置相同的采样器只创建一次。
+/// 用预设（`linear_repeat`、`linear_clamp`、`nearest_clamp`、`shadow_compare`）加`with_*`方法修改得到所需的配置。
+#[derive(Debug, Clone, Copy)]
+pub struct SamplerDesc {
+    pub address_mode: [wgpu::AddressMode; 3], // u、v、w方向的寻址方式
+    pub mag_filter: wgpu::FilterMode,         // 放大时的过滤方式
+    pub min_filter: wgpu::FilterMode,         // 缩小时的过滤方式
+    pub mipmap_filter: wgpu::FilterMode,      // 相邻mip级别之间的过滤方式
+    pub lod_min_clamp: f32,                   // 最小LOD
+    pub lod_max_clamp: f32,                   // 最大LOD（0表示只使用第0级）
+    pub compare: Option<wgpu::CompareFunction>, // 比较函数（深度比较采样器，用于阴影贴图）
+    pub anisotropy: u16, // 各向异性过滤的最大采样数（1表示关闭，大于1时三种过滤都必须是线性）
+}
+
+impl Default for SamplerDesc {
+    /// 三线性过滤、重复寻址，见`linear_repeat`
+    fn default() -> Self {
+        Self::linear_repeat()
+    }
+}
+
+impl SamplerDesc {
+    /// 三线性过滤（各级内和相邻两级之间都线性插值），纹理坐标超出0..1时重复，用于大多数颜色贴图
+    pub const fn linear_repeat() -> Self {
+        Self {
+            address_mode: [wgpu::AddressMode::Repeat; 3],
+            mag_filter: wgpu::FilterMode::Linear,
+            min_filter: wgpu::FilterMode::Linear,
+            mipmap_filter: wgpu::FilterMode::Linear,
+            lod_min_clamp: 0.0,
+            lod_max_clamp: 32.0,
+            compare: None,
+            anisotropy: 1,
+        }
+    }
+
+    /// 三线性过滤，纹理坐标钳制到边缘，用于不应平铺的图片（全屏纹理、UI等）
+    pub const fn linear_clamp() -> Self {
+        Self {
+            address_mode: [wgpu::AddressMode::ClampToEdge; 3],
+            ..Self::linear_repeat()
+        }
+    }
+
+    /// 最近邻过滤，纹理坐标钳制到边缘，用于像素风格的图片或按整数坐标读取的数据纹理
+    pub const fn nearest_clamp() -> Self {
+        Self {
+            mag_filter: wgpu::FilterMode::Nearest,
+            min_filter: wgpu::FilterMode::Nearest,
+            mipmap_filter: wgpu::FilterMode::Nearest,
+            ..Self::linear_clamp()
+        }
+    }
+
+    /// 阴影贴图的比较采样器：线性过滤（硬件PCF）、钳制到边缘，深度小于等于参考值时通过
+    pub const fn shadow_compare() -> Self {
+        Self {
+            compare: Some(wgpu::CompareFunction::LessEqual),
+            mipmap_filter: wgpu::FilterMode::Nearest,
+            ..Self::linear_clamp()
+        }
+    }
+
+    /// 修改三个方向的寻址方式
+    pub const fn with_address_mode(mut self, mode: wgpu::AddressMode) -> Self {
+        self.address_mode = [mode; 3];
+        self
+    }
+
+    /// 修改各向异性过滤的最大采样数（1表示关闭）
+    pub const fn with_anisotropy(mut self, anisotropy: u16) -> Self {
+        self.anisotropy = anisotropy;
+        self
+    }
+
+    /// 修改LOD范围
+    pub const fn with_lod_clamp(mut self, min: f32, max: f32) -> Self {
+        self.lod_min_clamp = min;
+        self.lod_max_clamp = max;
+        self
+    }
+
+    /// 检查wgpu会拒绝的组合，返回可读的`InvalidSampler`错误（wgpu本身只报告笼统的验证错误）
+    ///
+    /// 各向异性过滤必须在1..=`MAX_ANISOTROPY`之间，大于1时放大、缩小和mipmap过滤都必须是线性；
+    /// LOD范围必须是有限值，不能为负或颠倒。
+    pub fn validate(&self) -> Result<(), WgpuAppError> {
+        if !(1..=MAX_ANISOTROPY).contains(&self.anisotropy) {
+            return Err(WgpuAppError::InvalidSampler(format!(
+                "anisotropy must be between 1 and {MAX_ANISOTROPY}, got {}",
+                self.anisotropy
+            )));
+        }
+        let filters = [self.mag_filter, self.min_filter, self.mipmap_filter];
+        if self.anisotropy > 1 && filters.contains(&wgpu::FilterMode::Nearest) {
+            return Err(WgpuAppError::InvalidSampler(format!(
+                "anisotropy {} requires linear mag, min and mipmap filters, got {filters:?}",
+                self.anisotropy
+            )));
+        }
+        // 写成`!(..)`使NaN也不通过（与NaN的比较总是false）
+        let lod = [self.lod_min_clamp, self.lod_max_clamp];
+        if !(lod.iter().all(|lod| lod.is_finite())
+            && self.lod_min_clamp >= 0.0
+            && self.lod_max_clamp >= self.lod_min_clamp)
+        {
+            return Err(WgpuAppError::InvalidSampler(format!(
+                "invalid LOD clamp {}..{}",
+                self.lod_min_clamp, self.lod_max_clamp
+            )));
+        }
+        Ok(())
+    }
+
+    /// 对应的wgpu采样器描述符
+    pub fn descriptor<'a>(&self, label: Option<&'a str>) -> wgpu::SamplerDescriptor<'a> {
+        let [address_mode_u, address_mode_v, address_mode_w] = self.address_mode;
+        wgpu::SamplerDescriptor {
+            label,
+            address_mode_u,
+            address_mode_v,
+            address_mode_w,
+            mag_filter: self.mag_filter,
+            min_filter: self.min_filter,
+            mipmap_filter: self.mipmap_filter,
+            lod_min_clamp: self.lod_min_clamp,
+            lod_max_clamp: self.lod_max_clamp,
+            compare: self.compare,
+            anisotropy_clamp: self.anisotropy,
+            border_color: None,
+        }
+    }
+
+    /// 验证后创建采样器（不经过缓存）
+    pub fn create(
+        &self,
+        device: &wgpu::Device,
+        label: Option<&str>,
+    ) -> Result<wgpu::Sampler, WgpuAppError> {
+        self.validate()?;
+        Ok(device.create_sampler(&self.descriptor(label)))
+    }
+
+    /// 用于比较和哈希的字段（浮点数按位比较）
+    fn key(&self) -> impl Eq + Hash {
+        (
+            self.address_mode,
+            self.mag_filter,
+            self.min_filter,
+            self.mipmap_filter,
+            self.lod_min_clamp.to_bits(),
+            self.lod_max_clamp.to_bits(),
+            self.compare,
+            self.anisotropy,
+        )
+    }
+}
+
+impl PartialEq for SamplerDesc {
+    fn eq(&self, other: &Self) -> bool {
+        self.key() == other.key()
+    }
+}
+
+impl Eq for SamplerDesc {}
+
+impl Hash for SamplerDesc {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.key().hash(state);
+    }
+}
+
+/// 按配置共享的采样器缓存：配置相同的纹理共用一个采样器，而不是每张纹理各创建一个
+///
+/// 通过`&self`取用（内部加锁），应用把它放在`WgpuApp::samplers`中，渲染器通过`InitContext::samplers`和
+/// `RenderContext::samplers`使用；克隆得到的句柄共享同一份缓存，之后还要创建纹理的渲染器保存一份克隆即可。
+#[derive(Debug, Clone)]
+pub struct SamplerCache {
+    device: wgpu::Device,
+    samplers: Arc<Mutex<HashMap<SamplerDesc, wgpu::Sampler>>>,
+}
+
+impl SamplerCache {
+    // wasm上的wgpu对象不是Send，缓存只在主线程上使用
+    #[cfg_attr(target_arch = "wasm32", allow(clippy::arc_with_non_send_sync))]
+    pub fn new(device: &wgpu::Device) -> Self {
+        Self {
+            device: device.clone(),
+            samplers: Arc::new(Mutex::new(HashMap::new())),
+        }
+    }
+
+    /// 取得与desc匹配的采样器，第一次用到时验证并创建（克隆只增加引用计数）
+    pub fn get(&self, desc: &SamplerDesc) -> Result<wgpu::Sampler, WgpuAppError> {
+        let mut samplers = self.samplers.lock();
+        if let Some(sampler) = samplers.get(desc) {
+            return Ok(sampler.clone());
+        }
+        let sampler = desc.create(&self.device, Some("Cached Sampler"))?;
+        samplers.insert(*desc, sampler.clone());
+        Ok(sampler)
+    }
+
+    /// 已创建的采样器数量
+    pub fn len(&self) -> usize {
+        self.samplers.lock().len()
+    }
+
+    /// 是否还没有创建任何采样器
+    pub fn is_empty(&self) -> bool {
+        self.samplers.lock().is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// desc应被validate拒绝，且错误信息包含needle
+    fn assert_invalid(desc: SamplerDesc, needle: &str) {
+        match desc.validate() {
+            Err(WgpuAppError::InvalidSampler(message)) => {
+                assert!(message.contains(needle), "`{message}` lacks `{needle}`");
+            }
+            other => panic!("expected InvalidSampler for {desc:?}, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn presets_are_valid() {
+        for desc in [
+            SamplerDesc::linear_repeat(),
+            SamplerDesc::linear_clamp(),
+            SamplerDesc::nearest_clamp(),
+            SamplerDesc::shadow_compare(),
+            SamplerDesc::linear_repeat().with_anisotropy(MAX_ANISOTROPY),
+        ] {
+            assert!(desc.validate().is_ok(), "{desc:?}");
+        }
+    }
+
+    #[test]
+    fn anisotropy_outside_the_range_is_rejected() {
+        assert_invalid(SamplerDesc::linear_repeat().with_anisotropy(0), "got 0");
+        assert_invalid(
+            SamplerDesc::linear_repeat().with_anisotropy(MAX_ANISOTROPY + 1),
+            "got 17",
+        );
+    }
+
+    #[test]
+    fn anisotropy_with_a_nearest_filter_is_rejected() {
+        assert_invalid(
+            SamplerDesc::nearest_clamp().with_anisotropy(4),
+            "requires linear",
+        );
+        // 只有mipmap过滤是最近邻也不行
+        assert_invalid(
+            SamplerDesc::shadow_compare().with_anisotropy(4),
+            "requires linear",
+        );
+        // 关闭各向异性过滤时最近邻没有问题
+        assert!(
+            SamplerDesc::nearest_clamp()
+                .with_anisotropy(1)
+                .validate()
+                .is_ok()
+        );
+    }
+
+    #[test]
+    fn invalid_lod_ranges_are_rejected() {
+        assert_invalid(SamplerDesc::linear_repeat().with_lod_clamp(4.0, 2.0), "LOD");
+        assert_invalid(
+            SamplerDesc::linear_repeat().with_lod_clamp(-1.0, 2.0),
+            "LOD",
+        );
+        for (min, max) in [
+            (f32::NAN, 2.0),
+            (0.0, f32::NAN),
+            (f32::NAN, f32::NAN),
+            (0.0, f32::INFINITY),
+            (f32::NEG_INFINITY, 0.0),
+        ] {
+            assert_invalid(SamplerDesc::linear_repeat().with_lod_clamp(min, max), "LOD");
+        }
+        // 只使用第0级的空范围是合法的
+        assert!(
+            SamplerDesc::linear_repeat()
+                .with_lod_clamp(0.0, 0.0)
+                .validate()
+                .is_ok()
+        );
+    }
+}
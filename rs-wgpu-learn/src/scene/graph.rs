@@ -0,0 +1,313 @@
+use crate::math::Transform;
+use glam::Mat4;
+
+/// 场景图中节点的句柄（节点数组中的下标），只在创建它的场景图中有效
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct NodeId(usize);
+
+impl NodeId {
+    /// 节点在场景图中的下标，与`SceneGraph::world_transforms`的下标一致
+    pub fn index(self) -> usize {
+        self.0
+    }
+}
+
+/// 场景图中的节点：相对父节点的本地变换、可选的网格和材质句柄，以及父子关系
+///
+/// 网格和材质句柄是使用者自己管理的数组中的下标，场景图只负责保存。
+#[derive(Debug, Clone)]
+pub struct Node {
+    pub name: String,            // 名称（调试和日志用，可以为空）
+    pub local: Transform,        // 相对父节点（根节点相对世界）的变换
+    pub mesh: Option<usize>,     // 网格句柄
+    pub material: Option<usize>, // 材质句柄
+    parent: Option<NodeId>,      // 父节点，根节点为None
+    children: Vec<NodeId>,       // 子节点，按添加顺序
+}
+
+impl Node {
+    /// 没有网格和材质的节点
+    pub fn new(name: impl Into<String>, local: Transform) -> Self {
+        Self {
+            name: name.into(),
+            local,
+            mesh: None,
+            material: None,
+            parent: None,
+            children: Vec::new(),
+        }
+    }
+
+    /// 设置网格句柄
+    pub fn with_mesh(mut self, mesh: usize) -> Self {
+        self.mesh = Some(mesh);
+        self
+    }
+
+    /// 设置材质句柄
+    pub fn with_material(mut self, material: usize) -> Self {
+        self.material = Some(material);
+        self
+    }
+
+    /// 父节点
+    pub fn parent(&self) -> Option<NodeId> {
+        self.parent
+    }
+
+    /// 子节点
+    pub fn children(&self) -> &[NodeId] {
+        &self.children
+    }
+}
+
+/// 场景图：节点保存在数组中，父子关系用下标表示（不使用Rc，不会产生引用环）
+///
+/// 修改节点的本地变换后调用`update_world_transforms`，从根节点出发遍历一次，
+/// 按“父节点的世界矩阵 × 本地矩阵”填写所有节点的世界矩阵，结果可直接用于实例或uniform数据。
+#[derive(Debug, Clone, Default)]
+pub struct SceneGraph {
+    nodes: Vec<Node>,
+    roots: Vec<NodeId>,
+    world: Vec<Mat4>, // 与nodes一一对应的世界矩阵
+}
+
+impl SceneGraph {
+    /// 创建空的场景图
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 添加节点，parent为None时作为根节点；新节点的世界矩阵在下一次`update_world_transforms`后有效
+    ///
+    /// # Panics
+    /// parent不属于该场景图时panic。
+    pub fn add(&mut self, mut node: Node, parent: Option<NodeId>) -> NodeId {
+        let id = NodeId(self.nodes.len());
+        node.parent = parent;
+        node.children.clear();
+        match parent {
+            Some(parent) => self.nodes[parent.0].children.push(id),
+            None => self.roots.push(id),
+        }
+        self.nodes.push(node);
+        self.world.push(Mat4::IDENTITY);
+        id
+    }
+
+    /// 节点数
+    pub fn len(&self) -> usize {
+        self.nodes.len()
+    }
+
+    /// 是否没有节点
+    pub fn is_empty(&self) -> bool {
+        self.nodes.is_empty()
+    }
+
+    /// 节点
+    pub fn node(&self, id: NodeId) -> &Node {
+        &self.nodes[id.0]
+    }
+
+    /// 可修改的节点（父子关系只能通过`set_parent`修改）
+    pub fn node_mut(&mut self, id: NodeId) -> &mut Node {
+        &mut self.nodes[id.0]
+    }
+
+    /// 根节点，按添加顺序
+    pub fn roots(&self) -> &[NodeId] {
+        &self.roots
+    }
+
+    /// 所有节点及其句柄，按添加顺序
+    pub fn iter(&self) -> impl Iterator<Item = (NodeId, &Node)> {
+        self.nodes
+            .iter()
+            .enumerate()
+            .map(|(i, node)| (NodeId(i), node))
+    }
+
+    /// 从根节点深度优先遍历一次，更新所有节点的世界矩阵
+    ///
+    /// 用显式的栈代替递归，层级很深时也不会栈溢出；父节点总是先于子节点出栈，
+    /// 所以处理子节点时父节点的世界矩阵已经是本帧的结果。
+    pub fn update_world_transforms(&mut self) {
+        let mut stack = self
+            .roots
+            .iter()
+            .rev()
+            .map(|&root| (root, Mat4::IDENTITY))
+            .collect::<Vec<_>>();
+        while let Some((id, parent_world)) = stack.pop() {
+            let node = &self.nodes[id.0];
+            let world = parent_world * node.local.matrix();
+            self.world[id.0] = world;
+            stack.extend(node.children.iter().rev().map(|&child| (child, world)));
+        }
+    }
+
+    /// 所有节点的世界矩阵（下标为`NodeId::index`），上一次`update_world_transforms`的结果
+    pub fn world_transforms(&self) -> &[Mat4] {
+        &self.world
+    }
+
+    /// 节点的世界矩阵，上一次`update_world_transforms`的结果
+    pub fn world_transform(&self, id: NodeId) -> Mat4 {
+        self.world[id.0]
+    }
+
+    /// 沿父节点链重新计算节点当前的世界矩阵（不依赖`update_world_transforms`的结果）
+    pub fn compute_world_transform(&self, id: NodeId) -> Mat4 {
+        let mut world = self.nodes[id.0].local.matrix();
+        let mut parent = self.nodes[id.0].parent;
+        while let Some(id) = parent {
+            world = self.nodes[id.0].local.matrix() * world;
+            parent = self.nodes[id.0].parent;
+        }
+        world
+    }
+
+    /// ancestor是否是node本身或它的祖先
+    pub fn is_ancestor(&self, ancestor: NodeId, node: NodeId) -> bool {
+        let mut current = Some(node);
+        while let Some(id) = current {
+            if id == ancestor {
+                return true;
+            }
+            current = self.nodes[id.0].parent;
+        }
+        false
+    }
+
+    /// 把节点移到新的父节点下（None时成为根节点），重新计算本地变换使世界变换保持不变
+    ///
+    /// 新的本地矩阵为“新父节点世界矩阵的逆 × 节点的世界矩阵”，再分解为平移、旋转和缩放；
+    /// 父节点链中有非均匀缩放和旋转时世界矩阵含有切变，分解的结果是近似的。
+    ///
+    /// # Panics
+    /// parent是node本身或它的后代（会形成环）时panic。
+    pub fn set_parent(&mut self, node: NodeId, parent: Option<NodeId>) {
+        if let Some(parent) = parent {
+            assert!(
+                !self.is_ancestor(node, parent),
+                "cannot parent node {:?} to itself or one of its descendants",
+                self.nodes[node.0].name
+            );
+        }
+        let world = self.compute_world_transform(node);
+        let parent_world = parent.map_or(Mat4::IDENTITY, |parent| {
+            self.compute_world_transform(parent)
+        });
+        self.nodes[node.0].local = Transform::from_matrix(parent_world.inverse() * world);
+
+        // 从原来的父节点（或根节点列表）中移除，再加入新的父节点
+        let siblings = match self.nodes[node.0].parent {
+            Some(old) => &mut self.nodes[old.0].children,
+            None => &mut self.roots,
+        };
+        siblings.retain(|&id| id != node);
+        match parent {
+            Some(parent) => self.nodes[parent.0].children.push(node),
+            None => self.roots.push(node),
+        }
+        self.nodes[node.0].parent = parent;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use glam::{Quat, Vec3};
+
+    /// 允许的浮点误差
+    const TOLERANCE: f32 = 1e-4;
+
+    /// 太阳→行星→卫星三层的场景图：行星绕太阳转90°并放大2倍，卫星相对行星平移
+    fn solar_system() -> (SceneGraph, [NodeId; 3]) {
+        let mut graph = SceneGraph::new();
+        let sun = graph.add(
+            Node::new("sun", Transform::from_translation(Vec3::new(1.0, 0.0, 0.0))),
+            None,
+        );
+        let planet = graph.add(
+            Node::new(
+                "planet",
+                Transform {
+                    translation: Vec3::new(10.0, 0.0, 0.0),
+                    rotation: Quat::from_rotation_y(std::f32::consts::FRAC_PI_2),
+                    scale: Vec3::splat(2.0),
+                },
+            ),
+            Some(sun),
+        );
+        let moon = graph.add(
+            Node::new(
+                "moon",
+                Transform::from_translation(Vec3::new(3.0, 0.0, 0.0)),
+            ),
+            Some(planet),
+        );
+        (graph, [sun, planet, moon])
+    }
+
+    #[test]
+    fn update_world_transforms_composes_the_parent_chain() {
+        let (mut graph, [sun, planet, moon]) = solar_system();
+        graph.update_world_transforms();
+
+        // 太阳在(1, 0, 0)，行星在太阳右边10个单位
+        let origin = |id| graph.world_transform(id).transform_point3(Vec3::ZERO);
+        assert!(origin(sun).abs_diff_eq(Vec3::new(1.0, 0.0, 0.0), TOLERANCE));
+        assert!(origin(planet).abs_diff_eq(Vec3::new(11.0, 0.0, 0.0), TOLERANCE));
+        // 卫星的+X偏移经行星的旋转变为-Z，再放大2倍
+        assert!(origin(moon).abs_diff_eq(Vec3::new(11.0, 0.0, -6.0), TOLERANCE));
+
+        for id in [sun, planet, moon] {
+            assert!(
+                graph
+                    .world_transform(id)
+                    .abs_diff_eq(graph.compute_world_transform(id), TOLERANCE)
+            );
+        }
+        assert_eq!(graph.world_transforms().len(), 3);
+    }
+
+    #[test]
+    fn set_parent_keeps_the_world_transform() {
+        let (mut graph, [sun, planet, moon]) = solar_system();
+        let before = graph.compute_world_transform(moon);
+
+        // 移到太阳下
+        graph.set_parent(moon, Some(sun));
+        assert_eq!(graph.node(moon).parent(), Some(sun));
+        assert_eq!(graph.node(sun).children(), &[planet, moon]);
+        assert!(graph.node(planet).children().is_empty());
+        assert!(
+            graph
+                .compute_world_transform(moon)
+                .abs_diff_eq(before, TOLERANCE)
+        );
+
+        // 再变为根节点
+        graph.set_parent(moon, None);
+        assert_eq!(graph.node(moon).parent(), None);
+        assert_eq!(graph.roots(), &[sun, moon]);
+        assert_eq!(graph.node(sun).children(), &[planet]);
+        assert!(
+            graph
+                .compute_world_transform(moon)
+                .abs_diff_eq(before, TOLERANCE)
+        );
+
+        graph.update_world_transforms();
+        assert!(graph.world_transform(moon).abs_diff_eq(before, TOLERANCE));
+    }
+
+    #[test]
+    #[should_panic(expected = "one of its descendants")]
+    fn set_parent_to_a_descendant_panics() {
+        let (mut graph, [sun, _, moon]) = solar_system();
+        graph.set_parent(sun, Some(moon));
+    }
+}
@@ -0,0 +1,159 @@
+mod graph;
+
+use crate::{InitContext, InputState, RenderContext, Renderer, WgpuAppError};
+pub use graph::{Node, NodeId, SceneGraph};
+use std::{path::Path, time::Duration};
+use winit::event::WindowEvent;
+
+/// 可以拖放到窗口上的文件类型（扩展名），其他文件记录日志后忽略
+pub const DROPPABLE_EXTENSIONS: &[&str] = &["wgsl", "png", "obj", "gltf", "glb"];
+
+/// 场景的重绘方式
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum RedrawMode {
+    /// 每帧都重绘（动画场景）
+    #[default]
+    Continuous,
+    /// 只在场景需要（见`Scene::needs_redraw`）、窗口尺寸或可见性变化时重绘（静态场景）
+    OnDemand,
+}
+
+/// 场景：在渲染器的基础上增加每帧逻辑、输入处理和尺寸变化回调
+pub trait Scene: Renderer {
+    /// 每帧渲染前调用，dt为距上一帧的时间，input为当前的键盘和鼠标输入状态
+    fn update(&mut self, _dt: Duration, _queue: &wgpu::Queue, _input: &InputState) {}
+
+    /// 以固定步长调用（每帧零到多次，在update之前），dt为步长（秒）
+    ///
+    /// 适合物理模拟等需要与刷新率无关的逻辑，渲染时用`RenderContext::alpha`在前后两个状态间插值。
+    fn fixed_update(&mut self, _dt: f32) {}
+
+    /// 处理窗口事件，返回true表示事件已被场景消费
+    fn input(&mut self, _event: &WindowEvent) -> bool {
+        false
+    }
+
+    /// 表面尺寸变化后调用，默认转发给`Renderer::resize`
+    fn resized(&mut self, _device: &wgpu::Device, config: &wgpu::SurfaceConfiguration) {
+        self.resize(config);
+    }
+
+    /// 重绘方式，默认每帧都重绘
+    fn redraw_mode(&self) -> RedrawMode {
+        RedrawMode::Continuous
+    }
+
+    /// 按需重绘时，场景内容是否变化需要重绘（处理完每个窗口事件及每帧渲染后查询，返回true后应清除标记）
+    fn needs_redraw(&mut self) -> bool {
+        false
+    }
+
+    /// 显示在窗口标题中的场景状态（如绘制调用数），默认没有
+    fn status(&self) -> Option<String> {
+        None
+    }
+
+    /// 场景是否按`RenderContext::wireframe`切换线框（通常借助`PipelineSet`），默认不支持
+    ///
+    /// 不支持时按Z键只会在标题中提示线框不可用。
+    fn supports_wireframe(&self) -> bool {
+        false
+    }
+
+    /// 文件拖放到窗口上时调用（一次拖放多个文件时按顺序逐个调用），返回场景是否使用了该文件
+    ///
+    /// 只会收到`DROPPABLE_EXTENSIONS`中列出的文件类型；加载失败时场景自行记录错误并保持原状。
+    fn file_dropped(&mut self, _path: &Path, _device: &wgpu::Device, _queue: &wgpu::Queue) -> bool {
+        false
+    }
+
+    /// 应用正常退出前调用（此时已提交的GPU工作都已完成），用于保存状态或输出录制结果等
+    fn shutdown(&mut self) {}
+}
+
+/// 装箱的场景：具体类型在运行时才确定（例如通过命令行选择演示）
+#[cfg(not(target_arch = "wasm32"))]
+pub type BoxedScene = Box<dyn Scene + Send>;
+/// 装箱的场景：具体类型在运行时才确定（例如通过命令行选择演示）
+#[cfg(target_arch = "wasm32")]
+pub type BoxedScene = Box<dyn Scene>;
+
+impl Renderer for BoxedScene {
+    /// 装箱的场景不知道要创建哪种具体场景，需通过`WgpuAppBuilder::build_with`传入构造函数
+    fn init(_ctx: &InitContext) -> Result<Self, WgpuAppError> {
+        Err(WgpuAppError::NoRendererInit)
+    }
+
+    fn render(
+        &mut self,
+        ctx: &RenderContext,
+        view: &wgpu::TextureView,
+        encoder: &mut wgpu::CommandEncoder,
+    ) {
+        (**self).render(ctx, view, encoder);
+    }
+
+    fn resize(&mut self, config: &wgpu::SurfaceConfiguration) {
+        (**self).resize(config);
+    }
+
+    fn sample_count_changed(
+        &mut self,
+        device: &wgpu::Device,
+        config: &wgpu::SurfaceConfiguration,
+        sample_count: u32,
+    ) -> Result<(), WgpuAppError> {
+        (**self).sample_count_changed(device, config, sample_count)
+    }
+
+    fn shader_changed(
+        &mut self,
+        device: &wgpu::Device,
+        config: &wgpu::SurfaceConfiguration,
+        path: &Path,
+    ) {
+        (**self).shader_changed(device, config, path);
+    }
+}
+
+impl Scene for BoxedScene {
+    fn update(&mut self, dt: Duration, queue: &wgpu::Queue, input: &InputState) {
+        (**self).update(dt, queue, input);
+    }
+
+    fn fixed_update(&mut self, dt: f32) {
+        (**self).fixed_update(dt);
+    }
+
+    fn input(&mut self, event: &WindowEvent) -> bool {
+        (**self).input(event)
+    }
+
+    fn resized(&mut self, device: &wgpu::Device, config: &wgpu::SurfaceConfiguration) {
+        (**self).resized(device, config);
+    }
+
+    fn redraw_mode(&self) -> RedrawMode {
+        (**self).redraw_mode()
+    }
+
+    fn needs_redraw(&mut self) -> bool {
+        (**self).needs_redraw()
+    }
+
+    fn status(&self) -> Option<String> {
+        (**self).status()
+    }
+
+    fn supports_wireframe(&self) -> bool {
+        (**self).supports_wireframe()
+    }
+
+    fn file_dropped(&mut self, path: &Path, device: &wgpu::Device, queue: &wgpu::Queue) -> bool {
+        (**self).file_dropped(path, device, queue)
+    }
+
+    fn shutdown(&mut self) {
+        (**self).shutdown();
+    }
+}
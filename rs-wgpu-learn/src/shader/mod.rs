@@ -0,0 +1,279 @@
+mod preprocess;
+
+use crate::WgpuAppError;
+#[cfg(not(target_arch = "wasm32"))]
+use log::{debug, info};
+pub use preprocess::{ExpandedSource, SourceMap, preprocess};
+use std::{
+    collections::HashMap,
+    path::{Path, PathBuf},
+};
+use thiserror::Error;
+
+/// 着色器解析或验证错误，message中包含出错的文件、行号和列标记
+#[derive(Debug, Error)]
+#[error("{message}")]
+pub struct ShaderError {
+    pub path: PathBuf,   // 出错的着色器文件
+    pub message: String, // 格式化后的错误信息
+}
+
+/// 默认的着色器资源目录（相对于工作目录）
+const DEFAULT_ASSET_DIR: &str = "./source";
+
+/// 编译进二进制的着色器副本，磁盘上找不到文件时使用（例如`cargo install`后运行）
+const EMBEDDED_SHADERS: &[(&str, &str)] = &[
+    (
+        "bouncing_quad.wgsl",
+        include_str!("../../../source/bouncing_quad.wgsl"),
+    ),
+    (
+        "brick_cube.wgsl",
+        include_str!("../../../source/brick_cube.wgsl"),
+    ),
+    ("common.wgsl", include_str!("../../../source/common.wgsl")),
+    (
+        "debug_lines.wgsl",
+        include_str!("../../../source/debug_lines.wgsl"),
+    ),
+    (
+        "depth_quads.wgsl",
+        include_str!("../../../source/depth_quads.wgsl"),
+    ),
+    (
+        "storage_wobble.wgsl",
+        include_str!("../../../source/storage_wobble.wgsl"),
+    ),
+    (
+        "storage_wobble_compute.wgsl",
+        include_str!("../../../source/storage_wobble_compute.wgsl"),
+    ),
+    ("mipmap.wgsl", include_str!("../../../source/mipmap.wgsl")),
+    (
+        "mipmap_plane.wgsl",
+        include_str!("../../../source/mipmap_plane.wgsl"),
+    ),
+    (
+        "texture_array.wgsl",
+        include_str!("../../../source/texture_array.wgsl"),
+    ),
+    (
+        "textured_quad.wgsl",
+        include_str!("../../../source/textured_quad.wgsl"),
+    ),
+    (
+        "triangle.wgsl",
+        include_str!("../../../source/triangle.wgsl"),
+    ),
+    (
+        "dynamic_uniform.wgsl",
+        include_str!("../../../source/dynamic_uniform.wgsl"),
+    ),
+    (
+        "gltf_viewer.wgsl",
+        include_str!("../../../source/gltf_viewer.wgsl"),
+    ),
+    (
+        "equirect_to_cube.wgsl",
+        include_str!("../../../source/equirect_to_cube.wgsl"),
+    ),
+    ("gizmo.wgsl", include_str!("../../../source/gizmo.wgsl")),
+    ("globals.wgsl", include_str!("../../../source/globals.wgsl")),
+    ("grid.wgsl", include_str!("../../../source/grid.wgsl")),
+    (
+        "instancing.wgsl",
+        include_str!("../../../source/instancing.wgsl"),
+    ),
+    (
+        "multi_draw.wgsl",
+        include_str!("../../../source/multi_draw.wgsl"),
+    ),
+    (
+        "noise_fog.wgsl",
+        include_str!("../../../source/noise_fog.wgsl"),
+    ),
+    (
+        "noise_volume.wgsl",
+        include_str!("../../../source/noise_volume.wgsl"),
+    ),
+    (
+        "normal_map.wgsl",
+        include_str!("../../../source/normal_map.wgsl"),
+    ),
+    (
+        "obj_viewer.wgsl",
+        include_str!("../../../source/obj_viewer.wgsl"),
+    ),
+    ("pbr.wgsl", include_str!("../../../source/pbr.wgsl")),
+    (
+        "push_constants.wgsl",
+        include_str!("../../../source/push_constants.wgsl"),
+    ),
+    (
+        "rotating_triangle.wgsl",
+        include_str!("../../../source/rotating_triangle.wgsl"),
+    ),
+    (
+        "shadertoy.wgsl",
+        include_str!("../../../source/shadertoy.wgsl"),
+    ),
+    ("skybox.wgsl", include_str!("../../../source/skybox.wgsl")),
+    ("tonemap.wgsl", include_str!("../../../source/tonemap.wgsl")),
+    (
+        "vertex_color.wgsl",
+        include_str!("../../../source/vertex_color.wgsl"),
+    ),
+];
+
+/// 着色器资源根目录：优先使用`ASSET_DIR`环境变量，否则为`./source`
+pub fn asset_root() -> PathBuf {
+    std::env::var_os("ASSET_DIR")
+        .map(PathBuf::from)
+        .unwrap_or_else(|| PathBuf::from(DEFAULT_ASSET_DIR))
+}
+
+/// 解析着色器文件的绝对路径（用于错误信息和文件监听）
+pub fn resolve(path: impl AsRef<Path>) -> PathBuf {
+    let path = asset_root().join(path);
+    std::path::absolute(&path).unwrap_or(path)
+}
+
+/// 读取着色器源码，文件不存在时回退到内嵌副本
+///
+/// 浏览器上没有文件系统，直接使用内嵌副本。
+pub fn read_source(path: impl AsRef<Path>) -> Result<String, WgpuAppError> {
+    let path = path.as_ref();
+    #[cfg(target_arch = "wasm32")]
+    return embedded(path)
+        .map(str::to_string)
+        .ok_or_else(|| WgpuAppError::ShaderLoad {
+            path: path.to_path_buf(),
+            source: std::io::ErrorKind::NotFound.into(),
+        });
+    #[cfg(not(target_arch = "wasm32"))]
+    read_file(path)
+}
+
+/// 从资源目录读取着色器源码，文件不存在时回退到内嵌副本
+#[cfg(not(target_arch = "wasm32"))]
+fn read_file(path: &Path) -> Result<String, WgpuAppError> {
+    let resolved = resolve(path);
+    match std::fs::read_to_string(&resolved) {
+        Ok(source) => {
+            debug!("Loaded shader from {}", resolved.display());
+            Ok(source)
+        }
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => embedded(path)
+            .map(|source| {
+                info!(
+                    "Shader {} not found, using embedded copy",
+                    resolved.display()
+                );
+                source.to_string()
+            })
+            .ok_or(WgpuAppError::ShaderLoad {
+                path: resolved,
+                source: e,
+            }),
+        Err(e) => Err(WgpuAppError::ShaderLoad {
+            path: resolved,
+            source: e,
+        }),
+    }
+}
+
+/// 在运行时从资源目录加载WGSL着色器并创建着色器模块
+pub fn load(
+    device: &wgpu::Device,
+    path: impl AsRef<Path>,
+) -> Result<wgpu::ShaderModule, WgpuAppError> {
+    load_with_defines(device, path, &HashMap::new())
+}
+
+/// 加载着色器，经过预处理（展开include、替换常量）后创建着色器模块
+pub fn load_with_defines(
+    device: &wgpu::Device,
+    path: impl AsRef<Path>,
+    defines: &HashMap<String, String>,
+) -> Result<wgpu::ShaderModule, WgpuAppError> {
+    let path = path.as_ref();
+    let expanded = preprocess(path, defines)?;
+    // 先用naga验证，得到可读的错误信息，避免wgpu在设备层面报出难以阅读的错误
+    expanded.validate()?;
+    Ok(device.create_shader_module(wgpu::ShaderModuleDescriptor {
+        label: path.to_str(),
+        source: wgpu::ShaderSource::Wgsl(expanded.source.into()),
+    }))
+}
+
+/// 判断着色器（包括其include的文件）是否依赖指定文件，用于热重载时筛选需要重建的管线
+pub fn depends_on(shader: impl AsRef<Path>, changed: impl AsRef<Path>) -> bool {
+    let (shader, changed) = (shader.as_ref(), changed.as_ref());
+    match preprocess(shader, &HashMap::new()) {
+        Ok(expanded) => expanded
+            .source_map
+            .files()
+            .iter()
+            .any(|file| file == changed),
+        // 展开失败（例如include写错）时至少在根文件修改后重试
+        Err(_) => shader == changed,
+    }
+}
+
+/// 使用naga解析并验证WGSL源码
+///
+/// 错误信息的格式与naga的`emit_to_string`一致：文件路径、出错行及列下方的插入符。
+pub fn validate(source: &str, path: &Path) -> Result<(), ShaderError> {
+    let path_str = path.display().to_string();
+    let module = naga::front::wgsl::parse_str(source).map_err(|e| ShaderError {
+        path: path.to_path_buf(),
+        message: e.emit_to_string_with_path(source, &path_str),
+    })?;
+    naga::valid::Validator::new(
+        naga::valid::ValidationFlags::all(),
+        naga::valid::Capabilities::all(),
+    )
+    .validate(&module)
+    .map_err(|e| ShaderError {
+        path: path.to_path_buf(),
+        message: e.emit_to_string_with_path(source, &path_str),
+    })?;
+    Ok(())
+}
+
+/// 查找内嵌的着色器副本，路径不匹配时再按文件名查找
+///
+/// 资源目录之外的着色器（如拖放到窗口上的文件）因此也能include`common.wgsl`、`globals.wgsl`等共用文件。
+fn embedded(path: &Path) -> Option<&'static str> {
+    EMBEDDED_SHADERS
+        .iter()
+        .find(|(name, _)| Path::new(name) == path)
+        .or_else(|| {
+            let file_name = path.file_name()?;
+            EMBEDDED_SHADERS
+                .iter()
+                .find(|(name, _)| Path::new(name).file_name() == Some(file_name))
+        })
+        .map(|(_, source)| *source)
+}
+
+/// 捕获闭包执行期间产生的验证错误，而不是交给设备的未捕获错误处理（只记录日志，调用方无从得知）
+///
+/// 用于热重载等着色器可能写错、但程序需要继续运行的场景。
+/// wasm上无法阻塞等待错误作用域的结果，此时直接执行闭包。
+pub fn catch_validation<T>(device: &wgpu::Device, f: impl FnOnce() -> T) -> Result<T, wgpu::Error> {
+    #[cfg(target_arch = "wasm32")]
+    {
+        let _ = device;
+        Ok(f())
+    }
+    #[cfg(not(target_arch = "wasm32"))]
+    {
+        device.push_error_scope(wgpu::ErrorFilter::Validation);
+        let value = f();
+        match pollster::block_on(device.pop_error_scope()) {
+            Some(error) => Err(error),
+            None => Ok(value),
+        }
+    }
+}
@@ -0,0 +1,405 @@
+use super::{ShaderError, read_source};
+use crate::WgpuAppError;
+use std::{
+    collections::HashMap,
+    path::{Component, Path, PathBuf},
+};
+
+/// include的最大嵌套深度
+const MAX_INCLUDE_DEPTH: usize = 16;
+
+/// 源码映射：记录展开后每一行来自哪个文件的哪一行
+#[derive(Debug, Clone, Default)]
+pub struct SourceMap {
+    files: Vec<PathBuf>,      // 参与展开的所有文件
+    lines: Vec<(usize, u32)>, // 展开后第i行对应的(文件序号, 原始行号)
+}
+
+impl SourceMap {
+    /// 查询展开后的行（从1开始）对应的原始文件和行号
+    pub fn lookup(&self, line: u32) -> Option<(&Path, u32)> {
+        let (file, line) = *self.lines.get(line.checked_sub(1)? as usize)?;
+        Some((&self.files[file], line))
+    }
+
+    /// 参与展开的所有文件（包括根文件），路径相对于资源目录
+    pub fn files(&self) -> &[PathBuf] {
+        &self.files
+    }
+}
+
+/// 预处理后的着色器源码
+#[derive(Debug, Clone)]
+pub struct ExpandedSource {
+    pub source: String,        // 完全展开后的WGSL源码
+    pub source_map: SourceMap, // 展开后行号到原始文件的映射
+}
+
+impl ExpandedSource {
+    /// 使用naga验证展开后的源码，错误信息指向原始文件和行号
+    pub fn validate(&self) -> Result<(), ShaderError> {
+        let module = naga::front::wgsl::parse_str(&self.source)
+            .map_err(|e| self.error(e.message(), e.location(&self.source), None))?;
+        naga::valid::Validator::new(
+            naga::valid::ValidationFlags::all(),
+            naga::valid::Capabilities::all(),
+        )
+        .validate(&module)
+        .map_err(|e| {
+            let label = e.spans().next().map(|(_, label)| label.as_str());
+            self.error(&e.as_inner().to_string(), e.location(&self.source), label)
+        })?;
+        Ok(())
+    }
+
+    /// 生成与naga风格一致的错误信息：原始文件路径、行号、出错行和列下方的插入符
+    fn error(
+        &self,
+        message: &str,
+        location: Option<naga::SourceLocation>,
+        label: Option<&str>,
+    ) -> ShaderError {
+        let root = super::resolve(&self.source_map.files[0]);
+        let Some((location, (path, line))) =
+            location.and_then(|loc| Some((loc, self.source_map.lookup(loc.line_number)?)))
+        else {
+            return ShaderError {
+                message: format!("error: {message}\n  --> {}", root.display()),
+                path: root,
+            };
+        };
+
+        // 显示展开后的行内容（#define替换后列号以展开结果为准）
+        let text = self
+            .source
+            .lines()
+            .nth(location.line_number as usize - 1)
+            .unwrap_or_default();
+        let start = (location.line_position as usize - 1).min(text.len());
+        let column = text
+            .get(..start)
+            .map_or(start, |prefix| prefix.chars().count());
+        let width = (location.length as usize).clamp(1, text.len().saturating_sub(start).max(1));
+        let gutter = " ".repeat(line.to_string().len());
+        let path = super::resolve(path);
+        ShaderError {
+            message: format!(
+                "error: {message}\n\
+                 {gutter}--> {}:{line}:{}\n\
+                 {gutter} |\n\
+                 {line} | {text}\n\
+                 {gutter} | {}{} {}",
+                path.display(),
+                column + 1,
+                " ".repeat(column),
+                "^".repeat(width),
+                label.unwrap_or_default(),
+            )
+            .trim_end()
+            .to_string(),
+            path,
+        }
+    }
+}
+
+/// 展开着色器中的`#include "file.wgsl"`并替换`//#define NAME value`常量
+///
+/// include路径相对于包含它的文件解析；defines中的值优先于文件内`//#define`给出的默认值。
+pub fn preprocess(
+    path: impl AsRef<Path>,
+    defines: &HashMap<String, String>,
+) -> Result<ExpandedSource, WgpuAppError> {
+    let mut expander = Expander {
+        defines: defines.clone(),
+        stack: Vec::new(),
+        source: String::new(),
+        source_map: SourceMap::default(),
+    };
+    expander.expand(path.as_ref(), None)?;
+    Ok(ExpandedSource {
+        source: expander.source,
+        source_map: expander.source_map,
+    })
+}
+
+/// 预处理的中间状态
+struct Expander {
+    defines: HashMap<String, String>, // 当前生效的常量
+    stack: Vec<PathBuf>,              // 当前include链，用于检测循环
+    source: String,                   // 已展开的源码
+    source_map: SourceMap,            // 已展开部分的源码映射
+}
+
+impl Expander {
+    /// 递归展开一个文件，included_from为包含它的文件及行号
+    fn expand(
+        &mut self,
+        path: &Path,
+        included_from: Option<(&Path, u32)>,
+    ) -> Result<(), WgpuAppError> {
+        if self.stack.iter().any(|p| p == path) {
+            let chain = self
+                .stack
+                .iter()
+                .chain(std::iter::once(&path.to_path_buf()))
+                .map(|p| p.display().to_string())
+                .collect::<Vec<_>>()
+                .join(" -> ");
+            return Err(include_error(
+                included_from,
+                format!("include cycle: {chain}"),
+            ));
+        }
+        if self.stack.len() >= MAX_INCLUDE_DEPTH {
+            return Err(include_error(
+                included_from,
+                format!("include depth exceeds {MAX_INCLUDE_DEPTH}"),
+            ));
+        }
+
+        let source = read_source(path)?;
+        self.stack.push(path.to_path_buf());
+        let file = self.source_map.files.len();
+        self.source_map.files.push(path.to_path_buf());
+
+        for (index, line) in source.lines().enumerate() {
+            let line_number = index as u32 + 1;
+            let trimmed = line.trim_start();
+            if let Some(rest) = trimmed.strip_prefix("#include") {
+                let Some(name) = rest
+                    .trim()
+                    .strip_prefix('"')
+                    .and_then(|r| r.strip_suffix('"'))
+                else {
+                    return Err(include_error(
+                        Some((path, line_number)),
+                        format!("malformed include directive `{trimmed}`"),
+                    ));
+                };
+                let target = normalize(&path.parent().unwrap_or(Path::new("")).join(name));
+                self.expand(&target, Some((path, line_number)))?;
+                continue;
+            }
+            if let Some(rest) = trimmed.strip_prefix("//#define") {
+                let mut parts = rest.trim().splitn(2, char::is_whitespace);
+                if let Some(name) = parts.next().filter(|name| !name.is_empty()) {
+                    let value = parts.next().unwrap_or_default().trim().to_string();
+                    self.defines.entry(name.to_string()).or_insert(value);
+                }
+            }
+            let line = substitute(line, &self.defines);
+            self.source.push_str(&line);
+            self.source.push('\n');
+            self.source_map.lines.push((file, line_number));
+        }
+
+        self.stack.pop();
+        Ok(())
+    }
+}
+
+/// 生成指向include指令所在位置的错误
+fn include_error(location: Option<(&Path, u32)>, message: String) -> WgpuAppError {
+    let (path, message) = match location {
+        Some((path, line)) => {
+            let path = super::resolve(path);
+            let message = format!("error: {message}\n  --> {}:{line}", path.display());
+            (path, message)
+        }
+        None => (PathBuf::new(), format!("error: {message}")),
+    };
+    ShaderError { path, message }.into()
+}
+
+/// 按标识符边界替换常量
+fn substitute(line: &str, defines: &HashMap<String, String>) -> String {
+    if defines.is_empty() || line.trim_start().starts_with("//#define") {
+        return line.to_string();
+    }
+    let mut output = String::with_capacity(line.len());
+    let mut rest = line;
+    while let Some(start) = rest.find(|c: char| c.is_alphabetic() || c == '_') {
+        output.push_str(&rest[..start]);
+        rest = &rest[start..];
+        let end = rest
+            .find(|c: char| !(c.is_alphanumeric() || c == '_'))
+            .unwrap_or(rest.len());
+        let ident = &rest[..end];
+        output.push_str(defines.get(ident).map_or(ident, String::as_str));
+        rest = &rest[end..];
+    }
+    output.push_str(rest);
+    output
+}
+
+/// 词法上规范化路径（处理`.`和`..`），使同一文件的不同写法能被识别为同一个
+fn normalize(path: &Path) -> PathBuf {
+    let mut normalized = PathBuf::new();
+    for component in path.components() {
+        match component {
+            Component::CurDir => {}
+            Component::ParentDir => {
+                normalized.pop();
+            }
+            component => normalized.push(component),
+        }
+    }
+    normalized
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// 在临时目录中写入一组着色器文件，返回目录的绝对路径（绝对路径不受资源目录影响）
+    fn write_files(name: &str, files: &[(&str, &str)]) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!(
+            "rs-wgpu-learn-preprocess-{name}-{}",
+            std::process::id()
+        ));
+        for (path, source) in files {
+            let path = dir.join(path);
+            std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+            std::fs::write(path, source).unwrap();
+        }
+        dir
+    }
+
+    /// 取出错误信息，预处理错误都是`ShaderCompile`
+    fn error_message(error: WgpuAppError) -> String {
+        match error {
+            WgpuAppError::ShaderCompile(e) => e.message,
+            other => panic!("expected a shader error, got {other}"),
+        }
+    }
+
+    #[test]
+    fn nested_includes_expand_in_place_with_source_map() {
+        let dir = write_files(
+            "nested",
+            &[
+                (
+                    "main.wgsl",
+                    "// main\n#include \"lib/a.wgsl\"\nfn main_fn() {}\n",
+                ),
+                ("lib/a.wgsl", "#include \"../common/b.wgsl\"\nfn a() {}\n"),
+                ("common/b.wgsl", "fn b() {}\n"),
+            ],
+        );
+        let expanded = preprocess(dir.join("main.wgsl"), &HashMap::new()).unwrap();
+        assert_eq!(
+            expanded.source,
+            "// main\nfn b() {}\nfn a() {}\nfn main_fn() {}\n"
+        );
+
+        let lookup = |line| {
+            let (path, line) = expanded.source_map.lookup(line).unwrap();
+            (path.strip_prefix(&dir).unwrap().to_path_buf(), line)
+        };
+        assert_eq!(lookup(1), (PathBuf::from("main.wgsl"), 1));
+        assert_eq!(lookup(2), (PathBuf::from("common/b.wgsl"), 1));
+        assert_eq!(lookup(3), (PathBuf::from("lib/a.wgsl"), 2));
+        assert_eq!(lookup(4), (PathBuf::from("main.wgsl"), 3));
+        assert!(expanded.source_map.lookup(0).is_none());
+        assert!(expanded.source_map.lookup(5).is_none());
+        assert_eq!(expanded.source_map.files().len(), 3);
+    }
+
+    #[test]
+    fn including_the_same_file_twice_is_not_a_cycle() {
+        let dir = write_files(
+            "diamond",
+            &[
+                ("main.wgsl", "#include \"a.wgsl\"\n#include \"./b.wgsl\"\n"),
+                ("a.wgsl", "#include \"shared.wgsl\"\n"),
+                ("b.wgsl", "#include \"shared.wgsl\"\n"),
+                ("shared.wgsl", "// shared\n"),
+            ],
+        );
+        let expanded = preprocess(dir.join("main.wgsl"), &HashMap::new()).unwrap();
+        assert_eq!(expanded.source, "// shared\n// shared\n");
+    }
+
+    #[test]
+    fn include_cycle_is_reported_at_the_directive() {
+        let dir = write_files(
+            "cycle",
+            &[
+                ("a.wgsl", "#include \"b.wgsl\"\n"),
+                ("b.wgsl", "// b\n#include \"./a.wgsl\"\n"),
+            ],
+        );
+        let message = error_message(preprocess(dir.join("a.wgsl"), &HashMap::new()).unwrap_err());
+        assert!(message.contains("include cycle"), "{message}");
+        assert!(message.contains("a.wgsl -> "), "{message}");
+        assert!(message.contains("b.wgsl:2"), "{message}");
+    }
+
+    #[test]
+    fn self_include_is_a_cycle() {
+        let dir = write_files("self", &[("a.wgsl", "#include \"a.wgsl\"\n")]);
+        let message = error_message(preprocess(dir.join("a.wgsl"), &HashMap::new()).unwrap_err());
+        assert!(message.contains("include cycle"), "{message}");
+    }
+
+    #[test]
+    fn include_depth_is_limited() {
+        let files = (0..=MAX_INCLUDE_DEPTH)
+            .map(|i| {
+                (
+                    format!("{i}.wgsl"),
+                    format!("#include \"{}.wgsl\"\n", i + 1),
+                )
+            })
+            .collect::<Vec<_>>();
+        let files = files
+            .iter()
+            .map(|(path, source)| (path.as_str(), source.as_str()))
+            .collect::<Vec<_>>();
+        let dir = write_files("depth", &files);
+        let message = error_message(preprocess(dir.join("0.wgsl"), &HashMap::new()).unwrap_err());
+        assert!(message.contains("include depth exceeds"), "{message}");
+    }
+
+    #[test]
+    fn malformed_include_is_an_error() {
+        let dir = write_files("malformed", &[("a.wgsl", "#include common.wgsl\n")]);
+        let message = error_message(preprocess(dir.join("a.wgsl"), &HashMap::new()).unwrap_err());
+        assert!(message.contains("malformed include"), "{message}");
+    }
+
+    #[test]
+    fn defines_substitute_whole_identifiers_and_override_defaults() {
+        let dir = write_files(
+            "defines",
+            &[(
+                "a.wgsl",
+                "//#define COUNT 4\n//#define SCALE 1.0\nconst c = COUNT * SCALE + COUNTER;\n",
+            )],
+        );
+        let defines = HashMap::from([("SCALE".to_string(), "2.5".to_string())]);
+        let expanded = preprocess(dir.join("a.wgsl"), &defines).unwrap();
+        assert_eq!(
+            expanded.source.lines().last(),
+            Some("const c = 4 * 2.5 + COUNTER;")
+        );
+    }
+
+    #[test]
+    fn validation_errors_point_at_the_included_file() {
+        let dir = write_files(
+            "validate",
+            &[
+                ("main.wgsl", "#include \"lib.wgsl\"\nfn main_fn() {}\n"),
+                (
+                    "lib.wgsl",
+                    "fn ok() {}\nfn broken() -> f32 { return 1u; }\n",
+                ),
+            ],
+        );
+        let expanded = preprocess(dir.join("main.wgsl"), &HashMap::new()).unwrap();
+        let error = expanded.validate().unwrap_err();
+        assert!(error.path.ends_with("lib.wgsl"), "{}", error.path.display());
+        assert!(error.message.contains("lib.wgsl:2:"), "{}", error.message);
+    }
+}
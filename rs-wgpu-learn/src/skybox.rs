@@ -0,0 +1,204 @@
+use crate::{
+    CubeMap, DepthTexture, RenderContext, WgpuAppError,
+    binding::{self, BindGroupBuilder, BindGroupLayoutBuilder, BindingLayout},
+    rebuild_pipeline, shader,
+};
+use glam::{Mat3, Mat4};
+use std::path::Path;
+
+/// 天空盒使用的着色器文件
+const SHADER: &str = "skybox.wgsl";
+
+/// 与着色器中SkyboxUniform对应的数据
+#[repr(C)]
+#[derive(Debug, Clone, Copy, bytemuck::Pod, bytemuck::Zeroable)]
+struct SkyboxUniform {
+    inv_view_proj: [[f32; 4]; 4],
+}
+
+/// 用立方体贴图绘制的天空盒：一个位于远平面上的全屏三角形，片元着色器把像素还原成方向后采样立方体贴图
+///
+/// 视图矩阵去掉平移后再求逆，所以天空只随相机旋转，不随相机移动。深度测试为LessEqual且不写入深度，
+/// 要在不透明几何体之后、同一个带深度附件（清除为1.0）的渲染通道中`draw`，只填充没有被几何体覆盖的像素，
+/// 透明物体和参考网格在它之后绘制。与`InfiniteGrid`一样在渲染通道之前`prepare`。只适用于透视投影。
+pub struct SkyboxPass {
+    pub visible: bool, // 是否绘制
+    uniform_buffer: wgpu::Buffer,
+    uniform_bind_group: wgpu::BindGroup,
+    cube_layout: BindingLayout,       // 第1组的布局（替换贴图时使用）
+    cube_bind_group: wgpu::BindGroup, // 第1组：立方体贴图和采样器
+    pipeline: wgpu::RenderPipeline,
+    pipeline_layout: wgpu::PipelineLayout,
+    format: wgpu::TextureFormat,
+    sample_count: u32,
+    cache: Option<wgpu::PipelineCache>,
+}
+
+impl SkyboxPass {
+    /// 创建天空盒，format和sample_count需与绘制时的渲染通道一致
+    pub fn new(
+        device: &wgpu::Device,
+        format: wgpu::TextureFormat,
+        sample_count: u32,
+        cache: Option<&wgpu::PipelineCache>,
+        cube_map: &CubeMap,
+    ) -> Result<Self, WgpuAppError> {
+        let uniform_buffer = device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some("Skybox Uniform Buffer"),
+            size: std::mem::size_of::<SkyboxUniform>() as u64,
+            usage: wgpu::BufferUsages::UNIFORM | wgpu::BufferUsages::COPY_DST,
+            mapped_at_creation: false,
+        });
+        let uniform_layout = BindGroupLayoutBuilder::new()
+            .label("Skybox Uniform Layout")
+            .uniform(wgpu::ShaderStages::FRAGMENT)
+            .build(device);
+        let uniform_bind_group = BindGroupBuilder::new(&uniform_layout)
+            .label("Skybox Uniform Bind Group")
+            .buffer(&uniform_buffer)
+            .build(device);
+        let cube_layout = CubeMap::create_layout(
+            device,
+            "Skybox Cube Map Layout",
+            wgpu::ShaderStages::FRAGMENT,
+        );
+        let pipeline_layout = binding::pipeline_layout(
+            device,
+            Some("Skybox Pipeline Layout"),
+            &[&uniform_layout, &cube_layout],
+        );
+        let shader = shader::load(device, SHADER)?;
+        let pipeline = create_pipeline(
+            device,
+            &shader,
+            format,
+            &pipeline_layout,
+            sample_count,
+            cache,
+        );
+        Ok(Self {
+            visible: true,
+            uniform_buffer,
+            uniform_bind_group,
+            cube_bind_group: cube_map.bind_group(device, &cube_layout),
+            cube_layout,
+            pipeline,
+            pipeline_layout,
+            format,
+            sample_count,
+            cache: cache.cloned(),
+        })
+    }
+
+    /// 换用另一张立方体贴图
+    pub fn set_cube_map(&mut self, device: &wgpu::Device, cube_map: &CubeMap) {
+        self.cube_bind_group = cube_map.bind_group(device, &self.cube_layout);
+    }
+
+    /// 上传去掉平移的逆视图投影矩阵，需在开始渲染通道之前调用
+    pub fn prepare(
+        &self,
+        ctx: &RenderContext,
+        encoder: &mut wgpu::CommandEncoder,
+        view: Mat4,
+        projection: Mat4,
+    ) {
+        if !self.visible {
+            return;
+        }
+        let rotation = Mat4::from_mat3(Mat3::from_mat4(view));
+        let uniform = SkyboxUniform {
+            inv_view_proj: (projection * rotation).inverse().to_cols_array_2d(),
+        };
+        ctx.upload(encoder, &self.uniform_buffer, 0, &uniform);
+    }
+
+    /// 在渲染通道中绘制天空盒（不透明几何体之后）
+    pub fn draw(&self, pass: &mut wgpu::RenderPass) {
+        if !self.visible {
+            return;
+        }
+        pass.set_pipeline(&self.pipeline);
+        pass.set_bind_group(0, &self.uniform_bind_group, &[]);
+        pass.set_bind_group(1, &self.cube_bind_group, &[]);
+        pass.draw(0..3, 0..1);
+    }
+
+    /// 着色器文件变化时重建管线
+    pub fn shader_changed(&mut self, device: &wgpu::Device, path: &Path) {
+        if shader::depends_on(SHADER, path) {
+            let _ = self.rebuild_pipeline(device, self.sample_count);
+        }
+    }
+
+    /// 多重采样数变化时重建管线
+    pub fn sample_count_changed(
+        &mut self,
+        device: &wgpu::Device,
+        sample_count: u32,
+    ) -> Result<(), WgpuAppError> {
+        self.rebuild_pipeline(device, sample_count)
+    }
+
+    /// 用`rebuild_pipeline`按sample_count重建管线，成功后才更新多重采样数
+    fn rebuild_pipeline(
+        &mut self,
+        device: &wgpu::Device,
+        sample_count: u32,
+    ) -> Result<(), WgpuAppError> {
+        rebuild_pipeline(device, SHADER, &mut self.pipeline, |shader| {
+            create_pipeline(
+                device,
+                shader,
+                self.format,
+                &self.pipeline_layout,
+                sample_count,
+                self.cache.as_ref(),
+            )
+        })?;
+        self.sample_count = sample_count;
+        Ok(())
+    }
+}
+
+/// 创建天空盒管线：不混合，LessEqual深度测试但不写入深度（三角形位于远平面，深度为1）
+fn create_pipeline(
+    device: &wgpu::Device,
+    shader: &wgpu::ShaderModule,
+    format: wgpu::TextureFormat,
+    layout: &wgpu::PipelineLayout,
+    sample_count: u32,
+    cache: Option<&wgpu::PipelineCache>,
+) -> wgpu::RenderPipeline {
+    device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
+        label: Some("Skybox Pipeline"),
+        layout: Some(layout),
+        vertex: wgpu::VertexState {
+            module: shader,
+            entry_point: Some("vs"),
+            buffers: &[],
+            compilation_options: Default::default(),
+        },
+        fragment: Some(wgpu::FragmentState {
+            module: shader,
+            entry_point: Some("fs"),
+            targets: &[Some(wgpu::ColorTargetState {
+                format,
+                blend: Some(wgpu::BlendState::REPLACE),
+                write_mask: wgpu::ColorWrites::ALL,
+            })],
+            compilation_options: Default::default(),
+        }),
+        primitive: Default::default(),
+        depth_stencil: Some(wgpu::DepthStencilState {
+            depth_write_enabled: false,
+            ..DepthTexture::depth_stencil_state()
+        }),
+        multisample: wgpu::MultisampleState {
+            count: sample_count,
+            ..Default::default()
+        },
+        multiview: None,
+        cache,
+    })
+}
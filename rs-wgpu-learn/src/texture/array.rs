@@ -0,0 +1,332 @@
+use super::Texture;
+use crate::{
+    SamplerCache, SamplerDesc, WgpuAppError,
+    binding::{BindGroupLayoutBuilder, BindingLayout},
+    check_array_layers, check_texture_size,
+    mipmap::{MipmapGenerator, mip_level_count},
+};
+use std::collections::HashMap;
+
+/// 创建`ArrayTexture`：添加尺寸相同的图片，每张图片一层，一次性上传
+///
+/// 也可以由所有图片尺寸相同的`AtlasBuilder`转换得到（见`AtlasBuilder::into_array`）。
+pub struct ArrayTextureBuilder {
+    label: String,
+    format: wgpu::TextureFormat, // 纹理格式（Rgba8UnormSrgb或Rgba8Unorm）
+    generate_mips: bool,         // 是否生成完整的mip链
+    layers: Vec<(String, image::RgbaImage)>,
+}
+
+impl Default for ArrayTextureBuilder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl ArrayTextureBuilder {
+    /// 空的构建器：`Rgba8UnormSrgb`格式，生成mip链
+    pub fn new() -> Self {
+        Self {
+            label: "Texture Array".to_string(),
+            format: wgpu::TextureFormat::Rgba8UnormSrgb,
+            generate_mips: true,
+            layers: Vec::new(),
+        }
+    }
+
+    /// 设置纹理的调试标签
+    pub fn label(mut self, label: &str) -> Self {
+        self.label = label.to_string();
+        self
+    }
+
+    /// 设置纹理格式：颜色贴图用`Rgba8UnormSrgb`，法线等数据贴图用`Rgba8Unorm`
+    pub fn format(mut self, format: wgpu::TextureFormat) -> Self {
+        self.format = format;
+        self
+    }
+
+    /// 设置是否生成mip链（每一层分别生成）
+    pub fn mipmaps(mut self, generate_mips: bool) -> Self {
+        self.generate_mips = generate_mips;
+        self
+    }
+
+    /// 添加一张解码后的图片（转换为RGBA8）作为新的一层，返回层号，name用于查找和错误信息
+    pub fn add(&mut self, name: &str, image: &image::DynamicImage) -> u32 {
+        self.add_rgba(name, image.to_rgba8())
+    }
+
+    /// 添加一块RGBA8像素作为新的一层，返回层号（按添加顺序从0开始）
+    pub fn add_rgba(&mut self, name: &str, image: image::RgbaImage) -> u32 {
+        self.layers.push((name.to_string(), image));
+        self.layers.len() as u32 - 1
+    }
+
+    /// 层数
+    pub fn len(&self) -> usize {
+        self.layers.len()
+    }
+
+    /// 是否还没有添加图片
+    pub fn is_empty(&self) -> bool {
+        self.layers.is_empty()
+    }
+
+    /// 检查各层尺寸一致，返回它们共同的宽高；没有图片或尺寸不一致时返回`InvalidTextureArray`
+    pub(super) fn layer_size(&self) -> Result<(u32, u32), WgpuAppError> {
+        let Some((_, first)) = self.layers.first() else {
+            return Err(WgpuAppError::InvalidTextureArray(format!(
+                "'{}' has no layers",
+                self.label
+            )));
+        };
+        let size = first.dimensions();
+        let mismatched = self
+            .layers
+            .iter()
+            .find(|(_, image)| image.dimensions() != size);
+        if let Some((name, image)) = mismatched {
+            return Err(WgpuAppError::InvalidTextureArray(format!(
+                "layer '{name}' of '{}' is {}x{}, expected {}x{}",
+                self.label,
+                image.width(),
+                image.height(),
+                size.0,
+                size.1
+            )));
+        }
+        Ok(size)
+    }
+
+    /// 创建`TextureViewDimension::D2Array`视图的纹理并上传所有层（三线性过滤、重复寻址）
+    ///
+    /// 没有图片或尺寸不一致时返回`InvalidTextureArray`，单层尺寸超出设备限制时返回`TextureTooLarge`，
+    /// 层数超出`max_texture_array_layers`时返回`TooManyArrayLayers`。
+    pub fn build(
+        self,
+        device: &wgpu::Device,
+        queue: &wgpu::Queue,
+        samplers: &SamplerCache,
+    ) -> Result<ArrayTexture, WgpuAppError> {
+        let (width, height) = self.layer_size()?;
+        let limits = device.limits();
+        check_texture_size(&limits, width, height)?;
+        let layers = self.layers.len() as u32;
+        check_array_layers(&limits, layers)?;
+
+        // 1. 创建纹理，mip链在单层的临时纹理中生成后复制进来，所以不需要作为渲染目标
+        let mut generator = self
+            .generate_mips
+            .then(|| MipmapGenerator::new(device))
+            .transpose()?;
+        let mip_level_count = match generator {
+            Some(_) => mip_level_count(width, height),
+            None => 1,
+        };
+        let size = wgpu::Extent3d {
+            width,
+            height,
+            depth_or_array_layers: layers,
+        };
+        let texture = device.create_texture(&wgpu::TextureDescriptor {
+            label: Some(&self.label),
+            size,
+            mip_level_count,
+            sample_count: 1,
+            dimension: wgpu::TextureDimension::D2,
+            format: self.format,
+            usage: wgpu::TextureUsages::TEXTURE_BINDING | wgpu::TextureUsages::COPY_DST,
+            view_formats: &[],
+        });
+
+        // 2. 各层首尾相接地写入一次：每行4 * width字节，每层height行
+        let pixels = self
+            .layers
+            .iter()
+            .flat_map(|(_, image)| image.as_raw().iter().copied())
+            .collect::<Vec<_>>();
+        queue.write_texture(
+            wgpu::TexelCopyTextureInfo {
+                texture: &texture,
+                mip_level: 0,
+                origin: wgpu::Origin3d::ZERO,
+                aspect: wgpu::TextureAspect::All,
+            },
+            &pixels,
+            wgpu::TexelCopyBufferLayout {
+                offset: 0,
+                bytes_per_row: Some(4 * width),
+                rows_per_image: Some(height),
+            },
+            size,
+        );
+
+        // 3. 每层在单层纹理中生成mip链，再把第1级之后的各级复制到数组的对应层：
+        // GL后端按层数决定纹理目标，不能把数组纹理的一层当作D2视图采样（`MipmapGenerator`逐层生成时需要这样做）
+        if let Some(generator) = &mut generator {
+            let mut encoder = device.create_command_encoder(&wgpu::CommandEncoderDescriptor {
+                label: Some("Mipmap Encoder"),
+            });
+            for (layer, (name, image)) in (0..).zip(&self.layers) {
+                let source = mip_source(device, queue, name, image, self.format, mip_level_count);
+                generator.generate(device, &mut encoder, &source);
+                for level in 1..mip_level_count {
+                    encoder.copy_texture_to_texture(
+                        wgpu::TexelCopyTextureInfo {
+                            texture: &source,
+                            mip_level: level,
+                            origin: wgpu::Origin3d::ZERO,
+                            aspect: wgpu::TextureAspect::All,
+                        },
+                        wgpu::TexelCopyTextureInfo {
+                            texture: &texture,
+                            mip_level: level,
+                            origin: wgpu::Origin3d {
+                                x: 0,
+                                y: 0,
+                                z: layer,
+                            },
+                            aspect: wgpu::TextureAspect::All,
+                        },
+                        wgpu::Extent3d {
+                            width: (width >> level).max(1),
+                            height: (height >> level).max(1),
+                            depth_or_array_layers: 1,
+                        },
+                    );
+                }
+            }
+            queue.submit([encoder.finish()]);
+        }
+
+        // 4. 视图的维度必须显式指定：只有一层时默认视图是D2而不是D2Array
+        let view = texture.create_view(&wgpu::TextureViewDescriptor {
+            label: Some(&self.label),
+            dimension: Some(wgpu::TextureViewDimension::D2Array),
+            ..Default::default()
+        });
+        let sampler = samplers.get(&SamplerDesc::linear_repeat())?;
+        let names = self
+            .layers
+            .into_iter()
+            .map(|(name, _)| name)
+            .collect::<Vec<_>>();
+        let layers = names.iter().cloned().zip(0..).collect();
+        Ok(ArrayTexture {
+            texture: Texture {
+                texture,
+                view,
+                sampler,
+            },
+            names,
+            layers,
+        })
+    }
+}
+
+/// 二维数组纹理：尺寸相同的多张图片各占一层，着色器中声明为`texture_2d_array<f32>`，
+/// 用`textureSample(t, s, uv, layer)`按层号采样
+///
+/// 用`ArrayTextureBuilder`创建。与图集相比每层都有自己的mip链、可以平铺（重复寻址），
+/// 不同的物体只需在实例数据中带上层号，就能共用一个绑定组、在一次实例化绘制中使用不同的贴图。
+pub struct ArrayTexture {
+    texture: Texture,             // 视图为D2Array的纹理和采样器
+    names: Vec<String>,           // 按层号保存的名称
+    layers: HashMap<String, u32>, // 名称到层号（同名时指向最后添加的一层）
+}
+
+impl ArrayTexture {
+    /// 纹理、D2Array视图和采样器（绑定时与其他纹理相同，见`Texture::bind_group`）
+    pub fn texture(&self) -> &Texture {
+        &self.texture
+    }
+
+    /// 换用另一个采样器，纹理和视图不变
+    pub fn with_sampler(mut self, sampler: wgpu::Sampler) -> Self {
+        self.texture.sampler = sampler;
+        self
+    }
+
+    /// 层数
+    pub fn len(&self) -> usize {
+        self.names.len()
+    }
+
+    /// 是否没有任何层（`ArrayTextureBuilder`不会创建这样的纹理）
+    pub fn is_empty(&self) -> bool {
+        self.names.is_empty()
+    }
+
+    /// 按名称查找层号
+    pub fn layer(&self, name: &str) -> Option<u32> {
+        self.layers.get(name).copied()
+    }
+
+    /// 层的名称
+    pub fn name(&self, layer: u32) -> &str {
+        &self.names[layer as usize]
+    }
+
+    /// 创建与`Texture::bind_group`对应的绑定组布局：绑定0为可过滤的二维数组纹理，绑定1为过滤采样器
+    pub fn create_layout(
+        device: &wgpu::Device,
+        label: &str,
+        visibility: wgpu::ShaderStages,
+    ) -> BindingLayout {
+        BindGroupLayoutBuilder::new()
+            .label(label)
+            .texture(
+                visibility,
+                wgpu::TextureSampleType::Float { filterable: true },
+                wgpu::TextureViewDimension::D2Array,
+            )
+            .sampler(visibility, wgpu::SamplerBindingType::Filtering)
+            .build(device)
+    }
+}
+
+/// 生成一层的mip链用的单层纹理：第0级写入image，其余各级由`MipmapGenerator`渲染后复制到数组纹理
+fn mip_source(
+    device: &wgpu::Device,
+    queue: &wgpu::Queue,
+    label: &str,
+    image: &image::RgbaImage,
+    format: wgpu::TextureFormat,
+    mip_level_count: u32,
+) -> wgpu::Texture {
+    let size = wgpu::Extent3d {
+        width: image.width(),
+        height: image.height(),
+        depth_or_array_layers: 1,
+    };
+    let texture = device.create_texture(&wgpu::TextureDescriptor {
+        label: Some(label),
+        size,
+        mip_level_count,
+        sample_count: 1,
+        dimension: wgpu::TextureDimension::D2,
+        format,
+        usage: wgpu::TextureUsages::TEXTURE_BINDING
+            | wgpu::TextureUsages::RENDER_ATTACHMENT
+            | wgpu::TextureUsages::COPY_SRC
+            | wgpu::TextureUsages::COPY_DST,
+        view_formats: &[],
+    });
+    queue.write_texture(
+        wgpu::TexelCopyTextureInfo {
+            texture: &texture,
+            mip_level: 0,
+            origin: wgpu::Origin3d::ZERO,
+            aspect: wgpu::TextureAspect::All,
+        },
+        image.as_raw(),
+        wgpu::TexelCopyBufferLayout {
+            offset: 0,
+            bytes_per_row: Some(4 * size.width),
+            rows_per_image: Some(size.height),
+        },
+        size,
+    );
+    texture
+}
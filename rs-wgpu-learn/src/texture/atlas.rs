@@ -0,0 +1,429 @@
+use super::{ArrayTextureBuilder, Texture};
+use crate::{SamplerCache, SamplerDesc, WgpuAppError, check_texture_size};
+use std::collections::HashMap;
+
+/// 图集中一张图片的句柄，按添加顺序从0编号，之后添加图片也不会改变已有的编号
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct AtlasId(u32);
+
+impl AtlasId {
+    /// 添加顺序中的下标
+    pub fn index(self) -> usize {
+        self.0 as usize
+    }
+}
+
+/// 图片在图集中占据的像素矩形（不含填充）
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct AtlasRect {
+    pub x: u32,
+    pub y: u32,
+    pub width: u32,
+    pub height: u32,
+}
+
+/// 创建`Atlas`：设置图集边长和填充，添加图片后一次性打包并上传
+///
+/// 打包前按高度从高到低排序（同一行的图片高度相近，浪费的空间更少），但`add`返回的编号仍按添加顺序。
+pub struct AtlasBuilder {
+    label: String,
+    size: u32,    // 图集纹理的边长（像素）
+    padding: u32, // 每张图片四周留出的透明像素
+    entries: Vec<(String, image::RgbaImage)>,
+}
+
+impl AtlasBuilder {
+    /// 边长为size的正方形图集，默认每张图片四周留1像素的填充
+    pub fn new(size: u32) -> Self {
+        Self {
+            label: "Texture Atlas".to_string(),
+            size,
+            padding: 1,
+            entries: Vec::new(),
+        }
+    }
+
+    /// 设置纹理的调试标签
+    pub fn label(mut self, label: &str) -> Self {
+        self.label = label.to_string();
+        self
+    }
+
+    /// 设置每张图片四周留出的透明像素（线性过滤时不会采样到相邻的图片）
+    pub fn padding(mut self, padding: u32) -> Self {
+        self.padding = padding;
+        self
+    }
+
+    /// 添加一张解码后的图片（转换为RGBA8），name用于查找和错误信息
+    pub fn add(&mut self, name: &str, image: &image::DynamicImage) -> AtlasId {
+        self.add_rgba(name, image.to_rgba8())
+    }
+
+    /// 添加一块RGBA8像素（可用`image::RgbaImage::from_raw`由原始字节构造）
+    pub fn add_rgba(&mut self, name: &str, image: image::RgbaImage) -> AtlasId {
+        self.entries.push((name.to_string(), image));
+        AtlasId(self.entries.len() as u32 - 1)
+    }
+
+    /// 所有图片尺寸相同时改为创建二维数组纹理：每张图片一层，层号等于`add`返回的`AtlasId`的下标
+    ///
+    /// 尺寸相同的贴图放进数组纹理不需要填充，还可以生成mip链和平铺。
+    /// 没有图片或尺寸不一致时返回`InvalidTextureArray`。
+    pub fn into_array(self) -> Result<ArrayTextureBuilder, WgpuAppError> {
+        let mut array = ArrayTextureBuilder::new().label(&self.label);
+        for (name, image) in self.entries {
+            array.add_rgba(&name, image);
+        }
+        array.layer_size()?;
+        Ok(array)
+    }
+
+    /// 打包所有图片，创建`Rgba8UnormSrgb`纹理（只有一级，线性过滤、钳制到边缘）并上传
+    ///
+    /// 图集尺寸超出设备限制时返回`TextureTooLarge`，有图片放不下时返回列出该图片的`AtlasFull`。
+    pub fn build(
+        self,
+        device: &wgpu::Device,
+        queue: &wgpu::Queue,
+        samplers: &SamplerCache,
+    ) -> Result<Atlas, WgpuAppError> {
+        check_texture_size(&device.limits(), self.size, self.size)?;
+
+        // 1. 按高度从高到低打包，结果按添加顺序保存
+        let mut packer = ShelfPacker::new(self.size, self.padding);
+        let mut order: Vec<usize> = (0..self.entries.len()).collect();
+        order.sort_by_key(|&i| std::cmp::Reverse(self.entries[i].1.height()));
+        let mut rects = vec![None; self.entries.len()];
+        for i in order {
+            let (name, image) = &self.entries[i];
+            rects[i] = Some(packer.pack(name, image.width(), image.height())?);
+        }
+
+        // 2. 创建纹理（初始为全透明，填充区域保持透明）并逐张写入
+        let texture = device.create_texture(&wgpu::TextureDescriptor {
+            label: Some(&self.label),
+            size: wgpu::Extent3d {
+                width: self.size,
+                height: self.size,
+                depth_or_array_layers: 1,
+            },
+            mip_level_count: 1,
+            sample_count: 1,
+            dimension: wgpu::TextureDimension::D2,
+            format: wgpu::TextureFormat::Rgba8UnormSrgb,
+            usage: wgpu::TextureUsages::TEXTURE_BINDING | wgpu::TextureUsages::COPY_DST,
+            view_formats: &[],
+        });
+        let view = texture.create_view(&wgpu::TextureViewDescriptor {
+            label: Some(&self.label),
+            ..Default::default()
+        });
+        let sampler = samplers.get(&SamplerDesc::linear_clamp())?;
+        let mut atlas = Atlas {
+            texture: Texture {
+                texture,
+                view,
+                sampler,
+            },
+            packer,
+            entries: Vec::with_capacity(self.entries.len()),
+            names: HashMap::new(),
+        };
+        for ((name, image), rect) in self.entries.into_iter().zip(rects.into_iter().flatten()) {
+            atlas.insert(queue, name, &image, rect);
+        }
+        Ok(atlas)
+    }
+}
+
+/// 纹理图集：许多小图片打包进一张纹理，精灵和文字可以用同一个绑定组绘制
+///
+/// 用`AtlasBuilder`创建。每张图片通过`AtlasId`取得纹理坐标矩形（`uv_rect`），
+/// 创建后仍可用`add`继续添加图片：放进剩余的空闲空间，只上传新图片所在的区域，纹理不会重建。
+/// 图集没有mip链（缩小时相邻的图片会混在一起），适合按接近原始尺寸显示的精灵和字形。
+pub struct Atlas {
+    texture: Texture,
+    packer: ShelfPacker,
+    entries: Vec<(String, AtlasRect)>, // 按编号保存的名称和像素矩形
+    names: HashMap<String, AtlasId>,   // 名称到编号（同名时指向最后添加的图片）
+}
+
+impl Atlas {
+    /// 图集纹理（绑定时与其他纹理相同，见`Texture::bind_group`）
+    pub fn texture(&self) -> &Texture {
+        &self.texture
+    }
+
+    /// 图集的边长（像素）
+    pub fn size(&self) -> u32 {
+        self.packer.size
+    }
+
+    /// 图片数量
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// 是否没有图片
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// 按名称查找图片
+    pub fn id(&self, name: &str) -> Option<AtlasId> {
+        self.names.get(name).copied()
+    }
+
+    /// 图片的名称
+    pub fn name(&self, id: AtlasId) -> &str {
+        &self.entries[id.index()].0
+    }
+
+    /// 图片在图集中的像素矩形
+    pub fn rect(&self, id: AtlasId) -> AtlasRect {
+        self.entries[id.index()].1
+    }
+
+    /// 图片的纹理坐标矩形`[u_min, v_min, u_max, v_max]`（左上角为(0, 0)，与网格的纹理坐标约定一致）
+    pub fn uv_rect(&self, id: AtlasId) -> [f32; 4] {
+        let rect = self.rect(id);
+        let size = self.packer.size as f32;
+        [
+            rect.x as f32 / size,
+            rect.y as f32 / size,
+            (rect.x + rect.width) as f32 / size,
+            (rect.y + rect.height) as f32 / size,
+        ]
+    }
+
+    /// 在剩余的空闲空间中添加一张图片，只上传它所在的区域，见`add_rgba`
+    pub fn add(
+        &mut self,
+        queue: &wgpu::Queue,
+        name: &str,
+        image: &image::DynamicImage,
+    ) -> Result<AtlasId, WgpuAppError> {
+        self.add_rgba(queue, name, &image.to_rgba8())
+    }
+
+    /// 在剩余的空闲空间中添加一块RGBA8像素，只上传它所在的区域
+    ///
+    /// 放不下时返回`AtlasFull`，图集保持不变。
+    pub fn add_rgba(
+        &mut self,
+        queue: &wgpu::Queue,
+        name: &str,
+        image: &image::RgbaImage,
+    ) -> Result<AtlasId, WgpuAppError> {
+        let rect = self.packer.pack(name, image.width(), image.height())?;
+        Ok(self.insert(queue, name.to_string(), image, rect))
+    }
+
+    /// 把图片写入rect并记录它
+    fn insert(
+        &mut self,
+        queue: &wgpu::Queue,
+        name: String,
+        image: &image::RgbaImage,
+        rect: AtlasRect,
+    ) -> AtlasId {
+        if rect.width > 0 && rect.height > 0 {
+            queue.write_texture(
+                wgpu::TexelCopyTextureInfo {
+                    texture: &self.texture.texture,
+                    mip_level: 0,
+                    origin: wgpu::Origin3d {
+                        x: rect.x,
+                        y: rect.y,
+                        z: 0,
+                    },
+                    aspect: wgpu::TextureAspect::All,
+                },
+                image.as_raw(),
+                wgpu::TexelCopyBufferLayout {
+                    offset: 0,
+                    bytes_per_row: Some(4 * rect.width),
+                    rows_per_image: Some(rect.height),
+                },
+                wgpu::Extent3d {
+                    width: rect.width,
+                    height: rect.height,
+                    depth_or_array_layers: 1,
+                },
+            );
+        }
+        let id = AtlasId(self.entries.len() as u32);
+        self.names.insert(name.clone(), id);
+        self.entries.push((name, rect));
+        id
+    }
+}
+
+/// 货架（shelf）打包：图片从左到右排成若干行，每行的高度由第一张图片决定，放不进已有的行时在下方开新行
+///
+/// 每张图片占据的区域四周各多出padding像素，相邻两张图片之间相隔2*padding，与图集边缘相隔padding。
+struct ShelfPacker {
+    size: u32,
+    padding: u32,
+    shelves: Vec<Shelf>,
+    bottom: u32, // 已有各行下方空闲区域的起点
+}
+
+/// 货架中的一行
+struct Shelf {
+    y: u32,      // 行的上边缘
+    height: u32, // 行高（含填充）
+    x: u32,      // 行中空闲区域的起点
+}
+
+impl ShelfPacker {
+    fn new(size: u32, padding: u32) -> Self {
+        Self {
+            size,
+            padding,
+            shelves: Vec::new(),
+            bottom: 0,
+        }
+    }
+
+    /// 为width x height的图片分配位置：选择放得下的行中最矮的一行，都放不下时开新行
+    fn pack(&mut self, name: &str, width: u32, height: u32) -> Result<AtlasRect, WgpuAppError> {
+        let padded_width = width.saturating_add(2 * self.padding);
+        let padded_height = height.saturating_add(2 * self.padding);
+        let size = self.size;
+        let shelf = self
+            .shelves
+            .iter_mut()
+            .filter(|shelf| padded_height <= shelf.height && padded_width <= size - shelf.x)
+            .min_by_key(|shelf| shelf.height);
+        let shelf = match shelf {
+            Some(shelf) => shelf,
+            None if padded_width <= size && padded_height <= size - self.bottom => {
+                self.shelves.push(Shelf {
+                    y: self.bottom,
+                    height: padded_height,
+                    x: 0,
+                });
+                self.bottom += padded_height;
+                self.shelves.last_mut().expect("a shelf was just pushed")
+            }
+            None => {
+                return Err(WgpuAppError::AtlasFull {
+                    name: name.to_string(),
+                    width,
+                    height,
+                    size,
+                });
+            }
+        };
+        let rect = AtlasRect {
+            x: shelf.x + self.padding,
+            y: shelf.y + self.padding,
+            width,
+            height,
+        };
+        shelf.x += padded_width;
+        Ok(rect)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// 测试用的xorshift32伪随机数，固定种子使失败可以复现
+    fn xorshift(state: &mut u32) -> u32 {
+        *state ^= *state << 13;
+        *state ^= *state >> 17;
+        *state ^= *state << 5;
+        *state
+    }
+
+    /// 含填充的区域（x, y, 宽, 高）
+    fn padded(rect: AtlasRect, padding: u32) -> (u32, u32, u32, u32) {
+        (
+            rect.x - padding,
+            rect.y - padding,
+            rect.width + 2 * padding,
+            rect.height + 2 * padding,
+        )
+    }
+
+    #[test]
+    fn random_sizes_pack_into_disjoint_padded_rects_inside_the_atlas() {
+        const SIZE: u32 = 256;
+        for seed in 1..=20 {
+            for padding in [0, 1, 3] {
+                let mut state = seed;
+                let mut packer = ShelfPacker::new(SIZE, padding);
+                let mut packed = Vec::new();
+                let mut full = 0;
+                for i in 0..200 {
+                    let width = xorshift(&mut state) % 72 + 1;
+                    let height = xorshift(&mut state) % 72 + 1;
+                    match packer.pack(&format!("image-{i}"), width, height) {
+                        Ok(rect) => {
+                            assert_eq!((rect.width, rect.height), (width, height));
+                            packed.push(padded(rect, padding));
+                        }
+                        Err(WgpuAppError::AtlasFull { .. }) => full += 1,
+                        Err(e) => panic!("unexpected error {e}"),
+                    }
+                }
+                assert!(
+                    packed.len() > 10,
+                    "seed {seed}: only {} packed",
+                    packed.len()
+                );
+                assert!(full > 0, "seed {seed}: 200 images should not all fit");
+
+                for (i, &(x, y, w, h)) in packed.iter().enumerate() {
+                    assert!(
+                        x + w <= SIZE && y + h <= SIZE,
+                        "seed {seed}, padding {padding}: {:?} leaves the atlas",
+                        packed[i]
+                    );
+                    for &(ox, oy, ow, oh) in &packed[..i] {
+                        let disjoint = x + w <= ox || ox + ow <= x || y + h <= oy || oy + oh <= y;
+                        assert!(
+                            disjoint,
+                            "seed {seed}, padding {padding}: {:?} overlaps {:?}",
+                            (x, y, w, h),
+                            (ox, oy, ow, oh)
+                        );
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn equal_squares_fill_the_atlas_exactly() {
+        let mut packer = ShelfPacker::new(8, 1);
+        for i in 0..4 {
+            let rect = packer.pack(&format!("square-{i}"), 2, 2).unwrap();
+            assert_eq!(padded(rect, 1).2, 4);
+        }
+        assert!(matches!(
+            packer.pack("extra", 1, 1),
+            Err(WgpuAppError::AtlasFull { size: 8, .. })
+        ));
+    }
+
+    #[test]
+    fn images_larger_than_the_atlas_are_rejected() {
+        let mut packer = ShelfPacker::new(16, 1);
+        assert!(packer.pack("wide", 15, 1).is_err()); // 加上填充后超出
+        assert!(packer.pack("tall", 1, u32::MAX).is_err()); // 加填充时饱和而不是溢出
+        assert_eq!(
+            packer.pack("fits", 14, 14).unwrap(),
+            AtlasRect {
+                x: 1,
+                y: 1,
+                width: 14,
+                height: 14,
+            }
+        );
+    }
+}
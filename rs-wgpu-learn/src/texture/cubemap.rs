@@ -0,0 +1,204 @@
+use crate::{
+    SamplerCache, SamplerDesc, WgpuAppError,
+    binding::{BindGroupBuilder, BindGroupLayoutBuilder, BindingLayout},
+    check_texture_size,
+    mipmap::{MipmapGenerator, mip_level_count},
+};
+use std::path::Path;
+
+/// 立方体贴图六个面的名称，顺序与纹理的数组层一致
+pub const CUBE_FACES: [&str; 6] = ["+X", "-X", "+Y", "-Y", "+Z", "-Z"];
+
+/// 立方体贴图：6层的二维纹理、`Cube`维度的视图和采样器（克隆只增加引用计数）
+///
+/// 面的顺序为+X、-X、+Y、-Y、+Z、-Z。立方体贴图使用左手坐标系：站在立方体中心看向+Z面时+X在右、+Y在上，
+/// 而本项目的世界坐标是右手系、相机默认看向-Z，所以采样时把方向的z取反（见skybox.wgsl），
+/// 十字布局中央的+Z面出现在默认相机的正前方，各面的文字都不会镜像。
+#[derive(Debug, Clone)]
+pub struct CubeMap {
+    pub texture: wgpu::Texture,  // 6层的纹理
+    pub view: wgpu::TextureView, // Cube维度的视图
+    pub sampler: wgpu::Sampler,  // 线性过滤、钳制到边缘的采样器
+}
+
+impl CubeMap {
+    /// 用六张正方形图片（顺序见`CUBE_FACES`）创建`Rgba8UnormSrgb`立方体贴图，并用mipmaps生成完整的mip链
+    ///
+    /// 各面的尺寸必须相同且宽高相等，否则返回`InvalidCubeMap`；尺寸超出设备限制时返回`TextureTooLarge`。
+    pub fn from_faces(
+        device: &wgpu::Device,
+        queue: &wgpu::Queue,
+        samplers: &SamplerCache,
+        faces: &[image::DynamicImage; 6],
+        mipmaps: &mut MipmapGenerator,
+        label: Option<&str>,
+    ) -> Result<Self, WgpuAppError> {
+        let size = faces[0].width();
+        for (face, name) in faces.iter().zip(CUBE_FACES) {
+            if face.width() != size || face.height() != size {
+                return Err(WgpuAppError::InvalidCubeMap(format!(
+                    "face {name} is {}x{}, expected {size}x{size}",
+                    face.width(),
+                    face.height()
+                )));
+            }
+        }
+        check_texture_size(&device.limits(), size, size)?;
+
+        // 1. 创建6层纹理，六个面按层依次写入
+        let extent = wgpu::Extent3d {
+            width: size,
+            height: size,
+            depth_or_array_layers: 6,
+        };
+        let texture = device.create_texture(&wgpu::TextureDescriptor {
+            label,
+            size: extent,
+            mip_level_count: mip_level_count(size, size),
+            sample_count: 1,
+            dimension: wgpu::TextureDimension::D2,
+            format: wgpu::TextureFormat::Rgba8UnormSrgb,
+            usage: wgpu::TextureUsages::TEXTURE_BINDING
+                | wgpu::TextureUsages::COPY_DST
+                | wgpu::TextureUsages::RENDER_ATTACHMENT,
+            view_formats: &[],
+        });
+        let pixels: Vec<u8> = faces
+            .iter()
+            .flat_map(|face| face.to_rgba8().into_raw())
+            .collect();
+        queue.write_texture(
+            wgpu::TexelCopyTextureInfo {
+                texture: &texture,
+                mip_level: 0,
+                origin: wgpu::Origin3d::ZERO,
+                aspect: wgpu::TextureAspect::All,
+            },
+            &pixels,
+            wgpu::TexelCopyBufferLayout {
+                offset: 0,
+                bytes_per_row: Some(4 * size),
+                rows_per_image: Some(size),
+            },
+            extent,
+        );
+
+        // 2. 每个面分别生成mip链（面之间的接缝在较小的级别上可能略微可见）
+        let mut encoder = device.create_command_encoder(&wgpu::CommandEncoderDescriptor {
+            label: Some("Cube Map Mipmap Encoder"),
+        });
+        mipmaps.generate(device, &mut encoder, &texture);
+        queue.submit([encoder.finish()]);
+
+        let view = texture.create_view(&wgpu::TextureViewDescriptor {
+            label,
+            dimension: Some(wgpu::TextureViewDimension::Cube),
+            ..Default::default()
+        });
+        let sampler = samplers.get(&SamplerDesc::linear_clamp())?;
+        Ok(Self {
+            texture,
+            view,
+            sampler,
+        })
+    }
+
+    /// 把十字布局的图片切成六个面，见`from_faces`
+    ///
+    /// 横向十字（宽高比4:3）：第一行中间为+Y，第二行依次为-X、+Z、+X、-Z，第三行中间为-Y。
+    /// 纵向十字（宽高比3:4）：前三行与横向十字的左三列相同，第四行中间为旋转了180度的-Z。
+    /// 其他宽高比返回`InvalidCubeMap`。
+    pub fn from_cross(
+        device: &wgpu::Device,
+        queue: &wgpu::Queue,
+        samplers: &SamplerCache,
+        image: &image::DynamicImage,
+        mipmaps: &mut MipmapGenerator,
+        label: Option<&str>,
+    ) -> Result<Self, WgpuAppError> {
+        let (width, height) = (image.width(), image.height());
+        let face = |column: u32, row: u32, size: u32| {
+            image.crop_imm(column * size, row * size, size, size)
+        };
+        let faces = if width * 3 == height * 4 && width > 0 {
+            let size = width / 4;
+            [
+                face(2, 1, size),
+                face(0, 1, size),
+                face(1, 0, size),
+                face(1, 2, size),
+                face(1, 1, size),
+                face(3, 1, size),
+            ]
+        } else if width * 4 == height * 3 && width > 0 {
+            let size = width / 3;
+            [
+                face(2, 1, size),
+                face(0, 1, size),
+                face(1, 0, size),
+                face(1, 2, size),
+                face(1, 1, size),
+                face(1, 3, size).rotate180(),
+            ]
+        } else {
+            return Err(WgpuAppError::InvalidCubeMap(format!(
+                "a cross layout image must be 4:3 or 3:4, got {width}x{height}"
+            )));
+        };
+        Self::from_faces(device, queue, samplers, &faces, mipmaps, label)
+    }
+
+    /// 从文件加载十字布局的PNG或JPEG图片，见`from_cross`
+    pub fn load_cross(
+        device: &wgpu::Device,
+        queue: &wgpu::Queue,
+        samplers: &SamplerCache,
+        path: &Path,
+        mipmaps: &mut MipmapGenerator,
+    ) -> Result<Self, WgpuAppError> {
+        let bytes = std::fs::read(path).map_err(|source| WgpuAppError::TextureRead {
+            path: path.to_path_buf(),
+            source,
+        })?;
+        let image = image::load_from_memory(&bytes).map_err(WgpuAppError::ImageDecode)?;
+        Self::from_cross(
+            device,
+            queue,
+            samplers,
+            &image,
+            mipmaps,
+            Some(&path.display().to_string()),
+        )
+    }
+
+    /// 每个面的边长（像素）
+    pub fn face_size(&self) -> u32 {
+        self.texture.width()
+    }
+
+    /// 创建与`bind_group`对应的绑定组布局：绑定0为可过滤的立方体纹理，绑定1为过滤采样器
+    pub fn create_layout(
+        device: &wgpu::Device,
+        label: &str,
+        visibility: wgpu::ShaderStages,
+    ) -> BindingLayout {
+        BindGroupLayoutBuilder::new()
+            .label(label)
+            .texture(
+                visibility,
+                wgpu::TextureSampleType::Float { filterable: true },
+                wgpu::TextureViewDimension::Cube,
+            )
+            .sampler(visibility, wgpu::SamplerBindingType::Filtering)
+            .build(device)
+    }
+
+    /// 用立方体视图和采样器创建绑定组，layout通常由`create_layout`创建
+    pub fn bind_group(&self, device: &wgpu::Device, layout: &BindingLayout) -> wgpu::BindGroup {
+        BindGroupBuilder::new(layout)
+            .label("Cube Map Bind Group")
+            .texture(&self.view)
+            .sampler(&self.sampler)
+            .build(device)
+    }
+}
@@ -0,0 +1,294 @@
+use super::CubeMap;
+use crate::{
+    SamplerCache, SamplerDesc, WgpuAppError,
+    binding::{self, BindGroupBuilder, BindGroupLayoutBuilder},
+    check_texture_size,
+    mipmap::{MipmapGenerator, mip_level_count},
+    shader,
+};
+use log::info;
+use std::path::Path;
+use wgpu::util::DeviceExt;
+
+/// 全景图转换为立方体贴图使用的着色器文件
+const SHADER: &str = "equirect_to_cube.wgsl";
+
+/// 环境贴图（全景图和立方体贴图）的格式：半精度浮点，可以过滤、渲染，保留大于1的亮度
+const FORMAT: wgpu::TextureFormat = wgpu::TextureFormat::Rgba16Float;
+
+/// 与着色器中FaceUniform对应的数据（补齐到16字节）
+#[repr(C)]
+#[derive(Debug, Clone, Copy, bytemuck::Pod, bytemuck::Zeroable)]
+struct FaceUniform {
+    face: u32,
+    lod: f32,
+    face_size: f32,
+    _padding: u32,
+}
+
+/// HDR环境贴图：由等距柱状投影的全景图（如Poly Haven的`.hdr`文件）转换成的`Rgba16Float`立方体贴图
+///
+/// 可以直接交给`SkyboxPass`绘制（需配合`Tonemapper`把大于1的亮度映射到屏幕），之后也用于基于图像的光照。
+/// 全景图的中心位于-Z方向（相机默认的正前方），向右为+X，上边缘为天顶。
+#[derive(Debug, Clone)]
+pub struct EnvironmentMap {
+    pub cube_map: CubeMap, // 带完整mip链的立方体贴图
+    pub peak: f32,         // 全景图中最亮的颜色分量（线性值，用于估计动态范围）
+}
+
+impl EnvironmentMap {
+    /// 加载Radiance HDR（`.hdr`）全景图并转换为每个面face_size x face_size的立方体贴图，见`from_equirect_image`
+    pub fn from_equirect(
+        device: &wgpu::Device,
+        queue: &wgpu::Queue,
+        samplers: &SamplerCache,
+        path: &Path,
+        face_size: u32,
+    ) -> Result<Self, WgpuAppError> {
+        let bytes = std::fs::read(path).map_err(|source| WgpuAppError::TextureRead {
+            path: path.to_path_buf(),
+            source,
+        })?;
+        let label = path.display().to_string();
+        Self::from_hdr_bytes(device, queue, samplers, &bytes, face_size, Some(&label))
+    }
+
+    /// 解码内存中的Radiance HDR全景图，见`from_equirect_image`
+    pub fn from_hdr_bytes(
+        device: &wgpu::Device,
+        queue: &wgpu::Queue,
+        samplers: &SamplerCache,
+        bytes: &[u8],
+        face_size: u32,
+        label: Option<&str>,
+    ) -> Result<Self, WgpuAppError> {
+        let image = image::load_from_memory_with_format(bytes, image::ImageFormat::Hdr)
+            .map_err(WgpuAppError::ImageDecode)?;
+        Self::from_equirect_image(
+            device,
+            queue,
+            samplers,
+            &image.to_rgba32f(),
+            face_size,
+            label,
+        )
+    }
+
+    /// 把线性颜色的全景图上传为带mip链的`Rgba16Float`纹理，再在GPU上分六次渲染到立方体贴图的六个面
+    ///
+    /// 全景图的宽高比应为2:1（其他比例会被拉伸）。全景图比立方体贴图精细时从较小的mip级别采样，避免走样；
+    /// 立方体贴图转换后生成完整的mip链。face_size为0或尺寸超出设备限制时返回错误。
+    pub fn from_equirect_image(
+        device: &wgpu::Device,
+        queue: &wgpu::Queue,
+        samplers: &SamplerCache,
+        image: &image::Rgba32FImage,
+        face_size: u32,
+        label: Option<&str>,
+    ) -> Result<Self, WgpuAppError> {
+        let (width, height) = image.dimensions();
+        if face_size == 0 || width == 0 || height == 0 {
+            return Err(WgpuAppError::InvalidCubeMap(format!(
+                "cannot convert a {width}x{height} panorama to {face_size}px faces"
+            )));
+        }
+        let limits = device.limits();
+        check_texture_size(&limits, width, height)?;
+        check_texture_size(&limits, face_size, face_size)?;
+        let mut generator = MipmapGenerator::new(device)?;
+        let mut encoder = device.create_command_encoder(&wgpu::CommandEncoderDescriptor {
+            label: Some("Equirect To Cube Encoder"),
+        });
+
+        // 1. 全景图转换为半精度浮点后上传，并生成mip链
+        let mip_usage = wgpu::TextureUsages::TEXTURE_BINDING
+            | wgpu::TextureUsages::COPY_DST
+            | wgpu::TextureUsages::RENDER_ATTACHMENT;
+        let equirect = device.create_texture(&wgpu::TextureDescriptor {
+            label: Some("Equirect Panorama"),
+            size: wgpu::Extent3d {
+                width,
+                height,
+                depth_or_array_layers: 1,
+            },
+            mip_level_count: mip_level_count(width, height),
+            sample_count: 1,
+            dimension: wgpu::TextureDimension::D2,
+            format: FORMAT,
+            usage: mip_usage,
+            view_formats: &[],
+        });
+        let pixels: Vec<half::f16> = image
+            .as_raw()
+            .iter()
+            .map(|&value| half::f16::from_f32(value))
+            .collect();
+        queue.write_texture(
+            equirect.as_image_copy(),
+            bytemuck::cast_slice(&pixels),
+            wgpu::TexelCopyBufferLayout {
+                offset: 0,
+                bytes_per_row: Some(8 * width),
+                rows_per_image: Some(height),
+            },
+            equirect.size(),
+        );
+        generator.generate(device, &mut encoder, &equirect);
+
+        // 2. 创建立方体贴图，每个面一个uniform缓冲区和绑定组
+        let texture = device.create_texture(&wgpu::TextureDescriptor {
+            label,
+            size: wgpu::Extent3d {
+                width: face_size,
+                height: face_size,
+                depth_or_array_layers: 6,
+            },
+            mip_level_count: mip_level_count(face_size, face_size),
+            sample_count: 1,
+            dimension: wgpu::TextureDimension::D2,
+            format: FORMAT,
+            usage: mip_usage,
+            view_formats: &[],
+        });
+        let layout = BindGroupLayoutBuilder::new()
+            .label("Equirect To Cube Layout")
+            .uniform(wgpu::ShaderStages::FRAGMENT)
+            .texture(
+                wgpu::ShaderStages::FRAGMENT,
+                wgpu::TextureSampleType::Float { filterable: true },
+                wgpu::TextureViewDimension::D2,
+            )
+            .sampler(
+                wgpu::ShaderStages::FRAGMENT,
+                wgpu::SamplerBindingType::Filtering,
+            )
+            .build(device);
+        let equirect_view = equirect.create_view(&Default::default());
+        // 经度方向首尾相接（重复），纬度方向在两极钳制
+        let sampler = device.create_sampler(
+            &SamplerDesc {
+                address_mode: [
+                    wgpu::AddressMode::Repeat,
+                    wgpu::AddressMode::ClampToEdge,
+                    wgpu::AddressMode::ClampToEdge,
+                ],
+                ..SamplerDesc::linear_clamp()
+            }
+            .descriptor(Some("Equirect Sampler")),
+        );
+        // 全景图每弧度的像素数（width / 2π）与面中心每弧度的像素数（face_size / 2）之比
+        let lod = (width as f32 / (std::f32::consts::PI * face_size as f32))
+            .log2()
+            .max(0.0);
+
+        // 3. 分六次渲染各个面的第0级，再生成立方体贴图的mip链
+        let pipeline_layout =
+            binding::pipeline_layout(device, Some("Equirect To Cube Pipeline Layout"), &[&layout]);
+        let pipeline = create_pipeline(device, &shader::load(device, SHADER)?, &pipeline_layout);
+        for face in 0..6 {
+            let uniform = FaceUniform {
+                face,
+                lod,
+                face_size: face_size as f32,
+                _padding: 0,
+            };
+            let buffer = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+                label: Some("Equirect To Cube Uniform Buffer"),
+                contents: bytemuck::bytes_of(&uniform),
+                usage: wgpu::BufferUsages::UNIFORM,
+            });
+            let bind_group = BindGroupBuilder::new(&layout)
+                .label("Equirect To Cube Bind Group")
+                .buffer(&buffer)
+                .texture(&equirect_view)
+                .sampler(&sampler)
+                .build(device);
+            let target = texture.create_view(&wgpu::TextureViewDescriptor {
+                label: Some("Cube Face View"),
+                dimension: Some(wgpu::TextureViewDimension::D2),
+                base_mip_level: 0,
+                mip_level_count: Some(1),
+                base_array_layer: face,
+                array_layer_count: Some(1),
+                ..Default::default()
+            });
+            let mut pass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
+                label: Some("Equirect To Cube Pass"),
+                color_attachments: &[Some(wgpu::RenderPassColorAttachment {
+                    view: &target,
+                    resolve_target: None,
+                    ops: wgpu::Operations {
+                        load: wgpu::LoadOp::Clear(wgpu::Color::BLACK),
+                        store: wgpu::StoreOp::Store,
+                    },
+                })],
+                depth_stencil_attachment: None,
+                timestamp_writes: None,
+                occlusion_query_set: None,
+            });
+            pass.set_pipeline(&pipeline);
+            pass.set_bind_group(0, &bind_group, &[]);
+            pass.draw(0..3, 0..1);
+        }
+        generator.generate(device, &mut encoder, &texture);
+        queue.submit([encoder.finish()]);
+
+        let peak = image
+            .pixels()
+            .flat_map(|pixel| &pixel.0[..3])
+            .copied()
+            .filter(|value| value.is_finite())
+            .fold(0.0, f32::max);
+        info!(
+            "Converted {width}x{height} panorama {} to {face_size}px cube faces (peak {peak:.1})",
+            label.unwrap_or("")
+        );
+        let view = texture.create_view(&wgpu::TextureViewDescriptor {
+            label,
+            dimension: Some(wgpu::TextureViewDimension::Cube),
+            ..Default::default()
+        });
+        let sampler = samplers.get(&SamplerDesc::linear_clamp())?;
+        Ok(Self {
+            cube_map: CubeMap {
+                texture,
+                view,
+                sampler,
+            },
+            peak,
+        })
+    }
+}
+
+/// 创建写入`Rgba16Float`立方体贴图单个面的全屏管线
+fn create_pipeline(
+    device: &wgpu::Device,
+    shader: &wgpu::ShaderModule,
+    layout: &wgpu::PipelineLayout,
+) -> wgpu::RenderPipeline {
+    device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
+        label: Some("Equirect To Cube Pipeline"),
+        layout: Some(layout),
+        vertex: wgpu::VertexState {
+            module: shader,
+            entry_point: Some("vs"),
+            buffers: &[],
+            compilation_options: Default::default(),
+        },
+        fragment: Some(wgpu::FragmentState {
+            module: shader,
+            entry_point: Some("fs"),
+            targets: &[Some(wgpu::ColorTargetState {
+                format: FORMAT,
+                blend: None,
+                write_mask: wgpu::ColorWrites::ALL,
+            })],
+            compilation_options: Default::default(),
+        }),
+        primitive: Default::default(),
+        depth_stencil: None,
+        multisample: Default::default(),
+        multiview: None,
+        cache: None,
+    })
+}
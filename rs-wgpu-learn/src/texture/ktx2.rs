@@ -0,0 +1,240 @@
+use super::Texture;
+use crate::{
+    SamplerCache, SamplerDesc, WgpuAppError, check_texture_size,
+    mipmap::{mip_level_count, mip_size},
+};
+use ::ktx2::{Format, SupercompressionScheme};
+use log::info;
+
+/// KTX2中支持的格式：对应的wgpu格式，以及没有`TEXTURE_COMPRESSION_BC`时在CPU上解码的方式
+struct FormatInfo {
+    format: wgpu::TextureFormat, // 直接上传时的格式
+    fallback: Option<Fallback>,  // 设备不支持该格式时的解码方式（None表示只能直接上传）
+    fallback_srgb: bool,         // 解码后使用Rgba8UnormSrgb还是Rgba8Unorm
+}
+
+/// 在CPU上把BCn块解码为RGBA8
+#[derive(Clone, Copy)]
+enum Fallback {
+    Bc1,
+    Bc3,
+    Bc5,
+    Bc7,
+}
+
+impl Texture {
+    /// 加载KTX2纹理（`toktx`等工具生成），上传文件中预先生成的全部mip级别
+    ///
+    /// 支持未压缩的RGBA8和BC1/BC3/BC5/BC7格式，数据可以经过Zstd超压缩（toktx的`--zcmp`）。
+    /// 设备启用了`Features::TEXTURE_COMPRESSION_BC`时直接上传压缩数据（显存占用为RGBA8的1/4到1/8），
+    /// 否则（或宽高不是4的倍数时）在CPU上解码为RGBA8再上传；BC5_SNORM没有解码方式，此时返回错误。
+    /// 只支持二维纹理，立方体贴图、纹理数组、Basis Universal（需要转码）和ZLIB超压缩返回`Ktx2`错误。
+    /// 文件没有mip链时纹理只有一级（压缩格式无法渲染生成mipmap）。采样器为samplers中的`SamplerDesc::linear_repeat`。
+    pub fn from_ktx2(
+        device: &wgpu::Device,
+        queue: &wgpu::Queue,
+        samplers: &SamplerCache,
+        bytes: &[u8],
+        label: Option<&str>,
+    ) -> Result<Self, WgpuAppError> {
+        let invalid = |message: String| WgpuAppError::Ktx2(message);
+        let reader = ::ktx2::Reader::new(bytes).map_err(|e| invalid(e.to_string()))?;
+        let header = reader.header();
+
+        // 1. 检查纹理类型和格式，决定直接上传还是解码
+        if header.pixel_depth > 0 || header.layer_count > 0 || header.face_count != 1 {
+            return Err(invalid(format!(
+                "only 2D textures are supported (depth {}, layers {}, faces {})",
+                header.pixel_depth, header.layer_count, header.face_count
+            )));
+        }
+        let Some(ktx_format) = header.format else {
+            return Err(invalid(
+                "Basis Universal payloads need transcoding, which is not supported".to_string(),
+            ));
+        };
+        let info = format_info(ktx_format)
+            .ok_or_else(|| invalid(format!("unsupported format {ktx_format:?}")))?;
+        let (width, height) = (header.pixel_width, header.pixel_height.max(1));
+        check_texture_size(&device.limits(), width, height)?;
+        let (block_width, block_height) = info.format.block_dimensions();
+        let native = device.features().contains(info.format.required_features())
+            && width.is_multiple_of(block_width)
+            && height.is_multiple_of(block_height);
+        let (format, fallback) = match (native, info.fallback) {
+            (true, _) => (info.format, None),
+            (false, Some(fallback)) => {
+                let format = if info.fallback_srgb {
+                    wgpu::TextureFormat::Rgba8UnormSrgb
+                } else {
+                    wgpu::TextureFormat::Rgba8Unorm
+                };
+                (format, Some(fallback))
+            }
+            (false, None) => {
+                return Err(invalid(format!(
+                    "{:?} is not supported by the device and cannot be decoded on the CPU",
+                    info.format
+                )));
+            }
+        };
+        let level_count = header.level_count.max(1);
+        if level_count > mip_level_count(width, height) {
+            return Err(invalid(format!(
+                "{level_count} mip levels is too many for {width}x{height}"
+            )));
+        }
+
+        // 2. 创建纹理，逐级解压（Zstd）、必要时解码，按块对齐的行宽写入
+        let texture = device.create_texture(&wgpu::TextureDescriptor {
+            label,
+            size: wgpu::Extent3d {
+                width,
+                height,
+                depth_or_array_layers: 1,
+            },
+            mip_level_count: level_count,
+            sample_count: 1,
+            dimension: wgpu::TextureDimension::D2,
+            format,
+            usage: wgpu::TextureUsages::TEXTURE_BINDING | wgpu::TextureUsages::COPY_DST,
+            view_formats: &[],
+        });
+        let block_size = info
+            .format
+            .block_copy_size(None)
+            .expect("supported KTX2 formats have a single aspect");
+        for (level, data) in (0..level_count).zip(reader.levels()) {
+            let (level_width, level_height) = mip_size(width, height, level);
+            let data = match header.supercompression_scheme {
+                None => data.data.to_vec(),
+                Some(SupercompressionScheme::Zstandard) => {
+                    let mut decompressed = vec![0; data.uncompressed_byte_length as usize];
+                    ruzstd::decoding::FrameDecoder::new()
+                        .decode_all(data.data, &mut decompressed)
+                        .map_err(|e| invalid(format!("level {level}: {e}")))?;
+                    decompressed
+                }
+                Some(scheme) => {
+                    return Err(invalid(format!(
+                        "{scheme:?} supercompression is not supported"
+                    )));
+                }
+            };
+            // 压缩格式按4x4块存储，不足一块的边缘（以及小于4像素的mip级别）也占一整块
+            let blocks_x = level_width.div_ceil(block_width);
+            let blocks_y = level_height.div_ceil(block_height);
+            let expected = (blocks_x * blocks_y * block_size) as usize;
+            if data.len() < expected {
+                return Err(invalid(format!(
+                    "level {level} has {} bytes, expected {expected}",
+                    data.len()
+                )));
+            }
+            let (pixels, bytes_per_row, rows, extent) = match fallback {
+                Some(fallback) => (
+                    decode(fallback, &data, level_width, level_height)
+                        .map_err(|e| invalid(format!("level {level}: {e}")))?,
+                    4 * level_width,
+                    level_height,
+                    (level_width, level_height),
+                ),
+                None => (
+                    data,
+                    blocks_x * block_size,
+                    blocks_y,
+                    (blocks_x * block_width, blocks_y * block_height),
+                ),
+            };
+            queue.write_texture(
+                wgpu::TexelCopyTextureInfo {
+                    texture: &texture,
+                    mip_level: level,
+                    origin: wgpu::Origin3d::ZERO,
+                    aspect: wgpu::TextureAspect::All,
+                },
+                &pixels,
+                wgpu::TexelCopyBufferLayout {
+                    offset: 0,
+                    bytes_per_row: Some(bytes_per_row),
+                    rows_per_image: Some(rows),
+                },
+                wgpu::Extent3d {
+                    width: extent.0,
+                    height: extent.1,
+                    depth_or_array_layers: 1,
+                },
+            );
+        }
+        info!(
+            "Loaded KTX2 {} ({width}x{height}, {level_count} levels, {:?}{})",
+            label.unwrap_or("texture"),
+            info.format,
+            if fallback.is_some() {
+                ", decoded to RGBA8"
+            } else {
+                ""
+            }
+        );
+
+        let view = texture.create_view(&wgpu::TextureViewDescriptor {
+            label,
+            ..Default::default()
+        });
+        let sampler = samplers.get(&SamplerDesc::linear_repeat())?;
+        Ok(Self {
+            texture,
+            view,
+            sampler,
+        })
+    }
+}
+
+/// KTX2（Vulkan）格式对应的wgpu格式和解码方式，不支持的格式返回None
+fn format_info(format: Format) -> Option<FormatInfo> {
+    use wgpu::TextureFormat as F;
+    let (format, fallback, fallback_srgb) = match format {
+        Format::R8G8B8A8_UNORM => (F::Rgba8Unorm, None, false),
+        Format::R8G8B8A8_SRGB => (F::Rgba8UnormSrgb, None, true),
+        // BC1的RGB和RGBA变体数据相同，区别只在于是否使用1位alpha
+        Format::BC1_RGB_UNORM_BLOCK | Format::BC1_RGBA_UNORM_BLOCK => {
+            (F::Bc1RgbaUnorm, Some(Fallback::Bc1), false)
+        }
+        Format::BC1_RGB_SRGB_BLOCK | Format::BC1_RGBA_SRGB_BLOCK => {
+            (F::Bc1RgbaUnormSrgb, Some(Fallback::Bc1), true)
+        }
+        Format::BC3_UNORM_BLOCK => (F::Bc3RgbaUnorm, Some(Fallback::Bc3), false),
+        Format::BC3_SRGB_BLOCK => (F::Bc3RgbaUnormSrgb, Some(Fallback::Bc3), true),
+        Format::BC5_UNORM_BLOCK => (F::Bc5RgUnorm, Some(Fallback::Bc5), false),
+        Format::BC5_SNORM_BLOCK => (F::Bc5RgSnorm, None, false),
+        Format::BC7_UNORM_BLOCK => (F::Bc7RgbaUnorm, Some(Fallback::Bc7), false),
+        Format::BC7_SRGB_BLOCK => (F::Bc7RgbaUnormSrgb, Some(Fallback::Bc7), true),
+        _ => return None,
+    };
+    Some(FormatInfo {
+        format,
+        fallback,
+        fallback_srgb,
+    })
+}
+
+/// 把一级BCn数据解码为紧密排列的RGBA8（BC5的两个通道放在R和G中，B为0，A为255）
+fn decode(fallback: Fallback, data: &[u8], width: u32, height: u32) -> Result<Vec<u8>, String> {
+    let (width, height) = (width as usize, height as usize);
+    let mut pixels = vec![0u32; width * height];
+    let decode = match fallback {
+        Fallback::Bc1 => texture2ddecoder::decode_bc1,
+        Fallback::Bc3 => texture2ddecoder::decode_bc3,
+        Fallback::Bc5 => texture2ddecoder::decode_bc5,
+        Fallback::Bc7 => texture2ddecoder::decode_bc7,
+    };
+    decode(data, width, height, &mut pixels)?;
+    // 解码器输出的每个像素按小端序为BGRA
+    Ok(pixels
+        .into_iter()
+        .flat_map(|pixel| {
+            let [b, g, r, a] = pixel.to_le_bytes();
+            [r, g, b, a]
+        })
+        .collect())
+}
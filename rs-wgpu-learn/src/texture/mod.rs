@@ -0,0 +1,235 @@
+mod array;
+mod atlas;
+mod cubemap;
+mod environment;
+mod ktx2;
+mod volume;
+
+pub use array::{ArrayTexture, ArrayTextureBuilder};
+pub use atlas::{Atlas, AtlasBuilder, AtlasId, AtlasRect};
+pub use cubemap::{CUBE_FACES, CubeMap};
+pub use environment::EnvironmentMap;
+pub use volume::VolumeTexture;
+
+use crate::{
+    SamplerCache, SamplerDesc, WgpuAppError,
+    binding::{BindGroupBuilder, BindGroupLayoutBuilder, BindingLayout},
+    check_texture_size,
+    mipmap::{MipmapGenerator, mip_level_count},
+};
+use std::path::{Path, PathBuf};
+
+/// 可在着色器中采样的二维纹理：纹理、视图和采样器（克隆只增加引用计数）
+#[derive(Debug, Clone)]
+pub struct Texture {
+    pub texture: wgpu::Texture,  // 纹理
+    pub view: wgpu::TextureView, // 整张纹理的视图
+    pub sampler: wgpu::Sampler,  // 采样器
+}
+
+impl Texture {
+    /// 从文件加载纹理：`.ktx2`文件见`from_ktx2`，其他文件按PNG或JPEG解码（见`from_bytes`）
+    ///
+    /// 同一目录下有同名的`.ktx2`文件时（如`wood.png`旁的`wood.ktx2`）优先加载它，
+    /// 预先压缩和生成mip链的纹理加载更快、占用显存更少，此时不使用mipmaps。
+    pub fn load(
+        device: &wgpu::Device,
+        queue: &wgpu::Queue,
+        samplers: &SamplerCache,
+        path: &Path,
+        mipmaps: Option<&mut MipmapGenerator>,
+    ) -> Result<Self, WgpuAppError> {
+        let path = ktx2_sibling(path).unwrap_or_else(|| path.to_path_buf());
+        let bytes = std::fs::read(&path).map_err(|source| WgpuAppError::TextureRead {
+            path: path.clone(),
+            source,
+        })?;
+        let label = path.display().to_string();
+        if is_ktx2(&path) {
+            Self::from_ktx2(device, queue, samplers, &bytes, Some(&label))
+        } else {
+            Self::from_bytes(device, queue, samplers, &bytes, Some(&label), mipmaps)
+        }
+    }
+
+    /// 解码PNG或JPEG图片（格式由文件内容判断）并创建颜色纹理，见`from_image`
+    pub fn from_bytes(
+        device: &wgpu::Device,
+        queue: &wgpu::Queue,
+        samplers: &SamplerCache,
+        bytes: &[u8],
+        label: Option<&str>,
+        mipmaps: Option<&mut MipmapGenerator>,
+    ) -> Result<Self, WgpuAppError> {
+        let image = image::load_from_memory(bytes).map_err(WgpuAppError::ImageDecode)?;
+        Self::from_image(device, queue, samplers, &image, label, mipmaps)
+    }
+
+    /// 用解码后的图片创建`Rgba8UnormSrgb`颜色纹理，采样器为samplers中的`SamplerDesc::linear_repeat`（可用`with_sampler`替换）
+    ///
+    /// 灰度、RGB和16位图片都先转换为RGBA8（灰度复制到RGB三个通道，缺少alpha时为不透明），尺寸不要求是2的幂。
+    /// 传入mipmaps时生成完整的mip链（见`from_rgba8`），缩小显示时不再闪烁。
+    pub fn from_image(
+        device: &wgpu::Device,
+        queue: &wgpu::Queue,
+        samplers: &SamplerCache,
+        image: &image::DynamicImage,
+        label: Option<&str>,
+        mipmaps: Option<&mut MipmapGenerator>,
+    ) -> Result<Self, WgpuAppError> {
+        Self::from_rgba8(
+            device,
+            queue,
+            label,
+            &image.to_rgba8(),
+            wgpu::TextureFormat::Rgba8UnormSrgb,
+            samplers.get(&SamplerDesc::linear_repeat())?,
+            mipmaps,
+        )
+    }
+
+    /// 用RGBA8图片创建纹理
+    ///
+    /// format应为`Rgba8UnormSrgb`（颜色贴图）或`Rgba8Unorm`（法线等数据贴图）。sampler可以来自`SamplerCache`，
+    /// 使配置相同的纹理共用一个采样器。
+    /// 传入mipmaps时纹理包含完整的mip链（见`mip_level_count`），第0级之外的各级由它立即生成并提交，
+    /// 否则只有一级。批量加载时所有纹理共用调用方的一个生成器，管线只创建一次。
+    /// 尺寸超出设备限制时返回`TextureTooLarge`。
+    pub fn from_rgba8(
+        device: &wgpu::Device,
+        queue: &wgpu::Queue,
+        label: Option<&str>,
+        image: &image::RgbaImage,
+        format: wgpu::TextureFormat,
+        sampler: wgpu::Sampler,
+        mipmaps: Option<&mut MipmapGenerator>,
+    ) -> Result<Self, WgpuAppError> {
+        let (width, height) = image.dimensions();
+        check_texture_size(&device.limits(), width, height)?;
+        let (mip_level_count, usage) = match mipmaps {
+            Some(_) => (
+                mip_level_count(width, height),
+                wgpu::TextureUsages::RENDER_ATTACHMENT,
+            ),
+            None => (1, wgpu::TextureUsages::empty()),
+        };
+        let size = wgpu::Extent3d {
+            width,
+            height,
+            depth_or_array_layers: 1,
+        };
+        let texture = device.create_texture(&wgpu::TextureDescriptor {
+            label,
+            size,
+            mip_level_count,
+            sample_count: 1,
+            dimension: wgpu::TextureDimension::D2,
+            format,
+            usage: wgpu::TextureUsages::TEXTURE_BINDING | wgpu::TextureUsages::COPY_DST | usage,
+            view_formats: &[],
+        });
+        // write_texture不要求bytes_per_row按COPY_BYTES_PER_ROW_ALIGNMENT对齐（wgpu在暂存时重新排列），
+        // 任意宽度的紧密排列数据都可以直接写入
+        queue.write_texture(
+            wgpu::TexelCopyTextureInfo {
+                texture: &texture,
+                mip_level: 0,
+                origin: wgpu::Origin3d::ZERO,
+                aspect: wgpu::TextureAspect::All,
+            },
+            image.as_raw(),
+            wgpu::TexelCopyBufferLayout {
+                offset: 0,
+                bytes_per_row: Some(4 * width),
+                rows_per_image: Some(height),
+            },
+            size,
+        );
+        if let Some(generator) = mipmaps {
+            let mut encoder = device.create_command_encoder(&wgpu::CommandEncoderDescriptor {
+                label: Some("Mipmap Encoder"),
+            });
+            generator.generate(device, &mut encoder, &texture);
+            queue.submit([encoder.finish()]);
+        }
+        let view = texture.create_view(&wgpu::TextureViewDescriptor {
+            label,
+            ..Default::default()
+        });
+        Ok(Self {
+            texture,
+            view,
+            sampler,
+        })
+    }
+
+    /// 1x1的纯色纹理，用作缺少贴图时的默认值（采样器为samplers中的`SamplerDesc::nearest_clamp`）
+    pub fn solid(
+        device: &wgpu::Device,
+        queue: &wgpu::Queue,
+        samplers: &SamplerCache,
+        label: Option<&str>,
+        rgba: [u8; 4],
+        format: wgpu::TextureFormat,
+    ) -> Self {
+        Self::from_rgba8(
+            device,
+            queue,
+            label,
+            &image::RgbaImage::from_pixel(1, 1, image::Rgba(rgba)),
+            format,
+            samplers
+                .get(&SamplerDesc::nearest_clamp())
+                .expect("the nearest_clamp preset is always valid"),
+            None,
+        )
+        .expect("1x1 textures are always within the device limits")
+    }
+
+    /// 换用另一个采样器（例如来自`SamplerCache`的最近邻或各向异性采样器），纹理和视图不变
+    pub fn with_sampler(mut self, sampler: wgpu::Sampler) -> Self {
+        self.sampler = sampler;
+        self
+    }
+
+    /// 创建与`bind_group`对应的绑定组布局：绑定0为可过滤的二维纹理，绑定1为过滤采样器
+    pub fn create_layout(
+        device: &wgpu::Device,
+        label: &str,
+        visibility: wgpu::ShaderStages,
+    ) -> BindingLayout {
+        BindGroupLayoutBuilder::new()
+            .label(label)
+            .texture(
+                visibility,
+                wgpu::TextureSampleType::Float { filterable: true },
+                wgpu::TextureViewDimension::D2,
+            )
+            .sampler(visibility, wgpu::SamplerBindingType::Filtering)
+            .build(device)
+    }
+
+    /// 用纹理视图和采样器创建绑定组，layout通常由`create_layout`创建
+    pub fn bind_group(&self, device: &wgpu::Device, layout: &BindingLayout) -> wgpu::BindGroup {
+        BindGroupBuilder::new(layout)
+            .label("Texture Bind Group")
+            .texture(&self.view)
+            .sampler(&self.sampler)
+            .build(device)
+    }
+}
+
+/// path不是`.ktx2`文件且同一目录下有同名的`.ktx2`文件时返回它的路径
+pub(crate) fn ktx2_sibling(path: &Path) -> Option<PathBuf> {
+    if is_ktx2(path) {
+        return None;
+    }
+    let sibling = path.with_extension("ktx2");
+    sibling.is_file().then_some(sibling)
+}
+
+/// 扩展名是否为`.ktx2`（不区分大小写）
+fn is_ktx2(path: &Path) -> bool {
+    path.extension()
+        .is_some_and(|ext| ext.eq_ignore_ascii_case("ktx2"))
+}
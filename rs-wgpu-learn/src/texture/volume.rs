@@ -0,0 +1,149 @@
+use crate::{
+    SamplerCache, SamplerDesc, WgpuAppError,
+    binding::{BindGroupBuilder, BindGroupLayoutBuilder, BindingLayout},
+    check_volume_size,
+};
+
+/// 三维纹理：`TextureDimension::D3`的纹理、D3视图和采样器（克隆只增加引用计数）
+///
+/// 着色器中声明为`texture_3d<f32>`，用0..1的三维纹理坐标采样，三个方向都做线性过滤。
+/// 纹素按x、y、z的顺序排列：先是第0片（z = 0）的height行，每行width个纹素，然后是下一片。
+/// 只有一级，采样器为重复寻址（可平铺的体积数据可以无缝滚动）。
+#[derive(Debug, Clone)]
+pub struct VolumeTexture {
+    pub texture: wgpu::Texture,  // 三维纹理
+    pub view: wgpu::TextureView, // D3维度的视图
+    pub sampler: wgpu::Sampler,  // 线性过滤、重复寻址的采样器
+}
+
+impl VolumeTexture {
+    /// 创建width × height × depth的三维纹理（内容为0），之后用`write`或`write_slice`上传
+    ///
+    /// 用途总是包含`TEXTURE_BINDING`和`COPY_DST`，需要在计算着色器中写入时再加上`STORAGE_BINDING`
+    /// （格式须支持存储纹理）。任一方向超出`max_texture_dimension_3d`时返回`VolumeTooLarge`。
+    pub fn new(
+        device: &wgpu::Device,
+        samplers: &SamplerCache,
+        label: Option<&str>,
+        size: wgpu::Extent3d,
+        format: wgpu::TextureFormat,
+        usage: wgpu::TextureUsages,
+    ) -> Result<Self, WgpuAppError> {
+        check_volume_size(&device.limits(), size)?;
+        let texture = device.create_texture(&wgpu::TextureDescriptor {
+            label,
+            size,
+            mip_level_count: 1,
+            sample_count: 1,
+            dimension: wgpu::TextureDimension::D3,
+            format,
+            usage: wgpu::TextureUsages::TEXTURE_BINDING | wgpu::TextureUsages::COPY_DST | usage,
+            view_formats: &[],
+        });
+        let view = texture.create_view(&wgpu::TextureViewDescriptor {
+            label,
+            dimension: Some(wgpu::TextureViewDimension::D3),
+            ..Default::default()
+        });
+        let sampler = samplers.get(&SamplerDesc::linear_repeat())?;
+        Ok(Self {
+            texture,
+            view,
+            sampler,
+        })
+    }
+
+    /// 纹理尺寸（depth_or_array_layers为深度）
+    pub fn size(&self) -> wgpu::Extent3d {
+        self.texture.size()
+    }
+
+    /// 覆盖写入整个体积，texels为紧密排列的纹素（格式与纹理一致）
+    ///
+    /// # Panics
+    /// texels的长度与纹理的字节数不一致时panic。
+    pub fn write(&self, queue: &wgpu::Queue, texels: &[u8]) {
+        self.write_slices(queue, 0, self.size().depth_or_array_layers, texels);
+    }
+
+    /// 覆盖写入第z片（深度方向的一层）
+    ///
+    /// # Panics
+    /// z超出深度，或texels的长度与一片的字节数不一致时panic。
+    pub fn write_slice(&self, queue: &wgpu::Queue, z: u32, texels: &[u8]) {
+        self.write_slices(queue, z, 1, texels);
+    }
+
+    /// 从第first片开始写入count片：每行width个纹素，每片height行，片与片首尾相接
+    fn write_slices(&self, queue: &wgpu::Queue, first: u32, count: u32, texels: &[u8]) {
+        let size = self.size();
+        assert!(
+            first + count <= size.depth_or_array_layers,
+            "writing slices {first}..{} of a volume with depth {}",
+            first + count,
+            size.depth_or_array_layers
+        );
+        let texel_size = self
+            .texture
+            .format()
+            .block_copy_size(None)
+            .expect("volume textures use single-aspect uncompressed formats");
+        // 三维纹理的rows_per_image是每片的行数：wgpu据此找到下一片的起点，写入多片时必须提供
+        let bytes_per_row = size.width * texel_size;
+        let rows_per_image = size.height;
+        assert_eq!(
+            texels.len(),
+            (bytes_per_row * rows_per_image * count) as usize,
+            "volume slices {first}..{} need {bytes_per_row} bytes per row and {rows_per_image} rows per slice",
+            first + count
+        );
+        queue.write_texture(
+            wgpu::TexelCopyTextureInfo {
+                texture: &self.texture,
+                mip_level: 0,
+                origin: wgpu::Origin3d {
+                    x: 0,
+                    y: 0,
+                    z: first,
+                },
+                aspect: wgpu::TextureAspect::All,
+            },
+            texels,
+            wgpu::TexelCopyBufferLayout {
+                offset: 0,
+                bytes_per_row: Some(bytes_per_row),
+                rows_per_image: Some(rows_per_image),
+            },
+            wgpu::Extent3d {
+                depth_or_array_layers: count,
+                ..size
+            },
+        );
+    }
+
+    /// 创建与`bind_group`对应的绑定组布局：绑定0为可过滤的三维纹理，绑定1为过滤采样器
+    pub fn create_layout(
+        device: &wgpu::Device,
+        label: &str,
+        visibility: wgpu::ShaderStages,
+    ) -> BindingLayout {
+        BindGroupLayoutBuilder::new()
+            .label(label)
+            .texture(
+                visibility,
+                wgpu::TextureSampleType::Float { filterable: true },
+                wgpu::TextureViewDimension::D3,
+            )
+            .sampler(visibility, wgpu::SamplerBindingType::Filtering)
+            .build(device)
+    }
+
+    /// 用D3视图和采样器创建绑定组，layout通常由`create_layout`创建
+    pub fn bind_group(&self, device: &wgpu::Device, layout: &BindingLayout) -> wgpu::BindGroup {
+        BindGroupBuilder::new(layout)
+            .label("Volume Texture Bind Group")
+            .texture(&self.view)
+            .sampler(&self.sampler)
+            .build(device)
+    }
+}
@@ -0,0 +1,305 @@
+use crate::{
+    RenderContext, ResourcePool, WgpuAppError,
+    binding::{self, BindGroupBuilder, BindGroupLayoutBuilder, BindingLayout},
+    rebuild_pipeline, shader,
+};
+use std::path::Path;
+
+/// 色调映射使用的着色器文件
+const SHADER: &str = "tonemap.wgsl";
+
+/// 场景渲染到的HDR颜色纹理的格式，绘制到`Tonemapper::color_attachment`的管线须使用它
+pub const HDR_FORMAT: wgpu::TextureFormat = wgpu::TextureFormat::Rgba16Float;
+
+/// 与着色器中TonemapUniform对应的数据（补齐到16字节）
+#[repr(C)]
+#[derive(Debug, Clone, Copy, bytemuck::Pod, bytemuck::Zeroable)]
+struct TonemapUniform {
+    exposure: f32,
+    curve: u32,
+    encode_srgb: u32,
+    _padding: u32,
+}
+
+/// 把HDR颜色压缩到0..1的曲线
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum TonemapOperator {
+    /// 直接截断，大于1的亮度都变成白色（用于对比）
+    Clamp,
+    /// Reinhard：c / (1 + c)，柔和但高光发灰
+    Reinhard,
+    /// ACES电影曲线的近似，对比度更高，高光平滑地过渡到白色
+    #[default]
+    Aces,
+}
+
+impl TonemapOperator {
+    /// 显示用的名称
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Clamp => "clamp",
+            Self::Reinhard => "Reinhard",
+            Self::Aces => "ACES",
+        }
+    }
+
+    /// 依次切换到下一种曲线
+    pub fn next(self) -> Self {
+        match self {
+            Self::Clamp => Self::Reinhard,
+            Self::Reinhard => Self::Aces,
+            Self::Aces => Self::Clamp,
+        }
+    }
+}
+
+/// 色调映射后处理：场景先绘制到表面大小的`HDR_FORMAT`纹理（亮度可以远大于1），
+/// 再由一个全屏三角形乘以曝光、经过色调映射曲线后写入表面
+///
+/// 每帧先调用`prepare`从资源池取用HDR纹理，场景的渲染通道使用`color_attachment`代替
+/// `RenderContext::color_attachment`（深度附件不变），开启MSAA时绘制到同样采样数的多重采样HDR纹理并解析，
+/// 之后调用`render`写入表面视图。表面不是sRGB格式时在着色器中做sRGB编码。
+/// 表面尺寸和多重采样数变化时需调用`resize`和`sample_count_changed`。
+pub struct Tonemapper {
+    pub exposure: f32,              // 曝光值（EV），每加1亮度翻倍
+    pub operator: TonemapOperator,  // 色调映射曲线
+    targets: Option<HdrTargets>,    // 本帧从资源池取用的HDR纹理和绑定组（prepare之前为None）
+    layout: BindingLayout,          // 取用HDR纹理后用于创建绑定组
+    pipeline: wgpu::RenderPipeline, // 单采样的全屏管线，直接写入表面视图
+    format: wgpu::TextureFormat,    // 表面格式
+    sample_count: u32,              // 场景使用的多重采样数
+    size: (u32, u32),               // 表面尺寸
+    uniform_buffer: wgpu::Buffer,
+    pipeline_layout: wgpu::PipelineLayout,
+    cache: Option<wgpu::PipelineCache>,
+}
+
+/// 场景绘制的目标：解析后的HDR纹理和开启MSAA时的多重采样纹理，以及引用HDR纹理的绑定组
+struct HdrTargets {
+    view: wgpu::TextureView,
+    msaa_view: Option<wgpu::TextureView>,
+    bind_group: wgpu::BindGroup,
+}
+
+impl Tonemapper {
+    /// 为config描述的表面创建色调映射，sample_count需与场景的渲染通道（和深度纹理）一致
+    pub fn new(
+        device: &wgpu::Device,
+        config: &wgpu::SurfaceConfiguration,
+        sample_count: u32,
+        cache: Option<&wgpu::PipelineCache>,
+    ) -> Result<Self, WgpuAppError> {
+        let uniform_buffer = device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some("Tonemap Uniform Buffer"),
+            size: std::mem::size_of::<TonemapUniform>() as u64,
+            usage: wgpu::BufferUsages::UNIFORM | wgpu::BufferUsages::COPY_DST,
+            mapped_at_creation: false,
+        });
+        let layout = BindGroupLayoutBuilder::new()
+            .label("Tonemap Bind Group Layout")
+            .uniform(wgpu::ShaderStages::FRAGMENT)
+            .texture(
+                wgpu::ShaderStages::FRAGMENT,
+                wgpu::TextureSampleType::Float { filterable: false },
+                wgpu::TextureViewDimension::D2,
+            )
+            .build(device);
+        let pipeline_layout =
+            binding::pipeline_layout(device, Some("Tonemap Pipeline Layout"), &[&layout]);
+        let shader = shader::load(device, SHADER)?;
+        let pipeline = create_pipeline(device, &shader, config.format, &pipeline_layout, cache);
+        Ok(Self {
+            exposure: 0.0,
+            operator: TonemapOperator::default(),
+            targets: None,
+            layout,
+            uniform_buffer,
+            pipeline,
+            pipeline_layout,
+            format: config.format,
+            sample_count,
+            size: (config.width.max(1), config.height.max(1)),
+            cache: cache.cloned(),
+        })
+    }
+
+    /// 从资源池取用本帧的HDR纹理（GPU仍在使用的纹理不会被交出），在场景的渲染通道之前调用
+    pub fn prepare(&mut self, ctx: &RenderContext) {
+        let (view, msaa_view) =
+            create_views(&mut ctx.pool.borrow_mut(), self.size, self.sample_count);
+        let bind_group = create_bind_group(ctx.device, &self.layout, &self.uniform_buffer, &view);
+        self.targets = Some(HdrTargets {
+            view,
+            msaa_view,
+            bind_group,
+        });
+    }
+
+    /// 场景渲染通道的颜色附件：开启MSAA时绘制到多重采样HDR纹理并解析到HDR纹理
+    ///
+    /// # Panics
+    /// 本帧还没有调用`prepare`时panic。
+    pub fn color_attachment(
+        &self,
+        ops: wgpu::Operations<wgpu::Color>,
+    ) -> wgpu::RenderPassColorAttachment<'_> {
+        let targets = self.targets();
+        crate::frame::color_attachment(&targets.view, targets.msaa_view.as_ref(), ops)
+    }
+
+    /// 把HDR纹理色调映射后写入view（表面视图），在场景的渲染通道结束之后调用
+    pub fn render(
+        &self,
+        ctx: &RenderContext,
+        view: &wgpu::TextureView,
+        encoder: &mut wgpu::CommandEncoder,
+    ) {
+        let uniform = TonemapUniform {
+            exposure: self.exposure.exp2(),
+            curve: self.operator as u32,
+            encode_srgb: !self.format.is_srgb() as u32,
+            _padding: 0,
+        };
+        ctx.upload(encoder, &self.uniform_buffer, 0, &uniform);
+        let mut pass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
+            label: Some("Tonemap Pass"),
+            color_attachments: &[Some(wgpu::RenderPassColorAttachment {
+                view,
+                resolve_target: None,
+                ops: wgpu::Operations {
+                    load: wgpu::LoadOp::Clear(wgpu::Color::BLACK),
+                    store: wgpu::StoreOp::Store,
+                },
+            })],
+            depth_stencil_attachment: None,
+            timestamp_writes: None,
+            occlusion_query_set: None,
+        });
+        pass.set_pipeline(&self.pipeline);
+        pass.set_bind_group(0, &self.targets().bind_group, &[]);
+        pass.draw(0..3, 0..1);
+    }
+
+    /// 表面尺寸变化时记录新尺寸，下一次`prepare`取用新尺寸的HDR纹理
+    pub fn resize(&mut self, config: &wgpu::SurfaceConfiguration) {
+        self.size = (config.width.max(1), config.height.max(1));
+        self.targets = None;
+    }
+
+    /// 多重采样数变化时记录新采样数，下一次`prepare`取用对应的多重采样HDR纹理
+    /// （色调映射管线本身是单采样的，不需要重建）
+    pub fn sample_count_changed(&mut self, sample_count: u32) {
+        self.sample_count = sample_count;
+        self.targets = None;
+    }
+
+    /// 着色器文件变化时重建管线
+    pub fn shader_changed(&mut self, device: &wgpu::Device, path: &Path) {
+        if shader::depends_on(SHADER, path) {
+            let _ = self.rebuild_pipeline(device);
+        }
+    }
+
+    /// 本帧的HDR纹理
+    fn targets(&self) -> &HdrTargets {
+        self.targets
+            .as_ref()
+            .expect("Tonemapper::prepare must be called before drawing the scene")
+    }
+
+    /// 重新加载着色器并重建管线（见`rebuild_pipeline`），失败时保留旧管线
+    fn rebuild_pipeline(&mut self, device: &wgpu::Device) -> Result<(), WgpuAppError> {
+        rebuild_pipeline(device, SHADER, &mut self.pipeline, |shader| {
+            create_pipeline(
+                device,
+                shader,
+                self.format,
+                &self.pipeline_layout,
+                self.cache.as_ref(),
+            )
+        })
+    }
+}
+
+/// 从资源池取用size大小的HDR纹理，sample_count大于1时再取用多重采样纹理
+fn create_views(
+    pool: &mut ResourcePool,
+    (width, height): (u32, u32),
+    sample_count: u32,
+) -> (wgpu::TextureView, Option<wgpu::TextureView>) {
+    let mut create = |label, sample_count, usage| {
+        pool.get_texture(&wgpu::TextureDescriptor {
+            label: Some(label),
+            size: wgpu::Extent3d {
+                width,
+                height,
+                depth_or_array_layers: 1,
+            },
+            mip_level_count: 1,
+            sample_count,
+            dimension: wgpu::TextureDimension::D2,
+            format: HDR_FORMAT,
+            usage: wgpu::TextureUsages::RENDER_ATTACHMENT | usage,
+            view_formats: &[],
+        })
+        .create_view(&Default::default())
+    };
+    let view = create("HDR Color", 1, wgpu::TextureUsages::TEXTURE_BINDING);
+    let msaa_view = (sample_count > 1).then(|| {
+        create(
+            "HDR Multisampled Color",
+            sample_count,
+            wgpu::TextureUsages::empty(),
+        )
+    });
+    (view, msaa_view)
+}
+
+/// 绑定uniform缓冲区和HDR纹理
+fn create_bind_group(
+    device: &wgpu::Device,
+    layout: &BindingLayout,
+    uniform_buffer: &wgpu::Buffer,
+    hdr_view: &wgpu::TextureView,
+) -> wgpu::BindGroup {
+    BindGroupBuilder::new(layout)
+        .label("Tonemap Bind Group")
+        .buffer(uniform_buffer)
+        .texture(hdr_view)
+        .build(device)
+}
+
+/// 创建单采样的全屏色调映射管线，写入format格式的表面
+fn create_pipeline(
+    device: &wgpu::Device,
+    shader: &wgpu::ShaderModule,
+    format: wgpu::TextureFormat,
+    layout: &wgpu::PipelineLayout,
+    cache: Option<&wgpu::PipelineCache>,
+) -> wgpu::RenderPipeline {
+    device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
+        label: Some("Tonemap Pipeline"),
+        layout: Some(layout),
+        vertex: wgpu::VertexState {
+            module: shader,
+            entry_point: Some("vs"),
+            buffers: &[],
+            compilation_options: Default::default(),
+        },
+        fragment: Some(wgpu::FragmentState {
+            module: shader,
+            entry_point: Some("fs"),
+            targets: &[Some(wgpu::ColorTargetState {
+                format,
+                blend: Some(wgpu::BlendState::REPLACE),
+                write_mask: wgpu::ColorWrites::ALL,
+            })],
+            compilation_options: Default::default(),
+        }),
+        primitive: Default::default(),
+        depth_stencil: None,
+        multisample: Default::default(),
+        multiview: None,
+        cache,
+    })
+}
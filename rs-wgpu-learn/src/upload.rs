@@ -0,0 +1,97 @@
+use crate::WgpuAppError;
+use std::num::NonZeroU64;
+
+/// 暂存缓冲区默认的块大小，单次写入超过块大小时会分配一块刚好够用的
+pub const DEFAULT_CHUNK_SIZE: u64 = 1 << 20;
+
+/// 通过暂存缓冲区更新GPU缓冲区：数据先写入已映射的暂存块，再由命令编码器复制到目标
+///
+/// 与`queue.write_buffer`不同，复制命令记录在调用时传入的编码器中，按命令顺序执行；
+/// 暂存块在GPU用完后回收复用，不会每帧重新分配。每帧提交前调用`finish`，提交后调用`recall`
+/// （`Frame::finish`会自动完成）。
+pub struct Uploader {
+    device: wgpu::Device,
+    belt: wgpu::util::StagingBelt,
+    uploaded: u64, // 本帧写入的字节数（recall时清零）
+}
+
+impl Uploader {
+    pub fn new(device: &wgpu::Device, chunk_size: u64) -> Self {
+        Self {
+            device: device.clone(),
+            belt: wgpu::util::StagingBelt::new(chunk_size),
+            uploaded: 0,
+        }
+    }
+
+    /// 把data写入target的offset处，复制命令记录到encoder中
+    ///
+    /// offset和data的长度都必须是`COPY_BUFFER_ALIGNMENT`的倍数（不会补齐，以免覆盖紧随其后的数据），
+    /// 不满足或写入范围超出target时返回错误，什么也不写。
+    pub fn write(
+        &mut self,
+        encoder: &mut wgpu::CommandEncoder,
+        target: &wgpu::Buffer,
+        offset: wgpu::BufferAddress,
+        data: &[u8],
+    ) -> Result<(), WgpuAppError> {
+        let len = data.len() as u64;
+        let aligned = offset.is_multiple_of(wgpu::COPY_BUFFER_ALIGNMENT)
+            && len.is_multiple_of(wgpu::COPY_BUFFER_ALIGNMENT);
+        if !aligned
+            || offset
+                .checked_add(len)
+                .is_none_or(|end| end > target.size())
+        {
+            return Err(WgpuAppError::InvalidUpload {
+                offset,
+                len,
+                size: target.size(),
+            });
+        }
+        let Some(size) = NonZeroU64::new(len) else {
+            return Ok(());
+        };
+        let mut view = self
+            .belt
+            .write_buffer(encoder, target, offset, size, &self.device);
+        view.copy_from_slice(data);
+        self.uploaded += len;
+        Ok(())
+    }
+
+    /// 与`write`相同，但接受任意`Pod`类型的切片
+    ///
+    /// 类型大小不是`COPY_BUFFER_ALIGNMENT`的倍数时无法编译，长度因此总是对齐的。
+    pub fn write_slice<T: bytemuck::Pod>(
+        &mut self,
+        encoder: &mut wgpu::CommandEncoder,
+        target: &wgpu::Buffer,
+        offset: wgpu::BufferAddress,
+        data: &[T],
+    ) -> Result<(), WgpuAppError> {
+        const {
+            assert!(
+                (size_of::<T>() as u64).is_multiple_of(wgpu::COPY_BUFFER_ALIGNMENT),
+                "uploaded types must be a multiple of 4 bytes"
+            )
+        };
+        self.write(encoder, target, offset, bytemuck::cast_slice(data))
+    }
+
+    /// 结束本帧的写入（取消暂存块的映射），必须在提交编码器之前调用
+    pub fn finish(&mut self) {
+        self.belt.finish();
+    }
+
+    /// 回收已提交的暂存块，GPU用完后可以在之后的帧中复用，应在提交之后调用
+    pub fn recall(&mut self) {
+        self.belt.recall();
+        self.uploaded = 0;
+    }
+
+    /// 本帧（上一次recall之后）写入的字节数
+    pub fn uploaded_bytes(&self) -> u64 {
+        self.uploaded
+    }
+}
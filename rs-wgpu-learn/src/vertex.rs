@@ -0,0 +1,311 @@
+use wgpu::util::DeviceExt;
+
+/// 可以作为顶点属性的字段类型及其对应的顶点格式（由`vertex_layout!`使用）
+#[diagnostic::on_unimplemented(
+    message = "`{Self}` cannot be used as a vertex attribute",
+    label = "unsupported vertex field type",
+    note = "supported types: f32, [f32; 2], [f32; 3], [f32; 4], [[f32; 4]; 4], u32 and [u8; 4] (Unorm8x4)"
+)]
+pub trait VertexField: bytemuck::Pod {
+    /// 每个位置（`@location`）的格式
+    const FORMAT: wgpu::VertexFormat;
+    /// 占用的连续位置数，顶点属性最多是vec4，矩阵需要拆成多列
+    const LOCATIONS: u32 = 1;
+}
+
+impl VertexField for f32 {
+    const FORMAT: wgpu::VertexFormat = wgpu::VertexFormat::Float32;
+}
+
+impl VertexField for [f32; 2] {
+    const FORMAT: wgpu::VertexFormat = wgpu::VertexFormat::Float32x2;
+}
+
+impl VertexField for [f32; 3] {
+    const FORMAT: wgpu::VertexFormat = wgpu::VertexFormat::Float32x3;
+}
+
+impl VertexField for [f32; 4] {
+    const FORMAT: wgpu::VertexFormat = wgpu::VertexFormat::Float32x4;
+}
+
+/// 4x4矩阵（按列存储），占用4个连续位置，着色器中由4个vec4f重新组成mat4x4f
+impl VertexField for [[f32; 4]; 4] {
+    const FORMAT: wgpu::VertexFormat = wgpu::VertexFormat::Float32x4;
+    const LOCATIONS: u32 = 4;
+}
+
+impl VertexField for u32 {
+    const FORMAT: wgpu::VertexFormat = wgpu::VertexFormat::Uint32;
+}
+
+/// 4个字节的颜色，着色器中读到的是归一化到0..1的vec4f
+impl VertexField for [u8; 4] {
+    const FORMAT: wgpu::VertexFormat = wgpu::VertexFormat::Unorm8x4;
+}
+
+/// 定义顶点结构体并生成对应的顶点缓冲区布局，避免手写属性偏移量出错
+///
+/// 结构体自动添加`#[repr(C)]`以及`Clone`、`Copy`、`bytemuck::Pod`、`bytemuck::Zeroable`，
+/// 并生成`ATTRIBUTES`、`LAYOUT`常量和`layout()`：字段按声明顺序依次占用`@location`
+/// （矩阵占用连续的4个，每列一个），偏移量取自`offset_of!`，格式由字段类型决定
+/// （见`VertexField`，不支持的类型无法编译）。步长、偏移量与结构体的内存排列在编译期断言一致。
+///
+/// 结构体前可以加两个选项（写在其他属性之前或之后均可）：
+/// - `#[step_mode(Instance)]`：按实例步进，用于每个实例一份的数据（默认为`Vertex`）
+/// - `#[start_location(N)]`：第一个字段的`@location`（默认为0），与顶点缓冲区同时使用时避免冲突
+///
+/// ```text
+/// vertex_layout! {
+///     #[step_mode(Instance)]
+///     #[start_location(2)]
+///     pub struct InstanceRaw {
+///         pub offset: [f32; 2], // @location(2)
+///         pub color: [u8; 4],   // @location(3)
+///     }
+/// }
+/// ```
+#[macro_export]
+macro_rules! vertex_layout {
+    (@parse [$mode:ident] [$location:expr] [$($attr:tt)*]
+        #[step_mode($new_mode:ident)] $($rest:tt)*) => {
+        $crate::vertex_layout!(@parse [$new_mode] [$location] [$($attr)*] $($rest)*);
+    };
+    (@parse [$mode:ident] [$location:expr] [$($attr:tt)*]
+        #[start_location($new_location:expr)] $($rest:tt)*) => {
+        $crate::vertex_layout!(@parse [$mode] [$new_location] [$($attr)*] $($rest)*);
+    };
+    (@parse [$mode:ident] [$location:expr] [$($attr:tt)*]
+        #[$meta:meta] $($rest:tt)*) => {
+        $crate::vertex_layout!(@parse [$mode] [$location] [$($attr)* #[$meta]] $($rest)*);
+    };
+    (@parse [$mode:ident] [$location:expr] [$($attr:tt)*]
+        $vis:vis struct $name:ident {
+            $($(#[$field_meta:meta])* $field_vis:vis $field:ident : $ty:ty),* $(,)?
+        }) => {
+        $($attr)*
+        #[repr(C)]
+        #[derive(Clone, Copy, ::bytemuck::Pod, ::bytemuck::Zeroable)]
+        $vis struct $name {
+            $($(#[$field_meta])* $field_vis $field: $ty,)*
+        }
+
+        impl $name {
+            /// 顶点属性：字段按声明顺序依次占用`@location`
+            pub const ATTRIBUTES: [::wgpu::VertexAttribute;
+                0 $(+ <$ty as $crate::VertexField>::LOCATIONS as usize)*] = {
+                let formats = [$(<$ty as $crate::VertexField>::FORMAT),*];
+                let locations = [$(<$ty as $crate::VertexField>::LOCATIONS),*];
+                let offsets = [$(::std::mem::offset_of!($name, $field) as ::wgpu::BufferAddress),*];
+                let mut attributes = [::wgpu::VertexAttribute {
+                    format: ::wgpu::VertexFormat::Float32,
+                    offset: 0,
+                    shader_location: 0,
+                }; 0 $(+ <$ty as $crate::VertexField>::LOCATIONS as usize)*];
+                let (mut field, mut i) = (0, 0);
+                while field < formats.len() {
+                    // 占用多个位置的字段（矩阵）每列偏移一个格式的大小
+                    let mut column = 0;
+                    while column < locations[field] {
+                        attributes[i] = ::wgpu::VertexAttribute {
+                            format: formats[field],
+                            offset: offsets[field] + column as u64 * formats[field].size(),
+                            shader_location: ($location) + i as u32,
+                        };
+                        column += 1;
+                        i += 1;
+                    }
+                    field += 1;
+                }
+                attributes
+            };
+
+            /// 顶点缓冲区布局，传给`VertexState.buffers`
+            pub const LAYOUT: ::wgpu::VertexBufferLayout<'static> = ::wgpu::VertexBufferLayout {
+                array_stride: ::std::mem::size_of::<$name>() as ::wgpu::BufferAddress,
+                step_mode: ::wgpu::VertexStepMode::$mode,
+                attributes: &Self::ATTRIBUTES,
+            };
+
+            /// 顶点缓冲区布局（与`LAYOUT`相同）
+            pub const fn layout() -> ::wgpu::VertexBufferLayout<'static> {
+                Self::LAYOUT
+            }
+        }
+
+        // 布局与结构体的内存排列必须一致，否则着色器读到的数据会错位
+        const _: () = {
+            let stride = ::std::mem::size_of::<$name>() as ::wgpu::BufferAddress;
+            assert!($name::LAYOUT.array_stride == stride);
+            let offsets: &[::wgpu::BufferAddress] =
+                &[$(::std::mem::offset_of!($name, $field) as ::wgpu::BufferAddress),*];
+            let locations: &[u32] = &[$(<$ty as $crate::VertexField>::LOCATIONS),*];
+            let (mut field, mut i) = (0, 0);
+            while field < offsets.len() {
+                assert!($name::ATTRIBUTES[i].offset == offsets[field]);
+                i += locations[field] as usize;
+                field += 1;
+            }
+            let mut i = 0;
+            while i < $name::ATTRIBUTES.len() {
+                let attribute = &$name::ATTRIBUTES[i];
+                assert!(attribute.offset + attribute.format.size() <= stride);
+                i += 1;
+            }
+        };
+    };
+    ($($input:tt)*) => {
+        $crate::vertex_layout!(@parse [Vertex] [0] [] $($input)*);
+    };
+}
+
+vertex_layout! {
+    /// 顶点数据：位置和颜色，按`#[repr(C)]`布局后直接作为字节上传到顶点缓冲区
+    #[derive(Debug, PartialEq)]
+    pub struct Vertex {
+        pub position: [f32; 3], // 裁剪空间坐标，@location(0)
+        pub color: [f32; 3],    // 线性空间的RGB颜色，@location(1)
+    }
+}
+
+vertex_layout! {
+    /// 带法线和纹理坐标的顶点，用于需要光照或贴图的网格（见`shapes`）
+    #[derive(Debug, PartialEq)]
+    pub struct MeshVertex {
+        pub position: [f32; 3], // 模型空间坐标，@location(0)
+        pub normal: [f32; 3],   // 单位法线（朝外），@location(1)
+        pub uv: [f32; 2],       // 纹理坐标（左上角为(0, 0)），@location(2)
+    }
+}
+
+vertex_layout! {
+    /// 在MeshVertex的基础上增加切线，用于法线贴图（如glTF模型）
+    #[derive(Debug, PartialEq)]
+    pub struct TangentVertex {
+        pub position: [f32; 3], // 模型空间坐标，@location(0)
+        pub normal: [f32; 3],   // 单位法线，@location(1)
+        pub tangent: [f32; 4],  // 单位切线xyz，w为副切线方向（±1）；没有切线时为0，@location(2)
+        pub uv: [f32; 2],       // 纹理坐标（左上角为(0, 0)），@location(3)
+    }
+}
+
+/// 带模型空间位置的顶点，`Mesh::new`用它在创建网格时计算包围盒
+pub trait VertexPosition: bytemuck::Pod {
+    /// 顶点位置
+    fn position(&self) -> [f32; 3];
+}
+
+impl VertexPosition for Vertex {
+    fn position(&self) -> [f32; 3] {
+        self.position
+    }
+}
+
+impl VertexPosition for MeshVertex {
+    fn position(&self) -> [f32; 3] {
+        self.position
+    }
+}
+
+impl VertexPosition for TangentVertex {
+    fn position(&self) -> [f32; 3] {
+        self.position
+    }
+}
+
+/// 带法线的顶点，用于调试时显示法线（见`DebugLines::add_normals`）
+pub trait VertexNormal: VertexPosition {
+    /// 顶点法线（模型空间）
+    fn normal(&self) -> [f32; 3];
+}
+
+impl VertexNormal for MeshVertex {
+    fn normal(&self) -> [f32; 3] {
+        self.normal
+    }
+}
+
+impl VertexNormal for TangentVertex {
+    fn normal(&self) -> [f32; 3] {
+        self.normal
+    }
+}
+
+/// 用顶点数据创建顶点缓冲区
+pub fn create_vertex_buffer<V: bytemuck::Pod>(
+    device: &wgpu::Device,
+    label: Option<&str>,
+    vertices: &[V],
+) -> wgpu::Buffer {
+    device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+        label,
+        contents: bytemuck::cast_slice(vertices),
+        usage: wgpu::BufferUsages::VERTEX,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::mem::{offset_of, size_of};
+
+    #[test]
+    fn vertex_layout_matches_the_struct() {
+        assert_eq!(size_of::<Vertex>(), 24);
+        assert_eq!(Vertex::LAYOUT.array_stride, size_of::<Vertex>() as u64);
+        assert_eq!(Vertex::LAYOUT.step_mode, wgpu::VertexStepMode::Vertex);
+        assert_eq!(
+            Vertex::ATTRIBUTES,
+            [
+                wgpu::VertexAttribute {
+                    format: wgpu::VertexFormat::Float32x3,
+                    offset: offset_of!(Vertex, position) as u64,
+                    shader_location: 0,
+                },
+                wgpu::VertexAttribute {
+                    format: wgpu::VertexFormat::Float32x3,
+                    offset: offset_of!(Vertex, color) as u64,
+                    shader_location: 1,
+                },
+            ]
+        );
+        assert_eq!(Vertex::ATTRIBUTES[1].offset, 12);
+    }
+
+    #[test]
+    fn vertex_bytes_follow_the_layout() {
+        let vertex = Vertex {
+            position: [1.0, 2.0, 3.0],
+            color: [0.25, 0.5, 0.75],
+        };
+        let bytes = bytemuck::bytes_of(&vertex);
+        let color_offset = Vertex::ATTRIBUTES[1].offset as usize;
+        let color: [f32; 3] = bytemuck::pod_read_unaligned(&bytes[color_offset..color_offset + 12]);
+        assert_eq!(color, vertex.color);
+    }
+
+    vertex_layout! {
+        #[step_mode(Instance)]
+        #[start_location(3)]
+        struct InstanceRaw {
+            model: [[f32; 4]; 4],
+            tint: [u8; 4],
+        }
+    }
+
+    #[test]
+    fn matrices_take_one_location_per_column_after_the_start_location() {
+        let layout = InstanceRaw::layout();
+        assert_eq!(layout.step_mode, wgpu::VertexStepMode::Instance);
+        assert_eq!(layout.array_stride, 68);
+        let locations: Vec<_> = InstanceRaw::ATTRIBUTES
+            .iter()
+            .map(|attribute| (attribute.shader_location, attribute.offset))
+            .collect();
+        assert_eq!(locations, [(3, 0), (4, 16), (5, 32), (6, 48), (7, 64)]);
+        assert_eq!(
+            InstanceRaw::ATTRIBUTES[4].format,
+            wgpu::VertexFormat::Unorm8x4
+        );
+    }
+}
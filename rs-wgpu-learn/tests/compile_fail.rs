@@ -0,0 +1,8 @@
+// trybuild在桌面上编译测试用例
+#![cfg(not(target_arch = "wasm32"))]
+
+/// 不支持的字段类型不能用于`vertex_layout!`，错误信息指出是哪个类型
+#[test]
+fn vertex_layout_rejects_unsupported_field_types() {
+    trybuild::TestCases::new().compile_fail("tests/compile_fail/*.rs");
+}
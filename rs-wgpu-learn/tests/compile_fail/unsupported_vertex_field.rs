@@ -0,0 +1,8 @@
+rs_wgpu_learn::vertex_layout! {
+    pub struct SkinnedVertex {
+        pub position: [f32; 3],
+        pub joints: [u16; 2],
+    }
+}
+
+fn main() {}
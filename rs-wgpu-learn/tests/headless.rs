@@ -0,0 +1,768 @@
+// 无窗口应用只在桌面上可用
+#![cfg(not(target_arch = "wasm32"))]
+
+use glam::Vec3;
+use rs_wgpu_learn::{
+    CUBE_FACES, CubeMap, HeadlessApp, InitContext, RenderContext, Renderer, SamplerDesc,
+    VolumeTexture, WgpuAppBuilder, WgpuAppError,
+    camera::{Camera, Projection},
+    mipmap::MipmapGenerator,
+    noise::{self, NoiseGenerator, NoiseParams},
+    renderers::Triangle,
+    shader,
+    skybox::SkyboxPass,
+};
+use std::{collections::HashMap, f64::consts::PI, path::Path};
+use wgpu::util::DeviceExt;
+
+/// 离屏纹理边长
+const SIZE: u32 = 64;
+
+/// 带调试文字的十字布局立方体贴图，每个面的背景颜色不同
+const DEBUG_CUBE_MAP: &[u8] = include_bytes!("assets/debug_cubemap.png");
+
+/// 创建无窗口的应用，主要后端没有可用适配器时改用GL后端（例如只有软件光栅化的CI机器）
+///
+/// 适配器支持时启用`TEXTURE_ADAPTER_SPECIFIC_FORMAT_FEATURES`，以便测试`R16Float`存储纹理等格式特性。
+///
+/// 两种后端都没有适配器时返回None，测试跳过。
+fn headless<R: Renderer>() -> Option<HeadlessApp<R>> {
+    let build = |backends| {
+        pollster::block_on(
+            WgpuAppBuilder::new()
+                .backends(backends)
+                .optional_features(wgpu::Features::TEXTURE_ADAPTER_SPECIFIC_FORMAT_FEATURES)
+                .build_headless::<R>(SIZE, SIZE),
+        )
+    };
+    let result = match build(wgpu::Backends::PRIMARY) {
+        Err(WgpuAppError::NoAdapter(_)) => build(wgpu::Backends::GL),
+        result => result,
+    };
+    match result {
+        Ok(app) => Some(app),
+        Err(WgpuAppError::NoAdapter(backends)) => {
+            eprintln!("No adapter for {backends:?}, skipping headless test");
+            None
+        }
+        Err(e) => panic!("Failed to create headless app: {e}"),
+    }
+}
+
+/// 无窗口的三角形应用，见`headless`
+fn headless_triangle() -> Option<HeadlessApp<Triangle>> {
+    headless()
+}
+
+#[test]
+fn triangle_covers_the_center_pixel() {
+    let Some(mut app) = headless_triangle() else {
+        return;
+    };
+    app.render();
+    let pixels = app.read_pixels().unwrap();
+    assert_eq!(pixels.len(), (SIZE * SIZE * 4) as usize);
+
+    let center = ((SIZE / 2 * SIZE + SIZE / 2) * 4) as usize;
+    let [r, g, b, _] = pixels[center..center + 4] else {
+        unreachable!()
+    };
+    assert!(
+        r > 0 || g > 0 || b > 0,
+        "center pixel is black: {:?}",
+        &pixels[center..center + 4]
+    );
+}
+
+#[test]
+fn corner_pixel_keeps_the_clear_color() {
+    let Some(mut app) = headless_triangle() else {
+        return;
+    };
+    app.render();
+    let pixels = app.read_pixels().unwrap();
+    // 清除色为黑色，三角形不覆盖左上角
+    assert_eq!(&pixels[..3], &[0, 0, 0]);
+}
+
+#[test]
+fn sampler_cache_creates_each_configuration_once() {
+    let Some(app) = headless_triangle() else {
+        return;
+    };
+    // 渲染器初始化时可能已经创建了一些采样器，用一个它们不会用到的配置
+    let desc = SamplerDesc::linear_repeat()
+        .with_address_mode(wgpu::AddressMode::MirrorRepeat)
+        .with_lod_clamp(0.0, 3.0);
+    let before = app.samplers.len();
+    app.samplers.get(&desc).unwrap();
+    app.samplers.get(&desc).unwrap();
+    assert_eq!(app.samplers.len(), before + 1);
+
+    // 无效的配置返回错误，不会进入缓存
+    assert!(app.samplers.get(&desc.with_anisotropy(0)).is_err());
+    assert_eq!(app.samplers.len(), before + 1);
+}
+
+#[test]
+fn unaligned_uploads_are_rejected_without_touching_neighbours() {
+    let Some(app) = headless_triangle() else {
+        return;
+    };
+    let device = &app.device;
+    let target = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+        label: Some("Upload Target"),
+        contents: &[0xAA; 16],
+        usage: wgpu::BufferUsages::COPY_DST | wgpu::BufferUsages::COPY_SRC,
+    });
+    let readback = device.create_buffer(&wgpu::BufferDescriptor {
+        label: Some("Upload Readback"),
+        size: 16,
+        usage: wgpu::BufferUsages::COPY_DST | wgpu::BufferUsages::MAP_READ,
+        mapped_at_creation: false,
+    });
+
+    let mut encoder = device.create_command_encoder(&Default::default());
+    let mut uploader = app.uploader.borrow_mut();
+    // 未对齐的偏移量和长度都返回错误而不是panic或补0
+    assert!(matches!(
+        uploader.write(&mut encoder, &target, 2, &[1; 4]),
+        Err(WgpuAppError::InvalidUpload { offset: 2, .. })
+    ));
+    assert!(matches!(
+        uploader.write(&mut encoder, &target, 4, &[1; 3]),
+        Err(WgpuAppError::InvalidUpload { len: 3, .. })
+    ));
+    assert!(matches!(
+        uploader.write_slice(&mut encoder, &target, 12, &[1u32; 2]),
+        Err(WgpuAppError::InvalidUpload { .. })
+    ));
+    uploader
+        .write_slice(&mut encoder, &target, 4, &[0x0101_0101u32])
+        .unwrap();
+    uploader.finish();
+    encoder.copy_buffer_to_buffer(&target, 0, &readback, 0, 16);
+    app.queue.submit(Some(encoder.finish()));
+    uploader.recall();
+
+    readback
+        .slice(..)
+        .map_async(wgpu::MapMode::Read, |r| r.unwrap());
+    device.poll(wgpu::Maintain::Wait);
+    let bytes = readback.slice(..).get_mapped_range().to_vec();
+    let mut expected = [0xAA; 16];
+    expected[4..8].fill(1);
+    assert_eq!(bytes, expected);
+}
+
+/// 读回texture第level级左上角的一个像素（RGBA8）
+fn read_texel(app: &HeadlessApp<Triangle>, texture: &wgpu::Texture, level: u32) -> [u8; 4] {
+    let buffer = app.device.create_buffer(&wgpu::BufferDescriptor {
+        label: Some("Texel Readback"),
+        size: 4,
+        usage: wgpu::BufferUsages::COPY_DST | wgpu::BufferUsages::MAP_READ,
+        mapped_at_creation: false,
+    });
+    let mut encoder = app.device.create_command_encoder(&Default::default());
+    encoder.copy_texture_to_buffer(
+        wgpu::TexelCopyTextureInfo {
+            texture,
+            mip_level: level,
+            origin: wgpu::Origin3d::ZERO,
+            aspect: wgpu::TextureAspect::All,
+        },
+        wgpu::TexelCopyBufferInfo {
+            buffer: &buffer,
+            layout: wgpu::TexelCopyBufferLayout {
+                offset: 0,
+                bytes_per_row: None,
+                rows_per_image: None,
+            },
+        },
+        wgpu::Extent3d::default(),
+    );
+    app.queue.submit(Some(encoder.finish()));
+    buffer
+        .slice(..)
+        .map_async(wgpu::MapMode::Read, |r| r.unwrap());
+    app.device.poll(wgpu::Maintain::Wait);
+    buffer.slice(..).get_mapped_range()[..4].try_into().unwrap()
+}
+
+#[test]
+fn odd_sized_mip_levels_weight_every_parent_texel() {
+    let Some(app) = headless_triangle() else {
+        return;
+    };
+    // 3x3的图片只有中心是白色：下一级（1x1）覆盖全部9个像素，每个占1/9
+    // （在中心线性采样只会读到中心的白色）
+    let mut image = image::RgbaImage::from_pixel(3, 3, image::Rgba([0, 0, 0, 255]));
+    image.put_pixel(1, 1, image::Rgba([255, 255, 255, 255]));
+    let size = wgpu::Extent3d {
+        width: 3,
+        height: 3,
+        depth_or_array_layers: 1,
+    };
+    let texture = app.device.create_texture(&wgpu::TextureDescriptor {
+        label: Some("Odd Mipmap Test"),
+        size,
+        mip_level_count: 2,
+        sample_count: 1,
+        dimension: wgpu::TextureDimension::D2,
+        format: wgpu::TextureFormat::Rgba8Unorm,
+        usage: wgpu::TextureUsages::TEXTURE_BINDING
+            | wgpu::TextureUsages::RENDER_ATTACHMENT
+            | wgpu::TextureUsages::COPY_DST
+            | wgpu::TextureUsages::COPY_SRC,
+        view_formats: &[],
+    });
+    app.queue.write_texture(
+        texture.as_image_copy(),
+        &image,
+        wgpu::TexelCopyBufferLayout {
+            offset: 0,
+            bytes_per_row: Some(3 * 4),
+            rows_per_image: None,
+        },
+        size,
+    );
+    let mut encoder = app.device.create_command_encoder(&Default::default());
+    MipmapGenerator::new(&app.device)
+        .unwrap()
+        .generate(&app.device, &mut encoder, &texture);
+    app.queue.submit(Some(encoder.finish()));
+    let [r, g, b, a] = read_texel(&app, &texture, 1);
+    for channel in [r, g, b] {
+        assert!(channel.abs_diff(28) <= 1, "{:?}", [r, g, b, a]);
+    }
+    assert_eq!(a, 255);
+}
+
+/// 用`DEBUG_CUBE_MAP`绘制天空盒的测试渲染器，相机位于原点看向camera.target
+struct SkyboxProbe {
+    skybox: SkyboxPass,
+    camera: Camera,
+}
+
+impl Renderer for SkyboxProbe {
+    fn init(ctx: &InitContext) -> Result<Self, WgpuAppError> {
+        let InitContext {
+            device,
+            queue,
+            config,
+            sample_count,
+            cache,
+            samplers,
+            ..
+        } = *ctx;
+        let image = image::load_from_memory(DEBUG_CUBE_MAP).map_err(WgpuAppError::ImageDecode)?;
+        let cube_map = CubeMap::from_cross(
+            device,
+            queue,
+            samplers,
+            &image,
+            &mut MipmapGenerator::new(device)?,
+            Some("Debug Cube Map"),
+        )?;
+        Ok(Self {
+            skybox: SkyboxPass::new(device, config.format, sample_count, cache, &cube_map)?,
+            camera: Camera {
+                eye: Vec3::ZERO,
+                projection: Projection::Perspective {
+                    // 比一个面的90度窄得多：画面只落在一个面内，并且纹理被放大，只采样第0级
+                    fovy: 40f32.to_radians(),
+                    znear: 0.1,
+                    zfar: 10.0,
+                },
+                ..Default::default()
+            },
+        })
+    }
+
+    fn render(
+        &mut self,
+        ctx: &RenderContext,
+        view: &wgpu::TextureView,
+        encoder: &mut wgpu::CommandEncoder,
+    ) {
+        self.skybox.prepare(
+            ctx,
+            encoder,
+            self.camera.view(),
+            self.camera.projection(1.0),
+        );
+        let mut pass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
+            label: Some("Skybox Probe Pass"),
+            color_attachments: &[Some(ctx.color_attachment(
+                view,
+                wgpu::Operations {
+                    load: wgpu::LoadOp::Clear(ctx.clear_color),
+                    store: wgpu::StoreOp::Store,
+                },
+            ))],
+            depth_stencil_attachment: ctx.depth_attachment(),
+            timestamp_writes: None,
+            occlusion_query_set: None,
+        });
+        self.skybox.draw(&mut pass);
+    }
+}
+
+#[test]
+fn skybox_shows_the_cross_face_in_each_axis_direction() {
+    let Some(mut app) = headless::<SkyboxProbe>() else {
+        return;
+    };
+    // 每个面右下角的背景颜色（左上角有方向标记），面在十字中的位置与`CubeMap::from_cross`一致
+    let cross = image::load_from_memory(DEBUG_CUBE_MAP).unwrap().to_rgba8();
+    let face_size = cross.width() / 4;
+    let backgrounds = [(2, 1), (0, 1), (1, 0), (1, 2), (1, 1), (3, 1)].map(|(column, row)| {
+        let pixel = cross.get_pixel((column + 1) * face_size - 4, (row + 1) * face_size - 4);
+        [pixel[0], pixel[1], pixel[2]]
+    });
+
+    // 立方体贴图是左手系，天空盒采样时把z取反，所以相机看向世界的-Z时看到的是+Z面
+    let directions = [
+        (Vec3::X, Vec3::Y, "+X"),
+        (Vec3::NEG_X, Vec3::Y, "-X"),
+        (Vec3::Y, Vec3::Z, "+Y"),
+        (Vec3::NEG_Y, Vec3::Z, "-Y"),
+        (Vec3::NEG_Z, Vec3::Y, "+Z"),
+        (Vec3::Z, Vec3::Y, "-Z"),
+    ];
+    for (direction, up, expected) in directions {
+        app.renderer.camera.target = direction;
+        app.renderer.camera.up = up;
+        app.render();
+        let pixels = app.read_pixels().unwrap();
+
+        // 文字是白色的，按背景颜色统计每个面占的像素，文字和过滤后的边缘不计入
+        let mut counts = [0; 6];
+        for pixel in pixels.chunks_exact(4) {
+            let matched = backgrounds.iter().position(|background| {
+                background
+                    .iter()
+                    .zip(pixel)
+                    .all(|(&a, &b)| a.abs_diff(b) <= 4)
+            });
+            if let Some(face) = matched {
+                counts[face] += 1;
+            }
+        }
+        let (face, &count) = counts
+            .iter()
+            .enumerate()
+            .max_by_key(|&(_, count)| *count)
+            .unwrap();
+        assert_eq!(
+            CUBE_FACES[face], expected,
+            "looking along {direction}: face pixel counts {counts:?}"
+        );
+        assert!(
+            count > pixels.len() / 4 / 2,
+            "looking along {direction}: only {count} pixels match {expected}"
+        );
+    }
+}
+
+/// 在计算着色器中对每组输入调用pbr.wgsl的`pbr_direct`（法线为+Z，辐射度为1）
+const PBR_PROBE: &str = r#"
+struct Case {
+    albedo: vec3f,
+    metallic: f32,
+    v: vec3f,
+    roughness: f32,
+    l: vec3f,
+    _padding: f32,
+}
+
+@group(0) @binding(0) var<storage, read> cases: array<Case>;
+@group(0) @binding(1) var<storage, read_write> results: array<vec4f>;
+
+@compute @workgroup_size(1)
+fn main(@builtin(global_invocation_id) id: vec3u) {
+    let input = cases[id.x];
+    var surface: PbrSurface;
+    surface.albedo = input.albedo;
+    surface.metallic = input.metallic;
+    surface.emissive = vec3f(0.0);
+    surface.roughness = input.roughness;
+    surface.normal = vec3f(0.0, 0.0, 1.0);
+    surface.occlusion = 1.0;
+    results[id.x] = vec4f(pbr_direct(surface, input.v, input.l, vec3f(1.0)), 0.0);
+}
+"#;
+
+/// glTF 2.0规范附录B的金属度-粗糙度BRDF乘以n·l（法线为+Z），Khronos的参考查看器使用同样的公式
+fn reference_brdf(
+    albedo: [f64; 3],
+    metallic: f64,
+    roughness: f64,
+    v: [f64; 3],
+    l: [f64; 3],
+) -> [f64; 3] {
+    let (n_dot_v, n_dot_l) = (v[2], l[2]);
+    if n_dot_l <= 0.0 {
+        return [0.0; 3];
+    }
+    let h = [v[0] + l[0], v[1] + l[1], v[2] + l[2]];
+    let length = (h[0] * h[0] + h[1] * h[1] + h[2] * h[2]).sqrt();
+    let n_dot_h = h[2] / length;
+    let v_dot_h = (v[0] * h[0] + v[1] * h[1] + v[2] * h[2]) / length;
+
+    let alpha = roughness * roughness;
+    let a2 = alpha * alpha;
+    let d = a2 / (PI * (n_dot_h * n_dot_h * (a2 - 1.0) + 1.0).powi(2));
+    let visibility = 0.5
+        / (n_dot_l * (n_dot_v * n_dot_v * (1.0 - a2) + a2).sqrt()
+            + n_dot_v * (n_dot_l * n_dot_l * (1.0 - a2) + a2).sqrt());
+    albedo.map(|base| {
+        let f0 = 0.04 + (base - 0.04) * metallic;
+        let fresnel = f0 + (1.0 - f0) * (1.0 - v_dot_h).powi(5);
+        let diffuse = (1.0 - fresnel) * base * (1.0 - metallic) / PI;
+        (diffuse + fresnel * d * visibility) * n_dot_l
+    })
+}
+
+/// 天顶角theta、方位角phi（度）对应的单位向量，theta = 0为法线方向
+fn direction(theta: f64, phi: f64) -> [f64; 3] {
+    let (theta, phi) = (theta.to_radians(), phi.to_radians());
+    [
+        theta.sin() * phi.cos(),
+        theta.sin() * phi.sin(),
+        theta.cos(),
+    ]
+}
+
+// DamagedHelmet与参考查看器并排对比的验收方式需要样例模型和参考查看器，无法离线进行；
+// 改为逐项比较直接光照的BRDF与规范公式，覆盖不同的粗糙度、金属度和入射、出射角度（包括掠射角）
+#[test]
+fn pbr_direct_lighting_matches_the_gltf_reference_brdf() {
+    let Some(app) = headless_triangle() else {
+        return;
+    };
+    let device = &app.device;
+
+    // 1. 生成输入：每组12个f32，与着色器中的Case对应
+    let albedo = [0.8, 0.5, 0.2];
+    let mut cases = Vec::new();
+    for roughness in [0.2, 0.5, 0.8, 1.0] {
+        for metallic in [0.0, 0.5, 1.0] {
+            for view_theta in [0.0, 30.0, 60.0, 80.0] {
+                for (light_theta, light_phi) in [
+                    (0.0, 0.0),
+                    (45.0, 180.0),
+                    (70.0, 90.0),
+                    (85.0, 0.0),
+                    (100.0, 0.0),
+                ] {
+                    cases.push((
+                        roughness,
+                        metallic,
+                        direction(view_theta, 0.0),
+                        direction(light_theta, light_phi),
+                    ));
+                }
+            }
+        }
+    }
+    let input = cases
+        .iter()
+        .flat_map(|&(roughness, metallic, v, l)| {
+            [
+                albedo[0], albedo[1], albedo[2], metallic, v[0], v[1], v[2], roughness, l[0], l[1],
+                l[2], 0.0,
+            ]
+            .map(|x: f64| x as f32)
+        })
+        .collect::<Vec<_>>();
+
+    // 2. 把pbr.wgsl（展开include后）与测试入口拼在一起，在计算着色器中求值
+    // 着色器或绑定出错时结果全为0，捕获验证错误直接报告
+    device.push_error_scope(wgpu::ErrorFilter::Validation);
+    let pbr = shader::preprocess(Path::new("pbr.wgsl"), &HashMap::new()).unwrap();
+    let module = device.create_shader_module(wgpu::ShaderModuleDescriptor {
+        label: Some("PBR Probe"),
+        source: wgpu::ShaderSource::Wgsl(format!("{}\n{PBR_PROBE}", pbr.source).into()),
+    });
+    let pipeline = device.create_compute_pipeline(&wgpu::ComputePipelineDescriptor {
+        label: Some("PBR Probe Pipeline"),
+        layout: None,
+        module: &module,
+        entry_point: Some("main"),
+        compilation_options: Default::default(),
+        cache: None,
+    });
+    let input_buffer = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+        label: Some("PBR Probe Cases"),
+        contents: bytemuck::cast_slice(&input),
+        usage: wgpu::BufferUsages::STORAGE,
+    });
+    let size = (cases.len() * 16) as u64;
+    let output_buffer = device.create_buffer(&wgpu::BufferDescriptor {
+        label: Some("PBR Probe Results"),
+        size,
+        usage: wgpu::BufferUsages::STORAGE | wgpu::BufferUsages::COPY_SRC,
+        mapped_at_creation: false,
+    });
+    let readback = device.create_buffer(&wgpu::BufferDescriptor {
+        label: Some("PBR Probe Readback"),
+        size,
+        usage: wgpu::BufferUsages::COPY_DST | wgpu::BufferUsages::MAP_READ,
+        mapped_at_creation: false,
+    });
+    let bind_group = device.create_bind_group(&wgpu::BindGroupDescriptor {
+        label: Some("PBR Probe Bind Group"),
+        layout: &pipeline.get_bind_group_layout(0),
+        entries: &[
+            wgpu::BindGroupEntry {
+                binding: 0,
+                resource: input_buffer.as_entire_binding(),
+            },
+            wgpu::BindGroupEntry {
+                binding: 1,
+                resource: output_buffer.as_entire_binding(),
+            },
+        ],
+    });
+    let mut encoder = device.create_command_encoder(&Default::default());
+    {
+        let mut pass = encoder.begin_compute_pass(&Default::default());
+        pass.set_pipeline(&pipeline);
+        pass.set_bind_group(0, &bind_group, &[]);
+        pass.dispatch_workgroups(cases.len() as u32, 1, 1);
+    }
+    encoder.copy_buffer_to_buffer(&output_buffer, 0, &readback, 0, size);
+    app.queue.submit(Some(encoder.finish()));
+    if let Some(e) = pollster::block_on(device.pop_error_scope()) {
+        panic!("{e}");
+    }
+    readback
+        .slice(..)
+        .map_async(wgpu::MapMode::Read, |r| r.unwrap());
+    device.poll(wgpu::Maintain::Wait);
+    let results =
+        bytemuck::cast_slice::<u8, [f32; 4]>(&readback.slice(..).get_mapped_range()).to_vec();
+
+    // 3. 与规范公式逐通道比较
+    for (&(roughness, metallic, v, l), result) in cases.iter().zip(&results) {
+        let expected = reference_brdf(albedo, metallic, roughness, v, l);
+        for (&actual, expected) in result.iter().zip(expected) {
+            assert!(
+                (f64::from(actual) - expected).abs() <= 1e-3 * expected + 1e-6,
+                "roughness {roughness}, metallic {metallic}, v {v:?}, l {l:?}: got {result:?}, expected {expected}"
+            );
+        }
+    }
+}
+
+/// 噪声测试的体积边长（不是工作组大小4的整数倍，覆盖边界处越界的线程）
+const NOISE_SIZE: u32 = 6;
+
+/// 噪声测试的参数
+const NOISE_PARAMS: NoiseParams = NoiseParams {
+    seed: 7,
+    period: 3,
+    octaves: 2,
+};
+
+/// 创建NOISE_SIZE³、带COPY_SRC用途的噪声体积
+fn noise_volume_texture(
+    app: &HeadlessApp<Triangle>,
+    format: wgpu::TextureFormat,
+    usage: wgpu::TextureUsages,
+) -> VolumeTexture {
+    VolumeTexture::new(
+        &app.device,
+        &app.samplers,
+        Some("Noise Volume"),
+        wgpu::Extent3d {
+            width: NOISE_SIZE,
+            height: NOISE_SIZE,
+            depth_or_array_layers: NOISE_SIZE,
+        },
+        format,
+        usage | wgpu::TextureUsages::COPY_SRC,
+    )
+    .unwrap()
+}
+
+/// 逐层读回R16Float或R32Float的体积，每行按256字节对齐
+fn read_volume(app: &HeadlessApp<Triangle>, volume: &VolumeTexture) -> Vec<f32> {
+    let size = volume.size();
+    let bytes_per_row = wgpu::COPY_BYTES_PER_ROW_ALIGNMENT;
+    let buffer = app.device.create_buffer(&wgpu::BufferDescriptor {
+        label: Some("Noise Readback"),
+        size: u64::from(bytes_per_row * size.height * size.depth_or_array_layers),
+        usage: wgpu::BufferUsages::COPY_DST | wgpu::BufferUsages::MAP_READ,
+        mapped_at_creation: false,
+    });
+    let mut encoder = app.device.create_command_encoder(&Default::default());
+    encoder.copy_texture_to_buffer(
+        volume.texture.as_image_copy(),
+        wgpu::TexelCopyBufferInfo {
+            buffer: &buffer,
+            layout: wgpu::TexelCopyBufferLayout {
+                offset: 0,
+                bytes_per_row: Some(bytes_per_row),
+                rows_per_image: Some(size.height),
+            },
+        },
+        size,
+    );
+    app.queue.submit(Some(encoder.finish()));
+    buffer
+        .slice(..)
+        .map_async(wgpu::MapMode::Read, |r| r.unwrap());
+    app.device.poll(wgpu::Maintain::Wait);
+    let data = buffer.slice(..).get_mapped_range();
+    let rows = data.chunks(bytes_per_row as usize);
+    match volume.texture.format() {
+        wgpu::TextureFormat::R16Float => rows
+            .flat_map(|row| bytemuck::cast_slice::<u8, half::f16>(&row[..size.width as usize * 2]))
+            .map(|texel| texel.to_f32())
+            .collect(),
+        wgpu::TextureFormat::R32Float => rows
+            .flat_map(|row| bytemuck::cast_slice::<u8, f32>(&row[..size.width as usize * 4]))
+            .copied()
+            .collect(),
+        format => panic!("unexpected noise format {format:?}"),
+    }
+}
+
+/// 与CPU参考实现`noise_volume`的前layers层逐个比较，tolerance为允许的误差
+fn assert_matches_cpu_noise(texels: &[f32], layers: u32, tolerance: f32) {
+    let mut expected = noise::noise_volume(NOISE_SIZE, NOISE_PARAMS);
+    assert_eq!(texels.len(), expected.len());
+    let texels = &texels[..(NOISE_SIZE * NOISE_SIZE * layers) as usize];
+    expected.truncate(texels.len());
+    for (i, (actual, expected)) in texels.iter().zip(&expected).enumerate() {
+        assert!(
+            (actual - expected).abs() <= tolerance,
+            "texel {i}: got {actual}, expected {expected}"
+        );
+    }
+    // 噪声不是常数（排除着色器没有写入、全部为0的情况）
+    assert!(texels.iter().any(|&t| (t - texels[0]).abs() > 0.05));
+}
+
+#[test]
+fn noise_generator_uses_compute_only_when_the_adapter_allows_r16float_storage() {
+    let Some(app) = headless_triangle() else {
+        return;
+    };
+    let generator = NoiseGenerator::new(&app.adapter, &app.device, None);
+    let storage = app
+        .adapter
+        .get_texture_format_features(NoiseGenerator::FORMAT)
+        .allowed_usages
+        .contains(wgpu::TextureUsages::STORAGE_BINDING)
+        && app
+            .device
+            .features()
+            .contains(wgpu::Features::TEXTURE_ADAPTER_SPECIFIC_FORMAT_FEATURES)
+        && app.adapter_info.backend != wgpu::Backend::Gl;
+    // llvmpipe的GL后端不允许R16Float存储纹理，这里走CPU回退
+    assert_eq!(generator.uses_compute(), storage);
+
+    let usage = match generator.uses_compute() {
+        true => wgpu::TextureUsages::STORAGE_BINDING,
+        false => wgpu::TextureUsages::empty(),
+    };
+    let volume = noise_volume_texture(&app, NoiseGenerator::FORMAT, usage);
+    app.device.push_error_scope(wgpu::ErrorFilter::Validation);
+    generator.generate(&app.device, &app.queue, &volume, NOISE_PARAMS);
+    if let Some(e) = pollster::block_on(app.device.pop_error_scope()) {
+        panic!("{e}");
+    }
+    // R16Float在0..1内的精度约为5e-4
+    assert_matches_cpu_noise(&read_volume(&app, &volume), NOISE_SIZE, 2e-3);
+}
+
+#[test]
+fn noise_compute_shader_matches_the_cpu_reference() {
+    let Some(app) = headless_triangle() else {
+        return;
+    };
+    let device = &app.device;
+    if !app
+        .adapter
+        .get_downlevel_capabilities()
+        .flags
+        .contains(wgpu::DownlevelFlags::COMPUTE_SHADERS)
+        || device.limits().max_storage_textures_per_shader_stage == 0
+    {
+        eprintln!("No compute shaders or storage textures, skipping the noise shader test");
+        return;
+    }
+
+    // 1. R16Float存储纹理不一定可用（如llvmpipe），改写为所有后端都支持存储的R32Float，计算逻辑不变
+    device.push_error_scope(wgpu::ErrorFilter::Validation);
+    let source = shader::preprocess(Path::new("noise_volume.wgsl"), &HashMap::new())
+        .unwrap()
+        .source;
+    assert!(source.contains("r16float"));
+    let module = device.create_shader_module(wgpu::ShaderModuleDescriptor {
+        label: Some("Noise Probe"),
+        source: wgpu::ShaderSource::Wgsl(source.replace("r16float", "r32float").into()),
+    });
+    let pipeline = device.create_compute_pipeline(&wgpu::ComputePipelineDescriptor {
+        label: Some("Noise Probe Pipeline"),
+        layout: None,
+        module: &module,
+        entry_point: Some("cs"),
+        compilation_options: Default::default(),
+        cache: None,
+    });
+
+    // 2. 按着色器中的NoiseParams写入参数，每个线程写入一个纹素（工作组大小为4）
+    let params = [
+        NOISE_SIZE,
+        NOISE_PARAMS.seed,
+        NOISE_PARAMS.period,
+        NOISE_PARAMS.octaves,
+    ];
+    let params_buffer = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+        label: Some("Noise Probe Params"),
+        contents: bytemuck::cast_slice(&params),
+        usage: wgpu::BufferUsages::UNIFORM,
+    });
+    let volume = noise_volume_texture(
+        &app,
+        wgpu::TextureFormat::R32Float,
+        wgpu::TextureUsages::STORAGE_BINDING,
+    );
+    let bind_group = device.create_bind_group(&wgpu::BindGroupDescriptor {
+        label: Some("Noise Probe Bind Group"),
+        layout: &pipeline.get_bind_group_layout(0),
+        entries: &[
+            wgpu::BindGroupEntry {
+                binding: 0,
+                resource: params_buffer.as_entire_binding(),
+            },
+            wgpu::BindGroupEntry {
+                binding: 1,
+                resource: wgpu::BindingResource::TextureView(&volume.view),
+            },
+        ],
+    });
+    let mut encoder = device.create_command_encoder(&Default::default());
+    {
+        let mut pass = encoder.begin_compute_pass(&Default::default());
+        pass.set_pipeline(&pipeline);
+        pass.set_bind_group(0, &bind_group, &[]);
+        let groups = NOISE_SIZE.div_ceil(4);
+        pass.dispatch_workgroups(groups, groups, groups);
+    }
+    app.queue.submit(Some(encoder.finish()));
+    if let Some(e) = pollster::block_on(device.pop_error_scope()) {
+        panic!("{e}");
+    }
+
+    // 3. 与CPU参考实现比较（只差浮点误差）；GL后端把三维存储纹理只绑定为第0层，只比较这一层
+    let layers = match app.adapter_info.backend {
+        wgpu::Backend::Gl => 1,
+        _ => NOISE_SIZE,
+    };
+    assert_matches_cpu_noise(&read_volume(&app, &volume), layers, 1e-4);
+}
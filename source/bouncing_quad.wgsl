@@ -0,0 +1,23 @@
+// 在窗口中反弹的正方形：位置由CPU按固定步长更新，渲染时在前后两次更新之间插值
+
+struct QuadUniform {
+    center: vec2f,    // 插值后的中心位置（NDC）
+    half_size: vec2f, // 半边长（NDC，x方向已按宽高比缩放）
+}
+
+@group(0) @binding(0) var<uniform> quad: QuadUniform;
+
+@vertex
+fn vs(@builtin(vertex_index) vertex_index: u32) -> @builtin(position) vec4f {
+    // 两个三角形组成一个四边形
+    var corners = array(
+        vec2f(-1.0, -1.0), vec2f(1.0, -1.0), vec2f(1.0, 1.0),
+        vec2f(-1.0, -1.0), vec2f(1.0, 1.0), vec2f(-1.0, 1.0),
+    );
+    return vec4f(quad.center + corners[vertex_index] * quad.half_size, 0.0, 1.0);
+}
+
+@fragment
+fn fs() -> @location(0) vec4f {
+    return vec4f(0.2, 0.8, 0.4, 1.0);
+}
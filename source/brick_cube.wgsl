@@ -0,0 +1,81 @@
+// 法线贴图：旋转的砖块立方体被绕它移动的点光源照亮，顶点来自TangentVertex（切线由generate_tangents计算）
+// 材质的normal_map为1时用法线贴图在切线空间中扰动法线，为0时只使用顶点法线（每个面都是平的）
+// 立方体的-X、-Y、-Z面纹理坐标左右镜像（切线的w为-1），砖块的凹凸仍与其他面一样朝向光源
+
+#include "normal_map.wgsl"
+
+struct SceneUniform {
+    view_proj: mat4x4f,    // 相机的视图投影矩阵
+    model: mat4x4f,        // 模型空间到世界空间的变换（旋转）
+    normal: mat3x3f,       // 法线矩阵
+    light_position: vec3f, // 点光源的位置（世界空间）
+    light_intensity: f32,  // 点光源的强度（按距离的平方衰减）
+    eye: vec3f,            // 相机位置（世界空间），用于高光
+    ambient: f32,          // 环境光强度
+}
+
+struct Material {
+    normal_scale: f32, // 法线贴图xy分量的缩放
+    normal_map: u32,   // 是否使用法线贴图（0表示只使用顶点法线）
+}
+
+@group(0) @binding(0) var<uniform> scene: SceneUniform;
+
+@group(1) @binding(0) var<uniform> material: Material;
+@group(1) @binding(1) var albedo_texture: texture_2d<f32>;
+@group(1) @binding(2) var albedo_sampler: sampler;
+@group(1) @binding(3) var normal_texture: texture_2d<f32>;
+@group(1) @binding(4) var normal_sampler: sampler;
+
+const SHININESS: f32 = 32.0; // Blinn-Phong高光指数
+const SPECULAR: f32 = 0.25;  // 高光强度
+
+struct VertexInput {
+    @location(0) position: vec3f,
+    @location(1) normal: vec3f,
+    @location(2) tangent: vec4f,
+    @location(3) uv: vec2f,
+}
+
+struct VertexOutput {
+    @builtin(position) position: vec4f,
+    @location(0) world_position: vec3f,
+    @location(1) normal: vec3f,
+    @location(2) tangent: vec4f,
+    @location(3) uv: vec2f,
+}
+
+@vertex
+fn vs(in: VertexInput) -> VertexOutput {
+    let world = scene.model * vec4f(in.position, 1.0);
+    var out: VertexOutput;
+    out.position = scene.view_proj * world;
+    out.world_position = world.xyz;
+    out.normal = scene.normal * in.normal;
+    // 切线位于表面内，与位置一样用模型矩阵变换
+    let model3 = mat3x3f(scene.model[0].xyz, scene.model[1].xyz, scene.model[2].xyz);
+    out.tangent = vec4f(model3 * in.tangent.xyz, in.tangent.w);
+    out.uv = in.uv;
+    return out;
+}
+
+@fragment
+fn fs(in: VertexOutput) -> @location(0) vec4f {
+    let albedo = textureSample(albedo_texture, albedo_sampler, in.uv).rgb;
+    let sampled = textureSample(normal_texture, normal_sampler, in.uv).xyz;
+    var n = normalize(in.normal);
+    if material.normal_map != 0u {
+        n = perturb_normal(in.normal, in.tangent, sampled, material.normal_scale);
+    }
+
+    // 点光源的漫反射和Blinn-Phong高光
+    let to_light = scene.light_position - in.world_position;
+    let l = normalize(to_light);
+    let v = normalize(scene.eye - in.world_position);
+    let h = normalize(l + v);
+    let radiance = scene.light_intensity / dot(to_light, to_light);
+    let diffuse = max(dot(n, l), 0.0);
+    let specular = select(0.0, pow(max(dot(n, h), 0.0), SHININESS) * SPECULAR, diffuse > 0.0);
+    let color = albedo * (scene.ambient + diffuse * radiance) + specular * radiance;
+    return vec4f(color, 1.0);
+}
@@ -0,0 +1,15 @@
+// 多个着色器共用的辅助函数，通过 #include "common.wgsl" 引入
+
+const TAU: f32 = 6.2831853;
+
+// 根据色相（弧度）返回彩虹色，三个通道相位相差120度
+fn hue_to_rgb(hue: f32) -> vec3f {
+    return vec3f(sin(hue), sin(hue + TAU / 3.0), sin(hue + TAU * 2.0 / 3.0)) * 0.5 + 0.5;
+}
+
+// 将二维点绕原点旋转angle弧度
+fn rotate2d(p: vec2f, angle: f32) -> vec2f {
+    let c = cos(angle);
+    let s = sin(angle);
+    return vec2f(p.x * c - p.y * s, p.x * s + p.y * c);
+}
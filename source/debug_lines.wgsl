@@ -0,0 +1,30 @@
+// 立即模式调试线：世界空间的线段端点乘以视图投影矩阵，直接输出顶点颜色
+
+struct DebugUniform {
+    view_proj: mat4x4f, // 视图投影矩阵（深度范围0..1）
+}
+
+@group(0) @binding(0) var<uniform> debug: DebugUniform;
+
+struct VertexInput {
+    @location(0) position: vec3f,
+    @location(1) color: vec4f,
+}
+
+struct VertexOutput {
+    @builtin(position) position: vec4f,
+    @location(0) color: vec4f,
+}
+
+@vertex
+fn vs(in: VertexInput) -> VertexOutput {
+    var out: VertexOutput;
+    out.position = debug.view_proj * vec4f(in.position, 1.0);
+    out.color = in.color;
+    return out;
+}
+
+@fragment
+fn fs(in: VertexOutput) -> @location(0) vec4f {
+    return in.color;
+}
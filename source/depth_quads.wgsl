@@ -0,0 +1,29 @@
+// 两个部分重叠的四边形：实例0在近处（z=0.2），实例1在远处（z=0.8）
+// 先绘制近处的四边形，只有深度测试生效时远处的四边形才会被正确遮挡
+
+struct VertexOutput {
+    @builtin(position) position: vec4f,
+    @location(0) color: vec4f,
+}
+
+@vertex
+fn vs(@builtin(vertex_index) vertex_index: u32, @builtin(instance_index) instance_index: u32) -> VertexOutput {
+    // 两个三角形组成一个四边形
+    var corners = array(
+        vec2f(-0.5, -0.5), vec2f(0.5, -0.5), vec2f(0.5, 0.5),
+        vec2f(-0.5, -0.5), vec2f(0.5, 0.5), vec2f(-0.5, 0.5),
+    );
+    var offsets = array(vec2f(-0.2, -0.2), vec2f(0.2, 0.2)); // 两个四边形错开放置
+    var depths = array(0.2, 0.8);                            // 近处 / 远处
+    var colors = array(vec4f(1.0, 0.5, 0.0, 1.0), vec4f(0.0, 0.4, 1.0, 1.0));
+
+    var out: VertexOutput;
+    out.position = vec4f(corners[vertex_index] + offsets[instance_index], depths[instance_index], 1.0);
+    out.color = colors[instance_index];
+    return out;
+}
+
+@fragment
+fn fs(in: VertexOutput) -> @location(0) vec4f {
+    return in.color;
+}
@@ -0,0 +1,41 @@
+// 动态偏移：每个立方体单独绘制，模型矩阵和颜色来自同一个uniform缓冲区中的不同槽位
+// 每次绘制前用set_bind_group的动态偏移选择槽位，着色器中看到的只是一个普通的uniform
+
+struct CameraUniform {
+    view_proj: mat4x4f, // 世界空间到裁剪空间的变换（深度范围0..1）
+}
+
+struct ObjectUniform {
+    model: mat4x4f, // 模型矩阵
+    color: vec4f,   // 线性空间的RGBA颜色
+}
+
+@group(0) @binding(0) var<uniform> camera: CameraUniform;
+@group(1) @binding(0) var<uniform> object: ObjectUniform;
+
+struct VertexInput {
+    @location(0) position: vec3f,
+    @location(1) normal: vec3f,
+    @location(2) uv: vec2f,
+}
+
+struct VertexOutput {
+    @builtin(position) position: vec4f,
+    @location(0) normal: vec3f,
+}
+
+@vertex
+fn vs(in: VertexInput) -> VertexOutput {
+    var out: VertexOutput;
+    out.position = camera.view_proj * object.model * vec4f(in.position, 1.0);
+    // 模型矩阵只有旋转和平移，直接变换法线
+    out.normal = (object.model * vec4f(in.normal, 0.0)).xyz;
+    return out;
+}
+
+@fragment
+fn fs(in: VertexOutput) -> @location(0) vec4f {
+    let light = normalize(vec3f(0.4, 0.8, 0.6));
+    let diffuse = max(dot(normalize(in.normal), light), 0.0);
+    return vec4f(object.color.rgb * (0.2 + 0.8 * diffuse), object.color.a);
+}
@@ -0,0 +1,48 @@
+// 把等距柱状投影（equirectangular）的全景图渲染到立方体贴图的一个面：全屏三角形覆盖整个面，
+// 片元着色器由像素中心算出立方体贴图的方向，转换成经纬度后采样全景图。
+// 方向按像素中心（而不是以90度视野的相机投影顶点）计算，相邻两个面边缘的像素与棱相距都是半个像素，
+// 采样时硬件在面之间过滤，棱上没有重复或错位的一行像素，接缝不可见。
+
+struct FaceUniform {
+    face: u32,      // 面的下标（+X、-X、+Y、-Y、+Z、-Z）
+    lod: f32,       // 采样全景图的mip级别（全景图比立方体贴图精细时避免走样）
+    face_size: f32, // 面的边长（像素）
+}
+
+@group(0) @binding(0) var<uniform> params: FaceUniform;
+@group(0) @binding(1) var equirect: texture_2d<f32>;
+@group(0) @binding(2) var equirect_sampler: sampler;
+
+const PI: f32 = 3.14159265358979;
+
+@vertex
+fn vs(@builtin(vertex_index) index: u32) -> @builtin(position) vec4f {
+    // 覆盖整个面的大三角形
+    let ndc = vec2f(f32(index == 1u) * 4.0 - 1.0, f32(index == 2u) * 4.0 - 1.0);
+    return vec4f(ndc, 0.0, 1.0);
+}
+
+// 立方体贴图（左手坐标系）中第face个面上(s, t)处的方向，s向右、t向下，范围-1..1
+fn face_direction(face: u32, s: f32, t: f32) -> vec3f {
+    switch face {
+        case 0u: { return vec3f(1.0, -t, -s); }
+        case 1u: { return vec3f(-1.0, -t, s); }
+        case 2u: { return vec3f(s, 1.0, t); }
+        case 3u: { return vec3f(s, -1.0, -t); }
+        case 4u: { return vec3f(s, -t, 1.0); }
+        default: { return vec3f(-s, -t, -1.0); }
+    }
+}
+
+@fragment
+fn fs(@builtin(position) position: vec4f) -> @location(0) vec4f {
+    // position.xy是像素中心（x + 0.5, y + 0.5）
+    let st = position.xy / params.face_size * 2.0 - 1.0;
+    // 转回右手系的世界方向（与skybox.wgsl中z取反对应）
+    let direction = normalize(face_direction(params.face, st.x, st.y) * vec3f(1.0, 1.0, -1.0));
+    // 全景图的中心（u = 0.5）位于-Z方向（相机默认的正前方），u向右对应+X，图片上边缘为天顶
+    let u = 0.5 + atan2(direction.x, -direction.z) / (2.0 * PI);
+    let v = 0.5 - asin(clamp(direction.y, -1.0, 1.0)) / PI;
+    // u在背后（±180度）不连续，用固定的mip级别采样，避免导数在那里跳变选到最小的一级
+    return vec4f(textureSampleLevel(equirect, equirect_sampler, vec2f(u, v), params.lod).rgb, 1.0);
+}
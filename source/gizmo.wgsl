@@ -0,0 +1,46 @@
+// 平移手柄：同一个沿+Y的箭头网格绘制3个实例，实例的模型矩阵把箭头旋转到X、Y、Z轴并缩放到固定的屏幕大小
+// 使用简单的方向光着色，让圆柱和圆锥有立体感
+
+struct GizmoUniform {
+    view_proj: mat4x4f, // 视图投影矩阵（深度范围0..1）
+}
+
+@group(0) @binding(0) var<uniform> gizmo: GizmoUniform;
+
+struct VertexInput {
+    @location(0) position: vec3f,
+    @location(1) normal: vec3f,
+    @location(2) uv: vec2f,
+}
+
+struct InstanceInput {
+    @location(5) model_0: vec4f, // 模型矩阵的4列
+    @location(6) model_1: vec4f,
+    @location(7) model_2: vec4f,
+    @location(8) model_3: vec4f,
+    @location(9) color: vec4f,
+}
+
+struct VertexOutput {
+    @builtin(position) position: vec4f,
+    @location(0) normal: vec3f,
+    @location(9) color: vec4f,
+}
+
+@vertex
+fn vs(in: VertexInput, instance: InstanceInput) -> VertexOutput {
+    let model = mat4x4f(instance.model_0, instance.model_1, instance.model_2, instance.model_3);
+    var out: VertexOutput;
+    out.position = gizmo.view_proj * model * vec4f(in.position, 1.0);
+    // 模型矩阵只有旋转和均匀缩放，可以直接变换法线
+    out.normal = (model * vec4f(in.normal, 0.0)).xyz;
+    out.color = instance.color;
+    return out;
+}
+
+@fragment
+fn fs(in: VertexOutput) -> @location(0) vec4f {
+    let light = normalize(vec3f(0.4, 0.8, 0.6));
+    let diffuse = max(dot(normalize(in.normal), light), 0.0);
+    return vec4f(in.color.rgb * (0.5 + 0.5 * diffuse), in.color.a);
+}
@@ -0,0 +1,12 @@
+// 应用每帧维护的全局uniform，通过 #include "globals.wgsl" 引入，固定绑定在第0组第0个绑定
+// 时间随暂停（空格）和慢放/快进（[ ]）一起停止或变速；坐标以像素为单位，原点在左上角（与@builtin(position)一致）
+
+struct Globals {
+    time: f32,          // 场景时间（秒）
+    delta_time: f32,    // 本帧的场景时间增量（秒）
+    resolution: vec2f,  // 表面尺寸（像素）
+    mouse: vec4f,       // xy：左键按住时的光标位置；zw：按下左键的位置，z按住时为正，w只在按下的那一帧为正
+    frame: u32,         // 帧序号
+}
+
+@group(0) @binding(0) var<uniform> globals: Globals;
@@ -0,0 +1,116 @@
+// 旋转展示glTF场景：顶点来自TangentVertex，金属度-粗糙度PBR材质（见pbr.wgsl）在一个方向光和均匀环境光下着色
+// 场景先平移到包围盒中心并缩放到单位球内，再绕Y轴旋转，相机位于+Z方向看向原点，光源与相机相对固定
+
+#include "pbr.wgsl"
+
+struct ViewUniform {
+    center: vec3f, // 场景包围盒中心（世界空间）
+    scale: f32,    // 缩放到单位球内的倍数
+    angle: f32,    // 绕Y轴的旋转角度（弧度）
+    aspect: f32,   // 表面宽高比
+    selected: u32, // 高亮的对象ID（0表示没有）
+}
+
+struct Object {
+    model: mat4x4f,  // 模型空间到世界空间的变换
+    normal: mat3x3f, // 法线矩阵（未归一化的逆转置）
+    id: u32,         // 拾取用的对象ID（从1开始）
+}
+
+@group(0) @binding(0) var<uniform> view: ViewUniform;
+
+@group(1) @binding(0) var<uniform> material: PbrMaterial;
+@group(1) @binding(1) var base_color_texture: texture_2d<f32>;
+@group(1) @binding(2) var base_color_sampler: sampler;
+@group(1) @binding(3) var metallic_roughness_texture: texture_2d<f32>;
+@group(1) @binding(4) var metallic_roughness_sampler: sampler;
+@group(1) @binding(5) var normal_texture: texture_2d<f32>;
+@group(1) @binding(6) var normal_sampler: sampler;
+@group(1) @binding(7) var occlusion_texture: texture_2d<f32>;
+@group(1) @binding(8) var occlusion_sampler: sampler;
+@group(1) @binding(9) var emissive_texture: texture_2d<f32>;
+@group(1) @binding(10) var emissive_sampler: sampler;
+
+@group(2) @binding(0) var<uniform> object: Object;
+
+const CAMERA_DISTANCE: f32 = 3.0; // 相机到原点的距离
+const FOV_Y: f32 = 0.7853982;     // 纵向视野（45度）
+const NEAR: f32 = 0.1;
+const FAR: f32 = 10.0;
+const LIGHT_DIRECTION: vec3f = vec3f(0.4, 0.8, 0.6); // 指向光源的方向（观察空间，未归一化）
+const LIGHT_RADIANCE: vec3f = vec3f(3.0);            // 方向光的颜色 × 强度
+const AMBIENT: vec3f = vec3f(0.25);                  // 均匀环境光
+
+struct VertexInput {
+    @location(0) position: vec3f,
+    @location(1) normal: vec3f,
+    @location(2) tangent: vec4f,
+    @location(3) uv: vec2f,
+}
+
+struct VertexOutput {
+    @builtin(position) position: vec4f,
+    @location(0) view_position: vec3f, // 旋转后的位置（相机位于(0, 0, CAMERA_DISTANCE)）
+    @location(1) normal: vec3f,
+    @location(2) tangent: vec4f, // xyz未归一化，全为0表示没有切线
+    @location(3) uv: vec2f,
+}
+
+// 绕Y轴旋转
+fn rotate_y(p: vec3f, angle: f32) -> vec3f {
+    let c = cos(angle);
+    let s = sin(angle);
+    return vec3f(c * p.x + s * p.z, p.y, -s * p.x + c * p.z);
+}
+
+@vertex
+fn vs(in: VertexInput) -> VertexOutput {
+    let world_position = (object.model * vec4f(in.position, 1.0)).xyz;
+    let world = rotate_y((world_position - view.center) * view.scale, view.angle);
+    // 观察空间：相机在(0, 0, CAMERA_DISTANCE)，看向-Z
+    let z = world.z - CAMERA_DISTANCE;
+    // 透视投影，深度映射到0..1（wgpu的裁剪空间）
+    let f = 1.0 / tan(FOV_Y / 2.0);
+    var out: VertexOutput;
+    out.position = vec4f(
+        world.x * f / view.aspect,
+        world.y * f,
+        z * FAR / (NEAR - FAR) + NEAR * FAR / (NEAR - FAR),
+        -z,
+    );
+    out.view_position = world;
+    out.normal = rotate_y(object.normal * in.normal, view.angle);
+    let model3 = mat3x3f(object.model[0].xyz, object.model[1].xyz, object.model[2].xyz);
+    out.tangent = vec4f(rotate_y(model3 * in.tangent.xyz, view.angle), in.tangent.w);
+    out.uv = in.uv;
+    return out;
+}
+
+@fragment
+fn fs(in: VertexOutput) -> @location(0) vec4f {
+    let surface = pbr_surface(
+        material,
+        textureSample(base_color_texture, base_color_sampler, in.uv),
+        textureSample(metallic_roughness_texture, metallic_roughness_sampler, in.uv),
+        textureSample(occlusion_texture, occlusion_sampler, in.uv).r,
+        textureSample(emissive_texture, emissive_sampler, in.uv).rgb,
+        in.normal,
+        in.tangent,
+        textureSample(normal_texture, normal_sampler, in.uv).xyz,
+    );
+
+    let v = normalize(vec3f(0.0, 0.0, CAMERA_DISTANCE) - in.view_position);
+    let l = normalize(LIGHT_DIRECTION);
+    var color = pbr_direct(surface, v, l, LIGHT_RADIANCE) + pbr_ambient(surface, v, AMBIENT)
+        + surface.emissive;
+    if view.selected != 0u && object.id == view.selected {
+        color = mix(color, vec3f(1.0, 0.6, 0.1), 0.5); // 选中的对象叠加橙色
+    }
+    return vec4f(color, 1.0);
+}
+
+// 拾取：输出对象ID（深度测试保证最前面的对象胜出）
+@fragment
+fn fs_id() -> @location(0) u32 {
+    return object.id;
+}
@@ -0,0 +1,92 @@
+// 无限参考网格：全屏三角形覆盖整个屏幕，片元着色器用逆视图投影矩阵把像素还原成射线，
+// 与水平面y = height求交后在世界空间中按坐标计算网格线。输出交点的深度，被场景几何体正确遮挡。
+// 线宽按屏幕空间导数（fwidth）抗锯齿，格子小于像素时淡出细线以减轻摩尔纹，远处按距离淡出。
+
+struct GridUniform {
+    view_proj: mat4x4f,     // 视图投影矩阵（深度范围0..1）
+    inv_view_proj: mat4x4f, // 逆视图投影矩阵
+    height: f32,            // 网格所在平面的y坐标
+    spacing: f32,           // 细线间距（世界单位）
+    major_every: f32,       // 每隔多少条细线一条粗线
+    fade_distance: f32,     // 网格完全淡出的距离
+}
+
+@group(0) @binding(0) var<uniform> grid: GridUniform;
+
+const MINOR_COLOR: vec4f = vec4f(0.35, 0.35, 0.35, 0.5);
+const MAJOR_COLOR: vec4f = vec4f(0.5, 0.5, 0.5, 0.8);
+const X_AXIS_COLOR: vec4f = vec4f(0.9, 0.2, 0.2, 1.0); // X轴（z = 0）
+const Z_AXIS_COLOR: vec4f = vec4f(0.2, 0.4, 0.9, 1.0); // Z轴（x = 0）
+
+struct VertexOutput {
+    @builtin(position) position: vec4f,
+    @location(0) ndc: vec2f, // 归一化设备坐标
+}
+
+struct FragmentOutput {
+    @location(0) color: vec4f,
+    @builtin(frag_depth) depth: f32,
+}
+
+@vertex
+fn vs(@builtin(vertex_index) index: u32) -> VertexOutput {
+    // 覆盖整个屏幕的大三角形
+    let ndc = vec2f(f32(index == 1u) * 4.0 - 1.0, f32(index == 2u) * 4.0 - 1.0);
+    var out: VertexOutput;
+    out.position = vec4f(ndc, 0.0, 1.0);
+    out.ndc = ndc;
+    return out;
+}
+
+// 把归一化设备坐标还原到世界空间
+fn unproject(ndc: vec2f, depth: f32) -> vec3f {
+    let world = grid.inv_view_proj * vec4f(ndc, depth, 1.0);
+    return world.xyz / world.w;
+}
+
+// 到最近网格线的距离（以像素为单位），coord为以线间距为单位的坐标
+fn line_distance(coord: vec2f) -> vec2f {
+    return abs(fract(coord - 0.5) - 0.5) / fwidth(coord);
+}
+
+@fragment
+fn fs(in: VertexOutput) -> FragmentOutput {
+    // 像素对应的射线（近平面到远平面）与网格平面求交
+    let near = unproject(in.ndc, 0.0);
+    let far = unproject(in.ndc, 1.0);
+    let t = (grid.height - near.y) / (far.y - near.y);
+    let position = near + t * (far - near);
+
+    // 细线：格子比一个像素还小时淡出，避免摩尔纹
+    let minor_coord = position.xz / grid.spacing;
+    let minor_width = max(fwidth(minor_coord).x, fwidth(minor_coord).y);
+    let minor_line = line_distance(minor_coord);
+    let minor = (1.0 - min(min(minor_line.x, minor_line.y), 1.0)) * (1.0 - smoothstep(0.3, 0.6, minor_width));
+    let major_line = line_distance(minor_coord / grid.major_every);
+    let major = 1.0 - min(min(major_line.x, major_line.y), 1.0);
+
+    var color = mix(MINOR_COLOR * minor, MAJOR_COLOR, major);
+    // 坐标轴：线宽与粗线相同
+    let axis = abs(position.xz) / fwidth(position.xz);
+    if axis.y < 1.0 {
+        color = mix(color, X_AXIS_COLOR, 1.0 - axis.y);
+    }
+    if axis.x < 1.0 {
+        color = mix(color, Z_AXIS_COLOR, 1.0 - axis.x);
+    }
+
+    // 远处淡出
+    let fade = 1.0 - smoothstep(grid.fade_distance * 0.5, grid.fade_distance, distance(position, near));
+    color.a *= fade;
+
+    // 射线与平面平行（t为NaN）或交点在相机后方、远平面之外时没有网格
+    if !(t > 0.0 && t <= 1.0) || color.a <= 0.001 {
+        discard;
+    }
+
+    let clip = grid.view_proj * vec4f(position, 1.0);
+    var out: FragmentOutput;
+    out.color = color;
+    out.depth = clip.z / clip.w;
+    return out;
+}
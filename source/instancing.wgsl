@@ -0,0 +1,47 @@
+// 实例化绘制：所有立方体共用一个网格，模型矩阵和颜色来自按实例步进的顶点缓冲区
+// 网格在XZ平面上铺开，相机从斜上方看向原点（视图投影矩阵由程序中的Camera计算）
+
+struct CameraUniform {
+    view_proj: mat4x4f, // 世界空间到裁剪空间的变换（深度范围0..1）
+}
+
+@group(0) @binding(0) var<uniform> camera: CameraUniform;
+
+struct VertexInput {
+    @location(0) position: vec3f,
+    @location(1) normal: vec3f,
+    @location(2) uv: vec2f,
+}
+
+// 模型矩阵按列拆成4个属性（顶点属性最多是vec4）
+struct InstanceInput {
+    @location(5) model_0: vec4f,
+    @location(6) model_1: vec4f,
+    @location(7) model_2: vec4f,
+    @location(8) model_3: vec4f,
+    @location(9) color: vec4f,
+}
+
+struct VertexOutput {
+    @builtin(position) position: vec4f,
+    @location(0) normal: vec3f,
+    @location(1) color: vec4f,
+}
+
+@vertex
+fn vs(in: VertexInput, instance: InstanceInput) -> VertexOutput {
+    let model = mat4x4f(instance.model_0, instance.model_1, instance.model_2, instance.model_3);
+    var out: VertexOutput;
+    out.position = camera.view_proj * model * vec4f(in.position, 1.0);
+    // 模型矩阵只有旋转、平移和均匀缩放，可以直接变换法线（片元着色器中归一化）
+    out.normal = (model * vec4f(in.normal, 0.0)).xyz;
+    out.color = instance.color;
+    return out;
+}
+
+@fragment
+fn fs(in: VertexOutput) -> @location(0) vec4f {
+    let light = normalize(vec3f(0.4, 0.8, 0.6));
+    let diffuse = max(dot(normalize(in.normal), light), 0.0);
+    return vec4f(in.color.rgb * (0.2 + 0.8 * diffuse), in.color.a);
+}
@@ -0,0 +1,48 @@
+// 生成mipmap：全屏三角形覆盖下一级，每个像素按精确的权重读取上一级对应的像素并求平均
+//
+// 上一级在某个轴上为偶数2n时，下一级的第i个像素恰好覆盖第2i、2i + 1两个像素，各占1/2；
+// 为奇数2n + 1时，每个像素覆盖(2n + 1) / n个像素，跨过第2i、2i + 1、2i + 2三个像素，
+// 权重依次为(n - i)、n、(i + 1)除以2n + 1。只用线性过滤采样在奇数尺寸时会漏掉一部分像素，
+// 画面逐级向左上方偏移。
+
+@group(0) @binding(0) var source: texture_2d<f32>;
+
+@vertex
+fn vs(@builtin(vertex_index) index: u32) -> @builtin(position) vec4f {
+    // 覆盖整个目标的大三角形
+    let uv = vec2f(f32(index == 1u) * 2.0, f32(index == 2u) * 2.0);
+    return vec4f(uv.x * 2.0 - 1.0, 1.0 - uv.y * 2.0, 0.0, 1.0);
+}
+
+// 上一级尺寸为size时，下一级第i个像素在该轴上读取的三个像素（从2i开始）的权重
+fn axis_weights(size: u32, i: u32) -> vec3f {
+    if size == 1u {
+        return vec3f(1.0, 0.0, 0.0); // 已经是1，下一级仍为1
+    }
+    if size % 2u == 0u {
+        return vec3f(0.5, 0.5, 0.0);
+    }
+    let n = f32(size / 2u);
+    let x = f32(i);
+    return vec3f(n - x, n, x + 1.0) / (2.0 * n + 1.0);
+}
+
+@fragment
+fn fs(@builtin(position) position: vec4f) -> @location(0) vec4f {
+    // sRGB纹理读取时转换为线性颜色、写入时再转换回去，平均在线性空间中进行
+    let size = textureDimensions(source);
+    let texel = vec2u(position.xy);
+    let wx = axis_weights(size.x, texel.x);
+    let wy = axis_weights(size.y, texel.y);
+    var color = vec4f(0.0);
+    for (var y = 0u; y < 3u; y++) {
+        for (var x = 0u; x < 3u; x++) {
+            let weight = wx[x] * wy[y];
+            if weight > 0.0 {
+                let coord = min(texel * 2u + vec2u(x, y), size - 1u);
+                color += textureLoad(source, coord, 0) * weight;
+            }
+        }
+    }
+    return color;
+}
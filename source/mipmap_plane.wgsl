@@ -0,0 +1,36 @@
+// 伸向地平线的贴图平面：远处的纹理被大幅缩小，没有mipmap时会闪烁并出现摩尔纹
+
+struct CameraUniform {
+    view_proj: mat4x4f, // 世界空间到裁剪空间的变换（深度范围0..1）
+}
+
+@group(0) @binding(0) var<uniform> camera: CameraUniform;
+@group(1) @binding(0) var image: texture_2d<f32>;
+@group(1) @binding(1) var image_sampler: sampler;
+
+// 纹理在平面上每个方向重复的次数（与MipmapPlane中的REPEAT一致）
+const REPEAT: f32 = 100.0;
+
+struct VertexInput {
+    @location(0) position: vec3f,
+    @location(2) uv: vec2f, // 覆盖整个平面一次
+}
+
+struct VertexOutput {
+    @builtin(position) position: vec4f,
+    @location(0) uv: vec2f,
+}
+
+@vertex
+fn vs(in: VertexInput) -> VertexOutput {
+    var out: VertexOutput;
+    out.position = camera.view_proj * vec4f(in.position, 1.0);
+    out.uv = in.uv * REPEAT;
+    return out;
+}
+
+@fragment
+fn fs(in: VertexOutput) -> @location(0) vec4f {
+    // 采样器按uv的屏幕空间导数选择mip级别
+    return textureSample(image, image_sampler, in.uv);
+}
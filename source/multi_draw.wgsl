@@ -0,0 +1,61 @@
+// 多重间接绘制：几千个形状的顶点已经烘焙到世界空间，共用一个顶点缓冲区和索引缓冲区，
+// 每个形状是间接缓冲区中的一条绘制参数（first_index和base_vertex选择自己的那一段）
+
+#include "common.wgsl"
+
+struct ViewUniform {
+    aspect: f32,          // 表面宽高比
+    camera_distance: f32, // 相机到原点的距离
+    angle: f32,           // 整个场景绕Y轴旋转的角度（弧度）
+}
+
+@group(0) @binding(0) var<uniform> view: ViewUniform;
+
+const CAMERA_PITCH: f32 = 0.7; // 相机向下俯视的角度（弧度）
+const FOV_Y: f32 = 0.7853982;  // 纵向视野（45度）
+const NEAR: f32 = 1.0;
+const FAR: f32 = 300.0;
+
+struct VertexInput {
+    @location(0) position: vec3f,
+    @location(1) normal: vec3f,
+    @location(2) uv: vec2f,
+}
+
+struct VertexOutput {
+    @builtin(position) position: vec4f,
+    @location(0) normal: vec3f,
+    @location(1) color: vec3f,
+}
+
+// 绕Y轴旋转后再绕X轴转向相机
+fn to_view(p: vec3f) -> vec3f {
+    let xz = rotate2d(p.xz, view.angle);
+    let yz = rotate2d(vec2f(p.y, xz.y), CAMERA_PITCH);
+    return vec3f(xz.x, yz.x, yz.y);
+}
+
+@vertex
+fn vs(in: VertexInput) -> VertexOutput {
+    let p = to_view(in.position);
+    let z = p.z - view.camera_distance;
+    let f = 1.0 / tan(FOV_Y / 2.0);
+    var out: VertexOutput;
+    out.position = vec4f(
+        p.x * f / view.aspect,
+        p.y * f,
+        z * FAR / (NEAR - FAR) + NEAR * FAR / (NEAR - FAR),
+        -z,
+    );
+    out.normal = to_view(in.normal);
+    // 颜色按形状所在的方位角渐变
+    out.color = hue_to_rgb(atan2(in.position.z, in.position.x));
+    return out;
+}
+
+@fragment
+fn fs(in: VertexOutput) -> @location(0) vec4f {
+    let light = normalize(vec3f(0.4, 0.8, 0.6));
+    let diffuse = max(dot(normalize(in.normal), light), 0.0);
+    return vec4f(in.color * (0.2 + 0.8 * diffuse), 1.0);
+}
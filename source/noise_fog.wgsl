@@ -0,0 +1,88 @@
+// 三维纹理：沿视线在单位立方体内步进，采样可平铺的噪声体积得到随时间飘动的雾
+// 绘制立方体的背面，相机进入立方体后也能看到雾；透射率足够低时提前结束步进
+
+struct FogUniform {
+    view_proj: mat4x4f, // 世界空间到裁剪空间的变换
+    eye: vec3f,         // 相机位置（世界空间，立方体以原点为中心、边长为1）
+    time: f32,          // 场景时间（秒）
+    wind: vec3f,        // 噪声的滚动速度（纹理坐标/秒），体积可平铺所以可以一直滚动
+    density: f32,       // 消光系数（每单位长度）
+}
+
+@group(0) @binding(0) var<uniform> fog: FogUniform;
+@group(1) @binding(0) var t_volume: texture_3d<f32>;
+@group(1) @binding(1) var s_volume: sampler;
+
+const STEPS: i32 = 96;                            // 沿立方体对角线的步数
+const COVERAGE: f32 = 0.45;                       // 噪声低于这个值处没有雾
+const EDGE_FADE: f32 = 0.1;                       // 靠近立方体表面时雾逐渐变淡的距离
+const SHADOW_DISTANCE: f32 = 0.08;                // 朝光源方向估计自阴影的距离
+const MIN_TRANSMITTANCE: f32 = 0.01;              // 透射率低于它时后面的雾已看不见
+const LIGHT_COLOR = vec3f(1.0, 0.95, 0.85);       // 受光一侧的颜色
+const SHADOW_COLOR = vec3f(0.25, 0.3, 0.4);       // 背光一侧的颜色
+
+struct VertexInput {
+    @location(0) position: vec3f,
+    @location(1) normal: vec3f,
+    @location(2) uv: vec2f,
+}
+
+struct VertexOutput {
+    @builtin(position) position: vec4f,
+    @location(0) world: vec3f,
+}
+
+@vertex
+fn vs(in: VertexInput) -> VertexOutput {
+    var out: VertexOutput;
+    out.position = fog.view_proj * vec4f(in.position, 1.0);
+    out.world = in.position;
+    return out;
+}
+
+// p处雾的浓度（0..1）：滚动的噪声减去覆盖阈值，在立方体表面附近淡出
+fn density_at(p: vec3f) -> f32 {
+    let uvw = p + 0.5 + fog.wind * fog.time;
+    // 循环中的采样不在统一控制流中，不能用textureSample（需要导数），只能显式指定mip级
+    let noise = textureSampleLevel(t_volume, s_volume, uvw, 0.0).r;
+    let edge = 0.5 - max(max(abs(p.x), abs(p.y)), abs(p.z));
+    return smoothstep(COVERAGE, 1.0, noise) * smoothstep(0.0, EDGE_FADE, edge);
+}
+
+@fragment
+fn fs(in: VertexOutput) -> @location(0) vec4f {
+    // 视线与立方体[-0.5, 0.5]³的交点（slab法），相机在立方体内时从相机处开始
+    let dir = normalize(in.world - fog.eye);
+    let inv = 1.0 / dir;
+    let t0 = (vec3f(-0.5) - fog.eye) * inv;
+    let t1 = (vec3f(0.5) - fog.eye) * inv;
+    let t_min = min(t0, t1);
+    let t_max = max(t0, t1);
+    let near = max(max(max(t_min.x, t_min.y), t_min.z), 0.0);
+    let far = min(min(t_max.x, t_max.y), t_max.z);
+
+    // 按像素抖动起点（交错梯度噪声），把步进产生的条带变成细小的噪点
+    let step = sqrt(3.0) / f32(STEPS);
+    let jitter = fract(52.9829189 * fract(dot(in.position.xy, vec2f(0.06711056, 0.00583715))));
+    let light = normalize(vec3f(0.4, 0.8, 0.3));
+    var t = near + step * jitter;
+    var transmittance = 1.0;
+    var color = vec3f(0.0);
+    for (var i = 0; i < STEPS && t < far; i++) {
+        let p = fog.eye + dir * t;
+        let density = density_at(p);
+        if density > 0.0 {
+            // 朝光源方向再采样一次，估计这一点被前方的雾遮挡了多少光
+            let shadow = exp(-density_at(p + light * SHADOW_DISTANCE) * fog.density * SHADOW_DISTANCE);
+            let absorbed = 1.0 - exp(-density * fog.density * step);
+            color += transmittance * absorbed * mix(SHADOW_COLOR, LIGHT_COLOR, shadow);
+            transmittance *= 1.0 - absorbed;
+            if transmittance < MIN_TRANSMITTANCE {
+                break;
+            }
+        }
+        t += step;
+    }
+    // 预乘alpha，与背景按1 - alpha混合
+    return vec4f(color, 1.0 - transmittance);
+}
@@ -0,0 +1,70 @@
+// 可平铺的三维噪声：每个线程计算一个纹素的分形Perlin噪声，写入R16Float的三维存储纹理
+// 晶格坐标按周期回绕，体积三个方向都可以无缝平铺；CPU回退（noise.rs）使用相同的哈希和梯度
+
+struct NoiseParams {
+    size: u32,    // 体积的边长（纹素）
+    seed: u32,    // 随机种子
+    period: u32,  // 第一个八度每个方向的晶格数（每个八度翻倍）
+    octaves: u32, // 叠加的八度数
+}
+
+@group(0) @binding(0) var<uniform> params: NoiseParams;
+@group(0) @binding(1) var volume: texture_storage_3d<r16float, write>;
+
+// 晶格点上的12个梯度方向（立方体的棱的中点）
+const GRADIENTS = array<vec3f, 12>(
+    vec3f(1.0, 1.0, 0.0), vec3f(-1.0, 1.0, 0.0), vec3f(1.0, -1.0, 0.0), vec3f(-1.0, -1.0, 0.0),
+    vec3f(1.0, 0.0, 1.0), vec3f(-1.0, 0.0, 1.0), vec3f(1.0, 0.0, -1.0), vec3f(-1.0, 0.0, -1.0),
+    vec3f(0.0, 1.0, 1.0), vec3f(0.0, -1.0, 1.0), vec3f(0.0, 1.0, -1.0), vec3f(0.0, -1.0, -1.0),
+);
+
+// 晶格点的整数哈希（u32乘法自动回绕）
+fn hash(c: vec3u, seed: u32) -> u32 {
+    var h = (c.x * 0x8da6b343u) ^ (c.y * 0xd8163841u) ^ (c.z * 0xcb1ab31fu) ^ (seed * 0x165667b1u);
+    h ^= h >> 16u;
+    h *= 0x7feb352du;
+    h ^= h >> 15u;
+    h *= 0x846ca68bu;
+    return h ^ (h >> 16u);
+}
+
+// 晶格角cell + offset处的梯度与相对位置f - offset的点积，角坐标按period回绕
+fn corner(cell: vec3u, offset: vec3u, f: vec3f, period: u32, seed: u32) -> f32 {
+    let g = GRADIENTS[hash((cell + offset) % period, seed) % 12u];
+    return dot(g, f - vec3f(offset));
+}
+
+// 周期为period个晶格的三维Perlin噪声（约-1..1）
+fn perlin(p: vec3f, period: u32, seed: u32) -> f32 {
+    let cell = vec3u(floor(p));
+    let f = fract(p);
+    let u = f * f * f * (f * (f * 6.0 - 15.0) + 10.0);
+    let x00 = mix(corner(cell, vec3u(0u, 0u, 0u), f, period, seed), corner(cell, vec3u(1u, 0u, 0u), f, period, seed), u.x);
+    let x10 = mix(corner(cell, vec3u(0u, 1u, 0u), f, period, seed), corner(cell, vec3u(1u, 1u, 0u), f, period, seed), u.x);
+    let x01 = mix(corner(cell, vec3u(0u, 0u, 1u), f, period, seed), corner(cell, vec3u(1u, 0u, 1u), f, period, seed), u.x);
+    let x11 = mix(corner(cell, vec3u(0u, 1u, 1u), f, period, seed), corner(cell, vec3u(1u, 1u, 1u), f, period, seed), u.x);
+    return mix(mix(x00, x10, u.y), mix(x01, x11, u.y), u.z);
+}
+
+@compute @workgroup_size(4, 4, 4)
+fn cs(@builtin(global_invocation_id) id: vec3u) {
+    // 边长不是4的倍数时最后一组工作组会超出体积
+    if any(id >= vec3u(params.size)) {
+        return;
+    }
+    let uvw = (vec3f(id) + 0.5) / f32(params.size);
+    var sum = 0.0;
+    var total = 0.0;
+    var amplitude = 1.0;
+    for (var octave = 0u; octave < params.octaves; octave++) {
+        let period = params.period << octave;
+        sum += amplitude * perlin(uvw * f32(period), period, params.seed + octave);
+        total += amplitude;
+        amplitude *= 0.5;
+    }
+    var value = 0.5;
+    if total > 0.0 {
+        value = clamp(0.5 + 0.5 * sum / total, 0.0, 1.0);
+    }
+    textureStore(volume, id, vec4f(value, 0.0, 0.0, 1.0));
+}
@@ -0,0 +1,17 @@
+// 切线空间法线贴图的辅助函数，通过 #include "normal_map.wgsl" 引入
+// 切线由TangentVertex提供（见generate_tangents），法线贴图按线性数据（Rgba8Unorm）采样
+
+// 用法线贴图的采样值sampled（0..1）扰动插值后的法线，normal和tangent.xyz须在同一空间（如世界空间）
+// 切线先对法线做Gram-Schmidt正交化，副切线 = cross(法线, 切线) * w（MikkTSpace约定，w < 0表示纹理坐标镜像），
+// scale缩放切线空间法线的xy分量。切线为0（网格没有切线）时返回归一化的原法线
+fn perturb_normal(normal: vec3f, tangent: vec4f, sampled: vec3f, scale: f32) -> vec3f {
+    let n = normalize(normal);
+    if dot(tangent.xyz, tangent.xyz) < 1e-8 {
+        return n;
+    }
+    let t = normalize(tangent.xyz - n * dot(n, tangent.xyz));
+    let b = cross(n, t) * select(1.0, -1.0, tangent.w < 0.0);
+    let mapped = sampled * 2.0 - 1.0;
+    let local = normalize(vec3f(mapped.xy * scale, mapped.z));
+    return normalize(mat3x3f(t, b, n) * local);
+}
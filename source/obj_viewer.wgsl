@@ -0,0 +1,78 @@
+// 旋转展示模型：顶点来自MeshVertex（位置、法线、纹理坐标），使用方向光的漫反射着色
+// 模型矩阵（由CPU上的Transform生成）把模型移到包围盒中心、缩放到单位球内并放到世界空间，再用相机的视图投影矩阵变换到裁剪空间
+// 不支持线模式时，线框由vs_wireframe和fs_wireframe根据重心坐标绘制（网格需展开索引、不带索引绘制）
+
+struct ViewUniform {
+    view_proj: mat4x4f, // 相机的视图投影矩阵（深度范围0..1）
+    model: mat4x4f,     // 模型空间到世界空间的变换
+    normal: mat3x3f,    // 法线矩阵（未归一化的逆转置）
+}
+
+@group(0) @binding(0) var<uniform> view: ViewUniform;
+
+const WIRE_WIDTH: f32 = 1.0; // 重心坐标线框的线宽（像素）
+
+struct VertexInput {
+    @location(0) position: vec3f,
+    @location(1) normal: vec3f,
+    @location(2) uv: vec2f,
+}
+
+struct VertexOutput {
+    @builtin(position) position: vec4f,
+    @location(0) normal: vec3f,
+}
+
+// 重心坐标线框的顶点输出
+struct WireframeOutput {
+    @builtin(position) position: vec4f,
+    @location(0) normal: vec3f,
+    @location(1) barycentric: vec3f, // 三角形内的重心坐标（顶点处为单位向量）
+}
+
+// 变换到裁剪空间，法线用法线矩阵变换到世界空间
+fn transform(in: VertexInput) -> VertexOutput {
+    var out: VertexOutput;
+    out.position = view.view_proj * view.model * vec4f(in.position, 1.0);
+    out.normal = view.normal * in.normal;
+    return out;
+}
+
+// 方向光的漫反射着色
+fn shade(normal: vec3f) -> vec4f {
+    let light = normalize(vec3f(0.4, 0.8, 0.6));
+    let diffuse = max(dot(normalize(normal), light), 0.0);
+    let base = vec3f(0.8, 0.8, 0.8);
+    return vec4f(base * (0.15 + 0.85 * diffuse), 1.0);
+}
+
+@vertex
+fn vs(in: VertexInput) -> VertexOutput {
+    return transform(in);
+}
+
+@fragment
+fn fs(in: VertexOutput) -> @location(0) vec4f {
+    return shade(in.normal);
+}
+
+@vertex
+fn vs_wireframe(in: VertexInput, @builtin(vertex_index) index: u32) -> WireframeOutput {
+    let transformed = transform(in);
+    var out: WireframeOutput;
+    out.position = transformed.position;
+    out.normal = transformed.normal;
+    // 不带索引绘制时每三个连续的顶点组成一个三角形
+    out.barycentric = vec3f(f32(index % 3u == 0u), f32(index % 3u == 1u), f32(index % 3u == 2u));
+    return out;
+}
+
+@fragment
+fn fs_wireframe(in: WireframeOutput) -> @location(0) vec4f {
+    // 到最近一条边的距离（像素）：重心坐标除以它在屏幕上的变化率
+    let distance = in.barycentric / fwidth(in.barycentric);
+    if min(min(distance.x, distance.y), distance.z) > WIRE_WIDTH {
+        discard;
+    }
+    return shade(in.normal);
+}
@@ -0,0 +1,114 @@
+// 金属度-粗糙度PBR着色（与glTF 2.0一致），通过 #include "pbr.wgsl" 引入（已包含normal_map.wgsl）
+// 直接光照使用Cook-Torrance BRDF：GGX法线分布、高度相关的Smith可见性项和Schlick菲涅尔，漫反射为Lambert，
+// 各项与glTF 2.0规范附录B（Khronos的参考查看器）的公式相同；
+// 环境光是均匀的常量颜色（还没有基于图像的光照），镜面部分用解析近似代替预积分的BRDF查找表。
+// 贴图的绑定由包含它的着色器声明（见PbrMaterial::create_layout），采样后交给pbr_surface
+
+#include "normal_map.wgsl"
+
+const PI: f32 = 3.14159265;
+const MIN_ROUGHNESS: f32 = 0.045; // 粗糙度的下限，避免完全光滑时高光退化成无穷小的亮点
+const DIELECTRIC_F0: vec3f = vec3f(0.04); // 非金属垂直入射时的反射率
+
+struct PbrMaterial {
+    base_color: vec4f,       // 线性空间的基础色因子
+    emissive: vec3f,         // 线性空间的自发光因子
+    metallic: f32,           // 金属度因子
+    roughness: f32,          // 感知粗糙度因子
+    normal_scale: f32,       // 法线贴图xy分量的缩放
+    occlusion_strength: f32, // 遮蔽强度
+    normal_map: u32,         // 是否有法线贴图（0表示只使用顶点法线）
+}
+
+// 着色点的表面参数：材质因子与贴图采样值相乘后的结果
+struct PbrSurface {
+    albedo: vec3f,   // 基础色（线性）
+    metallic: f32,   // 金属度
+    emissive: vec3f, // 自发光（线性）
+    roughness: f32,  // 感知粗糙度（已限制下限）
+    normal: vec3f,   // 单位法线（与光照方向在同一空间）
+    occlusion: f32,  // 环境光遮蔽（1表示不遮蔽）
+}
+
+// 合成表面参数，贴图采样值均为0..1：base_color来自sRGB贴图（已转为线性），
+// metallic_roughness的G为粗糙度、B为金属度，occlusion为遮蔽贴图的R通道，
+// normal和tangent为插值后的顶点数据，sampled_normal为法线贴图的采样值
+fn pbr_surface(
+    material: PbrMaterial,
+    base_color: vec4f,
+    metallic_roughness: vec4f,
+    occlusion: f32,
+    emissive: vec3f,
+    normal: vec3f,
+    tangent: vec4f,
+    sampled_normal: vec3f,
+) -> PbrSurface {
+    var surface: PbrSurface;
+    surface.albedo = (base_color * material.base_color).rgb;
+    surface.metallic = saturate(metallic_roughness.b * material.metallic);
+    surface.roughness = clamp(metallic_roughness.g * material.roughness, MIN_ROUGHNESS, 1.0);
+    surface.occlusion = 1.0 + material.occlusion_strength * (occlusion - 1.0);
+    surface.emissive = emissive * material.emissive;
+    surface.normal = normalize(normal);
+    if material.normal_map != 0u {
+        surface.normal = perturb_normal(normal, tangent, sampled_normal, material.normal_scale);
+    }
+    return surface;
+}
+
+// GGX（Trowbridge-Reitz）法线分布函数，alpha为粗糙度的平方
+fn distribution_ggx(n_dot_h: f32, alpha: f32) -> f32 {
+    let a2 = alpha * alpha;
+    let d = n_dot_h * n_dot_h * (a2 - 1.0) + 1.0;
+    return a2 / (PI * d * d);
+}
+
+// 高度相关的Smith几何遮蔽除以4(n·l)(n·v)得到的可见性项，alpha为粗糙度的平方
+// （Schlick-GGX取k = (粗糙度 + 1)² / 8的近似在掠射角时明显比参考实现暗）
+fn visibility_smith_ggx(n_dot_v: f32, n_dot_l: f32, alpha: f32) -> f32 {
+    let a2 = alpha * alpha;
+    let ggx_v = n_dot_l * sqrt(n_dot_v * n_dot_v * (1.0 - a2) + a2);
+    let ggx_l = n_dot_v * sqrt(n_dot_l * n_dot_l * (1.0 - a2) + a2);
+    return 0.5 / (ggx_v + ggx_l);
+}
+
+// Schlick菲涅尔近似
+fn fresnel_schlick(cos_theta: f32, f0: vec3f) -> vec3f {
+    return f0 + (1.0 - f0) * pow(1.0 - saturate(cos_theta), 5.0);
+}
+
+// 垂直入射时的反射率：非金属为4%，金属为基础色
+fn pbr_f0(surface: PbrSurface) -> vec3f {
+    return mix(DIELECTRIC_F0, surface.albedo, surface.metallic);
+}
+
+// 一个直接光源的贡献：v和l为指向相机和光源的单位向量，radiance为到达表面的辐射度（颜色 × 强度）
+fn pbr_direct(surface: PbrSurface, v: vec3f, l: vec3f, radiance: vec3f) -> vec3f {
+    let n = surface.normal;
+    let n_dot_l = dot(n, l);
+    if n_dot_l <= 0.0 {
+        return vec3f(0.0);
+    }
+    let h = normalize(v + l);
+    let n_dot_v = max(dot(n, v), 1e-4);
+    let alpha = surface.roughness * surface.roughness;
+    let f = fresnel_schlick(dot(h, v), pbr_f0(surface));
+    let d = distribution_ggx(max(dot(n, h), 0.0), alpha);
+    let specular = d * visibility_smith_ggx(n_dot_v, n_dot_l, alpha) * f;
+    // 被镜面反射的能量不再参与漫反射，金属没有漫反射
+    let diffuse = (1.0 - f) * (1.0 - surface.metallic) * surface.albedo / PI;
+    return (diffuse + specular) * radiance * n_dot_l;
+}
+
+// 均匀环境光的贡献（乘以遮蔽）：漫反射为albedo × ambient，镜面部分用Karis的解析近似估计预积分的BRDF
+fn pbr_ambient(surface: PbrSurface, v: vec3f, ambient: vec3f) -> vec3f {
+    let n_dot_v = max(dot(surface.normal, v), 1e-4);
+    let c0 = vec4f(-1.0, -0.0275, -0.572, 0.022);
+    let c1 = vec4f(1.0, 0.0425, 1.04, -0.04);
+    let r = surface.roughness * c0 + c1;
+    let a004 = min(r.x * r.x, exp2(-9.28 * n_dot_v)) * r.x + r.y;
+    let ab = vec2f(-1.04, 1.04) * a004 + r.zw;
+    let specular = pbr_f0(surface) * ab.x + ab.y;
+    let diffuse = (1.0 - specular) * (1.0 - surface.metallic) * surface.albedo;
+    return (diffuse + specular) * ambient * surface.occlusion;
+}
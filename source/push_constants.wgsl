@@ -0,0 +1,74 @@
+// 推送常量：同一个立方体网格绘制多次，每次绘制的位置和颜色通过推送常量传入；
+// 设备不支持推送常量时，由程序把下面的声明替换为带动态偏移的uniform绑定
+
+struct ViewUniform {
+    aspect: f32, // 表面宽高比
+    angle: f32,  // 立方体绕自身Y轴旋转的角度（弧度）
+}
+
+@group(0) @binding(0) var<uniform> view: ViewUniform;
+
+// 逐绘制数据
+struct DrawData {
+    offset: vec4f, // xyz为立方体中心，w为缩放
+    color: vec4f,  // 线性空间的颜色
+}
+
+//#define PUSH_CONSTANT_BINDING
+//#define PUSH_CONSTANT_SPACE push_constant
+PUSH_CONSTANT_BINDING var<PUSH_CONSTANT_SPACE> draw: DrawData;
+
+const CAMERA_DISTANCE: f32 = 9.0;
+const CAMERA_PITCH: f32 = 0.5; // 相机向下俯视的角度（弧度）
+const FOV_Y: f32 = 0.7853982;  // 纵向视野（45度）
+const NEAR: f32 = 0.1;
+const FAR: f32 = 50.0;
+
+struct VertexInput {
+    @location(0) position: vec3f,
+    @location(1) normal: vec3f,
+    @location(2) uv: vec2f,
+}
+
+struct VertexOutput {
+    @builtin(position) position: vec4f,
+    @location(0) normal: vec3f,
+}
+
+// 绕Y轴旋转
+fn rotate_y(p: vec3f, angle: f32) -> vec3f {
+    let c = cos(angle);
+    let s = sin(angle);
+    return vec3f(c * p.x + s * p.z, p.y, -s * p.x + c * p.z);
+}
+
+// 绕X轴旋转
+fn rotate_x(p: vec3f, angle: f32) -> vec3f {
+    let c = cos(angle);
+    let s = sin(angle);
+    return vec3f(p.x, c * p.y - s * p.z, s * p.y + c * p.z);
+}
+
+@vertex
+fn vs(in: VertexInput) -> VertexOutput {
+    let world = rotate_y(in.position * draw.offset.w, view.angle) + draw.offset.xyz;
+    let p = rotate_x(world, CAMERA_PITCH);
+    let z = p.z - CAMERA_DISTANCE;
+    let f = 1.0 / tan(FOV_Y / 2.0);
+    var out: VertexOutput;
+    out.position = vec4f(
+        p.x * f / view.aspect,
+        p.y * f,
+        z * FAR / (NEAR - FAR) + NEAR * FAR / (NEAR - FAR),
+        -z,
+    );
+    out.normal = rotate_x(rotate_y(in.normal, view.angle), CAMERA_PITCH);
+    return out;
+}
+
+@fragment
+fn fs(in: VertexOutput) -> @location(0) vec4f {
+    let light = normalize(vec3f(0.4, 0.8, 0.6));
+    let diffuse = max(dot(normalize(in.normal), light), 0.0);
+    return vec4f(draw.color.rgb * (0.2 + 0.8 * diffuse), draw.color.a);
+}
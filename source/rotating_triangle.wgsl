@@ -0,0 +1,39 @@
+#include "common.wgsl"
+
+// 颜色变化速度（可由Rust端覆盖）
+//#define COLOR_SPEED 1.0
+
+// 每帧由CPU更新的场景参数
+struct SceneUniform {
+    angle: f32, // 旋转角度（弧度）
+    time: f32,  // 场景运行时间（秒）
+    scale: f32, // 缩放倍数（由鼠标滚轮控制）
+}
+
+@group(0) @binding(0) var<uniform> scene: SceneUniform;
+
+struct VertexOutput {
+    @builtin(position) position: vec4f,
+    @location(0) color: vec4f,
+}
+
+@vertex
+fn vs(@builtin(vertex_index) vertex_index: u32) -> VertexOutput {
+    var pos = array(vec2f(0.0, 0.5), // 顶点1（顶部中心）
+    vec2f(-0.5, -0.5), // 顶点2（左下角）
+    vec2f(0.5, -0.5) // 顶点3（右下角）
+    );
+
+    // 每个顶点的颜色随时间在色相上循环，三个顶点相位相差120度
+    let hue = scene.time * COLOR_SPEED + f32(vertex_index) * TAU / 3.0;
+
+    var out: VertexOutput;
+    out.position = vec4f(rotate2d(pos[vertex_index] * scene.scale, scene.angle), 0.0, 1.0);
+    out.color = vec4f(hue_to_rgb(hue), 1.0);
+    return out;
+}
+
+@fragment
+fn fs(in: VertexOutput) -> @location(0) vec4f {
+    return in.color; // 使用插值后的顶点颜色
+}
@@ -0,0 +1,32 @@
+#include "common.wgsl"
+#include "globals.wgsl"
+
+// Shadertoy风格的演示：全屏三角形覆盖整个屏幕，片元着色器只依赖globals中的时间、分辨率和鼠标
+// 拖放的片元着色器同样 #include "globals.wgsl" 并提供 fs(@builtin(position) frag_coord: vec4f) 入口即可替换
+
+@vertex
+fn vs(@builtin(vertex_index) index: u32) -> @builtin(position) vec4f {
+    // 覆盖整个屏幕的大三角形
+    let ndc = vec2f(f32(index == 1u) * 4.0 - 1.0, f32(index == 2u) * 4.0 - 1.0);
+    return vec4f(ndc, 0.0, 1.0);
+}
+
+@fragment
+fn fs(@builtin(position) frag_coord: vec4f) -> @location(0) vec4f {
+    // 以短边为单位、原点在屏幕中心的坐标（y向上）
+    let scale = min(globals.resolution.x, globals.resolution.y);
+    let uv = (frag_coord.xy - globals.resolution * 0.5) / scale * vec2f(2.0, -2.0);
+
+    // 随时间流动的等离子色带
+    let t = globals.time;
+    let wave = sin(uv.x * 3.0 + t) + sin(uv.y * 4.0 - t * 1.3) + sin(length(uv) * 6.0 - t * 2.0);
+    var color = hue_to_rgb(wave * 1.2 + t * 0.5) * 0.8;
+
+    // 按住左键时在光标处画一个半径随时间脉动的圆环
+    if globals.mouse.z > 0.0 {
+        let d = length(frag_coord.xy - globals.mouse.xy) / scale;
+        let ring = 1.0 - smoothstep(0.0, 0.01, abs(d - 0.1 - 0.05 * sin(t * 4.0)));
+        color = mix(color, vec3f(1.0), ring);
+    }
+    return vec4f(color, 1.0);
+}
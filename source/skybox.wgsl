@@ -0,0 +1,35 @@
+// 天空盒：全屏三角形画在远平面上（深度为1），片元着色器用去掉平移的逆视图投影矩阵把像素还原成方向，
+// 采样立方体贴图。只随相机旋转，相机移动时天空保持不动。
+// 深度测试为LessEqual，只有没有被几何体覆盖（深度仍为清除值1.0）的像素才会画出天空。
+
+struct SkyboxUniform {
+    inv_view_proj: mat4x4f, // (投影 * 只含旋转的视图)的逆矩阵
+}
+
+@group(0) @binding(0) var<uniform> sky: SkyboxUniform;
+@group(1) @binding(0) var cube_texture: texture_cube<f32>;
+@group(1) @binding(1) var cube_sampler: sampler;
+
+struct VertexOutput {
+    @builtin(position) position: vec4f,
+    @location(0) ndc: vec2f, // 归一化设备坐标
+}
+
+@vertex
+fn vs(@builtin(vertex_index) index: u32) -> VertexOutput {
+    // 覆盖整个屏幕的大三角形，z = w = 1使它位于远平面上
+    let ndc = vec2f(f32(index == 1u) * 4.0 - 1.0, f32(index == 2u) * 4.0 - 1.0);
+    var out: VertexOutput;
+    out.position = vec4f(ndc, 1.0, 1.0);
+    out.ndc = ndc;
+    return out;
+}
+
+@fragment
+fn fs(in: VertexOutput) -> @location(0) vec4f {
+    // 视图矩阵不含平移，远平面上的点即从相机出发的方向
+    let world = sky.inv_view_proj * vec4f(in.ndc, 1.0, 1.0);
+    let direction = world.xyz / world.w;
+    // 立方体贴图是左手坐标系（+Z面在前），世界坐标是右手系（相机看向-Z），z取反后画面不会镜像
+    return textureSample(cube_texture, cube_sampler, direction * vec3f(1.0, 1.0, -1.0));
+}
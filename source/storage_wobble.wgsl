@@ -0,0 +1,76 @@
+// 存储缓冲区（渲染部分）：顶点着色器按顶点序号读取计算着色器写入的高度，
+// 并用相邻顶点的高度差求出法线
+
+struct WobbleUniform {
+    time: f32,            // 场景时间（秒）
+    resolution: u32,      // 网格每边的顶点数
+    aspect: f32,          // 表面宽高比
+    camera_distance: f32, // 相机到原点的距离
+}
+
+@group(0) @binding(0) var<uniform> wobble: WobbleUniform;
+@group(0) @binding(1) var<storage, read> heights: array<f32>;
+
+const CAMERA_PITCH: f32 = 0.6; // 相机向下俯视的角度（弧度）
+const FOV_Y: f32 = 0.7853982;  // 纵向视野（45度）
+const NEAR: f32 = 0.1;
+const FAR: f32 = 20.0;
+
+struct VertexInput {
+    @builtin(vertex_index) index: u32,
+    @location(0) position: vec3f,
+    @location(1) normal: vec3f,
+    @location(2) uv: vec2f,
+}
+
+struct VertexOutput {
+    @builtin(position) position: vec4f,
+    @location(0) normal: vec3f,
+    @location(1) uv: vec2f,
+}
+
+// 网格第(column, row)个顶点的高度，超出边界时取边界上的值
+fn height_at(column: i32, row: i32) -> f32 {
+    let last = i32(wobble.resolution) - 1;
+    let c = u32(clamp(column, 0, last));
+    let r = u32(clamp(row, 0, last));
+    return heights[r * wobble.resolution + c];
+}
+
+@vertex
+fn vs(in: VertexInput) -> VertexOutput {
+    let column = i32(in.index % wobble.resolution);
+    let row = i32(in.index / wobble.resolution);
+    let world = in.position + vec3f(0.0, height_at(column, row), 0.0);
+
+    // 中心差分求法线，spacing为相邻顶点的距离（平面边长为2）
+    let spacing = 2.0 / f32(wobble.resolution - 1u);
+    let dx = height_at(column + 1, row) - height_at(column - 1, row);
+    let dz = height_at(column, row + 1) - height_at(column, row - 1);
+    let normal = normalize(vec3f(-dx, 2.0 * spacing, -dz));
+
+    // 观察空间：绕X轴转向相机，相机在(0, 0, camera_distance)，看向-Z
+    let c = cos(CAMERA_PITCH);
+    let s = sin(CAMERA_PITCH);
+    let p = vec3f(world.x, c * world.y - s * world.z, s * world.y + c * world.z);
+    let z = p.z - wobble.camera_distance;
+    let f = 1.0 / tan(FOV_Y / 2.0);
+    var out: VertexOutput;
+    out.position = vec4f(
+        p.x * f / wobble.aspect,
+        p.y * f,
+        z * FAR / (NEAR - FAR) + NEAR * FAR / (NEAR - FAR),
+        -z,
+    );
+    out.normal = normal;
+    out.uv = in.uv;
+    return out;
+}
+
+@fragment
+fn fs(in: VertexOutput) -> @location(0) vec4f {
+    let light = normalize(vec3f(0.3, 0.8, 0.5));
+    let diffuse = max(dot(normalize(in.normal), light), 0.0);
+    let base = mix(vec3f(0.1, 0.3, 0.7), vec3f(0.2, 0.8, 0.7), in.uv.y);
+    return vec4f(base * (0.15 + 0.85 * diffuse), 1.0);
+}
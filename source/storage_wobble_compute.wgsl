@@ -0,0 +1,31 @@
+// 存储缓冲区（计算部分）：每个线程计算一个顶点的高度，写入read_write存储缓冲区，
+// 渲染部分（storage_wobble.wgsl）以只读方式读取同一个缓冲区
+
+struct WobbleUniform {
+    time: f32,            // 场景时间（秒）
+    resolution: u32,      // 网格每边的顶点数
+    aspect: f32,          // 表面宽高比
+    camera_distance: f32, // 相机到原点的距离
+}
+
+@group(0) @binding(0) var<uniform> wobble: WobbleUniform;
+@group(0) @binding(1) var<storage, read_write> heights: array<f32>;
+
+const WAVE_HEIGHT: f32 = 0.08; // 起伏幅度（平面边长为2）
+
+@compute @workgroup_size(64)
+fn cs(@builtin(global_invocation_id) id: vec3u) {
+    let index = id.x;
+    let count = wobble.resolution * wobble.resolution;
+    // 最后一个工作组可能超出顶点数
+    if index >= count {
+        return;
+    }
+    // 顶点在平面上的坐标（-1..1），与shapes::plane的顶点顺序一致（先沿X再沿Z）
+    let n = f32(wobble.resolution - 1u);
+    let p = vec2f(f32(index % wobble.resolution), f32(index / wobble.resolution)) / n * 2.0 - 1.0;
+    let t = wobble.time;
+    let ripple = sin(length(p) * 12.0 - t * 3.0);
+    let swell = sin(p.x * 3.0 + t) * cos(p.y * 2.0 - t * 0.7);
+    heights[index] = WAVE_HEIGHT * (ripple + swell);
+}
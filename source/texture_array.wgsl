@@ -0,0 +1,52 @@
+// 二维数组纹理：所有立方体的贴图是同一个texture_2d_array的不同层，层号随实例数据传入
+// 整个阵列只需一个纹理绑定组和一次实例化绘制
+
+struct CameraUniform {
+    view_proj: mat4x4f, // 世界空间到裁剪空间的变换（深度范围0..1）
+}
+
+@group(0) @binding(0) var<uniform> camera: CameraUniform;
+@group(1) @binding(0) var t_layers: texture_2d_array<f32>;
+@group(1) @binding(1) var s_layers: sampler;
+
+struct VertexInput {
+    @location(0) position: vec3f,
+    @location(1) normal: vec3f,
+    @location(2) uv: vec2f,
+}
+
+// 模型矩阵按列拆成4个属性，之后是贴图的层号
+struct InstanceInput {
+    @location(5) model_0: vec4f,
+    @location(6) model_1: vec4f,
+    @location(7) model_2: vec4f,
+    @location(8) model_3: vec4f,
+    @location(9) layer: u32,
+}
+
+struct VertexOutput {
+    @builtin(position) position: vec4f,
+    @location(0) normal: vec3f,
+    @location(1) uv: vec2f,
+    @location(2) @interpolate(flat) layer: u32, // 整数不能插值，必须声明为flat
+}
+
+@vertex
+fn vs(in: VertexInput, instance: InstanceInput) -> VertexOutput {
+    let model = mat4x4f(instance.model_0, instance.model_1, instance.model_2, instance.model_3);
+    var out: VertexOutput;
+    out.position = camera.view_proj * model * vec4f(in.position, 1.0);
+    // 模型矩阵只有旋转和平移，可以直接变换法线（片元着色器中归一化）
+    out.normal = (model * vec4f(in.normal, 0.0)).xyz;
+    out.uv = in.uv;
+    out.layer = instance.layer;
+    return out;
+}
+
+@fragment
+fn fs(in: VertexOutput) -> @location(0) vec4f {
+    let albedo = textureSample(t_layers, s_layers, in.uv, in.layer).rgb;
+    let light = normalize(vec3f(0.4, 0.8, 0.6));
+    let diffuse = max(dot(normalize(in.normal), light), 0.0);
+    return vec4f(albedo * (0.25 + 0.75 * diffuse), 1.0);
+}
@@ -0,0 +1,33 @@
+// 贴图四边形：窗口中央的正方形网格上贴一张图片，按图片的宽高比缩放并完整显示
+
+struct QuadUniform {
+    scale: vec2f, // 网格xy坐标的缩放（NDC），保持图片的宽高比
+}
+
+@group(0) @binding(0) var<uniform> quad: QuadUniform;
+@group(1) @binding(0) var image: texture_2d<f32>;
+@group(1) @binding(1) var image_sampler: sampler;
+
+struct VertexInput {
+    @location(0) position: vec3f, // 边长为2的正方形（-1..1）
+    @location(2) uv: vec2f,       // 纹理坐标（左上角为(0, 0)）
+}
+
+struct VertexOutput {
+    @builtin(position) position: vec4f,
+    @location(0) uv: vec2f,
+}
+
+@vertex
+fn vs(in: VertexInput) -> VertexOutput {
+    var out: VertexOutput;
+    out.position = vec4f(in.position.xy * quad.scale, 0.0, 1.0);
+    out.uv = in.uv;
+    return out;
+}
+
+@fragment
+fn fs(in: VertexOutput) -> @location(0) vec4f {
+    // sRGB纹理采样时自动转换为线性颜色
+    return textureSample(image, image_sampler, in.uv);
+}
@@ -0,0 +1,45 @@
+// 色调映射：把线性HDR颜色（可以远大于1）乘以曝光后压缩到0..1，写入表面。
+// 表面不是sRGB格式时在这里做sRGB编码，否则由硬件在写入时编码。
+
+struct TonemapUniform {
+    exposure: f32,    // 亮度倍数（2的曝光值次方）
+    curve: u32,       // 0：直接截断，1：Reinhard，2：ACES
+    encode_srgb: u32, // 是否需要在着色器中做sRGB编码
+}
+
+@group(0) @binding(0) var<uniform> tonemap: TonemapUniform;
+@group(0) @binding(1) var hdr: texture_2d<f32>;
+
+@vertex
+fn vs(@builtin(vertex_index) index: u32) -> @builtin(position) vec4f {
+    // 覆盖整个屏幕的大三角形
+    let ndc = vec2f(f32(index == 1u) * 4.0 - 1.0, f32(index == 2u) * 4.0 - 1.0);
+    return vec4f(ndc, 0.0, 1.0);
+}
+
+// ACES电影曲线的近似（Krzysztof Narkowicz）
+fn aces(x: vec3f) -> vec3f {
+    return clamp((x * (2.51 * x + 0.03)) / (x * (2.43 * x + 0.59) + 0.14), vec3f(0.0), vec3f(1.0));
+}
+
+// 线性颜色转换为sRGB编码
+fn linear_to_srgb(c: vec3f) -> vec3f {
+    let low = c * 12.92;
+    let high = 1.055 * pow(c, vec3f(1.0 / 2.4)) - 0.055;
+    return select(high, low, c <= vec3f(0.0031308));
+}
+
+@fragment
+fn fs(@builtin(position) position: vec4f) -> @location(0) vec4f {
+    let color = max(textureLoad(hdr, vec2u(position.xy), 0).rgb * tonemap.exposure, vec3f(0.0));
+    var mapped: vec3f;
+    switch tonemap.curve {
+        case 1u: { mapped = color / (1.0 + color); }
+        case 2u: { mapped = aces(color); }
+        default: { mapped = clamp(color, vec3f(0.0), vec3f(1.0)); }
+    }
+    if tonemap.encode_srgb != 0u {
+        mapped = linear_to_srgb(mapped);
+    }
+    return vec4f(mapped, 1.0);
+}
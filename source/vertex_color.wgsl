@@ -0,0 +1,25 @@
+// 顶点位置和颜色来自顶点缓冲区（见vertex.rs中的Vertex::LAYOUT）
+// 颜色在三个顶点之间插值，得到渐变的三角形
+
+struct VertexInput {
+    @location(0) position: vec3f,
+    @location(1) color: vec3f,
+}
+
+struct VertexOutput {
+    @builtin(position) position: vec4f,
+    @location(0) color: vec3f,
+}
+
+@vertex
+fn vs(in: VertexInput) -> VertexOutput {
+    var out: VertexOutput;
+    out.position = vec4f(in.position, 1.0);
+    out.color = in.color;
+    return out;
+}
+
+@fragment
+fn fs(in: VertexOutput) -> @location(0) vec4f {
+    return vec4f(in.color, 1.0);
+}